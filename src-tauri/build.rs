@@ -4,6 +4,7 @@ fn main() {
     );
     let _ = dotenvy::from_path(manifest_dir.join("../.env"));
     forward_env("BGM_APP_SECRET");
+    forward_env("DISCORD_CLIENT_ID");
     tauri_build::build()
 }
 
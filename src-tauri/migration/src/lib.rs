@@ -17,6 +17,44 @@ mod m20260706_000013_reconcile_indexes;
 mod m20260706_000014_migrate_game_sources;
 mod m20260712_000015_split_game_local_path;
 mod m20260722_000016_backfill_game_defaults;
+mod m20260801_000017_add_session_exit_code;
+mod m20260801_000018_add_prevent_sleep_setting;
+mod m20260801_000019_add_discord_rpc_setting;
+mod m20260801_000020_add_launch_args_env;
+mod m20260801_000021_add_launch_hooks;
+mod m20260801_000022_add_wine_config;
+mod m20260801_000023_add_le_profile;
+mod m20260801_000024_add_scan_exe_rules;
+mod m20260801_000025_add_game_engine;
+mod m20260801_000026_add_games_missing;
+mod m20260801_000027_add_user_library_watch;
+mod m20260801_000028_add_games_folder_size;
+mod m20260801_000029_add_games_bgm_sync_status;
+mod m20260801_000030_add_games_vndb_sync_status;
+mod m20260801_000031_add_game_sessions_note;
+mod m20260801_000032_add_user_backup_settings;
+mod m20260801_000033_add_savedata_pinned;
+mod m20260801_000034_cleanup_orphan_records;
+mod m20260801_000035_add_games_deleted_at;
+mod m20260801_000036_add_games_sort_indexes;
+mod m20260801_000037_create_game_notes;
+mod m20260801_000038_create_custom_fields;
+mod m20260801_000039_add_games_favorite_hidden;
+mod m20260801_000040_add_user_safe_mode;
+mod m20260801_000041_create_game_relations;
+mod m20260801_000042_add_collection_smart_filter;
+mod m20260801_000043_add_user_network_settings;
+mod m20260801_000044_add_user_metadata_merge_strategy;
+mod m20260801_000045_create_game_characters;
+mod m20260801_000046_add_user_remote_api;
+mod m20260801_000047_add_user_webhook;
+mod m20260801_000048_add_user_obs_output;
+mod m20260801_000049_add_filter_sort_indexes;
+mod m20260801_000050_add_games_archive_path;
+mod m20260801_000051_add_games_save_registry_keys;
+mod m20260801_000052_convert_games_savepath_to_array;
+mod m20260801_000053_add_savedata_label_comment;
+mod m20260801_000054_add_games_sync_field_timestamps;
 
 pub struct Migrator;
 
@@ -40,6 +78,44 @@ impl MigratorTrait for Migrator {
             Box::new(m20260706_000014_migrate_game_sources::Migration),
             Box::new(m20260712_000015_split_game_local_path::Migration),
             Box::new(m20260722_000016_backfill_game_defaults::Migration),
+            Box::new(m20260801_000017_add_session_exit_code::Migration),
+            Box::new(m20260801_000018_add_prevent_sleep_setting::Migration),
+            Box::new(m20260801_000019_add_discord_rpc_setting::Migration),
+            Box::new(m20260801_000020_add_launch_args_env::Migration),
+            Box::new(m20260801_000021_add_launch_hooks::Migration),
+            Box::new(m20260801_000022_add_wine_config::Migration),
+            Box::new(m20260801_000023_add_le_profile::Migration),
+            Box::new(m20260801_000024_add_scan_exe_rules::Migration),
+            Box::new(m20260801_000025_add_game_engine::Migration),
+            Box::new(m20260801_000026_add_games_missing::Migration),
+            Box::new(m20260801_000027_add_user_library_watch::Migration),
+            Box::new(m20260801_000028_add_games_folder_size::Migration),
+            Box::new(m20260801_000029_add_games_bgm_sync_status::Migration),
+            Box::new(m20260801_000030_add_games_vndb_sync_status::Migration),
+            Box::new(m20260801_000031_add_game_sessions_note::Migration),
+            Box::new(m20260801_000032_add_user_backup_settings::Migration),
+            Box::new(m20260801_000033_add_savedata_pinned::Migration),
+            Box::new(m20260801_000034_cleanup_orphan_records::Migration),
+            Box::new(m20260801_000035_add_games_deleted_at::Migration),
+            Box::new(m20260801_000036_add_games_sort_indexes::Migration),
+            Box::new(m20260801_000037_create_game_notes::Migration),
+            Box::new(m20260801_000038_create_custom_fields::Migration),
+            Box::new(m20260801_000039_add_games_favorite_hidden::Migration),
+            Box::new(m20260801_000040_add_user_safe_mode::Migration),
+            Box::new(m20260801_000041_create_game_relations::Migration),
+            Box::new(m20260801_000042_add_collection_smart_filter::Migration),
+            Box::new(m20260801_000043_add_user_network_settings::Migration),
+            Box::new(m20260801_000044_add_user_metadata_merge_strategy::Migration),
+            Box::new(m20260801_000045_create_game_characters::Migration),
+            Box::new(m20260801_000046_add_user_remote_api::Migration),
+            Box::new(m20260801_000047_add_user_webhook::Migration),
+            Box::new(m20260801_000048_add_user_obs_output::Migration),
+            Box::new(m20260801_000049_add_filter_sort_indexes::Migration),
+            Box::new(m20260801_000050_add_games_archive_path::Migration),
+            Box::new(m20260801_000051_add_games_save_registry_keys::Migration),
+            Box::new(m20260801_000052_convert_games_savepath_to_array::Migration),
+            Box::new(m20260801_000053_add_savedata_label_comment::Migration),
+            Box::new(m20260801_000054_add_games_sync_field_timestamps::Migration),
         ]
     }
 }
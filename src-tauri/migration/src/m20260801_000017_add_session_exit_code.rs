@@ -0,0 +1,41 @@
+//! 给 game_sessions 表新增进程退出码字段
+//!
+//! 用于记录游戏会话结束时的进程退出码，非 0 表示异常退出（崩溃）。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameSessions::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(GameSessions::ExitCode).integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameSessions::Table)
+                    .drop_column(GameSessions::ExitCode)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GameSessions {
+    Table,
+    ExitCode,
+}
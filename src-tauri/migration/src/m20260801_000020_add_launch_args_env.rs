@@ -0,0 +1,60 @@
+//! 给 games 表新增每个游戏的自定义启动参数与环境变量
+//!
+//! - launch_args: JSON 字符串数组，追加到启动命令之后
+//! - launch_env: JSON 对象（变量名 -> 值），注入到游戏进程环境
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 的 ALTER TABLE 每次只能添加一列
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Games::LaunchArgs).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Games::LaunchEnv).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .drop_column(Games::LaunchEnv)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .drop_column(Games::LaunchArgs)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    LaunchArgs,
+    LaunchEnv,
+}
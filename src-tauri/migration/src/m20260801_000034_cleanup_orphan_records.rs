@@ -0,0 +1,36 @@
+//! 清理早期版本未启用外键约束时残留的孤儿数据
+//!
+//! 各子表在建表时已声明 `ON DELETE CASCADE`，但旧版本连接未执行
+//! `PRAGMA foreign_keys = ON`，删除游戏或合集时级联并未生效。
+//! 当前连接已强制开启外键约束，此迁移一次性删除引用已不存在的记录，
+//! 使 `PRAGMA foreign_key_check` 恢复干净。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const CLEANUP_SQL: [&str; 5] = [
+    "DELETE FROM game_sessions WHERE game_id NOT IN (SELECT id FROM games)",
+    "DELETE FROM game_statistics WHERE game_id NOT IN (SELECT id FROM games)",
+    "DELETE FROM savedata WHERE game_id NOT IN (SELECT id FROM games)",
+    "DELETE FROM game_sources WHERE game_id NOT IN (SELECT id FROM games)",
+    "DELETE FROM game_collection_link WHERE game_id NOT IN (SELECT id FROM games) \
+     OR collection_id NOT IN (SELECT id FROM collections)",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in CLEANUP_SQL {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // 删除的孤儿数据无法恢复
+        Ok(())
+    }
+}
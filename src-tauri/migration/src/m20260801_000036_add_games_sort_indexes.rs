@@ -0,0 +1,40 @@
+//! 为按评分、加入时间与占用空间排序补充索引
+//!
+//! 评分排序沿用 `idx_games_user_rating`，这里补充 `created_at` 与
+//! `folder_size` 的索引，保证大库排序时无需全表扫描。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SORT_INDEXES: [&str; 2] = [
+    "CREATE INDEX IF NOT EXISTS idx_games_created_at ON games(created_at, id)",
+    "CREATE INDEX IF NOT EXISTS idx_games_folder_size \
+     ON games(folder_size) \
+     WHERE folder_size IS NOT NULL",
+];
+
+const DROP_SORT_INDEXES: [&str; 2] = [
+    "DROP INDEX IF EXISTS idx_games_created_at",
+    "DROP INDEX IF EXISTS idx_games_folder_size",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in SORT_INDEXES {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DROP_SORT_INDEXES {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! 新增游戏笔记表
+//!
+//! 每个游戏可保存多条 Markdown 笔记（攻略要点、路线顺序、CG 缺漏等），
+//! 删除游戏时级联删除。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameNotes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GameNotes::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(GameNotes::GameId).integer().not_null())
+                    .col(
+                        ColumnDef::new(GameNotes::Title)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(GameNotes::Content)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(GameNotes::CreatedAt)
+                            .integer()
+                            .default(Expr::cust("(strftime('%s', 'now'))")),
+                    )
+                    .col(
+                        ColumnDef::new(GameNotes::UpdatedAt)
+                            .integer()
+                            .default(Expr::cust("(strftime('%s', 'now'))")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_notes_game")
+                            .from(GameNotes::Table, GameNotes::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_game_notes_game_updated_at")
+                    .table(GameNotes::Table)
+                    .col(GameNotes::GameId)
+                    .col(GameNotes::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameNotes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GameNotes {
+    Table,
+    Id,
+    GameId,
+    Title,
+    Content,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
@@ -0,0 +1,142 @@
+//! 新增用户自定义字段
+//!
+//! `custom_fields` 保存用户定义的字段（如"汉化组"、"购入渠道"、"价格"），
+//! `game_custom_field_values` 保存每个游戏的字段值，删除字段或游戏时级联删除。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CustomFields::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CustomFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFields::Name)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFields::FieldType)
+                            .text()
+                            .not_null()
+                            .default("text"),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFields::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFields::CreatedAt)
+                            .integer()
+                            .default(Expr::cust("(strftime('%s', 'now'))")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameCustomFieldValues::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GameCustomFieldValues::GameId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GameCustomFieldValues::FieldId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GameCustomFieldValues::Value)
+                            .text()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(GameCustomFieldValues::GameId)
+                            .col(GameCustomFieldValues::FieldId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_custom_field_values_game")
+                            .from(GameCustomFieldValues::Table, GameCustomFieldValues::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_custom_field_values_field")
+                            .from(GameCustomFieldValues::Table, GameCustomFieldValues::FieldId)
+                            .to(CustomFields::Table, CustomFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按字段筛选游戏
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_game_custom_field_values_field_value")
+                    .table(GameCustomFieldValues::Table)
+                    .col(GameCustomFieldValues::FieldId)
+                    .col(GameCustomFieldValues::Value)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameCustomFieldValues::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CustomFields::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CustomFields {
+    Table,
+    Id,
+    Name,
+    FieldType,
+    SortOrder,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum GameCustomFieldValues {
+    Table,
+    GameId,
+    FieldId,
+    Value,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
@@ -0,0 +1,81 @@
+//! 新增游戏关联关系表
+//!
+//! 保存从 VNDB relations 同步的关联作品（续作、前作、FD、同系列等），
+//! 关联对象以 VNDB ID 记录，库中尚未添加的作品也会保留，删除游戏时级联删除。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameRelations::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GameRelations::GameId).integer().not_null())
+                    .col(
+                        ColumnDef::new(GameRelations::RelatedVndbId)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GameRelations::Relation).text().not_null())
+                    .col(
+                        ColumnDef::new(GameRelations::Official)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(GameRelations::GameId)
+                            .col(GameRelations::RelatedVndbId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_relations_game")
+                            .from(GameRelations::Table, GameRelations::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 按关联作品反查
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_game_relations_related_vndb_id")
+                    .table(GameRelations::Table)
+                    .col(GameRelations::RelatedVndbId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameRelations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GameRelations {
+    Table,
+    GameId,
+    RelatedVndbId,
+    Relation,
+    Official,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
@@ -0,0 +1,39 @@
+//! 给 collections 表新增智能合集筛选条件（JSON），设置后合集中的游戏由条件实时决定
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Collections::SmartFilter).text().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Collections::Table)
+                    .drop_column(Collections::SmartFilter)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Collections {
+    Table,
+    SmartFilter,
+}
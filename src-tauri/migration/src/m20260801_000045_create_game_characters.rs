@@ -0,0 +1,80 @@
+//! 新增游戏角色表
+//!
+//! 保存从 BGM 条目拉取的角色与声优列表，声优以 JSON 数组保存在 actors 列，
+//! 删除游戏时级联删除。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameCharacters::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GameCharacters::GameId).integer().not_null())
+                    .col(
+                        ColumnDef::new(GameCharacters::CharacterId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GameCharacters::Name).text().not_null())
+                    .col(ColumnDef::new(GameCharacters::Relation).text().not_null())
+                    .col(ColumnDef::new(GameCharacters::Image).text().null())
+                    .col(
+                        ColumnDef::new(GameCharacters::Actors)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(GameCharacters::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(GameCharacters::GameId)
+                            .col(GameCharacters::CharacterId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_characters_game")
+                            .from(GameCharacters::Table, GameCharacters::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameCharacters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GameCharacters {
+    Table,
+    GameId,
+    CharacterId,
+    Name,
+    Relation,
+    Image,
+    Actors,
+    SortOrder,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
@@ -0,0 +1,42 @@
+//! 为按游玩状态筛选、按最近游玩排序与按日期统计会话补充索引
+//!
+//! `games(date)` 已由 `idx_games_date_asc` / `idx_games_date_desc` 覆盖，这里补充
+//! `games(clear)`、`game_statistics(last_played)` 与 `game_sessions(game_id, date)`，
+//! 保证大库筛选、排序与统计时无需全表扫描。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const FILTER_SORT_INDEXES: [&str; 3] = [
+    "CREATE INDEX IF NOT EXISTS idx_games_clear ON games(clear, id)",
+    "CREATE INDEX IF NOT EXISTS idx_game_statistics_last_played \
+     ON game_statistics((last_played IS NULL), last_played)",
+    "CREATE INDEX IF NOT EXISTS idx_game_sessions_game_date ON game_sessions(game_id, date)",
+];
+
+const DROP_FILTER_SORT_INDEXES: [&str; 3] = [
+    "DROP INDEX IF EXISTS idx_games_clear",
+    "DROP INDEX IF EXISTS idx_game_statistics_last_played",
+    "DROP INDEX IF EXISTS idx_game_sessions_game_date",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in FILTER_SORT_INDEXES {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DROP_FILTER_SORT_INDEXES {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
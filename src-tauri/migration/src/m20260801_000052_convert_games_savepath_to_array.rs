@@ -0,0 +1,39 @@
+//! 把 games.savepath 从单个路径改为 JSON 字符串数组，以支持多个存档路径
+//!
+//! 空路径清空为 NULL，其余路径包装为只有一项的数组；已经是数组的值保持不变。
+//! 回滚时只保留第一个路径。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const UP_SQL: [&str; 2] = [
+    "UPDATE games SET savepath = NULL WHERE savepath IS NOT NULL AND trim(savepath) = ''",
+    "UPDATE games SET savepath = json_array(savepath) \
+     WHERE savepath IS NOT NULL \
+     AND NOT (json_valid(savepath) AND json_type(savepath) = 'array')",
+];
+
+const DOWN_SQL: &str = "UPDATE games SET savepath = json_extract(savepath, '$[0]') \
+     WHERE savepath IS NOT NULL \
+     AND json_valid(savepath) AND json_type(savepath) = 'array'";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in UP_SQL {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(DOWN_SQL)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! savedata 表添加 label / comment 字段，为备份标注用途（如“true end 前”“全 CG”）
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 每条 ALTER TABLE 只能添加一列
+        for column in [Savedata::Label, Savedata::Comment] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Savedata::Table)
+                        .add_column_if_not_exists(ColumnDef::new(column).text().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Savedata::Label, Savedata::Comment] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Savedata::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Savedata {
+    Table,
+    Label,
+    Comment,
+}
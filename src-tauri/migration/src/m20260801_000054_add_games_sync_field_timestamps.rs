@@ -0,0 +1,66 @@
+//! 给 games 表新增游玩状态、个人评分与短评各自的修改时间，供局域网同步逐字段比较新旧
+//!
+//! 已有数据以 `updated_at` 作为这些字段的修改时间，与此前按整条记录比较的结果一致。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const COLUMNS: [Games; 3] = [
+    Games::ClearUpdatedAt,
+    Games::UserRatingUpdatedAt,
+    Games::UserReviewUpdatedAt,
+];
+
+const BACKFILL_SQL: [&str; 3] = [
+    "UPDATE games SET clear_updated_at = updated_at WHERE clear IS NOT NULL",
+    "UPDATE games SET user_rating_updated_at = updated_at \
+     WHERE json_extract(custom_data, '$.user_rating') IS NOT NULL",
+    "UPDATE games SET user_review_updated_at = updated_at \
+     WHERE json_extract(custom_data, '$.user_review') IS NOT NULL",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 每条 ALTER TABLE 只能添加一列
+        for column in COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Games::Table)
+                        .add_column_if_not_exists(ColumnDef::new(column).integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        let db = manager.get_connection();
+        for sql in BACKFILL_SQL {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Games::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    ClearUpdatedAt,
+    UserRatingUpdatedAt,
+    UserReviewUpdatedAt,
+}
@@ -1,5 +1,14 @@
 pub mod archive;
+pub mod audit;
+pub mod collection_share;
 pub mod common;
 pub mod covers;
 pub mod database;
+pub mod library;
+pub mod registry;
+pub mod retention;
+pub mod save_path;
 pub mod savedata;
+pub mod savedata_diff;
+pub mod schedule;
+pub mod sessions;
@@ -1,15 +1,42 @@
 //! 7z 压缩/解压工具模块
 //!
-//! 提供基于 Zstd 的 7z 压缩与解压功能，供存档备份、自定义封面备份等多处复用。
+//! 提供 7z 压缩与解压功能，供存档备份、自定义封面备份等多处复用。
+//! 默认使用 Zstd，存档备份可按设置改用不压缩（Store）或 LZMA2，并可用密码加密。
 
-use sevenz_rust2::{ArchiveWriter, decompress_file, encoder_options::ZstandardOptions};
-use std::fs;
-use std::path::Path;
+use crate::entity::user::BackupCompression;
+use sevenz_rust2::encoder_options::{AesEncoderOptions, Lzma2Options, ZstandardOptions};
+use sevenz_rust2::{
+    ArchiveEntry, ArchiveReader, ArchiveWriter, EncoderConfiguration, EncoderMethod, Password,
+    decompress_file, decompress_file_with_extract_fn, decompress_file_with_password,
+    default_entry_extract_fn,
+};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
-/// 速度与压缩率折中：使用 Zstd 低压缩等级。
-const ZSTD_COMPRESSION_LEVEL: u32 = 3;
+/// 进度回调要求中止时返回的错误
+pub const ARCHIVE_CANCELLED: &str = "操作已取消";
 
-/// 创建 7z 压缩包（递归压缩整个目录）
+/// 统计读取字节数的读取器，回调返回 false 时以错误中止读取
+struct ProgressReader<'a, R> {
+    inner: R,
+    processed: &'a mut u64,
+    on_progress: &'a mut dyn FnMut(u64) -> bool,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        *self.processed += read as u64;
+        if !(self.on_progress)(*self.processed) {
+            return Err(io::Error::other(ARCHIVE_CANCELLED));
+        }
+        Ok(read)
+    }
+}
+
+/// 创建 7z 压缩包（递归压缩整个目录），使用 Zstd 默认等级
 ///
 /// # Arguments
 /// * `source_dir` - 源目录路径
@@ -20,15 +47,111 @@ const ZSTD_COMPRESSION_LEVEL: u32 = 3;
 pub fn create_7z_archive(
     source_dir: &Path,
     archive_path: &Path,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    create_7z_archive_with(
+        source_dir,
+        archive_path,
+        BackupCompression::Zstd,
+        None,
+        None,
+    )
+}
+
+fn content_method(compression: BackupCompression, level: Option<u32>) -> EncoderConfiguration {
+    let level = compression.effective_level(level);
+    log::debug!("7z 压缩参数: codec={:?}, level={:?}", compression, level);
+    match compression {
+        BackupCompression::Store => EncoderMethod::COPY.into(),
+        BackupCompression::Lzma2 => Lzma2Options::from_level(level.unwrap_or_default()).into(),
+        BackupCompression::Zstd => ZstandardOptions::from_level(level.unwrap_or_default()).into(),
+    }
+}
+
+/// 按指定压缩方式与等级创建 7z 压缩包（递归压缩整个目录）
+///
+/// # Arguments
+/// * `compression` - 压缩方式
+/// * `level` - 压缩等级，超出范围时截断，为空时使用压缩方式的默认等级
+/// * `password` - 提供时以 AES-256 加密内容与文件列表
+///
+/// # Returns
+/// * `Result<u64, Box<dyn std::error::Error>>` - 压缩包文件大小或错误
+pub fn create_7z_archive_with(
+    source_dir: &Path,
+    archive_path: &Path,
+    compression: BackupCompression,
+    level: Option<u32>,
+    password: Option<&str>,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    create_7z_archive_from_dirs(
+        &[(String::new(), source_dir.to_path_buf())],
+        archive_path,
+        compression,
+        level,
+        password,
+    )
+}
+
+/// 把多个目录写入同一个 7z 压缩包，`sources` 为 `(压缩包内目录, 源目录)`
+///
+/// 压缩包内目录为空时源目录的内容直接放在根目录，否则放在该目录下。
+/// 用于存档备份同时打包多个存档目录与导出的注册表文件。符号链接不跟随也不压缩。
+pub fn create_7z_archive_from_dirs(
+    sources: &[(String, PathBuf)],
+    archive_path: &Path,
+    compression: BackupCompression,
+    level: Option<u32>,
+    password: Option<&str>,
 ) -> Result<u64, Box<dyn std::error::Error>> {
     let mut writer = ArchiveWriter::create(archive_path)?;
+    let mut methods = Vec::with_capacity(2);
+    if let Some(password) = password {
+        methods.push(AesEncoderOptions::new(Password::from(password)).into());
+        writer.set_encrypt_header(true);
+    }
+    methods.push(content_method(compression, level));
+    writer.set_content_methods(methods);
 
-    let zstd_options = ZstandardOptions::from_level(ZSTD_COMPRESSION_LEVEL);
-    log::debug!("7z 压缩参数: codec=ZSTD, level={}", ZSTD_COMPRESSION_LEVEL);
-    writer.set_content_methods(vec![zstd_options.into()]);
+    let mut pushed_dirs: Vec<String> = Vec::new();
+    for (prefix, source_dir) in sources {
+        let prefix = prefix.trim_matches('/');
+        // 逐级写入前缀目录，空目录也能完整还原
+        let mut dir = String::new();
+        for part in prefix.split('/').filter(|part| !part.is_empty()) {
+            if !dir.is_empty() {
+                dir.push('/');
+            }
+            dir.push_str(part);
+            if !pushed_dirs.contains(&dir) {
+                writer.push_archive_entry::<&[u8]>(ArchiveEntry::new_directory(&dir), None)?;
+                pushed_dirs.push(dir.clone());
+            }
+        }
 
-    // 递归添加源目录中的所有文件，过滤器返回 true 表示包含
-    writer.push_source_path(source_dir, |_| true)?;
+        for entry in WalkDir::new(source_dir).min_depth(1).follow_links(false) {
+            let entry = entry?;
+            let relative = entry
+                .path()
+                .strip_prefix(source_dir)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let name = if prefix.is_empty() {
+                relative
+            } else {
+                format!("{}/{}", prefix, relative)
+            };
+            if entry.file_type().is_dir() {
+                writer.push_archive_entry::<&[u8]>(ArchiveEntry::new_directory(&name), None)?;
+            } else if entry.file_type().is_file() {
+                writer.push_archive_entry(
+                    ArchiveEntry::from_path(entry.path(), name),
+                    Some(File::open(entry.path())?),
+                )?;
+            } else {
+                log::warn!("跳过符号链接: {}", entry.path().display());
+            }
+        }
+    }
 
     writer.finish()?;
 
@@ -36,9 +159,95 @@ pub fn create_7z_archive(
     Ok(metadata.len())
 }
 
-/// 解压 7z 压缩包（覆盖模式）
+/// 逐个文件创建 7z 压缩包，回调累计读取的源文件字节数
+///
+/// 回调返回 false 时中止压缩，由调用方删除未完成的压缩包。符号链接不跟随也不压缩。
+pub fn create_7z_archive_with_progress(
+    source_dir: &Path,
+    archive_path: &Path,
+    compression: BackupCompression,
+    on_progress: &mut dyn FnMut(u64) -> bool,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut writer = ArchiveWriter::create(archive_path)?;
+    writer.set_content_methods(vec![content_method(compression, None)]);
+
+    let mut processed = 0_u64;
+    for entry in WalkDir::new(source_dir).min_depth(1).follow_links(false) {
+        let entry = entry?;
+        let name = entry
+            .path()
+            .strip_prefix(source_dir)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if entry.file_type().is_dir() {
+            writer.push_archive_entry::<&[u8]>(ArchiveEntry::new_directory(&name), None)?;
+        } else if entry.file_type().is_file() {
+            let reader = ProgressReader {
+                inner: File::open(entry.path())?,
+                processed: &mut processed,
+                on_progress: &mut *on_progress,
+            };
+            writer.push_archive_entry(ArchiveEntry::from_path(entry.path(), name), Some(reader))?;
+        } else {
+            log::warn!("跳过符号链接: {}", entry.path().display());
+        }
+    }
+    writer.finish()?;
+
+    Ok(fs::metadata(archive_path)?.len())
+}
+
+/// 解压 7z 压缩包到目标目录，回调累计解压出的字节数
 ///
-/// 解压前会先清空目标目录的所有内容，确保恢复结果完整干净。
+/// 回调返回 false 时中止解压，由调用方清理已解压的文件。
+pub fn extract_7z_archive_with_progress(
+    archive_path: &Path,
+    target_dir: &Path,
+    on_progress: &mut dyn FnMut(u64) -> bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut processed = 0_u64;
+    decompress_file_with_extract_fn(archive_path, target_dir, |entry, reader, dest| {
+        let mut reader = ProgressReader {
+            inner: reader,
+            processed: &mut processed,
+            on_progress: &mut *on_progress,
+        };
+        default_entry_extract_fn(entry, &mut reader, dest)
+    })?;
+    Ok(())
+}
+
+/// 压缩包中一个文件的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFileInfo {
+    /// 压缩包内路径，统一使用 `/` 分隔
+    pub name: String,
+    pub size: u64,
+    /// 压缩时记录的 CRC32，没有记录时为空
+    pub crc: Option<u32>,
+}
+
+/// 只读取文件列表，不解压内容；文件列表加密的压缩包需要提供密码
+pub fn list_7z_archive_files(
+    archive_path: &Path,
+    password: Option<&str>,
+) -> Result<Vec<ArchiveFileInfo>, Box<dyn std::error::Error>> {
+    let password = password.map(Password::from).unwrap_or_else(Password::empty);
+    let reader = ArchiveReader::open(archive_path, password)?;
+    Ok(reader
+        .archive()
+        .files
+        .iter()
+        .filter(|entry| !entry.is_directory)
+        .map(|entry| ArchiveFileInfo {
+            name: entry.name.replace('\\', "/"),
+            size: entry.size,
+            crc: entry.has_crc.then_some(entry.crc as u32),
+        })
+        .collect())
+}
+
+/// 解压 7z 压缩包（覆盖模式）
 ///
 /// # Arguments
 /// * `archive_path` - 压缩包路径
@@ -49,6 +258,56 @@ pub fn create_7z_archive(
 pub fn extract_7z_archive(
     archive_path: &Path,
     target_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    extract_7z_archive_with_password(archive_path, target_dir, None)
+}
+
+/// 解压 7z 压缩包（覆盖模式），加密的压缩包需要提供密码
+///
+/// 先解压到目标目录旁的临时目录，成功后再清空目标目录并移入解压结果，
+/// 密码错误或压缩包损坏时不会破坏目标目录中的现有文件。
+pub fn extract_7z_archive_with_password(
+    archive_path: &Path,
+    target_dir: &Path,
+    password: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let staging_dir = extract_7z_archive_to_staging(archive_path, target_dir, password)?;
+    replace_dir_with_staging(&staging_dir, target_dir)
+}
+
+/// 解压到目标目录旁的临时目录并返回该目录，失败时删除临时目录
+///
+/// 调用方可以在 [`replace_dir_with_staging`] 覆盖目标目录前检查或取走解压结果中的文件。
+pub fn extract_7z_archive_to_staging(
+    archive_path: &Path,
+    target_dir: &Path,
+    password: Option<&str>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let name = target_dir.file_name().ok_or("无效的解压目录")?;
+    let staging_dir =
+        target_dir.with_file_name(format!(".{}.reina-restore", name.to_string_lossy()));
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    fs::create_dir_all(&staging_dir)?;
+
+    let extracted = match password {
+        Some(password) => {
+            decompress_file_with_password(archive_path, &staging_dir, Password::from(password))
+        }
+        None => decompress_file(archive_path, &staging_dir),
+    };
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(e.into());
+    }
+    Ok(staging_dir)
+}
+
+/// 清空目标目录后移入临时目录中的解压结果，并删除临时目录
+pub fn replace_dir_with_staging(
+    staging_dir: &Path,
+    target_dir: &Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // 如果目标目录存在，先清空内容以实现覆盖
     if target_dir.exists() {
@@ -65,22 +324,40 @@ pub fn extract_7z_archive(
         fs::create_dir_all(target_dir)?;
     }
 
-    decompress_file(archive_path, target_dir)?;
+    for entry in fs::read_dir(staging_dir)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = target_dir.join(entry.file_name());
+        // 临时目录与目标目录不在同一磁盘时无法重命名，改为复制
+        if fs::rename(&source, &target).is_err() {
+            copy_entry(&source, &target)?;
+        }
+    }
+    fs::remove_dir_all(staging_dir)?;
+    Ok(())
+}
+
+/// 复制文件或整个目录
+fn copy_entry(source: &Path, target: &Path) -> io::Result<()> {
+    if !source.is_dir() {
+        return fs::copy(source, target).map(|_| ());
+    }
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        copy_entry(&entry.path(), &target.join(entry.file_name()))?;
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use crate::utils::test_util::temp_dir;
 
     #[test]
     fn zstd_archive_round_trip() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let root = std::env::temp_dir().join(format!("reina_archive_test_{unique}"));
+        let root = temp_dir("archive-test", "");
         let source = root.join("source");
         let archive = root.join("backup.7z");
         let target = root.join("target");
@@ -95,4 +372,145 @@ mod tests {
         assert_eq!(fs::read(target.join("savedata.bin")).unwrap(), content);
         fs::remove_dir_all(root).unwrap();
     }
+
+    #[test]
+    fn store_and_lzma2_archives_round_trip() {
+        let root = temp_dir("archive-methods", "");
+        let source = root.join("source");
+        let content = b"ReinaManager archive method test".repeat(64);
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("savedata.bin"), &content).unwrap();
+
+        for (index, compression) in [BackupCompression::Store, BackupCompression::Lzma2]
+            .into_iter()
+            .enumerate()
+        {
+            let archive = root.join(format!("backup_{index}.7z"));
+            let target = root.join(format!("target_{index}"));
+            create_7z_archive_with(&source, &archive, compression, Some(99), None).unwrap();
+            extract_7z_archive(&archive, &target).unwrap();
+            assert_eq!(fs::read(target.join("savedata.bin")).unwrap(), content);
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn prefixed_dirs_are_extracted_to_staging_before_replace() {
+        let root = temp_dir("archive-dirs", "");
+        let source = root.join("source");
+        let registry = root.join("registry");
+        let archive = root.join("backup.7z");
+        let target = root.join("target");
+        fs::create_dir_all(source.join("empty")).unwrap();
+        fs::create_dir_all(&registry).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(source.join("savedata.bin"), b"save").unwrap();
+        fs::write(target.join("old.bin"), b"old").unwrap();
+        fs::write(
+            registry.join("0.reg"),
+            b"Windows Registry Editor Version 5.00",
+        )
+        .unwrap();
+
+        create_7z_archive_from_dirs(
+            &[(String::new(), source), (".registry".to_string(), registry)],
+            &archive,
+            BackupCompression::Zstd,
+            None,
+            None,
+        )
+        .unwrap();
+        let staging = extract_7z_archive_to_staging(&archive, &target, None).unwrap();
+        assert!(staging.join(".registry").join("0.reg").is_file());
+        assert!(staging.join("empty").is_dir());
+        assert_eq!(fs::read(target.join("old.bin")).unwrap(), b"old");
+
+        fs::remove_dir_all(staging.join(".registry")).unwrap();
+        replace_dir_with_staging(&staging, &target).unwrap();
+        assert!(!staging.exists());
+        assert!(!target.join("old.bin").exists());
+        assert!(!target.join(".registry").exists());
+        assert_eq!(fs::read(target.join("savedata.bin")).unwrap(), b"save");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn encrypted_archive_requires_correct_password() {
+        let root = temp_dir("archive-aes", "");
+        let source = root.join("source");
+        let archive = root.join("backup.7z");
+        let target = root.join("target");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(source.join("savedata.bin"), b"secret").unwrap();
+        fs::write(target.join("current.bin"), b"current").unwrap();
+
+        create_7z_archive_with(
+            &source,
+            &archive,
+            BackupCompression::Zstd,
+            None,
+            Some("reina"),
+        )
+        .unwrap();
+
+        let files = list_7z_archive_files(&archive, Some("reina")).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].name.as_str(), files[0].size), ("savedata.bin", 6));
+        assert!(files[0].crc.is_some());
+
+        assert!(extract_7z_archive_with_password(&archive, &target, Some("wrong")).is_err());
+        assert_eq!(fs::read(target.join("current.bin")).unwrap(), b"current");
+
+        extract_7z_archive_with_password(&archive, &target, Some("reina")).unwrap();
+        assert_eq!(fs::read(target.join("savedata.bin")).unwrap(), b"secret");
+        assert!(!target.join("current.bin").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn progress_archive_round_trip_and_cancel() {
+        let root = temp_dir("archive-progress", "");
+        let source = root.join("source");
+        let archive = root.join("game.7z");
+        let target = root.join("target");
+        fs::create_dir_all(source.join("data").join("empty")).unwrap();
+        fs::write(source.join("game.exe"), [1_u8; 100]).unwrap();
+        fs::write(source.join("data").join("data.xp3"), [2_u8; 300]).unwrap();
+
+        let mut compressed = 0;
+        create_7z_archive_with_progress(
+            &source,
+            &archive,
+            BackupCompression::Zstd,
+            &mut |processed| {
+                compressed = processed;
+                true
+            },
+        )
+        .unwrap();
+        assert_eq!(compressed, 400);
+
+        let mut extracted = 0;
+        extract_7z_archive_with_progress(&archive, &target, &mut |processed| {
+            extracted = processed;
+            true
+        })
+        .unwrap();
+        assert_eq!(extracted, 400);
+        assert_eq!(
+            fs::read(target.join("data").join("data.xp3")).unwrap(),
+            [2_u8; 300]
+        );
+        assert!(target.join("data").join("empty").is_dir());
+
+        let cancelled = create_7z_archive_with_progress(
+            &source,
+            &root.join("cancelled.7z"),
+            BackupCompression::Zstd,
+            &mut |_| false,
+        );
+        assert!(cancelled.is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
 }
@@ -0,0 +1,223 @@
+//! 存档备份一致性检查
+//!
+//! 对比 savedata 表与备份目录（`backups/game_{id}/`）中的文件：
+//! 磁盘上存在但没有记录的为孤儿文件，有记录但文件已丢失的为孤儿记录。
+
+use super::savedata::resolve_savedata_backup_root;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::entity::savedata;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::{State, command};
+
+/// 没有数据库记录的备份文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanBackupFile {
+    pub game_id: i32,
+    pub file: String,
+    pub file_size: u64,
+    pub path: String,
+}
+
+/// 一致性检查结果
+#[derive(Debug, Default, Serialize)]
+pub struct SavedataAudit {
+    pub orphan_files: Vec<OrphanBackupFile>,
+    /// 备份文件已不存在的记录
+    pub orphan_records: Vec<savedata::Model>,
+}
+
+/// 一致性清理结果
+#[derive(Debug, Default, Serialize)]
+pub struct SavedataCleanupResult {
+    pub files_removed: usize,
+    pub records_removed: usize,
+    pub errors: Vec<String>,
+}
+
+/// 从备份目录名（`game_{id}`）解析游戏 ID
+fn parse_game_dir(name: &str) -> Option<i32> {
+    name.strip_prefix("game_")?.parse().ok()
+}
+
+/// 扫描 `game_{id}` 备份目录中的文件，尚未与记录对比
+fn scan_backup_files(
+    backup_root: &Path,
+    game_id: Option<i32>,
+) -> Result<Vec<OrphanBackupFile>, String> {
+    if !backup_root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(backup_root).map_err(|e| format!("读取备份目录失败: {}", e))?
+    {
+        let Ok(entry) = entry else { continue };
+        let Some(dir_game_id) = entry.file_name().to_str().and_then(parse_game_dir) else {
+            continue;
+        };
+        if game_id.is_some_and(|game_id| game_id != dir_game_id) || !entry.path().is_dir() {
+            continue;
+        }
+
+        let game_dir = fs::read_dir(entry.path())
+            .map_err(|e| format!("读取备份目录失败 {:?}: {}", entry.path(), e))?;
+        for file in game_dir.flatten() {
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            files.push(OrphanBackupFile {
+                game_id: dir_game_id,
+                file: file.file_name().to_string_lossy().into_owned(),
+                file_size: metadata.len(),
+                path: file.path().to_string_lossy().into_owned(),
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// 按（游戏 ID、文件名）对比磁盘文件与数据库记录
+fn compare(files: Vec<OrphanBackupFile>, records: Vec<savedata::Model>) -> SavedataAudit {
+    let on_disk: HashSet<(i32, &str)> = files
+        .iter()
+        .map(|file| (file.game_id, file.file.as_str()))
+        .collect();
+    let recorded: HashSet<(i32, &str)> = records
+        .iter()
+        .map(|record| (record.game_id, record.file.as_str()))
+        .collect();
+
+    let orphan_records = records
+        .iter()
+        .filter(|record| !on_disk.contains(&(record.game_id, record.file.as_str())))
+        .cloned()
+        .collect();
+    let orphan_files = files
+        .iter()
+        .filter(|file| !recorded.contains(&(file.game_id, file.file.as_str())))
+        .cloned()
+        .collect();
+
+    SavedataAudit {
+        orphan_files,
+        orphan_records,
+    }
+}
+
+async fn run_audit(db: &DatabaseConnection, game_id: Option<i32>) -> Result<SavedataAudit, String> {
+    let backup_root = resolve_savedata_backup_root(db).await?;
+    let records = match game_id {
+        Some(game_id) => GamesRepository::get_savedata_records(db, game_id).await,
+        None => GamesRepository::get_all_savedata_records(db).await,
+    }
+    .map_err(|e| format!("获取备份记录失败: {}", e))?;
+    let files = scan_backup_files(&backup_root, game_id)?;
+    Ok(compare(files, records))
+}
+
+/// 检查存档备份文件与数据库记录是否一致
+///
+/// # Arguments
+/// * `game_id` - 游戏ID，为空时检查全部游戏
+#[command]
+pub async fn audit_savedata(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+) -> Result<SavedataAudit, String> {
+    run_audit(&db, game_id).await
+}
+
+/// 清理孤儿文件与孤儿记录
+///
+/// 先重新检查一次，只删除检查时仍不一致的文件与记录。
+///
+/// # Arguments
+/// * `game_id` - 游戏ID，为空时清理全部游戏
+#[command]
+pub async fn cleanup_savedata_orphans(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+) -> Result<SavedataCleanupResult, String> {
+    let audit = run_audit(&db, game_id).await?;
+    let mut result = SavedataCleanupResult::default();
+
+    for file in &audit.orphan_files {
+        match fs::remove_file(&file.path) {
+            Ok(()) => result.files_removed += 1,
+            Err(e) => result
+                .errors
+                .push(format!("删除孤儿文件失败 {}: {}", file.path, e)),
+        }
+    }
+    for record in &audit.orphan_records {
+        match GamesRepository::delete_savedata_record(&db, record.id).await {
+            Ok(_) => result.records_removed += 1,
+            Err(e) => result
+                .errors
+                .push(format!("删除孤儿记录失败 (ID: {}): {}", record.id, e)),
+        }
+    }
+
+    log::info!(
+        "存档备份一致性清理完成 files_removed={} records_removed={} errors={}",
+        result.files_removed,
+        result.records_removed,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+
+    #[test]
+    fn finds_orphan_files_and_records() {
+        let root = temp_dir("savedata-audit", "");
+        fs::create_dir_all(root.join("game_1")).unwrap();
+        fs::create_dir_all(root.join("game_2")).unwrap();
+        fs::create_dir_all(root.join("other")).unwrap();
+        fs::write(root.join("game_1/a.7z"), b"a").unwrap();
+        fs::write(root.join("game_1/b.7z"), b"bb").unwrap();
+        fs::write(root.join("game_2/c.7z"), b"c").unwrap();
+        fs::write(root.join("other/d.7z"), b"d").unwrap();
+
+        let record = |id: i32, game_id: i32, file: &str| savedata::Model {
+            id,
+            game_id,
+            file: file.to_string(),
+            backup_time: 0,
+            file_size: 0,
+            pinned: false,
+            label: None,
+            comment: None,
+        };
+        let records = vec![record(1, 1, "a.7z"), record(2, 1, "missing.7z")];
+
+        let audit = compare(scan_backup_files(&root, Some(1)).unwrap(), records.clone());
+        assert_eq!(audit.orphan_files.len(), 1);
+        assert_eq!(audit.orphan_files[0].file, "b.7z");
+        assert_eq!(audit.orphan_files[0].file_size, 2);
+        assert_eq!(audit.orphan_records.len(), 1);
+        assert_eq!(audit.orphan_records[0].id, 2);
+
+        let audit = compare(scan_backup_files(&root, None).unwrap(), records);
+        let mut orphan_files: Vec<_> = audit
+            .orphan_files
+            .iter()
+            .map(|file| (file.game_id, file.file.as_str()))
+            .collect();
+        orphan_files.sort();
+        assert_eq!(orphan_files, [(1, "b.7z"), (2, "c.7z")]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
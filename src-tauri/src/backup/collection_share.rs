@@ -0,0 +1,401 @@
+//! 合集导出与导入（分享）
+//!
+//! 导出合集及其子合集的结构，以及其中每个游戏的名称与 bgm / vndb 外部 ID。
+//! 导入时总是新建合集，按外部 ID 匹配本地库中的游戏，未匹配的游戏列入报告待补充。
+//! 智能合集只导出筛选条件，由导入方按自己的游戏库重新计算。
+
+use super::library::GameMatchIndex;
+use crate::database::dto::FullGameData;
+use crate::database::repository::collections_repository::CollectionsRepository;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::prelude::*;
+use crate::entity::smart_filter::SmartFilter;
+use crate::entity::{collections, game_collection_link};
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, NotSet,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{State, command};
+
+/// 合集导出文件格式版本，结构发生不兼容变化时递增
+pub const COLLECTION_EXPORT_VERSION: u32 = 1;
+
+/// 合集导出文件
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionExport {
+    pub version: u32,
+    /// 导出时的应用版本
+    pub app_version: String,
+    /// 导出时间（Unix 秒）
+    pub exported_at: i64,
+    /// 导出的合集，父合集在前
+    pub collections: Vec<SharedCollection>,
+}
+
+/// 导出文件中的合集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedCollection {
+    /// 在导出文件中的合集 ID，仅用于表示层级
+    pub id: i32,
+    /// 父合集在导出文件中的 ID，导出的根合集为空
+    pub parent_id: Option<i32>,
+    pub name: String,
+    pub icon: Option<String>,
+    pub sort_order: i32,
+    #[serde(default)]
+    pub smart_filter: Option<SmartFilter>,
+    #[serde(default)]
+    pub games: Vec<SharedGame>,
+}
+
+/// 导出文件中的游戏，只保留用于匹配与提示的信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedGame {
+    pub name: Option<String>,
+    pub bgm_id: Option<String>,
+    pub vndb_id: Option<String>,
+}
+
+/// 导出结果摘要
+#[derive(Debug, Serialize)]
+pub struct ExportCollectionResult {
+    pub path: String,
+    pub collection_count: usize,
+    pub game_count: usize,
+}
+
+/// 导入时未能在本地库中找到的游戏
+#[derive(Debug, Serialize)]
+pub struct MissingSharedGame {
+    /// 所在合集在本库中的 ID
+    pub collection_id: i32,
+    pub collection_name: String,
+    pub game: SharedGame,
+}
+
+/// 合集导入报告
+#[derive(Debug, Default, Serialize)]
+pub struct ImportCollectionReport {
+    /// 导入后的根合集 ID
+    pub root_ids: Vec<i32>,
+    pub collections_created: usize,
+    pub games_matched: usize,
+    /// 待补充的游戏
+    pub missing: Vec<MissingSharedGame>,
+}
+
+fn shared_game(game: &FullGameData) -> SharedGame {
+    let external_id = |source: &str| {
+        game.sources
+            .iter()
+            .find(|item| item.source == source)
+            .and_then(|item| item.external_id.clone())
+            .filter(|id| !id.trim().is_empty())
+    };
+    SharedGame {
+        name: game.display_name(),
+        bgm_id: external_id("bgm"),
+        vndb_id: external_id("vndb"),
+    }
+}
+
+/// 按层级顺序收集合集及其全部子孙合集
+fn collect_subtree(all: &[collections::Model], root_id: i32) -> Vec<&collections::Model> {
+    let mut subtree: Vec<&collections::Model> =
+        all.iter().filter(|item| item.id == root_id).collect();
+    let mut index = 0;
+    while index < subtree.len() {
+        let parent_id = subtree[index].id;
+        subtree.extend(all.iter().filter(|item| item.parent_id == Some(parent_id)));
+        index += 1;
+    }
+    subtree
+}
+
+/// 导出合集（含子合集）为 JSON 文件
+///
+/// # Arguments
+/// * `id` - 要导出的合集ID
+/// * `path` - 导出文件的完整路径
+#[command]
+pub async fn export_collection(
+    db: State<'_, DatabaseConnection>,
+    id: i32,
+    path: String,
+) -> Result<ExportCollectionResult, String> {
+    let all = Collections::find()
+        .order_by_asc(collections::Column::SortOrder)
+        .order_by_asc(collections::Column::Id)
+        .all(db.inner())
+        .await
+        .map_err(|e| format!("读取合集数据失败: {}", e))?;
+    let subtree = collect_subtree(&all, id);
+    if subtree.is_empty() {
+        return Err(format!("合集不存在 (ID: {})", id));
+    }
+
+    let games: HashMap<i32, FullGameData> = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?
+    .into_iter()
+    .map(|game| (game.id, game))
+    .collect();
+
+    let mut shared = Vec::with_capacity(subtree.len());
+    for collection in subtree {
+        let game_ids = if collection.smart_filter.is_some() {
+            Vec::new()
+        } else {
+            CollectionsRepository::get_games_in_collection(&db, collection.id)
+                .await
+                .map_err(|e| format!("读取合集中的游戏失败: {}", e))?
+        };
+        shared.push(SharedCollection {
+            id: collection.id,
+            parent_id: collection.parent_id.filter(|_| collection.id != id),
+            name: collection.name.clone(),
+            icon: collection.icon.clone(),
+            sort_order: collection.sort_order,
+            smart_filter: collection.smart_filter.clone(),
+            games: game_ids
+                .iter()
+                .filter_map(|game_id| games.get(game_id))
+                .map(shared_game)
+                .collect(),
+        });
+    }
+
+    let export = CollectionExport {
+        version: COLLECTION_EXPORT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        collections: shared,
+    };
+    let content =
+        serde_json::to_vec_pretty(&export).map_err(|e| format!("序列化合集失败: {}", e))?;
+    if let Some(parent) = Path::new(&path).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("创建导出目录失败: {}", e))?;
+    }
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    let game_count = export
+        .collections
+        .iter()
+        .map(|collection| collection.games.len())
+        .sum();
+    log::info!(
+        "合集导出完成 path={} collections={} games={}",
+        path,
+        export.collections.len(),
+        game_count
+    );
+    Ok(ExportCollectionResult {
+        path,
+        collection_count: export.collections.len(),
+        game_count,
+    })
+}
+
+/// 在事务中按层级新建合集并关联匹配到的游戏
+async fn import_collections_in(
+    txn: &DatabaseTransaction,
+    index: &GameMatchIndex,
+    export: CollectionExport,
+    parent_id: Option<i32>,
+) -> Result<ImportCollectionReport, DbErr> {
+    let file_ids: HashSet<i32> = export.collections.iter().map(|item| item.id).collect();
+    let mut report = ImportCollectionReport::default();
+    let mut id_map: HashMap<i32, i32> = HashMap::new();
+    let mut pending = export.collections;
+    let now = chrono::Utc::now().timestamp() as i32;
+
+    // 父合集先于子合集处理；父合集不在文件中的视为导入的根合集
+    loop {
+        let before = pending.len();
+        let mut deferred = Vec::new();
+        for collection in pending {
+            let file_parent = collection.parent_id.filter(|id| file_ids.contains(id));
+            let mapped_parent = match file_parent {
+                None => parent_id,
+                Some(parent) => match id_map.get(&parent) {
+                    Some(mapped) => Some(*mapped),
+                    None => {
+                        deferred.push(collection);
+                        continue;
+                    }
+                },
+            };
+            let created = collections::ActiveModel {
+                id: NotSet,
+                name: Set(collection.name.trim().to_string()),
+                parent_id: Set(mapped_parent),
+                sort_order: Set(collection.sort_order),
+                icon: Set(collection.icon.clone()),
+                smart_filter: Set(collection.smart_filter.clone()),
+                created_at: Set(Some(now)),
+                updated_at: Set(Some(now)),
+            }
+            .insert(txn)
+            .await?;
+            report.collections_created += 1;
+            if file_parent.is_none() {
+                report.root_ids.push(created.id);
+            }
+            id_map.insert(collection.id, created.id);
+
+            let mut linked = HashSet::new();
+            for game in collection.games {
+                let external_ids: Vec<(&str, &str)> =
+                    [("bgm", &game.bgm_id), ("vndb", &game.vndb_id)]
+                        .into_iter()
+                        .filter_map(|(source, id)| Some((source, id.as_deref()?)))
+                        .collect();
+                let Some((game_id, _)) = index.find_parts(&external_ids, None) else {
+                    report.missing.push(MissingSharedGame {
+                        collection_id: created.id,
+                        collection_name: created.name.clone(),
+                        game,
+                    });
+                    continue;
+                };
+                if !linked.insert(game_id) {
+                    continue;
+                }
+                game_collection_link::ActiveModel {
+                    id: NotSet,
+                    game_id: Set(game_id),
+                    collection_id: Set(created.id),
+                    sort_order: Set(linked.len() as i32 - 1),
+                    created_at: Set(Some(now)),
+                }
+                .insert(txn)
+                .await?;
+                report.games_matched += 1;
+            }
+        }
+        if deferred.is_empty() || deferred.len() == before {
+            if !deferred.is_empty() {
+                log::warn!("{} 个合集的父合集存在循环引用，已跳过", deferred.len());
+            }
+            break;
+        }
+        pending = deferred;
+    }
+    Ok(report)
+}
+
+/// 从 JSON 文件导入合集
+///
+/// 总是新建合集，不与已有合集合并；游戏按 bgm / vndb 外部 ID 匹配本地库。
+///
+/// # Arguments
+/// * `path` - 导出文件路径
+/// * `parent_id` - 导入到指定合集下，为空时作为根合集导入
+#[command]
+pub async fn import_collection(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+    parent_id: Option<i32>,
+) -> Result<ImportCollectionReport, String> {
+    let content = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("读取导入文件失败: {}", e))?;
+    let export: CollectionExport =
+        serde_json::from_slice(&content).map_err(|e| format!("解析导入文件失败: {}", e))?;
+    if export.version > COLLECTION_EXPORT_VERSION {
+        return Err(format!(
+            "导入文件格式版本 {} 高于当前支持的版本 {}，请先升级应用",
+            export.version, COLLECTION_EXPORT_VERSION
+        ));
+    }
+    if let Some(parent_id) = parent_id {
+        Collections::find_by_id(parent_id)
+            .one(db.inner())
+            .await
+            .map_err(|e| format!("读取合集数据失败: {}", e))?
+            .ok_or_else(|| format!("目标合集不存在 (ID: {})", parent_id))?;
+    }
+
+    let games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let index = GameMatchIndex::from_games(&games);
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| format!("开启导入事务失败: {}", e))?;
+    let report = import_collections_in(&txn, &index, export, parent_id)
+        .await
+        .map_err(|e| format!("导入合集失败: {}", e))?;
+    txn.commit()
+        .await
+        .map_err(|e| format!("提交导入事务失败: {}", e))?;
+
+    log::info!(
+        "合集导入完成 path={} collections={} matched={} missing={}",
+        path,
+        report.collections_created,
+        report.games_matched,
+        report.missing.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collection(id: i32, parent_id: Option<i32>) -> collections::Model {
+        collections::Model {
+            id,
+            name: format!("合集{}", id),
+            parent_id,
+            sort_order: 0,
+            icon: None,
+            smart_filter: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn collects_collection_subtree_parents_first() {
+        let all = vec![
+            collection(1, None),
+            collection(2, Some(1)),
+            collection(3, None),
+            collection(4, Some(2)),
+            collection(5, Some(3)),
+        ];
+        let ids: Vec<i32> = collect_subtree(&all, 1)
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(ids, [1, 2, 4]);
+        assert!(collect_subtree(&all, 9).is_empty());
+    }
+}
@@ -2,12 +2,18 @@ use crate::backup::common::{
     BackupOptions, BackupResult, cleanup_auto_backup_files, resolve_backup_dir,
 };
 use crate::backup::covers::{backup_custom_covers_archive, delete_all_covers_dir};
-use crate::database::db::close_connection;
-use sea_orm::{ConnectionTrait, DatabaseConnection};
+use crate::backup::schedule::SCHEDULED_BACKUP_PREFIX;
+use crate::database::db::{close_connection, establish_connection, remove_wal_files};
+use migration::{MigrationName, Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, Statement};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 use tauri::{State, command};
+use url::Url;
 
 use reina_path::get_db_path;
 
@@ -17,6 +23,8 @@ pub struct ImportResult {
     pub success: bool,
     pub message: String,
     pub backup_path: Option<String>,
+    /// 数据库连接已关闭，无论成功与否前端都必须重启应用以重新连接
+    pub restart_required: bool,
 }
 
 // ==================== 数据库备份和导入 ====================
@@ -27,10 +35,28 @@ fn generate_backup_filename() -> String {
     format!("reina_manager_{}.db", timestamp)
 }
 
+/// 退出时自动备份的文件名前缀
+const AUTO_BACKUP_PREFIX: &str = "reina_manager_auto_";
+
+/// SQLite 数据库文件头
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// 数据库备份文件信息
+#[derive(Debug, Serialize)]
+pub struct DbBackupFile {
+    pub file_name: String,
+    pub path: String,
+    pub file_size: u64,
+    /// 修改时间（Unix 时间戳，秒）
+    pub modified: i64,
+    /// 备份类型：manual 手动 / auto 退出时自动 / scheduled 定时
+    pub kind: String,
+}
+
 /// 生成带自动备份标记的数据库备份文件名，便于保留策略只清理自动备份。
 fn generate_auto_backup_filename() -> String {
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
-    format!("reina_manager_auto_{}.db", timestamp)
+    format!("{}{}.db", AUTO_BACKUP_PREFIX, timestamp)
 }
 
 /// 使用 VACUUM INTO 进行数据库热备份
@@ -61,10 +87,17 @@ pub async fn backup_database(
 }
 
 pub async fn backup_database_file(db: &DatabaseConnection) -> Result<BackupResult, String> {
-    // 生成备份文件名并确定目标路径
-    let backup_name = generate_backup_filename();
     let backup_dir = resolve_backup_dir(db).await?;
-    let target_path = backup_dir.join(&backup_name);
+    backup_database_file_to(db, &backup_dir, &generate_backup_filename()).await
+}
+
+/// 使用 VACUUM INTO 将数据库热备份到指定目录与文件名
+pub async fn backup_database_file_to(
+    db: &DatabaseConnection,
+    backup_dir: &Path,
+    backup_name: &str,
+) -> Result<BackupResult, String> {
+    let target_path = backup_dir.join(backup_name);
 
     // 将路径转换为字符串
     // SQLite 在 Windows 上也支持正斜杠，使用正斜杠可以避免转义问题
@@ -108,7 +141,7 @@ async fn backup_database_file_cold(
 
     if let Some(max_auto_backups) = max_auto_backups
         && let Err(e) =
-            cleanup_auto_backup_files(&backup_dir, "reina_manager_auto_", ".db", max_auto_backups)
+            cleanup_auto_backup_files(&backup_dir, AUTO_BACKUP_PREFIX, ".db", max_auto_backups)
     {
         log::warn!("清理旧数据库自动备份失败: {}", e);
     }
@@ -207,6 +240,7 @@ pub async fn import_database(
     log::info!("导入数据库前已清空封面目录");
 
     // 步骤6：复制文件覆盖现有数据库
+    remove_wal_files(&target_db_path);
     fs::copy(src_path, &target_db_path).map_err(|e| format!("复制数据库文件失败: {}", e))?;
     log::info!("数据库文件已复制: {} -> {:?}", source_path, target_db_path);
 
@@ -215,5 +249,244 @@ pub async fn import_database(
         success: true,
         message: "数据库导入成功，已备份自定义封面并清空封面缓存，应用将自动重启".to_string(),
         backup_path: result_backup_path,
+        restart_required: true,
     })
 }
+
+fn backup_kind(file_name: &str) -> &'static str {
+    if file_name.starts_with(AUTO_BACKUP_PREFIX) {
+        "auto"
+    } else if file_name.starts_with(SCHEDULED_BACKUP_PREFIX) {
+        "scheduled"
+    } else {
+        "manual"
+    }
+}
+
+/// 列出数据库备份目录中的备份文件，最新的在前
+#[command]
+pub async fn list_db_backups(
+    db: State<'_, DatabaseConnection>,
+) -> Result<Vec<DbBackupFile>, String> {
+    let backup_dir = resolve_backup_dir(&db).await?;
+    let entries = fs::read_dir(&backup_dir).map_err(|e| format!("读取备份目录失败: {}", e))?;
+
+    let mut backups: Vec<DbBackupFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let file_name = entry.file_name().to_str()?.to_string();
+            if !metadata.is_file() || !file_name.ends_with(".db") {
+                return None;
+            }
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_secs() as i64);
+            Some(DbBackupFile {
+                kind: backup_kind(&file_name).to_string(),
+                path: entry.path().to_string_lossy().into_owned(),
+                file_size: metadata.len(),
+                modified,
+                file_name,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(backups)
+}
+
+/// 检查连接中的数据库是否完好，且迁移记录都能被当前版本识别
+async fn check_backup_compatibility(conn: &DatabaseConnection) -> Result<(), String> {
+    let quick_check = conn
+        .query_one(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "PRAGMA quick_check".to_string(),
+        ))
+        .await
+        .map_err(|e| format!("检查备份文件完整性失败: {}", e))?
+        .and_then(|row| row.try_get_by_index::<String>(0).ok());
+    if quick_check.as_deref() != Some("ok") {
+        return Err(format!(
+            "备份文件已损坏: {}",
+            quick_check.unwrap_or_default()
+        ));
+    }
+
+    let rows = conn
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT version FROM seaql_migrations".to_string(),
+        ))
+        .await
+        .map_err(|_| "备份文件不是 ReinaManager 数据库".to_string())?;
+    let known: HashSet<String> = Migrator::migrations()
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect();
+    let unknown = rows
+        .iter()
+        .filter_map(|row| row.try_get::<String>("", "version").ok())
+        .filter(|version| !known.contains(version))
+        .count();
+    if unknown > 0 {
+        return Err("备份来自更新版本的 ReinaManager，请先升级应用".to_string());
+    }
+    Ok(())
+}
+
+/// 校验备份文件是 SQLite 数据库且与当前版本兼容
+async fn validate_db_backup(path: &Path) -> Result<(), String> {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|_| "不是有效的 SQLite 数据库文件".to_string())?;
+    if &header != SQLITE_HEADER {
+        return Err("不是有效的 SQLite 数据库文件".to_string());
+    }
+
+    let url =
+        Url::from_file_path(path).map_err(|_| format!("无效的备份文件路径: {}", path.display()))?;
+    let conn = Database::connect(format!("sqlite:{}?mode=ro", url.path()))
+        .await
+        .map_err(|e| format!("打开备份文件失败: {}", e))?;
+    let result = check_backup_compatibility(&conn).await;
+    if let Err(e) = conn.close().await {
+        log::warn!("关闭备份文件连接失败: {}", e);
+    }
+    result
+}
+
+/// 连接替换后的数据库并执行迁移，确认可以正常使用
+async fn migrate_restored_database() -> Result<(), String> {
+    let conn = establish_connection()
+        .await
+        .map_err(|e| format!("连接恢复后的数据库失败: {}", e))?;
+    let result = Migrator::up(&conn, None)
+        .await
+        .map_err(|e| format!("恢复后的数据库迁移失败: {}", e));
+    close_connection(conn)
+        .await
+        .map_err(|e| format!("关闭数据库连接失败: {}", e))?;
+    result
+}
+
+/// 从备份文件恢复数据库（覆盖现有数据库）
+///
+/// 恢复前先校验备份文件并热备份当前数据库；替换文件后重新执行迁移，
+/// 任一步骤失败都会用恢复前的备份回滚。
+///
+/// 关闭数据库连接之后的结果（成功、已回滚、回滚失败）都以 `restart_required` 为 true 的
+/// [`ImportResult`] 返回，由前端重启应用重新连接数据库；只有关闭连接前的校验失败返回错误。
+///
+/// # Arguments
+///
+/// * `backup_path` - 备份文件路径
+#[command]
+pub async fn restore_database(
+    db: State<'_, DatabaseConnection>,
+    backup_path: String,
+) -> Result<ImportResult, String> {
+    let src_path = Path::new(&backup_path);
+    if !src_path.is_file() {
+        return Err(format!("备份文件不存在: {}", backup_path));
+    }
+
+    let target_db_path = get_db_path()?;
+    if let (Ok(source), Ok(target)) = (
+        fs::canonicalize(src_path),
+        fs::canonicalize(&target_db_path),
+    ) && source == target
+    {
+        return Err("不能恢复当前正在使用的数据库文件".to_string());
+    }
+
+    validate_db_backup(src_path).await?;
+
+    // 关闭连接前热备份当前数据库，作为回滚点
+    let rollback_path = backup_database_file(&db)
+        .await?
+        .path
+        .ok_or("恢复前备份当前数据库失败")?;
+
+    close_connection(db.inner().clone())
+        .await
+        .map_err(|e| format!("关闭数据库连接失败: {}", e))?;
+    log::info!("数据库连接已关闭，准备恢复数据库");
+
+    remove_wal_files(&target_db_path);
+    let restored = match fs::copy(src_path, &target_db_path) {
+        Ok(_) => migrate_restored_database().await,
+        Err(e) => Err(format!("复制数据库文件失败: {}", e)),
+    };
+    if let Err(e) = restored {
+        log::error!("数据库恢复失败，回滚到恢复前的备份: {}", e);
+        remove_wal_files(&target_db_path);
+        let message = match fs::copy(&rollback_path, &target_db_path) {
+            Ok(_) => format!("{}，已回滚到恢复前的数据库，应用将自动重启", e),
+            Err(rollback_err) => format!(
+                "{}；回滚失败: {}，恢复前的备份位于 {}",
+                e, rollback_err, rollback_path
+            ),
+        };
+        return Ok(ImportResult {
+            success: false,
+            message,
+            backup_path: Some(rollback_path),
+            restart_required: true,
+        });
+    }
+
+    log::info!(
+        "数据库已从备份恢复: {} -> {:?}",
+        backup_path,
+        target_db_path
+    );
+    Ok(ImportResult {
+        success: true,
+        message: "数据库恢复成功，应用将自动重启".to_string(),
+        backup_path: Some(rollback_path),
+        restart_required: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_unknown_migrations() {
+        let conn = Database::connect("sqlite::memory:").await.unwrap();
+        conn.execute_unprepared(
+            "CREATE TABLE seaql_migrations (version TEXT PRIMARY KEY, applied_at INTEGER)",
+        )
+        .await
+        .unwrap();
+        let known = Migrator::migrations()[0].name().to_string();
+        conn.execute_unprepared(&format!(
+            "INSERT INTO seaql_migrations VALUES ('{}', 0)",
+            known
+        ))
+        .await
+        .unwrap();
+        assert!(check_backup_compatibility(&conn).await.is_ok());
+
+        conn.execute_unprepared(
+            "INSERT INTO seaql_migrations VALUES ('m29990101_000001_future', 0)",
+        )
+        .await
+        .unwrap();
+        assert!(check_backup_compatibility(&conn).await.is_err());
+    }
+
+    #[test]
+    fn classifies_backup_files() {
+        assert_eq!(backup_kind("reina_manager_20240101_000000.db"), "manual");
+        assert_eq!(backup_kind("reina_manager_auto_20240101_000000.db"), "auto");
+        assert_eq!(
+            backup_kind("reina_manager_scheduled_20240101_000000.db"),
+            "scheduled"
+        );
+    }
+}
@@ -0,0 +1,1040 @@
+//! 游戏库 JSON 导出与导入
+//!
+//! 将游戏（含外部数据源）、合集、统计、游玩会话、存档备份记录与设置序列化为
+//! 一个带格式版本号的 JSON 文件，用于跨设备迁移与灾备。
+//! 与数据库文件备份不同，导出文件与表结构解耦，可在不同版本间导入。
+//!
+//! 导入时按 bgm/vndb 外部 ID 与本地目录匹配已有游戏，并按冲突策略跳过、覆盖或合并，
+//! 整个导入在单个事务内完成。用户设置与设备相关，不随导入恢复。
+//! 启动钩子、启动环境变量与 Wine 运行器路径都能让启动时执行任意程序，默认不导入，
+//! 需调用方显式开启并在报告中列出导入的钩子。
+
+use crate::database::dto::{FullGameData, InsertGameData, UpdateGameData, UpsertGameSourceData};
+use crate::database::repository::game_stats_repository::GameStatsRepository;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::launch_options::{LaunchEnv, LaunchHooks, WineConfig};
+use crate::entity::prelude::*;
+use crate::entity::{
+    collections, game_collection_link, game_sessions, game_statistics, savedata, user,
+};
+use crate::game::scan::ImportPathIndex;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
+    NotSet, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{State, command};
+
+/// 导出文件格式版本，结构发生不兼容变化时递增
+pub const LIBRARY_EXPORT_VERSION: u32 = 1;
+
+/// 游戏库导出文件
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryExport {
+    pub version: u32,
+    /// 导出时的应用版本
+    pub app_version: String,
+    /// 导出时间（Unix 秒）
+    pub exported_at: i64,
+    pub games: Vec<FullGameData>,
+    pub collections: Vec<collections::Model>,
+    pub collection_links: Vec<game_collection_link::Model>,
+    pub statistics: Vec<game_statistics::Model>,
+    pub sessions: Vec<game_sessions::Model>,
+    pub savedata: Vec<savedata::Model>,
+    /// 用户设置，不包含 BGM/VNDB 授权信息
+    pub settings: Option<user::Model>,
+}
+
+/// 导出结果摘要
+#[derive(Debug, Serialize)]
+pub struct ExportLibraryResult {
+    pub path: String,
+    pub game_count: usize,
+    pub collection_count: usize,
+    pub session_count: usize,
+    pub savedata_count: usize,
+}
+
+/// 从数据库收集完整的导出数据
+pub async fn collect_library_export(db: &DatabaseConnection) -> Result<LibraryExport, String> {
+    let games =
+        GamesRepository::find_all(db, GameType::All, SortOption::Addtime, SortOrder::Asc, None)
+            .await
+            .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let collections = Collections::find()
+        .order_by_asc(collections::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| format!("读取合集数据失败: {}", e))?;
+    let collection_links = GameCollectionLink::find()
+        .order_by_asc(game_collection_link::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| format!("读取合集关联失败: {}", e))?;
+    let statistics = GameStatistics::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("读取游戏统计失败: {}", e))?;
+    let sessions = GameSessions::find()
+        .order_by_asc(game_sessions::Column::SessionId)
+        .all(db)
+        .await
+        .map_err(|e| format!("读取游玩会话失败: {}", e))?;
+    let savedata = Savedata::find()
+        .order_by_asc(savedata::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| format!("读取存档备份记录失败: {}", e))?;
+    let settings = User::find_by_id(1)
+        .one(db)
+        .await
+        .map_err(|e| format!("读取用户设置失败: {}", e))?
+        .map(|mut settings| {
+            // 授权信息不随导出文件流转
+            settings.bgm_auth = None;
+            settings.vndb_token = None;
+            settings
+        });
+
+    Ok(LibraryExport {
+        version: LIBRARY_EXPORT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        games,
+        collections,
+        collection_links,
+        statistics,
+        sessions,
+        savedata,
+        settings,
+    })
+}
+
+/// 导出整个游戏库为 JSON 文件
+///
+/// # Arguments
+/// * `path` - 导出文件的完整路径
+#[command]
+pub async fn export_library(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+) -> Result<ExportLibraryResult, String> {
+    let export = collect_library_export(&db).await?;
+    let content =
+        serde_json::to_vec_pretty(&export).map_err(|e| format!("序列化游戏库失败: {}", e))?;
+
+    if let Some(parent) = Path::new(&path).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("创建导出目录失败: {}", e))?;
+    }
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    log::info!(
+        "游戏库导出完成 path={} games={} sessions={}",
+        path,
+        export.games.len(),
+        export.sessions.len()
+    );
+    Ok(ExportLibraryResult {
+        path,
+        game_count: export.games.len(),
+        collection_count: export.collections.len(),
+        session_count: export.sessions.len(),
+        savedata_count: export.savedata.len(),
+    })
+}
+
+/// 导入时与已有游戏冲突的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflictStrategy {
+    /// 保留已有游戏，忽略导入文件中的对应条目
+    Skip,
+    /// 以导入文件为准覆盖已有游戏及其游玩记录
+    Overwrite,
+    /// 只补全已有游戏的空字段与缺失数据源，游玩会话取并集
+    Merge,
+}
+
+/// 单个游戏的导入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportGameAction {
+    Inserted,
+    Skipped,
+    Overwritten,
+    Merged,
+}
+
+/// 单个游戏的导入明细
+#[derive(Debug, Serialize)]
+pub struct ImportLibraryItem {
+    /// 在导入文件中的游戏 ID
+    pub source_id: i32,
+    pub name: Option<String>,
+    pub action: ImportGameAction,
+    /// 导入后在本库中的游戏 ID
+    pub game_id: i32,
+    /// 命中已有游戏的匹配方式：bgm / vndb / localpath
+    pub matched_by: Option<String>,
+}
+
+/// 导入时写入的启动钩子
+#[derive(Debug, Serialize)]
+pub struct ImportedLaunchHooks {
+    /// 导入后在本库中的游戏 ID
+    pub game_id: i32,
+    pub name: Option<String>,
+    pub hooks: LaunchHooks,
+}
+
+/// 导入报告
+#[derive(Debug, Default, Serialize)]
+pub struct ImportLibraryReport {
+    pub total: usize,
+    pub inserted: usize,
+    pub skipped: usize,
+    pub overwritten: usize,
+    pub merged: usize,
+    pub collections_created: usize,
+    pub links_added: usize,
+    pub sessions_added: usize,
+    pub savedata_added: usize,
+    /// 实际写入的启动钩子，供用户核对将要执行的命令
+    pub imported_hooks: Vec<ImportedLaunchHooks>,
+    /// 因未开启导入而忽略了启动钩子、启动环境变量或 Wine 运行器路径的游戏数
+    pub hooks_ignored: usize,
+    pub items: Vec<ImportLibraryItem>,
+}
+
+/// 用于匹配已有游戏的外部数据源
+const MATCH_SOURCES: [&str; 2] = ["bgm", "vndb"];
+
+/// 已有游戏的匹配索引：外部 ID 优先，其次为本地目录
+#[derive(Default)]
+pub(crate) struct GameMatchIndex {
+    external_ids: HashMap<(String, String), i32>,
+    paths: ImportPathIndex,
+}
+
+impl GameMatchIndex {
+    pub(crate) fn from_games(games: &[FullGameData]) -> Self {
+        let mut index = Self::default();
+        for game in games {
+            index.insert(game, game.id);
+        }
+        index
+    }
+
+    pub(crate) fn insert(&mut self, game: &FullGameData, game_id: i32) {
+        let external_ids = game
+            .sources
+            .iter()
+            .filter_map(|source| Some((source.source.as_str(), source.external_id.as_deref()?)));
+        self.insert_parts(external_ids, game.localpath.as_deref(), game_id);
+    }
+
+    /// 按数据源外部 ID 与本地目录登记游戏
+    pub(crate) fn insert_parts<'a>(
+        &mut self,
+        external_ids: impl IntoIterator<Item = (&'a str, &'a str)>,
+        localpath: Option<&str>,
+        game_id: i32,
+    ) {
+        for (source, external_id) in external_ids {
+            if MATCH_SOURCES.contains(&source) {
+                self.external_ids
+                    .entry((source.to_string(), external_id.to_string()))
+                    .or_insert(game_id);
+            }
+        }
+        if let Some(localpath) = localpath {
+            self.paths.insert(Path::new(localpath), game_id);
+        }
+    }
+
+    fn find(&self, game: &FullGameData) -> Option<(i32, &'static str)> {
+        let external_ids: Vec<(&str, &str)> = game
+            .sources
+            .iter()
+            .filter_map(|source| Some((source.source.as_str(), source.external_id.as_deref()?)))
+            .collect();
+        self.find_parts(&external_ids, game.localpath.as_deref())
+    }
+
+    /// 查找匹配的已有游戏，返回游戏 ID 与匹配方式（bgm / vndb / localpath）
+    pub(crate) fn find_parts(
+        &self,
+        external_ids: &[(&str, &str)],
+        localpath: Option<&str>,
+    ) -> Option<(i32, &'static str)> {
+        MATCH_SOURCES
+            .iter()
+            .find_map(|source| {
+                let (_, external_id) = external_ids.iter().find(|(name, _)| name == source)?;
+                self.external_ids
+                    .get(&(source.to_string(), external_id.to_string()))
+                    .map(|game_id| (*game_id, *source))
+            })
+            .or_else(|| {
+                self.paths
+                    .matched_game_id(Path::new(localpath?))
+                    .map(|game_id| (game_id, "localpath"))
+            })
+    }
+}
+
+fn upsert_sources_of(game: &FullGameData) -> Vec<UpsertGameSourceData> {
+    game.sources
+        .iter()
+        .map(|source| UpsertGameSourceData {
+            source: source.source.clone(),
+            external_id: source.external_id.clone(),
+            data: source.data.clone(),
+        })
+        .collect()
+}
+
+/// 根据是否允许导入启动钩子返回要写入的钩子
+fn incoming_hooks(game: &FullGameData, import_hooks: bool) -> Option<LaunchHooks> {
+    game.launch_hooks.clone().filter(|_| import_hooks)
+}
+
+/// 启动环境变量可以注入动态库等，与启动钩子一同受 `import_hooks` 控制
+fn incoming_launch_env(game: &FullGameData, import_hooks: bool) -> Option<LaunchEnv> {
+    game.launch_env.clone().filter(|_| import_hooks)
+}
+
+/// 未开启导入启动钩子时 Wine 运行器路径沿用 `current`，其余 Wine 配置照常导入
+fn incoming_wine_config(
+    current: Option<&WineConfig>,
+    game: &FullGameData,
+    import_hooks: bool,
+) -> Option<WineConfig> {
+    if import_hooks {
+        return game.wine_config.clone();
+    }
+    let runner_path = current.and_then(|config| config.runner_path.clone());
+    WineConfig {
+        runner_path,
+        ..game.wine_config.clone().unwrap_or_default()
+    }
+    .cleaned()
+}
+
+/// 导入文件中是否有受 `import_hooks` 控制的启动设置
+fn has_launch_commands(game: &FullGameData) -> bool {
+    game.launch_hooks.is_some()
+        || game.launch_env.is_some()
+        || game
+            .wine_config
+            .as_ref()
+            .is_some_and(|config| config.runner_path.is_some())
+}
+
+fn insert_data_from(game: &FullGameData, import_hooks: bool) -> InsertGameData {
+    InsertGameData {
+        id_type: game.id_type.clone(),
+        date: game.date.clone(),
+        localpath: game.localpath.clone(),
+        executable: game.executable.clone(),
+        savepath: game.savepath.clone(),
+        save_registry_keys: game.save_registry_keys.clone(),
+        autosave: game.autosave,
+        maxbackups: game.maxbackups,
+        clear: game.clear,
+        le_launch: game.le_launch,
+        le_profile: game.le_profile.clone(),
+        magpie: game.magpie,
+        launch_args: game.launch_args.clone(),
+        launch_env: incoming_launch_env(game, import_hooks),
+        launch_hooks: incoming_hooks(game, import_hooks),
+        wine_config: incoming_wine_config(None, game, import_hooks),
+        engine: game.engine.clone(),
+        custom_data: game.custom_data.clone(),
+        sources: upsert_sources_of(game),
+    }
+}
+
+/// 插入时未写入的开关类字段，新游戏插入后单独补写
+fn insert_followup_update(game: &FullGameData) -> Option<UpdateGameData> {
+    if game.autosave.is_none()
+        && game.maxbackups.is_none()
+        && game.le_launch.is_none()
+        && game.magpie.is_none()
+    {
+        return None;
+    }
+    Some(UpdateGameData {
+        autosave: game.autosave.map(Some),
+        maxbackups: game.maxbackups.map(Some),
+        le_launch: game.le_launch.map(Some),
+        magpie: game.magpie.map(Some),
+        ..Default::default()
+    })
+}
+
+/// 覆盖策略：所有字段与数据源以导入文件为准
+///
+/// 未开启导入启动钩子时保留已有的钩子、启动环境变量与 Wine 运行器路径。
+fn overwrite_update(
+    current: &FullGameData,
+    incoming: &FullGameData,
+    import_hooks: bool,
+) -> UpdateGameData {
+    let incoming_sources: HashSet<&str> = incoming
+        .sources
+        .iter()
+        .map(|source| source.source.as_str())
+        .collect();
+
+    UpdateGameData {
+        id_type: Some(incoming.id_type.clone()),
+        date: Some(incoming.date.clone()),
+        localpath: Some(incoming.localpath.clone()),
+        executable: Some(incoming.executable.clone()),
+        savepath: Some(incoming.savepath.clone()),
+        save_registry_keys: Some(incoming.save_registry_keys.clone()),
+        autosave: Some(incoming.autosave),
+        maxbackups: Some(incoming.maxbackups),
+        clear: Some(incoming.clear),
+        le_launch: Some(incoming.le_launch),
+        le_profile: Some(incoming.le_profile.clone()),
+        magpie: Some(incoming.magpie),
+        launch_args: Some(incoming.launch_args.clone()),
+        launch_env: import_hooks.then(|| incoming.launch_env.clone()),
+        launch_hooks: import_hooks.then(|| incoming.launch_hooks.clone()),
+        wine_config: Some(incoming_wine_config(
+            current.wine_config.as_ref(),
+            incoming,
+            import_hooks,
+        )),
+        engine: Some(incoming.engine.clone()),
+        custom_data: Some(incoming.custom_data.clone()),
+        upsert_sources: Some(upsert_sources_of(incoming)),
+        remove_sources: Some(
+            current
+                .sources
+                .iter()
+                .filter(|source| !incoming_sources.contains(source.source.as_str()))
+                .map(|source| source.source.clone())
+                .collect(),
+        ),
+    }
+}
+
+/// 已有值为空而导入值非空时返回需要写入的值
+fn fill_empty<T: Clone>(current: &Option<T>, incoming: &Option<T>) -> Option<Option<T>> {
+    (current.is_none() && incoming.is_some()).then(|| incoming.clone())
+}
+
+/// 合并策略：只补全空字段与缺失的数据源，已有值一律保留
+fn merge_update(
+    current: &FullGameData,
+    incoming: &FullGameData,
+    import_hooks: bool,
+) -> UpdateGameData {
+    let current_sources: HashSet<&str> = current
+        .sources
+        .iter()
+        .map(|source| source.source.as_str())
+        .collect();
+    let added_sources: Vec<UpsertGameSourceData> = upsert_sources_of(incoming)
+        .into_iter()
+        .filter(|source| !current_sources.contains(source.source.as_str()))
+        .collect();
+
+    // 数据源变化后同步主类型：单一数据源取其名称，多个数据源为 mixed
+    let id_type = (!added_sources.is_empty()).then(|| {
+        if current_sources.len() + added_sources.len() > 1 {
+            "mixed".to_string()
+        } else {
+            added_sources[0].source.clone()
+        }
+    });
+
+    // 启动文件名依附于目录，只在补全目录时一并写入
+    let localpath = fill_empty(&current.localpath, &incoming.localpath);
+    let executable = localpath
+        .as_ref()
+        .and_then(|_| incoming.executable.clone().map(Some));
+
+    UpdateGameData {
+        id_type,
+        date: fill_empty(&current.date, &incoming.date),
+        localpath,
+        executable,
+        savepath: fill_empty(&current.savepath, &incoming.savepath),
+        save_registry_keys: fill_empty(&current.save_registry_keys, &incoming.save_registry_keys),
+        autosave: fill_empty(&current.autosave, &incoming.autosave),
+        maxbackups: fill_empty(&current.maxbackups, &incoming.maxbackups),
+        clear: fill_empty(&current.clear, &incoming.clear),
+        le_launch: fill_empty(&current.le_launch, &incoming.le_launch),
+        le_profile: fill_empty(&current.le_profile, &incoming.le_profile),
+        magpie: fill_empty(&current.magpie, &incoming.magpie),
+        launch_args: fill_empty(&current.launch_args, &incoming.launch_args),
+        launch_env: fill_empty(
+            &current.launch_env,
+            &incoming_launch_env(incoming, import_hooks),
+        ),
+        launch_hooks: fill_empty(
+            &current.launch_hooks,
+            &incoming_hooks(incoming, import_hooks),
+        ),
+        wine_config: fill_empty(
+            &current.wine_config,
+            &incoming_wine_config(None, incoming, import_hooks),
+        ),
+        engine: fill_empty(&current.engine, &incoming.engine),
+        custom_data: fill_empty(&current.custom_data, &incoming.custom_data),
+        upsert_sources: (!added_sources.is_empty()).then_some(added_sources),
+        remove_sources: None,
+    }
+}
+
+/// 写入导入文件中的游玩会话，返回写入数量
+///
+/// `existing_starts` 中已有相同开始时间的会话视为重复，不再写入。
+async fn insert_sessions(
+    txn: &DatabaseTransaction,
+    game_id: i32,
+    sessions: &[&game_sessions::Model],
+    existing_starts: &HashSet<i32>,
+) -> Result<usize, DbErr> {
+    let mut inserted = 0;
+    for session in sessions {
+        if existing_starts.contains(&session.start_time) {
+            continue;
+        }
+        game_sessions::ActiveModel {
+            session_id: NotSet,
+            game_id: Set(game_id),
+            start_time: Set(session.start_time),
+            end_time: Set(session.end_time),
+            duration: Set(session.duration),
+            date: Set(session.date.clone()),
+            exit_code: Set(session.exit_code),
+            note: Set(session.note.clone()),
+        }
+        .insert(txn)
+        .await?;
+        inserted += 1;
+    }
+    Ok(inserted)
+}
+
+/// 用导入文件的统计替换当前统计；文件中没有统计时从会话重建
+async fn replace_statistics(
+    txn: &DatabaseTransaction,
+    game_id: i32,
+    statistics: Option<&game_statistics::Model>,
+) -> Result<(), DbErr> {
+    GameStatistics::delete_by_id(game_id).exec(txn).await?;
+    match statistics {
+        Some(statistics) => {
+            game_statistics::ActiveModel {
+                game_id: Set(game_id),
+                total_time: Set(statistics.total_time),
+                session_count: Set(statistics.session_count),
+                last_played: Set(statistics.last_played),
+                daily_stats: Set(statistics.daily_stats.clone()),
+            }
+            .insert(txn)
+            .await?;
+            Ok(())
+        }
+        None => GameStatsRepository::rebuild_statistics_in(txn, game_id).await,
+    }
+}
+
+/// 导入单个游戏的游玩会话与统计，返回写入的会话数量
+async fn import_play_records(
+    txn: &DatabaseTransaction,
+    game_id: i32,
+    action: ImportGameAction,
+    sessions: &[&game_sessions::Model],
+    statistics: Option<&game_statistics::Model>,
+) -> Result<usize, DbErr> {
+    match action {
+        ImportGameAction::Skipped => Ok(0),
+        ImportGameAction::Inserted | ImportGameAction::Overwritten => {
+            GameSessions::delete_many()
+                .filter(game_sessions::Column::GameId.eq(game_id))
+                .exec(txn)
+                .await?;
+            let inserted = insert_sessions(txn, game_id, sessions, &HashSet::new()).await?;
+            if statistics.is_some() || inserted > 0 {
+                replace_statistics(txn, game_id, statistics).await?;
+            }
+            Ok(inserted)
+        }
+        ImportGameAction::Merged => {
+            let existing_starts = GameSessions::find()
+                .filter(game_sessions::Column::GameId.eq(game_id))
+                .all(txn)
+                .await?
+                .into_iter()
+                .map(|session| session.start_time)
+                .collect();
+            let inserted = insert_sessions(txn, game_id, sessions, &existing_starts).await?;
+            if inserted > 0 {
+                GameStatsRepository::rebuild_statistics_in(txn, game_id).await?;
+            }
+            Ok(inserted)
+        }
+    }
+}
+
+/// 按名称与父合集匹配已有合集，不存在时创建，返回导入文件合集 ID 到本库合集 ID 的映射
+async fn import_collections(
+    txn: &DatabaseTransaction,
+    incoming: &[collections::Model],
+    report: &mut ImportLibraryReport,
+) -> Result<HashMap<i32, i32>, DbErr> {
+    let mut existing: HashMap<(Option<i32>, String), i32> = Collections::find()
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|collection| ((collection.parent_id, collection.name), collection.id))
+        .collect();
+    let mut id_map = HashMap::new();
+    let mut pending: Vec<&collections::Model> = incoming.iter().collect();
+    let now = chrono::Utc::now().timestamp() as i32;
+
+    // 父合集先于子合集处理；父合集缺失的条目最终被丢弃
+    loop {
+        let before = pending.len();
+        let mut deferred = Vec::new();
+        for collection in pending {
+            let parent_id = match collection.parent_id {
+                None => None,
+                Some(parent) => match id_map.get(&parent) {
+                    Some(mapped) => Some(*mapped),
+                    None => {
+                        deferred.push(collection);
+                        continue;
+                    }
+                },
+            };
+            let key = (parent_id, collection.name.clone());
+            let collection_id = match existing.get(&key) {
+                Some(id) => *id,
+                None => {
+                    let created = collections::ActiveModel {
+                        id: NotSet,
+                        name: Set(collection.name.clone()),
+                        parent_id: Set(parent_id),
+                        sort_order: Set(collection.sort_order),
+                        icon: Set(collection.icon.clone()),
+                        smart_filter: Set(collection.smart_filter.clone()),
+                        created_at: Set(Some(now)),
+                        updated_at: Set(Some(now)),
+                    }
+                    .insert(txn)
+                    .await?;
+                    report.collections_created += 1;
+                    existing.insert(key, created.id);
+                    created.id
+                }
+            };
+            id_map.insert(collection.id, collection_id);
+        }
+        if deferred.is_empty() || deferred.len() == before {
+            if !deferred.is_empty() {
+                log::warn!("{} 个合集的父合集不存在，已跳过", deferred.len());
+            }
+            break;
+        }
+        pending = deferred;
+    }
+
+    Ok(id_map)
+}
+
+/// 在单个事务内执行导入
+async fn import_library_in(
+    txn: &DatabaseTransaction,
+    existing_games: Vec<FullGameData>,
+    export: LibraryExport,
+    strategy: ImportConflictStrategy,
+    import_hooks: bool,
+) -> Result<ImportLibraryReport, DbErr> {
+    let mut report = ImportLibraryReport {
+        total: export.games.len(),
+        ..Default::default()
+    };
+    let mut index = GameMatchIndex::from_games(&existing_games);
+    let mut current_games: HashMap<i32, FullGameData> = existing_games
+        .into_iter()
+        .map(|game| (game.id, game))
+        .collect();
+
+    let mut sessions_by_game: HashMap<i32, Vec<&game_sessions::Model>> = HashMap::new();
+    for session in &export.sessions {
+        sessions_by_game
+            .entry(session.game_id)
+            .or_default()
+            .push(session);
+    }
+    let statistics_by_game: HashMap<i32, &game_statistics::Model> = export
+        .statistics
+        .iter()
+        .map(|statistics| (statistics.game_id, statistics))
+        .collect();
+
+    let now = chrono::Utc::now().timestamp() as i32;
+    // 导入文件游戏 ID -> 本库游戏 ID，仅包含实际写入的游戏
+    let mut game_id_map = HashMap::new();
+
+    for game in &export.games {
+        let matched = index.find(game);
+        let (game_id, action, written_hooks) = match matched {
+            None => {
+                let data = insert_data_from(game, import_hooks).cleaned();
+                let written_hooks = data.launch_hooks.clone();
+                let inserted = GamesRepository::insert_aggregate(txn, data, now).await?;
+                if let Some(updates) = insert_followup_update(game) {
+                    GamesRepository::update_aggregate(txn, inserted.id, updates.cleaned(), now)
+                        .await?;
+                }
+                index.insert(game, inserted.id);
+                (inserted.id, ImportGameAction::Inserted, written_hooks)
+            }
+            Some((game_id, _)) => {
+                let current = current_games
+                    .get(&game_id)
+                    .ok_or_else(|| DbErr::RecordNotFound(format!("game {game_id} not found")))?;
+                let (updates, action) = match strategy {
+                    ImportConflictStrategy::Skip => (None, ImportGameAction::Skipped),
+                    ImportConflictStrategy::Overwrite => (
+                        Some(overwrite_update(current, game, import_hooks)),
+                        ImportGameAction::Overwritten,
+                    ),
+                    ImportConflictStrategy::Merge => (
+                        Some(merge_update(current, game, import_hooks)),
+                        ImportGameAction::Merged,
+                    ),
+                };
+                let mut written_hooks = None;
+                if let Some(updates) = updates {
+                    let updates = updates.cleaned();
+                    written_hooks = updates.launch_hooks.clone().flatten();
+                    let updated =
+                        GamesRepository::update_aggregate(txn, game_id, updates, now).await?;
+                    current_games.insert(game_id, updated);
+                }
+                (game_id, action, written_hooks)
+            }
+        };
+
+        if let Some(hooks) = written_hooks {
+            report.imported_hooks.push(ImportedLaunchHooks {
+                game_id,
+                name: game.display_name(),
+                hooks,
+            });
+        } else if !import_hooks && action != ImportGameAction::Skipped && has_launch_commands(game)
+        {
+            report.hooks_ignored += 1;
+        }
+
+        match action {
+            ImportGameAction::Inserted => report.inserted += 1,
+            ImportGameAction::Skipped => report.skipped += 1,
+            ImportGameAction::Overwritten => report.overwritten += 1,
+            ImportGameAction::Merged => report.merged += 1,
+        }
+        if action != ImportGameAction::Skipped {
+            game_id_map.insert(game.id, game_id);
+            report.sessions_added += import_play_records(
+                txn,
+                game_id,
+                action,
+                sessions_by_game
+                    .get(&game.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                statistics_by_game.get(&game.id).copied(),
+            )
+            .await?;
+        }
+        report.items.push(ImportLibraryItem {
+            source_id: game.id,
+            name: game.display_name(),
+            action,
+            game_id,
+            matched_by: matched.map(|(_, matched_by)| matched_by.to_string()),
+        });
+    }
+
+    let collection_id_map = import_collections(txn, &export.collections, &mut report).await?;
+    let mut existing_links: HashSet<(i32, i32)> = GameCollectionLink::find()
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|link| (link.game_id, link.collection_id))
+        .collect();
+    for link in &export.collection_links {
+        let (Some(game_id), Some(collection_id)) = (
+            game_id_map.get(&link.game_id),
+            collection_id_map.get(&link.collection_id),
+        ) else {
+            continue;
+        };
+        if !existing_links.insert((*game_id, *collection_id)) {
+            continue;
+        }
+        game_collection_link::ActiveModel {
+            id: NotSet,
+            game_id: Set(*game_id),
+            collection_id: Set(*collection_id),
+            sort_order: Set(link.sort_order),
+            created_at: Set(Some(now)),
+        }
+        .insert(txn)
+        .await?;
+        report.links_added += 1;
+    }
+
+    let mut existing_savedata: HashSet<(i32, String)> = Savedata::find()
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|record| (record.game_id, record.file))
+        .collect();
+    for record in &export.savedata {
+        let Some(game_id) = game_id_map.get(&record.game_id) else {
+            continue;
+        };
+        if !existing_savedata.insert((*game_id, record.file.clone())) {
+            continue;
+        }
+        savedata::ActiveModel {
+            id: NotSet,
+            game_id: Set(*game_id),
+            file: Set(record.file.clone()),
+            backup_time: Set(record.backup_time),
+            file_size: Set(record.file_size),
+            pinned: Set(record.pinned),
+            label: Set(record.label.clone()),
+            comment: Set(record.comment.clone()),
+        }
+        .insert(txn)
+        .await?;
+        report.savedata_added += 1;
+    }
+
+    Ok(report)
+}
+
+/// 从 JSON 导出文件导入游戏库
+///
+/// # Arguments
+/// * `path` - 导出文件路径
+/// * `strategy` - 与已有游戏冲突时的处理策略
+/// * `import_hooks` - 是否导入启动钩子，默认不导入
+#[command]
+pub async fn import_library(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+    strategy: ImportConflictStrategy,
+    import_hooks: Option<bool>,
+) -> Result<ImportLibraryReport, String> {
+    let import_hooks = import_hooks.unwrap_or(false);
+    let content = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("读取导入文件失败: {}", e))?;
+    let export: LibraryExport =
+        serde_json::from_slice(&content).map_err(|e| format!("解析导入文件失败: {}", e))?;
+    if export.version > LIBRARY_EXPORT_VERSION {
+        return Err(format!(
+            "导入文件格式版本 {} 高于当前支持的版本 {}，请先升级应用",
+            export.version, LIBRARY_EXPORT_VERSION
+        ));
+    }
+
+    let existing_games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| format!("开启导入事务失败: {}", e))?;
+    let report = import_library_in(&txn, existing_games, export, strategy, import_hooks)
+        .await
+        .map_err(|e| format!("导入游戏库失败: {}", e))?;
+    txn.commit()
+        .await
+        .map_err(|e| format!("提交导入事务失败: {}", e))?;
+
+    log::info!(
+        "游戏库导入完成 path={} strategy={:?} total={} inserted={} skipped={} overwritten={} merged={} hooks_imported={} hooks_ignored={}",
+        path,
+        strategy,
+        report.total,
+        report.inserted,
+        report.skipped,
+        report.overwritten,
+        report.merged,
+        report.imported_hooks.len(),
+        report.hooks_ignored
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn game(value: serde_json::Value) -> FullGameData {
+        serde_json::from_value(value).expect("测试游戏数据应能反序列化")
+    }
+
+    #[test]
+    fn match_index_prefers_external_id_over_localpath() {
+        let mut index = GameMatchIndex::default();
+        index.insert(
+            &game(json!({
+                "id": 1,
+                "id_type": "bgm",
+                "localpath": "/games/A",
+                "sources": [{ "source": "bgm", "external_id": "100", "data": null }]
+            })),
+            1,
+        );
+        index.insert(
+            &game(json!({ "id": 2, "id_type": "custom", "localpath": "/games/B", "sources": [] })),
+            2,
+        );
+
+        let by_id = game(json!({
+            "id": 9,
+            "id_type": "bgm",
+            "localpath": "/games/B",
+            "sources": [{ "source": "bgm", "external_id": "100", "data": null }]
+        }));
+        assert_eq!(index.find(&by_id), Some((1, "bgm")));
+
+        let by_path =
+            game(json!({ "id": 9, "id_type": "custom", "localpath": "/games/B", "sources": [] }));
+        assert_eq!(index.find(&by_path), Some((2, "localpath")));
+
+        let unmatched =
+            game(json!({ "id": 9, "id_type": "custom", "localpath": "/games/C", "sources": [] }));
+        assert_eq!(index.find(&unmatched), None);
+    }
+
+    #[test]
+    fn merge_only_fills_empty_fields_and_missing_sources() {
+        let current = game(json!({
+            "id": 1,
+            "id_type": "bgm",
+            "savepath": "/saves/A",
+            "clear": 2,
+            "sources": [{ "source": "bgm", "external_id": "100", "data": null }]
+        }));
+        let incoming = game(json!({
+            "id": 7,
+            "id_type": "mixed",
+            "localpath": "/games/A",
+            "executable": "game.exe",
+            "savepath": "/other/saves",
+            "clear": 1,
+            "sources": [
+                { "source": "bgm", "external_id": "999", "data": null },
+                { "source": "vndb", "external_id": "v17", "data": null }
+            ]
+        }));
+
+        let updates = merge_update(&current, &incoming, false);
+        assert_eq!(updates.id_type.as_deref(), Some("mixed"));
+        assert_eq!(updates.localpath, Some(Some("/games/A".to_string())));
+        assert_eq!(updates.executable, Some(Some("game.exe".to_string())));
+        assert_eq!(updates.savepath, None);
+        assert_eq!(updates.clear, None);
+        let added = updates.upsert_sources.expect("应补全缺失的数据源");
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].source, "vndb");
+        assert!(updates.remove_sources.is_none());
+    }
+
+    #[test]
+    fn launch_hooks_are_only_imported_when_enabled() {
+        let current = game(json!({
+            "id": 1,
+            "id_type": "custom",
+            "wine_config": { "runner_path": "/usr/bin/wine" },
+            "sources": []
+        }));
+        let empty = game(json!({ "id": 2, "id_type": "custom", "sources": [] }));
+        let incoming = game(json!({
+            "id": 7,
+            "id_type": "custom",
+            "launch_hooks": { "pre_launch": [{ "program": "calc.exe" }] },
+            "launch_env": { "LD_PRELOAD": "/tmp/hook.so" },
+            "wine_config": { "runner_path": "/tmp/runner.sh", "prefix": "/tmp/prefix" },
+            "sources": []
+        }));
+        assert!(has_launch_commands(&incoming));
+
+        let inserted = insert_data_from(&incoming, false);
+        assert!(inserted.launch_hooks.is_none());
+        assert!(inserted.launch_env.is_none());
+        let wine_config = inserted.wine_config.unwrap();
+        assert_eq!(wine_config.runner_path, None);
+        assert_eq!(wine_config.prefix.as_deref(), Some("/tmp/prefix"));
+
+        // 覆盖时保留已有的运行器路径，其余 Wine 配置以导入文件为准
+        let overwritten = overwrite_update(&current, &incoming, false);
+        assert!(overwritten.launch_hooks.is_none());
+        assert!(overwritten.launch_env.is_none());
+        let wine_config = overwritten.wine_config.unwrap().unwrap();
+        assert_eq!(wine_config.runner_path.as_deref(), Some("/usr/bin/wine"));
+        assert_eq!(wine_config.prefix.as_deref(), Some("/tmp/prefix"));
+
+        let merged = merge_update(&empty, &incoming, false);
+        assert!(merged.launch_hooks.is_none());
+        assert!(merged.launch_env.is_none());
+        assert_eq!(merged.wine_config.unwrap().unwrap().runner_path, None);
+
+        let inserted = insert_data_from(&incoming, true);
+        assert!(inserted.launch_hooks.is_some());
+        assert!(inserted.launch_env.is_some());
+        assert_eq!(
+            inserted.wine_config.unwrap().runner_path.as_deref(),
+            Some("/tmp/runner.sh")
+        );
+        let overwritten = overwrite_update(&current, &incoming, true);
+        assert!(matches!(overwritten.launch_hooks, Some(Some(_))));
+        assert!(matches!(overwritten.launch_env, Some(Some(_))));
+        assert_eq!(
+            overwritten
+                .wine_config
+                .unwrap()
+                .unwrap()
+                .runner_path
+                .as_deref(),
+            Some("/tmp/runner.sh")
+        );
+        let merged = merge_update(&empty, &incoming, true);
+        assert!(matches!(merged.launch_hooks, Some(Some(_))));
+        assert!(matches!(merged.launch_env, Some(Some(_))));
+    }
+}
@@ -0,0 +1,221 @@
+//! 注册表存档
+//!
+//! 部分老游戏把进度写在 `HKEY_CURRENT_USER` 注册表中。创建存档备份时用 `reg export` 把游戏配置的
+//! `save_registry_keys` 逐个导出为 .reg 文件，放进压缩包的 [`REGISTRY_DIR`] 目录；恢复时先用
+//! `reg import` 导入，再把其余文件还原到存档目录。非 Windows 系统跳过注册表并记录警告。
+//!
+//! 导入前会解析每个 .reg 文件的 `[HKEY_...]` 键头，只要有一个键不在游戏的
+//! `save_registry_keys` 之内就拒绝导入，避免被篡改的备份改写其它注册表项。
+
+use crate::entity::save_options::normalize_registry_key;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 压缩包中存放导出的注册表文件的目录
+pub const REGISTRY_DIR: &str = ".reina_registry";
+
+/// 把注册表键导出为 `export_dir` 中的 .reg 文件，返回导出的文件数
+///
+/// 尚不存在的键（如游戏还未运行过）跳过。`export_dir` 整体写入压缩包的 [`REGISTRY_DIR`] 目录。
+pub fn export_registry_keys(keys: &[String], export_dir: &Path) -> Result<usize, String> {
+    let keys = keys
+        .iter()
+        .map(|key| normalize_registry_key(key))
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.is_empty() {
+        return Ok(0);
+    }
+    platform_export(&keys, export_dir)
+}
+
+#[cfg(target_os = "windows")]
+fn reg_command() -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut command = std::process::Command::new("reg");
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn run_reg(command: &mut std::process::Command) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("无法运行 reg: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_export(keys: &[String], export_dir: &Path) -> Result<usize, String> {
+    fs::create_dir_all(export_dir).map_err(|e| format!("创建注册表导出目录失败: {}", e))?;
+    let mut exported = 0;
+    for (index, key) in keys.iter().enumerate() {
+        if run_reg(reg_command().arg("query").arg(key)).is_err() {
+            log::warn!("注册表键不存在，跳过导出: {}", key);
+            continue;
+        }
+        let file = export_dir.join(format!("{}.reg", index));
+        run_reg(reg_command().arg("export").arg(key).arg(&file).arg("/y"))
+            .map_err(|e| format!("导出注册表键失败 {}: {}", key, e))?;
+        exported += 1;
+    }
+    log::info!("已导出 {} 个注册表键", exported);
+    Ok(exported)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_export(keys: &[String], _export_dir: &Path) -> Result<usize, String> {
+    log::warn!("当前系统不支持注册表存档，已跳过 {} 个注册表键", keys.len());
+    Ok(0)
+}
+
+/// 解码 .reg 文件内容：`reg export` 输出带 BOM 的 UTF-16LE，手写文件通常为 UTF-8
+fn decode_reg_file(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// 列出 .reg 文件中所有 `[...]` 键头（删除键的 `[-...]` 同样列出）
+fn registry_file_keys(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.strip_prefix('[')?.strip_suffix(']'))
+        .map(|key| key.strip_prefix('-').unwrap_or(key).to_string())
+        .collect()
+}
+
+/// 键等于允许的键或位于其子键下
+fn is_allowed_registry_key(key: &str, allowed: &[String]) -> bool {
+    let key = key.to_ascii_lowercase();
+    allowed.iter().any(|allowed| {
+        let allowed = allowed.to_ascii_lowercase();
+        key == allowed
+            || key
+                .strip_prefix(&allowed)
+                .is_some_and(|rest| rest.starts_with('\\'))
+    })
+}
+
+/// 检查 .reg 文件只涉及允许的注册表键
+fn validate_registry_file(file: &Path, allowed: &[String]) -> Result<(), String> {
+    let bytes = fs::read(file).map_err(|e| format!("读取注册表备份失败: {}", e))?;
+    for key in registry_file_keys(&decode_reg_file(&bytes)) {
+        let allowed_key =
+            normalize_registry_key(&key).is_ok_and(|key| is_allowed_registry_key(&key, allowed));
+        if !allowed_key {
+            return Err(format!(
+                "注册表备份 {} 包含未在游戏中配置的注册表键，已拒绝导入: {}",
+                file.file_name().unwrap_or_default().to_string_lossy(),
+                key
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 按文件名顺序导入 `dir` 中的 .reg 文件，返回导入的文件数
+///
+/// 所有文件中的键都必须位于 `allowed_keys`（游戏的 `save_registry_keys`）之内，
+/// 任一文件不符合时整体拒绝，不导入任何文件。
+pub fn import_registry_dir(dir: &Path, allowed_keys: &[String]) -> Result<usize, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("读取注册表备份失败: {}", e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("reg"))
+        })
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Ok(0);
+    }
+
+    let allowed: Vec<String> = allowed_keys
+        .iter()
+        .filter_map(|key| normalize_registry_key(key).ok())
+        .collect();
+    for file in &files {
+        validate_registry_file(file, &allowed)?;
+    }
+    platform_import(&files)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_import(files: &[PathBuf]) -> Result<usize, String> {
+    for file in files {
+        run_reg(reg_command().arg("import").arg(file))
+            .map_err(|e| format!("导入注册表失败 {}: {}", file.display(), e))?;
+    }
+    log::info!("已导入 {} 个注册表文件", files.len());
+    Ok(files.len())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_import(files: &[PathBuf]) -> Result<usize, String> {
+    log::warn!(
+        "当前系统不支持注册表存档，已跳过 {} 个注册表文件",
+        files.len()
+    );
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16_reg(content: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn parses_section_headers_from_utf16_export() {
+        let content = "Windows Registry Editor Version 5.00\r\n\r\n\
+            [HKEY_CURRENT_USER\\Software\\Game]\r\n\"Save\"=dword:00000001\r\n\r\n\
+            [-HKEY_CURRENT_USER\\Software\\Game\\Old]\r\n";
+        assert_eq!(
+            registry_file_keys(&decode_reg_file(&utf16_reg(content))),
+            vec![
+                r"HKEY_CURRENT_USER\Software\Game",
+                r"HKEY_CURRENT_USER\Software\Game\Old"
+            ]
+        );
+    }
+
+    #[test]
+    fn only_keys_under_configured_keys_are_allowed() {
+        let allowed = vec![r"HKEY_CURRENT_USER\Software\Game".to_string()];
+        assert!(is_allowed_registry_key(
+            r"HKEY_CURRENT_USER\Software\Game",
+            &allowed
+        ));
+        assert!(is_allowed_registry_key(
+            r"hkey_current_user\software\game\Slot1",
+            &allowed
+        ));
+        assert!(!is_allowed_registry_key(
+            r"HKEY_CURRENT_USER\Software\GameOther",
+            &allowed
+        ));
+        assert!(!is_allowed_registry_key(
+            r"HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Run",
+            &allowed
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! 存档备份保留策略
+//!
+//! 按数量（游戏的 maxbackups）、按时间（最近 N 天）以及"每天一份 + 每周一份"的
+//! 分层规则决定保留哪些备份。各规则取并集：命中任意一条即保留，其余备份连同
+//! 数据库记录一起删除。锁定的备份始终保留，也不占用数量名额。
+
+use super::savedata::{delete_backup_record, resolve_savedata_backup_root};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::savedata;
+use crate::entity::user::BackupRetention;
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{State, command};
+
+/// 保留策略执行结果
+#[derive(Debug, Default, Serialize)]
+pub struct RetentionResult {
+    pub kept: usize,
+    pub deleted: usize,
+    pub errors: Vec<String>,
+}
+
+fn local_time(backup_time: i32) -> Option<DateTime<Local>> {
+    Local.timestamp_opt(i64::from(backup_time), 0).single()
+}
+
+/// 按时间从新到旧遍历，每个分组（日期或周）保留最新的一份，直到凑够 `limit` 个分组
+fn keep_latest_per_group<K: Eq + std::hash::Hash>(
+    records: &[&savedata::Model],
+    limit: Option<u32>,
+    group_key: impl Fn(DateTime<Local>) -> K,
+    keep: &mut HashSet<i32>,
+) {
+    let Some(limit) = limit.map(|limit| limit as usize) else {
+        return;
+    };
+    let mut groups = HashSet::new();
+    for record in records {
+        if groups.len() >= limit {
+            break;
+        }
+        if let Some(time) = local_time(record.backup_time)
+            && groups.insert(group_key(time))
+        {
+            keep.insert(record.id);
+        }
+    }
+}
+
+/// 计算按保留策略应删除的备份
+///
+/// # Arguments
+/// * `records` - 游戏的全部备份记录
+/// * `max_count` - 保留最新的备份数量，为空时不按数量保留
+/// * `retention` - 按时间与分层规则额外保留的备份
+/// * `now` - 当前时间
+///
+/// 所有规则均为空时不删除任何备份。
+pub fn expired_backups<'a>(
+    records: &'a [savedata::Model],
+    max_count: Option<usize>,
+    retention: &BackupRetention,
+    now: DateTime<Local>,
+) -> Vec<&'a savedata::Model> {
+    if max_count.is_none()
+        && retention.keep_days.is_none()
+        && retention.keep_daily.is_none()
+        && retention.keep_weekly.is_none()
+    {
+        return Vec::new();
+    }
+
+    let mut sorted: Vec<&savedata::Model> =
+        records.iter().filter(|record| !record.pinned).collect();
+    sorted.sort_by(|a, b| b.backup_time.cmp(&a.backup_time).then(b.id.cmp(&a.id)));
+
+    let mut keep: HashSet<i32> = sorted
+        .iter()
+        .take(max_count.unwrap_or(0))
+        .map(|record| record.id)
+        .collect();
+
+    if let Some(days) = retention.keep_days {
+        let cutoff = now.timestamp() - i64::from(days) * 86_400;
+        keep.extend(
+            sorted
+                .iter()
+                .filter(|record| i64::from(record.backup_time) >= cutoff)
+                .map(|record| record.id),
+        );
+    }
+
+    keep_latest_per_group(
+        &sorted,
+        retention.keep_daily,
+        |time| time.date_naive(),
+        &mut keep,
+    );
+    keep_latest_per_group(
+        &sorted,
+        retention.keep_weekly,
+        |time| {
+            let week = time.iso_week();
+            (week.year(), week.week())
+        },
+        &mut keep,
+    );
+
+    sorted
+        .into_iter()
+        .filter(|record| !keep.contains(&record.id))
+        .collect()
+}
+
+/// 按保留策略清理游戏的存档备份
+///
+/// # Arguments
+/// * `reserved` - 为即将创建的备份预留的数量，会从最大备份数量中扣除
+pub(crate) async fn enforce_retention(
+    db: &DatabaseConnection,
+    game_id: i32,
+    reserved: usize,
+) -> Result<RetentionResult, String> {
+    let game = GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(|e| format!("获取游戏信息失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let retention = db.get_settings().await?.backup_settings().retention;
+    let records = GamesRepository::get_savedata_records(db, game_id)
+        .await
+        .map_err(|e| format!("获取备份记录失败: {}", e))?;
+
+    let max_count = game
+        .maxbackups
+        .map(|max| usize::try_from(max).unwrap_or(0).saturating_sub(reserved));
+    let expired = expired_backups(&records, max_count, &retention, Local::now());
+    if expired.is_empty() {
+        return Ok(RetentionResult {
+            kept: records.len(),
+            ..Default::default()
+        });
+    }
+
+    let backup_dir = resolve_savedata_backup_root(db)
+        .await?
+        .join(format!("game_{}", game_id));
+    let mut result = RetentionResult::default();
+    for record in &expired {
+        match delete_backup_record(db, &backup_dir.join(&record.file), record.id).await {
+            Some(error) => result.errors.push(error),
+            None => result.deleted += 1,
+        }
+    }
+    result.kept = records.len() - expired.len();
+
+    log::debug!(
+        "存档备份保留策略执行完成 game_id={} kept={} deleted={}",
+        game_id,
+        result.kept,
+        result.deleted
+    );
+    if !result.errors.is_empty() {
+        log::warn!(
+            "执行备份保留策略时遇到 {} 个错误:\n{}",
+            result.errors.len(),
+            result.errors.join("\n")
+        );
+    }
+    Ok(result)
+}
+
+/// 按保留策略清理指定游戏的存档备份
+///
+/// # Arguments
+/// * `game_id` - 游戏ID
+#[command]
+pub async fn apply_retention_policy(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<RetentionResult, String> {
+    enforce_retention(&db, game_id, 0).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: i32, time: DateTime<Local>) -> savedata::Model {
+        savedata::Model {
+            id,
+            game_id: 1,
+            file: format!("savedata_{}.7z", id),
+            backup_time: time.timestamp() as i32,
+            file_size: 0,
+            pinned: false,
+            label: None,
+            comment: None,
+        }
+    }
+
+    fn ids(records: Vec<&savedata::Model>) -> Vec<i32> {
+        let mut ids: Vec<i32> = records.into_iter().map(|record| record.id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn combines_count_age_and_tiered_rules() {
+        let now = Local.with_ymd_and_hms(2024, 6, 20, 12, 0, 0).unwrap();
+        let hours_ago = |hours: i64| now - chrono::Duration::hours(hours);
+        let records = vec![
+            record(1, hours_ago(1)),
+            record(2, hours_ago(2)),
+            record(3, hours_ago(24)),
+            record(4, hours_ago(25)),
+            record(5, hours_ago(24 * 9)),
+            record(6, hours_ago(24 * 10)),
+            record(7, hours_ago(24 * 40)),
+            savedata::Model {
+                pinned: true,
+                ..record(8, hours_ago(24 * 400))
+            },
+        ];
+
+        let none = BackupRetention::default();
+        assert!(expired_backups(&records, None, &none, now).is_empty());
+        assert_eq!(
+            ids(expired_backups(&records, Some(2), &none, now)),
+            [3, 4, 5, 6, 7]
+        );
+
+        let days = BackupRetention {
+            keep_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(ids(expired_backups(&records, Some(0), &days, now)), [7]);
+
+        let tiered = BackupRetention {
+            keep_daily: Some(2),
+            keep_weekly: Some(3),
+            ..Default::default()
+        };
+        // 每天：1（今天）、3（昨天）；每周：1（本周）、5（上周）、7
+        assert_eq!(
+            ids(expired_backups(&records, Some(0), &tiered, now)),
+            [2, 4, 6]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! 存档路径模板
+//!
+//! `savepath` 中的每个路径都可以使用 `%VAR%` 环境变量、开头的 `~`（用户主目录）与 `{game_dir}`（游戏目录）占位符。
+//! 数据库中保存模板原文，备份、恢复与失效路径巡检时在后端按当前电脑展开，换电脑后路径依然有效。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use sea_orm::DatabaseConnection;
+use std::path::PathBuf;
+use tauri::{State, command};
+
+/// 游戏目录占位符
+pub const GAME_DIR_PLACEHOLDER: &str = "{game_dir}";
+
+/// 当前用户的主目录
+fn home_dir() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let home = std::env::var("USERPROFILE");
+    #[cfg(not(target_os = "windows"))]
+    let home = std::env::var("HOME");
+    home.ok().filter(|home| !home.is_empty())
+}
+
+/// 环境变量名只允许字母、数字与 `_()`，如 `ProgramFiles(x86)`
+fn is_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '(' | ')'))
+}
+
+fn expand_env_vars(
+    template: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%').map(|end| &after[..end]) {
+            Some(name) if is_env_name(name) => {
+                let value = lookup(name).ok_or_else(|| format!("环境变量未定义: %{}%", name))?;
+                expanded.push_str(&value);
+                rest = &after[name.len() + 1..];
+            }
+            _ => {
+                expanded.push('%');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn expand_with(
+    template: &str,
+    game_dir: Option<&str>,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    home: Option<String>,
+) -> Result<PathBuf, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("存档路径为空".to_string());
+    }
+    let mut path = expand_env_vars(template, lookup)?;
+
+    if path == "~" || path.starts_with("~/") || path.starts_with("~\\") {
+        let home = home.ok_or_else(|| "无法确定用户主目录".to_string())?;
+        path = format!("{}{}", home, &path[1..]);
+    }
+
+    if path.contains(GAME_DIR_PLACEHOLDER) {
+        let game_dir = game_dir
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .ok_or_else(|| {
+                format!(
+                    "存档路径使用了 {}，但游戏未设置本地目录",
+                    GAME_DIR_PLACEHOLDER
+                )
+            })?;
+        let game_dir = game_dir.trim_end_matches(['/', '\\']);
+        path = path.replace(GAME_DIR_PLACEHOLDER, game_dir);
+    }
+    Ok(PathBuf::from(path))
+}
+
+/// 展开存档路径模板
+///
+/// # Arguments
+/// * `template` - 数据库中保存的 `savepath`
+/// * `game_dir` - 游戏目录，用于替换 `{game_dir}`
+pub fn expand_save_path(template: &str, game_dir: Option<&str>) -> Result<PathBuf, String> {
+    expand_with(
+        template,
+        game_dir,
+        &|name| std::env::var(name).ok(),
+        home_dir(),
+    )
+}
+
+/// 按游戏的 `localpath` 展开游戏的全部存档路径，未设置存档路径时返回空列表
+pub(crate) fn expand_game_save_paths(game: &FullGameData) -> Result<Vec<PathBuf>, String> {
+    game.savepath
+        .iter()
+        .flat_map(|savepaths| &savepaths.0)
+        .filter(|savepath| !savepath.trim().is_empty())
+        .map(|savepath| expand_save_path(savepath, game.localpath.as_deref()))
+        .collect()
+}
+
+/// 获取游戏展开后的全部存档路径，供打开文件夹等直接使用路径的场景
+#[command]
+pub async fn resolve_game_savepaths(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<String>, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    Ok(expand_game_save_paths(&game)?
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "APPDATA" => Some(r"C:\Users\reina\AppData\Roaming".to_string()),
+            "ProgramFiles(x86)" => Some(r"C:\Program Files (x86)".to_string()),
+            _ => None,
+        }
+    }
+
+    fn expand(template: &str, game_dir: Option<&str>) -> Result<PathBuf, String> {
+        expand_with(template, game_dir, &lookup, Some("/home/reina".to_string()))
+    }
+
+    #[test]
+    fn expands_env_vars_home_and_game_dir() {
+        assert_eq!(
+            expand(r"%APPDATA%\Yuzusoft\Senren", None).unwrap(),
+            PathBuf::from(r"C:\Users\reina\AppData\Roaming\Yuzusoft\Senren")
+        );
+        assert_eq!(
+            expand(r"%ProgramFiles(x86)%\Key\save", None).unwrap(),
+            PathBuf::from(r"C:\Program Files (x86)\Key\save")
+        );
+        assert_eq!(
+            expand("~/.local/share/saves", None).unwrap(),
+            PathBuf::from("/home/reina/.local/share/saves")
+        );
+        assert_eq!(
+            expand("{game_dir}/savedata", Some("/games/Summer Pockets/")).unwrap(),
+            PathBuf::from("/games/Summer Pockets/savedata")
+        );
+        assert_eq!(
+            expand("/saves/100%/50% off", None).unwrap(),
+            PathBuf::from("/saves/100%/50% off")
+        );
+    }
+
+    #[test]
+    fn rejects_unresolvable_templates() {
+        assert_eq!(
+            expand(r"%LOCALAPPDATA%\save", None).unwrap_err(),
+            "环境变量未定义: %LOCALAPPDATA%"
+        );
+        assert!(expand("{game_dir}/save", None).is_err());
+        assert!(expand_with("~/save", None, &lookup, None).is_err());
+        assert!(expand("  ", None).is_err());
+    }
+}
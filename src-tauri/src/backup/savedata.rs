@@ -1,11 +1,23 @@
-use super::archive::{create_7z_archive, extract_7z_archive};
+use super::archive::{
+    create_7z_archive_from_dirs, extract_7z_archive_to_staging, replace_dir_with_staging,
+};
+use super::registry::{REGISTRY_DIR, export_registry_keys, import_registry_dir};
+use super::retention::enforce_retention;
+use super::save_path::expand_game_save_paths;
+use crate::database::dto::FullGameData;
 use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::user::BackupCompression;
+use crate::error::AppError;
+use crate::utils::credentials::{delete_secret, read_secret, write_secret};
+use crate::utils::fs::{COPY_CANCELLED, copy_dir_chunked};
+use crate::utils::tasks::{TaskKind, TaskManager};
 use chrono::Utc;
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::{State, command};
+use tauri::{AppHandle, Runtime, State, command};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackupInfo {
@@ -13,9 +25,75 @@ pub struct BackupInfo {
     pub backup_time: i64,
     pub file_size: u64,
     pub backup_path: String,
+    /// 是否以密码加密
+    pub encrypted: bool,
 }
+
+/// 加密备份文件名的后缀
+const ENCRYPTED_BACKUP_SUFFIX: &str = "_enc.7z";
+
+/// 游戏有多个存档路径时，压缩包中按路径序号存放各目录的位置（`.reina_paths/<序号>`）
+///
+/// 只有一个存档路径时内容直接放在压缩包根目录，与旧版备份格式一致。
+const SAVE_PATHS_DIR: &str = ".reina_paths";
+
+/// 系统凭据管理器中默认备份密码的键名
+const BACKUP_PASSWORD_CREDENTIAL: &str = "savedata_backup_password";
+
+/// 根据文件名判断备份是否加密
+pub(super) fn is_encrypted_backup(file_name: &str) -> bool {
+    file_name.ends_with(ENCRYPTED_BACKUP_SUFFIX)
+}
+
+/// 调用方未提供密码时使用保存在系统凭据管理器中的默认备份密码
+pub(super) async fn password_or_stored(password: Option<String>) -> Option<String> {
+    if let Some(password) = password.filter(|password| !password.is_empty()) {
+        return Some(password);
+    }
+    read_secret(BACKUP_PASSWORD_CREDENTIAL)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("读取默认备份密码失败: {}", e);
+            None
+        })
+        .filter(|password| !password.is_empty())
+}
+
+/// 保存默认备份密码到系统凭据管理器，传空值时删除
+///
+/// 自动备份与未输入密码的恢复、对比都会使用该密码。
+#[command]
+pub async fn set_savedata_backup_password(password: Option<String>) -> Result<(), String> {
+    let result = match password.filter(|password| !password.is_empty()) {
+        Some(password) => write_secret(BACKUP_PASSWORD_CREDENTIAL, &password).await,
+        None => delete_secret(BACKUP_PASSWORD_CREDENTIAL).await,
+    };
+    result.map_err(|e| format!("保存默认备份密码失败: {}", e))
+}
+
+/// 是否已保存默认备份密码
+#[command]
+pub async fn has_savedata_backup_password() -> Result<bool, String> {
+    Ok(password_or_stored(None).await.is_some())
+}
+
+async fn load_game(db: &DatabaseConnection, game_id: i32) -> Result<FullGameData, String> {
+    GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))
+}
+
+/// 开启默认加密时取已保存的默认备份密码，没有时拒绝备份而不是写出明文备份
+fn default_encryption_password(stored: Option<String>, game_id: i32) -> Result<String, AppError> {
+    stored.ok_or(AppError::BackupPasswordRequired { game_id })
+}
+
 /// 创建游戏存档备份
 ///
+/// 游戏的全部存档路径（见 [`super::save_path`]）打包进同一个压缩包；
+/// 配置了 `save_registry_keys` 时，注册表键导出为 .reg 文件一并打包（见 [`super::registry`]）。
+///
 /// 备份目录优先级：
 /// 1. 使用 user.save_root_path/backups（如果设置且非空）
 /// 2. 使用默认路径：
@@ -25,25 +103,42 @@ pub struct BackupInfo {
 /// # Arguments
 /// * `app` - Tauri应用句柄
 /// * `game_id` - 游戏ID
-/// * `source_path` - 源存档文件夹路径
+/// * `compression` - 压缩方式，为空时使用设置中的默认压缩方式
+/// * `compression_level` - 压缩等级，与 `compression` 同时为空时使用设置中的默认等级
+/// * `password` - 备份密码，提供时以 AES-256 加密；设置中开启默认加密而未提供时使用已保存的默认备份密码
 ///
 /// # Returns
-/// * `Result<BackupInfo, String>` - 备份信息或错误消息
+/// * `Result<BackupInfo, AppError>` - 备份信息或错误；开启默认加密却没有可用密码时返回
+///   `backup_password_required`，不会退回为不加密备份
 #[tauri::command]
 pub async fn create_savedata_backup(
     db: State<'_, DatabaseConnection>,
     game_id: i64,
-    source_path: String,
-) -> Result<BackupInfo, String> {
-    let source_path = Path::new(&source_path);
+    compression: Option<BackupCompression>,
+    compression_level: Option<u32>,
+    password: Option<String>,
+) -> Result<BackupInfo, AppError> {
+    let game = load_game(&db, game_id as i32).await?;
+    let source_paths = expand_game_save_paths(&game)?;
+    let mut password = password.filter(|password| !password.is_empty());
 
     // 验证源路径是否存在
-    if !source_path.exists() {
-        return Err("源存档文件夹不存在".to_string());
+    if source_paths.is_empty() {
+        return Err("游戏未设置存档路径".into());
+    }
+    for source_path in &source_paths {
+        if !source_path.exists() {
+            return Err(format!("源存档文件夹不存在: {}", source_path.display()).into());
+        }
+        if !source_path.is_dir() {
+            return Err(format!("源路径必须是一个文件夹: {}", source_path.display()).into());
+        }
     }
 
-    if !source_path.is_dir() {
-        return Err("源路径必须是一个文件夹".to_string());
+    let backup_settings = db.get_settings().await?.backup_settings();
+    if password.is_none() && backup_settings.encrypt_by_default {
+        let stored = password_or_stored(None).await;
+        password = Some(default_encryption_password(stored, game_id as i32)?);
     }
 
     let backup_root = resolve_savedata_backup_root(&db).await?;
@@ -53,24 +148,84 @@ pub async fn create_savedata_backup(
 
     fs::create_dir_all(&game_backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
 
-    // 检查并清理超出限制的备份（异步处理）
-    cleanup_old_backups(&db, &game_backup_dir, game_id).await?;
+    // 按保留策略清理旧备份，为新备份预留一个名额
+    enforce_retention(&db, game_id as i32, 1).await?;
 
     // 生成备份文件名（带时间戳）
     let now = Utc::now();
     let timestamp = now.timestamp();
-    let backup_filename = format!("savedata_{}_{}.7z", game_id, now.format("%Y%m%d_%H%M%S"));
+    let encrypted = password.is_some();
+    let backup_filename = format!(
+        "savedata_{}_{}{}",
+        game_id,
+        now.format("%Y%m%d_%H%M%S"),
+        if encrypted {
+            ENCRYPTED_BACKUP_SUFFIX
+        } else {
+            ".7z"
+        }
+    );
     let backup_file_path = game_backup_dir.join(&backup_filename);
 
+    // 未指定压缩方式时使用设置中的默认值
+    let (compression, compression_level) = match compression {
+        Some(compression) => (compression, compression_level),
+        None => (
+            backup_settings.compression,
+            compression_level.or(backup_settings.compression_level),
+        ),
+    };
+
+    let mut sources: Vec<(String, PathBuf)> = if source_paths.len() == 1 {
+        vec![(String::new(), source_paths[0].clone())]
+    } else {
+        source_paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| (format!("{}/{}", SAVE_PATHS_DIR, index), path.clone()))
+            .collect()
+    };
+
+    // 导出注册表键到临时目录，随存档一起打包
+    let registry_keys = game
+        .save_registry_keys
+        .map(|keys| keys.0)
+        .unwrap_or_default();
+    let registry_export_dir = std::env::temp_dir().join(format!(
+        "reina-registry-{}-{}",
+        game_id,
+        now.timestamp_millis()
+    ));
+    let exported = export_registry_keys(&registry_keys, &registry_export_dir);
+    if matches!(exported, Ok(count) if count > 0) {
+        sources.push((REGISTRY_DIR.to_string(), registry_export_dir.clone()));
+    }
+
     // 创建7z压缩包
-    let backup_size = create_7z_archive(source_path, &backup_file_path)
-        .map_err(|e| format!("创建压缩包失败: {}", e))?;
+    let backup_size = exported.and_then(|_| {
+        create_7z_archive_from_dirs(
+            &sources,
+            &backup_file_path,
+            compression,
+            compression_level,
+            password.as_deref(),
+        )
+        .map_err(|e| {
+            let _ = fs::remove_file(&backup_file_path);
+            format!("创建压缩包失败: {}", e)
+        })
+    });
+    if registry_export_dir.exists() {
+        let _ = fs::remove_dir_all(&registry_export_dir);
+    }
+    let backup_size = backup_size?;
 
     log::info!(
-        "存档备份创建成功 game_id={} file={} size={} bytes",
+        "存档备份创建成功 game_id={} file={} size={} bytes encrypted={}",
         game_id,
         backup_filename,
-        backup_size
+        backup_size,
+        encrypted
     );
 
     Ok(BackupInfo {
@@ -78,47 +233,144 @@ pub async fn create_savedata_backup(
         backup_time: timestamp,
         file_size: backup_size,
         backup_path: backup_file_path.to_string_lossy().to_string(),
+        encrypted,
     })
 }
 
 /// 恢复存档备份
 ///
+/// 备份中带有导出的注册表文件时先校验其中的键都属于游戏配置的 `save_registry_keys` 再导入，
+/// 校验或导入失败则不改动存档目录。
+/// 多路径备份按序号还原到游戏当前的各个存档路径；旧版单路径备份还原到第一个存档路径。
+///
 /// # Arguments
+/// * `game_id` - 游戏ID，用于获取存档路径
 /// * `backup_file_path` - 备份文件完整路径
-/// * `target_path` - 目标恢复路径
+/// * `password` - 备份密码，恢复加密备份时未提供则使用已保存的默认备份密码
 ///
 /// # Returns
 /// * `Result<(), String>` - 成功或错误消息
 #[tauri::command]
 pub async fn restore_savedata_backup(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
     backup_file_path: String,
-    target_path: String,
+    password: Option<String>,
 ) -> Result<(), String> {
     let backup_path = Path::new(&backup_file_path);
-    let target_path = Path::new(&target_path);
+    let game = load_game(&db, game_id).await?;
+    let target_paths = expand_game_save_paths(&game)?;
 
     // 验证备份文件是否存在
     if !backup_path.exists() {
         return Err("备份文件不存在".to_string());
     }
+    let Some(first_target) = target_paths.first() else {
+        return Err("游戏未设置存档路径".to_string());
+    };
 
     // 确保目标路径存在
-    if !target_path.exists() {
-        fs::create_dir_all(target_path).map_err(|e| format!("创建目标目录失败: {}", e))?;
+    for target_path in &target_paths {
+        if !target_path.exists() {
+            fs::create_dir_all(target_path).map_err(|e| format!("创建目标目录失败: {}", e))?;
+        }
+    }
+
+    let encrypted = backup_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(is_encrypted_backup);
+    let password = if encrypted {
+        password_or_stored(password).await
+    } else {
+        None
+    };
+    if encrypted && password.is_none() {
+        return Err("该备份已加密，请输入密码".to_string());
     }
 
-    // 解压7z文件
-    extract_7z_archive(backup_path, target_path).map_err(|e| format!("解压备份失败: {}", e))?;
+    // 解压7z文件到第一个存档路径旁的临时目录，失败时目标目录保持不变
+    let staging_dir = extract_7z_archive_to_staging(backup_path, first_target, password.as_deref())
+        .map_err(|e| {
+            if encrypted {
+                format!("解压备份失败（密码可能错误）: {}", e)
+            } else {
+                format!("解压备份失败: {}", e)
+            }
+        })?;
+
+    let registry_keys = game
+        .save_registry_keys
+        .map(|keys| keys.0)
+        .unwrap_or_default();
+    let restored = restore_from_staging(&staging_dir, &target_paths, &registry_keys);
+    if staging_dir.exists()
+        && let Err(e) = fs::remove_dir_all(&staging_dir)
+    {
+        log::warn!("清理临时目录失败 {}: {}", staging_dir.display(), e);
+    }
+    restored?;
 
     log::info!(
-        "存档备份恢复成功 file={}",
+        "存档备份恢复成功 game_id={} file={}",
+        game_id,
         backup_path
             .file_name()
             .and_then(|name| name.to_str())
             .unwrap_or("<unknown>")
     );
-    log::debug!("存档备份恢复目标路径: {}", target_path.display());
+    log::debug!("存档备份恢复目标路径: {:?}", target_paths);
+
+    Ok(())
+}
+
+/// 把解压结果还原到各个存档路径，先检查备份布局与路径数量，再导入注册表并覆盖存档目录
+fn restore_from_staging(
+    staging_dir: &Path,
+    target_paths: &[PathBuf],
+    registry_keys: &[String],
+) -> Result<(), String> {
+    let paths_dir = staging_dir.join(SAVE_PATHS_DIR);
+    let mut restores: Vec<(PathBuf, &Path)> = Vec::new();
+    if paths_dir.is_dir() {
+        for entry in fs::read_dir(&paths_dir).map_err(|e| format!("读取备份内容失败: {}", e))?
+        {
+            let entry = entry.map_err(|e| format!("读取备份内容失败: {}", e))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let target = name
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| target_paths.get(index))
+                .ok_or_else(|| {
+                    format!(
+                        "备份中的存档目录 {} 没有对应的存档路径，当前游戏只设置了 {} 个存档路径",
+                        name,
+                        target_paths.len()
+                    )
+                })?;
+            restores.push((entry.path(), target));
+        }
+    } else {
+        if target_paths.len() > 1 {
+            log::warn!(
+                "备份只包含一个存档目录，将还原到第一个存档路径: {}",
+                target_paths[0].display()
+            );
+        }
+        restores.push((staging_dir.to_path_buf(), &target_paths[0]));
+    }
+
+    // 注册表文件不属于存档目录，导入后从解压结果中移除
+    let registry_dir = staging_dir.join(REGISTRY_DIR);
+    if registry_dir.is_dir() {
+        import_registry_dir(&registry_dir, registry_keys)?;
+        fs::remove_dir_all(&registry_dir).map_err(|e| format!("清理注册表备份失败: {}", e))?;
+    }
 
+    for (source, target) in restores {
+        replace_dir_with_staging(&source, target)
+            .map_err(|e| format!("还原存档文件失败 {}: {}", target.display(), e))?;
+    }
     Ok(())
 }
 
@@ -129,12 +381,23 @@ pub struct MoveResult {
 }
 
 /// 移动存档备份文件夹到新位置
+///
+/// 同盘时直接重命名；跨盘时分块复制并通过 `task-progress` 事件上报进度，
+/// 复制完成后才删除旧文件夹。复制失败或被取消时清理已复制的目标目录，旧文件夹保持不变。
 #[command]
-pub async fn move_backup_folder(old_path: String, new_path: String) -> Result<MoveResult, String> {
+pub async fn move_backup_folder<R: Runtime>(
+    app_handle: AppHandle<R>,
+    tasks: State<'_, TaskManager>,
+    old_path: String,
+    new_path: String,
+) -> Result<MoveResult, String> {
     let old_backup_path = Path::new(&old_path);
     let new_backup_path = Path::new(&new_path);
 
-    if !old_backup_path.exists() {
+    if !tokio::fs::try_exists(old_backup_path)
+        .await
+        .unwrap_or(false)
+    {
         return Ok(MoveResult {
             success: true,
             message: "旧备份文件夹不存在，无需移动".to_string(),
@@ -142,8 +405,7 @@ pub async fn move_backup_folder(old_path: String, new_path: String) -> Result<Mo
     }
 
     if let Some(parent) = new_backup_path.parent()
-        && !parent.exists()
-        && let Err(e) = fs::create_dir_all(parent)
+        && let Err(e) = tokio::fs::create_dir_all(parent).await
     {
         return Ok(MoveResult {
             success: false,
@@ -151,54 +413,64 @@ pub async fn move_backup_folder(old_path: String, new_path: String) -> Result<Mo
         });
     }
 
-    if new_backup_path.exists() {
+    if tokio::fs::try_exists(new_backup_path).await.unwrap_or(true) {
         return Ok(MoveResult {
             success: false,
             message: "目标位置已存在备份文件夹，请手动处理".to_string(),
         });
     }
 
-    match fs::rename(old_backup_path, new_backup_path) {
-        Ok(_) => Ok(MoveResult {
+    if tokio::fs::rename(old_backup_path, new_backup_path)
+        .await
+        .is_ok()
+    {
+        return Ok(MoveResult {
             success: true,
             message: "备份文件夹移动成功".to_string(),
-        }),
-        Err(_) => match copy_dir_recursive(old_backup_path, new_backup_path) {
-            Ok(_) => match fs::remove_dir_all(old_backup_path) {
-                Ok(_) => Ok(MoveResult {
-                    success: true,
-                    message: "备份文件夹移动成功（通过复制）".to_string(),
-                }),
-                Err(e) => Ok(MoveResult {
-                    success: false,
-                    message: format!("文件夹已复制到新位置，但删除旧文件夹失败: {}", e),
-                }),
-            },
-            Err(e) => Ok(MoveResult {
-                success: false,
-                message: format!("移动文件夹失败: {}", e),
-            }),
-        },
+        });
     }
-}
-
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    fs::create_dir_all(dst)?;
 
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
+    let task = tasks.start(&app_handle, TaskKind::BackupFolderMove)?;
+    let cancelled = task.cancel_flag();
+    let copied = copy_dir_chunked(
+        old_backup_path,
+        new_backup_path,
+        &cancelled,
+        |copied, total| task.progress(copied, total),
+    )
+    .await;
+    task.finish(&copied);
 
-        if ty.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
+    if let Err(e) = copied {
+        // 目标目录由本次移动创建，整体清理
+        if let Err(cleanup) = tokio::fs::remove_dir_all(new_backup_path).await {
+            log::warn!(
+                "清理目标目录失败 {}: {}",
+                new_backup_path.display(),
+                cleanup
+            );
         }
+        let message = if e == COPY_CANCELLED {
+            "已取消移动，旧备份文件夹保持不变".to_string()
+        } else {
+            format!("移动文件夹失败: {}", e)
+        };
+        return Ok(MoveResult {
+            success: false,
+            message,
+        });
     }
 
-    Ok(())
+    match tokio::fs::remove_dir_all(old_backup_path).await {
+        Ok(_) => Ok(MoveResult {
+            success: true,
+            message: "备份文件夹移动成功（通过复制）".to_string(),
+        }),
+        Err(e) => Ok(MoveResult {
+            success: false,
+            message: format!("文件夹已复制到新位置，但删除旧文件夹失败: {}", e),
+        }),
+    }
 }
 
 /// 删除单个备份记录（文件 + 数据库）
@@ -212,7 +484,7 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), Box<dyn std::error::
 ///
 /// # Returns
 /// * `Option<String>` - 如果有错误返回错误信息，否则返回 None
-async fn delete_backup_record(
+pub(super) async fn delete_backup_record(
     db: &DatabaseConnection,
     backup_file_path: &Path,
     backup_id: i32,
@@ -277,88 +549,151 @@ pub async fn delete_savedata_backup(
     Ok(())
 }
 
-async fn resolve_savedata_backup_root(db: &DatabaseConnection) -> Result<PathBuf, String> {
-    use crate::database::repository::settings_repository::DbSettingsExt;
-    let settings = db.get_settings().await?;
-
-    let backup_root = if let Some(custom) = settings.save_root_path_value() {
-        PathBuf::from(custom).join("backups")
-    } else {
-        reina_path::get_base_data_dir()?.join("backups")
-    };
-
-    Ok(backup_root)
-}
-
-/// 清理超出数量限制的旧备份（基于数据库记录，异步处理）
-///
-/// 从 games 表中读取该游戏的 maxbackups 设置
+/// 锁定或解锁存档备份，锁定的备份不会被保留策略与批量删除清理
 ///
 /// # Arguments
-/// * `db` - 数据库连接
-/// * `backup_dir` - 备份目录路径
-/// * `game_id` - 游戏ID
-///
-/// # Returns
-/// * `Result<(), String>` - 成功或错误消息
-async fn cleanup_old_backups(
-    db: &DatabaseConnection,
-    backup_dir: &Path,
-    game_id: i64,
+/// * `backup_id` - 备份记录ID
+/// * `pinned` - 是否锁定
+#[command]
+pub async fn pin_savedata_backup(
+    db: State<'_, DatabaseConnection>,
+    backup_id: i32,
+    pinned: bool,
 ) -> Result<(), String> {
-    // 从数据库获取游戏信息，读取 maxbackups 设置
-    let game = GamesRepository::find_by_id(db, game_id as i32)
+    let found = GamesRepository::set_savedata_pinned(&db, backup_id, pinned)
         .await
-        .map_err(|e| format!("获取游戏信息失败: {}", e))?;
-
-    // 获取最大备份数量（前端已设置默认值20，不会为null）
-    let max_backups = game
-        .and_then(|g| g.maxbackups)
-        .expect("maxbackups should not be null") as usize;
+        .map_err(|e| format!("更新备份锁定状态失败: {}", e))?;
+    if !found {
+        return Err("备份记录不存在".to_string());
+    }
+    log::info!(
+        "存档备份锁定状态已更新 backup_id={} pinned={}",
+        backup_id,
+        pinned
+    );
+    Ok(())
+}
 
-    // 从数据库获取该游戏的所有备份记录
-    let mut records = GamesRepository::get_savedata_records(db, game_id as i32)
-        .await
-        .map_err(|e| format!("获取备份记录失败: {}", e))?;
+/// 批量删除结果
+#[derive(Debug, Default, Serialize)]
+pub struct BatchDeleteBackupsResult {
+    pub deleted: usize,
+    /// 因已锁定而跳过的数量
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
 
-    // 如果备份数量未超过限制，直接返回
-    if records.len() < max_backups {
-        return Ok(());
+/// 批量删除存档备份（文件 + 数据库记录），已锁定的备份会被跳过
+///
+/// # Arguments
+/// * `backup_ids` - 备份记录ID列表
+#[command]
+pub async fn delete_savedata_backups(
+    db: State<'_, DatabaseConnection>,
+    backup_ids: Vec<i32>,
+) -> Result<BatchDeleteBackupsResult, String> {
+    let backup_root = resolve_savedata_backup_root(&db).await?;
+    let mut result = BatchDeleteBackupsResult::default();
+
+    for backup_id in backup_ids {
+        let record = match GamesRepository::get_savedata_record_by_id(&db, backup_id).await {
+            Ok(Some(record)) => record,
+            Ok(None) => {
+                result
+                    .errors
+                    .push(format!("备份记录不存在 (ID: {})", backup_id));
+                continue;
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("获取备份记录失败 (ID: {}): {}", backup_id, e));
+                continue;
+            }
+        };
+        if record.pinned {
+            result.skipped += 1;
+            continue;
+        }
+
+        let backup_path = backup_root
+            .join(format!("game_{}", record.game_id))
+            .join(&record.file);
+        match delete_backup_record(&db, &backup_path, backup_id).await {
+            Some(error) => result.errors.push(error),
+            None => result.deleted += 1,
+        }
     }
 
-    // 按备份时间排序（最旧的在前）
-    records.sort_by_key(|r| r.backup_time);
+    log::info!(
+        "批量删除存档备份完成 deleted={} skipped={} errors={}",
+        result.deleted,
+        result.skipped,
+        result.errors.len()
+    );
+    Ok(result)
+}
 
-    // 计算需要删除的备份数量（保留最新的 max_backups - 1 个，为新备份留出空间）
-    let to_delete_count = records.len() - (max_backups - 1);
-    let records_to_delete = &records[..to_delete_count];
+pub(crate) async fn resolve_savedata_backup_root(
+    db: &DatabaseConnection,
+) -> Result<PathBuf, String> {
+    let settings = db.get_settings().await?;
 
-    // 收集错误信息，不中断循环
-    let mut errors: Vec<String> = Vec::new();
+    let backup_root = if let Some(custom) = settings.save_root_path_value() {
+        PathBuf::from(custom).join("backups")
+    } else {
+        reina_path::get_base_data_dir()?.join("backups")
+    };
 
-    // 使用通用函数删除文件和数据库记录
-    for record in records_to_delete {
-        let backup_file_path = backup_dir.join(&record.file);
+    Ok(backup_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
 
-        if let Some(error) = delete_backup_record(db, &backup_file_path, record.id).await {
-            errors.push(error);
+    #[test]
+    fn restores_each_save_path_from_staging() {
+        let root = temp_dir("savedata-paths", "");
+        let targets = vec![root.join("game_dir"), root.join("appdata")];
+        for target in &targets {
+            fs::create_dir_all(target).unwrap();
+            fs::write(target.join("old.sav"), b"old").unwrap();
+        }
+
+        let staging = root.join("staging");
+        fs::create_dir_all(staging.join(SAVE_PATHS_DIR).join("5")).unwrap();
+        let err = restore_from_staging(&staging, &targets, &[]).unwrap_err();
+        assert!(err.contains("只设置了 2 个存档路径"));
+        assert!(targets[0].join("old.sav").is_file());
+
+        fs::remove_dir_all(&staging).unwrap();
+        for index in 0..2 {
+            let dir = staging.join(SAVE_PATHS_DIR).join(index.to_string());
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("{index}.sav")), b"new").unwrap();
+        }
+        restore_from_staging(&staging, &targets, &[]).unwrap();
+        for (index, target) in targets.iter().enumerate() {
+            assert!(!target.join("old.sav").exists());
+            assert_eq!(
+                fs::read(target.join(format!("{index}.sav"))).unwrap(),
+                b"new"
+            );
         }
+        fs::remove_dir_all(root).unwrap();
     }
 
-    log::debug!(
-        "旧存档备份清理完成 game_id={} deleted_count={}",
-        game_id,
-        records_to_delete.len()
-    );
+    #[test]
+    fn default_encryption_requires_stored_password() {
+        let err = default_encryption_password(None, 3).unwrap_err();
+        assert_eq!(err, AppError::BackupPasswordRequired { game_id: 3 });
+        assert_eq!(err.code(), "backup_password_required");
 
-    // 有错误时记录日志，但不终止备份流程
-    if !errors.is_empty() {
-        log::warn!(
-            "清理旧备份时遇到 {} 个错误:\n{}",
-            errors.len(),
-            errors.join("\n")
-        );
-    }
-
-    Ok(())
-}
+        assert_eq!(
+            default_encryption_password(Some("secret".to_string()), 3).unwrap(),
+            "secret"
+        );
+    }
+}
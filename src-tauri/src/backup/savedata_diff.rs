@@ -0,0 +1,197 @@
+//! 存档备份差异对比
+//!
+//! 只读取两个备份压缩包的文件列表（不解压），按压缩包内路径对比新增、删除与修改的文件。
+//! 大小不同或压缩时记录的 CRC32 不同即视为修改，用于判断哪个备份对应哪条路线。
+
+use super::archive::{ArchiveFileInfo, list_7z_archive_files};
+use super::savedata::{is_encrypted_backup, password_or_stored, resolve_savedata_backup_root};
+use crate::database::repository::games_repository::GamesRepository;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{State, command};
+
+/// 只存在于一个备份中的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffFileEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+/// 两个备份中内容不同的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModifiedFileEntry {
+    pub path: String,
+    pub size_a: u64,
+    pub size_b: u64,
+}
+
+/// 备份 B 相对备份 A 的差异
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SavedataDiff {
+    /// 只在 B 中存在
+    pub added: Vec<DiffFileEntry>,
+    /// 只在 A 中存在
+    pub removed: Vec<DiffFileEntry>,
+    pub modified: Vec<ModifiedFileEntry>,
+    /// 内容相同的文件数
+    pub unchanged: usize,
+}
+
+/// 对比两份文件列表，结果按路径排序
+///
+/// 任一方没有记录 CRC32 时只比较大小。
+fn diff_file_lists(files_a: Vec<ArchiveFileInfo>, files_b: Vec<ArchiveFileInfo>) -> SavedataDiff {
+    let mut files_a: BTreeMap<String, ArchiveFileInfo> = files_a
+        .into_iter()
+        .map(|file| (file.name.clone(), file))
+        .collect();
+    let mut diff = SavedataDiff::default();
+
+    let files_b: BTreeMap<String, ArchiveFileInfo> = files_b
+        .into_iter()
+        .map(|file| (file.name.clone(), file))
+        .collect();
+    for (path, file_b) in files_b {
+        let Some(file_a) = files_a.remove(&path) else {
+            diff.added.push(DiffFileEntry {
+                path,
+                size: file_b.size,
+            });
+            continue;
+        };
+        let crc_changed = matches!((file_a.crc, file_b.crc), (Some(a), Some(b)) if a != b);
+        if file_a.size != file_b.size || crc_changed {
+            diff.modified.push(ModifiedFileEntry {
+                path,
+                size_a: file_a.size,
+                size_b: file_b.size,
+            });
+        } else {
+            diff.unchanged += 1;
+        }
+    }
+
+    diff.removed = files_a
+        .into_values()
+        .map(|file| DiffFileEntry {
+            path: file.name,
+            size: file.size,
+        })
+        .collect();
+    diff
+}
+
+/// 读取备份记录对应压缩包的文件列表
+async fn list_backup_files(
+    db: &DatabaseConnection,
+    backup_root: &Path,
+    backup_id: i32,
+    password: Option<&str>,
+) -> Result<Vec<ArchiveFileInfo>, String> {
+    let record = GamesRepository::get_savedata_record_by_id(db, backup_id)
+        .await
+        .map_err(|e| format!("获取备份记录失败: {}", e))?
+        .ok_or_else(|| format!("备份记录不存在 (ID: {})", backup_id))?;
+    let backup_path = backup_root
+        .join(format!("game_{}", record.game_id))
+        .join(&record.file);
+    if !backup_path.is_file() {
+        return Err(format!("备份文件不存在: {}", record.file));
+    }
+
+    let encrypted = is_encrypted_backup(&record.file);
+    if encrypted && password.is_none() {
+        return Err(format!("备份已加密，请输入密码: {}", record.file));
+    }
+    list_7z_archive_files(&backup_path, password.filter(|_| encrypted)).map_err(|e| {
+        if encrypted {
+            format!("读取备份失败（密码可能错误） {}: {}", record.file, e)
+        } else {
+            format!("读取备份失败 {}: {}", record.file, e)
+        }
+    })
+}
+
+/// 对比两个存档备份的文件列表
+///
+/// # Arguments
+/// * `backup_a` - 作为基准的备份记录ID
+/// * `backup_b` - 与之对比的备份记录ID
+/// * `password` - 备份密码，对比加密备份时未提供则使用已保存的默认备份密码
+///
+/// # Returns
+/// * `Result<SavedataDiff, String>` - 备份 B 相对备份 A 的差异或错误消息
+#[command]
+pub async fn diff_savedata_backups(
+    db: State<'_, DatabaseConnection>,
+    backup_a: i32,
+    backup_b: i32,
+    password: Option<String>,
+) -> Result<SavedataDiff, String> {
+    let password = password_or_stored(password).await;
+    let backup_root = resolve_savedata_backup_root(&db).await?;
+    let files_a = list_backup_files(&db, &backup_root, backup_a, password.as_deref()).await?;
+    let files_b = list_backup_files(&db, &backup_root, backup_b, password.as_deref()).await?;
+
+    let diff = diff_file_lists(files_a, files_b);
+    log::info!(
+        "存档备份对比完成 a={} b={} added={} removed={} modified={} unchanged={}",
+        backup_a,
+        backup_b,
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len(),
+        diff.unchanged
+    );
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64, crc: Option<u32>) -> ArchiveFileInfo {
+        ArchiveFileInfo {
+            name: name.to_string(),
+            size,
+            crc,
+        }
+    }
+
+    #[test]
+    fn diffs_by_size_and_crc() {
+        let files_a = vec![
+            file("global.sav", 100, Some(1)),
+            file("route_a.sav", 50, Some(2)),
+            file("system.sav", 10, Some(3)),
+            file("config.ini", 8, None),
+        ];
+        let files_b = vec![
+            file("system.sav", 10, Some(4)),
+            file("global.sav", 120, Some(1)),
+            file("route_b.sav", 60, Some(5)),
+            file("config.ini", 8, Some(6)),
+        ];
+
+        let diff = diff_file_lists(files_a, files_b);
+        assert_eq!(
+            diff.added,
+            vec![DiffFileEntry {
+                path: "route_b.sav".to_string(),
+                size: 60
+            }]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![DiffFileEntry {
+                path: "route_a.sav".to_string(),
+                size: 50
+            }]
+        );
+        let modified: Vec<&str> = diff.modified.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(modified, vec!["global.sav", "system.sav"]);
+        assert_eq!(diff.unchanged, 1);
+    }
+}
@@ -0,0 +1,110 @@
+//! 数据库定时备份
+//!
+//! 后台按设置的周期（每天 / 每周）使用 VACUUM INTO 热备份数据库，
+//! 文件名带 `reina_manager_scheduled_` 前缀，轮转时只清理定时备份，
+//! 不影响手动备份与退出时的自动备份。上一次备份时间取自备份目录中
+//! 最新定时备份文件的修改时间，应用重启后仍能按周期继续。
+
+use crate::backup::common::{cleanup_auto_backup_files, resolve_backup_dir};
+use crate::backup::database::backup_database_file_to;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use sea_orm::DatabaseConnection;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// 检查是否需要备份的间隔
+const DB_BACKUP_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+/// 定时备份文件名前缀
+pub(crate) const SCHEDULED_BACKUP_PREFIX: &str = "reina_manager_scheduled_";
+/// 未设置保留份数时保留的定时备份数量
+const DEFAULT_SCHEDULED_BACKUP_KEEP: usize = 7;
+
+/// 启动数据库定时备份任务，应用启动时调用一次
+///
+/// 每轮都重新读取设置，修改周期或保留份数后无需重启任务。
+pub fn spawn_db_backup_scheduler(db: DatabaseConnection) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(DB_BACKUP_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_scheduled_backup(&db).await {
+                log::warn!("数据库定时备份失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 备份目录中最新一份定时备份的修改时间
+fn last_scheduled_backup(backup_dir: &Path) -> Option<SystemTime> {
+    fs::read_dir(backup_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|name| {
+                name.starts_with(SCHEDULED_BACKUP_PREFIX) && name.ends_with(".db")
+            })
+        })
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// 距离上次备份是否已超过一个周期；时钟回拨时视为未到期
+fn is_backup_due(last: Option<SystemTime>, now: SystemTime, period: Duration) -> bool {
+    match last {
+        None => true,
+        Some(last) => now
+            .duration_since(last)
+            .is_ok_and(|elapsed| elapsed >= period),
+    }
+}
+
+async fn run_scheduled_backup(db: &DatabaseConnection) -> Result<(), String> {
+    let settings = db.get_settings().await?.backup_settings();
+    let Some(period) = settings.db_backup_interval.period_secs() else {
+        return Ok(());
+    };
+
+    let backup_dir = resolve_backup_dir(db).await?;
+    if !is_backup_due(
+        last_scheduled_backup(&backup_dir),
+        SystemTime::now(),
+        Duration::from_secs(period),
+    ) {
+        return Ok(());
+    }
+
+    let backup_name = format!(
+        "{}{}.db",
+        SCHEDULED_BACKUP_PREFIX,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
+    backup_database_file_to(db, &backup_dir, &backup_name).await?;
+
+    let keep = settings
+        .db_backup_keep
+        .unwrap_or(DEFAULT_SCHEDULED_BACKUP_KEEP);
+    let deleted = cleanup_auto_backup_files(&backup_dir, SCHEDULED_BACKUP_PREFIX, ".db", keep)?;
+    log::info!(
+        "数据库定时备份完成 file={} removed={}",
+        backup_name,
+        deleted.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_is_due_after_one_period() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::UNIX_EPOCH + day * 10;
+        assert!(is_backup_due(None, now, day));
+        assert!(is_backup_due(Some(now - day), now, day));
+        assert!(!is_backup_due(Some(now - day / 2), now, day));
+        assert!(!is_backup_due(Some(now + day), now, day));
+    }
+}
@@ -0,0 +1,156 @@
+//! 游玩会话 CSV 导出
+//!
+//! 导出的文件带 UTF-8 BOM，便于 Excel 直接识别中文游戏名。时间按本机时区格式化，
+//! 时长单位为分钟。
+
+use crate::database::repository::game_stats_repository::GameStatsRepository;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::game_sessions;
+use chrono::{Local, TimeZone};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{State, command};
+
+const CSV_HEADER: [&str; 9] = [
+    "session_id",
+    "game_id",
+    "game_name",
+    "start_time",
+    "end_time",
+    "duration_minutes",
+    "date",
+    "exit_code",
+    "note",
+];
+
+/// 会话导出结果
+#[derive(Debug, Serialize)]
+pub struct ExportSessionsResult {
+    pub path: String,
+    pub session_count: usize,
+}
+
+/// 按 RFC 4180 转义单个字段
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_timestamp(timestamp: i32) -> String {
+    Local
+        .timestamp_opt(i64::from(timestamp), 0)
+        .single()
+        .map(|datetime| datetime.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn sessions_to_csv(sessions: &[game_sessions::Model], names: &HashMap<i32, String>) -> String {
+    let mut content = String::from("\u{feff}");
+    content.push_str(&CSV_HEADER.join(","));
+    content.push_str("\r\n");
+
+    for session in sessions {
+        let row = [
+            session.session_id.to_string(),
+            session.game_id.to_string(),
+            names.get(&session.game_id).cloned().unwrap_or_default(),
+            format_timestamp(session.start_time),
+            format_timestamp(session.end_time),
+            session.duration.to_string(),
+            session.date.clone(),
+            session
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+            session.note.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        content.push_str(&row.join(","));
+        content.push_str("\r\n");
+    }
+    content
+}
+
+/// 将游玩会话导出为 CSV
+///
+/// # Arguments
+/// * `game_id` - 只导出指定游戏的会话，为空时导出全库
+/// * `path` - 导出文件路径
+#[command]
+pub async fn export_sessions_csv(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+    path: String,
+) -> Result<ExportSessionsResult, String> {
+    let sessions = GameStatsRepository::get_all_sessions(&db, game_id)
+        .await
+        .map_err(|e| format!("读取游戏会话失败: {}", e))?;
+    let names: HashMap<i32, String> = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?
+    .into_iter()
+    .filter_map(|game| game.display_name().map(|name| (game.id, name)))
+    .collect();
+
+    if let Some(parent) = Path::new(&path).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("创建导出目录失败: {}", e))?;
+    }
+    tokio::fs::write(&path, sessions_to_csv(&sessions, &names))
+        .await
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    log::info!("游玩会话导出完成 path={} sessions={}", path, sessions.len());
+    Ok(ExportSessionsResult {
+        path,
+        session_count: sessions.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_fields_and_writes_bom() {
+        let session = game_sessions::Model {
+            session_id: 1,
+            game_id: 7,
+            start_time: 1_700_000_000,
+            end_time: 1_700_003_600,
+            duration: 60,
+            date: "2023-11-15".to_string(),
+            exit_code: Some(0),
+            note: Some("第一章, \"共通线\"".to_string()),
+        };
+        let names = HashMap::from([(7, "千恋＊万花".to_string())]);
+
+        let content = sessions_to_csv(&[session], &names);
+        let mut lines = content.split("\r\n");
+        assert_eq!(
+            lines.next(),
+            Some(
+                "\u{feff}session_id,game_id,game_name,start_time,end_time,duration_minutes,date,exit_code,note"
+            )
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("1,7,千恋＊万花,"));
+        assert!(row.ends_with(",60,2023-11-15,0,\"第一章, \"\"共通线\"\"\""));
+    }
+}
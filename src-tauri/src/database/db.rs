@@ -1,8 +1,11 @@
+use sea_orm::sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
 use sea_orm::{
     ConnectOptions, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr,
-    RuntimeErr, Statement,
+    RuntimeErr, Statement, TransactionTrait,
 };
+use serde::Serialize;
 use std::fs;
+use std::path::Path;
 use std::time::Duration;
 use url::Url;
 
@@ -10,6 +13,12 @@ use reina_path::{get_db_path, is_portable_mode};
 
 // ==================== 数据库连接管理 ====================
 
+/// 连接池大小。WAL 模式下读连接不会被写事务阻塞，前端并发查询不必排队
+const MAX_CONNECTIONS: u32 = 4;
+
+/// 多个连接争用写锁时的等待时间，超时才返回 `database is locked`
+const BUSY_TIMEOUT_SECS: u64 = 5;
+
 /// Establish a SeaORM database connection.
 pub async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
     // 1. 获取数据库路径（自动判断便携模式）
@@ -48,9 +57,16 @@ pub async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
     // 4. 设置连接选项
     let mut options = ConnectOptions::new(connection_string);
     options
-        .max_connections(1)
+        .max_connections(MAX_CONNECTIONS)
         .min_connections(1)
-        .connect_timeout(Duration::from_secs(8));
+        .connect_timeout(Duration::from_secs(8))
+        .map_sqlx_sqlite_opts(|opts| {
+            // 每个新连接都会应用这些 PRAGMA
+            opts.journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal)
+                .busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS))
+                .foreign_keys(true)
+        });
 
     // 5. 在开发模式下启用日志
     #[cfg(debug_assertions)]
@@ -63,11 +79,8 @@ pub async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
         options.sqlx_logging(false);
     }
 
-    // 6. 连接数据库，并为当前唯一连接启用外键约束
+    // 6. 连接数据库，确认外键约束与 WAL 模式已生效
     let connection = Database::connect(options).await?;
-    connection
-        .execute_unprepared("PRAGMA foreign_keys = ON")
-        .await?;
 
     let foreign_keys = connection
         .query_one(Statement::from_string(
@@ -82,11 +95,237 @@ pub async fn establish_connection() -> Result<DatabaseConnection, DbErr> {
         return Err(DbErr::Custom("SQLite 外键约束未启用".to_string()));
     }
 
+    let journal_mode = connection
+        .query_one(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "PRAGMA journal_mode".to_string(),
+        ))
+        .await?
+        .map(|row| row.try_get::<String>("", "journal_mode"))
+        .transpose()?
+        .unwrap_or_default();
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        log::warn!("SQLite 未能启用 WAL 模式，当前日志模式: {}", journal_mode);
+    }
+
     Ok(connection)
 }
 
 /// 关闭数据库连接
+///
+/// 关闭前把 WAL 中的内容写回主文件，之后可以直接复制或覆盖数据库文件。
 pub async fn close_connection(conn: DatabaseConnection) -> Result<(), DbErr> {
+    if let Err(e) = conn
+        .execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)")
+        .await
+    {
+        log::warn!("关闭前写回 WAL 失败: {}", e);
+    }
     conn.close().await?;
     Ok(())
 }
+
+/// 删除数据库的 `-wal` / `-shm` 附属文件
+///
+/// 覆盖数据库文件前调用，避免残留的旧 WAL 在下次打开时被应用到新文件上。
+pub(crate) fn remove_wal_files(db_path: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(suffix);
+        match fs::remove_file(&path) {
+            Ok(()) => log::debug!("已删除 {}", Path::new(&path).display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("删除 {} 失败: {}", Path::new(&path).display(), e),
+        }
+    }
+}
+
+// ==================== 数据库维护 ====================
+
+/// 数据库整理结果
+#[derive(Debug, Serialize)]
+pub struct OptimizeResult {
+    /// 整理前的数据库大小（字节）
+    pub size_before: i64,
+    /// 整理后的数据库大小（字节）
+    pub size_after: i64,
+}
+
+/// 数据库完整性检查结果
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// `PRAGMA integrity_check` 报告的问题
+    pub errors: Vec<String>,
+    /// `PRAGMA foreign_key_check` 报告的外键违规，格式为"表名 rowid=… -> 引用表"
+    pub foreign_key_violations: Vec<String>,
+}
+
+/// 孤儿数据清理结果，各字段为对应表删除的行数
+#[derive(Debug, Default, Serialize)]
+pub struct OrphanCleanupResult {
+    pub sessions: u64,
+    pub statistics: u64,
+    pub savedata: u64,
+    pub sources: u64,
+    pub collection_links: u64,
+}
+
+fn sqlite_statement(sql: &str) -> Statement {
+    Statement::from_string(DatabaseBackend::Sqlite, sql.to_string())
+}
+
+async fn database_size(conn: &DatabaseConnection) -> Result<i64, DbErr> {
+    conn.query_one(sqlite_statement(
+        "SELECT page_count * page_size AS size FROM pragma_page_count(), pragma_page_size()",
+    ))
+    .await?
+    .ok_or_else(|| DbErr::Custom("无法读取数据库大小".to_string()))?
+    .try_get::<i64>("", "size")
+}
+
+/// 整理数据库：VACUUM 回收碎片空间，ANALYZE 更新查询计划统计信息
+pub async fn optimize(conn: &DatabaseConnection) -> Result<OptimizeResult, DbErr> {
+    let size_before = database_size(conn).await?;
+    conn.execute_unprepared("VACUUM").await?;
+    conn.execute_unprepared("ANALYZE").await?;
+    let size_after = database_size(conn).await?;
+    Ok(OptimizeResult {
+        size_before,
+        size_after,
+    })
+}
+
+/// 检查数据库完整性与外键约束
+pub async fn check_integrity(conn: &DatabaseConnection) -> Result<IntegrityReport, DbErr> {
+    let errors: Vec<String> = conn
+        .query_all(sqlite_statement("PRAGMA integrity_check"))
+        .await?
+        .iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .filter(|message| message != "ok")
+        .collect();
+
+    let foreign_key_violations: Vec<String> = conn
+        .query_all(sqlite_statement("PRAGMA foreign_key_check"))
+        .await?
+        .iter()
+        .map(|row| {
+            let table = row.try_get_by_index::<String>(0).unwrap_or_default();
+            let rowid = row.try_get_by_index::<Option<i64>>(1).ok().flatten();
+            let parent = row.try_get_by_index::<String>(2).unwrap_or_default();
+            match rowid {
+                Some(rowid) => format!("{} rowid={} -> {}", table, rowid, parent),
+                None => format!("{} -> {}", table, parent),
+            }
+        })
+        .collect();
+
+    Ok(IntegrityReport {
+        ok: errors.is_empty() && foreign_key_violations.is_empty(),
+        errors,
+        foreign_key_violations,
+    })
+}
+
+async fn delete_orphans<C: ConnectionTrait>(
+    conn: &C,
+    table: &str,
+    condition: &str,
+) -> Result<u64, DbErr> {
+    let result = conn
+        .execute_unprepared(&format!("DELETE FROM {} WHERE {}", table, condition))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// 删除引用已不存在的游戏或合集的记录
+///
+/// 正常情况下外键级联会自动删除这些记录，这里用于清理旧版本未启用外键约束时的残留。
+pub async fn cleanup_orphans(conn: &DatabaseConnection) -> Result<OrphanCleanupResult, DbErr> {
+    let txn = conn.begin().await?;
+    let missing_game = "game_id NOT IN (SELECT id FROM games)";
+    let result = OrphanCleanupResult {
+        sessions: delete_orphans(&txn, "game_sessions", missing_game).await?,
+        statistics: delete_orphans(&txn, "game_statistics", missing_game).await?,
+        savedata: delete_orphans(&txn, "savedata", missing_game).await?,
+        sources: delete_orphans(&txn, "game_sources", missing_game).await?,
+        collection_links: delete_orphans(
+            &txn,
+            "game_collection_link",
+            "game_id NOT IN (SELECT id FROM games) \
+             OR collection_id NOT IN (SELECT id FROM collections)",
+        )
+        .await?,
+    };
+    txn.commit().await?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_foreign_key_violations() {
+        let conn = Database::connect("sqlite::memory:").await.unwrap();
+        conn.execute_unprepared(
+            r#"
+            CREATE TABLE games (id INTEGER PRIMARY KEY);
+            CREATE TABLE savedata (
+                id INTEGER PRIMARY KEY,
+                game_id INTEGER NOT NULL REFERENCES games(id)
+            );
+            INSERT INTO games (id) VALUES (1);
+            INSERT INTO savedata (id, game_id) VALUES (1, 1), (2, 99);
+            "#,
+        )
+        .await
+        .unwrap();
+
+        let report = check_integrity(&conn).await.unwrap();
+        assert!(!report.ok);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.foreign_key_violations, ["savedata rowid=2 -> games"]);
+
+        let result = optimize(&conn).await.unwrap();
+        assert!(result.size_after > 0);
+    }
+
+    #[tokio::test]
+    async fn removes_orphan_records() {
+        let conn = Database::connect("sqlite::memory:").await.unwrap();
+        conn.execute_unprepared(
+            r#"
+            CREATE TABLE games (id INTEGER PRIMARY KEY);
+            CREATE TABLE collections (id INTEGER PRIMARY KEY);
+            CREATE TABLE game_sessions (id INTEGER PRIMARY KEY, game_id INTEGER);
+            CREATE TABLE game_statistics (game_id INTEGER PRIMARY KEY);
+            CREATE TABLE savedata (id INTEGER PRIMARY KEY, game_id INTEGER);
+            CREATE TABLE game_sources (id INTEGER PRIMARY KEY, game_id INTEGER);
+            CREATE TABLE game_collection_link (
+                id INTEGER PRIMARY KEY,
+                game_id INTEGER,
+                collection_id INTEGER
+            );
+            INSERT INTO games (id) VALUES (1);
+            INSERT INTO collections (id) VALUES (10);
+            INSERT INTO game_sessions (game_id) VALUES (1), (2), (2);
+            INSERT INTO game_statistics (game_id) VALUES (1), (2);
+            INSERT INTO savedata (game_id) VALUES (3);
+            INSERT INTO game_sources (game_id) VALUES (1);
+            INSERT INTO game_collection_link (game_id, collection_id)
+                VALUES (1, 10), (1, 11), (2, 10);
+            "#,
+        )
+        .await
+        .unwrap();
+
+        let result = cleanup_orphans(&conn).await.unwrap();
+        assert_eq!(result.sessions, 2);
+        assert_eq!(result.statistics, 1);
+        assert_eq!(result.savedata, 1);
+        assert_eq!(result.sources, 0);
+        assert_eq!(result.collection_links, 2);
+    }
+}
@@ -3,8 +3,14 @@
 //! 用于前后端数据交互的结构定义。
 //! 重构后采用单表架构，元数据以 JSON 列形式嵌入 games 表。
 
-use crate::entity::custom_data::CustomData;
-use crate::entity::user::BgmAuth;
+use crate::entity::custom_data::{CustomData, SourceType};
+use crate::entity::launch_options::{LaunchArgs, LaunchEnv, LaunchHooks, WineConfig};
+use crate::entity::save_options::{SavePaths, SaveRegistryKeys};
+use crate::entity::smart_filter::SmartFilter;
+use crate::entity::user::{
+    BackupSettings, BgmAuth, DiscordRpcSettings, LibraryWatchSettings, MetadataMergeStrategy,
+    NetworkSettings, ObsOutputSettings, RemoteApiSettings, ScanExeRules, WebhookSettings,
+};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
@@ -103,7 +109,14 @@ impl InsertGameData {
         self.date = clean_option_string(self.date);
         self.localpath = clean_option_local_path(self.localpath);
         self.executable = clean_option_executable(self.executable);
-        self.savepath = clean_option_string(self.savepath);
+        self.savepath = self.savepath.and_then(SavePaths::cleaned);
+        self.save_registry_keys = self.save_registry_keys.and_then(SaveRegistryKeys::cleaned);
+        self.le_profile = clean_option_string(self.le_profile);
+        self.launch_args = self.launch_args.and_then(LaunchArgs::cleaned);
+        self.launch_env = self.launch_env.and_then(LaunchEnv::cleaned);
+        self.launch_hooks = self.launch_hooks.and_then(LaunchHooks::cleaned);
+        self.wine_config = self.wine_config.and_then(WineConfig::cleaned);
+        self.engine = clean_option_string(self.engine);
         self.sources = self
             .sources
             .into_iter()
@@ -120,7 +133,26 @@ impl UpdateGameData {
         self.date = clean_double_option_string(self.date);
         self.localpath = clean_double_option_local_path(self.localpath);
         self.executable = clean_double_option_executable(self.executable);
-        self.savepath = clean_double_option_string(self.savepath);
+        self.savepath = self
+            .savepath
+            .map(|inner| inner.and_then(SavePaths::cleaned));
+        self.save_registry_keys = self
+            .save_registry_keys
+            .map(|inner| inner.and_then(SaveRegistryKeys::cleaned));
+        self.le_profile = clean_double_option_string(self.le_profile);
+        self.launch_args = self
+            .launch_args
+            .map(|inner| inner.and_then(LaunchArgs::cleaned));
+        self.launch_env = self
+            .launch_env
+            .map(|inner| inner.and_then(LaunchEnv::cleaned));
+        self.launch_hooks = self
+            .launch_hooks
+            .map(|inner| inner.and_then(LaunchHooks::cleaned));
+        self.wine_config = self
+            .wine_config
+            .map(|inner| inner.and_then(WineConfig::cleaned));
+        self.engine = clean_double_option_string(self.engine);
         self.upsert_sources = self.upsert_sources.map(|sources| {
             sources
                 .into_iter()
@@ -156,6 +188,8 @@ pub struct InsertCollectionData {
     pub parent_id: Option<i32>,
     pub sort_order: i32,
     pub icon: Option<String>,
+    /// 智能合集筛选条件，为空时创建普通合集
+    pub smart_filter: Option<SmartFilter>,
 }
 
 /// 用于更新合集的数据结构
@@ -165,6 +199,8 @@ pub struct UpdateCollectionData {
     pub parent_id: Option<Option<i32>>,
     pub sort_order: Option<i32>,
     pub icon: Option<Option<String>>,
+    /// `Some(None)` 时转为普通合集
+    pub smart_filter: Option<Option<SmartFilter>>,
 }
 
 /// 清洗 InsertCollectionData 中的空字符串
@@ -209,6 +245,30 @@ pub struct UpdateSettingsData {
     pub le_path: Option<Option<String>>,
     #[serde(default, deserialize_with = "double_option")]
     pub magpie_path: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub prevent_sleep: Option<Option<bool>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub discord_rpc: Option<Option<DiscordRpcSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub wine_config: Option<Option<WineConfig>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub scan_exe_rules: Option<Option<ScanExeRules>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub library_watch: Option<Option<LibraryWatchSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub backup_settings: Option<Option<BackupSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub safe_mode: Option<Option<bool>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub network_settings: Option<Option<NetworkSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub metadata_merge_strategy: Option<Option<MetadataMergeStrategy>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub remote_api: Option<Option<RemoteApiSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub webhook: Option<Option<WebhookSettings>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub obs_output: Option<Option<ObsOutputSettings>>,
 }
 
 /// 清洗 UpdateSettingsData 中的空字符串
@@ -221,6 +281,33 @@ impl UpdateSettingsData {
         self.db_backup_path = clean_double_option_string(self.db_backup_path);
         self.le_path = clean_double_option_string(self.le_path);
         self.magpie_path = clean_double_option_string(self.magpie_path);
+        self.wine_config = self
+            .wine_config
+            .map(|inner| inner.and_then(WineConfig::cleaned));
+        self.scan_exe_rules = self
+            .scan_exe_rules
+            .map(|inner| inner.map(ScanExeRules::cleaned));
+        self.library_watch = self
+            .library_watch
+            .map(|inner| inner.map(LibraryWatchSettings::cleaned));
+        self.backup_settings = self
+            .backup_settings
+            .map(|inner| inner.map(BackupSettings::cleaned));
+        self.network_settings = self
+            .network_settings
+            .map(|inner| inner.map(NetworkSettings::cleaned));
+        self.metadata_merge_strategy = self
+            .metadata_merge_strategy
+            .map(|inner| inner.map(MetadataMergeStrategy::cleaned));
+        self.remote_api = self
+            .remote_api
+            .map(|inner| inner.map(RemoteApiSettings::cleaned));
+        self.webhook = self
+            .webhook
+            .map(|inner| inner.map(WebhookSettings::cleaned));
+        self.obs_output = self
+            .obs_output
+            .map(|inner| inner.map(ObsOutputSettings::cleaned));
         self
     }
 }
@@ -249,20 +336,256 @@ pub struct FullGameData {
     pub date: Option<String>,
     pub localpath: Option<String>,
     pub executable: Option<String>,
-    pub savepath: Option<String>,
+    pub savepath: Option<SavePaths>,
+    pub save_registry_keys: Option<SaveRegistryKeys>,
     pub autosave: Option<i32>,
     pub maxbackups: Option<i32>,
     pub clear: Option<i32>,
     pub le_launch: Option<i32>,
+    pub le_profile: Option<String>,
     pub magpie: Option<i32>,
+    pub launch_args: Option<LaunchArgs>,
+    pub launch_env: Option<LaunchEnv>,
+    pub launch_hooks: Option<LaunchHooks>,
+    pub wine_config: Option<WineConfig>,
+    pub engine: Option<String>,
+    /// 游戏目录已不存在时为 1
+    pub missing: Option<i32>,
+    /// 游戏目录占用空间（字节）
+    pub folder_size: Option<i64>,
+    /// Bangumi 收藏状态回写状态：pending / synced / failed
+    pub bgm_sync_status: Option<String>,
+    /// VNDB 列表回写状态：pending / synced / failed
+    pub vndb_sync_status: Option<String>,
+    /// 移入回收站的时间，为空表示未删除
+    pub deleted_at: Option<i32>,
+    /// 归档压缩包路径，为空表示未归档
+    pub archive_path: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub hidden: bool,
     pub custom_data: Option<CustomData>,
     pub sources: Vec<GameSourceData>,
     pub created_at: Option<i32>,
     pub updated_at: Option<i32>,
 }
 
+/// 混合数据源时读取名称、封面等展示字段的优先级，与前端保持一致。
+const DISPLAY_SOURCE_PRIORITY: [&str; 4] = ["bgm", "vndb", "ymgal", "kun"];
+
+impl FullGameData {
+    /// 读取指定数据源中非空的字符串字段
+    fn source_string_value(&self, source: &str, field: &str) -> Option<&str> {
+        self.sources
+            .iter()
+            .find(|item| item.source == source)
+            .and_then(|item| item.data.as_ref())
+            .and_then(|data| data.get(field))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 数据源读取顺序：`id_type` 对应的数据源优先，其余按固定优先级
+    pub(crate) fn sources_by_priority(&self) -> impl Iterator<Item = &str> {
+        let primary = (!matches!(self.id_type.as_str(), "mixed" | "custom" | "Whitecloud"))
+            .then_some(self.id_type.as_str());
+        primary
+            .into_iter()
+            .chain(DISPLAY_SOURCE_PRIORITY.iter().copied())
+    }
+
+    /// 按 `id_type` 优先、其余按固定优先级查找第一个非空的数据源字符串字段
+    pub(crate) fn source_string_field(&self, field: &str) -> Option<&str> {
+        self.sources_by_priority()
+            .find_map(|source| self.source_string_value(source, field))
+    }
+
+    /// 开发商：`custom_data.developer` > 数据源 `developer`，多个开发商以 `/` 分隔
+    pub fn developer(&self) -> Option<String> {
+        self.custom_data
+            .as_ref()
+            .and_then(|data| data.developer.as_deref())
+            .map(str::trim)
+            .filter(|developer| !developer.is_empty())
+            .or_else(|| self.source_string_field("developer"))
+            .map(ToOwned::to_owned)
+    }
+
+    /// 拆分后的开发商列表，去除空白与重复项
+    pub fn developers(&self) -> Vec<String> {
+        let mut developers: Vec<String> = Vec::new();
+        if let Some(developer) = self.developer() {
+            for name in developer
+                .split('/')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                if !developers.iter().any(|existing| existing == name) {
+                    developers.push(name.to_string());
+                }
+            }
+        }
+        developers
+    }
+
+    /// 个人评分，0 与非法值视为未评分
+    pub fn user_rating(&self) -> Option<f64> {
+        self.custom_data
+            .as_ref()?
+            .user_rating
+            .filter(|rating| rating.is_finite() && *rating > 0.0)
+    }
+
+    /// 发行年份，取自 `date` 的前四位
+    pub fn release_year(&self) -> Option<i32> {
+        self.date.as_deref()?.get(..4)?.parse().ok()
+    }
+
+    /// 标签：`custom_data.tags` > 第一个标签非空的数据源
+    pub fn tags(&self) -> Vec<String> {
+        if let Some(tags) = self
+            .custom_data
+            .as_ref()
+            .and_then(|data| data.tags.as_ref())
+            .filter(|tags| !tags.is_empty())
+        {
+            return tags.clone();
+        }
+
+        self.sources_by_priority()
+            .find_map(|source| {
+                let tags: Vec<String> = self
+                    .sources
+                    .iter()
+                    .find(|item| item.source == source)?
+                    .data
+                    .as_ref()?
+                    .get("tags")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect();
+                (!tags.is_empty()).then_some(tags)
+            })
+            .unwrap_or_default()
+    }
+
+    /// 游戏展示名称：`custom_data.name` > 主数据源 `name` > 其余数据源 `name`
+    pub fn display_name(&self) -> Option<String> {
+        self.custom_data
+            .as_ref()
+            .and_then(|data| data.name.as_deref())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .or_else(|| self.source_string_field("name"))
+            .map(ToOwned::to_owned)
+    }
+
+    /// 远程封面地址：自定义网络封面优先，本地自定义封面不返回
+    pub fn remote_cover_url(&self) -> Option<String> {
+        if let Some(image) = self
+            .custom_data
+            .as_ref()
+            .and_then(|data| data.image.as_deref())
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+        {
+            return (image.starts_with("http://") || image.starts_with("https://"))
+                .then(|| image.to_string());
+        }
+
+        self.source_string_field("image")
+            .filter(|image| image.starts_with("http://") || image.starts_with("https://"))
+            .map(ToOwned::to_owned)
+    }
+
+    /// 是否为成人内容：用户自定义标记优先，否则任一数据源标记即视为 NSFW
+    pub fn is_nsfw(&self) -> bool {
+        if let Some(nsfw) = self.custom_data.as_ref().and_then(|data| data.nsfw) {
+            return nsfw;
+        }
+
+        self.sources.iter().any(|source| {
+            source
+                .data
+                .as_ref()
+                .and_then(|data| data.get("nsfw"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// 游戏列表用的轻量数据，不携带各数据源的完整 JSON 元数据
+#[derive(Clone, Debug, Serialize)]
+pub struct GameSummary {
+    pub id: i32,
+    pub id_type: String,
+    /// 展示名称，规则同 [`FullGameData::display_name`]
+    pub name: Option<String>,
+    /// 数据源中的中文名
+    pub name_cn: Option<String>,
+    /// 封面：自定义封面（路径或 URL）> 选定的封面数据源 > 其余数据源
+    pub image: Option<String>,
+    pub date: Option<String>,
+    pub localpath: Option<String>,
+    pub clear: Option<i32>,
+    pub missing: Option<i32>,
+    pub favorite: bool,
+    pub hidden: bool,
+    pub nsfw: bool,
+    /// 总游玩时长（分钟）
+    pub total_time: i32,
+    pub last_played: Option<i32>,
+    pub created_at: Option<i32>,
+}
+
+impl GameSummary {
+    pub fn new(game: &FullGameData, total_time: i32, last_played: Option<i32>) -> Self {
+        let custom = game.custom_data.as_ref();
+        let image = custom
+            .and_then(|data| data.image.as_deref())
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+            .or_else(|| {
+                let source = match custom?.cover_source.as_ref()? {
+                    SourceType::Bgm => "bgm",
+                    SourceType::Vndb => "vndb",
+                    SourceType::Ymgal => "ymgal",
+                    SourceType::Kun => "kun",
+                };
+                game.source_string_value(source, "image")
+            })
+            .or_else(|| game.source_string_field("image"))
+            .map(ToOwned::to_owned);
+
+        Self {
+            id: game.id,
+            id_type: game.id_type.clone(),
+            name: game.display_name(),
+            name_cn: game.source_string_field("name_cn").map(ToOwned::to_owned),
+            image,
+            date: game.date.clone(),
+            localpath: game.localpath.clone(),
+            clear: game.clear,
+            missing: game.missing,
+            favorite: game.favorite,
+            hidden: game.hidden,
+            nsfw: game.is_nsfw(),
+            total_time,
+            last_played,
+            created_at: game.created_at,
+        }
+    }
+}
+
 /// 用于插入游戏聚合的数据结构。
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct InsertGameData {
     pub id_type: String,
 
@@ -270,12 +593,26 @@ pub struct InsertGameData {
     pub date: Option<String>,
     pub localpath: Option<String>,
     pub executable: Option<String>,
-    pub savepath: Option<String>,
+    pub savepath: Option<SavePaths>,
+    #[serde(default)]
+    pub save_registry_keys: Option<SaveRegistryKeys>,
     pub autosave: Option<i32>,
     pub maxbackups: Option<i32>,
     pub clear: Option<i32>,
     pub le_launch: Option<i32>,
+    #[serde(default)]
+    pub le_profile: Option<String>,
     pub magpie: Option<i32>,
+    #[serde(default)]
+    pub launch_args: Option<LaunchArgs>,
+    #[serde(default)]
+    pub launch_env: Option<LaunchEnv>,
+    #[serde(default)]
+    pub launch_hooks: Option<LaunchHooks>,
+    #[serde(default)]
+    pub wine_config: Option<WineConfig>,
+    #[serde(default)]
+    pub engine: Option<String>,
 
     pub custom_data: Option<CustomData>,
     #[serde(default)]
@@ -314,7 +651,9 @@ pub struct UpdateGameData {
     #[serde(default, deserialize_with = "double_option")]
     pub executable: Option<Option<String>>,
     #[serde(default, deserialize_with = "double_option")]
-    pub savepath: Option<Option<String>>,
+    pub savepath: Option<Option<SavePaths>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub save_registry_keys: Option<Option<SaveRegistryKeys>>,
     #[serde(default, deserialize_with = "double_option")]
     pub autosave: Option<Option<i32>>,
     #[serde(default, deserialize_with = "double_option")]
@@ -324,9 +663,23 @@ pub struct UpdateGameData {
     #[serde(default, deserialize_with = "double_option")]
     pub le_launch: Option<Option<i32>>,
     #[serde(default, deserialize_with = "double_option")]
+    pub le_profile: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
     pub magpie: Option<Option<i32>>,
     #[serde(default, deserialize_with = "double_option")]
+    pub launch_args: Option<Option<LaunchArgs>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub launch_env: Option<Option<LaunchEnv>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub launch_hooks: Option<Option<LaunchHooks>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub wine_config: Option<Option<WineConfig>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub engine: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
     pub custom_data: Option<Option<CustomData>>,
+    pub favorite: Option<bool>,
+    pub hidden: Option<bool>,
     pub upsert_sources: Option<Vec<UpsertGameSourceData>>,
     pub remove_sources: Option<Vec<String>>,
 }
@@ -334,7 +687,8 @@ pub struct UpdateGameData {
 #[cfg(test)]
 mod tests {
     use super::{
-        UpsertGameSourceData, clean_double_option_local_path, clean_json_value, clean_local_path,
+        FullGameData, GameSummary, UpsertGameSourceData, clean_double_option_local_path,
+        clean_json_value, clean_local_path,
     };
     use serde_json::json;
     use std::path::{MAIN_SEPARATOR, PathBuf};
@@ -408,4 +762,35 @@ mod tests {
         assert_eq!(clean_local_path(root.to_string()), Some(root.to_string()));
         assert_eq!(clean_double_option_local_path(Some(None)), Some(None));
     }
+
+    #[test]
+    fn game_summary_picks_display_fields() {
+        let game: FullGameData = serde_json::from_value(json!({
+            "id": 7,
+            "id_type": "mixed",
+            "clear": 3,
+            "custom_data": { "cover_source": "vndb" },
+            "sources": [
+                {
+                    "source": "bgm",
+                    "external_id": "1",
+                    "data": { "name": "千恋＊万花", "name_cn": "千恋万花", "image": "https://bgm/1.jpg" }
+                },
+                {
+                    "source": "vndb",
+                    "external_id": "v1",
+                    "data": { "name": "Senren Banka", "image": "https://vndb/1.jpg", "nsfw": true }
+                }
+            ],
+        }))
+        .unwrap();
+
+        let summary = GameSummary::new(&game, 120, Some(1_700_000_000));
+        assert_eq!(summary.name.as_deref(), Some("千恋＊万花"));
+        assert_eq!(summary.name_cn.as_deref(), Some("千恋万花"));
+        assert_eq!(summary.image.as_deref(), Some("https://vndb/1.jpg"));
+        assert!(summary.nsfw);
+        assert_eq!(summary.clear, Some(3));
+        assert_eq!(summary.total_time, 120);
+    }
 }
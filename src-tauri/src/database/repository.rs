@@ -1,4 +1,8 @@
-pub mod collections_repository;
+pub mod collections_repository;
+pub mod custom_fields_repository;
+pub mod game_characters_repository;
+pub mod game_notes_repository;
+pub mod game_relations_repository;
 pub mod game_stats_repository;
 pub mod games_repository;
 pub mod settings_repository;
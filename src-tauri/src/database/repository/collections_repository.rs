@@ -1,14 +1,77 @@
-use crate::database::dto::{InsertCollectionData, UpdateCollectionData};
-use crate::database::repository::games_repository::SortOrder;
+use crate::database::dto::{FullGameData, InsertCollectionData, UpdateCollectionData};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
 use crate::entity::prelude::*;
+use crate::entity::smart_filter::SmartFilter;
 use crate::entity::{collections, game_collection_link};
-use sea_orm::{sea_query::Expr, *};
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    *,
+};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
 /// 合集数据仓库
 pub struct CollectionsRepository;
 
+/// 排除回收站中游戏的关联记录；移入回收站时保留关联，恢复后仍在原合集中
+fn active_game_link() -> SimpleExpr {
+    Expr::cust(
+        "game_collection_link.game_id NOT IN (SELECT id FROM games WHERE deleted_at IS NOT NULL)",
+    )
+}
+
+/// 游戏是否满足智能合集的筛选条件，名称比较忽略大小写
+fn smart_filter_matches(filter: &SmartFilter, game: &FullGameData) -> bool {
+    let contains = |values: &[String], target: &str| {
+        values
+            .iter()
+            .any(|value| value.trim().eq_ignore_ascii_case(target.trim()))
+    };
+    let tags = game.tags();
+    if !filter.tags.iter().all(|tag| contains(&tags, tag)) {
+        return false;
+    }
+    let developers = game.developers();
+    if !filter.developers.is_empty()
+        && !filter
+            .developers
+            .iter()
+            .any(|developer| contains(&developers, developer))
+    {
+        return false;
+    }
+    if !filter.play_status.is_empty()
+        && !game
+            .clear
+            .is_some_and(|clear| filter.play_status.contains(&clear))
+    {
+        return false;
+    }
+    if filter.min_rating.is_some() || filter.max_rating.is_some() {
+        let Some(rating) = game.user_rating() else {
+            return false;
+        };
+        if filter.min_rating.is_some_and(|min| rating < min)
+            || filter.max_rating.is_some_and(|max| rating > max)
+        {
+            return false;
+        }
+    }
+    if filter.year_from.is_some() || filter.year_to.is_some() {
+        let Some(year) = game.release_year() else {
+            return false;
+        };
+        if filter.year_from.is_some_and(|from| year < from)
+            || filter.year_to.is_some_and(|to| year > to)
+        {
+            return false;
+        }
+    }
+    true
+}
+
 /// 带游戏数量的分类
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryWithCount {
@@ -37,6 +100,7 @@ pub struct GroupWithCount {
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CollectionBackendSortField {
+    #[serde(other)]
     CreatedAt,
     UpdatedAt,
     GameCount,
@@ -115,6 +179,85 @@ struct CategoryGamesDiff {
 }
 
 impl CollectionsRepository {
+    /// 计算智能合集中的游戏 ID，按加入游戏库的顺序排列；普通合集不出现在结果中
+    ///
+    /// 只有存在智能合集时才读取游戏数据。
+    async fn smart_collection_games(
+        db: &DatabaseConnection,
+        collections: &[collections::Model],
+    ) -> Result<std::collections::HashMap<i32, Vec<i32>>, DbErr> {
+        let smart: Vec<(i32, &SmartFilter)> = collections
+            .iter()
+            .filter_map(|collection| Some((collection.id, collection.smart_filter.as_ref()?)))
+            .collect();
+        if smart.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let games =
+            GamesRepository::find_all(db, GameType::All, SortOption::Addtime, SortOrder::Asc, None)
+                .await?;
+        Ok(smart
+            .into_iter()
+            .map(|(collection_id, filter)| {
+                let game_ids = games
+                    .iter()
+                    .filter(|game| smart_filter_matches(filter, game))
+                    .map(|game| game.id)
+                    .collect();
+                (collection_id, game_ids)
+            })
+            .collect())
+    }
+
+    /// 智能合集的游戏由筛选条件决定，不能手动增删
+    async fn ensure_not_smart<C: ConnectionTrait>(
+        db: &C,
+        collection_ids: &[i32],
+    ) -> Result<(), DbErr> {
+        let smart = Collections::find()
+            .filter(collections::Column::Id.is_in(collection_ids.to_vec()))
+            .filter(collections::Column::SmartFilter.is_not_null())
+            .one(db)
+            .await?;
+        match smart {
+            Some(collection) => Err(DbErr::Custom(format!(
+                "智能合集「{}」的游戏由筛选条件决定，不能手动修改",
+                collection.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// 校验合集的新父合集：父合集必须存在，且不能是合集自身或其子孙合集
+    async fn ensure_valid_parent<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        new_parent: Option<i32>,
+    ) -> Result<(), DbErr> {
+        let mut current = new_parent;
+        let mut visited = std::collections::HashSet::new();
+        while let Some(ancestor_id) = current {
+            if ancestor_id == id {
+                return Err(DbErr::Custom(
+                    "不能将合集移动到自身或其子合集下".to_string(),
+                ));
+            }
+            if !visited.insert(ancestor_id) {
+                return Err(DbErr::Custom(format!(
+                    "合集层级存在循环 (ID: {})",
+                    ancestor_id
+                )));
+            }
+            let ancestor = Collections::find_by_id(ancestor_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| DbErr::Custom(format!("父合集不存在 (ID: {})", ancestor_id)))?;
+            current = ancestor.parent_id;
+        }
+        Ok(())
+    }
+
     fn unique_ids(ids: Vec<i32>) -> Vec<i32> {
         let mut seen = std::collections::HashSet::new();
         ids.into_iter().filter(|id| seen.insert(*id)).collect()
@@ -322,6 +465,7 @@ impl CollectionsRepository {
             parent_id: Set(data.parent_id),
             sort_order: Set(data.sort_order),
             icon: Set(data.icon),
+            smart_filter: Set(data.smart_filter),
             created_at: Set(Some(now)),
             updated_at: Set(Some(now)),
         };
@@ -369,6 +513,7 @@ impl CollectionsRepository {
             active.name = Set(n);
         }
         if let Some(p) = data.parent_id {
+            Self::ensure_valid_parent(db, id, p).await?;
             active.parent_id = Set(p);
         }
         if let Some(s) = data.sort_order {
@@ -377,12 +522,53 @@ impl CollectionsRepository {
         if let Some(i) = data.icon {
             active.icon = Set(i);
         }
+        if let Some(f) = data.smart_filter {
+            active.smart_filter = Set(f);
+        }
 
         active.updated_at = Set(Some(chrono::Utc::now().timestamp() as i32));
 
         active.update(db).await
     }
 
+    /// 将合集连同其子合集移动到新的父合集下，排在新父合集子合集的末尾
+    ///
+    /// 校验与更新在同一事务中完成，`new_parent` 为空时移动为根合集。
+    pub async fn move_subtree(
+        db: &DatabaseConnection,
+        id: i32,
+        new_parent: Option<i32>,
+    ) -> Result<collections::Model, DbErr> {
+        let txn = db.begin().await?;
+
+        let existing = Collections::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Collection not found".to_string()))?;
+        Self::ensure_valid_parent(&txn, id, new_parent).await?;
+
+        let siblings = match new_parent {
+            Some(parent_id) => collections::Column::ParentId.eq(parent_id),
+            None => collections::Column::ParentId.is_null(),
+        };
+        let last_sort_order = Collections::find()
+            .filter(siblings)
+            .filter(collections::Column::Id.ne(id))
+            .order_by_desc(collections::Column::SortOrder)
+            .one(&txn)
+            .await?
+            .map(|collection| collection.sort_order);
+
+        let mut active: collections::ActiveModel = existing.into();
+        active.parent_id = Set(new_parent);
+        active.sort_order = Set(last_sort_order.map_or(0, |sort_order| sort_order + 1));
+        active.updated_at = Set(Some(chrono::Utc::now().timestamp() as i32));
+        let moved = active.update(&txn).await?;
+
+        txn.commit().await?;
+        Ok(moved)
+    }
+
     /// 删除合集（会级联删除子合集和游戏关联）
     pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<DeleteResult, DbErr> {
         Collections::delete_by_id(id).exec(db).await
@@ -409,12 +595,22 @@ impl CollectionsRepository {
     }
 
     /// 获取合集中的所有游戏 ID
+    ///
+    /// 智能合集实时按筛选条件计算，普通合集读取关联表。
     pub async fn get_games_in_collection(
         db: &DatabaseConnection,
         collection_id: i32,
     ) -> Result<Vec<i32>, DbErr> {
+        if let Some(collection) = Collections::find_by_id(collection_id).one(db).await?
+            && collection.smart_filter.is_some()
+        {
+            let mut smart = Self::smart_collection_games(db, &[collection]).await?;
+            return Ok(smart.remove(&collection_id).unwrap_or_default());
+        }
+
         let links = GameCollectionLink::find()
             .filter(game_collection_link::Column::CollectionId.eq(collection_id))
+            .filter(active_game_link())
             .order_by_asc(game_collection_link::Column::SortOrder)
             .all(db)
             .await?;
@@ -422,6 +618,64 @@ impl CollectionsRepository {
         Ok(links.into_iter().map(|link| link.game_id).collect())
     }
 
+    /// 获取合集及其全部子孙合集中的游戏 ID（去重），按合集层级与合集内排序排列
+    pub async fn get_games_in_subtree(
+        db: &DatabaseConnection,
+        collection_id: i32,
+    ) -> Result<Vec<i32>, DbErr> {
+        let all = Collections::find()
+            .order_by_asc(collections::Column::SortOrder)
+            .order_by_asc(collections::Column::Id)
+            .all(db)
+            .await?;
+        let mut subtree: Vec<&collections::Model> =
+            all.iter().filter(|item| item.id == collection_id).collect();
+        if subtree.is_empty() {
+            return Err(DbErr::RecordNotFound("Collection not found".to_string()));
+        }
+        let mut index = 0;
+        while index < subtree.len() {
+            let parent_id = subtree[index].id;
+            subtree.extend(
+                all.iter()
+                    .filter(|item| item.parent_id == Some(parent_id) && item.id != collection_id),
+            );
+            index += 1;
+        }
+
+        let subtree_ids: Vec<i32> = subtree.iter().map(|item| item.id).collect();
+        let smart: Vec<collections::Model> = subtree
+            .iter()
+            .filter(|item| item.smart_filter.is_some())
+            .map(|item| (*item).clone())
+            .collect();
+        let mut smart_games = Self::smart_collection_games(db, &smart).await?;
+        let links = GameCollectionLink::find()
+            .filter(game_collection_link::Column::CollectionId.is_in(subtree_ids.clone()))
+            .filter(active_game_link())
+            .order_by_asc(game_collection_link::Column::SortOrder)
+            .all(db)
+            .await?;
+
+        let mut seen = std::collections::HashSet::new();
+        let mut game_ids = Vec::new();
+        for id in subtree_ids {
+            let collection_games = smart_games.remove(&id).unwrap_or_else(|| {
+                links
+                    .iter()
+                    .filter(|link| link.collection_id == id)
+                    .map(|link| link.game_id)
+                    .collect()
+            });
+            game_ids.extend(
+                collection_games
+                    .into_iter()
+                    .filter(|game_id| seen.insert(*game_id)),
+            );
+        }
+        Ok(game_ids)
+    }
+
     /// 获取游戏所在的所有合集 ID
     pub async fn get_game_collection_ids(
         db: &DatabaseConnection,
@@ -449,6 +703,7 @@ impl CollectionsRepository {
         }
 
         let txn = db.begin().await?;
+        Self::ensure_not_smart(&txn, &collection_ids).await?;
         let current_links = GameCollectionLink::find()
             .filter(game_collection_link::Column::GameId.is_in(game_ids.clone()))
             .filter(game_collection_link::Column::CollectionId.is_in(collection_ids.clone()))
@@ -477,13 +732,15 @@ impl CollectionsRepository {
         game_id: i32,
         collection_ids: Vec<i32>,
     ) -> Result<(), DbErr> {
+        let collection_ids = Self::unique_ids(collection_ids);
         let txn = db.begin().await?;
+        Self::ensure_not_smart(&txn, &collection_ids).await?;
 
         let current_links = GameCollectionLink::find()
             .filter(game_collection_link::Column::GameId.eq(game_id))
             .all(&txn)
             .await?;
-        let target_pairs = Self::unique_ids(collection_ids)
+        let target_pairs = collection_ids
             .into_iter()
             .map(|collection_id| GameCollectionPair {
                 game_id,
@@ -517,6 +774,7 @@ impl CollectionsRepository {
         collection_id: i32,
     ) -> Result<(), DbErr> {
         let txn = db.begin().await?;
+        Self::ensure_not_smart(&txn, &[collection_id]).await?;
         let current_links = GameCollectionLink::find()
             .filter(game_collection_link::Column::CollectionId.eq(collection_id))
             .all(&txn)
@@ -531,6 +789,55 @@ impl CollectionsRepository {
         Ok(())
     }
 
+    /// 按给定的关联记录 ID 顺序批量调整合集内游戏的排序
+    ///
+    /// 未列出的关联记录保持原有相对顺序排在末尾，返回实际更新的记录数。
+    pub async fn reorder_games(
+        db: &DatabaseConnection,
+        collection_id: i32,
+        ordered_link_ids: Vec<i32>,
+    ) -> Result<usize, DbErr> {
+        let txn = db.begin().await?;
+        Self::ensure_not_smart(&txn, &[collection_id]).await?;
+        let current_links = GameCollectionLink::find()
+            .filter(game_collection_link::Column::CollectionId.eq(collection_id))
+            .order_by_asc(game_collection_link::Column::SortOrder)
+            .order_by_asc(game_collection_link::Column::Id)
+            .all(&txn)
+            .await?;
+
+        let ordered_link_ids = Self::unique_ids(ordered_link_ids);
+        if let Some(unknown) = ordered_link_ids
+            .iter()
+            .find(|id| !current_links.iter().any(|link| link.id == **id))
+        {
+            return Err(DbErr::Custom(format!(
+                "关联记录 {} 不属于合集 {}",
+                unknown, collection_id
+            )));
+        }
+
+        let rest = current_links
+            .iter()
+            .map(|link| link.id)
+            .filter(|id| !ordered_link_ids.contains(id));
+        let updates: Vec<(i32, i32)> = ordered_link_ids
+            .iter()
+            .copied()
+            .chain(rest)
+            .enumerate()
+            .filter_map(|(index, id)| {
+                let link = current_links.iter().find(|link| link.id == id)?;
+                (link.sort_order != index as i32).then_some((id, index as i32))
+            })
+            .collect();
+        let updated = updates.len();
+
+        Self::update_game_collection_sort_orders(&txn, updates).await?;
+        txn.commit().await?;
+        Ok(updated)
+    }
+
     // ==================== 前端友好的组合 API ====================
 
     /// 获取根分组列表（带游戏数量）
@@ -568,49 +875,67 @@ impl CollectionsRepository {
         Ok(groups)
     }
 
-    /// 批量获取多个分组的游戏数量
+    /// 批量获取多个分组的游戏数量（分组下所有分类的游戏去重计数，含智能合集）
     ///
     /// 返回 HashMap<group_id, game_count>
     pub async fn batch_count_games_in_groups(
         db: &DatabaseConnection,
         group_ids: Vec<i32>,
     ) -> Result<std::collections::HashMap<i32, u64>, DbErr> {
-        use std::collections::HashMap;
+        use std::collections::{HashMap, HashSet};
 
         if group_ids.is_empty() {
             return Ok(HashMap::new());
         }
 
-        let mut result = group_ids
+        let mut games_by_group = group_ids
             .iter()
             .copied()
-            .map(|group_id| (group_id, 0))
+            .map(|group_id| (group_id, HashSet::new()))
             .collect::<HashMap<_, _>>();
 
-        let counts = Collections::find()
+        let linked = Collections::find()
             .filter(collections::Column::ParentId.is_in(group_ids.clone()))
+            .filter(collections::Column::SmartFilter.is_null())
             .join(
                 JoinType::InnerJoin,
                 collections::Relation::GameCollectionLink.def(),
             )
+            .filter(active_game_link())
             .select_only()
             .column(collections::Column::ParentId)
-            .column_as(
-                Expr::col(game_collection_link::Column::GameId).count_distinct(),
-                "game_count",
-            )
-            .group_by(collections::Column::ParentId)
-            .into_tuple::<(Option<i32>, i64)>()
+            .column(game_collection_link::Column::GameId)
+            .distinct()
+            .into_tuple::<(Option<i32>, i32)>()
             .all(db)
             .await?;
+        for (group_id, game_id) in linked {
+            if let Some(games) = group_id.and_then(|id| games_by_group.get_mut(&id)) {
+                games.insert(game_id);
+            }
+        }
 
-        for (group_id, count) in counts {
-            if let Some(group_id) = group_id {
-                result.insert(group_id, count as u64);
+        let smart_categories = Collections::find()
+            .filter(collections::Column::ParentId.is_in(group_ids))
+            .filter(collections::Column::SmartFilter.is_not_null())
+            .all(db)
+            .await?;
+        let smart_games = Self::smart_collection_games(db, &smart_categories).await?;
+        for category in &smart_categories {
+            if let (Some(games), Some(game_ids)) = (
+                category
+                    .parent_id
+                    .and_then(|id| games_by_group.get_mut(&id)),
+                smart_games.get(&category.id),
+            ) {
+                games.extend(game_ids.iter().copied());
             }
         }
 
-        Ok(result)
+        Ok(games_by_group
+            .into_iter()
+            .map(|(group_id, games)| (group_id, games.len() as u64))
+            .collect())
     }
 
     /// 获取单个分组中的游戏总数（统计该分组下所有分类的游戏数）
@@ -620,24 +945,8 @@ impl CollectionsRepository {
         db: &DatabaseConnection,
         group_id: i32,
     ) -> Result<u64, DbErr> {
-        // 获取该分组下的所有分类
-        let categories = Self::find_children(db, group_id).await?;
-        let category_ids: Vec<i32> = categories.iter().map(|c| c.id).collect();
-
-        if category_ids.is_empty() {
-            return Ok(0);
-        }
-
-        // 统计这些分类中的游戏总数（去重）
-        let count = GameCollectionLink::find()
-            .filter(game_collection_link::Column::CollectionId.is_in(category_ids))
-            .select_only()
-            .column_as(game_collection_link::Column::GameId, "game_id")
-            .distinct()
-            .count(db)
-            .await?;
-
-        Ok(count)
+        let counts = Self::batch_count_games_in_groups(db, vec![group_id]).await?;
+        Ok(counts.get(&group_id).copied().unwrap_or(0))
     }
 
     /// 获取指定分组的分类列表（带游戏数量）
@@ -659,6 +968,7 @@ impl CollectionsRepository {
             .collect::<Vec<_>>();
         let counts = GameCollectionLink::find()
             .filter(game_collection_link::Column::CollectionId.is_in(category_ids))
+            .filter(active_game_link())
             .select_only()
             .column(game_collection_link::Column::CollectionId)
             .column_as(game_collection_link::Column::Id.count(), "game_count")
@@ -669,6 +979,7 @@ impl CollectionsRepository {
             .into_iter()
             .map(|(collection_id, count)| (collection_id, count as u64))
             .collect::<HashMap<_, _>>();
+        let smart_games = Self::smart_collection_games(db, &categories).await?;
 
         let mut categories = categories
             .into_iter()
@@ -677,7 +988,10 @@ impl CollectionsRepository {
                 name: category.name,
                 icon: category.icon,
                 sort_order: category.sort_order,
-                game_count: counts.get(&category.id).copied().unwrap_or(0),
+                game_count: match smart_games.get(&category.id) {
+                    Some(game_ids) => game_ids.len() as u64,
+                    None => counts.get(&category.id).copied().unwrap_or(0),
+                },
                 created_at: category.created_at,
                 updated_at: category.updated_at,
             })
@@ -697,3 +1011,184 @@ impl CollectionsRepository {
         Ok(categories)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Database;
+    use serde_json::json;
+
+    fn game(clear: i32, rating: Option<f64>, date: &str) -> FullGameData {
+        serde_json::from_value(json!({
+            "id": 1,
+            "id_type": "custom",
+            "date": date,
+            "clear": clear,
+            "custom_data": {
+                "developer": "Key/VisualArt's",
+                "tags": ["纯爱", "Nakige"],
+                "user_rating": rating,
+            },
+            "sources": [],
+        }))
+        .expect("测试游戏数据应能反序列化")
+    }
+
+    async fn setup_database() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared(
+            r#"
+            PRAGMA foreign_keys = ON;
+            CREATE TABLE collections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                parent_id INTEGER,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                icon TEXT,
+                smart_filter TEXT,
+                created_at INTEGER,
+                updated_at INTEGER,
+                FOREIGN KEY(parent_id) REFERENCES collections(id) ON DELETE CASCADE
+            );
+            CREATE TABLE games (
+                id INTEGER PRIMARY KEY,
+                deleted_at INTEGER
+            );
+            CREATE TABLE game_collection_link (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                game_id INTEGER NOT NULL,
+                collection_id INTEGER NOT NULL,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER,
+                FOREIGN KEY(game_id) REFERENCES games(id) ON DELETE CASCADE,
+                FOREIGN KEY(collection_id) REFERENCES collections(id) ON DELETE CASCADE
+            );
+            INSERT INTO collections (id, name, parent_id, sort_order) VALUES
+                (1, '分组', NULL, 0),
+                (2, '分类', 1, 0),
+                (3, '子分类', 2, 0),
+                (4, '其他分组', NULL, 1),
+                (5, '已有分类', 4, 3);
+            INSERT INTO games (id, deleted_at) VALUES (1, NULL), (2, NULL), (3, NULL), (4, NULL);
+            INSERT INTO game_collection_link (id, game_id, collection_id, sort_order) VALUES
+                (10, 1, 2, 0),
+                (11, 2, 2, 1),
+                (12, 3, 2, 2),
+                (13, 4, 2, 3),
+                (14, 1, 5, 0);
+            "#,
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    fn move_to(parent_id: Option<i32>) -> UpdateCollectionData {
+        UpdateCollectionData {
+            name: None,
+            parent_id: Some(parent_id),
+            sort_order: None,
+            icon: None,
+            smart_filter: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_moving_collection_under_itself() {
+        let db = setup_database().await;
+
+        for (id, parent_id) in [(1, 1), (1, 3), (2, 3), (2, 9)] {
+            assert!(matches!(
+                CollectionsRepository::update(&db, id, move_to(Some(parent_id))).await,
+                Err(DbErr::Custom(_))
+            ));
+            assert!(matches!(
+                CollectionsRepository::move_subtree(&db, id, Some(parent_id)).await,
+                Err(DbErr::Custom(_))
+            ));
+        }
+
+        let moved = CollectionsRepository::update(&db, 3, move_to(Some(1)))
+            .await
+            .unwrap();
+        assert_eq!(moved.parent_id, Some(1));
+    }
+
+    #[tokio::test]
+    async fn moves_collection_subtree_to_end_of_new_parent() {
+        let db = setup_database().await;
+
+        let moved = CollectionsRepository::move_subtree(&db, 2, Some(4))
+            .await
+            .unwrap();
+        assert_eq!((moved.parent_id, moved.sort_order), (Some(4), 4));
+        let child = Collections::find_by_id(3).one(&db).await.unwrap().unwrap();
+        assert_eq!(child.parent_id, Some(2));
+
+        let moved = CollectionsRepository::move_subtree(&db, 2, None)
+            .await
+            .unwrap();
+        assert_eq!((moved.parent_id, moved.sort_order), (None, 2));
+    }
+
+    #[tokio::test]
+    async fn reorders_collection_games_in_batch() {
+        let db = setup_database().await;
+
+        let updated = CollectionsRepository::reorder_games(&db, 2, vec![12, 10, 12])
+            .await
+            .unwrap();
+        assert_eq!(updated, 3);
+        assert_eq!(
+            CollectionsRepository::get_games_in_collection(&db, 2)
+                .await
+                .unwrap(),
+            [3, 1, 2, 4]
+        );
+
+        assert!(matches!(
+            CollectionsRepository::reorder_games(&db, 2, vec![14]).await,
+            Err(DbErr::Custom(_))
+        ));
+    }
+
+    #[test]
+    fn matches_smart_filter_conditions() {
+        let filter =
+            |value: serde_json::Value| -> SmartFilter { serde_json::from_value(value).unwrap() };
+        let rated = game(2, Some(9.0), "2018-06-29");
+
+        assert!(smart_filter_matches(&SmartFilter::default(), &rated));
+        assert!(smart_filter_matches(
+            &filter(json!({
+                "tags": ["nakige"],
+                "developers": ["key", "Yuzusoft"],
+                "play_status": [2, 3],
+                "min_rating": 8.5,
+                "year_from": 2018,
+                "year_to": 2018,
+            })),
+            &rated
+        ));
+        assert!(!smart_filter_matches(
+            &filter(json!({ "tags": ["纯爱", "校园"] })),
+            &rated
+        ));
+        assert!(!smart_filter_matches(
+            &filter(json!({ "play_status": [1] })),
+            &rated
+        ));
+        assert!(!smart_filter_matches(
+            &filter(json!({ "max_rating": 8 })),
+            &rated
+        ));
+        assert!(!smart_filter_matches(
+            &filter(json!({ "min_rating": 1 })),
+            &game(2, None, "2018-06-29")
+        ));
+        assert!(!smart_filter_matches(
+            &filter(json!({ "year_to": 2017 })),
+            &rated
+        ));
+    }
+}
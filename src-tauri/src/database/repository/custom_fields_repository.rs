@@ -0,0 +1,388 @@
+//! 用户自定义字段仓库。
+
+use crate::entity::prelude::*;
+use crate::entity::{custom_fields, game_custom_field_values};
+use sea_orm::sea_query::{Expr, LikeExpr, OnConflict};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+
+/// 自定义字段数据仓库
+pub struct CustomFieldsRepository;
+
+/// 自定义字段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    /// 数值字段，写入时校验并支持按范围筛选
+    Number,
+}
+
+impl CustomFieldType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Number => "number",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "number" => Self::Number,
+            _ => Self::Text,
+        }
+    }
+
+    /// 按字段类型清洗写入值，空值返回 Ok(None)
+    fn clean_value(self, value: &str) -> Result<Option<String>, DbErr> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        if self == Self::Number && !value.parse::<f64>().is_ok_and(f64::is_finite) {
+            return Err(DbErr::Custom(format!("字段值不是有效数字: {}", value)));
+        }
+        Ok(Some(value.to_string()))
+    }
+}
+
+/// 游戏的自定义字段值，带字段定义
+#[derive(Debug, Clone, PartialEq, Serialize, FromQueryResult)]
+pub struct GameCustomFieldValue {
+    pub field_id: i32,
+    pub name: String,
+    pub field_type: String,
+    pub value: String,
+}
+
+/// 按自定义字段筛选游戏的条件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomFieldFilter {
+    pub field_id: i32,
+    /// 文本包含（不区分大小写），为空时只要求有值
+    pub value: Option<String>,
+    /// 数值字段的下限（含）
+    pub min: Option<f64>,
+    /// 数值字段的上限（含）
+    pub max: Option<f64>,
+}
+
+/// 转义 LIKE 通配符，使关键词按字面匹配
+fn like_pattern(keyword: &str) -> LikeExpr {
+    let escaped = keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    LikeExpr::new(format!("%{}%", escaped)).escape('\\')
+}
+
+impl CustomFieldsRepository {
+    pub async fn find_fields(db: &DatabaseConnection) -> Result<Vec<custom_fields::Model>, DbErr> {
+        CustomFields::find()
+            .order_by_asc(custom_fields::Column::SortOrder)
+            .order_by_asc(custom_fields::Column::Id)
+            .all(db)
+            .await
+    }
+
+    async fn find_field<C>(db: &C, field_id: i32) -> Result<custom_fields::Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        CustomFields::find_by_id(field_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("custom field {} not found", field_id)))
+    }
+
+    /// 新建字段，排在已有字段之后
+    pub async fn create_field(
+        db: &DatabaseConnection,
+        name: &str,
+        field_type: CustomFieldType,
+    ) -> Result<custom_fields::Model, DbErr> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DbErr::Custom("字段名不能为空".to_string()));
+        }
+        let max_sort_order = CustomFields::find()
+            .select_only()
+            .column_as(custom_fields::Column::SortOrder.max(), "max_sort_order")
+            .into_tuple::<Option<i32>>()
+            .one(db)
+            .await?
+            .flatten();
+
+        custom_fields::ActiveModel {
+            name: Set(name.to_string()),
+            field_type: Set(field_type.as_str().to_string()),
+            sort_order: Set(max_sort_order.map_or(0, |sort_order| sort_order + 1)),
+            created_at: Set(Some(chrono::Utc::now().timestamp() as i32)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// 修改字段名或类型，改为数值类型时要求已有的值均为有效数字
+    pub async fn update_field(
+        db: &DatabaseConnection,
+        field_id: i32,
+        name: Option<String>,
+        field_type: Option<CustomFieldType>,
+    ) -> Result<custom_fields::Model, DbErr> {
+        let name = name.map(|name| name.trim().to_string());
+        if name.as_deref().is_some_and(str::is_empty) {
+            return Err(DbErr::Custom("字段名不能为空".to_string()));
+        }
+
+        let field = Self::find_field(db, field_id).await?;
+        if field_type == Some(CustomFieldType::Number)
+            && CustomFieldType::parse(&field.field_type) != CustomFieldType::Number
+        {
+            let values: Vec<String> = GameCustomFieldValues::find()
+                .select_only()
+                .column(game_custom_field_values::Column::Value)
+                .filter(game_custom_field_values::Column::FieldId.eq(field_id))
+                .into_tuple()
+                .all(db)
+                .await?;
+            for value in &values {
+                CustomFieldType::Number.clean_value(value)?;
+            }
+        }
+
+        custom_fields::ActiveModel {
+            id: Unchanged(field_id),
+            name: name.map_or(NotSet, Set),
+            field_type: field_type
+                .map_or(NotSet, |field_type| Set(field_type.as_str().to_string())),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    /// 删除字段及所有游戏上的字段值
+    pub async fn delete_field(db: &DatabaseConnection, field_id: i32) -> Result<u64, DbErr> {
+        CustomFields::delete_by_id(field_id)
+            .exec(db)
+            .await
+            .map(|result| result.rows_affected)
+    }
+
+    /// 获取游戏已填写的字段值，按字段顺序排列
+    pub async fn find_game_values(
+        db: &DatabaseConnection,
+        game_id: i32,
+    ) -> Result<Vec<GameCustomFieldValue>, DbErr> {
+        GameCustomFieldValues::find()
+            .select_only()
+            .column(game_custom_field_values::Column::FieldId)
+            .column(custom_fields::Column::Name)
+            .column(custom_fields::Column::FieldType)
+            .column(game_custom_field_values::Column::Value)
+            .inner_join(CustomFields)
+            .filter(game_custom_field_values::Column::GameId.eq(game_id))
+            .order_by_asc(custom_fields::Column::SortOrder)
+            .order_by_asc(custom_fields::Column::Id)
+            .into_model::<GameCustomFieldValue>()
+            .all(db)
+            .await
+    }
+
+    /// 设置游戏的字段值，值为空时删除
+    pub async fn set_game_value(
+        db: &DatabaseConnection,
+        game_id: i32,
+        field_id: i32,
+        value: Option<String>,
+    ) -> Result<Option<String>, DbErr> {
+        let field = Self::find_field(db, field_id).await?;
+        let value = match value {
+            Some(value) => CustomFieldType::parse(&field.field_type).clean_value(&value)?,
+            None => None,
+        };
+
+        match &value {
+            Some(value) => {
+                GameCustomFieldValues::insert(game_custom_field_values::ActiveModel {
+                    game_id: Set(game_id),
+                    field_id: Set(field_id),
+                    value: Set(value.clone()),
+                })
+                .on_conflict(
+                    OnConflict::columns([
+                        game_custom_field_values::Column::GameId,
+                        game_custom_field_values::Column::FieldId,
+                    ])
+                    .update_column(game_custom_field_values::Column::Value)
+                    .to_owned(),
+                )
+                .exec(db)
+                .await?;
+            }
+            None => {
+                GameCustomFieldValues::delete_by_id((game_id, field_id))
+                    .exec(db)
+                    .await?;
+            }
+        }
+        Ok(value)
+    }
+
+    /// 按字段值筛选游戏，回收站中的游戏不返回
+    pub async fn find_game_ids(
+        db: &DatabaseConnection,
+        filter: &CustomFieldFilter,
+    ) -> Result<Vec<i32>, DbErr> {
+        let mut query = GameCustomFieldValues::find()
+            .select_only()
+            .column(game_custom_field_values::Column::GameId)
+            .filter(game_custom_field_values::Column::FieldId.eq(filter.field_id))
+            .filter(Expr::cust(
+                "game_custom_field_values.game_id NOT IN (SELECT id FROM games WHERE deleted_at IS NOT NULL)",
+            ));
+        if let Some(value) = filter
+            .value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            query = query.filter(
+                Expr::col(game_custom_field_values::Column::Value).like(like_pattern(value)),
+            );
+        }
+        let numeric = || Expr::expr(Expr::cust("CAST(game_custom_field_values.value AS REAL)"));
+        if let Some(min) = filter.min {
+            query = query.filter(numeric().gte(min));
+        }
+        if let Some(max) = filter.max {
+            query = query.filter(numeric().lte(max));
+        }
+
+        query
+            .order_by_asc(game_custom_field_values::Column::GameId)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Database;
+
+    async fn setup_database() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared(
+            r#"
+            PRAGMA foreign_keys = ON;
+            CREATE TABLE games (
+                id INTEGER PRIMARY KEY,
+                deleted_at INTEGER
+            );
+            CREATE TABLE custom_fields (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                field_type TEXT NOT NULL DEFAULT 'text',
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER
+            );
+            CREATE TABLE game_custom_field_values (
+                game_id INTEGER NOT NULL,
+                field_id INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (game_id, field_id),
+                FOREIGN KEY(game_id) REFERENCES games(id) ON DELETE CASCADE,
+                FOREIGN KEY(field_id) REFERENCES custom_fields(id) ON DELETE CASCADE
+            );
+            INSERT INTO games (id, deleted_at) VALUES (1, NULL), (2, NULL), (3, 100);
+            "#,
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn sets_values_and_filters_games() {
+        let db = setup_database().await;
+        let group = CustomFieldsRepository::create_field(&db, " 汉化组 ", CustomFieldType::Text)
+            .await
+            .unwrap();
+        let price = CustomFieldsRepository::create_field(&db, "价格", CustomFieldType::Number)
+            .await
+            .unwrap();
+        assert_eq!(group.name, "汉化组");
+        assert_eq!(price.sort_order, group.sort_order + 1);
+
+        for (game_id, name, amount) in [(1, "Sakura 汉化组", "128"), (2, "其他", "68.5")] {
+            for (field_id, value) in [(group.id, name), (price.id, amount)] {
+                CustomFieldsRepository::set_game_value(&db, game_id, field_id, Some(value.into()))
+                    .await
+                    .unwrap();
+            }
+        }
+        CustomFieldsRepository::set_game_value(&db, 3, group.id, Some("sakura".into()))
+            .await
+            .unwrap();
+        assert!(
+            CustomFieldsRepository::set_game_value(&db, 1, price.id, Some("免费".into()))
+                .await
+                .is_err()
+        );
+
+        let find = |filter: CustomFieldFilter| {
+            let db = db.clone();
+            async move {
+                CustomFieldsRepository::find_game_ids(&db, &filter)
+                    .await
+                    .unwrap()
+            }
+        };
+        let by_name = CustomFieldFilter {
+            field_id: group.id,
+            value: Some("SAKURA".into()),
+            ..Default::default()
+        };
+        assert_eq!(find(by_name).await, [1]);
+        let cheap = CustomFieldFilter {
+            field_id: price.id,
+            max: Some(100.0),
+            ..Default::default()
+        };
+        assert_eq!(find(cheap).await, [2]);
+
+        let values = CustomFieldsRepository::find_game_values(&db, 1)
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].name, "汉化组");
+        assert_eq!(values[1].value, "128");
+
+        CustomFieldsRepository::set_game_value(&db, 1, group.id, Some("  ".into()))
+            .await
+            .unwrap();
+        assert!(
+            CustomFieldsRepository::update_field(
+                &db,
+                group.id,
+                None,
+                Some(CustomFieldType::Number)
+            )
+            .await
+            .is_err()
+        );
+        CustomFieldsRepository::delete_field(&db, price.id)
+            .await
+            .unwrap();
+        assert!(
+            CustomFieldsRepository::find_game_values(&db, 1)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
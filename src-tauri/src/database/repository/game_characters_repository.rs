@@ -0,0 +1,147 @@
+//! 游戏角色仓库。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::{GameListFilter, GamesRepository};
+use crate::entity::game_characters;
+use crate::entity::prelude::*;
+use sea_orm::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 游戏角色数据仓库
+pub struct GameCharactersRepository;
+
+/// 按声优搜索到的游戏及其中由该声优配音的角色
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceActorGame {
+    pub game: FullGameData,
+    pub characters: Vec<game_characters::Model>,
+}
+
+/// 转义 LIKE 通配符，配合 `ESCAPE '\'` 使用
+fn escape_like(keyword: &str) -> String {
+    keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl GameCharactersRepository {
+    /// 用新的角色列表替换游戏现有的角色，同一角色只保留第一条
+    pub async fn replace_for_game(
+        db: &DatabaseConnection,
+        game_id: i32,
+        characters: Vec<game_characters::Model>,
+    ) -> Result<usize, DbErr> {
+        let mut seen = HashSet::new();
+        let models: Vec<game_characters::ActiveModel> = characters
+            .into_iter()
+            .filter(|character| seen.insert(character.character_id))
+            .map(|character| game_characters::ActiveModel {
+                game_id: Set(game_id),
+                character_id: Set(character.character_id),
+                name: Set(character.name),
+                relation: Set(character.relation),
+                image: Set(character.image),
+                actors: Set(character.actors),
+                sort_order: Set(character.sort_order),
+            })
+            .collect();
+        let count = models.len();
+
+        let transaction = db.begin().await?;
+        GameCharacters::delete_many()
+            .filter(game_characters::Column::GameId.eq(game_id))
+            .exec(&transaction)
+            .await?;
+        if !models.is_empty() {
+            GameCharacters::insert_many(models)
+                .exec(&transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(count)
+    }
+
+    /// 获取游戏的角色列表，按 BGM 中的顺序排列
+    pub async fn find_by_game(
+        db: &DatabaseConnection,
+        game_id: i32,
+    ) -> Result<Vec<game_characters::Model>, DbErr> {
+        GameCharacters::find()
+            .filter(game_characters::Column::GameId.eq(game_id))
+            .order_by_asc(game_characters::Column::SortOrder)
+            .all(db)
+            .await
+    }
+
+    /// 按声优名称搜索库中的游戏，按游戏 ID 排序
+    ///
+    /// 声优名称部分匹配且忽略大小写，回收站中的游戏不返回。
+    pub async fn find_games_by_actor(
+        db: &DatabaseConnection,
+        keyword: &str,
+        filter: GameListFilter,
+    ) -> Result<Vec<VoiceActorGame>, DbErr> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let filter_condition: String = filter
+            .conditions("g")
+            .iter()
+            .map(|condition| format!(" AND {condition}"))
+            .collect();
+        let sql = format!(
+            r#"
+            SELECT c.game_id
+            FROM game_characters AS c
+            JOIN games AS g ON g.id = c.game_id
+            WHERE c.actors LIKE ?1 ESCAPE '\' AND g.deleted_at IS NULL{filter_condition}
+            "#
+        );
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                sql,
+                [format!("%{}%", escape_like(keyword)).into()],
+            ))
+            .await?;
+        let mut ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            ids.push(row.try_get::<i32>("", "game_id")?);
+        }
+        ids.sort_unstable();
+        ids.dedup();
+
+        // LIKE 也会命中 JSON 中的其他内容，按声优名称再筛选一次
+        let mut characters: HashMap<i32, Vec<game_characters::Model>> = HashMap::new();
+        for character in GameCharacters::find()
+            .filter(game_characters::Column::GameId.is_in(ids.clone()))
+            .order_by_asc(game_characters::Column::SortOrder)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|character| character.actors.contains_name(keyword))
+        {
+            characters
+                .entry(character.game_id)
+                .or_default()
+                .push(character);
+        }
+
+        let ids: Vec<i32> = ids
+            .into_iter()
+            .filter(|id| characters.contains_key(id))
+            .collect();
+        Ok(GamesRepository::find_full_games_in_order(db, &ids)
+            .await?
+            .into_iter()
+            .filter_map(|game| {
+                let characters = characters.remove(&game.id)?;
+                Some(VoiceActorGame { game, characters })
+            })
+            .collect())
+    }
+}
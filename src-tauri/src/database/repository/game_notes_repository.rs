@@ -0,0 +1,202 @@
+//! 游戏笔记仓库。
+
+use crate::entity::game_notes;
+use crate::entity::prelude::*;
+use sea_orm::sea_query::{Expr, LikeExpr};
+use sea_orm::*;
+
+/// 游戏笔记数据仓库
+pub struct GameNotesRepository;
+
+/// 转义 LIKE 通配符，使关键词按字面匹配
+fn like_pattern(keyword: &str) -> LikeExpr {
+    let escaped = keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    LikeExpr::new(format!("%{}%", escaped)).escape('\\')
+}
+
+impl GameNotesRepository {
+    /// 获取游戏的全部笔记，最近修改的在前
+    pub async fn find_by_game(
+        db: &DatabaseConnection,
+        game_id: i32,
+    ) -> Result<Vec<game_notes::Model>, DbErr> {
+        GameNotes::find()
+            .filter(game_notes::Column::GameId.eq(game_id))
+            .order_by_desc(game_notes::Column::UpdatedAt)
+            .order_by_desc(game_notes::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn create(
+        db: &DatabaseConnection,
+        game_id: i32,
+        title: String,
+        content: String,
+    ) -> Result<game_notes::Model, DbErr> {
+        let now = chrono::Utc::now().timestamp() as i32;
+        game_notes::ActiveModel {
+            game_id: Set(game_id),
+            title: Set(title),
+            content: Set(content),
+            created_at: Set(Some(now)),
+            updated_at: Set(Some(now)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// 更新笔记，参数为空的字段保持不变
+    pub async fn update(
+        db: &DatabaseConnection,
+        note_id: i32,
+        title: Option<String>,
+        content: Option<String>,
+    ) -> Result<game_notes::Model, DbErr> {
+        game_notes::ActiveModel {
+            id: Unchanged(note_id),
+            title: title.map_or(NotSet, Set),
+            content: content.map_or(NotSet, Set),
+            updated_at: Set(Some(chrono::Utc::now().timestamp() as i32)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn delete(db: &DatabaseConnection, note_id: i32) -> Result<u64, DbErr> {
+        GameNotes::delete_by_id(note_id)
+            .exec(db)
+            .await
+            .map(|result| result.rows_affected)
+    }
+
+    /// 按关键词搜索笔记标题与内容（不区分大小写），最近修改的在前
+    ///
+    /// 回收站中游戏的笔记不参与搜索。
+    pub async fn search(
+        db: &DatabaseConnection,
+        keyword: &str,
+        game_id: Option<i32>,
+    ) -> Result<Vec<game_notes::Model>, DbErr> {
+        let mut query = GameNotes::find()
+            .filter(
+                Condition::any()
+                    .add(Expr::col(game_notes::Column::Title).like(like_pattern(keyword)))
+                    .add(Expr::col(game_notes::Column::Content).like(like_pattern(keyword))),
+            )
+            .filter(Expr::cust(
+                "game_notes.game_id NOT IN (SELECT id FROM games WHERE deleted_at IS NOT NULL)",
+            ));
+        if let Some(game_id) = game_id {
+            query = query.filter(game_notes::Column::GameId.eq(game_id));
+        }
+        query
+            .order_by_desc(game_notes::Column::UpdatedAt)
+            .order_by_desc(game_notes::Column::Id)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Database;
+
+    async fn setup_database() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared(
+            r#"
+            PRAGMA foreign_keys = ON;
+            CREATE TABLE games (
+                id INTEGER PRIMARY KEY,
+                deleted_at INTEGER
+            );
+            CREATE TABLE game_notes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                game_id INTEGER NOT NULL,
+                title TEXT NOT NULL DEFAULT '',
+                content TEXT NOT NULL DEFAULT '',
+                created_at INTEGER,
+                updated_at INTEGER,
+                FOREIGN KEY(game_id) REFERENCES games(id) ON DELETE CASCADE
+            );
+            INSERT INTO games (id, deleted_at) VALUES (1, NULL), (2, NULL), (3, 100);
+            "#,
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn searches_notes_by_keyword() {
+        let db = setup_database().await;
+        let route = GameNotesRepository::create(
+            &db,
+            1,
+            "路线顺序".to_string(),
+            "先攻略 **Route_A**，再解锁真结局".to_string(),
+        )
+        .await
+        .unwrap();
+        GameNotesRepository::create(&db, 2, "CG 缺漏".to_string(), "缺 100% 收集".to_string())
+            .await
+            .unwrap();
+        GameNotesRepository::create(&db, 3, "路线".to_string(), String::new())
+            .await
+            .unwrap();
+
+        let ids = |notes: Vec<game_notes::Model>| -> Vec<i32> {
+            notes.into_iter().map(|note| note.game_id).collect()
+        };
+        assert_eq!(
+            ids(GameNotesRepository::search(&db, "路线", None)
+                .await
+                .unwrap()),
+            [1]
+        );
+        assert_eq!(
+            ids(GameNotesRepository::search(&db, "route_a", None)
+                .await
+                .unwrap()),
+            [1]
+        );
+        assert!(
+            GameNotesRepository::search(&db, "route%", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            ids(GameNotesRepository::search(&db, "100%", None)
+                .await
+                .unwrap()),
+            [2]
+        );
+        assert!(
+            GameNotesRepository::search(&db, "CG", Some(1))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let updated = GameNotesRepository::update(&db, route.id, None, Some("改写".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "路线顺序");
+        assert_eq!(updated.content, "改写");
+        assert_eq!(GameNotesRepository::delete(&db, route.id).await.unwrap(), 1);
+        assert!(
+            GameNotesRepository::find_by_game(&db, 1)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! 游戏关联关系仓库。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::{GameListFilter, GamesRepository};
+use crate::entity::game_relations;
+use crate::entity::prelude::*;
+use sea_orm::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 游戏关联关系数据仓库
+pub struct GameRelationsRepository;
+
+/// 库中已拥有的关联作品
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedGame {
+    /// VNDB 关联类型，表示该作品相对于当前游戏的关系
+    pub relation: String,
+    pub official: bool,
+    pub game: FullGameData,
+}
+
+impl GameRelationsRepository {
+    /// 用新的关联列表替换游戏现有的关联，同一关联作品只保留第一条
+    pub async fn replace_for_game(
+        db: &DatabaseConnection,
+        game_id: i32,
+        relations: Vec<game_relations::Model>,
+    ) -> Result<usize, DbErr> {
+        let mut seen = HashSet::new();
+        let models: Vec<game_relations::ActiveModel> = relations
+            .into_iter()
+            .filter(|relation| {
+                !relation.related_vndb_id.is_empty()
+                    && seen.insert(relation.related_vndb_id.clone())
+            })
+            .map(|relation| game_relations::ActiveModel {
+                game_id: Set(game_id),
+                related_vndb_id: Set(relation.related_vndb_id),
+                relation: Set(relation.relation),
+                official: Set(relation.official),
+            })
+            .collect();
+        let count = models.len();
+
+        let transaction = db.begin().await?;
+        GameRelations::delete_many()
+            .filter(game_relations::Column::GameId.eq(game_id))
+            .exec(&transaction)
+            .await?;
+        if !models.is_empty() {
+            GameRelations::insert_many(models)
+                .exec(&transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(count)
+    }
+
+    /// 查询游戏在库中已拥有的关联作品，按关联类型与游戏 ID 排序
+    ///
+    /// 关联作品通过 VNDB 数据源的条目 ID 匹配，回收站中的游戏不返回。
+    pub async fn find_related_games(
+        db: &DatabaseConnection,
+        game_id: i32,
+        filter: GameListFilter,
+    ) -> Result<Vec<RelatedGame>, DbErr> {
+        let filter_condition: String = filter
+            .conditions("g")
+            .iter()
+            .map(|condition| format!(" AND {condition}"))
+            .collect();
+        let sql = format!(
+            r#"
+            SELECT r.relation, r.official, g.id
+            FROM game_relations AS r
+            JOIN game_sources AS s
+              ON s.source = 'vndb' AND s.external_id = r.related_vndb_id
+            JOIN games AS g ON g.id = s.game_id
+            WHERE r.game_id = ?1 AND g.id <> ?1 AND g.deleted_at IS NULL{filter_condition}
+            ORDER BY r.relation, g.id
+            "#
+        );
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                sql,
+                [game_id.into()],
+            ))
+            .await?;
+        let mut relations = Vec::with_capacity(rows.len());
+        for row in rows {
+            relations.push((
+                row.try_get::<String>("", "relation")?,
+                row.try_get::<bool>("", "official")?,
+                row.try_get::<i32>("", "id")?,
+            ));
+        }
+
+        let ids: Vec<i32> = relations.iter().map(|(_, _, id)| *id).collect();
+        let mut games: HashMap<i32, FullGameData> =
+            GamesRepository::find_full_games_in_order(db, &ids)
+                .await?
+                .into_iter()
+                .map(|game| (game.id, game))
+                .collect();
+        Ok(relations
+            .into_iter()
+            .filter_map(|(relation, official, id)| {
+                let game = games.remove(&id)?;
+                Some(RelatedGame {
+                    relation,
+                    official,
+                    game,
+                })
+            })
+            .collect())
+    }
+}
@@ -1,9 +1,9 @@
 use crate::entity::prelude::*;
-use crate::entity::{game_sessions, game_statistics};
-use chrono::{Local, LocalResult, NaiveTime, TimeZone};
+use crate::entity::{game_sessions, game_statistics, games};
+use chrono::{Local, LocalResult, NaiveDate, NaiveTime, TimeZone};
 use sea_orm::*;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 /// 每日统计数据结构
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -26,12 +26,137 @@ struct SessionStatisticsContribution {
     daily_stats: Vec<DailyStats>,
 }
 
+/// 从其他启动器导入的游玩会话
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedSession {
+    pub start_time: i32,
+    pub end_time: i32,
+    /// 游玩时长（分钟）
+    pub duration: i32,
+}
+
 #[derive(Debug, Clone, Serialize, FromQueryResult)]
 pub struct GameLastPlayed {
     pub game_id: i32,
     pub last_played: Option<i32>,
 }
 
+/// 游玩时长聚合粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaytimeGranularity {
+    #[serde(other)]
+    Day,
+    /// 以周一为一周的起始，分组键为当周周一的日期
+    Week,
+    Month,
+    Year,
+}
+
+impl PlaytimeGranularity {
+    /// 将 `YYYY-MM-DD` 日期表达式映射为分组键的 SQL 表达式
+    fn period_expression(self, date: &str) -> String {
+        match self {
+            PlaytimeGranularity::Day => date.to_string(),
+            PlaytimeGranularity::Week => format!(
+                "date({date}, '-' || ((CAST(strftime('%w', {date}) AS INTEGER) + 6) % 7) || ' days')"
+            ),
+            PlaytimeGranularity::Month => format!("substr({date}, 1, 7)"),
+            PlaytimeGranularity::Year => format!("substr({date}, 1, 4)"),
+        }
+    }
+}
+
+/// 单个时间段的游玩时长聚合
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromQueryResult)]
+pub struct PlaytimeBucket {
+    /// 分组键：日 `YYYY-MM-DD`、周（周一日期）、月 `YYYY-MM`、年 `YYYY`
+    pub period: String,
+    /// 游玩时长（分钟）
+    pub playtime: i64,
+    /// 该时间段内有游玩记录的游戏数
+    pub game_count: i64,
+}
+
+/// 单个游戏在一段时间内的游玩时长
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromQueryResult)]
+pub struct GamePlaytime {
+    pub game_id: i32,
+    /// 游玩时长（分钟）
+    pub playtime: i64,
+}
+
+/// 热力图中单日的游玩时长
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaytimeDay {
+    pub date: String,
+    /// 游玩时长（分钟），没有游玩记录时为 0
+    pub playtime: i64,
+}
+
+/// 连续游玩天数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Streak {
+    /// 截至今天的连续游玩天数，今天尚未游玩时从昨天起算
+    pub current: i32,
+    /// 历史最长连续游玩天数
+    pub longest: i32,
+    pub longest_start: Option<String>,
+    pub longest_end: Option<String>,
+}
+
+/// 单个游戏的连续游玩天数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GameStreak {
+    pub game_id: i32,
+    #[serde(flatten)]
+    pub streak: Streak,
+}
+
+/// 全库与各游戏的连续游玩天数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StreakSummary {
+    #[serde(flatten)]
+    pub overall: Streak,
+    /// 按历史最长连续天数降序
+    pub games: Vec<GameStreak>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct GamePlayDate {
+    game_id: i32,
+    date: String,
+}
+
+/// 根据有游玩记录的日期计算连续天数，`dates` 需升序且不重复
+fn calculate_streak(dates: &[NaiveDate], today: NaiveDate) -> Streak {
+    let mut streak = Streak::default();
+    let mut run_start = None;
+    let mut run_length = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for &date in dates {
+        if previous.and_then(|previous| previous.succ_opt()) == Some(date) {
+            run_length += 1;
+        } else {
+            run_start = Some(date);
+            run_length = 1;
+        }
+        if run_length > streak.longest {
+            streak.longest = run_length;
+            streak.longest_start = run_start.map(|start| start.format("%Y-%m-%d").to_string());
+            streak.longest_end = Some(date.format("%Y-%m-%d").to_string());
+        }
+        previous = Some(date);
+    }
+
+    let yesterday = today.pred_opt();
+    if previous.is_some_and(|last| last == today || Some(last) == yesterday) {
+        streak.current = run_length;
+    }
+    streak
+}
+
 fn custom_error(message: impl Into<String>) -> DbErr {
     DbErr::Custom(message.into())
 }
@@ -78,6 +203,38 @@ fn manual_session_end_time(
     Ok(end_time)
 }
 
+/// 按日期补录会话的开始时间：默认从当天零点开始，
+/// 若因此结束时间晚于当前时间（补录今天的会话），则改为以当前时间为结束时间倒推
+fn manual_session_start_on_date<Tz: TimeZone>(
+    timezone: &Tz,
+    date: NaiveDate,
+    duration: i32,
+    current_time: i32,
+) -> Result<i32, DbErr> {
+    if duration <= 0 {
+        return Err(custom_error("游玩时长必须大于零"));
+    }
+    let midnight = timezone
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .ok_or_else(|| custom_error(format!("无法解析本地日期: {date}")))?
+        .timestamp();
+    if midnight > i64::from(current_time) {
+        return Err(custom_error("补录日期不能晚于今天"));
+    }
+
+    let duration_seconds = i64::from(duration) * 60;
+    let start_time = if midnight + duration_seconds > i64::from(current_time) {
+        i64::from(current_time) - duration_seconds
+    } else {
+        midnight
+    };
+    i32::try_from(start_time)
+        .ok()
+        .filter(|start_time| *start_time > 0)
+        .ok_or_else(|| custom_error("游玩时长超出支持范围"))
+}
+
 fn next_midnight_timestamp<Tz: TimeZone>(
     timezone: &Tz,
     date: chrono::NaiveDate,
@@ -346,6 +503,8 @@ impl GameStatsRepository {
         end_time: i32,
         duration: i32,
         date: String,
+        exit_code: Option<i32>,
+        note: Option<String>,
     ) -> Result<game_sessions::Model, DbErr>
     where
         C: ConnectionTrait,
@@ -357,24 +516,49 @@ impl GameStatsRepository {
             end_time: Set(end_time),
             duration: Set(duration),
             date: Set(date),
+            exit_code: Set(exit_code),
+            note: Set(note),
         }
         .insert(db)
         .await
     }
 
     /// 在同一事务内写入会话并增量更新统计
+    ///
+    /// `exit_code` 为游戏进程退出码，手动补录或无法获取时为 None。
     pub async fn record_session_with_statistics(
         db: &DatabaseConnection,
         game_id: i32,
         start_time: i32,
         end_time: i32,
         duration: i32,
+        exit_code: Option<i32>,
+    ) -> Result<game_sessions::Model, DbErr> {
+        Self::record_session(db, game_id, start_time, end_time, duration, exit_code, None).await
+    }
+
+    async fn record_session(
+        db: &DatabaseConnection,
+        game_id: i32,
+        start_time: i32,
+        end_time: i32,
+        duration: i32,
+        exit_code: Option<i32>,
+        note: Option<String>,
     ) -> Result<game_sessions::Model, DbErr> {
         let date = local_date_from_timestamp(end_time)?;
         let transaction = db.begin().await?;
-        let session =
-            Self::insert_session(&transaction, game_id, start_time, end_time, duration, date)
-                .await?;
+        let session = Self::insert_session(
+            &transaction,
+            game_id,
+            start_time,
+            end_time,
+            duration,
+            date,
+            exit_code,
+            note,
+        )
+        .await?;
 
         let projection = match Self::get_projection(&transaction, game_id).await {
             Ok(Some(mut projection)) => {
@@ -392,34 +576,188 @@ impl GameStatsRepository {
         Ok(session)
     }
 
+    fn current_time() -> Result<i32, DbErr> {
+        i32::try_from(chrono::Utc::now().timestamp())
+            .map_err(|_| custom_error("当前时间超出数据库整数范围"))
+    }
+
     /// 根据开始时间和分钟数创建手动会话
     pub async fn create_manual_session(
         db: &DatabaseConnection,
         game_id: i32,
         start_time: i32,
         duration: i32,
+        note: Option<String>,
     ) -> Result<game_sessions::Model, DbErr> {
         if game_id <= 0 {
             return Err(custom_error("游戏 ID 必须大于零"));
         }
 
-        let current_time = i32::try_from(chrono::Utc::now().timestamp())
-            .map_err(|_| custom_error("当前时间超出数据库整数范围"))?;
-        let end_time = manual_session_end_time(start_time, duration, current_time)?;
+        let end_time = manual_session_end_time(start_time, duration, Self::current_time()?)?;
+        let note = note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty());
+
+        Self::record_session(db, game_id, start_time, end_time, duration, None, note).await
+    }
+
+    /// 按本地日期和分钟数补录会话，开始时间规则见 [`manual_session_start_on_date`]
+    pub async fn add_manual_session_on_date(
+        db: &DatabaseConnection,
+        game_id: i32,
+        date: NaiveDate,
+        duration: i32,
+        note: Option<String>,
+    ) -> Result<game_sessions::Model, DbErr> {
+        let start_time =
+            manual_session_start_on_date(&Local, date, duration, Self::current_time()?)?;
+        Self::create_manual_session(db, game_id, start_time, duration, note).await
+    }
+
+    /// 修改会话的开始时间、时长或备注，并在同一事务内从会话重建统计
+    ///
+    /// 修改开始时间或时长时按 `开始时间 + 时长` 重新计算结束时间；
+    /// `note` 为 `Some(None)` 时清除备注。
+    pub async fn update_session(
+        db: &DatabaseConnection,
+        session_id: i32,
+        start_time: Option<i32>,
+        duration: Option<i32>,
+        note: Option<Option<String>>,
+    ) -> Result<game_sessions::Model, DbErr> {
+        let transaction = db.begin().await?;
+        let session = GameSessions::find_by_id(session_id)
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("会话不存在: {session_id}")))?;
+        let game_id = session.game_id;
+        let mut active: game_sessions::ActiveModel = session.clone().into();
+
+        if start_time.is_some() || duration.is_some() {
+            let start_time = start_time.unwrap_or(session.start_time);
+            let duration = duration.unwrap_or(session.duration);
+            let end_time = manual_session_end_time(start_time, duration, Self::current_time()?)?;
+            active.start_time = Set(start_time);
+            active.end_time = Set(end_time);
+            active.duration = Set(duration);
+            active.date = Set(local_date_from_timestamp(end_time)?);
+        }
+        if let Some(note) = note {
+            active.note = Set(note
+                .map(|note| note.trim().to_string())
+                .filter(|note| !note.is_empty()));
+        }
 
-        Self::record_session_with_statistics(db, game_id, start_time, end_time, duration).await
+        let updated = active.update(&transaction).await?;
+        Self::rebuild_statistics_in(&transaction, game_id).await?;
+        transaction.commit().await?;
+        Ok(updated)
     }
 
-    /// 从事实会话重建指定游戏的统计投影
-    pub async fn rebuild_statistics(db: &DatabaseConnection, game_id: i32) -> Result<(), DbErr> {
+    /// 导入外部游玩会话并从会话重建统计
+    ///
+    /// 与已有会话开始时间相同的记录视为重复并跳过，返回实际写入的会话数量。
+    pub async fn import_sessions(
+        db: &DatabaseConnection,
+        game_id: i32,
+        sessions: &[ImportedSession],
+    ) -> Result<usize, DbErr> {
         if game_id <= 0 {
             return Err(custom_error("游戏 ID 必须大于零"));
         }
 
         let transaction = db.begin().await?;
-        let projection = Self::calculate_projection(&transaction, game_id).await?;
-        Self::upsert_projection(&transaction, game_id, projection).await?;
-        transaction.commit().await
+        let mut existing_starts = GameSessions::find()
+            .filter(game_sessions::Column::GameId.eq(game_id))
+            .all(&transaction)
+            .await?
+            .into_iter()
+            .map(|session| session.start_time)
+            .collect::<HashSet<_>>();
+
+        let mut inserted = 0;
+        for session in sessions {
+            if session.duration <= 0
+                || session.start_time <= 0
+                || session.end_time <= session.start_time
+            {
+                return Err(custom_error("导入的会话起止时间或时长无效"));
+            }
+            if !existing_starts.insert(session.start_time) {
+                continue;
+            }
+            Self::insert_session(
+                &transaction,
+                game_id,
+                session.start_time,
+                session.end_time,
+                session.duration,
+                local_date_from_timestamp(session.end_time)?,
+                None,
+                None,
+            )
+            .await?;
+            inserted += 1;
+        }
+
+        if inserted > 0 {
+            Self::rebuild_statistics_in(&transaction, game_id).await?;
+        }
+        transaction.commit().await?;
+        Ok(inserted)
+    }
+
+    /// 从事实会话重建统计投影，返回重建的游戏数量
+    ///
+    /// `game_id` 为空时重建所有有会话或统计记录的游戏。
+    pub async fn rebuild_statistics(
+        db: &DatabaseConnection,
+        game_id: Option<i32>,
+    ) -> Result<usize, DbErr> {
+        if let Some(game_id) = game_id {
+            if game_id <= 0 {
+                return Err(custom_error("游戏 ID 必须大于零"));
+            }
+
+            let transaction = db.begin().await?;
+            Self::rebuild_statistics_in(&transaction, game_id).await?;
+            transaction.commit().await?;
+            return Ok(1);
+        }
+
+        let transaction = db.begin().await?;
+        let mut game_ids: BTreeSet<i32> = GameSessions::find()
+            .select_only()
+            .column(game_sessions::Column::GameId)
+            .distinct()
+            .into_tuple::<i32>()
+            .all(&transaction)
+            .await?
+            .into_iter()
+            .collect();
+        game_ids.extend(
+            GameStatistics::find()
+                .select_only()
+                .column(game_statistics::Column::GameId)
+                .into_tuple::<i32>()
+                .all(&transaction)
+                .await?,
+        );
+
+        for game_id in &game_ids {
+            Self::rebuild_statistics_in(&transaction, *game_id).await?;
+        }
+        transaction.commit().await?;
+        Ok(game_ids.len())
+    }
+
+    /// 在调用方事务内从事实会话重建统计投影
+    pub(crate) async fn rebuild_statistics_in(
+        transaction: &DatabaseTransaction,
+        game_id: i32,
+    ) -> Result<(), DbErr> {
+        let projection = Self::calculate_projection(transaction, game_id).await?;
+        Self::upsert_projection(transaction, game_id, projection).await
     }
 
     /// 获取游戏会话历史
@@ -438,6 +776,21 @@ impl GameStatsRepository {
             .await
     }
 
+    /// 按开始时间升序获取全部会话，`game_id` 为空时返回全库会话
+    pub async fn get_all_sessions(
+        db: &DatabaseConnection,
+        game_id: Option<i32>,
+    ) -> Result<Vec<game_sessions::Model>, DbErr> {
+        let mut query = GameSessions::find();
+        if let Some(game_id) = game_id {
+            query = query.filter(game_sessions::Column::GameId.eq(game_id));
+        }
+        query
+            .order_by_asc(game_sessions::Column::StartTime)
+            .all(db)
+            .await
+    }
+
     /// 获取指定游戏范围内的全局最近会话
     pub async fn get_recent_sessions_for_all(
         db: &DatabaseConnection,
@@ -585,6 +938,190 @@ impl GameStatsRepository {
         GameStatistics::find().all(db).await
     }
 
+    /// 按日期范围聚合游玩时长
+    ///
+    /// 直接在 SQL 中展开 `daily_stats`，`start`、`end` 为包含边界的本地日期 `YYYY-MM-DD`，
+    /// `game_id` 为空时统计全库（不含回收站中的游戏）。结果按时间段升序，没有游玩记录的时间段不返回。
+    pub async fn get_playtime_by_range(
+        db: &DatabaseConnection,
+        game_id: Option<i32>,
+        start: &str,
+        end: &str,
+        granularity: PlaytimeGranularity,
+    ) -> Result<Vec<PlaytimeBucket>, DbErr> {
+        let parse_date = |value: &str| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| custom_error(format!("无效日期: {value}")))
+        };
+        if parse_date(start)? > parse_date(end)? {
+            return Err(custom_error("开始日期不能晚于结束日期"));
+        }
+
+        let date = "json_extract(day.value, '$.date')";
+        let mut sql = format!(
+            r#"
+            SELECT
+                {period} AS period,
+                SUM(json_extract(day.value, '$.playtime')) AS playtime,
+                COUNT(DISTINCT statistics.game_id) AS game_count
+            FROM game_statistics AS statistics
+            JOIN games ON games.id = statistics.game_id,
+            json_each(statistics.daily_stats) AS day
+            WHERE {date} BETWEEN ? AND ?
+            "#,
+            period = granularity.period_expression(date),
+        );
+        let mut values: Vec<Value> = vec![start.into(), end.into()];
+        match game_id {
+            Some(game_id) => {
+                sql.push_str(" AND statistics.game_id = ?");
+                values.push(game_id.into());
+            }
+            None => sql.push_str(" AND games.deleted_at IS NULL"),
+        }
+        sql.push_str(" GROUP BY period ORDER BY period");
+
+        PlaytimeBucket::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            sql,
+            values,
+        ))
+        .all(db)
+        .await
+    }
+
+    /// 按游戏聚合日期范围内的游玩时长，按时长降序
+    ///
+    /// `start`、`end` 为包含边界的本地日期 `YYYY-MM-DD`，不含回收站中的游戏。
+    pub async fn get_playtime_by_game(
+        db: &DatabaseConnection,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<GamePlaytime>, DbErr> {
+        GamePlaytime::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+            SELECT
+                statistics.game_id AS game_id,
+                SUM(json_extract(day.value, '$.playtime')) AS playtime
+            FROM game_statistics AS statistics
+            JOIN games ON games.id = statistics.game_id,
+            json_each(statistics.daily_stats) AS day
+            WHERE games.deleted_at IS NULL
+                AND json_extract(day.value, '$.date') BETWEEN ? AND ?
+            GROUP BY statistics.game_id
+            HAVING playtime > 0
+            ORDER BY playtime DESC, statistics.game_id
+            "#,
+            [start.into(), end.into()],
+        ))
+        .all(db)
+        .await
+    }
+
+    /// 获取会话日期（结束时间所在的本地日期）在范围内的全部会话，不含回收站中的游戏
+    pub async fn get_sessions_by_date_range(
+        db: &DatabaseConnection,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<game_sessions::Model>, DbErr> {
+        GameSessions::find()
+            .inner_join(Games)
+            .filter(games::Column::DeletedAt.is_null())
+            .filter(game_sessions::Column::Date.between(start, end))
+            .order_by_asc(game_sessions::Column::StartTime)
+            .all(db)
+            .await
+    }
+
+    /// 获取指定年份每一天的游玩时长，用于绘制热力图
+    ///
+    /// 返回该年从 1 月 1 日到 12 月 31 日的全部日期，`game_id` 为空时统计全库。
+    pub async fn get_playtime_heatmap(
+        db: &DatabaseConnection,
+        year: i32,
+        game_id: Option<i32>,
+    ) -> Result<Vec<PlaytimeDay>, DbErr> {
+        let first_day = NaiveDate::from_ymd_opt(year, 1, 1)
+            .ok_or_else(|| custom_error(format!("无效年份: {year}")))?;
+        let last_day = NaiveDate::from_ymd_opt(year, 12, 31)
+            .ok_or_else(|| custom_error(format!("无效年份: {year}")))?;
+        let playtime: BTreeMap<String, i64> = Self::get_playtime_by_range(
+            db,
+            game_id,
+            &first_day.format("%Y-%m-%d").to_string(),
+            &last_day.format("%Y-%m-%d").to_string(),
+            PlaytimeGranularity::Day,
+        )
+        .await?
+        .into_iter()
+        .map(|bucket| (bucket.period, bucket.playtime))
+        .collect();
+
+        Ok(first_day
+            .iter_days()
+            .take_while(|day| *day <= last_day)
+            .map(|day| {
+                let date = day.format("%Y-%m-%d").to_string();
+                PlaytimeDay {
+                    playtime: playtime.get(&date).copied().unwrap_or(0),
+                    date,
+                }
+            })
+            .collect())
+    }
+
+    /// 计算全库与各游戏的连续游玩天数
+    ///
+    /// 以 `daily_stats` 中游玩时长大于零的日期为准，`today` 为当前本地日期，不含回收站中的游戏。
+    pub async fn get_streaks(
+        db: &DatabaseConnection,
+        today: NaiveDate,
+    ) -> Result<StreakSummary, DbErr> {
+        let rows = GamePlayDate::find_by_statement(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            r#"
+            SELECT
+                statistics.game_id AS game_id,
+                json_extract(day.value, '$.date') AS date
+            FROM game_statistics AS statistics
+            JOIN games ON games.id = statistics.game_id,
+            json_each(statistics.daily_stats) AS day
+            WHERE games.deleted_at IS NULL
+                AND json_extract(day.value, '$.playtime') > 0
+            "#,
+        ))
+        .all(db)
+        .await?;
+
+        let mut all_dates = BTreeSet::new();
+        let mut game_dates: BTreeMap<i32, BTreeSet<NaiveDate>> = BTreeMap::new();
+        for row in rows {
+            let date = NaiveDate::parse_from_str(&row.date, "%Y-%m-%d")
+                .map_err(|_| custom_error(format!("每日统计包含无效日期: {}", row.date)))?;
+            all_dates.insert(date);
+            game_dates.entry(row.game_id).or_default().insert(date);
+        }
+
+        let overall = calculate_streak(&all_dates.into_iter().collect::<Vec<_>>(), today);
+        let mut games: Vec<GameStreak> = game_dates
+            .into_iter()
+            .map(|(game_id, dates)| GameStreak {
+                game_id,
+                streak: calculate_streak(&dates.into_iter().collect::<Vec<_>>(), today),
+            })
+            .collect();
+        games.sort_by(|a, b| {
+            b.streak
+                .longest
+                .cmp(&a.streak.longest)
+                .then(b.streak.current.cmp(&a.streak.current))
+                .then(a.game_id.cmp(&b.game_id))
+        });
+
+        Ok(StreakSummary { overall, games })
+    }
+
     /// 获取所有游戏的最近游玩时间，不包含 daily_stats 大字段。
     pub async fn get_all_last_played(
         db: &DatabaseConnection,
@@ -632,6 +1169,8 @@ mod tests {
             end_time,
             duration,
             date: "2026-01-01".to_string(),
+            exit_code: None,
+            note: None,
         }
     }
 
@@ -645,7 +1184,8 @@ mod tests {
         db.execute_unprepared(
             r#"CREATE TABLE games (
                 id INTEGER PRIMARY KEY,
-                id_type TEXT NOT NULL
+                id_type TEXT NOT NULL,
+                deleted_at INTEGER
             )"#,
         )
         .await
@@ -658,6 +1198,8 @@ mod tests {
                 end_time INTEGER NOT NULL,
                 duration INTEGER NOT NULL,
                 date TEXT NOT NULL,
+                exit_code INTEGER,
+                note TEXT,
                 FOREIGN KEY(game_id) REFERENCES games(id) ON DELETE CASCADE
             )"#,
         )
@@ -855,10 +1397,11 @@ mod tests {
         let start_time = timestamp(1, 10);
         let end_time = timestamp(1, 12);
 
-        let inserted =
-            GameStatsRepository::record_session_with_statistics(&db, 1, start_time, end_time, 90)
-                .await
-                .expect("会话和统计应同时写入");
+        let inserted = GameStatsRepository::record_session_with_statistics(
+            &db, 1, start_time, end_time, 90, None,
+        )
+        .await
+        .expect("会话和统计应同时写入");
         let statistics = GameStatistics::find_by_id(1)
             .one(&db)
             .await
@@ -903,6 +1446,7 @@ mod tests {
             timestamp(1, 10),
             timestamp(1, 12),
             90,
+            None,
         )
         .await;
 
@@ -920,9 +1464,16 @@ mod tests {
     async fn rebuild_statistics_repairs_existing_projection() {
         let db = test_database().await;
         let end_time = timestamp(1, 12);
-        GameStatsRepository::record_session_with_statistics(&db, 1, timestamp(1, 10), end_time, 90)
-            .await
-            .expect("会话写入应成功");
+        GameStatsRepository::record_session_with_statistics(
+            &db,
+            1,
+            timestamp(1, 10),
+            end_time,
+            90,
+            Some(1),
+        )
+        .await
+        .expect("会话写入应成功");
         db.execute(Statement::from_string(
             DatabaseBackend::Sqlite,
             "UPDATE game_statistics SET total_time = 1, session_count = 99",
@@ -930,7 +1481,7 @@ mod tests {
         .await
         .expect("应写入错误投影");
 
-        GameStatsRepository::rebuild_statistics(&db, 1)
+        GameStatsRepository::rebuild_statistics(&db, Some(1))
             .await
             .expect("单游戏统计应重建成功");
         let statistics = GameStatistics::find_by_id(1)
@@ -943,4 +1494,214 @@ mod tests {
         assert_eq!(statistics.session_count, Some(1));
         assert_eq!(statistics.last_played, Some(end_time));
     }
+
+    #[tokio::test]
+    async fn rebuild_statistics_without_game_id_repairs_whole_library() {
+        let db = test_database().await;
+        GameStatsRepository::record_session_with_statistics(
+            &db,
+            1,
+            timestamp(1, 10),
+            timestamp(1, 12),
+            90,
+            Some(1),
+        )
+        .await
+        .expect("会话写入应成功");
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "UPDATE game_statistics SET total_time = 1, daily_stats = '[]'",
+        ))
+        .await
+        .expect("应写入错误投影");
+
+        assert_eq!(
+            GameStatsRepository::rebuild_statistics(&db, None)
+                .await
+                .expect("全库统计应重建成功"),
+            1
+        );
+        let statistics = GameStatistics::find_by_id(1)
+            .one(&db)
+            .await
+            .expect("统计查询应成功")
+            .expect("统计记录应存在");
+
+        assert_eq!(statistics.total_time, Some(90));
+        assert_ne!(statistics.daily_stats.as_deref(), Some("[]"));
+    }
+
+    #[tokio::test]
+    async fn aggregates_playtime_by_granularity() {
+        let db = test_database().await;
+        db.execute_unprepared(
+            r#"INSERT INTO games (id, id_type) VALUES (2, 'custom');
+               INSERT INTO games (id, id_type, deleted_at) VALUES (3, 'custom', 1);
+               INSERT INTO game_statistics (game_id, total_time, session_count, daily_stats)
+               VALUES
+                   (1, 100, 3, '[{"date":"2026-02-02","playtime":30},{"date":"2026-01-31","playtime":20},{"date":"2026-01-05","playtime":50}]'),
+                   (2, 40, 1, '[{"date":"2026-02-01","playtime":40}]'),
+                   (3, 90, 1, '[{"date":"2026-02-01","playtime":90}]')"#,
+        )
+        .await
+        .expect("应写入测试统计");
+
+        let bucket = |period: &str, playtime, game_count| PlaytimeBucket {
+            period: period.to_string(),
+            playtime,
+            game_count,
+        };
+        let monthly = GameStatsRepository::get_playtime_by_range(
+            &db,
+            None,
+            "2026-01-01",
+            "2026-12-31",
+            PlaytimeGranularity::Month,
+        )
+        .await
+        .expect("按月聚合应成功");
+        assert_eq!(
+            monthly,
+            vec![bucket("2026-01", 70, 1), bucket("2026-02", 70, 2)]
+        );
+
+        let weekly = GameStatsRepository::get_playtime_by_range(
+            &db,
+            Some(1),
+            "2026-01-06",
+            "2026-02-28",
+            PlaytimeGranularity::Week,
+        )
+        .await
+        .expect("按周聚合应成功");
+        assert_eq!(
+            weekly,
+            vec![bucket("2026-01-26", 20, 1), bucket("2026-02-02", 30, 1)]
+        );
+
+        assert!(
+            GameStatsRepository::get_playtime_by_range(
+                &db,
+                None,
+                "2026-02-01",
+                "2026-01-01",
+                PlaytimeGranularity::Day,
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn heatmap_covers_every_day_of_year() {
+        let db = test_database().await;
+        db.execute_unprepared(
+            r#"INSERT INTO game_statistics (game_id, total_time, session_count, daily_stats)
+               VALUES (1, 75, 2, '[{"date":"2024-12-31","playtime":45},{"date":"2024-02-29","playtime":30},{"date":"2023-12-31","playtime":10}]')"#,
+        )
+        .await
+        .expect("应写入测试统计");
+
+        let heatmap = GameStatsRepository::get_playtime_heatmap(&db, 2024, None)
+            .await
+            .expect("热力图查询应成功");
+        assert_eq!(heatmap.len(), 366);
+        assert_eq!(heatmap[0].date, "2024-01-01");
+        assert_eq!(heatmap[59].date, "2024-02-29");
+        assert_eq!(heatmap[59].playtime, 30);
+        assert_eq!(heatmap[365].playtime, 45);
+        assert_eq!(heatmap.iter().map(|day| day.playtime).sum::<i64>(), 75);
+    }
+
+    #[test]
+    fn streak_counts_consecutive_days() {
+        let date = |day| NaiveDate::from_ymd_opt(2026, 3, day).expect("测试日期应有效");
+        let dates = [date(1), date(2), date(3), date(5), date(9), date(10)];
+
+        let streak = calculate_streak(&dates, date(11));
+        assert_eq!(streak.current, 2);
+        assert_eq!(streak.longest, 3);
+        assert_eq!(streak.longest_start.as_deref(), Some("2026-03-01"));
+        assert_eq!(streak.longest_end.as_deref(), Some("2026-03-03"));
+
+        assert_eq!(calculate_streak(&dates, date(10)).current, 2);
+        assert_eq!(calculate_streak(&dates, date(12)).current, 0);
+        assert_eq!(calculate_streak(&[], date(12)), Streak::default());
+    }
+
+    #[tokio::test]
+    async fn streaks_merge_days_across_games() {
+        let db = test_database().await;
+        db.execute_unprepared(
+            r#"INSERT INTO games (id, id_type) VALUES (2, 'custom');
+               INSERT INTO game_statistics (game_id, total_time, session_count, daily_stats)
+               VALUES
+                   (1, 60, 2, '[{"date":"2026-03-03","playtime":30},{"date":"2026-03-01","playtime":30}]'),
+                   (2, 40, 2, '[{"date":"2026-03-02","playtime":20},{"date":"2026-03-01","playtime":20}]')"#,
+        )
+        .await
+        .expect("应写入测试统计");
+
+        let today = NaiveDate::from_ymd_opt(2026, 3, 3).expect("测试日期应有效");
+        let summary = GameStatsRepository::get_streaks(&db, today)
+            .await
+            .expect("连续天数统计应成功");
+        assert_eq!(summary.overall.current, 3);
+        assert_eq!(summary.overall.longest, 3);
+        assert_eq!(summary.games[0].game_id, 2);
+        assert_eq!(summary.games[0].streak.longest, 2);
+        assert_eq!(summary.games[0].streak.current, 2);
+        assert_eq!(summary.games[1].streak.current, 1);
+    }
+
+    #[test]
+    fn manual_session_on_date_starts_at_midnight_or_ends_now() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 2).expect("测试日期应有效");
+        assert_eq!(
+            manual_session_start_on_date(&timezone(), date, 90, timestamp(5, 0)).unwrap(),
+            timestamp(2, 0)
+        );
+        assert_eq!(
+            manual_session_start_on_date(&timezone(), date, 120, timestamp(2, 1)).unwrap(),
+            timestamp(1, 23)
+        );
+        assert!(manual_session_start_on_date(&timezone(), date, 30, timestamp(1, 12)).is_err());
+        assert!(manual_session_start_on_date(&timezone(), date, 0, timestamp(5, 0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn updating_session_rebuilds_statistics() {
+        let db = test_database().await;
+        let session = GameStatsRepository::create_manual_session(
+            &db,
+            1,
+            timestamp(1, 10),
+            60,
+            Some(" 旧存档 ".to_string()),
+        )
+        .await
+        .expect("手动会话应写入成功");
+        assert_eq!(session.note.as_deref(), Some("旧存档"));
+
+        let updated =
+            GameStatsRepository::update_session(&db, session.session_id, None, Some(150), None)
+                .await
+                .expect("会话应修改成功");
+        assert_eq!(updated.end_time, timestamp(1, 10) + 150 * 60);
+        assert_eq!(updated.note.as_deref(), Some("旧存档"));
+
+        let statistics = GameStatistics::find_by_id(1)
+            .one(&db)
+            .await
+            .expect("统计查询应成功")
+            .expect("统计记录应存在");
+        assert_eq!(statistics.total_time, Some(150));
+        assert_eq!(statistics.last_played, Some(updated.end_time));
+
+        let cleared =
+            GameStatsRepository::update_session(&db, session.session_id, None, None, Some(None))
+                .await
+                .expect("备注应清除成功");
+        assert_eq!(cleared.note, None);
+    }
 }
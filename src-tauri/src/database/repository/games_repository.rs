@@ -4,7 +4,9 @@ use crate::database::dto::{
     BatchOperationError, BatchOperationResult, FullGameData, GameSourceData, InsertGameData,
     UpdateGameData, UpsertGameSourceData,
 };
+use crate::database::repository::game_stats_repository::GameStatsRepository;
 use crate::entity::prelude::*;
+use crate::entity::save_options::SavePaths;
 use crate::entity::{game_sources, game_statistics, games, savedata};
 use sea_orm::sea_query::{Expr, OnConflict};
 use sea_orm::*;
@@ -14,23 +16,54 @@ use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::path::{Component, Path};
 
+/// 批量设置存档目录时支持的占位符
+const SAVEPATH_PLACEHOLDERS: [&str; 3] = ["{localpath}", "{name}", "{id}"];
+
+/// 按游戏渲染存档目录模板，模板引用的值缺失时返回 None
+fn render_savepath_template(template: &str, game: &FullGameData) -> Option<String> {
+    let mut rendered = template.replace("{id}", &game.id.to_string());
+    if rendered.contains("{localpath}") {
+        rendered = rendered.replace("{localpath}", game.localpath.as_deref()?);
+    }
+    if rendered.contains("{name}") {
+        rendered = rendered.replace("{name}", &game.display_name()?);
+    }
+    Some(rendered)
+}
+
+/// 逐项渲染存档目录模板，任一项无法渲染时返回 None
+fn render_savepath_templates(templates: &SavePaths, game: &FullGameData) -> Option<SavePaths> {
+    templates
+        .0
+        .iter()
+        .map(|template| render_savepath_template(template, game))
+        .collect::<Option<Vec<_>>>()
+        .map(SavePaths)
+}
+
 /// 游戏数据排序选项
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOption {
+    /// 未知的排序选项按加入时间排序，避免旧版前端传入的值导致整个列表加载失败
+    #[serde(other, alias = "added_time")]
     Addtime,
     Datetime,
     LastPlayed,
     BGMRank,
     VNDBRank,
+    #[serde(alias = "rating")]
     UserRatingRank,
     Namesort,
+    /// 按游戏目录占用空间
+    Size,
 }
 
 /// 排序方向
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
+    #[serde(other)]
     Asc,
     Desc,
 }
@@ -39,10 +72,123 @@ pub enum SortOrder {
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum GameType {
+    /// 未知的筛选类型视为全部游戏
+    #[serde(other)]
     All,
     Local,
     Online,
     IsCustom,
+    /// 已收藏的游戏
+    Favorite,
+}
+
+/// 游戏列表的附加筛选条件，默认不做额外筛选
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GameListFilter {
+    /// 排除隐藏的游戏
+    pub exclude_hidden: bool,
+    /// 排除 NSFW 作品（安全模式）
+    pub exclude_nsfw: bool,
+}
+
+impl GameListFilter {
+    /// 筛选条件的 SQL 片段，`table` 为 games 表在查询中的名称或别名
+    pub(crate) fn conditions(self, table: &str) -> Vec<String> {
+        let mut conditions = Vec::new();
+        if self.exclude_hidden {
+            conditions.push(format!("{table}.hidden = 0"));
+        }
+        if self.exclude_nsfw {
+            // 与 FullGameData::is_nsfw 一致：自定义标记优先，否则任一数据源标记即视为 NSFW
+            conditions.push(format!(
+                "COALESCE(json_extract({table}.custom_data, '$.nsfw'), EXISTS(\
+                 SELECT 1 FROM game_sources WHERE game_sources.game_id = {table}.id \
+                 AND json_extract(game_sources.data, '$.nsfw') = 1)) = 0"
+            ));
+        }
+        conditions
+    }
+}
+
+/// 路径巡检所需的游戏路径与缺失标记
+#[derive(Debug, Clone)]
+pub struct GamePathState {
+    pub id: i32,
+    pub localpath: Option<String>,
+    pub savepath: Option<SavePaths>,
+    pub missing: bool,
+}
+
+/// 批量路径替换中单个游戏的路径变化，未变化的字段新值为 None
+#[derive(Debug, Clone, Serialize)]
+pub struct GamePathChange {
+    pub game_id: i32,
+    pub old_localpath: Option<String>,
+    pub new_localpath: Option<String>,
+    pub old_savepath: Option<SavePaths>,
+    pub new_savepath: Option<SavePaths>,
+}
+
+/// 游玩状态回写在线服务的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    /// 本地状态已变更，等待回写
+    Pending,
+    Synced,
+    /// 回写失败，下次批量同步时重试
+    Failed,
+}
+
+impl SyncStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncStatus::Pending => "pending",
+            SyncStatus::Synced => "synced",
+            SyncStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 支持回写游玩状态的在线服务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncService {
+    Bgm,
+    Vndb,
+}
+
+impl SyncService {
+    /// 对应 `game_sources.source`
+    pub fn source(self) -> &'static str {
+        match self {
+            SyncService::Bgm => "bgm",
+            SyncService::Vndb => "vndb",
+        }
+    }
+
+    fn status_column(self) -> games::Column {
+        match self {
+            SyncService::Bgm => games::Column::BgmSyncStatus,
+            SyncService::Vndb => games::Column::VndbSyncStatus,
+        }
+    }
+}
+
+/// 回写在线服务所需的游戏状态
+#[derive(Debug, Clone)]
+pub struct SyncTarget {
+    pub game_id: i32,
+    pub external_id: String,
+    pub clear: Option<i32>,
+    pub user_rating: Option<f64>,
+}
+
+/// 游玩状态、个人评分与短评各自的修改时间（Unix 时间戳），为空表示从未修改
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncFieldTimes {
+    pub clear: Option<i32>,
+    pub user_rating: Option<i32>,
+    pub user_review: Option<i32>,
 }
 
 pub struct GamesRepository;
@@ -59,11 +205,26 @@ impl GamesRepository {
             g.localpath,
             g.executable,
             g.savepath,
+            g.save_registry_keys,
             g.autosave,
             g.maxbackups,
             g.clear,
             g.le_launch,
+            g.le_profile,
             g.magpie,
+            g.launch_args,
+            g.launch_env,
+            g.launch_hooks,
+            g.wine_config,
+            g.engine,
+            g.missing,
+            g.folder_size,
+            g.bgm_sync_status,
+            g.vndb_sync_status,
+            g.deleted_at,
+            g.archive_path,
+            g.favorite,
+            g.hidden,
             g.custom_data,
             g.created_at,
             g.updated_at,
@@ -297,15 +458,33 @@ impl GamesRepository {
             localpath: Set(game.localpath.clone()),
             executable: Set(game.executable.clone()),
             savepath: Set(game.savepath.clone()),
+            save_registry_keys: Set(game.save_registry_keys.clone()),
             autosave: NotSet,
             maxbackups: NotSet,
             clear: Set(Some(game.clear.unwrap_or(Self::DEFAULT_PLAY_STATUS))),
             le_launch: NotSet,
+            le_profile: Set(game.le_profile.clone()),
             magpie: NotSet,
+            launch_args: Set(game.launch_args.clone()),
+            launch_env: Set(game.launch_env.clone()),
+            launch_hooks: Set(game.launch_hooks.clone()),
+            wine_config: Set(game.wine_config.clone()),
+            engine: Set(game.engine.clone()),
+            missing: NotSet,
+            folder_size: NotSet,
+            bgm_sync_status: NotSet,
+            vndb_sync_status: NotSet,
+            deleted_at: NotSet,
+            archive_path: NotSet,
+            favorite: NotSet,
+            hidden: NotSet,
             custom_data: Set(game.custom_data.clone()),
             user_rating: NotSet,
             created_at: Set(Some(now)),
             updated_at: Set(Some(now)),
+            clear_updated_at: NotSet,
+            user_rating_updated_at: NotSet,
+            user_review_updated_at: NotSet,
         }
     }
 
@@ -321,12 +500,43 @@ impl GamesRepository {
             localpath: updates.localpath.clone().map_or(NotSet, Set),
             executable: updates.executable.clone().map_or(NotSet, Set),
             savepath: updates.savepath.clone().map_or(NotSet, Set),
+            save_registry_keys: updates.save_registry_keys.clone().map_or(NotSet, Set),
             autosave: updates.autosave.map_or(NotSet, Set),
             maxbackups: updates.maxbackups.map_or(NotSet, Set),
             clear: updates.clear.map_or(NotSet, Set),
             le_launch: updates.le_launch.map_or(NotSet, Set),
+            le_profile: updates.le_profile.clone().map_or(NotSet, Set),
             magpie: updates.magpie.map_or(NotSet, Set),
+            launch_args: updates.launch_args.clone().map_or(NotSet, Set),
+            launch_env: updates.launch_env.clone().map_or(NotSet, Set),
+            launch_hooks: updates.launch_hooks.clone().map_or(NotSet, Set),
+            wine_config: updates.wine_config.clone().map_or(NotSet, Set),
+            engine: updates.engine.clone().map_or(NotSet, Set),
+            // 修改路径后缺失标记与占用空间缓存失效，由下次巡检/统计重新计算
+            missing: if updates.localpath.is_some() {
+                Set(None)
+            } else {
+                NotSet
+            },
+            folder_size: if updates.localpath.is_some() {
+                Set(None)
+            } else {
+                NotSet
+            },
+            // 游玩状态或评分变更后进入回写队列，未绑定对应数据源的游戏不会被同步
+            bgm_sync_status: if updates.clear.is_some() {
+                Set(Some(SyncStatus::Pending.as_str().to_string()))
+            } else {
+                NotSet
+            },
+            vndb_sync_status: if updates.clear.is_some() || updates.custom_data.is_some() {
+                Set(Some(SyncStatus::Pending.as_str().to_string()))
+            } else {
+                NotSet
+            },
             custom_data: updates.custom_data.clone().map_or(NotSet, Set),
+            favorite: updates.favorite.map_or(NotSet, Set),
+            hidden: updates.hidden.map_or(NotSet, Set),
             user_rating: NotSet,
             updated_at: Set(Some(now)),
             ..Default::default()
@@ -387,7 +597,8 @@ impl GamesRepository {
         Ok(())
     }
 
-    async fn insert_aggregate<C>(
+    /// 在调用方提供的连接或事务内插入游戏聚合，调用方负责先执行 `cleaned()`
+    pub(crate) async fn insert_aggregate<C>(
         db: &C,
         mut game: InsertGameData,
         now: i32,
@@ -426,6 +637,7 @@ impl GamesRepository {
         Ok(result)
     }
 
+    /// 在一个事务内批量插入游戏，每条使用独立的保存点，失败的条目记入 `errors`
     pub async fn insert_batch(
         db: &DatabaseConnection,
         games: Vec<InsertGameData>,
@@ -488,7 +700,97 @@ impl GamesRepository {
         }
     }
 
-    async fn update_aggregate<C>(
+    /// 游玩状态、评分或短评的值实际变化时记录各自的修改时间
+    async fn stamp_sync_fields<C>(
+        db: &C,
+        game_id: i32,
+        updates: &UpdateGameData,
+        now: i32,
+        model: &mut games::ActiveModel,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if updates.clear.is_none() && updates.custom_data.is_none() {
+            return Ok(());
+        }
+        let current = Games::find_by_id(game_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("game {game_id} not found")))?;
+
+        if updates.clear.is_some_and(|clear| clear != current.clear) {
+            model.clear_updated_at = Set(Some(now));
+        }
+        if let Some(custom_data) = &updates.custom_data {
+            let old = current.custom_data.as_ref();
+            let new = custom_data.as_ref();
+            if new.and_then(|data| data.user_rating) != old.and_then(|data| data.user_rating) {
+                model.user_rating_updated_at = Set(Some(now));
+            }
+            if new.and_then(|data| data.user_review.as_deref())
+                != old.and_then(|data| data.user_review.as_deref())
+            {
+                model.user_review_updated_at = Set(Some(now));
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取各游戏游玩状态、评分与短评的修改时间
+    pub async fn get_sync_field_times(
+        db: &DatabaseConnection,
+    ) -> Result<HashMap<i32, SyncFieldTimes>, DbErr> {
+        let rows = Games::find()
+            .select_only()
+            .column(games::Column::Id)
+            .column(games::Column::ClearUpdatedAt)
+            .column(games::Column::UserRatingUpdatedAt)
+            .column(games::Column::UserReviewUpdatedAt)
+            .into_tuple::<(i32, Option<i32>, Option<i32>, Option<i32>)>()
+            .all(db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(game_id, clear, user_rating, user_review)| {
+                (
+                    game_id,
+                    SyncFieldTimes {
+                        clear,
+                        user_rating,
+                        user_review,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 覆盖写入字段修改时间，只写入有值的字段，不更新 updated_at
+    ///
+    /// 局域网同步采用对端的值后，用对端的修改时间替换本次更新记录的时间。
+    pub(crate) async fn set_sync_field_times<C>(
+        db: &C,
+        game_id: i32,
+        times: SyncFieldTimes,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let model = games::ActiveModel {
+            id: Set(game_id),
+            clear_updated_at: times.clear.map_or(NotSet, |time| Set(Some(time))),
+            user_rating_updated_at: times.user_rating.map_or(NotSet, |time| Set(Some(time))),
+            user_review_updated_at: times.user_review.map_or(NotSet, |time| Set(Some(time))),
+            ..Default::default()
+        };
+        if model.is_changed() {
+            model.update(db).await?;
+        }
+        Ok(())
+    }
+
+    /// 在调用方提供的连接或事务内更新游戏聚合，调用方负责先执行 `cleaned()`
+    pub(crate) async fn update_aggregate<C>(
         db: &C,
         game_id: i32,
         updates: UpdateGameData,
@@ -504,9 +806,9 @@ impl GamesRepository {
         let updates = Self::normalize_update_date(db, game_id, updates).await?;
         let updates = Self::normalize_update_path_state(db, game_id, updates).await?;
 
-        Self::build_update_active_model(game_id, &updates, now)
-            .update(db)
-            .await?;
+        let mut model = Self::build_update_active_model(game_id, &updates, now);
+        Self::stamp_sync_fields(db, game_id, &updates, now, &mut model).await?;
+        model.update(db).await?;
         Self::remove_sources(
             db,
             game_id,
@@ -563,6 +865,46 @@ impl GamesRepository {
         Ok(updated_games)
     }
 
+    /// 在一个事务内对多个游戏应用同一份更新
+    ///
+    /// `savepath` 可使用 `{localpath}`、`{name}`、`{id}` 占位符，按每个游戏分别渲染；
+    /// 游戏缺少占位符对应的值时保持其存档目录不变。任一游戏更新失败时整体回滚。
+    pub async fn bulk_update(
+        db: &DatabaseConnection,
+        ids: &[i32],
+        patch: UpdateGameData,
+    ) -> Result<Vec<FullGameData>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let transaction = db.begin().await?;
+        let now = chrono::Utc::now().timestamp() as i32;
+        let savepath_template = patch.savepath.clone().flatten().filter(|savepath| {
+            savepath
+                .0
+                .iter()
+                .any(|path| SAVEPATH_PLACEHOLDERS.iter().any(|key| path.contains(key)))
+        });
+        let mut updated_games = Vec::with_capacity(ids.len());
+        let mut seen = HashSet::new();
+
+        for game_id in ids.iter().copied().filter(|id| seen.insert(*id)) {
+            let mut update = patch.clone();
+            if let Some(template) = &savepath_template {
+                let current = Self::find_full_by_id(&transaction, game_id)
+                    .await?
+                    .ok_or_else(|| DbErr::RecordNotFound(format!("game {} not found", game_id)))?;
+                update.savepath = render_savepath_templates(template, &current).map(Some);
+            }
+            updated_games
+                .push(Self::update_aggregate(&transaction, game_id, update.cleaned(), now).await?);
+        }
+
+        transaction.commit().await?;
+        Ok(updated_games)
+    }
+
     async fn find_full_by_id<C>(db: &C, id: i32) -> Result<Option<FullGameData>, DbErr>
     where
         C: ConnectionTrait,
@@ -581,6 +923,7 @@ impl GamesRepository {
         Self::find_full_by_id(db, id).await
     }
 
+    /// 查询游戏库中的全部游戏（含隐藏的游戏，不含回收站中的游戏）
     pub async fn find_all(
         db: &DatabaseConnection,
         game_type: GameType,
@@ -588,7 +931,21 @@ impl GamesRepository {
         sort_order: SortOrder,
         language: Option<String>,
     ) -> Result<Vec<FullGameData>, DbErr> {
-        let ids = Self::find_ids(db, game_type, sort_option, sort_order, language.clone()).await?;
+        let filter = GameListFilter::default();
+        Self::find_listed(db, game_type, sort_option, sort_order, language, filter).await
+    }
+
+    /// 按附加筛选条件查询游戏列表
+    pub async fn find_listed(
+        db: &DatabaseConnection,
+        game_type: GameType,
+        sort_option: SortOption,
+        sort_order: SortOrder,
+        language: Option<String>,
+        filter: GameListFilter,
+    ) -> Result<Vec<FullGameData>, DbErr> {
+        let ids =
+            Self::find_listed_ids(db, game_type, sort_option, sort_order, language, filter).await?;
         Self::find_full_games_in_order(db, &ids).await
     }
 
@@ -598,18 +955,95 @@ impl GamesRepository {
         sort_option: SortOption,
         sort_order: SortOrder,
         language: Option<String>,
+    ) -> Result<Vec<i32>, DbErr> {
+        let filter = GameListFilter::default();
+        Self::find_listed_ids(db, game_type, sort_option, sort_order, language, filter).await
+    }
+
+    /// 按附加筛选条件查询排序后的游戏 ID
+    pub async fn find_listed_ids(
+        db: &DatabaseConnection,
+        game_type: GameType,
+        sort_option: SortOption,
+        sort_order: SortOrder,
+        language: Option<String>,
+        filter: GameListFilter,
     ) -> Result<Vec<i32>, DbErr> {
         // 名称排序：应用层排序，名称来自 JSON 列
         if matches!(sort_option, SortOption::Namesort) {
-            return Self::find_name_sorted_ids(db, game_type, sort_order, language).await;
+            return Self::find_name_sorted_ids(db, game_type, sort_order, language, filter).await;
         }
 
-        Self::find_ids_sql(db, game_type, sort_option, sort_order).await
+        Self::find_ids_sql(db, game_type, sort_option, sort_order, filter).await
+    }
+
+    /// 按关键词搜索游戏（不区分大小写），最近添加的在前
+    ///
+    /// 同时匹配自定义名称、别名，以及各数据源的原名、中文名与别名。
+    pub async fn search(
+        db: &DatabaseConnection,
+        keyword: &str,
+        filter: GameListFilter,
+    ) -> Result<Vec<FullGameData>, DbErr> {
+        let pattern = format!(
+            "%{}%",
+            keyword
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let filter_condition: String = filter
+            .conditions("g")
+            .iter()
+            .map(|condition| format!(" AND {condition}"))
+            .collect();
+        let sql = format!(
+            r#"
+            SELECT g.id
+            FROM games AS g
+            WHERE g.deleted_at IS NULL{filter_condition}
+              AND (
+                json_extract(g.custom_data, '$.name') LIKE ?1 ESCAPE '\'
+                OR EXISTS(
+                    SELECT 1 FROM json_each(g.custom_data, '$.aliases')
+                    WHERE value LIKE ?1 ESCAPE '\'
+                )
+                OR EXISTS(
+                    SELECT 1 FROM game_sources AS s
+                    WHERE s.game_id = g.id
+                      AND (
+                        json_extract(s.data, '$.name') LIKE ?1 ESCAPE '\'
+                        OR json_extract(s.data, '$.name_cn') LIKE ?1 ESCAPE '\'
+                        OR EXISTS(
+                            SELECT 1 FROM json_each(s.data, '$.aliases')
+                            WHERE value LIKE ?1 ESCAPE '\'
+                        )
+                      )
+                )
+              )
+            ORDER BY g.created_at DESC, g.id DESC
+            "#
+        );
+
+        let ids = db
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                sql,
+                [pattern.into()],
+            ))
+            .await?
+            .into_iter()
+            .map(|row| row.try_get::<i32>("", "id"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::find_full_games_in_order(db, &ids).await
     }
 
     // ==================== 查询操作 ====================
 
-    async fn find_full_games_in_order<C>(db: &C, ids: &[i32]) -> Result<Vec<FullGameData>, DbErr>
+    pub(crate) async fn find_full_games_in_order<C>(
+        db: &C,
+        ids: &[i32],
+    ) -> Result<Vec<FullGameData>, DbErr>
     where
         C: ConnectionTrait,
     {
@@ -635,14 +1069,21 @@ impl GamesRepository {
         Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
     }
 
-    fn full_game_from_row(row: QueryResult) -> Result<FullGameData, DbErr> {
-        let custom_data = row
-            .try_get::<Option<String>>("", "custom_data")?
+    /// 读取以 JSON 文本存储的可空列
+    fn parse_json_column<T: serde::de::DeserializeOwned>(
+        row: &QueryResult,
+        column: &str,
+    ) -> Result<Option<T>, DbErr> {
+        row.try_get::<Option<String>>("", column)?
             .map(|data| {
                 serde_json::from_str(&data)
-                    .map_err(|error| DbErr::Custom(format!("custom_data 解析失败: {}", error)))
+                    .map_err(|error| DbErr::Custom(format!("{} 解析失败: {}", column, error)))
             })
-            .transpose()?;
+            .transpose()
+    }
+
+    fn full_game_from_row(row: QueryResult) -> Result<FullGameData, DbErr> {
+        let custom_data = Self::parse_json_column(&row, "custom_data")?;
         let sources_json: String = row.try_get("", "sources_json")?;
         let sources = serde_json::from_str::<Vec<GameSourceData>>(&sources_json)
             .map_err(|error| DbErr::Custom(format!("sources 聚合结果解析失败: {}", error)))?;
@@ -653,12 +1094,27 @@ impl GamesRepository {
             date: row.try_get("", "date")?,
             localpath: row.try_get("", "localpath")?,
             executable: row.try_get("", "executable")?,
-            savepath: row.try_get("", "savepath")?,
+            savepath: Self::parse_json_column(&row, "savepath")?,
+            save_registry_keys: Self::parse_json_column(&row, "save_registry_keys")?,
             autosave: row.try_get("", "autosave")?,
             maxbackups: row.try_get("", "maxbackups")?,
             clear: row.try_get("", "clear")?,
             le_launch: row.try_get("", "le_launch")?,
+            le_profile: row.try_get("", "le_profile")?,
             magpie: row.try_get("", "magpie")?,
+            launch_args: Self::parse_json_column(&row, "launch_args")?,
+            launch_env: Self::parse_json_column(&row, "launch_env")?,
+            launch_hooks: Self::parse_json_column(&row, "launch_hooks")?,
+            wine_config: Self::parse_json_column(&row, "wine_config")?,
+            engine: row.try_get("", "engine")?,
+            missing: row.try_get("", "missing")?,
+            folder_size: row.try_get("", "folder_size")?,
+            bgm_sync_status: row.try_get("", "bgm_sync_status")?,
+            vndb_sync_status: row.try_get("", "vndb_sync_status")?,
+            deleted_at: row.try_get("", "deleted_at")?,
+            archive_path: row.try_get("", "archive_path")?,
+            favorite: row.try_get("", "favorite")?,
+            hidden: row.try_get("", "hidden")?,
             custom_data,
             sources,
             created_at: row.try_get("", "created_at")?,
@@ -666,10 +1122,63 @@ impl GamesRepository {
         })
     }
 
+    /// 永久删除游戏，关联数据随外键级联删除
     pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<DeleteResult, DbErr> {
         Games::delete_by_id(id).exec(db).await
     }
 
+    /// 将游戏移入回收站，返回实际移入的数量（已在回收站中的不计）
+    pub async fn soft_delete_many(db: &DatabaseConnection, ids: Vec<i32>) -> Result<u64, DbErr> {
+        let now = chrono::Utc::now().timestamp() as i32;
+        let result = Games::update_many()
+            .col_expr(games::Column::DeletedAt, Expr::value(now))
+            .filter(games::Column::Id.is_in(ids))
+            .filter(games::Column::DeletedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 从回收站恢复游戏
+    pub async fn restore(db: &DatabaseConnection, id: i32) -> Result<u64, DbErr> {
+        let result = Games::update_many()
+            .col_expr(games::Column::DeletedAt, Expr::value(Option::<i32>::None))
+            .filter(games::Column::Id.eq(id))
+            .filter(games::Column::DeletedAt.is_not_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 回收站中的游戏，最近删除的在前
+    pub async fn find_deleted(db: &DatabaseConnection) -> Result<Vec<FullGameData>, DbErr> {
+        let ids: Vec<i32> = Games::find()
+            .select_only()
+            .column(games::Column::Id)
+            .filter(games::Column::DeletedAt.is_not_null())
+            .order_by_desc(games::Column::DeletedAt)
+            .order_by_desc(games::Column::Id)
+            .into_tuple()
+            .all(db)
+            .await?;
+        Self::find_full_games_in_order(db, &ids).await
+    }
+
+    /// 永久删除回收站中的全部游戏，返回被删除的游戏 ID
+    pub async fn purge_deleted(db: &DatabaseConnection) -> Result<Vec<i32>, DbErr> {
+        let ids: Vec<i32> = Games::find()
+            .select_only()
+            .column(games::Column::Id)
+            .filter(games::Column::DeletedAt.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await?;
+        if !ids.is_empty() {
+            Self::delete_many(db, ids.clone()).await?;
+        }
+        Ok(ids)
+    }
+
     pub async fn delete_many(
         db: &DatabaseConnection,
         ids: Vec<i32>,
@@ -680,8 +1189,85 @@ impl GamesRepository {
             .await
     }
 
+    /// 将重复游戏合并到目标游戏并永久删除被合并的游戏
+    ///
+    /// 游玩会话、存档备份记录、笔记与合集关联转移到目标游戏，目标游戏的统计从会话重建；
+    /// 元数据、数据源与已填写的自定义字段以目标游戏为准，未填写的字段取被合并游戏的值。
+    /// 返回被转移的存档备份记录（转移前的 game_id），调用方负责移动对应的备份文件。
+    pub async fn merge_games(
+        db: &DatabaseConnection,
+        target_id: i32,
+        source_ids: &[i32],
+    ) -> Result<Vec<savedata::Model>, DbErr> {
+        let source_ids: Vec<i32> = source_ids
+            .iter()
+            .copied()
+            .filter(|id| *id != target_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if source_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let transaction = db.begin().await?;
+        if Games::find_by_id(target_id)
+            .one(&transaction)
+            .await?
+            .is_none()
+        {
+            return Err(DbErr::RecordNotFound(format!(
+                "game {} not found",
+                target_id
+            )));
+        }
+
+        let moved_savedata = Savedata::find()
+            .filter(savedata::Column::GameId.is_in(source_ids.clone()))
+            .all(&transaction)
+            .await?;
+        let id_list = source_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        for sql in [
+            format!("UPDATE game_sessions SET game_id = {target_id} WHERE game_id IN ({id_list})"),
+            format!("UPDATE savedata SET game_id = {target_id} WHERE game_id IN ({id_list})"),
+            format!("UPDATE game_notes SET game_id = {target_id} WHERE game_id IN ({id_list})"),
+            format!(
+                "INSERT OR IGNORE INTO game_collection_link (game_id, collection_id, sort_order, created_at)
+                 SELECT {target_id}, collection_id, sort_order, created_at
+                 FROM game_collection_link WHERE game_id IN ({id_list})"
+            ),
+            format!(
+                "INSERT OR IGNORE INTO game_custom_field_values (game_id, field_id, value)
+                 SELECT {target_id}, field_id, value
+                 FROM game_custom_field_values WHERE game_id IN ({id_list})"
+            ),
+            format!(
+                "INSERT OR IGNORE INTO game_relations (game_id, related_vndb_id, relation, official)
+                 SELECT {target_id}, related_vndb_id, relation, official
+                 FROM game_relations WHERE game_id IN ({id_list})"
+            ),
+        ] {
+            transaction.execute_unprepared(&sql).await?;
+        }
+
+        Games::delete_many()
+            .filter(games::Column::Id.is_in(source_ids))
+            .exec(&transaction)
+            .await?;
+        GameStatsRepository::rebuild_statistics_in(&transaction, target_id).await?;
+        transaction.commit().await?;
+        Ok(moved_savedata)
+    }
+
     pub async fn count(db: &DatabaseConnection) -> Result<u64, DbErr> {
-        Games::find().count(db).await
+        Games::find()
+            .filter(games::Column::DeletedAt.is_null())
+            .count(db)
+            .await
     }
 
     pub async fn get_source_bindings(
@@ -701,67 +1287,258 @@ impl GamesRepository {
 
     /// 获取所有非空游戏目录，用于扫描去重
     ///
-    /// 返回数据库中所有 `localpath` 字段的集合（仅非 NULL 值），
-    /// 使用 `HashSet` 以便调用方做 O(1) 精确匹配；前缀检查由调用方负责。
+    /// 返回数据库中所有 `(id, localpath)`（仅非 NULL 值）；
+    /// 路径规范化与前缀检查由调用方负责。
     pub async fn get_all_game_directories(
         db: &DatabaseConnection,
-    ) -> Result<HashSet<String>, DbErr> {
+    ) -> Result<Vec<(i32, String)>, DbErr> {
         Games::find()
             .select_only()
+            .column(games::Column::Id)
             .column(games::Column::Localpath)
             .filter(games::Column::Localpath.is_not_null())
-            .into_tuple::<String>()
+            .into_tuple::<(i32, String)>()
             .all(db)
             .await
-            .map(|paths| paths.into_iter().collect())
     }
 
-    fn build_base_query(game_type: GameType) -> Select<Games> {
-        let query = Games::find();
-        match game_type {
-            GameType::All => query,
-            GameType::Local => query.filter(games::Column::Localpath.is_not_null()),
-            GameType::Online => query.filter(games::Column::Localpath.is_null()),
-            GameType::IsCustom => query.filter(
-                Condition::any()
-                    .add(games::Column::IdType.eq("custom"))
-                    .add(games::Column::IdType.eq("Whitecloud")),
-            ),
-        }
+    /// 获取设置了游戏目录或存档目录的游戏路径状态，用于路径巡检
+    ///
+    /// 已归档（游戏目录已删除）与回收站中的游戏不参与巡检，不会被标记为文件缺失。
+    pub async fn get_path_states(db: &DatabaseConnection) -> Result<Vec<GamePathState>, DbErr> {
+        Self::query_path_states(
+            db,
+            Condition::all()
+                .add(games::Column::ArchivePath.is_null())
+                .add(games::Column::DeletedAt.is_null()),
+        )
+        .await
     }
 
-    /// 发行日期排序：无日期的游戏始终置末尾，升序/降序只影响非空日期。
-    fn apply_date_order(query: Select<Games>, sort_order: SortOrder) -> Select<Games> {
-        let query = query.order_by(Expr::col(games::Column::Date).is_null(), Order::Asc);
-        match sort_order {
-            SortOrder::Asc => query.order_by_asc(games::Column::Date),
-            SortOrder::Desc => query.order_by_desc(games::Column::Date),
-        }
-        .order_by_asc(games::Column::Id)
+    /// 获取所有设置了游戏目录或存档目录的游戏路径状态，包括已归档与回收站中的游戏
+    ///
+    /// 用于批量替换路径前缀，使这些游戏解压或恢复后仍指向新的位置。
+    pub async fn get_all_path_states(db: &DatabaseConnection) -> Result<Vec<GamePathState>, DbErr> {
+        Self::query_path_states(db, Condition::all()).await
     }
 
-    /// 最近游玩排序：无游玩记录始终置末尾，升序按最久优先，降序按最近优先。
-    fn apply_last_played_order(query: Select<Games>, sort_order: SortOrder) -> Select<Games> {
-        let query = query.left_join(game_statistics::Entity).order_by(
-            Expr::col(game_statistics::Column::LastPlayed).is_null(),
-            Order::Asc,
-        );
-        match sort_order {
-            SortOrder::Asc => query.order_by_asc(game_statistics::Column::LastPlayed),
-            SortOrder::Desc => query.order_by_desc(game_statistics::Column::LastPlayed),
-        }
-        .order_by_asc(games::Column::Id)
+    async fn query_path_states(
+        db: &DatabaseConnection,
+        condition: Condition,
+    ) -> Result<Vec<GamePathState>, DbErr> {
+        let rows = Games::find()
+            .select_only()
+            .column(games::Column::Id)
+            .column(games::Column::Localpath)
+            .column(games::Column::Savepath)
+            .column(games::Column::Missing)
+            .filter(
+                Condition::any()
+                    .add(games::Column::Localpath.is_not_null())
+                    .add(games::Column::Savepath.is_not_null()),
+            )
+            .filter(condition)
+            .into_tuple::<(i32, Option<String>, Option<SavePaths>, Option<i32>)>()
+            .all(db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, localpath, savepath, missing)| GamePathState {
+                id,
+                localpath,
+                savepath,
+                missing: missing.unwrap_or(0) != 0,
+            })
+            .collect())
     }
 
-    /// 应用层排序：按可选数值键排序，None 值统一置末尾
-    fn apply_optional_expression_order(
-        query: Select<Games>,
-        expression: &str,
-        direction: Order,
-    ) -> Select<Games> {
-        query
-            .order_by(Expr::cust(format!("({expression}) IS NULL")), Order::Asc)
-            .order_by(Expr::cust(format!("({expression})")), direction)
+    /// 在单个事务中写入批量路径替换结果
+    ///
+    /// 游戏目录变化时同时清除缺失标记，由下次巡检重新判断。
+    pub async fn apply_path_changes(
+        db: &DatabaseConnection,
+        changes: &[GamePathChange],
+    ) -> Result<(), DbErr> {
+        let transaction = db.begin().await?;
+        let now = chrono::Utc::now().timestamp() as i32;
+        for change in changes {
+            let mut active = games::ActiveModel {
+                id: Set(change.game_id),
+                updated_at: Set(Some(now)),
+                ..Default::default()
+            };
+            if let Some(localpath) = &change.new_localpath {
+                active.localpath = Set(Some(localpath.clone()));
+                active.missing = Set(None);
+            }
+            if let Some(savepath) = &change.new_savepath {
+                active.savepath = Set(Some(savepath.clone()));
+            }
+            active.update(&transaction).await?;
+        }
+        transaction.commit().await
+    }
+
+    /// 写入游戏目录占用空间缓存，不更新 updated_at
+    pub async fn set_folder_size(
+        db: &DatabaseConnection,
+        game_id: i32,
+        folder_size: Option<i64>,
+    ) -> Result<(), DbErr> {
+        Games::update_many()
+            .col_expr(games::Column::FolderSize, Expr::value(folder_size))
+            .filter(games::Column::Id.eq(game_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// 写入或清除游戏的归档压缩包路径
+    ///
+    /// 同时清除文件缺失标记：归档后不再巡检，解压后由下次巡检重新判断。
+    pub async fn set_archive_path(
+        db: &DatabaseConnection,
+        game_id: i32,
+        archive_path: Option<String>,
+    ) -> Result<(), DbErr> {
+        let now = chrono::Utc::now().timestamp() as i32;
+        Games::update_many()
+            .col_expr(games::Column::ArchivePath, Expr::value(archive_path))
+            .col_expr(games::Column::Missing, Expr::value(Option::<i32>::None))
+            .col_expr(games::Column::UpdatedAt, Expr::value(now))
+            .filter(games::Column::Id.eq(game_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// 批量设置游戏目录缺失标记，不更新 updated_at
+    pub async fn set_missing(
+        db: &DatabaseConnection,
+        game_ids: &[i32],
+        missing: bool,
+    ) -> Result<u64, DbErr> {
+        if game_ids.is_empty() {
+            return Ok(0);
+        }
+        let result = Games::update_many()
+            .col_expr(games::Column::Missing, Expr::value(i32::from(missing)))
+            .filter(games::Column::Id.is_in(game_ids.iter().copied()))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 获取绑定了对应数据源条目的回写目标
+    ///
+    /// 指定 `game_id` 时忽略回写状态直接返回该游戏，否则返回待回写与回写失败的游戏。
+    pub async fn get_sync_targets(
+        db: &DatabaseConnection,
+        service: SyncService,
+        game_id: Option<i32>,
+    ) -> Result<Vec<SyncTarget>, DbErr> {
+        let mut query = Games::find()
+            .select_only()
+            .column(games::Column::Id)
+            .column(game_sources::Column::ExternalId)
+            .column(games::Column::Clear)
+            .column(games::Column::UserRating)
+            .join(JoinType::InnerJoin, games::Relation::GameSources.def())
+            .filter(game_sources::Column::Source.eq(service.source()))
+            .filter(game_sources::Column::ExternalId.is_not_null());
+        query = match game_id {
+            Some(game_id) => query.filter(games::Column::Id.eq(game_id)),
+            None => query.filter(
+                service
+                    .status_column()
+                    .is_in([SyncStatus::Pending.as_str(), SyncStatus::Failed.as_str()]),
+            ),
+        };
+        let rows = query
+            .order_by_asc(games::Column::Id)
+            .into_tuple::<(i32, String, Option<i32>, Option<f64>)>()
+            .all(db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(game_id, external_id, clear, user_rating)| SyncTarget {
+                game_id,
+                external_id,
+                clear,
+                user_rating,
+            })
+            .collect())
+    }
+
+    /// 批量设置回写状态，不更新 updated_at
+    pub async fn set_sync_status(
+        db: &DatabaseConnection,
+        service: SyncService,
+        game_ids: &[i32],
+        status: SyncStatus,
+    ) -> Result<u64, DbErr> {
+        if game_ids.is_empty() {
+            return Ok(0);
+        }
+        let result = Games::update_many()
+            .col_expr(service.status_column(), Expr::value(status.as_str()))
+            .filter(games::Column::Id.is_in(game_ids.iter().copied()))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 按游戏类型筛选，回收站中的游戏总是排除
+    fn build_base_query(game_type: GameType, filter: GameListFilter) -> Select<Games> {
+        let mut query = Games::find().filter(games::Column::DeletedAt.is_null());
+        for condition in filter.conditions("games") {
+            query = query.filter(Expr::cust(condition));
+        }
+        match game_type {
+            GameType::All => query,
+            GameType::Local => query.filter(games::Column::Localpath.is_not_null()),
+            GameType::Online => query.filter(games::Column::Localpath.is_null()),
+            GameType::IsCustom => query.filter(
+                Condition::any()
+                    .add(games::Column::IdType.eq("custom"))
+                    .add(games::Column::IdType.eq("Whitecloud")),
+            ),
+            GameType::Favorite => query.filter(games::Column::Favorite.eq(true)),
+        }
+    }
+
+    /// 发行日期排序：无日期的游戏始终置末尾，升序/降序只影响非空日期。
+    fn apply_date_order(query: Select<Games>, sort_order: SortOrder) -> Select<Games> {
+        let query = query.order_by(Expr::col(games::Column::Date).is_null(), Order::Asc);
+        match sort_order {
+            SortOrder::Asc => query.order_by_asc(games::Column::Date),
+            SortOrder::Desc => query.order_by_desc(games::Column::Date),
+        }
+        .order_by_asc(games::Column::Id)
+    }
+
+    /// 最近游玩排序：无游玩记录始终置末尾，升序按最久优先，降序按最近优先。
+    fn apply_last_played_order(query: Select<Games>, sort_order: SortOrder) -> Select<Games> {
+        let query = query.left_join(game_statistics::Entity).order_by(
+            Expr::col(game_statistics::Column::LastPlayed).is_null(),
+            Order::Asc,
+        );
+        match sort_order {
+            SortOrder::Asc => query.order_by_asc(game_statistics::Column::LastPlayed),
+            SortOrder::Desc => query.order_by_desc(game_statistics::Column::LastPlayed),
+        }
+        .order_by_asc(games::Column::Id)
+    }
+
+    /// 应用层排序：按可选数值键排序，None 值统一置末尾
+    fn apply_optional_expression_order(
+        query: Select<Games>,
+        expression: &str,
+        direction: Order,
+    ) -> Select<Games> {
+        query
+            .order_by(Expr::cust(format!("({expression}) IS NULL")), Order::Asc)
+            .order_by(Expr::cust(format!("({expression})")), direction)
     }
 
     async fn find_ids_sql(
@@ -769,15 +1546,21 @@ impl GamesRepository {
         game_type: GameType,
         sort_option: SortOption,
         sort_order: SortOrder,
+        filter: GameListFilter,
     ) -> Result<Vec<i32>, DbErr> {
-        let query = Self::build_base_query(game_type)
+        let query = Self::build_base_query(game_type, filter)
             .select_only()
             .column(games::Column::Id);
 
         let query = match sort_option {
+            // 与 idx_games_created_at(created_at, id) 的列顺序一致，排序可直接走索引
             SortOption::Addtime => match sort_order {
-                SortOrder::Asc => query.order_by_asc(games::Column::Id),
-                SortOrder::Desc => query.order_by_desc(games::Column::Id),
+                SortOrder::Asc => query
+                    .order_by_asc(games::Column::CreatedAt)
+                    .order_by_asc(games::Column::Id),
+                SortOrder::Desc => query
+                    .order_by_desc(games::Column::CreatedAt)
+                    .order_by_desc(games::Column::Id),
             },
             SortOption::Datetime => Self::apply_date_order(query, sort_order),
             SortOption::LastPlayed => Self::apply_last_played_order(query, sort_order),
@@ -817,6 +1600,14 @@ impl GamesRepository {
                     .order_by(games::Column::UserRating, direction)
                     .order_by_asc(games::Column::Id)
             }
+            SortOption::Size => {
+                let direction = match sort_order {
+                    SortOrder::Asc => Order::Asc,
+                    SortOrder::Desc => Order::Desc,
+                };
+                Self::apply_optional_expression_order(query, "games.folder_size", direction)
+                    .order_by_asc(games::Column::Id)
+            }
             SortOption::Namesort => unreachable!(),
         };
 
@@ -834,13 +1625,21 @@ impl GamesRepository {
         game_type: GameType,
         sort_order: SortOrder,
         language: Option<String>,
+        filter: GameListFilter,
     ) -> Result<Vec<i32>, DbErr> {
-        let where_clause = match game_type {
+        let type_condition = match game_type {
             GameType::All => "",
-            GameType::Local => "WHERE g.localpath IS NOT NULL",
-            GameType::Online => "WHERE g.localpath IS NULL",
-            GameType::IsCustom => "WHERE g.id_type IN ('custom', 'Whitecloud')",
+            GameType::Local => " AND g.localpath IS NOT NULL",
+            GameType::Online => " AND g.localpath IS NULL",
+            GameType::IsCustom => " AND g.id_type IN ('custom', 'Whitecloud')",
+            GameType::Favorite => " AND g.favorite = 1",
         };
+        let filter_condition: String = filter
+            .conditions("g")
+            .iter()
+            .map(|condition| format!(" AND {condition}"))
+            .collect();
+        let where_clause = format!("WHERE g.deleted_at IS NULL{type_condition}{filter_condition}");
         let sql = format!(
             r#"
             SELECT
@@ -960,6 +1759,8 @@ impl GamesRepository {
         file_name: &str,
         backup_time: i32,
         file_size: i32,
+        label: Option<&str>,
+        comment: Option<&str>,
     ) -> Result<i32, DbErr> {
         let savedata_record = savedata::ActiveModel {
             id: NotSet,
@@ -967,6 +1768,9 @@ impl GamesRepository {
             file: Set(file_name.to_string()),
             backup_time: Set(backup_time),
             file_size: Set(file_size),
+            pinned: Set(false),
+            label: Set(non_empty(label).map(str::to_string)),
+            comment: Set(non_empty(comment).map(str::to_string)),
         };
         let result = savedata_record.insert(db).await?;
         Ok(result.id)
@@ -990,6 +1794,17 @@ impl GamesRepository {
             .await
     }
 
+    /// 获取全部游戏的备份记录
+    pub async fn get_all_savedata_records(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<savedata::Model>, DbErr> {
+        Savedata::find()
+            .order_by_asc(savedata::Column::GameId)
+            .order_by_desc(savedata::Column::BackupTime)
+            .all(db)
+            .await
+    }
+
     pub async fn get_savedata_record_by_id(
         db: &DatabaseConnection,
         backup_id: i32,
@@ -997,6 +1812,42 @@ impl GamesRepository {
         Savedata::find_by_id(backup_id).one(db).await
     }
 
+    /// 设置备份的锁定状态，返回是否找到该记录
+    pub async fn set_savedata_pinned(
+        db: &DatabaseConnection,
+        backup_id: i32,
+        pinned: bool,
+    ) -> Result<bool, DbErr> {
+        let result = Savedata::update_many()
+            .col_expr(savedata::Column::Pinned, Expr::value(pinned))
+            .filter(savedata::Column::Id.eq(backup_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 设置备份的标签与备注，空白内容视为清除，返回是否找到该记录
+    pub async fn set_savedata_note(
+        db: &DatabaseConnection,
+        backup_id: i32,
+        label: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<bool, DbErr> {
+        let result = Savedata::update_many()
+            .col_expr(
+                savedata::Column::Label,
+                Expr::value(non_empty(label).map(str::to_string)),
+            )
+            .col_expr(
+                savedata::Column::Comment,
+                Expr::value(non_empty(comment).map(str::to_string)),
+            )
+            .filter(savedata::Column::Id.eq(backup_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
     pub async fn delete_savedata_record(
         db: &DatabaseConnection,
         backup_id: i32,
@@ -1020,6 +1871,7 @@ struct NameSortEntry {
 mod tests {
     use super::*;
     use crate::entity::custom_data::CustomData;
+    use crate::entity::launch_options::{LaunchArgs, LaunchEnv};
     use sea_orm::Database;
     use serde_json::json;
 
@@ -1036,17 +1888,35 @@ mod tests {
                     localpath TEXT,
                     executable TEXT,
                     savepath TEXT,
+                    save_registry_keys TEXT,
                     autosave INTEGER DEFAULT 0,
                     maxbackups INTEGER DEFAULT 20,
                     clear INTEGER,
                     le_launch INTEGER DEFAULT 0,
+                    le_profile TEXT,
                     magpie INTEGER DEFAULT 0,
+                    launch_args TEXT,
+                    launch_env TEXT,
+                    launch_hooks TEXT,
+                    wine_config TEXT,
+                    engine TEXT,
+                    missing INTEGER,
+                    folder_size INTEGER,
+                    bgm_sync_status TEXT,
+                    vndb_sync_status TEXT,
+                    deleted_at INTEGER,
+                    archive_path TEXT,
+                    favorite BOOLEAN NOT NULL DEFAULT 0,
+                    hidden BOOLEAN NOT NULL DEFAULT 0,
                     custom_data TEXT,
                     user_rating REAL GENERATED ALWAYS AS (
                         CAST(json_extract(custom_data, '$.user_rating') AS REAL)
                     ) VIRTUAL,
                     created_at INTEGER,
-                    updated_at INTEGER
+                    updated_at INTEGER,
+                    clear_updated_at INTEGER,
+                    user_rating_updated_at INTEGER,
+                    user_review_updated_at INTEGER
                 );
                 CREATE TABLE game_sources (
                     game_id INTEGER NOT NULL,
@@ -1078,6 +1948,9 @@ mod tests {
                     file TEXT NOT NULL,
                     backup_time INTEGER NOT NULL,
                     file_size INTEGER NOT NULL,
+                    pinned BOOLEAN NOT NULL DEFAULT FALSE,
+                    label TEXT,
+                    comment TEXT,
                     FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
                 );
                 "#,
@@ -1098,11 +1971,18 @@ mod tests {
             localpath: None,
             executable: None,
             savepath: None,
+            save_registry_keys: None,
             autosave: None,
             maxbackups: None,
             clear: None,
             le_launch: None,
+            le_profile: None,
             magpie: None,
+            launch_args: None,
+            launch_env: None,
+            launch_hooks: None,
+            wine_config: None,
+            engine: None,
             custom_data,
             sources,
         }
@@ -1139,6 +2019,44 @@ mod tests {
         assert_eq!(batch.games[0].magpie, Some(0));
     }
 
+    #[tokio::test]
+    async fn launch_options_round_trip_and_can_be_cleared() {
+        let database = setup_database().await;
+        let mut data = insert_data("custom", None, Vec::new());
+        data.launch_args = Some(LaunchArgs(vec!["-fullscreen".to_string(), String::new()]));
+        data.launch_env = Some(LaunchEnv(
+            [("DXVK_HUD".to_string(), "fps".to_string())].into(),
+        ));
+
+        let inserted = GamesRepository::insert(&database, data).await.unwrap();
+        assert_eq!(
+            inserted.launch_args,
+            Some(LaunchArgs(vec!["-fullscreen".to_string()]))
+        );
+        assert_eq!(
+            inserted
+                .launch_env
+                .as_ref()
+                .and_then(|env| env.0.get("DXVK_HUD"))
+                .map(String::as_str),
+            Some("fps")
+        );
+
+        let updated = GamesRepository::update(
+            &database,
+            inserted.id,
+            UpdateGameData {
+                launch_args: Some(Some(LaunchArgs(vec![" ".to_string()]))),
+                launch_env: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.launch_args, None);
+        assert_eq!(updated.launch_env, None);
+    }
+
     #[tokio::test]
     async fn cleans_empty_source_metadata_before_insert_and_update() {
         let database = setup_database().await;
@@ -1478,6 +2396,212 @@ mod tests {
         assert_eq!(ids, vec![high.id, low.id]);
     }
 
+    #[tokio::test]
+    async fn filters_favorite_and_hidden_games() {
+        let database = setup_database().await;
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let game = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+                .await
+                .unwrap();
+            ids.push(game.id);
+        }
+        let (plain, favorite, hidden) = (ids[0], ids[1], ids[2]);
+        let updated = GamesRepository::update_batch(
+            &database,
+            vec![
+                (
+                    favorite,
+                    UpdateGameData {
+                        favorite: Some(true),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    hidden,
+                    UpdateGameData {
+                        favorite: Some(true),
+                        hidden: Some(true),
+                        ..Default::default()
+                    },
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+        assert!(updated[1].favorite && updated[1].hidden);
+
+        for sort_option in [SortOption::Addtime, SortOption::Namesort] {
+            let find = |game_type, include_hidden: bool| {
+                let filter = GameListFilter {
+                    exclude_hidden: !include_hidden,
+                    ..Default::default()
+                };
+                GamesRepository::find_listed_ids(
+                    &database,
+                    game_type,
+                    sort_option,
+                    SortOrder::Asc,
+                    None,
+                    filter,
+                )
+            };
+            assert_eq!(find(GameType::All, false).await.unwrap(), [plain, favorite]);
+            assert_eq!(
+                find(GameType::All, true).await.unwrap(),
+                [plain, favorite, hidden]
+            );
+            assert_eq!(find(GameType::Favorite, false).await.unwrap(), [favorite]);
+            assert_eq!(
+                find(GameType::Favorite, true).await.unwrap(),
+                [favorite, hidden]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn safe_mode_filter_excludes_nsfw_games() {
+        let database = setup_database().await;
+        let nsfw_data = |nsfw: Option<bool>| {
+            Some(CustomData {
+                nsfw,
+                ..Default::default()
+            })
+        };
+        let mut ids = Vec::new();
+        for data in [
+            insert_data("custom", None, Vec::new()),
+            insert_data("bgm", None, vec![source("bgm", "1", json!({"nsfw": true}))]),
+            insert_data(
+                "bgm",
+                nsfw_data(Some(false)),
+                vec![source("bgm", "2", json!({"nsfw": true}))],
+            ),
+            insert_data("custom", nsfw_data(Some(true)), Vec::new()),
+        ] {
+            ids.push(GamesRepository::insert(&database, data).await.unwrap().id);
+        }
+
+        let filter = GameListFilter {
+            exclude_nsfw: true,
+            ..Default::default()
+        };
+        for sort_option in [SortOption::Addtime, SortOption::Namesort] {
+            let listed = GamesRepository::find_listed_ids(
+                &database,
+                GameType::All,
+                sort_option,
+                SortOrder::Asc,
+                None,
+                filter,
+            )
+            .await
+            .unwrap();
+            assert_eq!(listed, [ids[0], ids[2]]);
+        }
+    }
+
+    #[tokio::test]
+    async fn searches_games_by_name_and_aliases() {
+        let database = setup_database().await;
+        let mut ids = Vec::new();
+        for data in [
+            insert_data(
+                "custom",
+                Some(CustomData {
+                    name: Some("Summer Pockets".to_string()),
+                    aliases: Some(vec!["夏日口袋".to_string()]),
+                    ..Default::default()
+                }),
+                Vec::new(),
+            ),
+            insert_data(
+                "bgm",
+                None,
+                vec![source(
+                    "bgm",
+                    "1",
+                    json!({"name": "千恋＊万花", "name_cn": "千恋万花", "aliases": ["Senren_Banka"]}),
+                )],
+            ),
+        ] {
+            ids.push(GamesRepository::insert(&database, data).await.unwrap().id);
+        }
+
+        let database = &database;
+        let search = |keyword: &'static str| async move {
+            GamesRepository::search(database, keyword, GameListFilter::default())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|game| game.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("pockets").await, [ids[0]]);
+        assert_eq!(search("夏日").await, [ids[0]]);
+        assert_eq!(search("千恋万花").await, [ids[1]]);
+        assert_eq!(search("senren_banka").await, [ids[1]]);
+        assert!(search("senren%").await.is_empty());
+    }
+
+    #[test]
+    fn unknown_list_options_fall_back_to_defaults() {
+        let game_type: GameType = serde_json::from_value(json!("noclear")).unwrap();
+        assert!(matches!(game_type, GameType::All));
+        let sort_option: SortOption = serde_json::from_value(json!("playtime")).unwrap();
+        assert!(matches!(sort_option, SortOption::Addtime));
+        let sort_order: SortOrder = serde_json::from_value(json!("random")).unwrap();
+        assert!(matches!(sort_order, SortOrder::Asc));
+
+        let sort_option: SortOption = serde_json::from_value(json!("added_time")).unwrap();
+        assert!(matches!(sort_option, SortOption::Addtime));
+        let sort_option: SortOption = serde_json::from_value(json!("rating")).unwrap();
+        assert!(matches!(sort_option, SortOption::UserRatingRank));
+    }
+
+    #[tokio::test]
+    async fn sorts_added_time_by_created_at_then_id() {
+        let database = setup_database().await;
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let game = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+                .await
+                .unwrap();
+            ids.push(game.id);
+        }
+        // 导入的游戏可能保留原始加入时间，id 顺序不再等同加入顺序
+        for (game_id, created_at) in [(ids[0], 300), (ids[1], 100), (ids[2], 100)] {
+            database
+                .execute_unprepared(&format!(
+                    "UPDATE games SET created_at = {created_at} WHERE id = {game_id}"
+                ))
+                .await
+                .unwrap();
+        }
+
+        let ascending = GamesRepository::find_ids(
+            &database,
+            GameType::All,
+            SortOption::Addtime,
+            SortOrder::Asc,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(ascending, vec![ids[1], ids[2], ids[0]]);
+
+        let descending = GamesRepository::find_ids(
+            &database,
+            GameType::All,
+            SortOption::Addtime,
+            SortOrder::Desc,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(descending, vec![ids[0], ids[2], ids[1]]);
+    }
+
     #[tokio::test]
     async fn sorts_last_played_chronologically_with_unplayed_last() {
         let database = setup_database().await;
@@ -1526,4 +2650,374 @@ mod tests {
         .unwrap();
         assert_eq!(descending, vec![newest.id, oldest.id, unplayed.id]);
     }
+
+    #[tokio::test]
+    async fn play_status_change_queues_bgm_sync() {
+        let database = setup_database().await;
+        let bound = GamesRepository::insert(
+            &database,
+            insert_data("bgm", None, vec![source("bgm", "1234", json!({}))]),
+        )
+        .await
+        .unwrap();
+        let unbound = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+            .await
+            .unwrap();
+        assert!(
+            GamesRepository::get_sync_targets(&database, SyncService::Bgm, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        for game_id in [bound.id, unbound.id] {
+            let updated = GamesRepository::update(
+                &database,
+                game_id,
+                UpdateGameData {
+                    clear: Some(Some(3)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            assert_eq!(updated.bgm_sync_status.as_deref(), Some("pending"));
+            assert_eq!(updated.vndb_sync_status.as_deref(), Some("pending"));
+        }
+
+        let targets = GamesRepository::get_sync_targets(&database, SyncService::Bgm, None)
+            .await
+            .unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].game_id, bound.id);
+        assert_eq!(targets[0].external_id, "1234");
+        assert_eq!(targets[0].clear, Some(3));
+
+        GamesRepository::set_sync_status(
+            &database,
+            SyncService::Bgm,
+            &[bound.id],
+            SyncStatus::Synced,
+        )
+        .await
+        .unwrap();
+        assert!(
+            GamesRepository::get_sync_targets(&database, SyncService::Bgm, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            GamesRepository::get_sync_targets(&database, SyncService::Bgm, Some(bound.id))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn bulk_update_applies_patch_and_renders_savepath() {
+        let database = setup_database().await;
+        let game_dir = std::path::PathBuf::from("game-root").join("Aster");
+        let with_path = GamesRepository::insert(
+            &database,
+            InsertGameData {
+                localpath: Some(game_dir.to_string_lossy().into_owned()),
+                ..insert_data("custom", None, Vec::new())
+            },
+        )
+        .await
+        .unwrap();
+        let without_path =
+            GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+                .await
+                .unwrap();
+
+        let updated = GamesRepository::bulk_update(
+            &database,
+            &[with_path.id, without_path.id, with_path.id],
+            UpdateGameData {
+                clear: Some(Some(2)),
+                le_launch: Some(Some(1)),
+                savepath: Some(Some(SavePaths(vec![format!(
+                    "{{localpath}}{}save",
+                    std::path::MAIN_SEPARATOR
+                )]))),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated.len(), 2);
+        assert!(
+            updated
+                .iter()
+                .all(|game| game.clear == Some(2) && game.le_launch == Some(1))
+        );
+        assert_eq!(
+            updated[0].savepath,
+            Some(SavePaths(vec![
+                game_dir.join("save").to_string_lossy().into_owned()
+            ]))
+        );
+        assert_eq!(updated[1].savepath, None);
+
+        let missing = GamesRepository::bulk_update(
+            &database,
+            &[with_path.id, 9999],
+            UpdateGameData {
+                clear: Some(Some(5)),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(missing.is_err());
+        let unchanged = GamesRepository::find_by_id(&database, with_path.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged.clear, Some(2));
+    }
+
+    #[tokio::test]
+    async fn soft_deleted_games_move_to_trash() {
+        let database = setup_database().await;
+        let kept = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+            .await
+            .unwrap();
+        let trashed = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+            .await
+            .unwrap();
+
+        let moved = GamesRepository::soft_delete_many(&database, vec![trashed.id])
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+        assert_eq!(GamesRepository::count(&database).await.unwrap(), 1);
+        for sort_option in [SortOption::Addtime, SortOption::Namesort] {
+            let ids = GamesRepository::find_ids(
+                &database,
+                GameType::All,
+                sort_option,
+                SortOrder::Asc,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(ids, [kept.id]);
+        }
+        let deleted = GamesRepository::find_deleted(&database).await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert!(deleted[0].deleted_at.is_some());
+
+        assert_eq!(
+            GamesRepository::restore(&database, trashed.id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            GamesRepository::restore(&database, trashed.id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(GamesRepository::count(&database).await.unwrap(), 2);
+
+        GamesRepository::soft_delete_many(&database, vec![trashed.id])
+            .await
+            .unwrap();
+        let purged = GamesRepository::purge_deleted(&database).await.unwrap();
+        assert_eq!(purged, [trashed.id]);
+        assert!(
+            GamesRepository::find_by_id(&database, trashed.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            GamesRepository::find_by_id(&database, kept.id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn savedata_label_and_comment_round_trip() {
+        let database = setup_database().await;
+        let game = GamesRepository::insert(&database, insert_data("custom", None, Vec::new()))
+            .await
+            .unwrap();
+
+        let backup_id = GamesRepository::save_savedata_record(
+            &database,
+            game.id,
+            "savedata_1.7z",
+            100,
+            10,
+            Some(" true end 前 "),
+            Some(""),
+        )
+        .await
+        .unwrap();
+        let record = GamesRepository::get_savedata_record_by_id(&database, backup_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.label.as_deref(), Some("true end 前"));
+        assert_eq!(record.comment, None);
+
+        assert!(
+            GamesRepository::set_savedata_note(&database, backup_id, None, Some("全 CG"))
+                .await
+                .unwrap()
+        );
+        let records = GamesRepository::get_savedata_records(&database, game.id)
+            .await
+            .unwrap();
+        assert_eq!(records[0].label, None);
+        assert_eq!(records[0].comment.as_deref(), Some("全 CG"));
+        assert!(
+            !GamesRepository::set_savedata_note(&database, backup_id + 1, None, None)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn archived_and_trashed_games_skip_path_checks() {
+        let database = setup_database().await;
+        let mut ids = Vec::new();
+        for name in ["active", "archived", "trashed"] {
+            let game = GamesRepository::insert(
+                &database,
+                InsertGameData {
+                    localpath: Some(format!("/reina-missing/{name}")),
+                    executable: Some("game.exe".to_string()),
+                    ..insert_data("custom", None, Vec::new())
+                },
+            )
+            .await
+            .unwrap();
+            ids.push(game.id);
+        }
+        let [active, archived, trashed] = ids[..] else {
+            unreachable!()
+        };
+
+        GamesRepository::set_missing(&database, &[archived], true)
+            .await
+            .unwrap();
+        GamesRepository::set_archive_path(&database, archived, Some("/archives/game.7z".into()))
+            .await
+            .unwrap();
+        GamesRepository::soft_delete_many(&database, vec![trashed])
+            .await
+            .unwrap();
+
+        let checked: Vec<i32> = GamesRepository::get_path_states(&database)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|state| state.id)
+            .collect();
+        assert_eq!(checked, [active]);
+        assert_eq!(
+            GamesRepository::get_all_path_states(&database)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+        let archived_game = GamesRepository::find_by_id(&database, archived)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(archived_game.missing, None);
+    }
+
+    async fn sync_field_times(database: &DatabaseConnection, game_id: i32) -> SyncFieldTimes {
+        GamesRepository::get_sync_field_times(database)
+            .await
+            .unwrap()
+            .remove(&game_id)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_sync_field_times_only_when_values_change() {
+        let database = setup_database().await;
+        let game = GamesRepository::insert(
+            &database,
+            insert_data(
+                "custom",
+                Some(CustomData {
+                    name: Some("素晴らしき日々".to_string()),
+                    user_rating: Some(8.0),
+                    ..Default::default()
+                }),
+                Vec::new(),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            sync_field_times(&database, game.id).await,
+            SyncFieldTimes::default()
+        );
+
+        let update = |clear: i32, name: &str, user_rating: f64| UpdateGameData {
+            clear: Some(Some(clear)),
+            custom_data: Some(Some(CustomData {
+                name: Some(name.to_string()),
+                user_rating: Some(user_rating),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        GamesRepository::update_aggregate(
+            &database,
+            game.id,
+            update(3, "素晴らしき日々", 8.0),
+            100,
+        )
+        .await
+        .unwrap();
+        GamesRepository::update_aggregate(&database, game.id, update(3, "すばひび", 8.0), 200)
+            .await
+            .unwrap();
+        GamesRepository::update_aggregate(&database, game.id, update(3, "すばひび", 9.5), 300)
+            .await
+            .unwrap();
+        assert_eq!(
+            sync_field_times(&database, game.id).await,
+            SyncFieldTimes {
+                clear: Some(100),
+                user_rating: Some(300),
+                user_review: None,
+            }
+        );
+
+        GamesRepository::set_sync_field_times(
+            &database,
+            game.id,
+            SyncFieldTimes {
+                user_review: Some(50),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            sync_field_times(&database, game.id).await,
+            SyncFieldTimes {
+                clear: Some(100),
+                user_rating: Some(300),
+                user_review: Some(50),
+            }
+        );
+    }
 }
@@ -2,11 +2,20 @@ use crate::database::dto::UpdateSettingsData;
 use crate::entity::prelude::*;
 use crate::entity::user;
 use crate::entity::user::Model;
+use crate::utils::credentials::{delete_secret, read_secret, write_secret};
 use sea_orm::*;
 
 /// 用户设置仓库
+///
+/// BGM 授权信息与 VNDB token 优先保存在系统凭据管理器中，数据库对应列留空；
+/// 凭据管理器不可用时回退为保存在数据库中。
 pub struct SettingsRepository;
 
+/// 系统凭据管理器中 BGM 授权信息（JSON）的键名
+const BGM_AUTH_CREDENTIAL: &str = "bgm_auth";
+/// 系统凭据管理器中 VNDB token 的键名
+const VNDB_TOKEN_CREDENTIAL: &str = "vndb_token";
+
 pub trait DbSettingsExt {
     /// 获取设置模型，并自动处理好错误转换
     async fn get_settings(&self) -> Result<Model, String>;
@@ -34,6 +43,18 @@ impl SettingsRepository {
                 db_backup_path: Set(None),
                 le_path: Set(None),
                 magpie_path: Set(None),
+                prevent_sleep: Set(None),
+                discord_rpc: Set(None),
+                wine_config: Set(None),
+                scan_exe_rules: Set(None),
+                library_watch: Set(None),
+                backup_settings: Set(None),
+                safe_mode: Set(None),
+                network_settings: Set(None),
+                metadata_merge_strategy: Set(None),
+                remote_api: Set(None),
+                webhook: Set(None),
+                obs_output: Set(None),
             };
 
             user.insert(db).await?;
@@ -46,10 +67,69 @@ impl SettingsRepository {
     pub async fn get_all_settings(db: &DatabaseConnection) -> Result<user::Model, DbErr> {
         Self::ensure_user_exists(db).await?;
 
-        User::find_by_id(1)
+        let settings = User::find_by_id(1)
             .one(db)
             .await?
-            .ok_or(DbErr::RecordNotFound("User record not found".to_string()))
+            .ok_or(DbErr::RecordNotFound("User record not found".to_string()))?;
+        Self::with_credentials(db, settings).await
+    }
+
+    /// 写入系统凭据管理器，`None` 时删除；返回凭据管理器是否可用
+    ///
+    /// 凭据管理器不可用的原因已由 [`crate::utils::credentials`] 在首次失败时记录，这里不再重复输出日志
+    async fn save_credential(key: &str, secret: Option<&str>) -> bool {
+        let result = match secret {
+            Some(secret) => write_secret(key, secret).await,
+            None => delete_secret(key).await,
+        };
+        result.is_ok()
+    }
+
+    async fn load_credential(key: &str) -> Option<String> {
+        read_secret(key).await.ok().flatten()
+    }
+
+    /// 从系统凭据管理器补全 token；数据库中仍有明文 token 时迁移到凭据管理器并清空对应列
+    ///
+    /// 凭据管理器在本次运行中已确认不可用时读写都会立即失败，此时使用数据库中的值，不会反复尝试迁移
+    async fn with_credentials(
+        db: &DatabaseConnection,
+        mut settings: Model,
+    ) -> Result<Model, DbErr> {
+        let mut active: user::ActiveModel = settings.clone().into();
+        let mut migrated = false;
+
+        match &settings.bgm_auth {
+            Some(auth) => {
+                let secret = serde_json::to_string(auth)
+                    .map_err(|e| DbErr::Custom(format!("序列化 BGM 授权信息失败: {}", e)))?;
+                if Self::save_credential(BGM_AUTH_CREDENTIAL, Some(&secret)).await {
+                    active.bgm_auth = Set(None);
+                    migrated = true;
+                }
+            }
+            None => {
+                settings.bgm_auth = Self::load_credential(BGM_AUTH_CREDENTIAL)
+                    .await
+                    .and_then(|secret| serde_json::from_str(&secret).ok());
+            }
+        }
+
+        match &settings.vndb_token {
+            Some(token) => {
+                if Self::save_credential(VNDB_TOKEN_CREDENTIAL, Some(token)).await {
+                    active.vndb_token = Set(None);
+                    migrated = true;
+                }
+            }
+            None => settings.vndb_token = Self::load_credential(VNDB_TOKEN_CREDENTIAL).await,
+        }
+
+        if migrated {
+            active.update(db).await?;
+            log::info!("已将数据库中的 token 迁移到系统凭据管理器");
+        }
+        Ok(settings)
     }
 
     /// 批量更新设置
@@ -69,11 +149,18 @@ impl SettingsRepository {
         let mut active: user::ActiveModel = user.into();
 
         if let Some(auth) = data.bgm_auth {
-            active.bgm_auth = Set(auth);
+            let secret = auth
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| DbErr::Custom(format!("序列化 BGM 授权信息失败: {}", e)))?;
+            let saved = Self::save_credential(BGM_AUTH_CREDENTIAL, secret.as_deref()).await;
+            active.bgm_auth = Set(if saved { None } else { auth });
         }
 
         if let Some(token) = data.vndb_token {
-            active.vndb_token = Set(token);
+            let saved = Self::save_credential(VNDB_TOKEN_CREDENTIAL, token.as_deref()).await;
+            active.vndb_token = Set(if saved { None } else { token });
         }
 
         if let Some(path) = data.save_root_path {
@@ -92,6 +179,54 @@ impl SettingsRepository {
             active.magpie_path = Set(path);
         }
 
+        if let Some(enabled) = data.prevent_sleep {
+            active.prevent_sleep = Set(enabled);
+        }
+
+        if let Some(discord_rpc) = data.discord_rpc {
+            active.discord_rpc = Set(discord_rpc);
+        }
+
+        if let Some(wine_config) = data.wine_config {
+            active.wine_config = Set(wine_config);
+        }
+
+        if let Some(rules) = data.scan_exe_rules {
+            active.scan_exe_rules = Set(rules);
+        }
+
+        if let Some(library_watch) = data.library_watch {
+            active.library_watch = Set(library_watch);
+        }
+
+        if let Some(backup_settings) = data.backup_settings {
+            active.backup_settings = Set(backup_settings);
+        }
+
+        if let Some(enabled) = data.safe_mode {
+            active.safe_mode = Set(enabled);
+        }
+
+        if let Some(network_settings) = data.network_settings {
+            active.network_settings = Set(network_settings);
+        }
+
+        if let Some(strategy) = data.metadata_merge_strategy {
+            active.metadata_merge_strategy = Set(strategy);
+        }
+
+        if let Some(remote_api) = data.remote_api {
+            active.remote_api = Set(remote_api);
+        }
+
+        if let Some(webhook) = data.webhook {
+            active.webhook = Set(webhook);
+        }
+
+        if let Some(obs_output) = data.obs_output {
+            active.obs_output = Set(obs_output);
+        }
+
         active.update(db).await?;
         Ok(())
     }
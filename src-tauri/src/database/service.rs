@@ -1,20 +1,42 @@
 use sea_orm::DatabaseConnection;
-use tauri::State;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Runtime, State};
 
+use crate::database::db::{
+    IntegrityReport, OptimizeResult, OrphanCleanupResult, check_integrity, cleanup_orphans,
+    optimize,
+};
 use crate::database::dto::{
-    BatchOperationResult, FullGameData, InsertCollectionData, InsertGameData, UpdateCollectionData,
-    UpdateGameData, UpdateSettingsData,
+    BatchOperationResult, FullGameData, GameSummary, InsertCollectionData, InsertGameData,
+    UpdateCollectionData, UpdateGameData, UpdateSettingsData,
 };
 use crate::database::repository::{
     collections_repository::{
         CategoryWithCount, CollectionBackendSortField, CollectionsRepository, GroupWithCount,
     },
-    game_stats_repository::{GameLastPlayed, GameStatsRepository},
-    games_repository::{GameType, GamesRepository, SortOption, SortOrder},
-    settings_repository::SettingsRepository,
+    custom_fields_repository::{
+        CustomFieldFilter, CustomFieldType, CustomFieldsRepository, GameCustomFieldValue,
+    },
+    game_notes_repository::GameNotesRepository,
+    game_stats_repository::{
+        GameLastPlayed, GameStatsRepository, PlaytimeBucket, PlaytimeDay, PlaytimeGranularity,
+        StreakSummary,
+    },
+    games_repository::{
+        GameListFilter, GamePathChange, GameType, GamesRepository, SortOption, SortOrder,
+    },
+    settings_repository::{DbSettingsExt, SettingsRepository},
 };
-use crate::entity::{savedata, user};
+use crate::entity::custom_data::CustomData;
+use crate::entity::smart_filter::SmartFilter;
+use crate::entity::user::ScanExeRules;
+use crate::entity::{custom_fields, game_notes, savedata, user};
+use crate::error::AppError;
 use crate::game::cover::{DownloadState, delete_game_cover_dir};
+use crate::game::scan::replace_path_prefix;
+use crate::utils::webhook::{self, WebhookEvent, notify_webhook};
+use crate::utils::{discord_rpc, http, obs_output, remote_api};
 
 // ==================== 游戏数据相关 ====================
 
@@ -23,12 +45,15 @@ use crate::game::cover::{DownloadState, delete_game_cover_dir};
 pub async fn insert_game(
     db: State<'_, DatabaseConnection>,
     game: InsertGameData,
-) -> Result<FullGameData, String> {
+) -> Result<FullGameData, AppError> {
     GamesRepository::insert(&db, game)
         .await
-        .map_err(|e| format!("插入游戏数据失败: {}", e))
+        .map_err(AppError::database("插入游戏数据失败"))
 }
 
+/// 批量插入游戏数据
+///
+/// 所有游戏在同一个事务内插入，单条失败只回滚该条；返回结果中的 `ids` 为新建游戏的 ID。
 #[tauri::command]
 pub async fn insert_games_batch(
     db: State<'_, DatabaseConnection>,
@@ -42,13 +67,27 @@ pub async fn insert_games_batch(
 pub async fn find_game_by_id(
     db: State<'_, DatabaseConnection>,
     id: i32,
-) -> Result<Option<FullGameData>, String> {
+) -> Result<Option<FullGameData>, AppError> {
     GamesRepository::find_by_id(&db, id)
         .await
-        .map_err(|e| format!("查询游戏数据失败: {}", e))
+        .map_err(AppError::database("查询游戏数据失败"))
+}
+
+/// 游戏列表的附加筛选：隐藏的游戏按 `show_hidden` 决定，安全模式下排除 NSFW 作品
+pub(crate) async fn list_filter(
+    db: &DatabaseConnection,
+    show_hidden: Option<bool>,
+) -> Result<GameListFilter, String> {
+    Ok(GameListFilter {
+        exclude_hidden: !show_hidden.unwrap_or(false),
+        exclude_nsfw: db.get_settings().await?.safe_mode_enabled(),
+    })
 }
 
 /// 获取所有游戏数据，支持按类型筛选和排序
+///
+/// 隐藏的游戏默认不返回，`show_hidden` 为 true 时一并返回；
+/// 开启安全模式时不返回 NSFW 作品。
 #[tauri::command]
 pub async fn find_all_games(
     db: State<'_, DatabaseConnection>,
@@ -56,16 +95,56 @@ pub async fn find_all_games(
     sort_option: SortOption,
     sort_order: SortOrder,
     language: Option<String>,
-) -> Result<Vec<FullGameData>, String> {
-    GamesRepository::find_all(&db, game_type, sort_option, sort_order, language)
+    show_hidden: Option<bool>,
+) -> Result<Vec<FullGameData>, AppError> {
+    let filter = list_filter(&db, show_hidden).await?;
+    GamesRepository::find_listed(&db, game_type, sort_option, sort_order, language, filter)
+        .await
+        .map_err(AppError::database("获取游戏数据失败"))
+}
+
+/// 获取游戏列表的轻量数据
+///
+/// 只包含列表页展示所需的名称、封面、时长与状态，不携带各数据源的完整 JSON，
+/// 筛选与排序规则与 [`find_all_games`] 相同。
+#[tauri::command]
+pub async fn find_game_summaries(
+    db: State<'_, DatabaseConnection>,
+    game_type: GameType,
+    sort_option: SortOption,
+    sort_order: SortOrder,
+    language: Option<String>,
+    show_hidden: Option<bool>,
+) -> Result<Vec<GameSummary>, AppError> {
+    let filter = list_filter(&db, show_hidden).await?;
+    let games =
+        GamesRepository::find_listed(&db, game_type, sort_option, sort_order, language, filter)
+            .await
+            .map_err(AppError::database("获取游戏数据失败"))?;
+    let statistics: HashMap<i32, _> = GameStatsRepository::get_all_statistics(&db)
         .await
-        .map_err(|e| format!("获取游戏数据失败: {}", e))
+        .map_err(AppError::database("读取游戏统计失败"))?
+        .into_iter()
+        .map(|item| (item.game_id, item))
+        .collect();
+
+    Ok(games
+        .iter()
+        .map(|game| {
+            let stats = statistics.get(&game.id);
+            GameSummary::new(
+                game,
+                stats.and_then(|stats| stats.total_time).unwrap_or(0),
+                stats.and_then(|stats| stats.last_played),
+            )
+        })
+        .collect())
 }
 
 /// 只返回排序/筛选后的游戏 ID 列表
 ///
 /// 前端已缓存完整游戏数据，切换排序/筛选时只需传输 ID 数组，
-/// 避免数 MB 级 JSON 反复穿过 IPC 桥梁。
+/// 避免数 MB 级 JSON 反复穿过 IPC 桥梁。筛选规则与 [`find_all_games`] 相同。
 #[tauri::command]
 pub async fn find_game_ids(
     db: State<'_, DatabaseConnection>,
@@ -73,10 +152,71 @@ pub async fn find_game_ids(
     sort_option: SortOption,
     sort_order: SortOrder,
     language: Option<String>,
-) -> Result<Vec<i32>, String> {
-    GamesRepository::find_ids(&db, game_type, sort_option, sort_order, language)
+    show_hidden: Option<bool>,
+) -> Result<Vec<i32>, AppError> {
+    let filter = list_filter(&db, show_hidden).await?;
+    GamesRepository::find_listed_ids(&db, game_type, sort_option, sort_order, language, filter)
+        .await
+        .map_err(AppError::database("获取游戏 ID 列表失败"))
+}
+
+/// 按名称或别名搜索游戏
+///
+/// 匹配自定义名称与别名，以及各数据源的原名、中文名与别名；
+/// 隐藏与安全模式的筛选规则与 [`find_all_games`] 相同。
+#[tauri::command]
+pub async fn search_games(
+    db: State<'_, DatabaseConnection>,
+    keyword: String,
+    show_hidden: Option<bool>,
+) -> Result<Vec<FullGameData>, AppError> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
+    let filter = list_filter(&db, show_hidden).await?;
+    GamesRepository::search(&db, keyword, filter)
         .await
-        .map_err(|e| format!("获取游戏 ID 列表失败: {}", e))
+        .map_err(AppError::database("搜索游戏失败"))
+}
+
+/// 查询更新前的游玩状态，用于更新后判断是否变更
+async fn play_statuses_before(db: &DatabaseConnection, ids: &[i32]) -> HashMap<i32, Option<i32>> {
+    if ids.is_empty() {
+        return HashMap::new();
+    }
+    match GamesRepository::find_full_games_in_order(db, ids).await {
+        Ok(games) => games
+            .into_iter()
+            .map(|game| (game.id, game.clear))
+            .collect(),
+        Err(e) => {
+            log::warn!("查询游玩状态失败: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// 对游玩状态发生变化的游戏发送 Webhook
+fn notify_play_status_changes(
+    db: &DatabaseConnection,
+    previous: &HashMap<i32, Option<i32>>,
+    updated: &[FullGameData],
+) {
+    for game in updated {
+        if let Some(&before) = previous.get(&game.id)
+            && before != game.clear
+        {
+            notify_webhook(
+                db,
+                game.id,
+                WebhookEvent::PlayStatusChanged {
+                    previous: before,
+                    current: game.clear,
+                },
+            );
+        }
+    }
 }
 
 /// 更新游戏数据（聚合架构）
@@ -85,26 +225,132 @@ pub async fn update_game(
     db: State<'_, DatabaseConnection>,
     game_id: i32,
     updates: UpdateGameData,
-) -> Result<FullGameData, String> {
-    GamesRepository::update(&db, game_id, updates)
+) -> Result<FullGameData, AppError> {
+    let status_ids: &[i32] = if updates.clear.is_some() {
+        &[game_id]
+    } else {
+        &[]
+    };
+    let previous = play_statuses_before(&db, status_ids).await;
+    let game = GamesRepository::update(&db, game_id, updates)
         .await
-        .map_err(|e| format!("更新游戏数据失败: {}", e))
+        .map_err(AppError::database("更新游戏数据失败"))?;
+    notify_play_status_changes(&db, &previous, std::slice::from_ref(&game));
+    Ok(game)
+}
+
+/// 在保留其他自定义字段的前提下修改个人评分与短评
+///
+/// `score` / `comment` 为 None 时保持不变，Some(None) 时清除。
+fn with_review(
+    custom_data: Option<CustomData>,
+    score: Option<Option<f64>>,
+    comment: Option<Option<String>>,
+) -> CustomData {
+    let mut data = custom_data.unwrap_or_default();
+    if let Some(score) = score {
+        data.user_rating = score;
+    }
+    if let Some(comment) = comment {
+        data.user_review = comment
+            .map(|comment| comment.trim().to_string())
+            .filter(|comment| !comment.is_empty());
+    }
+    data
+}
+
+async fn update_review(
+    db: &DatabaseConnection,
+    game_id: i32,
+    score: Option<Option<f64>>,
+    comment: Option<Option<String>>,
+) -> Result<FullGameData, AppError> {
+    let game = GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(AppError::database("获取游戏信息失败"))?
+        .ok_or(AppError::GameNotFound { game_id })?;
+    let updates = UpdateGameData {
+        custom_data: Some(Some(with_review(game.custom_data, score, comment))),
+        ..Default::default()
+    };
+    GamesRepository::update(db, game_id, updates)
+        .await
+        .map_err(AppError::database("更新个人评分失败"))
+}
+
+/// 设置个人评分与短评
+///
+/// # Arguments
+/// * `score` - 评分（0-10，保留一位小数），为空时保持不变，0 表示清除评分
+/// * `comment` - 短评，为空时保持不变，空字符串表示清除短评
+#[tauri::command]
+pub async fn set_game_review(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    score: Option<f64>,
+    comment: Option<String>,
+) -> Result<FullGameData, AppError> {
+    if let Some(score) = score
+        && !(0.0..=10.0).contains(&score)
+    {
+        return Err(AppError::invalid_argument(
+            "score",
+            format!("评分必须在 0-10 之间: {}", score),
+        ));
+    }
+    let score = score.map(|score| Some((score * 10.0).round() / 10.0).filter(|score| *score > 0.0));
+    update_review(&db, game_id, score, comment.map(Some)).await
+}
+
+/// 清除个人评分与短评
+#[tauri::command]
+pub async fn clear_game_review(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<FullGameData, AppError> {
+    update_review(&db, game_id, Some(None), Some(None)).await
+}
+
+/// 永久删除游戏后清理封面缓存与封面目录
+pub(crate) async fn cleanup_deleted_game_covers(cover_state: &DownloadState, ids: &[i32]) {
+    for game_id in ids {
+        if *game_id > 0 {
+            cover_state.mark_game_deleted(*game_id as u32).await;
+        }
+    }
+
+    for game_id in ids {
+        if let Err(err) = delete_game_cover_dir(*game_id).await {
+            log::warn!("清理游戏封面目录失败 game_id={}: {}", game_id, err);
+        }
+    }
 }
 
 /// 删除游戏
+///
+/// 默认移入回收站，可通过 `restore_game` 恢复；`permanent` 为 true 时永久删除。
 #[tauri::command]
 pub async fn delete_game(
     db: State<'_, DatabaseConnection>,
     cover_state: State<'_, DownloadState>,
     id: i32,
-) -> Result<u64, String> {
+    permanent: Option<bool>,
+) -> Result<u64, AppError> {
+    if !permanent.unwrap_or(false) {
+        let rows_affected = GamesRepository::soft_delete_many(&db, vec![id])
+            .await
+            .map_err(AppError::database("删除游戏失败"))?;
+        log::info!("游戏已移入回收站 game_id={}", id);
+        return Ok(rows_affected);
+    }
+
     let rows_affected = GamesRepository::delete(&db, id)
         .await
         .map(|result| result.rows_affected)
-        .map_err(|e| format!("删除游戏失败: {}", e))?;
+        .map_err(AppError::database("删除游戏失败"))?;
 
     if rows_affected > 0 {
-        cover_state.mark_game_deleted(id as u32).await;
+        cleanup_deleted_game_covers(&cover_state, &[id]).await;
         log::info!(
             "游戏删除成功 game_id={} rows_affected={}",
             id,
@@ -112,43 +358,38 @@ pub async fn delete_game(
         );
     }
 
-    if rows_affected > 0
-        && let Err(err) = delete_game_cover_dir(id).await
-    {
-        log::warn!("删除游戏封面目录失败 game_id={}: {}", id, err);
-    }
-
     Ok(rows_affected)
 }
 
 /// 批量删除游戏
+///
+/// 默认移入回收站，`permanent` 为 true 时永久删除。
 #[tauri::command]
 pub async fn delete_games_batch(
     db: State<'_, DatabaseConnection>,
     cover_state: State<'_, DownloadState>,
     ids: Vec<i32>,
-) -> Result<u64, String> {
+    permanent: Option<bool>,
+) -> Result<u64, AppError> {
+    let requested_count = ids.len();
+    if !permanent.unwrap_or(false) {
+        let rows_affected = GamesRepository::soft_delete_many(&db, ids)
+            .await
+            .map_err(AppError::database("批量删除游戏失败"))?;
+        log::info!(
+            "批量移入回收站完成 requested_count={} rows_affected={}",
+            requested_count,
+            rows_affected
+        );
+        return Ok(rows_affected);
+    }
+
     let rows_affected = GamesRepository::delete_many(&db, ids.clone())
         .await
         .map(|result| result.rows_affected)
-        .map_err(|e| format!("批量删除游戏失败: {}", e))?;
-    let requested_count = ids.len();
-
-    for game_id in &ids {
-        if *game_id > 0 {
-            cover_state.mark_game_deleted(*game_id as u32).await;
-        }
-    }
+        .map_err(AppError::database("批量删除游戏失败"))?;
 
-    for game_id in ids {
-        if let Err(err) = delete_game_cover_dir(game_id).await {
-            log::warn!(
-                "批量删除时清理游戏封面目录失败 game_id={}: {}",
-                game_id,
-                err
-            );
-        }
-    }
+    cleanup_deleted_game_covers(&cover_state, &ids).await;
 
     log::info!(
         "批量删除游戏完成 requested_count={} rows_affected={}",
@@ -159,6 +400,50 @@ pub async fn delete_games_batch(
     Ok(rows_affected)
 }
 
+/// 从回收站恢复游戏
+#[tauri::command]
+pub async fn restore_game(
+    db: State<'_, DatabaseConnection>,
+    id: i32,
+) -> Result<FullGameData, AppError> {
+    let restored = GamesRepository::restore(&db, id)
+        .await
+        .map_err(AppError::database("恢复游戏失败"))?;
+    if restored == 0 {
+        return Err(AppError::GameNotInTrash { game_id: id });
+    }
+    log::info!("游戏已从回收站恢复 game_id={}", id);
+
+    GamesRepository::find_by_id(&db, id)
+        .await
+        .map_err(AppError::database("读取游戏数据失败"))?
+        .ok_or(AppError::GameNotFound { game_id: id })
+}
+
+/// 获取回收站中的游戏，最近删除的在前
+#[tauri::command]
+pub async fn find_deleted_games(
+    db: State<'_, DatabaseConnection>,
+) -> Result<Vec<FullGameData>, String> {
+    GamesRepository::find_deleted(&db)
+        .await
+        .map_err(|e| format!("获取回收站游戏失败: {}", e))
+}
+
+/// 清空回收站，永久删除其中的全部游戏
+#[tauri::command]
+pub async fn purge_deleted_games(
+    db: State<'_, DatabaseConnection>,
+    cover_state: State<'_, DownloadState>,
+) -> Result<u64, String> {
+    let ids = GamesRepository::purge_deleted(&db)
+        .await
+        .map_err(|e| format!("清空回收站失败: {}", e))?;
+    cleanup_deleted_game_covers(&cover_state, &ids).await;
+    log::info!("回收站已清空 purged_count={}", ids.len());
+    Ok(ids.len() as u64)
+}
+
 /// 获取游戏总数
 #[tauri::command]
 pub async fn count_games(db: State<'_, DatabaseConnection>) -> Result<u64, String> {
@@ -186,14 +471,100 @@ pub async fn update_games_batch(
     db: State<'_, DatabaseConnection>,
     updates: Vec<(i32, UpdateGameData)>,
 ) -> Result<Vec<FullGameData>, String> {
-    GamesRepository::update_batch(&db, updates)
+    let status_ids: Vec<i32> = updates
+        .iter()
+        .filter(|(_, update)| update.clear.is_some())
+        .map(|(game_id, _)| *game_id)
+        .collect();
+    let previous = play_statuses_before(&db, &status_ids).await;
+    let updated = GamesRepository::update_batch(&db, updates)
+        .await
+        .map_err(|e| format!("批量更新数据失败: {}", e))?;
+    notify_play_status_changes(&db, &previous, &updated);
+    Ok(updated)
+}
+
+/// 对多个游戏设置相同字段
+///
+/// 在单个事务中应用同一份更新，任一游戏失败时全部回滚。
+/// `savepath` 可使用 `{localpath}`、`{name}`、`{id}` 占位符，按每个游戏分别渲染。
+#[tauri::command]
+pub async fn bulk_update_games(
+    db: State<'_, DatabaseConnection>,
+    ids: Vec<i32>,
+    patch: UpdateGameData,
+) -> Result<Vec<FullGameData>, String> {
+    let status_ids: &[i32] = if patch.clear.is_some() { &ids } else { &[] };
+    let previous = play_statuses_before(&db, status_ids).await;
+    let updated = GamesRepository::bulk_update(&db, &ids, patch)
         .await
-        .map_err(|e| format!("批量更新数据失败: {}", e))
+        .map_err(|e| format!("批量编辑游戏失败: {}", e))?;
+    log::info!("批量编辑游戏完成 updated_count={}", updated.len());
+    notify_play_status_changes(&db, &previous, &updated);
+    Ok(updated)
+}
+
+/// 批量替换游戏目录与存档目录的路径前缀
+///
+/// 用于整体移动游戏库（如 `D:\Galgame` -> `E:\Galgame`）后修正路径。
+/// `dry_run` 为 true 时只返回将受影响的游戏，不写入数据库。
+#[tauri::command]
+pub async fn migrate_localpath_prefix(
+    db: State<'_, DatabaseConnection>,
+    old_prefix: String,
+    new_prefix: String,
+    dry_run: bool,
+) -> Result<Vec<GamePathChange>, String> {
+    let old_prefix = old_prefix.trim();
+    let new_prefix = new_prefix.trim();
+    if old_prefix.is_empty() || new_prefix.is_empty() {
+        return Err("路径前缀不能为空".to_string());
+    }
+    let (old_prefix, new_prefix) = (Path::new(old_prefix), Path::new(new_prefix));
+
+    let changes: Vec<GamePathChange> = GamesRepository::get_all_path_states(&db)
+        .await
+        .map_err(|e| format!("查询游戏路径失败: {}", e))?
+        .into_iter()
+        .filter_map(|state| {
+            let replace = |path: &str| replace_path_prefix(path, old_prefix, new_prefix);
+            let new_localpath = state
+                .localpath
+                .as_deref()
+                .and_then(replace)
+                .filter(|replaced| Some(replaced) != state.localpath.as_ref());
+            let new_savepath = state
+                .savepath
+                .as_ref()
+                .and_then(|savepath| savepath.replaced(replace));
+            (new_localpath.is_some() || new_savepath.is_some()).then_some(GamePathChange {
+                game_id: state.id,
+                old_localpath: state.localpath,
+                new_localpath,
+                old_savepath: state.savepath,
+                new_savepath,
+            })
+        })
+        .collect();
+
+    if !dry_run && !changes.is_empty() {
+        GamesRepository::apply_path_changes(&db, &changes)
+            .await
+            .map_err(|e| format!("批量替换路径失败: {}", e))?;
+        log::info!(
+            "批量替换路径前缀完成 old={} new={} affected={}",
+            old_prefix.display(),
+            new_prefix.display(),
+            changes.len()
+        );
+    }
+
+    Ok(changes)
 }
 
 // ==================== 存档备份相关 ====================
 
-/// 保存存档备份记录
+/// 保存存档备份记录，`label` 与 `comment` 为可选的标签与备注
 #[tauri::command]
 pub async fn save_savedata_record(
     db: State<'_, DatabaseConnection>,
@@ -201,10 +572,38 @@ pub async fn save_savedata_record(
     file_name: String,
     backup_time: i32,
     file_size: i32,
+    label: Option<String>,
+    comment: Option<String>,
 ) -> Result<i32, String> {
-    GamesRepository::save_savedata_record(&db, game_id, &file_name, backup_time, file_size)
-        .await
-        .map_err(|e| format!("保存存档备份记录失败: {}", e))
+    GamesRepository::save_savedata_record(
+        &db,
+        game_id,
+        &file_name,
+        backup_time,
+        file_size,
+        label.as_deref(),
+        comment.as_deref(),
+    )
+    .await
+    .map_err(|e| format!("保存存档备份记录失败: {}", e))
+}
+
+/// 修改存档备份的标签与备注，传空值清除
+#[tauri::command]
+pub async fn update_savedata_note(
+    db: State<'_, DatabaseConnection>,
+    backup_id: i32,
+    label: Option<String>,
+    comment: Option<String>,
+) -> Result<(), String> {
+    let found =
+        GamesRepository::set_savedata_note(&db, backup_id, label.as_deref(), comment.as_deref())
+            .await
+            .map_err(|e| format!("更新备份说明失败: {}", e))?;
+    if !found {
+        return Err("备份记录不存在".to_string());
+    }
+    Ok(())
 }
 
 /// 获取指定游戏的备份数量
@@ -239,20 +638,59 @@ pub async fn create_manual_game_session(
     start_time: i32,
     duration: i32,
 ) -> Result<i32, String> {
-    GameStatsRepository::create_manual_session(&db, game_id, start_time, duration)
+    GameStatsRepository::create_manual_session(&db, game_id, start_time, duration, None)
         .await
         .map(|session| session.session_id)
         .map_err(|e| format!("创建游戏会话失败: {}", e))
 }
 
-/// 修复/调试命令：从全部事实会话重建指定游戏的统计投影
+/// 按日期补录游玩会话
 ///
-/// 常规会话增删已在事务内同步维护统计，不应调用此命令。
+/// # Arguments
+/// * `date` - 本地日期 `YYYY-MM-DD`，会话默认从当天零点开始
+/// * `minutes` - 游玩时长（分钟）
 #[tauri::command]
-pub async fn rebuild_game_statistics(
+pub async fn add_manual_session(
     db: State<'_, DatabaseConnection>,
     game_id: i32,
-) -> Result<(), String> {
+    date: String,
+    minutes: i32,
+    note: Option<String>,
+) -> Result<crate::entity::game_sessions::Model, String> {
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("无效日期 {}: {}", date, e))?;
+    GameStatsRepository::add_manual_session_on_date(&db, game_id, date, minutes, note)
+        .await
+        .map_err(|e| format!("补录游戏会话失败: {}", e))
+}
+
+/// 修改游戏会话，统计与每日时长随之重算
+///
+/// # Arguments
+/// * `start_time` / `duration` - 新的开始时间与时长（分钟），为空时保持不变
+/// * `note` - 新备注，传入空字符串清除备注，为空时保持不变
+#[tauri::command]
+pub async fn update_game_session(
+    db: State<'_, DatabaseConnection>,
+    session_id: i32,
+    start_time: Option<i32>,
+    duration: Option<i32>,
+    note: Option<String>,
+) -> Result<crate::entity::game_sessions::Model, String> {
+    GameStatsRepository::update_session(&db, session_id, start_time, duration, note.map(Some))
+        .await
+        .map_err(|e| format!("修改游戏会话失败: {}", e))
+}
+
+/// 修复/调试命令：从全部事实会话重建统计投影（总时长、会话次数、最近游玩、每日时长）
+///
+/// `game_id` 为空时重建全库，返回重建的游戏数量。
+/// 常规会话增删已在事务内同步维护统计，只在手动删改会话后修复不一致的统计时调用。
+#[tauri::command]
+pub async fn rebuild_game_statistics(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+) -> Result<usize, String> {
     GameStatsRepository::rebuild_statistics(&db, game_id)
         .await
         .map_err(|e| format!("重建游戏统计失败: {}", e))
@@ -326,6 +764,208 @@ pub async fn get_all_game_last_played(
         .map_err(|e| format!("获取所有游戏最近游玩时间失败: {}", e))
 }
 
+/// 按日期范围聚合游玩时长，`game_id` 为空时统计全库
+#[tauri::command]
+pub async fn get_playtime_by_range(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+    start: String,
+    end: String,
+    granularity: PlaytimeGranularity,
+) -> Result<Vec<PlaytimeBucket>, String> {
+    GameStatsRepository::get_playtime_by_range(&db, game_id, &start, &end, granularity)
+        .await
+        .map_err(|e| format!("聚合游玩时长失败: {}", e))
+}
+
+/// 获取指定年份每天的游玩时长（热力图），`game_id` 为空时统计全库
+#[tauri::command]
+pub async fn get_playtime_heatmap(
+    db: State<'_, DatabaseConnection>,
+    year: i32,
+    game_id: Option<i32>,
+) -> Result<Vec<PlaytimeDay>, String> {
+    GameStatsRepository::get_playtime_heatmap(&db, year, game_id)
+        .await
+        .map_err(|e| format!("获取游玩热力图失败: {}", e))
+}
+
+/// 获取全库与各游戏的连续游玩天数
+#[tauri::command]
+pub async fn get_streaks(db: State<'_, DatabaseConnection>) -> Result<StreakSummary, String> {
+    GameStatsRepository::get_streaks(&db, chrono::Local::now().date_naive())
+        .await
+        .map_err(|e| format!("统计连续游玩天数失败: {}", e))
+}
+
+// ==================== 游戏笔记相关 ====================
+
+/// 获取游戏的全部笔记，最近修改的在前
+#[tauri::command]
+pub async fn get_game_notes(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<game_notes::Model>, String> {
+    GameNotesRepository::find_by_game(&db, game_id)
+        .await
+        .map_err(|e| format!("获取游戏笔记失败: {}", e))
+}
+
+/// 新建游戏笔记
+///
+/// # Arguments
+/// * `title` - 笔记标题，可为空
+/// * `content` - Markdown 内容
+#[tauri::command]
+pub async fn create_game_note(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    title: Option<String>,
+    content: String,
+) -> Result<game_notes::Model, String> {
+    let title = title
+        .map(|title| title.trim().to_string())
+        .unwrap_or_default();
+    GameNotesRepository::create(&db, game_id, title, content)
+        .await
+        .map_err(|e| format!("创建游戏笔记失败: {}", e))
+}
+
+/// 更新游戏笔记，参数为空的字段保持不变
+#[tauri::command]
+pub async fn update_game_note(
+    db: State<'_, DatabaseConnection>,
+    note_id: i32,
+    title: Option<String>,
+    content: Option<String>,
+) -> Result<game_notes::Model, String> {
+    let title = title.map(|title| title.trim().to_string());
+    GameNotesRepository::update(&db, note_id, title, content)
+        .await
+        .map_err(|e| format!("更新游戏笔记失败: {}", e))
+}
+
+/// 删除游戏笔记
+#[tauri::command]
+pub async fn delete_game_note(
+    db: State<'_, DatabaseConnection>,
+    note_id: i32,
+) -> Result<u64, String> {
+    GameNotesRepository::delete(&db, note_id)
+        .await
+        .map_err(|e| format!("删除游戏笔记失败: {}", e))
+}
+
+/// 按关键词搜索笔记标题与内容
+///
+/// # Arguments
+/// * `keyword` - 关键词，不区分大小写
+/// * `game_id` - 只搜索指定游戏的笔记，为空时搜索全部游戏
+#[tauri::command]
+pub async fn search_game_notes(
+    db: State<'_, DatabaseConnection>,
+    keyword: String,
+    game_id: Option<i32>,
+) -> Result<Vec<game_notes::Model>, String> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
+    GameNotesRepository::search(&db, keyword, game_id)
+        .await
+        .map_err(|e| format!("搜索游戏笔记失败: {}", e))
+}
+
+// ==================== 自定义字段相关 ====================
+
+/// 获取全部自定义字段
+#[tauri::command]
+pub async fn get_custom_fields(
+    db: State<'_, DatabaseConnection>,
+) -> Result<Vec<custom_fields::Model>, String> {
+    CustomFieldsRepository::find_fields(&db)
+        .await
+        .map_err(|e| format!("获取自定义字段失败: {}", e))
+}
+
+/// 新建自定义字段
+///
+/// # Arguments
+/// * `name` - 字段名，如"汉化组"、"购入渠道"
+/// * `field_type` - 字段类型，默认为文本
+#[tauri::command]
+pub async fn create_custom_field(
+    db: State<'_, DatabaseConnection>,
+    name: String,
+    field_type: Option<CustomFieldType>,
+) -> Result<custom_fields::Model, String> {
+    CustomFieldsRepository::create_field(&db, &name, field_type.unwrap_or(CustomFieldType::Text))
+        .await
+        .map_err(|e| format!("创建自定义字段失败: {}", e))
+}
+
+/// 修改自定义字段的名称或类型，参数为空时保持不变
+#[tauri::command]
+pub async fn update_custom_field(
+    db: State<'_, DatabaseConnection>,
+    field_id: i32,
+    name: Option<String>,
+    field_type: Option<CustomFieldType>,
+) -> Result<custom_fields::Model, String> {
+    CustomFieldsRepository::update_field(&db, field_id, name, field_type)
+        .await
+        .map_err(|e| format!("更新自定义字段失败: {}", e))
+}
+
+/// 删除自定义字段，所有游戏上的该字段值一并删除
+#[tauri::command]
+pub async fn delete_custom_field(
+    db: State<'_, DatabaseConnection>,
+    field_id: i32,
+) -> Result<u64, String> {
+    CustomFieldsRepository::delete_field(&db, field_id)
+        .await
+        .map_err(|e| format!("删除自定义字段失败: {}", e))
+}
+
+/// 获取游戏已填写的自定义字段值
+#[tauri::command]
+pub async fn get_game_custom_fields(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<GameCustomFieldValue>, String> {
+    CustomFieldsRepository::find_game_values(&db, game_id)
+        .await
+        .map_err(|e| format!("获取游戏自定义字段失败: {}", e))
+}
+
+/// 设置游戏的自定义字段值
+///
+/// # Arguments
+/// * `value` - 字段值，为空或空字符串时清除；数值字段需为有效数字
+#[tauri::command]
+pub async fn set_game_custom_field(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    field_id: i32,
+    value: Option<String>,
+) -> Result<Option<String>, String> {
+    CustomFieldsRepository::set_game_value(&db, game_id, field_id, value)
+        .await
+        .map_err(|e| format!("设置自定义字段失败: {}", e))
+}
+
+/// 按自定义字段筛选游戏，返回游戏 ID 列表
+#[tauri::command]
+pub async fn find_games_by_custom_field(
+    db: State<'_, DatabaseConnection>,
+    filter: CustomFieldFilter,
+) -> Result<Vec<i32>, String> {
+    CustomFieldsRepository::find_game_ids(&db, &filter)
+        .await
+        .map_err(|e| format!("按自定义字段筛选游戏失败: {}", e))
+}
+
 // ==================== 用户设置相关 ====================
 
 /// 获取所有设置
@@ -338,15 +978,95 @@ pub async fn get_all_settings(db: State<'_, DatabaseConnection>) -> Result<user:
 
 /// 批量更新设置
 #[tauri::command]
-pub async fn update_settings(
+pub async fn update_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
     db: State<'_, DatabaseConnection>,
     data: UpdateSettingsData,
 ) -> Result<(), String> {
     let data = data.cleaned(); // 清洗空字符串
+    let discord_rpc_disabled = matches!(
+        &data.discord_rpc,
+        Some(settings) if !settings.as_ref().is_some_and(|settings| settings.enabled)
+    );
+    let obs_output_disabled = matches!(
+        &data.obs_output,
+        Some(settings) if !settings.as_ref().is_some_and(|settings| settings.enabled)
+    );
+    let network_settings = data
+        .network_settings
+        .clone()
+        .map(|settings| settings.unwrap_or_default());
+    if let Some(settings) = &network_settings {
+        http::validate_network_settings(settings)?;
+    }
+    let remote_api = data
+        .remote_api
+        .clone()
+        .map(|settings| settings.unwrap_or_default());
+    if let Some(settings) = &remote_api {
+        remote_api::validate_remote_api_settings(settings)?;
+    }
+    if let Some(Some(settings)) = &data.webhook {
+        webhook::validate_webhook_settings(settings)?;
+    }
+
+    SettingsRepository::update_settings(&db, data)
+        .await
+        .map_err(|e| format!("更新设置失败: {}", e))?;
+
+    if let Some(settings) = network_settings {
+        http::apply_network_settings(settings)?;
+    }
+    if let Some(settings) = remote_api {
+        remote_api::apply_remote_api_settings(&app_handle, &db, settings).await?;
+    }
+
+    // 关闭 Discord 状态后立即清除正在展示的游戏
+    if discord_rpc_disabled {
+        discord_rpc::clear_presence();
+    }
+    if obs_output_disabled {
+        obs_output::clear_obs_output().await;
+    }
+    Ok(())
+}
+
+/// 获取扫描目录时的 exe 排序与排除规则，未自定义时返回内置规则
+#[tauri::command]
+pub async fn get_scan_exe_rules(db: State<'_, DatabaseConnection>) -> Result<ScanExeRules, String> {
+    Ok(db.get_settings().await?.scan_exe_rules())
+}
+
+/// 保存自定义的 exe 排序与排除规则
+#[tauri::command]
+pub async fn update_scan_exe_rules(
+    db: State<'_, DatabaseConnection>,
+    rules: ScanExeRules,
+) -> Result<ScanExeRules, String> {
+    let rules = rules.cleaned();
+    let data = UpdateSettingsData {
+        scan_exe_rules: Some(Some(rules.clone())),
+        ..Default::default()
+    };
+    SettingsRepository::update_settings(&db, data)
+        .await
+        .map_err(|e| format!("更新扫描规则失败: {}", e))?;
+    Ok(rules)
+}
 
+/// 清除自定义规则，恢复内置的 exe 排序与排除规则
+#[tauri::command]
+pub async fn reset_scan_exe_rules(
+    db: State<'_, DatabaseConnection>,
+) -> Result<ScanExeRules, String> {
+    let data = UpdateSettingsData {
+        scan_exe_rules: Some(None),
+        ..Default::default()
+    };
     SettingsRepository::update_settings(&db, data)
         .await
-        .map_err(|e| format!("更新设置失败: {}", e))
+        .map_err(|e| format!("重置扫描规则失败: {}", e))?;
+    Ok(ScanExeRules::default())
 }
 
 // ==================== 合集相关 ====================
@@ -362,7 +1082,7 @@ fn validate_collection_sort(
     }
 }
 
-/// 创建合集
+/// 创建合集，传入 `smart_filter` 时创建智能合集
 #[tauri::command]
 pub async fn create_collection(
     db: State<'_, DatabaseConnection>,
@@ -370,12 +1090,14 @@ pub async fn create_collection(
     parent_id: Option<i32>,
     sort_order: i32,
     icon: Option<String>,
+    smart_filter: Option<SmartFilter>,
 ) -> Result<crate::entity::collections::Model, String> {
     let data = InsertCollectionData {
         name,
         parent_id,
         sort_order,
         icon,
+        smart_filter,
     }
     .cleaned(); // 清洗空字符串
 
@@ -416,12 +1138,14 @@ pub async fn update_collection(
     parent_id: Option<Option<i32>>,
     sort_order: Option<i32>,
     icon: Option<Option<String>>,
+    smart_filter: Option<Option<SmartFilter>>,
 ) -> Result<crate::entity::collections::Model, String> {
     let data = UpdateCollectionData {
         name,
         parent_id,
         sort_order,
         icon,
+        smart_filter,
     }
     .cleaned(); // 清洗空字符串
 
@@ -430,6 +1154,22 @@ pub async fn update_collection(
         .map_err(|e| format!("更新合集失败: {}", e))
 }
 
+/// 将合集连同其子合集移动到新的父合集下
+///
+/// # Arguments
+/// * `id` - 合集ID
+/// * `new_parent` - 新的父合集ID，为空时移动为根合集
+#[tauri::command]
+pub async fn move_collection_subtree(
+    db: State<'_, DatabaseConnection>,
+    id: i32,
+    new_parent: Option<i32>,
+) -> Result<crate::entity::collections::Model, String> {
+    CollectionsRepository::move_subtree(&db, id, new_parent)
+        .await
+        .map_err(|e| format!("移动合集失败: {}", e))
+}
+
 /// 删除合集
 #[tauri::command]
 pub async fn delete_collection(db: State<'_, DatabaseConnection>, id: i32) -> Result<u64, String> {
@@ -452,7 +1192,7 @@ pub async fn remove_games_from_collection(
         .map_err(|e| format!("从合集中批量移除游戏失败: {}", e))
 }
 
-/// 获取合集中的所有游戏 ID
+/// 获取合集中的所有游戏 ID，智能合集实时按筛选条件计算
 #[tauri::command]
 pub async fn get_games_in_collection(
     db: State<'_, DatabaseConnection>,
@@ -510,6 +1250,22 @@ pub async fn update_category_games(
         .map_err(|e| format!("批量更新分类游戏失败: {}", e))
 }
 
+/// 按关联记录 ID 顺序批量调整合集内游戏的排序
+///
+/// # Arguments
+/// * `collection_id` - 合集ID
+/// * `ordered_link_ids` - 按新顺序排列的关联记录ID，未列出的记录排在末尾
+#[tauri::command]
+pub async fn reorder_collection_games(
+    db: State<'_, DatabaseConnection>,
+    collection_id: i32,
+    ordered_link_ids: Vec<i32>,
+) -> Result<usize, String> {
+    CollectionsRepository::reorder_games(&db, collection_id, ordered_link_ids)
+        .await
+        .map_err(|e| format!("调整合集游戏排序失败: {}", e))
+}
+
 /// 获取分组中的游戏总数
 #[tauri::command]
 pub async fn count_games_in_group(
@@ -534,3 +1290,43 @@ pub async fn get_categories_with_count(
         .await
         .map_err(|e| format!("获取分类列表失败: {}", e))
 }
+
+// ==================== 数据库维护 ====================
+
+/// 整理数据库（VACUUM + ANALYZE），返回整理前后的大小
+#[tauri::command]
+pub async fn optimize_database(
+    db: State<'_, DatabaseConnection>,
+) -> Result<OptimizeResult, String> {
+    let result = optimize(&db)
+        .await
+        .map_err(|e| format!("整理数据库失败: {}", e))?;
+    log::info!(
+        "数据库整理完成 size_before={} size_after={}",
+        result.size_before,
+        result.size_after
+    );
+    Ok(result)
+}
+
+/// 检查数据库完整性与外键约束
+#[tauri::command]
+pub async fn check_database_integrity(
+    db: State<'_, DatabaseConnection>,
+) -> Result<IntegrityReport, String> {
+    check_integrity(&db)
+        .await
+        .map_err(|e| format!("检查数据库完整性失败: {}", e))
+}
+
+/// 清理引用已删除游戏或合集的孤儿记录（会话、统计、存档备份记录、数据源、合集关联）
+#[tauri::command]
+pub async fn cleanup_orphan_records(
+    db: State<'_, DatabaseConnection>,
+) -> Result<OrphanCleanupResult, String> {
+    let result = cleanup_orphans(&db)
+        .await
+        .map_err(|e| format!("清理孤儿数据失败: {}", e))?;
+    log::info!("孤儿数据清理完成: {:?}", result);
+    Ok(result)
+}
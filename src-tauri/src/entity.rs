@@ -5,10 +5,18 @@
 pub mod prelude;
 
 pub mod custom_data;
+pub mod launch_options;
+pub mod save_options;
+pub mod smart_filter;
 
 // === SeaORM 实体（对应数据库表）===
 pub mod collections;
+pub mod custom_fields;
+pub mod game_characters;
 pub mod game_collection_link;
+pub mod game_custom_field_values;
+pub mod game_notes;
+pub mod game_relations;
 pub mod game_sessions;
 pub mod game_sources;
 pub mod game_statistics;
@@ -1,9 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.16
 
+use super::smart_filter::SmartFilter;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "collections")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -14,6 +15,9 @@ pub struct Model {
     pub sort_order: i32,
     #[sea_orm(column_type = "Text", nullable)]
     pub icon: Option<String>,
+    /// 智能合集筛选条件，为空时为普通合集
+    #[sea_orm(column_type = "Text", nullable)]
+    pub smart_filter: Option<SmartFilter>,
     pub created_at: Option<i32>,
     pub updated_at: Option<i32>,
 }
@@ -0,0 +1,32 @@
+//! 用户自定义字段定义
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "custom_fields")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(column_type = "Text", unique)]
+    pub name: String,
+    /// 字段类型：text / number
+    #[sea_orm(column_type = "Text")]
+    pub field_type: String,
+    pub sort_order: i32,
+    pub created_at: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::game_custom_field_values::Entity")]
+    GameCustomFieldValues,
+}
+
+impl Related<super::game_custom_field_values::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameCustomFieldValues.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,70 @@
+//! 游戏角色与声优（来自 BGM 条目角色列表）
+
+use sea_orm::FromJsonQueryResult;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 角色的声优
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterActor {
+    /// BGM 人物 ID
+    pub id: i64,
+    pub name: String,
+}
+
+/// 角色的声优列表，以 JSON 数组保存
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct CharacterActors(pub Vec<CharacterActor>);
+
+impl CharacterActors {
+    /// 是否有声优名称包含关键词（忽略大小写与首尾空白）
+    pub fn contains_name(&self, keyword: &str) -> bool {
+        let keyword = keyword.trim().to_lowercase();
+        !keyword.is_empty()
+            && self
+                .0
+                .iter()
+                .any(|actor| actor.name.to_lowercase().contains(&keyword))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "game_characters")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub game_id: i32,
+    /// BGM 角色 ID
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub character_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    /// BGM 角色类型：主角 / 配角 / 客串
+    #[sea_orm(column_type = "Text")]
+    pub relation: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub image: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub actors: CharacterActors,
+    /// BGM 角色列表中的顺序
+    pub sort_order: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::games::Entity",
+        from = "Column::GameId",
+        to = "super::games::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Games,
+}
+
+impl Related<super::games::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Games.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,39 @@
+//! 游戏关联关系（来自 VNDB relations）
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "game_relations")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub game_id: i32,
+    /// 关联作品的 VNDB ID（如 `v17`）
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub related_vndb_id: String,
+    /// VNDB 关联类型：seq 续作 / preq 前作 / fan FD / ser 同系列 / side 外传 等
+    #[sea_orm(column_type = "Text")]
+    pub relation: String,
+    /// 是否为官方关联
+    pub official: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::games::Entity",
+        from = "Column::GameId",
+        to = "super::games::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Games,
+}
+
+impl Related<super::games::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Games.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -14,6 +14,10 @@ pub struct Model {
     pub duration: i32,
     #[sea_orm(column_type = "Text")]
     pub date: String,
+    pub exit_code: Option<i32>,
+    /// 会话备注，手动补录时填写
+    #[sea_orm(column_type = "Text", nullable)]
+    pub note: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -6,6 +6,8 @@ use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use super::custom_data::CustomData;
+use super::launch_options::{LaunchArgs, LaunchEnv, LaunchHooks, WineConfig};
+use super::save_options::{SavePaths, SaveRegistryKeys};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "games")]
@@ -23,13 +25,50 @@ pub struct Model {
     pub localpath: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub executable: Option<String>,
+    /// 存档路径，可以有多个
     #[sea_orm(column_type = "Text", nullable)]
-    pub savepath: Option<String>,
+    pub savepath: Option<SavePaths>,
+    /// 随存档一起备份的注册表键
+    #[sea_orm(column_type = "Text", nullable)]
+    pub save_registry_keys: Option<SaveRegistryKeys>,
     pub autosave: Option<i32>,
     pub maxbackups: Option<i32>,
     pub clear: Option<i32>,
     pub le_launch: Option<i32>,
+    /// Locale Emulator 转区配置 GUID，未设置时由 LEProc 自行选择配置
+    #[sea_orm(column_type = "Text", nullable)]
+    pub le_profile: Option<String>,
     pub magpie: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub launch_args: Option<LaunchArgs>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub launch_env: Option<LaunchEnv>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub launch_hooks: Option<LaunchHooks>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub wine_config: Option<WineConfig>,
+    /// 游戏引擎，取值见 [`GameEngine`](crate::game::engine::GameEngine)
+    #[sea_orm(column_type = "Text", nullable)]
+    pub engine: Option<String>,
+    /// 游戏目录已不存在（如移动硬盘未连接）时为 1
+    pub missing: Option<i32>,
+    /// 游戏目录占用空间（字节）缓存，由空间统计命令写入
+    pub folder_size: Option<i64>,
+    /// Bangumi 收藏状态回写状态：pending / synced / failed，未进入回写队列时为空
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bgm_sync_status: Option<String>,
+    /// VNDB 列表标签与投票回写状态，取值同 `bgm_sync_status`
+    #[sea_orm(column_type = "Text", nullable)]
+    pub vndb_sync_status: Option<String>,
+    /// 移入回收站的时间（Unix 时间戳），为空表示未删除
+    pub deleted_at: Option<i32>,
+    /// 游戏目录归档后的压缩包路径，为空表示未归档
+    #[sea_orm(column_type = "Text", nullable)]
+    pub archive_path: Option<String>,
+    /// 是否收藏
+    pub favorite: bool,
+    /// 是否隐藏，隐藏的游戏默认不出现在游戏列表中
+    pub hidden: bool,
 
     // === 用户覆盖元数据 ===
     #[sea_orm(column_type = "Text", nullable)]
@@ -39,12 +78,26 @@ pub struct Model {
     // === 时间戳 ===
     pub created_at: Option<i32>,
     pub updated_at: Option<i32>,
+    /// 游玩状态的修改时间，局域网同步据此逐字段比较新旧
+    pub clear_updated_at: Option<i32>,
+    /// 个人评分（`custom_data.user_rating`）的修改时间
+    pub user_rating_updated_at: Option<i32>,
+    /// 短评（`custom_data.user_review`）的修改时间
+    pub user_review_updated_at: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::game_characters::Entity")]
+    GameCharacters,
     #[sea_orm(has_many = "super::game_collection_link::Entity")]
     GameCollectionLink,
+    #[sea_orm(has_many = "super::game_custom_field_values::Entity")]
+    GameCustomFieldValues,
+    #[sea_orm(has_many = "super::game_notes::Entity")]
+    GameNotes,
+    #[sea_orm(has_many = "super::game_relations::Entity")]
+    GameRelations,
     #[sea_orm(has_many = "super::game_sources::Entity")]
     GameSources,
     #[sea_orm(has_many = "super::game_sessions::Entity")]
@@ -55,12 +108,36 @@ pub enum Relation {
     Savedata,
 }
 
+impl Related<super::game_characters::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameCharacters.def()
+    }
+}
+
 impl Related<super::game_collection_link::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GameCollectionLink.def()
     }
 }
 
+impl Related<super::game_custom_field_values::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameCustomFieldValues.def()
+    }
+}
+
+impl Related<super::game_notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameNotes.def()
+    }
+}
+
+impl Related<super::game_relations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameRelations.def()
+    }
+}
+
 impl Related<super::game_sources::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::GameSources.def()
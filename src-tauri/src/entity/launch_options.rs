@@ -0,0 +1,178 @@
+//! 启动选项 JSON 结构体
+//!
+//! 此文件定义了存储在 games.launch_args / games.launch_env / games.launch_hooks /
+//! games.wine_config 列中的 JSON 数据结构，user.wine_config 复用 [`WineConfig`] 作为全局默认值。
+
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 自定义启动参数（存储为 JSON 字符串数组），按顺序追加到启动命令之后
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct LaunchArgs(pub Vec<String>);
+
+/// 自定义环境变量（存储为 JSON 对象），注入到游戏进程环境
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct LaunchEnv(pub BTreeMap<String, String>);
+
+/// 启动前/游戏结束后依次执行的命令（存储为 JSON）
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct LaunchHooks {
+    /// 启动游戏前执行
+    pub pre_launch: Vec<HookCommand>,
+    /// 游戏会话结束后执行
+    pub post_exit: Vec<HookCommand>,
+}
+
+/// 单条钩子命令
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookCommand {
+    /// 可执行文件路径或 PATH 中的命令名
+    pub program: String,
+    pub args: Vec<String>,
+    /// 工作目录，未设置时使用游戏目录
+    pub working_dir: Option<String>,
+    /// 后台启动而不等待退出（适用于 Textractor 等常驻工具）
+    pub background: bool,
+    /// 等待退出的超时秒数，未设置时使用默认值
+    pub timeout_secs: Option<u64>,
+}
+
+/// Linux 下运行 Windows 游戏使用的兼容层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WineRunner {
+    Wine,
+    Proton,
+}
+
+/// Wine/Proton 启动配置（存储为 JSON）
+///
+/// 所有字段均可缺省：游戏级配置中缺省的字段回退到全局配置。
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct WineConfig {
+    pub runner: Option<WineRunner>,
+    /// wine 可执行文件或 proton 脚本路径
+    pub runner_path: Option<String>,
+    /// Wine 前缀目录；Proton 模式下作为 STEAM_COMPAT_DATA_PATH
+    pub prefix: Option<String>,
+    /// 是否启用 DXVK（false 时强制使用 WineD3D）
+    pub dxvk: Option<bool>,
+    /// DXVK_HUD 环境变量，例如 `fps,devinfo`
+    pub dxvk_hud: Option<String>,
+}
+
+impl WineConfig {
+    /// 以当前配置为准，缺省字段回退到 `fallback`
+    #[cfg(target_os = "linux")]
+    pub fn merged_with(self, fallback: Option<&WineConfig>) -> WineConfig {
+        let Some(fallback) = fallback else {
+            return self;
+        };
+        WineConfig {
+            runner: self.runner.or(fallback.runner),
+            runner_path: self.runner_path.or_else(|| fallback.runner_path.clone()),
+            prefix: self.prefix.or_else(|| fallback.prefix.clone()),
+            dxvk: self.dxvk.or(fallback.dxvk),
+            dxvk_hud: self.dxvk_hud.or_else(|| fallback.dxvk_hud.clone()),
+        }
+    }
+
+    /// 去除空字符串字段，全部缺省时返回 None
+    pub fn cleaned(self) -> Option<Self> {
+        let clean = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let config = WineConfig {
+            runner: self.runner,
+            runner_path: clean(self.runner_path),
+            prefix: clean(self.prefix),
+            dxvk: self.dxvk,
+            dxvk_hud: clean(self.dxvk_hud),
+        };
+        (config != WineConfig::default()).then_some(config)
+    }
+}
+
+impl LaunchArgs {
+    /// 去除空参数，全部为空时返回 None
+    pub fn cleaned(self) -> Option<Self> {
+        let args: Vec<String> = self
+            .0
+            .into_iter()
+            .filter(|arg| !arg.trim().is_empty())
+            .collect();
+        (!args.is_empty()).then_some(Self(args))
+    }
+}
+
+impl LaunchEnv {
+    /// 去除变量名为空或包含 `=`、NUL 的非法项，全部无效时返回 None
+    pub fn cleaned(self) -> Option<Self> {
+        let vars: BTreeMap<String, String> = self
+            .0
+            .into_iter()
+            .map(|(key, value)| (key.trim().to_string(), value))
+            .filter(|(key, value)| {
+                !key.is_empty() && !key.contains(['=', '\0']) && !value.contains('\0')
+            })
+            .collect();
+        (!vars.is_empty()).then_some(Self(vars))
+    }
+}
+
+impl LaunchHooks {
+    /// 去除未填写程序路径的命令，全部为空时返回 None
+    pub fn cleaned(self) -> Option<Self> {
+        let clean = |commands: Vec<HookCommand>| -> Vec<HookCommand> {
+            commands
+                .into_iter()
+                .filter_map(|mut command| {
+                    command.program = command.program.trim().to_string();
+                    command.working_dir = command
+                        .working_dir
+                        .map(|dir| dir.trim().to_string())
+                        .filter(|dir| !dir.is_empty());
+                    (!command.program.is_empty()).then_some(command)
+                })
+                .collect()
+        };
+        let hooks = Self {
+            pre_launch: clean(self.pre_launch),
+            post_exit: clean(self.post_exit),
+        };
+        (!hooks.pre_launch.is_empty() || !hooks.post_exit.is_empty()).then_some(hooks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleaned_drops_empty_and_invalid_entries() {
+        let args = LaunchArgs(vec!["-fullscreen".into(), " ".into(), String::new()]);
+        assert_eq!(args.cleaned(), Some(LaunchArgs(vec!["-fullscreen".into()])));
+        assert_eq!(LaunchArgs(vec![String::new()]).cleaned(), None);
+
+        let env = LaunchEnv(BTreeMap::from([
+            (" DXVK_HUD ".to_string(), "fps".to_string()),
+            ("A=B".to_string(), "1".to_string()),
+            (String::new(), "1".to_string()),
+        ]));
+        assert_eq!(
+            env.cleaned(),
+            Some(LaunchEnv(BTreeMap::from([(
+                "DXVK_HUD".to_string(),
+                "fps".to_string()
+            )])))
+        );
+    }
+}
@@ -4,7 +4,12 @@
 
 // === SeaORM 实体 ===
 pub use super::collections::Entity as Collections;
+pub use super::custom_fields::Entity as CustomFields;
+pub use super::game_characters::Entity as GameCharacters;
 pub use super::game_collection_link::Entity as GameCollectionLink;
+pub use super::game_custom_field_values::Entity as GameCustomFieldValues;
+pub use super::game_notes::Entity as GameNotes;
+pub use super::game_relations::Entity as GameRelations;
 pub use super::game_sessions::Entity as GameSessions;
 pub use super::game_sources::Entity as GameSources;
 pub use super::game_statistics::Entity as GameStatistics;
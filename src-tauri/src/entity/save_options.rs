@@ -0,0 +1,172 @@
+//! 存档选项 JSON 结构体
+//!
+//! 此文件定义了存储在 games.savepath / games.save_registry_keys 列中的 JSON 数据结构。
+
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// 存档路径（存储为 JSON 字符串数组），每一项都可以使用存档路径模板
+///
+/// 存档分散在多处（如游戏目录与 AppData）时配置多个路径，备份与恢复时全部处理。
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct SavePaths(pub Vec<String>);
+
+/// 兼容旧版的单个路径字符串（旧资料库备份、导入数据）
+impl<'de> Deserialize<'de> for SavePaths {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            One(String),
+            Many(Vec<String>),
+        }
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::One(path) => Self(vec![path]),
+            Raw::Many(paths) => Self(paths),
+        })
+    }
+}
+
+impl SavePaths {
+    /// 去除空路径与重复路径，全部为空时返回 None
+    pub fn cleaned(self) -> Option<Self> {
+        let mut paths: Vec<String> = Vec::new();
+        for path in self.0 {
+            let path = path.trim().to_string();
+            if !path.is_empty() && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        (!paths.is_empty()).then_some(Self(paths))
+    }
+
+    /// 逐项替换路径，`replace` 返回 None 的项保持不变；没有任何一项变化时返回 None
+    pub fn replaced(&self, mut replace: impl FnMut(&str) -> Option<String>) -> Option<Self> {
+        let mut changed = false;
+        let paths = self
+            .0
+            .iter()
+            .map(|path| match replace(path) {
+                Some(replaced) if replaced != *path => {
+                    changed = true;
+                    replaced
+                }
+                _ => path.clone(),
+            })
+            .collect();
+        changed.then_some(Self(paths))
+    }
+}
+
+/// 随存档一起备份的注册表键（存储为 JSON 字符串数组），只支持 `HKEY_CURRENT_USER` 下的键
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct SaveRegistryKeys(pub Vec<String>);
+
+/// 注册表根键的完整写法与缩写
+const CURRENT_USER: &str = "HKEY_CURRENT_USER";
+const CURRENT_USER_SHORT: &str = "HKCU";
+
+/// 规范化注册表键路径：统一使用 `\` 分隔并把 `HKCU` 展开为 `HKEY_CURRENT_USER`
+///
+/// 不属于 `HKEY_CURRENT_USER` 或没有子键时返回错误，避免导入时改写系统级配置。
+pub fn normalize_registry_key(key: &str) -> Result<String, String> {
+    let parts: Vec<&str> = key
+        .trim()
+        .split(['\\', '/'])
+        .filter(|part| !part.trim().is_empty())
+        .collect();
+    match parts.split_first() {
+        Some((root, rest))
+            if !rest.is_empty()
+                && (root.eq_ignore_ascii_case(CURRENT_USER)
+                    || root.eq_ignore_ascii_case(CURRENT_USER_SHORT)) =>
+        {
+            Ok(format!("{}\\{}", CURRENT_USER, rest.join("\\")))
+        }
+        _ => Err(format!(
+            "只支持备份 HKEY_CURRENT_USER 下的注册表键: {}",
+            key.trim()
+        )),
+    }
+}
+
+impl SaveRegistryKeys {
+    /// 去除空项与重复项，能规范化的键统一写法，全部为空时返回 None
+    ///
+    /// 不合法的键原样保留，由备份时报错提示用户修改。
+    pub fn cleaned(self) -> Option<Self> {
+        let mut keys: Vec<String> = Vec::new();
+        for key in self.0 {
+            let key = normalize_registry_key(&key).unwrap_or_else(|_| key.trim().to_string());
+            if !key.is_empty()
+                && !keys
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(&key))
+            {
+                keys.push(key);
+            }
+        }
+        (!keys.is_empty()).then_some(Self(keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_paths_accept_legacy_string_and_clean() {
+        let legacy: SavePaths = serde_json::from_str(r#""/saves/A""#).unwrap();
+        assert_eq!(legacy, SavePaths(vec!["/saves/A".to_string()]));
+        let paths: SavePaths =
+            serde_json::from_str(r#"[" /saves/A ", "", "/saves/A", "/saves/B"]"#).unwrap();
+        assert_eq!(
+            paths.cleaned(),
+            Some(SavePaths(vec![
+                "/saves/A".to_string(),
+                "/saves/B".to_string()
+            ]))
+        );
+        assert_eq!(
+            serde_json::to_string(&SavePaths(vec!["/saves/A".to_string()])).unwrap(),
+            r#"["/saves/A"]"#
+        );
+
+        let paths = SavePaths(vec!["/old/A".to_string(), "/other/B".to_string()]);
+        let replaced =
+            paths.replaced(|path| path.strip_prefix("/old").map(|rest| format!("/new{rest}")));
+        assert_eq!(
+            replaced,
+            Some(SavePaths(vec![
+                "/new/A".to_string(),
+                "/other/B".to_string()
+            ]))
+        );
+        assert_eq!(paths.replaced(|_| None), None);
+    }
+
+    #[test]
+    fn normalizes_current_user_keys_only() {
+        assert_eq!(
+            normalize_registry_key(r" HKCU\Software\Key\Clannad\ ").unwrap(),
+            r"HKEY_CURRENT_USER\Software\Key\Clannad"
+        );
+        assert!(normalize_registry_key(r"HKEY_LOCAL_MACHINE\Software\Key").is_err());
+        assert!(normalize_registry_key("HKCU").is_err());
+
+        let keys = SaveRegistryKeys(vec![
+            r"hkcu\Software\Key".to_string(),
+            r"HKEY_CURRENT_USER\Software\Key".to_string(),
+            " ".to_string(),
+        ]);
+        assert_eq!(
+            keys.cleaned(),
+            Some(SaveRegistryKeys(vec![
+                r"HKEY_CURRENT_USER\Software\Key".to_string()
+            ]))
+        );
+        assert_eq!(SaveRegistryKeys(vec![String::new()]).cleaned(), None);
+    }
+}
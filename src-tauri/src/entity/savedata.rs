@@ -13,6 +13,17 @@ pub struct Model {
     pub file: String,
     pub backup_time: i32,
     pub file_size: i32,
+    /// 锁定的备份不参与保留策略与批量删除
+    #[serde(default)]
+    pub pinned: bool,
+    /// 简短标签，如“true end 前”
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub label: Option<String>,
+    /// 备注说明
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
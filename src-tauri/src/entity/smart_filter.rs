@@ -0,0 +1,40 @@
+//! 智能合集筛选条件 JSON 结构体
+//!
+//! 存储在 collections.smart_filter 列中。设置了筛选条件的合集为智能合集，
+//! 其中的游戏由条件实时计算，不读取 game_collection_link 表。
+
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+
+/// 智能合集筛选条件，各条件之间为"且"关系，未设置的条件不参与筛选
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, FromJsonQueryResult)]
+#[serde(default)]
+pub struct SmartFilter {
+    /// 需同时包含的标签
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// 开发商，命中任意一个即可
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub developers: Vec<String>,
+
+    /// 游玩状态（games.clear），命中任意一个即可
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub play_status: Vec<i32>,
+
+    /// 个人评分下限（0-10），未评分的游戏不匹配
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_rating: Option<f64>,
+
+    /// 个人评分上限（0-10），未评分的游戏不匹配
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_rating: Option<f64>,
+
+    /// 发行年份下限，无发行日期的游戏不匹配
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_from: Option<i32>,
+
+    /// 发行年份上限，无发行日期的游戏不匹配
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_to: Option<i32>,
+}
@@ -3,6 +3,9 @@
 use sea_orm::FromJsonQueryResult;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::launch_options::WineConfig;
 
 /// BGM 授权信息。
 ///
@@ -17,6 +20,418 @@ pub struct BgmAuth {
     pub nickname: Option<String>,
 }
 
+/// Discord Rich Presence 设置。
+///
+/// 文案模板支持 `{name}`（游戏名）与 `{playtime}`（累计游玩时长）占位符。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiscordRpcSettings {
+    pub enabled: bool,
+    pub details_template: Option<String>,
+    pub state_template: Option<String>,
+    /// 隐藏 NSFW 作品的名称与封面
+    pub hide_nsfw: bool,
+    /// 隐藏 NSFW 作品时替代 `{name}` 的文案
+    pub nsfw_placeholder: Option<String>,
+}
+
+/// 游戏库根目录监听设置。
+///
+/// 开启后后台定期检查 `roots` 下的一级目录：出现未入库的新文件夹时提示入库，
+/// 已入库的游戏目录消失时标记为文件缺失。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LibraryWatchSettings {
+    pub enabled: bool,
+    pub roots: Vec<String>,
+}
+
+impl LibraryWatchSettings {
+    /// 去除空白与重复的根目录
+    pub fn cleaned(self) -> Self {
+        let mut roots: Vec<String> = Vec::with_capacity(self.roots.len());
+        for root in self.roots {
+            let root = root.trim().to_string();
+            if !root.is_empty() && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        Self {
+            enabled: self.enabled,
+            roots,
+        }
+    }
+}
+
+/// 存档备份的压缩方式，均使用 7z 容器
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupCompression {
+    /// 仅打包不压缩，适合已压缩过的大体积存档
+    Store,
+    /// 压缩率最高，速度较慢
+    Lzma2,
+    /// 速度与压缩率折中
+    #[default]
+    Zstd,
+}
+
+impl BackupCompression {
+    /// 压缩等级的取值范围与未指定时的默认等级
+    fn level_range(self) -> Option<(u32, u32, u32)> {
+        match self {
+            BackupCompression::Store => None,
+            BackupCompression::Lzma2 => Some((0, 9, 6)),
+            BackupCompression::Zstd => Some((1, 22, 3)),
+        }
+    }
+
+    /// 将压缩等级限制在有效范围内，未指定时使用默认等级
+    pub fn effective_level(self, level: Option<u32>) -> Option<u32> {
+        self.level_range()
+            .map(|(min, max, default)| level.map_or(default, |level| level.clamp(min, max)))
+    }
+}
+
+/// 存档备份设置。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// 新建备份默认使用的压缩方式
+    pub compression: BackupCompression,
+    /// 压缩等级，为空时使用压缩方式的默认等级
+    pub compression_level: Option<u32>,
+    /// 开启后新建备份必须提供密码，以 AES-256 加密
+    pub encrypt_by_default: bool,
+    /// 在游戏的最大备份数量之外额外保留的备份
+    pub retention: BackupRetention,
+    /// 数据库定时备份周期
+    pub db_backup_interval: DbBackupInterval,
+    /// 定时备份保留的份数，为空时使用默认值
+    pub db_backup_keep: Option<usize>,
+}
+
+/// 数据库定时备份周期
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DbBackupInterval {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DbBackupInterval {
+    /// 两次定时备份的间隔秒数，关闭时为 None
+    pub fn period_secs(self) -> Option<u64> {
+        match self {
+            DbBackupInterval::Off => None,
+            DbBackupInterval::Daily => Some(24 * 60 * 60),
+            DbBackupInterval::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// 存档备份保留策略，各项规则取并集，均为空时只按最大备份数量轮转。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackupRetention {
+    /// 保留最近 N 天内的全部备份
+    pub keep_days: Option<u32>,
+    /// 最近 N 个有备份的日期各保留当天最新的一份
+    pub keep_daily: Option<u32>,
+    /// 最近 N 个有备份的周各保留该周最新的一份
+    pub keep_weekly: Option<u32>,
+}
+
+impl BackupSettings {
+    /// 将压缩等级限制在当前压缩方式的有效范围内
+    pub fn cleaned(self) -> Self {
+        Self {
+            compression_level: self
+                .compression_level
+                .and_then(|level| self.compression.effective_level(Some(level))),
+            ..self
+        }
+    }
+}
+
+/// 网络设置，作用于后端共享的 HTTP 客户端（元数据抓取、封面下载等）。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// 代理地址（`http://` 或 `https://`），为空时直连
+    pub proxy_url: Option<String>,
+    /// 自定义 User-Agent，为空时使用内置值
+    pub user_agent: Option<String>,
+    /// 单次请求超时秒数，为空时使用默认值
+    pub timeout_secs: Option<u64>,
+    /// 连接失败、超时或服务端错误时的重试次数，为空时使用默认值
+    pub max_retries: Option<u32>,
+}
+
+impl NetworkSettings {
+    /// 超时的有效范围（秒）
+    pub const TIMEOUT_RANGE: (u64, u64) = (5, 300);
+    /// 重试次数上限
+    pub const MAX_RETRIES: u32 = 5;
+
+    /// 清理空字符串，并将超时与重试次数限制在有效范围内
+    pub fn cleaned(self) -> Self {
+        let trimmed = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let (min_timeout, max_timeout) = Self::TIMEOUT_RANGE;
+        Self {
+            proxy_url: trimmed(self.proxy_url),
+            user_agent: trimmed(self.user_agent),
+            timeout_secs: self
+                .timeout_secs
+                .map(|secs| secs.clamp(min_timeout, max_timeout)),
+            max_retries: self
+                .max_retries
+                .map(|retries| retries.min(Self::MAX_RETRIES)),
+        }
+    }
+}
+
+/// 会话事件 Webhook 设置：会话开始/结束、游玩状态变更时向 `url` POST JSON。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub url: Option<String>,
+    /// 不发送 NSFW 作品的名称与封面
+    pub hide_nsfw: bool,
+}
+
+impl WebhookSettings {
+    /// 清理空白地址
+    pub fn cleaned(self) -> Self {
+        Self {
+            url: self
+                .url
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty()),
+            ..self
+        }
+    }
+}
+
+/// OBS「正在游玩」文本输出设置：会话期间把渲染后的文案写入 `path`，结束后清空。
+///
+/// 文案模板支持 `{name}`（游戏名）、`{playtime}`（含本次的累计时长）与 `{session}`（本次时长）占位符。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ObsOutputSettings {
+    pub enabled: bool,
+    pub path: Option<String>,
+    pub template: Option<String>,
+    /// 隐藏 NSFW 作品的名称
+    pub hide_nsfw: bool,
+}
+
+impl ObsOutputSettings {
+    /// 清理空白路径与模板
+    pub fn cleaned(self) -> Self {
+        Self {
+            path: self
+                .path
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty()),
+            template: self.template.filter(|template| !template.trim().is_empty()),
+            ..self
+        }
+    }
+}
+
+/// 局域网远程控制接口设置：只读的游戏库与统计查询、远程启动游戏。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RemoteApiSettings {
+    pub enabled: bool,
+    /// 监听端口，为空时使用默认端口
+    pub port: Option<u16>,
+    /// 访问令牌，请求需携带 `Authorization: Bearer <token>`
+    pub token: Option<String>,
+}
+
+impl RemoteApiSettings {
+    /// 默认监听端口
+    pub const DEFAULT_PORT: u16 = 17890;
+    /// 访问令牌的最短长度
+    pub const MIN_TOKEN_LEN: usize = 8;
+
+    /// 清理空白令牌，端口 0 视为未设置
+    pub fn cleaned(self) -> Self {
+        Self {
+            enabled: self.enabled,
+            port: self.port.filter(|port| *port != 0),
+            token: self
+                .token
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty()),
+        }
+    }
+
+    /// 实际监听的端口
+    pub fn port_value(&self) -> u16 {
+        self.port.unwrap_or(Self::DEFAULT_PORT)
+    }
+}
+
+/// 元数据展示字段的合并策略：字段名到数据源优先级的映射。
+///
+/// 未配置的字段按 `id_type` 对应的数据源优先，其余依次为 bgm、vndb、ymgal、kun。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetadataMergeStrategy {
+    /// 如 `{ "title": ["bgm"], "image": ["vndb"], "summary": ["ymgal"] }`
+    pub fields: BTreeMap<String, Vec<String>>,
+}
+
+impl MetadataMergeStrategy {
+    /// 可配置优先级的字段，`title` 为各数据源中文名优先的展示标题
+    pub const FIELDS: [&str; 10] = [
+        "title",
+        "name",
+        "name_cn",
+        "image",
+        "summary",
+        "tags",
+        "aliases",
+        "date",
+        "developer",
+        "score",
+    ];
+    /// 可参与合并的数据源
+    pub const SOURCES: [&str; 4] = ["bgm", "vndb", "ymgal", "kun"];
+
+    /// 去掉未知的字段与数据源，数据源去重，空列表视为未配置
+    pub fn cleaned(self) -> Self {
+        let fields = self
+            .fields
+            .into_iter()
+            .filter(|(field, _)| Self::FIELDS.contains(&field.as_str()))
+            .filter_map(|(field, sources)| {
+                let mut cleaned: Vec<String> = Vec::new();
+                for source in sources {
+                    let source = source.trim().to_ascii_lowercase();
+                    if Self::SOURCES.contains(&source.as_str()) && !cleaned.contains(&source) {
+                        cleaned.push(source);
+                    }
+                }
+                (!cleaned.is_empty()).then_some((field, cleaned))
+            })
+            .collect();
+        Self { fields }
+    }
+
+    /// 指定字段配置的数据源优先级，未配置时为空
+    pub fn priority(&self, field: &str) -> &[String] {
+        self.fields
+            .get(field)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// 扫描目录时排序与排除启动程序的关键字规则。
+///
+/// 关键字按不区分大小写的子串匹配 exe 文件名（不含扩展名）：
+/// 命中 `priority_keywords` 的排在前面，命中 `demote_keywords` 的排在后面，
+/// 命中 `exclude_keywords` 的直接剔除。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ScanExeRules {
+    pub priority_keywords: Vec<String>,
+    pub demote_keywords: Vec<String>,
+    pub exclude_keywords: Vec<String>,
+}
+
+/// 内置的优先关键字（汉化版启动程序）
+const DEFAULT_PRIORITY_KEYWORDS: &[&str] = &["chs", "cn", "汉化", "中文"];
+
+/// 内置的置后关键字（设置程序等）
+const DEFAULT_DEMOTE_KEYWORDS: &[&str] = &["config", "setting"];
+
+/// 内置的排除关键字（卸载、安装、运行库与崩溃处理程序）
+const DEFAULT_EXCLUDE_KEYWORDS: &[&str] = &[
+    "unins",
+    "uninst",
+    "uninstall",
+    "setup",
+    "install",
+    "dxsetup",
+    "vcredist",
+    "vc_redist",
+    "dotnetfx",
+    "oalinst",
+    "crashhandler",
+    "crash_handler",
+    "crashreport",
+    "crash_report",
+    "bugreport",
+    "bug_report",
+    "unitycrashandler",
+];
+
+impl Default for ScanExeRules {
+    fn default() -> Self {
+        let owned = |keywords: &[&str]| keywords.iter().map(|k| k.to_string()).collect();
+        Self {
+            priority_keywords: owned(DEFAULT_PRIORITY_KEYWORDS),
+            demote_keywords: owned(DEFAULT_DEMOTE_KEYWORDS),
+            exclude_keywords: owned(DEFAULT_EXCLUDE_KEYWORDS),
+        }
+    }
+}
+
+impl ScanExeRules {
+    /// 统一转为小写、去除空白与重复关键字
+    pub fn cleaned(self) -> Self {
+        fn clean(keywords: Vec<String>) -> Vec<String> {
+            let mut cleaned: Vec<String> = Vec::with_capacity(keywords.len());
+            for keyword in keywords {
+                let keyword = keyword.trim().to_lowercase();
+                if !keyword.is_empty() && !cleaned.contains(&keyword) {
+                    cleaned.push(keyword);
+                }
+            }
+            cleaned
+        }
+
+        Self {
+            priority_keywords: clean(self.priority_keywords),
+            demote_keywords: clean(self.demote_keywords),
+            exclude_keywords: clean(self.exclude_keywords),
+        }
+    }
+
+    fn matches(keywords: &[String], lower_name: &str) -> bool {
+        keywords
+            .iter()
+            .any(|keyword| lower_name.contains(keyword.as_str()))
+    }
+
+    /// 文件名（小写）是否命中优先关键字
+    pub fn is_priority(&self, lower_name: &str) -> bool {
+        Self::matches(&self.priority_keywords, lower_name)
+    }
+
+    /// 文件名（小写）是否命中置后关键字
+    pub fn is_demoted(&self, lower_name: &str) -> bool {
+        Self::matches(&self.demote_keywords, lower_name)
+    }
+
+    /// 文件名（小写）是否命中排除关键字
+    pub fn is_excluded(&self, lower_name: &str) -> bool {
+        Self::matches(&self.exclude_keywords, lower_name)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "user")]
 pub struct Model {
@@ -34,6 +449,35 @@ pub struct Model {
     pub le_path: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub magpie_path: Option<String>,
+    pub prevent_sleep: Option<bool>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub discord_rpc: Option<DiscordRpcSettings>,
+    /// Linux 下 Wine/Proton 的全局默认配置
+    #[sea_orm(column_type = "Text", nullable)]
+    pub wine_config: Option<WineConfig>,
+    /// 扫描目录时的 exe 排序与排除规则，未设置时使用内置规则
+    #[sea_orm(column_type = "Text", nullable)]
+    pub scan_exe_rules: Option<ScanExeRules>,
+    /// 游戏库根目录监听设置，未设置时不监听
+    #[sea_orm(column_type = "Text", nullable)]
+    pub library_watch: Option<LibraryWatchSettings>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub backup_settings: Option<BackupSettings>,
+    /// 安全模式：游戏列表过滤 NSFW 作品，封面返回打码缩略图
+    pub safe_mode: Option<bool>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub network_settings: Option<NetworkSettings>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub metadata_merge_strategy: Option<MetadataMergeStrategy>,
+    /// 局域网远程控制接口设置，未设置时不启动
+    #[sea_orm(column_type = "Text", nullable)]
+    pub remote_api: Option<RemoteApiSettings>,
+    /// 会话事件 Webhook 设置，未设置时不发送
+    #[sea_orm(column_type = "Text", nullable)]
+    pub webhook: Option<WebhookSettings>,
+    /// OBS「正在游玩」文本输出设置，未设置时不输出
+    #[sea_orm(column_type = "Text", nullable)]
+    pub obs_output: Option<ObsOutputSettings>,
 }
 
 impl Model {
@@ -54,6 +498,61 @@ impl Model {
     pub fn magpie_path_value(&self) -> Option<&str> {
         self.magpie_path.as_deref()
     }
+
+    /// 游玩期间是否阻止系统休眠，未设置时默认关闭
+    pub fn prevent_sleep_enabled(&self) -> bool {
+        self.prevent_sleep.unwrap_or(false)
+    }
+
+    /// 是否开启安全模式，未设置时默认关闭
+    pub fn safe_mode_enabled(&self) -> bool {
+        self.safe_mode.unwrap_or(false)
+    }
+
+    /// Discord Rich Presence 设置，未设置时默认关闭
+    pub fn discord_rpc_settings(&self) -> DiscordRpcSettings {
+        self.discord_rpc.clone().unwrap_or_default()
+    }
+
+    /// 扫描目录时的 exe 规则，未设置时使用内置规则
+    pub fn scan_exe_rules(&self) -> ScanExeRules {
+        self.scan_exe_rules.clone().unwrap_or_default()
+    }
+
+    /// 游戏库根目录监听设置，未设置时默认关闭
+    pub fn library_watch_settings(&self) -> LibraryWatchSettings {
+        self.library_watch.clone().unwrap_or_default()
+    }
+
+    /// 存档备份设置，未设置时使用 Zstd 默认等级
+    pub fn backup_settings(&self) -> BackupSettings {
+        self.backup_settings.clone().unwrap_or_default()
+    }
+
+    /// 网络设置，未设置时直连并使用默认超时与重试次数
+    pub fn network_settings(&self) -> NetworkSettings {
+        self.network_settings.clone().unwrap_or_default()
+    }
+
+    /// 元数据字段合并策略，未设置时全部字段使用默认优先级
+    pub fn metadata_merge_strategy(&self) -> MetadataMergeStrategy {
+        self.metadata_merge_strategy.clone().unwrap_or_default()
+    }
+
+    /// 局域网远程控制接口设置，未设置时默认关闭
+    pub fn remote_api_settings(&self) -> RemoteApiSettings {
+        self.remote_api.clone().unwrap_or_default()
+    }
+
+    /// 会话事件 Webhook 设置，未设置时默认关闭
+    pub fn webhook_settings(&self) -> WebhookSettings {
+        self.webhook.clone().unwrap_or_default()
+    }
+
+    /// OBS 文本输出设置，未设置时默认关闭
+    pub fn obs_output_settings(&self) -> ObsOutputSettings {
+        self.obs_output.clone().unwrap_or_default()
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,160 @@
+//! 命令的结构化错误
+//!
+//! 命令返回 [`AppError`] 时前端收到 `{ code, params, message, detail }`：`code` 用于分支处理与
+//! i18n，`params` 是翻译插值所需的参数，`message` 保留中文描述供尚未适配的界面直接展示。
+//!
+//! 迁移是渐进的：`AppError` 可以由 `String` 转换（记为 `internal_error`），
+//! 因此仍返回 `Result<_, String>` 的辅助函数在已迁移的命令里可以直接使用 `?`。
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{Value, json};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    GameNotFound {
+        game_id: i32,
+    },
+    /// 游戏不在回收站中，无法恢复
+    GameNotInTrash {
+        game_id: i32,
+    },
+    /// 开启了默认加密备份，但没有保存默认备份密码
+    BackupPasswordRequired {
+        game_id: i32,
+    },
+    /// 参数不合法，`field` 为参数名
+    InvalidArgument {
+        field: String,
+        message: String,
+    },
+    /// 数据库操作失败，`context` 描述正在进行的操作
+    Database {
+        context: String,
+        detail: String,
+    },
+    /// 尚未细分的错误
+    Internal {
+        message: String,
+    },
+}
+
+impl AppError {
+    /// 生成数据库错误的转换函数，用于 `.map_err(AppError::database("查询游戏数据失败"))`
+    pub fn database<E: fmt::Display>(context: &'static str) -> impl FnOnce(E) -> Self {
+        move |e| AppError::Database {
+            context: context.to_string(),
+            detail: e.to_string(),
+        }
+    }
+
+    pub fn invalid_argument(field: &str, message: impl Into<String>) -> Self {
+        AppError::InvalidArgument {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// 前端据此区分错误类型的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::GameNotFound { .. } => "game_not_found",
+            AppError::GameNotInTrash { .. } => "game_not_in_trash",
+            AppError::BackupPasswordRequired { .. } => "backup_password_required",
+            AppError::InvalidArgument { .. } => "invalid_argument",
+            AppError::Database { .. } => "database_error",
+            AppError::Internal { .. } => "internal_error",
+        }
+    }
+
+    /// 翻译插值参数
+    fn params(&self) -> Value {
+        match self {
+            AppError::GameNotFound { game_id }
+            | AppError::GameNotInTrash { game_id }
+            | AppError::BackupPasswordRequired { game_id } => {
+                json!({ "gameId": game_id })
+            }
+            AppError::InvalidArgument { field, .. } => json!({ "field": field }),
+            AppError::Database { context, .. } => json!({ "context": context }),
+            AppError::Internal { .. } => json!({}),
+        }
+    }
+
+    /// 底层错误信息，仅用于排查
+    fn detail(&self) -> Option<&str> {
+        match self {
+            AppError::Database { detail, .. } => Some(detail),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::GameNotFound { game_id } => write!(f, "游戏不存在: {}", game_id),
+            AppError::GameNotInTrash { game_id } => write!(f, "游戏不在回收站中: {}", game_id),
+            AppError::BackupPasswordRequired { game_id } => {
+                write!(f, "已开启默认加密备份，但未保存默认备份密码: {}", game_id)
+            }
+            AppError::InvalidArgument { message, .. } => f.write_str(message),
+            AppError::Database { context, detail } => write!(f, "{}: {}", context, detail),
+            AppError::Internal { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("params", &self.params())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("detail", &self.detail())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_params_and_message() {
+        assert_eq!(
+            serde_json::to_value(AppError::GameNotFound { game_id: 7 }).unwrap(),
+            json!({
+                "code": "game_not_found",
+                "params": { "gameId": 7 },
+                "message": "游戏不存在: 7",
+                "detail": null,
+            })
+        );
+
+        let error = AppError::database("查询游戏数据失败")("database is locked");
+        assert_eq!(error.to_string(), "查询游戏数据失败: database is locked");
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "database_error");
+        assert_eq!(value["detail"], "database is locked");
+
+        let error: AppError = "读取设置失败".to_string().into();
+        assert_eq!(error.code(), "internal_error");
+    }
+}
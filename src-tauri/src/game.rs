@@ -1,4 +1,21 @@
+pub mod archive;
+pub mod characters;
 pub mod cover;
+pub mod desktop_shortcut;
+pub mod developers;
+pub mod duplicates;
+pub mod engine;
+pub mod hooks;
 pub mod launch;
 pub mod monitor;
+pub mod relations;
+pub mod relocate;
+pub mod report;
+pub mod save_detect;
 pub mod scan;
+pub mod screenshots;
+pub mod size;
+pub mod steam;
+pub mod summary;
+pub mod uninstall;
+pub mod watcher;
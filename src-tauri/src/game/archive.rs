@@ -0,0 +1,274 @@
+//! 游戏目录归档
+//!
+//! 把暂时不玩的游戏目录以 Zstd 压缩为 7z 存到其他磁盘，重新读取压缩包确认文件数与总大小
+//! 与原目录一致后才删除原目录（默认永久删除以释放空间，可选移入回收站）并记录 `archive_path`；
+//! 恢复时解压回原来的 `localpath` 并删除压缩包。进度通过 `task-progress` 事件上报，可以取消，
+//! 取消或失败时删除未完成的压缩包或已解压的文件，原目录或压缩包保持不变。
+
+use crate::backup::archive::{
+    ARCHIVE_CANCELLED, create_7z_archive_with_progress, extract_7z_archive_with_progress,
+    list_7z_archive_files,
+};
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::entity::user::BackupCompression;
+use crate::game::monitor::is_game_monitored;
+use crate::game::size::dir_size;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use crate::utils::trash::remove_path;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime, State, command};
+use walkdir::WalkDir;
+
+async fn load_game(db: &DatabaseConnection, game_id: i32) -> Result<FullGameData, String> {
+    GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))
+}
+
+/// 把取消导致的错误统一为取消提示
+fn archive_error(error: Box<dyn std::error::Error>) -> String {
+    let message = error.to_string();
+    if message.contains(ARCHIVE_CANCELLED) {
+        ARCHIVE_CANCELLED.to_string()
+    } else {
+        message
+    }
+}
+
+/// 统计目录中的非目录条目数与文件总大小，读取失败时返回错误
+///
+/// 符号链接等特殊文件也计入条目数：压缩包不包含它们，校验时会因数量不一致而失败。
+fn source_summary(dir: &Path) -> Result<(usize, u64), String> {
+    let mut count = 0;
+    let mut size = 0;
+    for entry in WalkDir::new(dir).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(|e| format!("读取游戏目录失败: {}", e))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        count += 1;
+        if entry.file_type().is_file() {
+            size += entry
+                .metadata()
+                .map_err(|e| format!("读取文件信息失败 {}: {}", entry.path().display(), e))?
+                .len();
+        }
+    }
+    Ok((count, size))
+}
+
+/// 重新打开压缩包，确认文件数与总大小和原目录一致
+fn verify_archive(archive: &Path, source: &Path) -> Result<(), String> {
+    let (source_count, source_size) = source_summary(source)?;
+    let files =
+        list_7z_archive_files(archive, None).map_err(|e| format!("校验压缩包失败: {}", e))?;
+    let archive_size: u64 = files.iter().map(|file| file.size).sum();
+    if files.len() != source_count || archive_size != source_size {
+        return Err(format!(
+            "压缩包校验不一致，已保留原目录：原目录 {} 个文件 {} 字节，压缩包 {} 个文件 {} 字节",
+            source_count,
+            source_size,
+            files.len(),
+            archive_size
+        ));
+    }
+    Ok(())
+}
+
+/// 归档结果
+#[derive(Debug, Serialize)]
+pub struct ArchiveGameResult {
+    pub game: FullGameData,
+    /// 实际释放的磁盘空间（字节），原目录移入回收站或删除失败时为 0
+    pub freed_bytes: u64,
+}
+
+/// 把游戏目录压缩归档到 `target_dir/<目录名>.7z`，校验通过后删除原目录
+///
+/// 归档用于释放空间，原目录默认直接删除；`permanent` 为 false 时改为移入系统回收站。
+#[command]
+pub async fn archive_game<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+    game_id: i32,
+    target_dir: String,
+    permanent: Option<bool>,
+) -> Result<ArchiveGameResult, String> {
+    if is_game_monitored(game_id as u32) {
+        return Err("游戏正在运行，无法归档".to_string());
+    }
+    let game = load_game(&db, game_id).await?;
+    if game.archive_path.is_some() {
+        return Err("游戏已归档".to_string());
+    }
+    let localpath = game
+        .localpath
+        .ok_or_else(|| "游戏未设置本地目录".to_string())?;
+    let source = PathBuf::from(&localpath);
+    if !source.is_dir() {
+        return Err(format!("游戏目录不存在: {}", localpath));
+    }
+    let folder_name = source
+        .file_name()
+        .ok_or_else(|| format!("无法获取游戏目录名: {}", localpath))?;
+    let target_dir = PathBuf::from(target_dir.trim());
+    let archive_path = target_dir.join(format!("{}.7z", folder_name.to_string_lossy()));
+    if archive_path.exists() {
+        return Err(format!(
+            "目标位置已存在同名压缩包: {}",
+            archive_path.display()
+        ));
+    }
+    fs::create_dir_all(&target_dir).map_err(|e| format!("创建归档目录失败: {}", e))?;
+
+    log::info!(
+        "开始归档游戏 game_id={} {} -> {}",
+        game_id,
+        source.display(),
+        archive_path.display()
+    );
+    let task = Arc::new(tasks.start(&app_handle, TaskKind::GameArchive)?);
+    let progress_task = task.clone();
+    let archive = archive_path.clone();
+    let compressed = tokio::task::spawn_blocking(move || {
+        let total = dir_size(&source);
+        let result = create_7z_archive_with_progress(
+            &source,
+            &archive,
+            BackupCompression::Zstd,
+            &mut |processed| {
+                progress_task.progress(processed, total);
+                !progress_task.is_cancelled()
+            },
+        )
+        .map_err(archive_error)
+        .and_then(|_| verify_archive(&archive, &source))
+        .map(|_| total);
+        if result.is_err() {
+            let _ = fs::remove_file(&archive);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("归档任务异常: {}", e))
+    .and_then(|result| result);
+    task.finish(&compressed);
+    let folder_size = compressed?;
+
+    let archive_value = archive_path.to_string_lossy().into_owned();
+    GamesRepository::set_archive_path(&db, game_id, Some(archive_value))
+        .await
+        .map_err(|e| format!("压缩包已创建，但记录归档状态失败: {}", e))?;
+    if let Err(e) = GamesRepository::set_folder_size(&db, game_id, Some(folder_size as i64)).await {
+        log::warn!("保存游戏占用空间失败 game_id={}: {}", game_id, e);
+    }
+    let source = PathBuf::from(&localpath);
+    let permanent = permanent.unwrap_or(true);
+    let removed = match tokio::task::spawn_blocking(move || remove_path(&source, permanent)).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            log::warn!("游戏已归档，但删除原目录失败 {}: {}", localpath, e);
+            false
+        }
+        Err(e) => {
+            log::warn!("删除已归档的游戏目录任务异常 game_id={}: {}", game_id, e);
+            false
+        }
+    };
+    // 移入回收站的目录仍占用磁盘空间
+    let freed_bytes = if removed && permanent { folder_size } else { 0 };
+
+    log::info!(
+        "游戏归档完成 game_id={} permanent={} freed_bytes={}",
+        game_id,
+        permanent,
+        freed_bytes
+    );
+    Ok(ArchiveGameResult {
+        game: load_game(&db, game_id).await?,
+        freed_bytes,
+    })
+}
+
+/// 把归档的游戏解压回原来的本地目录，完成后删除压缩包
+#[command]
+pub async fn unarchive_game<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+    game_id: i32,
+) -> Result<FullGameData, String> {
+    let game = load_game(&db, game_id).await?;
+    let archive_path = game
+        .archive_path
+        .map(PathBuf::from)
+        .ok_or_else(|| "游戏未归档".to_string())?;
+    if !archive_path.is_file() {
+        return Err(format!("归档压缩包不存在: {}", archive_path.display()));
+    }
+    let localpath = game
+        .localpath
+        .ok_or_else(|| "游戏未设置本地目录".to_string())?;
+    let target = PathBuf::from(&localpath);
+    if target.exists() {
+        return Err(format!("恢复位置已存在同名目录: {}", localpath));
+    }
+
+    log::info!(
+        "开始恢复归档游戏 game_id={} {} -> {}",
+        game_id,
+        archive_path.display(),
+        target.display()
+    );
+    // 解压出的大小以归档时记录的目录大小为准
+    let total = game
+        .folder_size
+        .and_then(|size| u64::try_from(size).ok())
+        .unwrap_or(0);
+    let task = Arc::new(tasks.start(&app_handle, TaskKind::GameArchive)?);
+    let progress_task = task.clone();
+    let archive = archive_path.clone();
+    let extracted = tokio::task::spawn_blocking(move || {
+        let result = fs::create_dir_all(&target)
+            .map_err(|e| format!("创建游戏目录失败: {}", e))
+            .and_then(|_| {
+                extract_7z_archive_with_progress(&archive, &target, &mut |processed| {
+                    progress_task.progress(processed, total.max(processed));
+                    !progress_task.is_cancelled()
+                })
+                .map_err(archive_error)
+            });
+        if result.is_err()
+            && let Err(e) = fs::remove_dir_all(&target)
+        {
+            log::warn!("清理未完成的游戏目录失败 {}: {}", target.display(), e);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("恢复任务异常: {}", e))
+    .and_then(|result| result);
+    task.finish(&extracted);
+    extracted?;
+
+    GamesRepository::set_archive_path(&db, game_id, None)
+        .await
+        .map_err(|e| format!("游戏已解压，但清除归档状态失败: {}", e))?;
+    if let Err(e) = tokio::fs::remove_file(&archive_path).await {
+        log::warn!(
+            "游戏已恢复，但删除压缩包失败 {}: {}",
+            archive_path.display(),
+            e
+        );
+    }
+
+    log::info!("归档游戏恢复完成 game_id={}", game_id);
+    load_game(&db, game_id).await
+}
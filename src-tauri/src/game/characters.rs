@@ -0,0 +1,139 @@
+//! 角色与声优
+//!
+//! 角色列表从 BGM 条目拉取后保存到 `game_characters` 表，详情页直接读取；
+//! 声优名称可用于反查库中的游戏。
+
+use crate::database::list_filter;
+use crate::database::repository::game_characters_repository::{
+    GameCharactersRepository, VoiceActorGame,
+};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::entity::game_characters::{self, CharacterActor, CharacterActors};
+use crate::metadata::bgm::{BgmCharacter, fetch_subject_characters};
+use crate::utils::bgm_auth::valid_bgm_auth;
+use sea_orm::DatabaseConnection;
+use tauri::{State, command};
+
+fn to_character_models(game_id: i32, characters: Vec<BgmCharacter>) -> Vec<game_characters::Model> {
+    characters
+        .into_iter()
+        .enumerate()
+        .map(|(index, character)| game_characters::Model {
+            game_id,
+            character_id: character.id,
+            name: character.name.trim().to_string(),
+            relation: character.relation,
+            image: character.image,
+            actors: CharacterActors(
+                character
+                    .actors
+                    .into_iter()
+                    .map(|(id, name)| CharacterActor {
+                        id,
+                        name: name.trim().to_string(),
+                    })
+                    .collect(),
+            ),
+            sort_order: index as i32,
+        })
+        .collect()
+}
+
+/// 从 BGM 拉取游戏的角色与声优列表并保存
+///
+/// # Returns
+/// 保存后的角色列表
+#[command]
+pub async fn fetch_bgm_characters(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<game_characters::Model>, String> {
+    let subject_id = GamesRepository::get_source_bindings(&db, "bgm")
+        .await
+        .map_err(|e| format!("读取 BGM 绑定失败: {}", e))?
+        .into_iter()
+        .find(|(id, _)| *id == game_id)
+        .ok_or_else(|| "该游戏未绑定 bgm 条目".to_string())
+        .and_then(|(_, subject_id)| {
+            subject_id
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("无效的 Bangumi 条目 ID: {}", subject_id))
+        })?;
+    let token = valid_bgm_auth(&db)
+        .await
+        .ok()
+        .map(|auth| auth.access_token.trim().to_string())
+        .filter(|token| !token.is_empty());
+
+    let characters = fetch_subject_characters(subject_id, token.as_deref()).await?;
+    let count = GameCharactersRepository::replace_for_game(
+        &db,
+        game_id,
+        to_character_models(game_id, characters),
+    )
+    .await
+    .map_err(|e| format!("保存角色列表失败: {}", e))?;
+    log::info!("角色列表已更新 game_id={} count={}", game_id, count);
+
+    get_game_characters(db, game_id).await
+}
+
+/// 获取已保存的角色列表
+#[command]
+pub async fn get_game_characters(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<game_characters::Model>, String> {
+    GameCharactersRepository::find_by_game(&db, game_id)
+        .await
+        .map_err(|e| format!("获取角色列表失败: {}", e))
+}
+
+/// 按声优名称搜索库中的游戏
+///
+/// 隐藏与安全模式的筛选规则与游戏列表相同。
+#[command]
+pub async fn search_games_by_voice_actor(
+    db: State<'_, DatabaseConnection>,
+    name: String,
+    show_hidden: Option<bool>,
+) -> Result<Vec<VoiceActorGame>, String> {
+    let filter = list_filter(&db, show_hidden).await?;
+    GameCharactersRepository::find_games_by_actor(&db, &name, filter)
+        .await
+        .map_err(|e| format!("按声优搜索游戏失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_bgm_characters() {
+        let characters = vec![
+            BgmCharacter {
+                id: 12,
+                name: " 朝武芳乃 ".to_string(),
+                relation: "主角".to_string(),
+                image: None,
+                actors: vec![(34, "遠野そよぎ".to_string())],
+            },
+            BgmCharacter {
+                id: 56,
+                name: "常陸茉子".to_string(),
+                relation: "主角".to_string(),
+                image: Some("https://lain.bgm.tv/pic/crt/m/56.jpg".to_string()),
+                actors: Vec::new(),
+            },
+        ];
+
+        let models = to_character_models(3, characters);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "朝武芳乃");
+        assert_eq!(models[1].sort_order, 1);
+        assert!(models[0].actors.contains_name("遠野"));
+        assert!(!models[0].actors.contains_name(" "));
+        assert!(!models[1].actors.contains_name("遠野"));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod candidates;
+pub(crate) mod censor;
 pub mod cloud;
 pub mod custom;
 
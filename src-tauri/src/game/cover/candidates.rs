@@ -0,0 +1,190 @@
+//! 封面候选搜索
+//!
+//! 从游戏已绑定的数据源收集候选封面：BGM 提供多个尺寸，VNDB 返回原图尺寸，
+//! 其余数据源（YMGal、Kun 等）使用已保存数据中的封面地址。
+//! 未绑定任何数据源时按游戏名称搜索 BGM / VNDB，取前几个结果的封面。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::metadata::matching::{clean_search_name, game_match_name};
+use crate::metadata::{bgm, vndb};
+use crate::utils::bgm_auth::valid_bgm_auth;
+use crate::utils::image::download_image;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Cursor;
+use tauri::{State, command};
+
+/// 按名称搜索时每个数据源取的结果数
+const NAME_SEARCH_LIMIT: usize = 3;
+
+/// 单个候选封面
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverCandidate {
+    pub source: String,
+    /// 尺寸档位（如 BGM 的 large / common）或条目 ID
+    pub label: String,
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl CoverCandidate {
+    fn new(source: &str, label: impl Into<String>, url: String) -> Self {
+        Self {
+            source: source.to_string(),
+            label: label.into(),
+            url,
+            width: None,
+            height: None,
+        }
+    }
+}
+
+fn stored_image(data: Option<&Value>) -> Option<String> {
+    data?
+        .get("image")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .map(ToOwned::to_owned)
+}
+
+async fn bgm_token(db: &DatabaseConnection) -> Option<String> {
+    valid_bgm_auth(db)
+        .await
+        .ok()
+        .map(|auth| auth.access_token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// 从已绑定的数据源收集候选，在线获取失败时退回已保存的封面地址
+async fn bound_source_candidates(
+    db: &DatabaseConnection,
+    game: &FullGameData,
+) -> Vec<CoverCandidate> {
+    let mut candidates = Vec::new();
+    for item in &game.sources {
+        let external_id = item.external_id.as_deref().unwrap_or_default().trim();
+        let fetched: Result<Vec<CoverCandidate>, String> = match item.source.as_str() {
+            "bgm" => match external_id.parse::<i64>() {
+                Ok(subject_id) => {
+                    let token = bgm_token(db).await;
+                    bgm::fetch_subject_images(subject_id, token.as_deref())
+                        .await
+                        .map(|images| {
+                            images
+                                .into_iter()
+                                .map(|(size, url)| CoverCandidate::new("bgm", size, url))
+                                .collect()
+                        })
+                }
+                Err(_) => Err(format!("无效的 Bangumi 条目 ID: {}", external_id)),
+            },
+            "vndb" if !external_id.is_empty() => {
+                vndb::fetch_cover(external_id).await.map(|cover| {
+                    cover
+                        .map(|cover| CoverCandidate {
+                            width: cover.dims.map(|(width, _)| width),
+                            height: cover.dims.map(|(_, height)| height),
+                            ..CoverCandidate::new("vndb", external_id, cover.url)
+                        })
+                        .into_iter()
+                        .collect()
+                })
+            }
+            _ => Ok(Vec::new()),
+        };
+
+        match fetched {
+            Ok(fetched) if !fetched.is_empty() => candidates.extend(fetched),
+            result => {
+                if let Err(e) = result {
+                    log::warn!(
+                        "获取候选封面失败 game_id={} source={}: {}",
+                        game.id,
+                        item.source,
+                        e
+                    );
+                }
+                if let Some(url) = stored_image(item.data.as_ref()) {
+                    candidates.push(CoverCandidate::new(&item.source, external_id, url));
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// 按游戏名称搜索 BGM / VNDB，收集搜索结果的封面
+async fn name_search_candidates(
+    db: &DatabaseConnection,
+    game: &FullGameData,
+) -> Vec<CoverCandidate> {
+    let Some(keyword) = game_match_name(game).map(|name| clean_search_name(&name)) else {
+        return Vec::new();
+    };
+    let token = bgm_token(db).await;
+    let searches = [
+        bgm::search_by_name(&keyword, token.as_deref(), NAME_SEARCH_LIMIT).await,
+        vndb::search_by_name(&keyword, NAME_SEARCH_LIMIT).await,
+    ];
+
+    let mut candidates = Vec::new();
+    for result in searches {
+        match result {
+            Ok(matches) => candidates.extend(matches.into_iter().filter_map(|matched| {
+                stored_image(Some(&matched.data)).map(|url| {
+                    CoverCandidate::new(matched.source.as_str(), matched.external_id, url)
+                })
+            })),
+            Err(e) => log::warn!("按名称搜索候选封面失败 keyword={}: {}", keyword, e),
+        }
+    }
+    candidates
+}
+
+/// 下载图片并读取尺寸，无法识别的格式返回 `None`
+async fn probe_dimensions(url: &str) -> Option<(u32, u32)> {
+    let bytes = download_image(url).await.ok()?;
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// 搜索游戏的候选封面
+///
+/// 结果按数据源顺序排列并去除重复地址，缺少尺寸的候选会下载后读取尺寸。
+#[command]
+pub async fn search_cover_candidates(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<CoverCandidate>, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+
+    let mut candidates = if game.sources.is_empty() {
+        name_search_candidates(&db, &game).await
+    } else {
+        bound_source_candidates(&db, &game).await
+    };
+
+    let mut seen = HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.url.clone()));
+    for candidate in candidates
+        .iter_mut()
+        .filter(|candidate| candidate.width.is_none())
+    {
+        if let Some((width, height)) = probe_dimensions(&candidate.url).await {
+            candidate.width = Some(width);
+            candidate.height = Some(height);
+        }
+    }
+    Ok(candidates)
+}
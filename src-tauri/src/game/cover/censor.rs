@@ -0,0 +1,87 @@
+//! 安全模式封面打码
+//!
+//! 安全模式开启时，NSFW 作品的封面不返回原图，而是缩小并高斯模糊后的 PNG。
+//! 原图缓存保持不变，关闭安全模式后即可恢复显示。
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use sea_orm::DatabaseConnection;
+
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+
+/// 打码缩略图的最大边长
+const CENSORED_MAX_SIZE: u32 = 96;
+/// 高斯模糊强度
+const CENSORED_BLUR_SIGMA: f32 = 6.0;
+/// 无法解码原图时返回的灰色占位图尺寸
+const PLACEHOLDER_SIZE: (u32, u32) = (60, 80);
+
+/// 打码封面的缓存策略：开关安全模式后需要立即生效，不允许缓存
+pub(crate) const CENSORED_CACHE_CONTROL: &str = "no-store";
+
+/// 当前请求的封面是否需要打码：已开启安全模式且游戏为 NSFW 作品
+///
+/// 查询失败时按需要打码处理，避免误显示原图。
+pub(crate) async fn should_censor_cover(db: &DatabaseConnection, game_id: u32) -> bool {
+    match db.get_settings().await {
+        Ok(settings) if !settings.safe_mode_enabled() => return false,
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("读取安全模式设置失败 game_id={}: {}", game_id, e);
+            return true;
+        }
+    }
+
+    match GamesRepository::find_by_id(db, game_id as i32).await {
+        Ok(game) => game.is_some_and(|game| game.is_nsfw()),
+        Err(e) => {
+            log::warn!("查询游戏数据失败 game_id={}: {}", game_id, e);
+            true
+        }
+    }
+}
+
+/// 生成打码缩略图（PNG），原图无法解码时返回灰色占位图
+pub(crate) fn censor_cover(bytes: &[u8]) -> Vec<u8> {
+    let censored = match image::load_from_memory(bytes) {
+        Ok(cover) => cover
+            .thumbnail(CENSORED_MAX_SIZE, CENSORED_MAX_SIZE)
+            .blur(CENSORED_BLUR_SIGMA),
+        Err(e) => {
+            log::debug!("封面解码失败，使用占位图: {}", e);
+            let (width, height) = PLACEHOLDER_SIZE;
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([128, 128, 128])))
+        }
+    };
+
+    let mut output = Cursor::new(Vec::new());
+    if let Err(e) = censored.write_to(&mut output, ImageFormat::Png) {
+        log::warn!("打码封面编码失败: {}", e);
+    }
+    output.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn censors_cover_into_small_png() {
+        let cover = DynamicImage::ImageRgb8(RgbImage::from_fn(300, 400, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, 0])
+        }));
+        let mut original = Cursor::new(Vec::new());
+        cover.write_to(&mut original, ImageFormat::Png).unwrap();
+
+        let censored = image::load_from_memory(&censor_cover(original.get_ref())).unwrap();
+        assert_eq!((censored.width(), censored.height()), (72, 96));
+
+        let placeholder = image::load_from_memory(&censor_cover(b"not an image")).unwrap();
+        assert_eq!(
+            (placeholder.width(), placeholder.height()),
+            PLACEHOLDER_SIZE
+        );
+    }
+}
@@ -10,6 +10,7 @@ use tauri::command;
 use tauri::http::StatusCode;
 use tokio::sync::{RwLock, Semaphore, watch};
 
+use super::censor::{CENSORED_CACHE_CONTROL, censor_cover, should_censor_cover};
 use crate::entity::prelude::Games;
 use crate::utils::image::{
     content_type_for_extension, content_type_for_file, infer_image_extension, make_image_response,
@@ -107,7 +108,7 @@ fn cloud_cover_file_stem(game_id: u32) -> String {
     format!("{DEFAULT_CLOUD_COVER_FILE_NAME}_{game_id}")
 }
 
-fn get_game_cover_dir(game_id: u32) -> Result<PathBuf, String> {
+pub(crate) fn get_game_cover_dir(game_id: u32) -> Result<PathBuf, String> {
     Ok(get_base_data_dir()?
         .join("covers")
         .join(format!("game_{}", game_id)))
@@ -129,7 +130,7 @@ fn build_temp_cache_path(game_cover_dir: &Path, game_id: u32, extension: &str) -
     ))
 }
 
-async fn get_cached_cloud_cover(game_cover_dir: &Path, game_id: u32) -> Option<PathBuf> {
+pub(crate) async fn get_cached_cloud_cover(game_cover_dir: &Path, game_id: u32) -> Option<PathBuf> {
     let file_stem = cloud_cover_file_stem(game_id);
 
     // O(1) 快速路径：直接探测最常见的图片扩展名（stat 系统调用，无需遍历目录）
@@ -226,6 +227,24 @@ fn make_ok_response(bytes: Vec<u8>, content_type: &str) -> tauri::http::Response
     make_image_response(bytes, content_type, "max-age=31536000, immutable")
 }
 
+/// 返回封面；`censor` 为 true 时改为返回打码缩略图
+async fn make_cover_response(
+    bytes: Vec<u8>,
+    content_type: &str,
+    censor: bool,
+) -> tauri::http::Response<Vec<u8>> {
+    if !censor {
+        return make_ok_response(bytes, content_type);
+    }
+    match tokio::task::spawn_blocking(move || censor_cover(&bytes)).await {
+        Ok(censored) => make_image_response(censored, "image/png", CENSORED_CACHE_CONTROL),
+        Err(e) => {
+            log::warn!("生成打码封面失败: {}", e);
+            make_status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 #[command]
 pub async fn delete_cloud_cache(
     game_id: u32,
@@ -516,6 +535,9 @@ pub fn register_game_cover_protocol<R: tauri::Runtime>(
                     }
                 }
 
+                // 安全模式下 NSFW 作品的封面统一打码
+                let censor = should_censor_cover(db.inner(), game_id).await;
+
                 // ── 步骤 1：内存集合短路（最快路径，无磁盘 I/O）──────────
                 {
                     let cached = state.cached_ids.read().await;
@@ -525,10 +547,14 @@ pub fn register_game_cover_protocol<R: tauri::Runtime>(
                             get_cached_cloud_cover(&game_cover_dir, game_id).await
                             && let Ok(bytes) = tokio::fs::read(&cache_path).await
                         {
-                            responder.respond(make_ok_response(
-                                bytes,
-                                content_type_for_file(&cache_path),
-                            ));
+                            responder.respond(
+                                make_cover_response(
+                                    bytes,
+                                    content_type_for_file(&cache_path),
+                                    censor,
+                                )
+                                .await,
+                            );
                             return;
                         }
                         // 内存标记存在但文件已被外部删除，清除过期记录
@@ -542,7 +568,10 @@ pub fn register_game_cover_protocol<R: tauri::Runtime>(
                 {
                     // 回填内存集合，下次请求走步骤 1
                     state.cached_ids.write().await.insert(game_id);
-                    responder.respond(make_ok_response(bytes, content_type_for_file(&cache_path)));
+                    responder.respond(
+                        make_cover_response(bytes, content_type_for_file(&cache_path), censor)
+                            .await,
+                    );
                     return;
                 }
 
@@ -592,8 +621,10 @@ pub fn register_game_cover_protocol<R: tauri::Runtime>(
                         && let Ok(bytes) = tokio::fs::read(&cache_path).await
                     {
                         state.cached_ids.write().await.insert(game_id);
-                        responder
-                            .respond(make_ok_response(bytes, content_type_for_file(&cache_path)));
+                        responder.respond(
+                            make_cover_response(bytes, content_type_for_file(&cache_path), censor)
+                                .await,
+                        );
                         return;
                     }
                     if state.is_game_deleted_marked(game_id).await {
@@ -638,7 +669,7 @@ pub fn register_game_cover_protocol<R: tauri::Runtime>(
                         // 回填内存缓存集合
                         state.cached_ids.write().await.insert(game_id);
                         let content_type = content_type_for_extension(&infer_cache_extension(&url));
-                        responder.respond(make_ok_response(bytes, content_type));
+                        responder.respond(make_cover_response(bytes, content_type, censor).await);
                     }
                     Err(CoverDownloadError::GameDeleted(e)) => {
                         log::debug!("封面下载终止 game_id={}: {}", game_id, e);
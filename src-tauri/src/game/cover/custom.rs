@@ -1,9 +1,17 @@
+use super::cloud::{DownloadState, delete_cloud_cache, get_game_cover_dir};
+use crate::database::dto::UpdateGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::entity::custom_data::CustomData;
+use crate::utils::image::{content_type_for_extension, download_image, infer_image_extension};
 use arboard::Clipboard;
 use image::{ColorType, ImageFormat};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
 use std::fs;
+use std::io::Cursor;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::command;
+use tauri::{State, command};
 
 fn map_clipboard_error(error: arboard::Error) -> String {
     let message = error.to_string();
@@ -57,12 +65,50 @@ pub async fn import_clipboard_image_to_temp(game_id: u32) -> Result<String, Stri
     Ok(target_path.to_string_lossy().to_string())
 }
 
+/// 删除封面目录下该游戏的全部自定义封面文件（`cover_{game_id}_*`）
+async fn remove_custom_cover_files(dir_path: &Path, game_id: u32) -> Result<(), String> {
+    if !tokio::fs::try_exists(dir_path).await.unwrap_or(false) {
+        return Ok(());
+    }
+
+    let expected_file_prefix = format!("cover_{}_", game_id);
+    let mut entries = tokio::fs::read_dir(dir_path)
+        .await
+        .map_err(|e| format!("无法读取封面目录: {}", e))?;
+
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("读取目录项失败: {}", e))?
+    {
+        let is_file = entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_file());
+        if !is_file {
+            continue;
+        }
+
+        let file_name = entry.file_name();
+        let file_name_str = file_name.to_string_lossy();
+        if !file_name_str.starts_with(&expected_file_prefix) {
+            continue;
+        }
+
+        tokio::fs::remove_file(entry.path())
+            .await
+            .map_err(|e| format!("无法删除自定义封面文件: {}", e))?;
+    }
+
+    Ok(())
+}
+
 /// 删除指定游戏的所有自定义封面文件，但保留封面目录
 #[command]
 pub async fn delete_game_covers(game_id: u32, covers_dir: String) -> Result<(), String> {
     let dir_path = Path::new(&covers_dir);
 
-    if !dir_path.exists() {
+    if !tokio::fs::try_exists(dir_path).await.unwrap_or(false) {
         return Ok(());
     }
 
@@ -79,25 +125,162 @@ pub async fn delete_game_covers(game_id: u32, covers_dir: String) -> Result<(),
         ));
     }
 
-    let expected_file_prefix = format!("cover_{}_", game_id);
-    let entries = fs::read_dir(dir_path).map_err(|e| format!("无法读取封面目录: {}", e))?;
+    remove_custom_cover_files(dir_path, game_id).await
+}
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-        let path = entry.path();
+/// 封面裁剪区域（像素）
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct CoverCrop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
 
-        if !path.is_file() {
-            continue;
-        }
+/// 按裁剪区域裁剪图片并编码为 PNG
+fn crop_image(bytes: &[u8], crop: CoverCrop) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| format!("无法解析图片: {}", e))?;
+    let fits = |offset: u32, length: u32, limit: u32| {
+        length > 0 && offset.checked_add(length).is_some_and(|end| end <= limit)
+    };
+    if !fits(crop.x, crop.width, image.width()) || !fits(crop.y, crop.height, image.height()) {
+        return Err(format!(
+            "裁剪区域超出图片范围: 图片尺寸 {}x{}",
+            image.width(),
+            image.height()
+        ));
+    }
 
-        let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy();
-        if !file_name_str.starts_with(&expected_file_prefix) {
-            continue;
-        }
+    let mut output = Cursor::new(Vec::new());
+    image
+        .crop_imm(crop.x, crop.y, crop.width, crop.height)
+        .write_to(&mut output, ImageFormat::Png)
+        .map_err(|e| format!("保存裁剪后的图片失败: {}", e))?;
+    Ok(output.into_inner())
+}
 
-        fs::remove_file(&path).map_err(|e| format!("无法删除自定义封面文件: {}", e))?;
+/// 图片文件扩展名：优先按文件头识别，其次取路径中的扩展名
+fn cover_extension(bytes: &[u8], path_or_url: &str) -> Result<String, String> {
+    image::guess_format(bytes)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .map(str::to_string)
+        .or_else(|| infer_image_extension(path_or_url))
+        .filter(|ext| content_type_for_extension(ext).starts_with("image/"))
+        .ok_or_else(|| "不支持的图片格式".to_string())
+}
+
+/// 设置自定义封面
+///
+/// 网络地址且不裁剪时直接保存地址；本地图片或需要裁剪的网络图片写入封面目录
+/// （`cover_{game_id}_{扩展名}_{时间戳}`），旧的自定义封面文件会被删除。
+///
+/// # Returns
+/// 写入 `custom_data.image` 的值
+#[command]
+pub async fn set_custom_cover(
+    db: State<'_, DatabaseConnection>,
+    cover_state: State<'_, DownloadState>,
+    game_id: i32,
+    path_or_url: String,
+    crop: Option<CoverCrop>,
+) -> Result<String, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let path_or_url = path_or_url.trim();
+    if path_or_url.is_empty() {
+        return Err("封面路径不能为空".to_string());
     }
+    let is_remote = path_or_url.starts_with("http://") || path_or_url.starts_with("https://");
+    let cover_dir = get_game_cover_dir(game_id as u32)?;
 
-    Ok(())
+    let image_value = if is_remote && crop.is_none() {
+        remove_custom_cover_files(&cover_dir, game_id as u32).await?;
+        path_or_url.to_string()
+    } else {
+        let bytes = if is_remote {
+            download_image(path_or_url).await?
+        } else {
+            tokio::fs::read(path_or_url)
+                .await
+                .map_err(|e| format!("读取图片文件失败: {}", e))?
+        };
+        let (bytes, extension) = match crop {
+            Some(crop) => {
+                let cropped = tokio::task::spawn_blocking(move || crop_image(&bytes, crop))
+                    .await
+                    .map_err(|e| format!("裁剪图片任务异常: {}", e))??;
+                (cropped, "png".to_string())
+            }
+            None => {
+                let extension = cover_extension(&bytes, path_or_url)?;
+                (bytes, extension)
+            }
+        };
+
+        let timestamp_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("获取系统时间失败: {}", e))?
+            .as_millis();
+        let versioned_file_name = format!("{}_{}", extension, timestamp_millis);
+        tokio::fs::create_dir_all(&cover_dir)
+            .await
+            .map_err(|e| format!("创建封面目录失败: {}", e))?;
+        remove_custom_cover_files(&cover_dir, game_id as u32).await?;
+        tokio::fs::write(
+            cover_dir.join(format!("cover_{}_{}", game_id, versioned_file_name)),
+            bytes,
+        )
+        .await
+        .map_err(|e| format!("写入封面文件失败: {}", e))?;
+        versioned_file_name
+    };
+
+    let custom_data = CustomData {
+        image: Some(image_value.clone()),
+        ..game.custom_data.unwrap_or_default()
+    };
+    let updates = UpdateGameData {
+        custom_data: Some(Some(custom_data)),
+        ..Default::default()
+    };
+    GamesRepository::update(&db, game_id, updates)
+        .await
+        .map_err(|e| format!("保存自定义封面失败: {}", e))?;
+    delete_cloud_cache(game_id as u32, cover_state).await?;
+
+    Ok(image_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GenericImageView};
+
+    #[test]
+    fn crops_image_within_bounds() {
+        let mut source = Cursor::new(Vec::new());
+        DynamicImage::new_rgba8(40, 30)
+            .write_to(&mut source, ImageFormat::Png)
+            .unwrap();
+        let source = source.into_inner();
+        let crop = |x, y, width, height| CoverCrop {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        let cropped = crop_image(&source, crop(10, 5, 20, 25)).unwrap();
+        assert_eq!(
+            image::load_from_memory(&cropped).unwrap().dimensions(),
+            (20, 25)
+        );
+        assert!(crop_image(&source, crop(30, 0, 20, 10)).is_err());
+        assert!(crop_image(&source, crop(0, 0, 0, 10)).is_err());
+        assert_eq!(cover_extension(&source, "/tmp/cover").unwrap(), "png");
+        assert!(cover_extension(b"not an image", "/tmp/cover.txt").is_err());
+    }
 }
@@ -0,0 +1,301 @@
+//! 游戏桌面快捷方式
+//!
+//! 在桌面或开始菜单生成启动游戏的快捷方式（Windows 为 .lnk，Linux 为 .desktop），
+//! 目标是本程序并附带 `reina://game/{id}/launch` 参数，经由本程序启动以保留时长统计。
+//! 图标优先使用游戏封面（安全模式下与界面一致打码），取不到时使用游戏 exe 的图标。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::cover::censor::{censor_cover, should_censor_cover};
+use crate::game::cover::cloud::{get_cached_cloud_cover, get_game_cover_dir};
+use crate::utils::image::download_image;
+use image::{ImageFormat, RgbaImage, imageops};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime, State, command};
+
+/// 封面图标边长
+const ICON_SIZE: u32 = 256;
+
+/// 快捷方式位置
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutLocation {
+    #[default]
+    Desktop,
+    StartMenu,
+}
+
+/// 生成可用作文件名的快捷方式名称
+fn shortcut_file_stem(game: &FullGameData) -> String {
+    let name: String = game
+        .display_name()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = name.trim().trim_end_matches('.').trim();
+    if name.is_empty() {
+        format!("游戏 {}", game.id)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 读取界面当前显示的封面：本地自定义封面 > 已缓存的远程封面 > 在线下载
+pub(crate) async fn load_cover_bytes(game: &FullGameData) -> Option<Vec<u8>> {
+    let game_id = game.id as u32;
+    let cover_dir = get_game_cover_dir(game_id).ok()?;
+    let custom_image = game
+        .custom_data
+        .as_ref()
+        .and_then(|data| data.image.as_deref())
+        .map(str::trim)
+        .filter(|image| !image.is_empty());
+
+    if let Some(image) = custom_image
+        && !image.starts_with("http://")
+        && !image.starts_with("https://")
+    {
+        let path = cover_dir.join(format!("cover_{}_{}", game_id, image));
+        if let Ok(bytes) = tokio::fs::read(&path).await {
+            return Some(bytes);
+        }
+    }
+    if custom_image.is_none()
+        && let Some(path) = get_cached_cloud_cover(&cover_dir, game_id).await
+        && let Ok(bytes) = tokio::fs::read(&path).await
+    {
+        return Some(bytes);
+    }
+
+    let url = game.remote_cover_url()?;
+    match download_image(&url).await {
+        Ok(bytes) => Some(bytes),
+        Err(e) => {
+            log::warn!("下载封面失败 game_id={}: {}", game_id, e);
+            None
+        }
+    }
+}
+
+/// 将封面等比缩放后居中放到透明正方形画布上，输出 PNG
+fn cover_icon_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let cover = image::load_from_memory(bytes)
+        .map_err(|e| format!("解析封面图片失败: {}", e))?
+        .thumbnail(ICON_SIZE, ICON_SIZE)
+        .to_rgba8();
+    let mut canvas = RgbaImage::new(ICON_SIZE, ICON_SIZE);
+    imageops::overlay(
+        &mut canvas,
+        &cover,
+        i64::from((ICON_SIZE - cover.width()) / 2),
+        i64::from((ICON_SIZE - cover.height()) / 2),
+    );
+
+    let mut output = Cursor::new(Vec::new());
+    canvas
+        .write_to(&mut output, ImageFormat::Png)
+        .map_err(|e| format!("编码图标失败: {}", e))?;
+    Ok(output.into_inner())
+}
+
+/// 把 PNG 包装为单图标的 ICO 文件（Vista 起支持内嵌 PNG）
+fn png_to_ico(png: &[u8]) -> Vec<u8> {
+    let mut ico = Vec::with_capacity(22 + png.len());
+    // ICONDIR：保留字段、类型（1 = 图标）、图像数量
+    ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    // ICONDIRENTRY：宽、高（0 表示 256）、调色板数、保留、色彩平面、位深、数据长度、数据偏移
+    ico.extend_from_slice(&[0, 0, 0, 0, 1, 0, 32, 0]);
+    ico.extend_from_slice(&(png.len() as u32).to_le_bytes());
+    ico.extend_from_slice(&22_u32.to_le_bytes());
+    ico.extend_from_slice(png);
+    ico
+}
+
+/// 由封面生成快捷方式图标文件，失败时返回 None
+async fn write_cover_icon(db: &DatabaseConnection, game: &FullGameData) -> Option<PathBuf> {
+    let mut bytes = load_cover_bytes(game).await?;
+    if should_censor_cover(db, game.id as u32).await {
+        bytes = censor_cover(&bytes);
+    }
+    let png = tokio::task::spawn_blocking(move || cover_icon_png(&bytes))
+        .await
+        .unwrap_or_else(|e| Err(format!("生成图标任务异常: {}", e)));
+    let png = match png {
+        Ok(png) => png,
+        Err(e) => {
+            log::warn!("生成快捷方式图标失败 game_id={}: {}", game.id, e);
+            return None;
+        }
+    };
+
+    let (extension, data) = if cfg!(target_os = "windows") {
+        ("ico", png_to_ico(&png))
+    } else {
+        ("png", png)
+    };
+    let dir = reina_path::get_base_data_dir().ok()?.join("shortcut_icons");
+    let path = dir.join(format!("game_{}.{}", game.id, extension));
+    let written = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, data));
+    if let Err(e) = written {
+        log::warn!("保存快捷方式图标失败 game_id={}: {}", game.id, e);
+        return None;
+    }
+    Some(path)
+}
+
+/// 快捷方式所在目录
+fn shortcut_dir<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    location: ShortcutLocation,
+) -> Result<PathBuf, String> {
+    let path = app_handle.path();
+    match location {
+        ShortcutLocation::Desktop => path
+            .desktop_dir()
+            .map_err(|e| format!("获取桌面目录失败: {}", e)),
+        ShortcutLocation::StartMenu => {
+            let data_dir = path
+                .data_dir()
+                .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+            if cfg!(target_os = "windows") {
+                Ok(data_dir.join(r"Microsoft\Windows\Start Menu\Programs\ReinaManager"))
+            } else {
+                Ok(data_dir.join("applications"))
+            }
+        }
+    }
+}
+
+/// 写入 .lnk 快捷方式，没有封面图标时使用游戏 exe 的图标
+#[cfg(target_os = "windows")]
+fn write_shortcut(
+    dir: &Path,
+    stem: &str,
+    game: &FullGameData,
+    launch_arg: &str,
+    icon: Option<&Path>,
+) -> Result<PathBuf, String> {
+    use crate::utils::shortcut::{ShortcutSpec, create_shortcut};
+
+    let exe = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let game_exe = game
+        .localpath
+        .as_deref()
+        .zip(game.executable.as_deref())
+        .map(|(dir, executable)| Path::new(dir).join(executable))
+        .filter(|path| path.is_file());
+    let path = dir.join(format!("{}.lnk", stem));
+    create_shortcut(
+        &path,
+        &ShortcutSpec {
+            target: &exe,
+            arguments: launch_arg,
+            working_dir: exe.parent(),
+            icon: icon.or(game_exe.as_deref()),
+            description: stem,
+        },
+    )?;
+    Ok(path)
+}
+
+/// 写入 .desktop 启动器并加上可执行权限
+#[cfg(target_os = "linux")]
+fn write_shortcut(
+    dir: &Path,
+    stem: &str,
+    game: &FullGameData,
+    launch_arg: &str,
+    icon: Option<&Path>,
+) -> Result<PathBuf, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let exe = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" {}\nTerminal=false\nCategories=Game;\n",
+        stem,
+        exe.display(),
+        launch_arg
+    );
+    if let Some(icon) = icon {
+        entry.push_str(&format!("Icon={}\n", icon.display()));
+    }
+
+    let path = dir.join(format!("reina-game-{}.desktop", game.id));
+    fs::write(&path, entry).map_err(|e| format!("写入快捷方式失败: {}", e))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("设置快捷方式权限失败: {}", e))?;
+    Ok(path)
+}
+
+/// 为游戏生成桌面或开始菜单快捷方式，同名快捷方式会被覆盖
+///
+/// # Returns
+/// 快捷方式文件路径
+#[command]
+pub async fn create_desktop_shortcut<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    location: Option<ShortcutLocation>,
+) -> Result<String, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let dir = shortcut_dir(&app_handle, location.unwrap_or_default())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建快捷方式目录失败: {}", e))?;
+
+    let icon = write_cover_icon(&db, &game).await;
+    let launch_arg = format!("reina://game/{}/launch", game_id);
+    let path = write_shortcut(
+        &dir,
+        &shortcut_file_stem(&game),
+        &game,
+        &launch_arg,
+        icon.as_deref(),
+    )?;
+    log::info!("已创建游戏快捷方式 game_id={}: {}", game_id, path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builds_shortcut_name_and_icon() {
+        let game: FullGameData = serde_json::from_value(json!({
+            "id": 5,
+            "id_type": "custom",
+            "custom_data": { "name": "Fate/stay night: \"Réalta Nua\"." },
+            "sources": [],
+        }))
+        .unwrap();
+        assert_eq!(shortcut_file_stem(&game), "Fate stay night   Réalta Nua");
+
+        let unnamed: FullGameData = serde_json::from_value(json!({
+            "id": 6,
+            "id_type": "custom",
+            "sources": [],
+        }))
+        .unwrap();
+        assert_eq!(shortcut_file_stem(&unnamed), "游戏 6");
+
+        let ico = png_to_ico(&[1, 2, 3]);
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 1, 0]);
+        assert_eq!(&ico[14..18], &3_u32.to_le_bytes());
+        assert_eq!(&ico[22..], &[1, 2, 3]);
+    }
+}
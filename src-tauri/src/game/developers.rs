@@ -0,0 +1,153 @@
+//! 按开发商浏览游戏库
+//!
+//! 开发商取自 `FullGameData::developers`（自定义开发商优先，否则取数据源），
+//! 多个开发商以 `/` 分隔时分别计入。名称比较忽略首尾空白与大小写。
+
+use crate::database::dto::FullGameData;
+use crate::database::list_filter;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{State, command};
+
+/// 开发商及其在库中的作品数量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeveloperEntry {
+    pub name: String,
+    pub game_count: usize,
+}
+
+/// 统计各开发商的作品数量，按作品数降序、名称升序排列
+///
+/// 仅大小写不同的名称合并为一项，使用最先出现的写法。
+fn collect_developers(games: &[FullGameData]) -> Vec<DeveloperEntry> {
+    let mut entries: Vec<DeveloperEntry> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+    for game in games {
+        for name in game.developers() {
+            let index = *index_by_key.entry(name.to_lowercase()).or_insert_with(|| {
+                entries.push(DeveloperEntry {
+                    name,
+                    game_count: 0,
+                });
+                entries.len() - 1
+            });
+            entries[index].game_count += 1;
+        }
+    }
+    entries.sort_by(|a, b| {
+        b.game_count
+            .cmp(&a.game_count)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    entries
+}
+
+fn is_developed_by(game: &FullGameData, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    game.developers()
+        .iter()
+        .any(|developer| developer.to_lowercase() == name)
+}
+
+async fn find_listed_games(
+    db: &DatabaseConnection,
+    show_hidden: Option<bool>,
+) -> Result<Vec<FullGameData>, String> {
+    let filter = list_filter(db, show_hidden).await?;
+    GamesRepository::find_listed(
+        db,
+        GameType::All,
+        SortOption::Datetime,
+        SortOrder::Desc,
+        None,
+        filter,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))
+}
+
+/// 获取游戏库中的全部开发商
+///
+/// 隐藏与安全模式的筛选规则与游戏列表相同。
+#[command]
+pub async fn get_all_developers(
+    db: State<'_, DatabaseConnection>,
+    show_hidden: Option<bool>,
+) -> Result<Vec<DeveloperEntry>, String> {
+    let games = find_listed_games(&db, show_hidden).await?;
+    Ok(collect_developers(&games))
+}
+
+/// 获取某个开发商的全部作品，按发行日期从新到旧排列
+///
+/// # Arguments
+/// * `name` - 开发商名称，忽略首尾空白与大小写
+#[command]
+pub async fn find_games_by_developer(
+    db: State<'_, DatabaseConnection>,
+    name: String,
+    show_hidden: Option<bool>,
+) -> Result<Vec<FullGameData>, String> {
+    if name.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let games = find_listed_games(&db, show_hidden).await?;
+    Ok(games
+        .into_iter()
+        .filter(|game| is_developed_by(game, &name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn game(id: i32, custom_developer: Option<&str>, source_developer: &str) -> FullGameData {
+        serde_json::from_value(json!({
+            "id": id,
+            "id_type": "bgm",
+            "custom_data": { "developer": custom_developer },
+            "sources": [{
+                "source": "bgm",
+                "external_id": id.to_string(),
+                "data": { "developer": source_developer },
+            }],
+        }))
+        .expect("测试游戏数据应能反序列化")
+    }
+
+    #[test]
+    fn collects_and_matches_developers() {
+        let games = vec![
+            game(1, None, "Key/VisualArt's"),
+            game(2, None, "key"),
+            game(3, Some("Yuzusoft"), "ゆずソフト"),
+            game(4, None, ""),
+        ];
+
+        assert_eq!(
+            collect_developers(&games),
+            [
+                DeveloperEntry {
+                    name: "Key".to_string(),
+                    game_count: 2,
+                },
+                DeveloperEntry {
+                    name: "VisualArt's".to_string(),
+                    game_count: 1,
+                },
+                DeveloperEntry {
+                    name: "Yuzusoft".to_string(),
+                    game_count: 1,
+                },
+            ]
+        );
+        assert!(is_developed_by(&games[1], " KEY "));
+        assert!(!is_developed_by(&games[2], "ゆずソフト"));
+    }
+}
@@ -0,0 +1,325 @@
+//! 重复游戏检测与合并
+//!
+//! 按 bgm / vndb 外部 ID、本地目录与标题相似度把疑似重复的游戏分组，
+//! 任意两条满足一项规则即归入同一组。合并时保留目标游戏的元数据，
+//! 其余游戏的游玩记录、存档备份、笔记与合集关联转移到目标游戏后永久删除。
+
+use crate::backup::savedata::resolve_savedata_backup_root;
+use crate::database::cleanup_deleted_game_covers;
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::game::cover::DownloadState;
+use crate::game::scan::is_same_or_descendant;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::{State, command};
+
+/// 默认的标题相似度阈值
+const DEFAULT_MIN_SIMILARITY: f64 = 0.9;
+/// 按外部 ID 判断重复的数据源
+const DUPLICATE_SOURCES: [&str; 2] = ["bgm", "vndb"];
+
+/// 一组疑似重复的游戏
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub games: Vec<FullGameData>,
+    /// 命中的规则：bgm / vndb / localpath / title / similar_title
+    pub matched_by: Vec<String>,
+}
+
+/// 合并结果
+#[derive(Debug, Default, Serialize)]
+pub struct MergeGamesResult {
+    pub target_id: i32,
+    pub merged: usize,
+    pub savedata_moved: usize,
+    pub errors: Vec<String>,
+}
+
+/// 标题比较形式：转小写并只保留字母与数字
+pub(crate) fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 按字符二元组计算 Dice 相似度
+pub(crate) fn title_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |value: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = value.chars().collect();
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let mut remaining: HashMap<(char, char), usize> = HashMap::new();
+    for bigram in &b {
+        *remaining.entry(*bigram).or_default() += 1;
+    }
+    let mut common = 0;
+    for bigram in &a {
+        if let Some(count) = remaining.get_mut(bigram).filter(|count| **count > 0) {
+            *count -= 1;
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+fn find_root(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = index;
+    while parents[current] != root {
+        current = std::mem::replace(&mut parents[current], root);
+    }
+    root
+}
+
+/// 两个游戏命中的重复规则
+fn duplicate_reasons(a: &FullGameData, b: &FullGameData, min_similarity: f64) -> Vec<&'static str> {
+    let mut reasons = Vec::new();
+    for source in DUPLICATE_SOURCES {
+        let external_id = |game: &FullGameData| {
+            game.sources
+                .iter()
+                .find(|item| item.source == source)
+                .and_then(|item| item.external_id.clone())
+                .filter(|id| !id.trim().is_empty())
+        };
+        if let (Some(a_id), Some(b_id)) = (external_id(a), external_id(b))
+            && a_id == b_id
+        {
+            reasons.push(source);
+        }
+    }
+
+    if let (Some(a_path), Some(b_path)) = (a.localpath.as_deref(), b.localpath.as_deref()) {
+        let (a_path, b_path) = (Path::new(a_path), Path::new(b_path));
+        if is_same_or_descendant(a_path, b_path) && is_same_or_descendant(b_path, a_path) {
+            reasons.push("localpath");
+        }
+    }
+
+    let titles = a
+        .display_name()
+        .zip(b.display_name())
+        .map(|(a_title, b_title)| (normalize_title(&a_title), normalize_title(&b_title)))
+        .filter(|(a_title, b_title)| !a_title.is_empty() && !b_title.is_empty());
+    if let Some((a_title, b_title)) = titles {
+        if a_title == b_title {
+            reasons.push("title");
+        } else if title_similarity(&a_title, &b_title) >= min_similarity {
+            reasons.push("similar_title");
+        }
+    }
+    reasons
+}
+
+/// 把疑似重复的游戏分组，只返回包含两个及以上游戏的分组
+fn group_duplicates(games: Vec<FullGameData>, min_similarity: f64) -> Vec<DuplicateGroup> {
+    let mut parents: Vec<usize> = (0..games.len()).collect();
+    let mut reasons: HashMap<(usize, usize), Vec<&'static str>> = HashMap::new();
+    for a in 0..games.len() {
+        for b in a + 1..games.len() {
+            let matched = duplicate_reasons(&games[a], &games[b], min_similarity);
+            if !matched.is_empty() {
+                let (root_a, root_b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+                parents[root_b] = root_a;
+                reasons.insert((a, b), matched);
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..games.len() {
+        let root = find_root(&mut parents, index);
+        members.entry(root).or_default().push(index);
+    }
+    let mut matched_by: HashMap<usize, BTreeSet<&'static str>> = HashMap::new();
+    for ((a, _), matched) in reasons {
+        let root = find_root(&mut parents, a);
+        matched_by.entry(root).or_default().extend(matched);
+    }
+
+    let mut games: Vec<Option<FullGameData>> = games.into_iter().map(Some).collect();
+    let mut groups: Vec<DuplicateGroup> = members
+        .into_iter()
+        .filter(|(_, indexes)| indexes.len() > 1)
+        .map(|(root, indexes)| DuplicateGroup {
+            games: indexes
+                .into_iter()
+                .filter_map(|index| games[index].take())
+                .collect(),
+            matched_by: matched_by
+                .remove(&root)
+                .unwrap_or_default()
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+        })
+        .collect();
+    groups.sort_by_key(|group| group.games.first().map(|game| game.id));
+    groups
+}
+
+/// 检测疑似重复的游戏
+///
+/// # Arguments
+/// * `min_similarity` - 标题相似度阈值（0-1），默认 0.9
+#[command]
+pub async fn find_duplicate_games(
+    db: State<'_, DatabaseConnection>,
+    min_similarity: Option<f64>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let min_similarity = min_similarity
+        .unwrap_or(DEFAULT_MIN_SIMILARITY)
+        .clamp(0.0, 1.0);
+    let games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+
+    let groups = group_duplicates(games, min_similarity);
+    log::info!("重复游戏检测完成 groups={}", groups.len());
+    Ok(groups)
+}
+
+/// 将重复游戏合并到目标游戏
+///
+/// 游玩记录与统计、存档备份、笔记、合集关联合并到目标游戏，被合并的游戏永久删除。
+///
+/// # Arguments
+/// * `target_id` - 保留的游戏ID
+/// * `source_ids` - 被合并的游戏ID列表
+#[command]
+pub async fn merge_games(
+    db: State<'_, DatabaseConnection>,
+    cover_state: State<'_, DownloadState>,
+    target_id: i32,
+    source_ids: Vec<i32>,
+) -> Result<MergeGamesResult, String> {
+    let source_ids: Vec<i32> = source_ids
+        .into_iter()
+        .filter(|id| *id != target_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut result = MergeGamesResult {
+        target_id,
+        ..Default::default()
+    };
+    if source_ids.is_empty() {
+        return Ok(result);
+    }
+
+    let moved = GamesRepository::merge_games(&db, target_id, &source_ids)
+        .await
+        .map_err(|e| format!("合并游戏失败: {}", e))?;
+    result.merged = source_ids.len();
+
+    // 数据库已提交，备份文件移动失败只记录错误，可通过存档一致性检查修复
+    let backup_root = resolve_savedata_backup_root(&db).await?;
+    let target_dir = backup_root.join(format!("game_{}", target_id));
+    for record in &moved {
+        let source = backup_root
+            .join(format!("game_{}", record.game_id))
+            .join(&record.file);
+        let destination = target_dir.join(&record.file);
+        if destination.exists() {
+            result
+                .errors
+                .push(format!("目标目录已存在同名备份 {:?}", destination));
+            continue;
+        }
+        let moved_file =
+            fs::create_dir_all(&target_dir).and_then(|_| fs::rename(&source, &destination));
+        match moved_file {
+            Ok(()) => result.savedata_moved += 1,
+            Err(e) => result
+                .errors
+                .push(format!("移动存档备份失败 {:?}: {}", source, e)),
+        }
+    }
+    for game_id in &source_ids {
+        let game_dir = backup_root.join(format!("game_{}", game_id));
+        if game_dir.is_dir() {
+            let _ = fs::remove_dir(game_dir);
+        }
+    }
+    cleanup_deleted_game_covers(&cover_state, &source_ids).await;
+
+    log::info!(
+        "合并游戏完成 target_id={} merged={} savedata_moved={} errors={}",
+        target_id,
+        result.merged,
+        result.savedata_moved,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn game(id: i32, name: &str, bgm_id: Option<&str>, localpath: Option<&str>) -> FullGameData {
+        let sources: Vec<_> = bgm_id
+            .map(|bgm_id| json!({ "source": "bgm", "external_id": bgm_id, "data": null }))
+            .into_iter()
+            .collect();
+        serde_json::from_value(json!({
+            "id": id,
+            "id_type": "custom",
+            "localpath": localpath,
+            "custom_data": { "name": name },
+            "sources": sources,
+        }))
+        .expect("测试游戏数据应能反序列化")
+    }
+
+    #[test]
+    fn groups_duplicates_by_id_path_and_title() {
+        let games = vec![
+            game(1, "Summer Pockets", Some("100"), None),
+            game(2, "summer-pockets", None, Some("/games/sp")),
+            game(3, "Other Game", None, Some("/games/sp/")),
+            game(4, "Riddle Joker", Some("200"), None),
+            game(5, "Riddle Joker!", None, None),
+            game(6, "Senren Banka", Some("300"), None),
+            game(7, "Café Stella", Some("400"), None),
+        ];
+
+        let groups = group_duplicates(games, 0.9);
+        let ids: Vec<Vec<i32>> = groups
+            .iter()
+            .map(|group| group.games.iter().map(|game| game.id).collect())
+            .collect();
+        assert_eq!(ids, [vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(groups[0].matched_by, ["localpath", "title"]);
+        assert_eq!(groups[1].matched_by, ["title"]);
+
+        assert!(title_similarity("summerpockets", "summerpocketsrb") > 0.9);
+        assert!(title_similarity("senrenbanka", "riddlejoker") < 0.2);
+    }
+}
@@ -0,0 +1,191 @@
+//! 游戏引擎识别
+//!
+//! 根据游戏目录中的特征文件判断常见的视觉小说/游戏引擎，
+//! 用于扫描结果展示、按引擎筛选以及后续按引擎推断存档路径。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::command;
+
+/// 可识别的游戏引擎，序列化值即 games.engine 列中保存的字符串
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEngine {
+    Kirikiri,
+    Renpy,
+    RpgMaker,
+    Nscripter,
+    Unity,
+    Siglus,
+    Artemis,
+    Bgi,
+    CatSystem2,
+    Tyrano,
+}
+
+impl GameEngine {
+    /// 与序列化值一致的引擎标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GameEngine::Kirikiri => "kirikiri",
+            GameEngine::Renpy => "renpy",
+            GameEngine::RpgMaker => "rpg_maker",
+            GameEngine::Nscripter => "nscripter",
+            GameEngine::Unity => "unity",
+            GameEngine::Siglus => "siglus",
+            GameEngine::Artemis => "artemis",
+            GameEngine::Bgi => "bgi",
+            GameEngine::CatSystem2 => "cat_system2",
+            GameEngine::Tyrano => "tyrano",
+        }
+    }
+}
+
+/// 游戏目录第一层的文件与子目录名（小写）
+struct DirListing {
+    files: HashSet<String>,
+    dirs: HashSet<String>,
+}
+
+impl DirListing {
+    fn read(dir: &Path) -> Option<Self> {
+        let mut files = HashSet::new();
+        let mut dirs = HashSet::new();
+        for entry in std::fs::read_dir(dir).ok()?.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => {
+                    dirs.insert(name);
+                }
+                Ok(_) => {
+                    files.insert(name);
+                }
+                Err(_) => {}
+            }
+        }
+        Some(Self { files, dirs })
+    }
+
+    fn has_file(&self, name: &str) -> bool {
+        self.files.contains(name)
+    }
+
+    fn has_dir(&self, name: &str) -> bool {
+        self.dirs.contains(name)
+    }
+
+    fn has_extension(&self, ext: &str) -> bool {
+        self.files.iter().any(|file| {
+            Path::new(file)
+                .extension()
+                .is_some_and(|file_ext| file_ext == ext)
+        })
+    }
+}
+
+/// 识别游戏目录使用的引擎，无法识别时返回 None
+///
+/// 只检查目录第一层及少数固定位置的特征文件，扫描大量目录时开销可控。
+pub fn detect_engine(dir: &Path) -> Option<GameEngine> {
+    let listing = DirListing::read(dir)?;
+
+    // 目录结构特征明确的引擎优先判断，避免被附带的资源包误判
+    if listing.has_dir("renpy")
+        || (listing.has_dir("game")
+            && DirListing::read(&dir.join("game"))
+                .is_some_and(|game| game.has_extension("rpa") || game.has_extension("rpyc")))
+    {
+        return Some(GameEngine::Renpy);
+    }
+    if listing.has_dir("tyrano") || dir.join("data").join("scenario").is_dir() {
+        return Some(GameEngine::Tyrano);
+    }
+    if listing.has_file("unityplayer.dll")
+        || (listing.dirs.iter().any(|name| name.ends_with("_data"))
+            && listing.has_file("unitycrashhandler64.exe"))
+    {
+        return Some(GameEngine::Unity);
+    }
+    if listing.has_extension("rgss3a")
+        || listing.has_extension("rgss2a")
+        || listing.has_extension("rgssad")
+        || dir.join("www").join("js").join("rpg_core.js").is_file()
+        || dir.join("js").join("rpg_core.js").is_file()
+        || dir.join("js").join("rmmz_core.js").is_file()
+    {
+        return Some(GameEngine::RpgMaker);
+    }
+    if listing.has_file("scene.pck") || listing.has_file("siglusengine.exe") {
+        return Some(GameEngine::Siglus);
+    }
+    if listing.has_extension("xp3") {
+        return Some(GameEngine::Kirikiri);
+    }
+    if listing.has_file("nscript.dat")
+        || listing.has_extension("nsa")
+        || listing.has_extension("sar")
+    {
+        return Some(GameEngine::Nscripter);
+    }
+    if listing.has_extension("pfs") {
+        return Some(GameEngine::Artemis);
+    }
+    if listing.has_file("bgi.gdb") || listing.has_file("sysprg.arc") {
+        return Some(GameEngine::Bgi);
+    }
+    if listing.has_file("cs2conf.dll") || listing.has_extension("int") {
+        return Some(GameEngine::CatSystem2);
+    }
+    None
+}
+
+/// 识别指定目录的游戏引擎，供手动添加游戏时使用
+#[command]
+pub async fn detect_game_engine(path: String) -> Result<Option<GameEngine>, String> {
+    let dir = Path::new(&path).to_path_buf();
+    if !dir.is_dir() {
+        return Err(format!("目录不存在或不是文件夹: {}", path));
+    }
+    tokio::task::spawn_blocking(move || detect_engine(&dir))
+        .await
+        .map_err(|e| format!("识别游戏引擎失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+    use std::fs;
+
+    fn temp_game_dir(name: &str) -> std::path::PathBuf {
+        let dir = temp_dir("engine", name);
+        fs::create_dir_all(&dir).expect("应能创建测试目录");
+        dir
+    }
+
+    #[test]
+    fn detects_engines_from_marker_files() {
+        let kirikiri = temp_game_dir("kirikiri");
+        fs::write(kirikiri.join("data.xp3"), []).unwrap();
+        assert_eq!(detect_engine(&kirikiri), Some(GameEngine::Kirikiri));
+
+        let renpy = temp_game_dir("renpy");
+        fs::create_dir_all(renpy.join("game")).unwrap();
+        fs::write(renpy.join("game").join("archive.rpa"), []).unwrap();
+        assert_eq!(detect_engine(&renpy), Some(GameEngine::Renpy));
+
+        let rpg_maker = temp_game_dir("rpgmaker");
+        fs::create_dir_all(rpg_maker.join("www").join("js")).unwrap();
+        fs::write(rpg_maker.join("www").join("js").join("rpg_core.js"), []).unwrap();
+        assert_eq!(detect_engine(&rpg_maker), Some(GameEngine::RpgMaker));
+
+        let unknown = temp_game_dir("unknown");
+        fs::write(unknown.join("game.exe"), []).unwrap();
+        assert_eq!(detect_engine(&unknown), None);
+
+        for dir in [kirikiri, renpy, rpg_maker, unknown] {
+            fs::remove_dir_all(dir).expect("应能清理测试目录");
+        }
+    }
+}
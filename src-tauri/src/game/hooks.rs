@@ -0,0 +1,243 @@
+//! 启动前/游戏结束后钩子
+//!
+//! 每个游戏可配置在启动前、会话结束后依次执行的命令，例如先打开 Textractor、
+//! 游戏结束后关闭特效工具。前台命令会等待退出并检查退出码；后台命令只负责启动。
+
+use crate::database::dto::FullGameData;
+use crate::entity::launch_options::HookCommand;
+use log::{debug, info, warn};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// 未配置超时时等待前台钩子退出的时间
+const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+
+/// 错误信息中保留的 stderr 末尾字符数
+const STDERR_TAIL_CHARS: usize = 500;
+
+/// 钩子执行阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreLaunch,
+    PostExit,
+}
+
+impl HookStage {
+    fn label(self) -> &'static str {
+        match self {
+            Self::PreLaunch => "启动前脚本",
+            Self::PostExit => "结束后脚本",
+        }
+    }
+
+    fn env_value(self) -> &'static str {
+        match self {
+            Self::PreLaunch => "pre_launch",
+            Self::PostExit => "post_exit",
+        }
+    }
+}
+
+/// 钩子执行时注入的游戏上下文，以 `REINA_*` 环境变量提供给命令
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub game_id: u32,
+    pub game_dir: Option<PathBuf>,
+    pub executable: Option<PathBuf>,
+}
+
+impl HookContext {
+    pub fn for_game(game: &FullGameData) -> Self {
+        let game_dir = game.localpath.as_deref().map(PathBuf::from);
+        let executable = game_dir
+            .as_ref()
+            .zip(game.executable.as_deref())
+            .map(|(dir, executable)| dir.join(executable));
+        Self {
+            game_id: game.id as u32,
+            game_dir,
+            executable,
+        }
+    }
+}
+
+/// 取出游戏配置中指定阶段的钩子命令
+pub fn hooks_for_stage(game: &FullGameData, stage: HookStage) -> Vec<HookCommand> {
+    game.launch_hooks
+        .as_ref()
+        .map(|hooks| match stage {
+            HookStage::PreLaunch => hooks.pre_launch.clone(),
+            HookStage::PostExit => hooks.post_exit.clone(),
+        })
+        .unwrap_or_default()
+}
+
+/// 按顺序执行游戏配置的指定阶段钩子
+pub async fn run_game_hooks(game: &FullGameData, stage: HookStage) -> Result<(), String> {
+    run_hooks(
+        hooks_for_stage(game, stage),
+        HookContext::for_game(game),
+        stage,
+    )
+    .await
+}
+
+/// 按顺序执行一组钩子，任一前台命令失败时立即返回详细错误
+pub async fn run_hooks(
+    commands: Vec<HookCommand>,
+    context: HookContext,
+    stage: HookStage,
+) -> Result<(), String> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        for (index, command) in commands.iter().enumerate() {
+            run_hook(command, &context, stage).map_err(|e| {
+                format!(
+                    "{}第 {} 条命令 ({}) 执行失败: {}",
+                    stage.label(),
+                    index + 1,
+                    command.program,
+                    e
+                )
+            })?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("{}任务异常退出: {}", stage.label(), e))?
+}
+
+fn build_command(hook: &HookCommand, context: &HookContext, stage: HookStage) -> Command {
+    let mut command = Command::new(&hook.program);
+    command.args(&hook.args);
+
+    let working_dir = hook
+        .working_dir
+        .as_deref()
+        .map(Path::new)
+        .or(context.game_dir.as_deref());
+    if let Some(dir) = working_dir.filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+
+    command.env("REINA_GAME_ID", context.game_id.to_string());
+    command.env("REINA_HOOK_STAGE", stage.env_value());
+    if let Some(dir) = &context.game_dir {
+        command.env("REINA_GAME_DIR", dir);
+    }
+    if let Some(executable) = &context.executable {
+        command.env("REINA_GAME_EXE", executable);
+    }
+
+    // 钩子多为控制台程序，避免在 GUI 下弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        if !hook.background {
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+    }
+
+    command
+}
+
+fn run_hook(hook: &HookCommand, context: &HookContext, stage: HookStage) -> Result<(), String> {
+    let mut command = build_command(hook, context, stage);
+    command.stdin(Stdio::null()).stdout(Stdio::null());
+
+    if hook.background {
+        command.stderr(Stdio::null());
+        let child = command.spawn().map_err(|e| format!("无法启动: {}", e))?;
+        info!(
+            "{}已在后台启动: game_id={} program={} pid={}",
+            stage.label(),
+            context.game_id,
+            hook.program,
+            child.id()
+        );
+        return Ok(());
+    }
+
+    command.stderr(Stdio::piped());
+    let mut child = command.spawn().map_err(|e| format!("无法启动: {}", e))?;
+
+    // 单独读取 stderr，避免输出过多时管道写满导致子进程阻塞
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output);
+            String::from_utf8_lossy(&output).into_owned()
+        })
+    });
+
+    let timeout = Duration::from_secs(hook.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("超过 {} 秒未退出，已终止", timeout.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("等待退出失败: {}", e)),
+        }
+    };
+
+    let stderr = stderr_reader
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+
+    if status.success() {
+        debug!(
+            "{}执行完成: game_id={} program={}",
+            stage.label(),
+            context.game_id,
+            hook.program
+        );
+        return Ok(());
+    }
+
+    let code = status.code().map_or_else(
+        || "被信号终止".to_string(),
+        |code| format!("退出码 {}", code),
+    );
+    let stderr_tail = tail_chars(stderr.trim(), STDERR_TAIL_CHARS);
+    if stderr_tail.is_empty() {
+        Err(code)
+    } else {
+        warn!("{} stderr: {}", stage.label(), stderr_tail);
+        Err(format!("{}，错误输出: {}", code, stderr_tail))
+    }
+}
+
+/// 截取字符串末尾的若干字符
+fn tail_chars(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - max_chars)
+        .map_or(0, |(index, _)| index);
+    &text[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_keeps_last_chars_on_char_boundary() {
+        assert_eq!(tail_chars("短文本", 10), "短文本");
+        assert_eq!(tail_chars("第一行错误信息", 4), "错误信息");
+    }
+}
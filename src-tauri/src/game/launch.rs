@@ -4,8 +4,111 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
+#[cfg(target_os = "linux")]
+mod wine;
+
+pub mod locale_emulator;
+
 #[cfg(target_os = "windows")]
 pub use windows::*;
 
 #[cfg(target_os = "linux")]
 pub use linux::*;
+
+use crate::database::dto::FullGameData;
+use crate::game::monitor::{KillOutcome, kill_game_session, kill_process_gracefully};
+use serde::Serialize;
+use std::process::Command;
+use std::time::Duration;
+
+/// 未指定时等待优雅关闭的超时时间（秒）
+const DEFAULT_KILL_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Serialize)]
+pub struct KillResult {
+    success: bool,
+    message: String,
+    /// 是否在超时前优雅关闭
+    graceful: bool,
+    /// 被强制终止的进程数量
+    killed_count: u32,
+}
+
+impl KillResult {
+    fn from_outcome(target: String, outcome: KillOutcome) -> Self {
+        let message = if outcome.graceful {
+            format!("{}已正常关闭", target)
+        } else {
+            format!(
+                "{}未响应关闭请求，已强制终止 {} 个进程",
+                target, outcome.killed_count
+            )
+        };
+        Self {
+            success: true,
+            message,
+            graceful: outcome.graceful,
+            killed_count: outcome.killed_count,
+        }
+    }
+}
+
+fn kill_timeout(timeout_secs: Option<u64>) -> Duration {
+    Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_KILL_TIMEOUT_SECS))
+}
+
+/// 结束游戏
+///
+/// 先请求游戏窗口正常关闭，超时后强制终止残留进程，监控会话随之结算时长。
+///
+/// # Arguments
+///
+/// * `game_id` - 游戏ID
+/// * `timeout_secs` - 等待正常关闭的秒数，默认 5 秒
+#[tauri::command]
+pub async fn kill_game(game_id: u32, timeout_secs: Option<u64>) -> Result<KillResult, String> {
+    kill_game_session(game_id, kill_timeout(timeout_secs))
+        .await
+        .map(|outcome| KillResult::from_outcome(format!("游戏 {} ", game_id), outcome))
+        .map_err(|e| format!("结束游戏失败: {}", e))
+}
+
+/// 结束指定进程，用于处理未被识别为游戏进程的卡死窗口
+///
+/// # Arguments
+///
+/// * `pid` - 进程 PID
+/// * `timeout_secs` - 等待正常关闭的秒数，默认 5 秒
+#[tauri::command]
+pub async fn kill_process(pid: u32, timeout_secs: Option<u64>) -> Result<KillResult, String> {
+    kill_process_gracefully(pid, kill_timeout(timeout_secs))
+        .await
+        .map(|outcome| KillResult::from_outcome(format!("进程 {} ", pid), outcome))
+        .map_err(|e| format!("结束进程失败: {}", e))
+}
+
+/// 合并游戏配置的启动参数与本次调用传入的参数，配置参数在前
+fn merge_launch_args(game: &FullGameData, args: Option<Vec<String>>) -> Option<Vec<String>> {
+    let mut merged: Vec<String> = game
+        .launch_args
+        .as_ref()
+        .map(|launch_args| launch_args.0.clone())
+        .unwrap_or_default();
+    merged.extend(args.unwrap_or_default());
+    (!merged.is_empty()).then_some(merged)
+}
+
+/// 将游戏配置的环境变量注入启动命令
+fn apply_launch_env(command: &mut Command, game: &FullGameData) {
+    if let Some(launch_env) = &game.launch_env {
+        command.envs(&launch_env.0);
+    }
+}
+
+/// 获取当前正在运行（监控中）的所有游戏 ID
+///
+/// 前端刷新或重新打开窗口后可据此恢复运行状态。
+#[tauri::command]
+pub fn get_running_game_ids() -> Vec<u32> {
+    crate::game::monitor::monitored_game_ids()
+}
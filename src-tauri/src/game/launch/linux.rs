@@ -1,5 +1,11 @@
+use super::wine;
+use super::{apply_launch_env, merge_launch_args};
 use crate::database::repository::games_repository::GamesRepository;
-use crate::game::monitor::{TimeTrackingMode, monitor_game, stop_game_session};
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::game::hooks::{HookStage, run_game_hooks};
+use crate::game::monitor::{
+    MonitorRegistration, TimeTrackingMode, monitor_game, stop_game_session,
+};
 use log::{debug, info};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
@@ -35,6 +41,12 @@ pub async fn launch_game<R: Runtime>(
         .await
         .map_err(|e| format!("查询游戏失败: {}", e))?
         .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    // 先占用监控槽位，同一游戏正在运行时直接拒绝重复启动
+    let registration = MonitorRegistration::acquire(game_id)?;
+    // 游戏配置的启动参数在前，本次调用传入的参数在后
+    let args = merge_launch_args(&game, args);
+    // 启动前脚本失败时中止启动，占位随 registration 析构释放
+    run_game_hooks(&game, HookStage::PreLaunch).await?;
     let game_dir = PathBuf::from(
         game.localpath
             .as_deref()
@@ -55,16 +67,34 @@ pub async fn launch_game<R: Runtime>(
     let systemd_unit_name = format!("reina_game_{}.scope", game_id);
     let _ = check_scope_or_reset_failed(&systemd_unit_name).await;
 
-    let mut command = {
+    let use_wine = wine::needs_wine(&executable_path);
+    let wine_invocation = if use_wine {
         let linux_launch_command = app_handle
             .store("settings.json")
             .ok()
             .and_then(|store| store.get("linux_launch_command"))
             .and_then(|v| v.as_str().map(|s| s.to_string()))
             .unwrap_or_else(|| "wine".to_string());
-        let linux_launch_command = expand_path(&app_handle, &linux_launch_command);
         debug!("使用的 Linux 启动命令: {:?}", linux_launch_command);
 
+        // 游戏级配置优先，缺省字段回退到全局配置
+        let settings = db.inner().get_settings().await?;
+        let wine_config = game
+            .wine_config
+            .clone()
+            .unwrap_or_default()
+            .merged_with(settings.wine_config.as_ref());
+        let home_dir = app_handle.path().home_dir().ok();
+        Some(wine::build_invocation(
+            &wine_config,
+            &linux_launch_command,
+            home_dir.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let mut command = {
         let mut cmd = Command::new("systemd-run");
         cmd.arg("--scope");
         cmd.arg("--user");
@@ -73,8 +103,10 @@ pub async fn launch_game<R: Runtime>(
         cmd.arg("--unit");
         cmd.arg(&systemd_unit_name);
 
-        if exe_name.to_string_lossy().ends_with(".exe") {
-            cmd.arg(&linux_launch_command);
+        if let Some(invocation) = &wine_invocation {
+            cmd.arg(&invocation.program);
+            cmd.args(&invocation.args);
+            cmd.envs(invocation.envs.iter().map(|(key, value)| (key, value)));
         }
         cmd.arg(&game_path);
         cmd.current_dir(&game_dir);
@@ -85,13 +117,14 @@ pub async fn launch_game<R: Runtime>(
     if let Some(arguments) = &args_clone {
         command.args(arguments);
     }
+    apply_launch_env(&mut command, &game);
 
     debug!(
         "准备启动游戏 game_id={} scope={} command={} arg_count={} cwd={}",
         game_id,
         systemd_unit_name,
-        if exe_name.to_string_lossy().ends_with(".exe") {
-            "systemd-run+wine"
+        if let Some(invocation) = &wine_invocation {
+            invocation.program.as_str()
         } else {
             "systemd-run"
         },
@@ -111,8 +144,8 @@ pub async fn launch_game<R: Runtime>(
                 app_handle.clone(),
                 db.inner().clone(),
                 time_tracking_mode,
-                game_id,
-                process_id,
+                registration,
+                child,
                 systemd_unit_name.clone(),
             )
             .await;
@@ -144,19 +177,6 @@ pub async fn stop_game(game_id: u32) -> Result<StopResult, String> {
     }
 }
 
-fn expand_path<R: Runtime>(app_handle: &AppHandle<R>, path: &str) -> String {
-    if path.starts_with('~') {
-        // 使用 Tauri 提供的内置路径解析
-        if let Ok(home_dir) = app_handle.path().home_dir() {
-            path.replacen('~', &home_dir.to_string_lossy(), 1)
-        } else {
-            path.to_string()
-        }
-    } else {
-        path.to_string()
-    }
-}
-
 /// 在 Linux 上检查 systemd scope 的状态，如果是 failed 则重置它
 /// 返回bool值表示scope是否已经存在
 /// # Arguments
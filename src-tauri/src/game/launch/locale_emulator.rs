@@ -0,0 +1,192 @@
+//! Locale Emulator 转区配置
+//!
+//! 解析 LEProc.exe 同目录下的 LEConfig.xml，供前端为游戏选择转区配置；
+//! 启动时按所选配置生成 `LEProc.exe -runas <GUID>` 参数。
+
+use crate::database::repository::settings_repository::DbSettingsExt;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+
+/// 使用 LE 默认配置启动的特殊取值，对应 `LEProc.exe -run`
+#[cfg(any(target_os = "windows", test))]
+pub const LE_DEFAULT_PROFILE: &str = "default";
+
+/// LEConfig.xml 中的一个转区配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LeProfile {
+    pub guid: String,
+    pub name: String,
+    /// 区域，例如 `ja-JP`
+    pub location: Option<String>,
+    pub run_as_admin: bool,
+}
+
+/// 生成传给 LEProc.exe 的参数（不含游戏自身的启动参数）
+///
+/// - 未选择配置：直接传入游戏路径，由 LE 优先使用程序专用配置
+/// - `default`：`-run`，使用 LE 默认配置
+/// - 其他值视为配置 GUID：`-runas <GUID>`
+#[cfg(any(target_os = "windows", test))]
+pub fn le_arguments(profile: Option<&str>, game_path: &str) -> Vec<String> {
+    match profile.map(str::trim).filter(|profile| !profile.is_empty()) {
+        None => vec![game_path.to_string()],
+        Some(profile) if profile.eq_ignore_ascii_case(LE_DEFAULT_PROFILE) => {
+            vec!["-run".to_string(), game_path.to_string()]
+        }
+        Some(guid) => vec![
+            "-runas".to_string(),
+            guid.to_string(),
+            game_path.to_string(),
+        ],
+    }
+}
+
+/// 列出 Locale Emulator 中的所有转区配置
+#[tauri::command]
+pub async fn list_le_profiles(db: State<'_, DatabaseConnection>) -> Result<Vec<LeProfile>, String> {
+    let settings = db.get_settings().await?;
+    let le_path = settings
+        .le_path
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+        .ok_or_else(|| "LE转区软件路径未设置，请先配置路径".to_string())?;
+
+    let config_path = Path::new(le_path)
+        .parent()
+        .map(|dir| dir.join("LEConfig.xml"))
+        .ok_or_else(|| format!("无效的LE路径: {}", le_path))?;
+    let content = tokio::fs::read_to_string(&config_path)
+        .await
+        .map_err(|e| format!("读取LE配置文件失败 {}: {}", config_path.display(), e))?;
+
+    Ok(parse_le_config(&content))
+}
+
+/// 解析 LEConfig.xml 中的 `<Profile>` 节点
+///
+/// 文件结构固定且简单，这里只做轻量的标签扫描，不引入完整的 XML 解析器。
+fn parse_le_config(content: &str) -> Vec<LeProfile> {
+    let mut profiles = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("<Profile") {
+        rest = &rest[start + "<Profile".len()..];
+        // 跳过 <Profiles> 等同前缀标签
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            continue;
+        }
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let attributes = &rest[..tag_end];
+        let self_closing = attributes.trim_end().ends_with('/');
+        rest = &rest[tag_end + 1..];
+
+        let body = if self_closing {
+            ""
+        } else {
+            let body_end = rest.find("</Profile>").unwrap_or(rest.len());
+            let body = &rest[..body_end];
+            rest = &rest[body_end..];
+            body
+        };
+
+        let Some(guid) = xml_attribute(attributes, "Guid") else {
+            continue;
+        };
+        profiles.push(LeProfile {
+            name: xml_attribute(attributes, "Name").unwrap_or_else(|| guid.clone()),
+            guid,
+            location: xml_element(body, "Location"),
+            run_as_admin: xml_element(body, "RunAsAdmin")
+                .is_some_and(|value| value.eq_ignore_ascii_case("true")),
+        });
+    }
+
+    profiles
+}
+
+fn xml_attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!("{}=\"", name);
+    let mut search = attributes;
+    while let Some(index) = search.find(&pattern) {
+        // 确保匹配的是完整属性名
+        let preceded_by_space = search[..index]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        let value_start = index + pattern.len();
+        if preceded_by_space {
+            let value = &search[value_start..];
+            let value_end = value.find('"')?;
+            return Some(xml_unescape(&value[..value_end]));
+        }
+        search = &search[value_start..];
+    }
+    None
+}
+
+fn xml_element(body: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = body.find(&open)? + open.len();
+    let end = body[start..].find(&close)? + start;
+    let value = xml_unescape(body[start..end].trim());
+    (!value.is_empty()).then_some(value)
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_from_le_config() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<LEConfig>
+  <Profiles>
+    <Profile Name="Run in Japanese" Guid="5a0b3b2b-0000-0000-0000-000000000001" MainMenu="false">
+      <Parameter />
+      <Location>ja-JP</Location>
+      <RunAsAdmin>false</RunAsAdmin>
+    </Profile>
+    <Profile Name="Admin &amp; JP" Guid="5a0b3b2b-0000-0000-0000-000000000002" MainMenu="true">
+      <Location>ja-JP</Location>
+      <RunAsAdmin>true</RunAsAdmin>
+    </Profile>
+  </Profiles>
+</LEConfig>"#;
+
+        let profiles = parse_le_config(content);
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Run in Japanese");
+        assert_eq!(profiles[0].location.as_deref(), Some("ja-JP"));
+        assert!(!profiles[0].run_as_admin);
+        assert_eq!(profiles[1].name, "Admin & JP");
+        assert!(profiles[1].run_as_admin);
+    }
+
+    #[test]
+    fn builds_le_arguments_for_profile() {
+        assert_eq!(le_arguments(None, "a.exe"), vec!["a.exe"]);
+        assert_eq!(
+            le_arguments(Some("default"), "a.exe"),
+            vec!["-run", "a.exe"]
+        );
+        assert_eq!(
+            le_arguments(Some("guid-1"), "a.exe"),
+            vec!["-runas", "guid-1", "a.exe"]
+        );
+    }
+}
@@ -1,18 +1,41 @@
+use super::locale_emulator::le_arguments;
+use super::{apply_launch_env, merge_launch_args};
 use crate::database::dto::UpdateSettingsData;
 use crate::database::repository::games_repository::GamesRepository;
 use crate::database::repository::settings_repository::{DbSettingsExt, SettingsRepository};
-use crate::game::monitor::{TimeTrackingMode, monitor_game, stop_game_session};
+use crate::game::hooks::{HookStage, run_game_hooks};
+use crate::game::monitor::{
+    MonitorRegistration, TimeTrackingMode, is_process_running as monitor_is_process_running,
+    kill_process_gracefully, monitor_game, stop_game_session, wait_for_game_exit,
+    wait_for_game_foreground,
+};
 use crate::utils::command_ext::CommandGuiExt;
+use crate::utils::shortcut::{is_shortcut, resolve_shortcut, split_arguments};
+use parking_lot::Mutex;
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 use tauri::{AppHandle, Runtime, State, command};
 use {
     log::{debug, info, warn},
     tokio::time,
 };
 
+/// 新启动的 Magpie 注册全局快捷键所需的等待时间（秒）
+const MAGPIE_STARTUP_DELAY_SECS: u64 = 1;
+
+/// 等待游戏窗口获得前台的最长时间（秒），覆盖 LE 转区与启动器的耗时
+const MAGPIE_WINDOW_TIMEOUT_SECS: u64 = 60;
+
+/// 游戏窗口就绪后触发放大前的等待时间（秒）
+const MAGPIE_SCALE_DELAY_SECS: u64 = 1;
+
+/// 关闭 Magpie 时等待其退出的时间（秒）
+const MAGPIE_CLOSE_TIMEOUT_SECS: u64 = 3;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LaunchResult {
     success: bool,
@@ -247,6 +270,12 @@ pub async fn launch_game<R: Runtime>(
         .await
         .map_err(|e| format!("查询游戏失败: {}", e))?
         .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    // 先占用监控槽位，同一游戏正在运行时直接拒绝重复启动
+    let registration = MonitorRegistration::acquire(game_id)?;
+    // 游戏配置的启动参数在前，本次调用传入的参数在后
+    let args = merge_launch_args(&game, args);
+    // 启动前脚本失败时中止启动，占位随 registration 析构释放
+    run_game_hooks(&game, HookStage::PreLaunch).await?;
     let game_dir = PathBuf::from(
         game.localpath
             .as_deref()
@@ -257,7 +286,46 @@ pub async fn launch_game<R: Runtime>(
             .as_deref()
             .ok_or_else(|| "游戏启动文件未设置".to_string())?,
     );
+    // 快捷方式先解析为真实目标，按实际 exe 启动与监控
+    let shortcut = if is_shortcut(&executable_path) {
+        let shortcut = resolve_shortcut(&executable_path)?;
+        debug!(
+            "已解析快捷方式 game_id={} lnk={} target={}",
+            game_id,
+            executable_path.display(),
+            shortcut.target.display()
+        );
+        Some(shortcut)
+    } else {
+        None
+    };
+    let (executable_path, launch_dir) = match &shortcut {
+        Some(shortcut) => (
+            shortcut.target.clone(),
+            shortcut
+                .effective_working_dir()
+                .unwrap_or_else(|| game_dir.clone()),
+        ),
+        None => (executable_path, game_dir.clone()),
+    };
+    // 快捷方式可能指向游戏目录之外，此时以目标所在目录检测游戏进程
+    let detection_dir = if executable_path.starts_with(&game_dir) {
+        game_dir.clone()
+    } else {
+        executable_path
+            .parent()
+            .map_or_else(|| game_dir.clone(), Path::to_path_buf)
+    };
     let game_path = executable_path.to_string_lossy().to_string();
+    // 快捷方式自带的参数位于其他启动参数之前
+    let args = match shortcut.as_ref().and_then(|s| s.arguments.as_deref()) {
+        Some(shortcut_args) => {
+            let mut merged = split_arguments(shortcut_args);
+            merged.extend(args.unwrap_or_default());
+            Some(merged)
+        }
+        None => args,
+    };
 
     let use_le = game.le_launch.unwrap_or(0) == 1;
     let use_magpie = game.magpie.unwrap_or(0) == 1;
@@ -305,13 +373,13 @@ pub async fn launch_game<R: Runtime>(
             .as_deref()
             .ok_or_else(|| "LE转区软件路径未设置，请先配置路径".to_string())?;
         let mut cmd = Command::new(le_path);
-        cmd.current_dir(&game_dir);
-        cmd.arg(&game_path);
+        cmd.current_dir(&launch_dir);
+        cmd.args(le_arguments(game.le_profile.as_deref(), &game_path));
         cmd
     } else {
         // 普通启动
         let mut cmd = Command::new(&game_path);
-        cmd.current_dir(&game_dir);
+        cmd.current_dir(&launch_dir);
         cmd
     };
 
@@ -320,6 +388,7 @@ pub async fn launch_game<R: Runtime>(
     if let Some(arguments) = &args_clone {
         command.args(arguments);
     }
+    apply_launch_env(&mut command, &game);
 
     debug!(
         "准备启动游戏 game_id={} mode={} magpie={} arg_count={} cwd={}",
@@ -332,7 +401,7 @@ pub async fn launch_game<R: Runtime>(
 
     match command.gui_safe().spawn() {
         Ok(child) => {
-            let detection_dir_str = game_dir.to_string_lossy().to_string();
+            let detection_dir_str = detection_dir.to_string_lossy().to_string();
             let process_id = child.id();
             info!(
                 "游戏启动成功 game_id={} pid={} mode={} magpie={}",
@@ -347,7 +416,7 @@ pub async fn launch_game<R: Runtime>(
                 app_handle.clone(),
                 db.inner().clone(),
                 time_tracking_mode,
-                game_id,
+                registration,
                 process_id,
                 detection_dir_str.clone(),
             )
@@ -355,12 +424,7 @@ pub async fn launch_game<R: Runtime>(
 
             // 如果需要Magpie放大，在后台启动
             if let Some(magpie_path) = magpie_path.clone() {
-                tokio::spawn(async move {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                    if let Err(e) = start_magpie_for_game(&magpie_path).await {
-                        warn!("启动Magpie失败: {}", e);
-                    }
-                });
+                spawn_magpie_for_game(game_id, magpie_path);
             }
 
             Ok(LaunchResult {
@@ -382,9 +446,15 @@ pub async fn launch_game<R: Runtime>(
                     "普通启动需要提权，准备回退到管理员启动 game_id={}: {}",
                     game_id, e
                 );
+                if game.launch_env.is_some() {
+                    warn!(
+                        "管理员启动无法注入自定义环境变量，将使用系统环境 game_id={}",
+                        game_id
+                    );
+                }
                 // 对于LE启动，需要用LE路径作为执行文件，游戏路径作为参数
                 let (exec_path, exec_args) = if use_le {
-                    let mut args = vec![game_path.clone()];
+                    let mut args = le_arguments(game.le_profile.as_deref(), &game_path);
                     if let Some(additional_args) = &args_clone {
                         args.extend(additional_args.clone());
                     }
@@ -401,10 +471,10 @@ pub async fn launch_game<R: Runtime>(
                 match win_elevated_launch::shell_execute_runas(
                     &exec_path,
                     exec_args.as_deref(),
-                    &game_dir,
+                    &launch_dir,
                 ) {
                     Ok(pid) => {
-                        let detection_dir_str = game_dir.to_string_lossy().to_string();
+                        let detection_dir_str = detection_dir.to_string_lossy().to_string();
                         info!(
                             "游戏提权启动成功 game_id={} pid={} mode={} magpie={}",
                             game_id,
@@ -417,7 +487,7 @@ pub async fn launch_game<R: Runtime>(
                             app_handle.clone(),
                             db.inner().clone(),
                             time_tracking_mode,
-                            game_id,
+                            registration,
                             pid,
                             detection_dir_str,
                         )
@@ -425,12 +495,7 @@ pub async fn launch_game<R: Runtime>(
 
                         // 如果需要Magpie放大，在后台启动
                         if let Some(magpie_path) = magpie_path.clone() {
-                            tokio::spawn(async move {
-                                time::sleep(time::Duration::from_secs(1)).await;
-                                if let Err(e) = start_magpie_for_game(&magpie_path).await {
-                                    warn!("启动Magpie失败: {}", e);
-                                }
-                            });
+                            spawn_magpie_for_game(game_id, magpie_path);
                         }
 
                         Ok(LaunchResult {
@@ -477,8 +542,30 @@ pub async fn stop_game(game_id: u32) -> Result<StopResult, String> {
     }
 }
 
+/// 由管理器拉起的 Magpie 进程及正在使用它的游戏
+///
+/// 只有管理器自己启动的 Magpie 才会在最后一个游戏退出后被关闭，
+/// 用户事先手动打开的 Magpie 保持不动。
+struct MagpieOwnership {
+    pid: u32,
+    game_ids: HashSet<u32>,
+}
+
+static MAGPIE_OWNERSHIP: Mutex<Option<MagpieOwnership>> = Mutex::new(None);
+
+/// 在后台为游戏拉起 Magpie、等待窗口就绪后触发放大，并在游戏退出后关闭 Magpie
+fn spawn_magpie_for_game(game_id: u32, magpie_path: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start_magpie_for_game(game_id, &magpie_path).await {
+            warn!("启动Magpie失败: {}", e);
+        }
+        wait_for_game_exit(game_id).await;
+        release_magpie_for_game(game_id).await;
+    });
+}
+
 /// 为游戏启动Magpie放大
-async fn start_magpie_for_game(magpie_path: &str) -> Result<(), String> {
+async fn start_magpie_for_game(game_id: u32, magpie_path: &str) -> Result<(), String> {
     // 检查Magpie是否已经在运行
     let magpie_was_running = is_process_running("Magpie.exe");
 
@@ -487,21 +574,39 @@ async fn start_magpie_for_game(magpie_path: &str) -> Result<(), String> {
         let mut command = Command::new(magpie_path);
         command.arg("-t"); // tray mode
 
-        let spawn_result = command.gui_safe().spawn();
-        match spawn_result {
-            Ok(_child) => {
-                debug!("Magpie启动成功，等待游戏窗口加载...");
-            }
-            Err(e) => {
-                return Err(format!("启动Magpie失败: {}", e));
-            }
-        }
+        let child = command
+            .gui_safe()
+            .spawn()
+            .map_err(|e| format!("启动Magpie失败: {}", e))?;
+        debug!("Magpie启动成功 pid={}，等待游戏窗口加载...", child.id());
+        *MAGPIE_OWNERSHIP.lock() = Some(MagpieOwnership {
+            pid: child.id(),
+            game_ids: HashSet::from([game_id]),
+        });
+        // 等待 Magpie 注册全局快捷键
+        time::sleep(Duration::from_secs(MAGPIE_STARTUP_DELAY_SECS)).await;
     } else {
         debug!("Magpie已经在运行中，准备激活放大...");
+        let mut ownership = MAGPIE_OWNERSHIP.lock();
+        match ownership.as_mut() {
+            // 仍是管理器启动的实例，由本游戏共同持有
+            Some(owned) if monitor_is_process_running(owned.pid) => {
+                owned.game_ids.insert(game_id);
+            }
+            // 之前拉起的实例已被用户关闭，当前运行的是用户自己的 Magpie
+            _ => *ownership = None,
+        }
     }
 
-    // 等待游戏窗口加载（无论Magpie是否新启动）
-    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    // 放大作用于前台窗口，等待游戏窗口获得前台后再触发，避免放大其他程序
+    if !wait_for_game_foreground(game_id, Duration::from_secs(MAGPIE_WINDOW_TIMEOUT_SECS)).await {
+        return Err(format!(
+            "{} 秒内未检测到游戏 {} 的前台窗口，已跳过放大",
+            MAGPIE_WINDOW_TIMEOUT_SECS, game_id
+        ));
+    }
+    // 窗口刚出现时可能仍在初始化分辨率，稍等再放大
+    time::sleep(Duration::from_secs(MAGPIE_SCALE_DELAY_SECS)).await;
 
     // 模拟Win+Shift+A快捷键激活放大
     match keyboard_simulator::simulate_win_shift_a() {
@@ -510,20 +615,37 @@ async fn start_magpie_for_game(magpie_path: &str) -> Result<(), String> {
             Ok(())
         }
         Err(e) => {
-            let error_msg = format!("Magpie放大激活失败: {}", e);
-            if magpie_was_running {
-                warn!("{}（Magpie进程已在运行）", error_msg);
-                // 如果Magpie本来就在运行，键盘模拟失败也不算严重错误
-                Ok(())
-            } else {
-                warn!("{}，但Magpie进程已启动", error_msg);
-                // 如果Magpie是刚启动的，键盘模拟失败也不算严重错误
-                Ok(())
-            }
+            // 键盘模拟失败不影响游戏本身，只记录警告
+            warn!("Magpie放大激活失败: {}", e);
+            Ok(())
         }
     }
 }
 
+/// 游戏退出后释放对 Magpie 的占用，没有游戏再使用时关闭管理器启动的 Magpie
+async fn release_magpie_for_game(game_id: u32) {
+    let pid = {
+        let mut ownership = MAGPIE_OWNERSHIP.lock();
+        let Some(owned) = ownership.as_mut() else {
+            return;
+        };
+        if !owned.game_ids.remove(&game_id) || !owned.game_ids.is_empty() {
+            return;
+        }
+        let pid = owned.pid;
+        *ownership = None;
+        pid
+    };
+
+    if !monitor_is_process_running(pid) {
+        return;
+    }
+    match kill_process_gracefully(pid, Duration::from_secs(MAGPIE_CLOSE_TIMEOUT_SECS)).await {
+        Ok(_) => info!("游戏 {} 已退出，已关闭 Magpie pid={}", game_id, pid),
+        Err(e) => warn!("关闭 Magpie 失败 pid={}: {}", pid, e),
+    }
+}
+
 /// 检查指定名称的进程是否在运行（使用 Windows ToolHelp API）
 fn is_process_running(process_name: &str) -> bool {
     use std::mem;
@@ -0,0 +1,162 @@
+//! Linux 下通过 Wine/Proton 运行 Windows 游戏
+//!
+//! 根据全局与游戏级 [`WineConfig`] 生成兼容层的启动程序、参数与环境变量；
+//! 未配置兼容层路径时回退到设置中的 Linux 启动命令（默认 `wine`）。
+
+use crate::entity::launch_options::{WineConfig, WineRunner};
+use std::path::Path;
+
+/// DXVK 接管的 Direct3D 相关 DLL
+const DXVK_DLLS: &str = "d3d9,d3d10core,d3d11,dxgi";
+
+/// 兼容层启动方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WineInvocation {
+    pub program: String,
+    /// 位于游戏路径之前的参数（例如 proton 的 `run`）
+    pub args: Vec<String>,
+    pub envs: Vec<(String, String)>,
+}
+
+/// 判断启动文件是否需要经由兼容层运行
+pub fn needs_wine(executable: &Path) -> bool {
+    executable
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["exe", "bat", "msi"]
+                .iter()
+                .any(|candidate| ext.eq_ignore_ascii_case(candidate))
+        })
+}
+
+/// 生成兼容层启动方式
+///
+/// # Arguments
+/// * `config` - 合并后的 Wine 配置
+/// * `default_command` - 设置中的 Linux 启动命令，未配置 runner_path 时使用
+/// * `home_dir` - 用户主目录，用于展开 `~` 与定位 Steam 安装目录
+pub fn build_invocation(
+    config: &WineConfig,
+    default_command: &str,
+    home_dir: Option<&Path>,
+) -> Result<WineInvocation, String> {
+    let expand = |path: &str| expand_home(path, home_dir);
+    let runner = config.runner.unwrap_or(WineRunner::Wine);
+    let prefix = config.prefix.as_deref().map(expand);
+    let mut envs = Vec::new();
+
+    let (program, args) = match runner {
+        WineRunner::Wine => {
+            if let Some(prefix) = &prefix {
+                envs.push(("WINEPREFIX".to_string(), prefix.clone()));
+            }
+            if let Some(dxvk) = config.dxvk {
+                // 启用时优先使用前缀内安装的 DXVK（native），否则强制内置 WineD3D
+                let mode = if dxvk { "n,b" } else { "b" };
+                envs.push((
+                    "WINEDLLOVERRIDES".to_string(),
+                    format!("{}={}", DXVK_DLLS, mode),
+                ));
+            }
+            let program = config
+                .runner_path
+                .as_deref()
+                .map(expand)
+                .unwrap_or_else(|| expand(default_command));
+            (program, Vec::new())
+        }
+        WineRunner::Proton => {
+            let program = config
+                .runner_path
+                .as_deref()
+                .map(expand)
+                .ok_or_else(|| "Proton 模式需要配置 proton 脚本路径".to_string())?;
+            let prefix = prefix.ok_or_else(|| "Proton 模式需要配置前缀目录".to_string())?;
+            envs.push(("STEAM_COMPAT_DATA_PATH".to_string(), prefix.clone()));
+
+            // proton 需要知道 Steam 安装目录，未安装 Steam 时指向前缀目录即可
+            let steam_dir = home_dir
+                .map(|home| home.join(".steam/steam"))
+                .filter(|dir| dir.is_dir())
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or(prefix);
+            envs.push(("STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(), steam_dir));
+
+            if config.dxvk == Some(false) {
+                envs.push(("PROTON_USE_WINED3D".to_string(), "1".to_string()));
+            }
+            (program, vec!["run".to_string()])
+        }
+    };
+
+    if let Some(hud) = &config.dxvk_hud {
+        envs.push(("DXVK_HUD".to_string(), hud.clone()));
+    }
+
+    Ok(WineInvocation {
+        program,
+        args,
+        envs,
+    })
+}
+
+fn expand_home(path: &str, home_dir: Option<&Path>) -> String {
+    match (path.strip_prefix('~'), home_dir) {
+        (Some(rest), Some(home)) => format!("{}{}", home.to_string_lossy(), rest),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wine_uses_prefix_and_dxvk_overrides() {
+        let config = WineConfig {
+            runner: Some(WineRunner::Wine),
+            prefix: Some("~/Games/prefix".to_string()),
+            dxvk: Some(false),
+            dxvk_hud: Some("fps".to_string()),
+            ..Default::default()
+        };
+
+        let invocation = build_invocation(&config, "wine", Some(Path::new("/home/reina"))).unwrap();
+
+        assert_eq!(invocation.program, "wine");
+        assert!(invocation.args.is_empty());
+        assert!(invocation.envs.contains(&(
+            "WINEPREFIX".to_string(),
+            "/home/reina/Games/prefix".to_string()
+        )));
+        assert!(invocation.envs.contains(&(
+            "WINEDLLOVERRIDES".to_string(),
+            "d3d9,d3d10core,d3d11,dxgi=b".to_string()
+        )));
+        assert!(
+            invocation
+                .envs
+                .contains(&("DXVK_HUD".to_string(), "fps".to_string()))
+        );
+    }
+
+    #[test]
+    fn proton_requires_script_and_prefix() {
+        let mut config = WineConfig {
+            runner: Some(WineRunner::Proton),
+            prefix: Some("/data/compat".to_string()),
+            ..Default::default()
+        };
+        assert!(build_invocation(&config, "wine", None).is_err());
+
+        config.runner_path = Some("/opt/proton/proton".to_string());
+        let invocation = build_invocation(&config, "wine", None).unwrap();
+        assert_eq!(invocation.program, "/opt/proton/proton");
+        assert_eq!(invocation.args, vec!["run".to_string()]);
+        assert!(invocation.envs.contains(&(
+            "STEAM_COMPAT_DATA_PATH".to_string(),
+            "/data/compat".to_string()
+        )));
+    }
+}
@@ -6,8 +6,12 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
-pub use session::TimeTrackingMode;
-pub(crate) use session::{MonitoredSession, finalize_monitored_session};
+pub use session::{KillOutcome, TimeTrackingMode, monitored_game_ids};
+pub(crate) use session::{
+    MonitorRegistration, MonitoredSession, acquire_session_sleep_inhibitor,
+    announce_session_started, finalize_monitored_session, is_game_monitored,
+    mark_session_stopped_by_user, wait_for_processes_exit,
+};
 
 #[cfg(target_os = "windows")]
 pub use windows::*;
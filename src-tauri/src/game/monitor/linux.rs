@@ -6,10 +6,15 @@
 // ============================================================================
 // 外部依赖导入
 // ============================================================================
-use super::{MonitoredSession, TimeTrackingMode, finalize_monitored_session};
+use super::{
+    KillOutcome, MonitorRegistration, MonitoredSession, TimeTrackingMode,
+    acquire_session_sleep_inhibitor, announce_session_started, finalize_monitored_session,
+    is_game_monitored, mark_session_stopped_by_user, monitored_game_ids, wait_for_processes_exit,
+};
 use log::{debug, error, info, warn};
 use sea_orm::DatabaseConnection;
 use serde_json::json;
+use std::process::Child;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Runtime};
 use tokio::sync::OnceCell;
@@ -37,22 +42,34 @@ static MANAGER_PROXY: OnceCell<zbus_systemd::systemd1::ManagerProxy<'static>> =
     OnceCell::const_new();
 
 /// 启动监控任务
+///
+/// 每个游戏运行在独立的 systemd scope 中，并拥有独立的监控任务，可同时监控多个游戏。
+///
+/// `child` 为 `systemd-run --scope` 子进程；scope 模式下 systemd-run 会直接 exec 游戏命令，
+/// 因此可在会话结束时回收它并读取游戏的退出码。
 pub async fn monitor_game<R: Runtime>(
     app_handle: AppHandle<R>,
     db: DatabaseConnection,
     time_tracking_mode: TimeTrackingMode,
-    game_id: u32,
-    process_id: u32,
+    registration: MonitorRegistration,
+    mut child: Child,
     systemd_scope: String,
 ) {
     let app_handle_clone = app_handle.clone();
+    let game_id = registration.game_id();
+    let process_id = child.id();
     tauri::async_runtime::spawn(async move {
         use tauri::Manager;
+        // 持有占位直到监控结束，避免同一游戏被重复启动监控
+        let _registration = registration;
+        // 按设置在会话期间阻止系统休眠，任务结束时自动释放
+        let _sleep_inhibitor = acquire_session_sleep_inhibitor(&db, game_id).await;
         if let Err(e) = run_game_monitor(
             app_handle_clone.app_handle(),
             &db,
             time_tracking_mode,
             game_id,
+            &mut child,
             &systemd_scope,
         )
         .await
@@ -69,6 +86,8 @@ pub async fn monitor_game<R: Runtime>(
                     start_time: timestamp,
                     end_time: timestamp,
                     accumulated_seconds: 0,
+                    // 游戏启动后立即退出（例如启动即崩溃）时同样需要上报退出码
+                    exit_code: read_exit_code(&mut child),
                 },
             )
             .await;
@@ -91,6 +110,115 @@ pub async fn stop_game_session(game_id: u32) -> Result<u32, String> {
     stop_game_unit(game_id).await.map(|_| 1)
 }
 
+/// 结束指定游戏的所有进程：先向 scope 发送 SIGTERM 请求优雅退出，超时后发送 SIGKILL
+///
+/// scope 内进程全部退出后，监控循环会检测到 scope 结束并照常结算时长；
+/// 会话先被标记为用户主动结束，游戏处理 SIGTERM 后返回的退出码不会被误报为崩溃。
+///
+/// # Arguments
+/// * `game_id` - 游戏 ID
+/// * `timeout` - 等待优雅退出的超时时间
+pub async fn kill_game_session(game_id: u32, timeout: Duration) -> Result<KillOutcome, String> {
+    if !is_game_monitored(game_id) {
+        return Err(format!("未找到游戏 {} 的监控会话", game_id));
+    }
+
+    let unit_name = format!("reina_game_{}.scope", game_id);
+    let pids = get_all_candidate_pids(&unit_name).await;
+    if pids.is_empty() {
+        return Ok(KillOutcome {
+            graceful: true,
+            killed_count: 0,
+        });
+    }
+
+    mark_session_stopped_by_user(game_id);
+    kill_game_unit(&unit_name, SIGTERM).await?;
+    if wait_for_processes_exit(&pids, timeout, is_process_running).await {
+        return Ok(KillOutcome {
+            graceful: true,
+            killed_count: 0,
+        });
+    }
+
+    let remaining = get_all_candidate_pids(&unit_name).await;
+    kill_game_unit(&unit_name, SIGKILL).await?;
+    info!(
+        "游戏 {} 优雅退出超时，已强制终止 {} 个进程",
+        game_id,
+        remaining.len()
+    );
+    Ok(KillOutcome {
+        graceful: false,
+        killed_count: remaining.len() as u32,
+    })
+}
+
+/// 结束指定 PID 的进程：先发送 SIGTERM，超时后发送 SIGKILL
+///
+/// 若该进程属于正在监控的游戏，先将会话标记为用户主动结束，
+/// 会话在 scope 结束后由监控循环结算，退出码不会被误报为崩溃。
+pub async fn kill_process_gracefully(pid: u32, timeout: Duration) -> Result<KillOutcome, String> {
+    if pid == std::process::id() {
+        return Err("不能结束管理器自身进程".to_string());
+    }
+    if !is_process_running(pid) {
+        return Err(format!("进程 {} 未在运行", pid));
+    }
+
+    for game_id in monitored_game_ids() {
+        let unit_name = format!("reina_game_{}.scope", game_id);
+        if get_all_candidate_pids(&unit_name).await.contains(&pid) {
+            mark_session_stopped_by_user(game_id);
+            break;
+        }
+    }
+
+    send_signal(pid, "TERM")?;
+    if wait_for_processes_exit(&[pid], timeout, is_process_running).await {
+        return Ok(KillOutcome {
+            graceful: true,
+            killed_count: 0,
+        });
+    }
+
+    send_signal(pid, "KILL")?;
+    info!("进程 {} 优雅退出超时，已强制终止", pid);
+    Ok(KillOutcome {
+        graceful: false,
+        killed_count: 1,
+    })
+}
+
+const SIGTERM: i32 = 15;
+const SIGKILL: i32 = 9;
+
+/// 向 scope 内所有进程发送信号
+async fn kill_game_unit(unit_name: &str, signal: i32) -> Result<(), String> {
+    let proxy = get_manager_proxy()
+        .await
+        .map_err(|e| format!("无法连接到 D-Bus Session Bus: {}", e))?;
+
+    proxy
+        .kill_unit(unit_name.to_string(), "all".to_string(), signal)
+        .await
+        .map_err(|e| format!("向 {} 发送信号 {} 失败: {}", unit_name, signal, e))
+}
+
+/// 通过 `kill` 命令向单个进程发送信号
+fn send_signal(pid: u32, signal: &str) -> Result<(), String> {
+    let status = std::process::Command::new("kill")
+        .args(["-s", signal, &pid.to_string()])
+        .status()
+        .map_err(|e| format!("执行 kill 命令失败: {}", e))?;
+
+    if status.success() || !is_process_running(pid) {
+        Ok(())
+    } else {
+        Err(format!("向进程 {} 发送 SIG{} 失败", pid, signal))
+    }
+}
+
 async fn stop_game_unit(game_id: u32) -> Result<(), String> {
     // 1. 连接到 Session Bus (对应 --user)
     let proxy = get_manager_proxy().await.map_err(|e| {
@@ -176,6 +304,7 @@ async fn get_all_candidate_pids(systemd_scope: &str) -> Vec<u32> {
         .unwrap_or_default()
         .into_iter()
         .filter(|&pid| pid != manager_pid) // 过滤掉管理器自身
+        .filter(|&pid| !is_wine_infrastructure(pid)) // 过滤掉 wine 常驻服务进程
         .collect();
 
     if available_pids.is_empty() {
@@ -190,12 +319,42 @@ async fn get_all_candidate_pids(systemd_scope: &str) -> Vec<u32> {
 
     available_pids
 }
-#[allow(unused)]
+/// Wine/Proton 的常驻服务进程名（/proc/<pid>/comm）
+///
+/// 游戏退出后 wineserver 等进程仍会在 scope 中停留一段时间，
+/// 它们不代表游戏仍在运行，不参与最佳进程选择与存活判定。
+const WINE_INFRASTRUCTURE_PROCESSES: [&str; 10] = [
+    "wineserver",
+    "services.exe",
+    "winedevice.exe",
+    "plugplay.exe",
+    "explorer.exe",
+    "rpcss.exe",
+    "svchost.exe",
+    "conhost.exe",
+    "tabtip.exe",
+    "start.exe",
+];
+
+fn is_wine_infrastructure(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid)).is_ok_and(|comm| {
+        let comm = comm.trim();
+        WINE_INFRASTRUCTURE_PROCESSES
+            .iter()
+            .any(|name| comm.eq_ignore_ascii_case(name))
+    })
+}
+
 fn is_process_running(pid: u32) -> bool {
-    use std::fs::exists;
-    // 在 Linux 上，可以通过检查 /proc/<pid> 目录是否存在来判断进程是否运行
-    let proc_path = format!("/proc/{}", pid);
-    exists(&proc_path).unwrap_or(false)
+    // 在 Linux 上，可以通过 /proc/<pid>/stat 判断进程是否运行；
+    // 已退出但尚未被回收的僵尸进程（状态 Z）视为未运行
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(stat) => stat
+            .rsplit_once(')')
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .is_some_and(|state| state != "Z" && state != "X"),
+        Err(_) => false,
+    }
 }
 
 /// 检查指定的 systemd user scope 是否处于活动状态（仅 Linux）。
@@ -417,6 +576,7 @@ async fn run_game_monitor(
     db: &DatabaseConnection,
     time_tracking_mode: TimeTrackingMode,
     game_id: u32,
+    child: &mut Child,
     systemd_scope: &str,
 ) -> Result<(), String> {
     // Linux 版本的监控逻辑实现
@@ -442,12 +602,7 @@ async fn run_game_monitor(
     );
 
     // 通知前端会话开始
-    if let Err(error) = app_handle.emit(
-        "game-session-started",
-        json!({ "gameId": game_id, "processId": best_pid, "startTime": start_time }),
-    ) {
-        warn!("无法发送 game-session-started 事件: {error}");
-    }
+    announce_session_started(app_handle, db, game_id, best_pid, start_time).await;
     let mut consecutive_failures = 0u32;
 
     // 等待 9 秒让游戏进程充分启动（例如 Launcher -> Game 的切换）
@@ -473,7 +628,9 @@ async fn run_game_monitor(
     loop {
         tick_interval.tick().await;
 
-        let game_running = is_game_running(systemd_scope).await;
+        // scope 仍处于 active 但只剩 wine 服务进程时，同样视为游戏已退出
+        let game_running = is_game_running(systemd_scope).await
+            && !get_all_candidate_pids(systemd_scope).await.is_empty();
         if !game_running {
             consecutive_failures += 1;
             debug!(
@@ -537,6 +694,8 @@ async fn run_game_monitor(
         }
     }
 
+    let exit_code = read_exit_code(child);
+
     finalize_monitored_session(
         app_handle,
         db,
@@ -547,6 +706,7 @@ async fn run_game_monitor(
             start_time,
             end_time: get_timestamp(),
             accumulated_seconds,
+            exit_code,
         },
     )
     .await;
@@ -554,6 +714,26 @@ async fn run_game_monitor(
     Ok(())
 }
 
+/// 回收 `systemd-run --scope` 子进程并读取退出码
+///
+/// scope 已结束时子进程应已退出；被信号终止（如用户停止游戏）时没有退出码，返回 None。
+fn read_exit_code(child: &mut Child) -> Option<i32> {
+    match child.try_wait() {
+        Ok(Some(status)) => status.code(),
+        Ok(None) => {
+            debug!(
+                "scope 已结束但子进程 {} 仍未退出，跳过退出码读取",
+                child.id()
+            );
+            None
+        }
+        Err(e) => {
+            debug!("读取子进程 {} 退出状态失败: {}", child.id(), e);
+            None
+        }
+    }
+}
+
 // ============================================================================
 // 工具函数
 // ============================================================================
@@ -1,8 +1,18 @@
 use crate::database::repository::game_stats_repository::GameStatsRepository;
-use log::{error, info, warn};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::game::hooks::{HookStage, run_game_hooks};
+use crate::utils::discord_rpc::{self, PresenceActivity};
+use crate::utils::obs_output::{start_obs_output, stop_obs_output};
+use crate::utils::power::{SleepInhibitor, inhibit_sleep_for_game};
+use crate::utils::webhook::{WebhookEvent, notify_webhook};
+use log::{debug, error, info, warn};
+use parking_lot::RwLock;
 use sea_orm::DatabaseConnection;
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashSet;
+use std::sync::OnceLock;
 use tauri::{AppHandle, Emitter, Runtime};
 
 const MIN_SESSION_SECONDS: u64 = 60;
@@ -24,6 +34,209 @@ fn round_seconds_to_minutes(seconds: u64) -> u64 {
     seconds / 60 + u64::from(seconds % 60 >= 30)
 }
 
+/// 正在监控的游戏 ID 集合，保证同一游戏同时只有一个监控会话
+static MONITORED_GAMES: OnceLock<RwLock<HashSet<u32>>> = OnceLock::new();
+
+fn monitored_games() -> &'static RwLock<HashSet<u32>> {
+    MONITORED_GAMES.get_or_init(|| RwLock::new(HashSet::new()))
+}
+
+/// 用户主动结束过进程的游戏 ID，会话结算时不按崩溃上报退出码
+static STOPPED_BY_USER: OnceLock<RwLock<HashSet<u32>>> = OnceLock::new();
+
+fn stopped_by_user() -> &'static RwLock<HashSet<u32>> {
+    STOPPED_BY_USER.get_or_init(|| RwLock::new(HashSet::new()))
+}
+
+/// 标记游戏会话由用户主动结束，应在终止进程前调用
+pub(crate) fn mark_session_stopped_by_user(game_id: u32) {
+    if is_game_monitored(game_id) {
+        stopped_by_user().write().insert(game_id);
+    }
+}
+
+/// 单个游戏的监控占位
+///
+/// 启动游戏前获取，随监控任务一起移动，析构时自动释放；
+/// 不同 game_id 的会话互不影响，可以同时监控多个游戏。
+pub(crate) struct MonitorRegistration {
+    game_id: u32,
+}
+
+impl MonitorRegistration {
+    /// 为指定游戏占用监控槽位，同一游戏已在监控中时返回错误
+    pub(crate) fn acquire(game_id: u32) -> Result<Self, String> {
+        if !monitored_games().write().insert(game_id) {
+            return Err(format!("游戏 {} 已在运行中", game_id));
+        }
+        debug!("已登记游戏监控会话: game_id={}", game_id);
+        #[cfg(target_os = "windows")]
+        crate::game::screenshots::hotkey::refresh_screenshot_hotkey();
+        Ok(Self { game_id })
+    }
+
+    pub(crate) fn game_id(&self) -> u32 {
+        self.game_id
+    }
+}
+
+impl Drop for MonitorRegistration {
+    fn drop(&mut self) {
+        monitored_games().write().remove(&self.game_id);
+        stopped_by_user().write().remove(&self.game_id);
+        debug!("已注销游戏监控会话: game_id={}", self.game_id);
+        #[cfg(target_os = "windows")]
+        crate::game::screenshots::hotkey::refresh_screenshot_hotkey();
+    }
+}
+
+/// 获取当前正在监控的所有游戏 ID
+pub fn monitored_game_ids() -> Vec<u32> {
+    let mut game_ids: Vec<u32> = monitored_games().read().iter().copied().collect();
+    game_ids.sort_unstable();
+    game_ids
+}
+
+/// 指定游戏当前是否处于监控中
+pub(crate) fn is_game_monitored(game_id: u32) -> bool {
+    monitored_games().read().contains(&game_id)
+}
+
+/// 结束进程的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KillOutcome {
+    /// 所有进程是否都在超时前自行退出
+    pub graceful: bool,
+    /// 超时后被强制终止的进程数量
+    pub killed_count: u32,
+}
+
+/// 轮询等待进程全部退出，超时返回 false
+pub(crate) async fn wait_for_processes_exit(
+    pids: &[u32],
+    timeout: std::time::Duration,
+    is_running: fn(u32) -> bool,
+) -> bool {
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if !pids.iter().any(|&pid| is_running(pid)) {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// 按用户设置为游戏会话阻止系统休眠，守卫应持有到会话结束
+pub(crate) async fn acquire_session_sleep_inhibitor(
+    db: &DatabaseConnection,
+    game_id: u32,
+) -> Option<SleepInhibitor> {
+    let enabled = match db.get_settings().await {
+        Ok(settings) => settings.prevent_sleep_enabled(),
+        Err(error) => {
+            warn!("读取休眠抑制设置失败，按关闭处理: {error}");
+            false
+        }
+    };
+    inhibit_sleep_for_game(enabled, game_id)
+}
+
+/// 通知前端会话开始，并按用户设置推送 Discord 状态、Webhook 与 OBS 文本
+pub(crate) async fn announce_session_started<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    game_id: u32,
+    process_id: u32,
+    start_time: u64,
+) {
+    if let Err(error) = app_handle.emit(
+        "game-session-started",
+        json!({ "gameId": game_id, "processId": process_id, "startTime": start_time }),
+    ) {
+        warn!("无法发送 game-session-started 事件: {error}");
+    }
+    notify_webhook(
+        db,
+        game_id as i32,
+        WebhookEvent::SessionStarted {
+            process_id,
+            start_time,
+        },
+    );
+
+    match build_presence_activity(db, game_id, start_time).await {
+        Ok(Some(activity)) => discord_rpc::update_presence(game_id, activity),
+        Ok(None) => {}
+        Err(error) => warn!("生成 Discord 状态失败: game_id={}, {}", game_id, error),
+    }
+    start_obs_output(db, game_id, start_time).await;
+}
+
+/// 根据设置、游戏信息和累计时长生成 Discord 状态，未启用时返回 None
+async fn build_presence_activity(
+    db: &DatabaseConnection,
+    game_id: u32,
+    start_time: u64,
+) -> Result<Option<PresenceActivity>, String> {
+    let settings = db.get_settings().await?.discord_rpc_settings();
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let db_game_id = i32::try_from(game_id).map_err(|_| "游戏 ID 超出范围".to_string())?;
+    let game = GamesRepository::find_by_id(db, db_game_id)
+        .await
+        .map_err(|e| format!("查询游戏失败: {}", e))?
+        .ok_or_else(|| format!("游戏 {} 不存在", game_id))?;
+    let playtime_minutes = GameStatsRepository::get_statistics(db, db_game_id)
+        .await
+        .map_err(|e| format!("查询游戏统计失败: {}", e))?
+        .and_then(|stats| stats.total_time)
+        .map_or(0, |minutes| u64::try_from(minutes).unwrap_or(0));
+
+    let hidden = settings.hide_nsfw && game.is_nsfw();
+    let name = if hidden {
+        settings
+            .nsfw_placeholder
+            .clone()
+            .filter(|placeholder| !placeholder.trim().is_empty())
+            .unwrap_or_else(|| discord_rpc::DEFAULT_NSFW_PLACEHOLDER.to_string())
+    } else {
+        game.display_name()
+            .unwrap_or_else(|| format!("游戏 #{}", game_id))
+    };
+
+    let render = |template: Option<&str>, default: &str| {
+        let template = template.filter(|template| !template.trim().is_empty());
+        discord_rpc::render_template(template.unwrap_or(default), &name, playtime_minutes)
+    };
+    let details = render(
+        settings.details_template.as_deref(),
+        discord_rpc::DEFAULT_DETAILS_TEMPLATE,
+    );
+    let state = render(
+        settings.state_template.as_deref(),
+        discord_rpc::DEFAULT_STATE_TEMPLATE,
+    );
+
+    Ok(Some(PresenceActivity {
+        details,
+        state: Some(state),
+        start_time,
+        large_image: if hidden {
+            None
+        } else {
+            game.remote_cover_url()
+        },
+        large_text: Some(name),
+    }))
+}
+
 pub(crate) struct MonitoredSession {
     pub time_tracking_mode: TimeTrackingMode,
     pub game_id: u32,
@@ -31,6 +244,8 @@ pub(crate) struct MonitoredSession {
     pub start_time: u64,
     pub end_time: u64,
     pub accumulated_seconds: u64,
+    /// 游戏进程退出码，无法获取或由用户主动停止时为 None
+    pub exit_code: Option<i32>,
 }
 
 fn calculate_session_duration(
@@ -56,11 +271,44 @@ fn calculate_session_duration(
     }))
 }
 
+/// 会话结束后执行游戏配置的结束后脚本，失败时通知前端
+async fn run_post_exit_hooks<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    game_id: u32,
+) {
+    let game = match GamesRepository::find_by_id(db, game_id as i32).await {
+        Ok(Some(game)) => game,
+        Ok(None) => return,
+        Err(error) => {
+            warn!("读取游戏 {} 的结束后脚本失败: {error}", game_id);
+            return;
+        }
+    };
+
+    if let Err(error) = run_game_hooks(&game, HookStage::PostExit).await {
+        warn!("游戏 {} {error}", game_id);
+        if let Err(emit_error) = app_handle.emit(
+            "game-hook-failed",
+            json!({ "gameId": game_id, "stage": "post_exit", "error": error }),
+        ) {
+            warn!("无法发送 game-hook-failed 事件: {emit_error}");
+        }
+    }
+}
+
 pub(crate) async fn finalize_monitored_session<R: Runtime>(
     app_handle: &AppHandle<R>,
     db: &DatabaseConnection,
-    session: MonitoredSession,
+    mut session: MonitoredSession,
 ) {
+    // 用户主动结束的进程退出码不代表崩溃
+    if stopped_by_user().write().remove(&session.game_id) {
+        session.exit_code = None;
+    }
+    discord_rpc::remove_presence(session.game_id);
+    stop_obs_output(session.game_id).await;
+
     let foreground_minutes = round_seconds_to_minutes(session.accumulated_seconds);
     let session_duration = calculate_session_duration(
         session.time_tracking_mode,
@@ -91,6 +339,7 @@ pub(crate) async fn finalize_monitored_session<R: Runtime>(
                         start_time,
                         end_time,
                         stored_duration_minutes,
+                        session.exit_code,
                     )
                     .await
                     {
@@ -141,10 +390,48 @@ pub(crate) async fn finalize_monitored_session<R: Runtime>(
             "sessionId": session_id,
             "durationMinutes": duration_minutes,
             "recordError": record_error,
+            "exitCode": session.exit_code,
         }),
     ) {
         warn!("无法发送 game-session-ended 事件: {error}");
     }
+    notify_webhook(
+        db,
+        session.game_id as i32,
+        WebhookEvent::SessionEnded {
+            start_time: session.start_time,
+            end_time: session.end_time,
+            duration_minutes: if recorded { duration_minutes } else { 0 },
+            recorded,
+            exit_code: session.exit_code,
+        },
+    );
+
+    // 结束后脚本在后台执行，不占用游戏的监控槽位
+    let (hook_app_handle, hook_db, hook_game_id) =
+        (app_handle.clone(), db.clone(), session.game_id);
+    tauri::async_runtime::spawn(async move {
+        run_post_exit_hooks(&hook_app_handle, &hook_db, hook_game_id).await;
+    });
+
+    if let Some(exit_code) = session.exit_code.filter(|code| *code != 0) {
+        warn!(
+            "游戏进程异常退出: game_id={}, pid={}, exit_code={}",
+            session.game_id, session.process_id, exit_code
+        );
+        if let Err(error) = app_handle.emit(
+            "game-crashed",
+            json!({
+                "gameId": session.game_id,
+                "processId": session.process_id,
+                "exitCode": exit_code,
+                "endTime": session.end_time,
+                "sessionId": session_id,
+            }),
+        ) {
+            warn!("无法发送 game-crashed 事件: {error}");
+        }
+    }
 }
 
 #[cfg(test)]
@@ -181,6 +468,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn registration_is_exclusive_per_game() {
+        let first = MonitorRegistration::acquire(900_001).expect("首次登记应成功");
+        let other = MonitorRegistration::acquire(900_002).expect("不同游戏应可同时登记");
+
+        assert!(MonitorRegistration::acquire(900_001).is_err());
+        assert!(monitored_game_ids().contains(&900_001));
+        assert!(monitored_game_ids().contains(&900_002));
+
+        drop(first);
+        assert!(!monitored_game_ids().contains(&900_001));
+        assert!(MonitorRegistration::acquire(900_001).is_ok());
+        drop(other);
+    }
+
+    #[test]
+    fn stopped_by_user_mark_is_cleared_with_registration() {
+        mark_session_stopped_by_user(900_101);
+        assert!(!stopped_by_user().read().contains(&900_101));
+
+        let registration = MonitorRegistration::acquire(900_101).unwrap();
+        mark_session_stopped_by_user(900_101);
+        assert!(stopped_by_user().read().contains(&900_101));
+        drop(registration);
+        assert!(!stopped_by_user().read().contains(&900_101));
+    }
+
     #[test]
     fn duration_below_threshold_is_not_recorded() {
         assert_eq!(
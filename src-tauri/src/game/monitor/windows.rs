@@ -3,7 +3,11 @@
 //! 使用事件驱动架构监控游戏进程的运行状态，追踪游戏时间。
 //! 包含前台窗口检测、进程切换处理、逃逸进程检测等功能。
 
-use super::{MonitoredSession, TimeTrackingMode, finalize_monitored_session};
+use super::{
+    KillOutcome, MonitorRegistration, MonitoredSession, TimeTrackingMode,
+    acquire_session_sleep_inhibitor, announce_session_started, finalize_monitored_session,
+    is_game_monitored, mark_session_stopped_by_user, wait_for_processes_exit,
+};
 use sea_orm::DatabaseConnection;
 
 // ============================================================================
@@ -17,7 +21,7 @@ use std::sync::{
     atomic::{AtomicBool, Ordering},
 };
 use std::time::SystemTime;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Runtime};
 use tokio::time::{MissedTickBehavior, interval};
 
@@ -26,7 +30,7 @@ use {
 };
 
 use windows::Win32::{
-    Foundation::CloseHandle,
+    Foundation::{CloseHandle, HANDLE, HWND, LPARAM, WPARAM},
     System::{
         Diagnostics::ToolHelp::{
             CREATE_TOOLHELP_SNAPSHOT_FLAGS, CreateToolhelp32Snapshot, PROCESSENTRY32W,
@@ -37,8 +41,12 @@ use windows::Win32::{
             PROCESS_TERMINATE, QueryFullProcessImageNameW, TerminateProcess,
         },
     },
-    UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
+    UI::WindowsAndMessaging::{
+        EnumWindows, GetForegroundWindow, GetWindowThreadProcessId, IsWindowVisible, PostMessageW,
+        WM_CLOSE,
+    },
 };
+use windows::core::BOOL;
 
 // ============================================================================
 // 常量定义
@@ -53,6 +61,12 @@ const TIME_UPDATE_INTERVAL_SECS: u64 = 1;
 /// 监控循环检查间隔（秒）
 const MONITOR_CHECK_INTERVAL_SECS: u64 = 1;
 
+/// 进程树刷新间隔（监控循环次数），用于发现游戏运行中新拉起的子进程
+const PROCESS_TREE_REFRESH_TICKS: u64 = 5;
+
+/// 等待游戏窗口就绪或会话结束时的轮询间隔（毫秒）
+const SESSION_POLL_INTERVAL_MS: u64 = 500;
+
 // ============================================================================
 // 数据结构定义
 // ============================================================================
@@ -88,6 +102,61 @@ impl MonitorState {
     }
 }
 
+/// 进程退出码观察者
+///
+/// Windows 进程对象在最后一个句柄关闭后即被销毁，进程退出后再 `OpenProcess`
+/// 无法读取退出码，因此需要在进程存活期间提前持有句柄。
+/// 句柄以整数形式保存，保证监控 Future 满足 `Send`。
+struct ProcessExitWatcher {
+    pid: u32,
+    raw_handle: usize,
+}
+
+impl ProcessExitWatcher {
+    /// 打开指定进程的查询句柄，进程已退出或无权访问时返回 None
+    fn open(pid: u32) -> Option<Self> {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+        if handle.is_invalid() {
+            return None;
+        }
+        Some(Self {
+            pid,
+            raw_handle: handle.0 as usize,
+        })
+    }
+
+    fn handle(&self) -> HANDLE {
+        HANDLE(self.raw_handle as *mut std::ffi::c_void)
+    }
+
+    /// 读取进程退出码，进程仍在运行（STILL_ACTIVE = 259）或读取失败时返回 None
+    fn exit_code(&self) -> Option<i32> {
+        let mut exit_code: u32 = 0;
+        unsafe { GetExitCodeProcess(self.handle(), &mut exit_code) }.ok()?;
+        // NTSTATUS 等异常退出码按位转换为 i32 存储
+        (exit_code != 259).then_some(exit_code as i32)
+    }
+}
+
+impl Drop for ProcessExitWatcher {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.handle());
+        }
+    }
+}
+
+/// 切换退出码观察目标，PID 未变化时保留原句柄
+fn watch_process_exit(watcher: &mut Option<ProcessExitWatcher>, pid: u32) {
+    if watcher.as_ref().is_some_and(|current| current.pid == pid) {
+        return;
+    }
+    *watcher = ProcessExitWatcher::open(pid);
+    if watcher.is_none() {
+        debug!("无法持有进程 {} 的句柄，结束时将无法读取退出码", pid);
+    }
+}
+
 /// Hook 线程守卫，确保线程在任何退出情况下都能正确停止
 ///
 /// 使用 RAII 模式，在析构时自动发送停止信号
@@ -181,13 +250,216 @@ pub async fn stop_game_session(game_id: u32) -> Result<u32, String> {
     Ok(terminated_count)
 }
 
+/// 结束指定游戏的所有进程：先向窗口发送 WM_CLOSE 请求优雅关闭，超时后强制终止
+///
+/// 优雅关闭成功时监控循环会照常检测到进程退出并结算时长；
+/// 需要强制终止时先发送停止信号，使会话按用户主动停止结算，不会被误报为崩溃。
+///
+/// # Arguments
+/// * `game_id` - 游戏 ID
+/// * `timeout` - 等待优雅关闭的超时时间
+pub async fn kill_game_session(game_id: u32, timeout: Duration) -> Result<KillOutcome, String> {
+    let (stop_signal, pids) = {
+        let sessions = get_sessions().read();
+        let session = sessions
+            .get(&game_id)
+            .ok_or_else(|| format!("未找到游戏 {} 的监控会话", game_id))?;
+        let pids: Vec<u32> = session.candidate_pids.read().iter().copied().collect();
+        (session.stop_signal.clone(), pids)
+    };
+
+    let running: Vec<u32> = pids
+        .into_iter()
+        .filter(|&pid| is_process_running(pid))
+        .collect();
+    let outcome = close_processes(&running, timeout, || {
+        stop_signal.store(true, Ordering::Release);
+    })
+    .await;
+
+    info!(
+        "游戏 {} 已结束: graceful={}, 强制终止 {} 个进程",
+        game_id, outcome.graceful, outcome.killed_count
+    );
+    Ok(outcome)
+}
+
+/// 结束指定 PID 的进程：先发送 WM_CLOSE，超时后强制终止
+///
+/// 若该进程属于正在监控的游戏，先将会话标记为用户主动结束，
+/// 会话在进程退出后由监控循环结算，退出码不会被误报为崩溃。
+pub async fn kill_process_gracefully(pid: u32, timeout: Duration) -> Result<KillOutcome, String> {
+    if pid == std::process::id() {
+        return Err("不能结束管理器自身进程".to_string());
+    }
+    if !is_process_running(pid) {
+        return Err(format!("进程 {} 未在运行", pid));
+    }
+
+    let owner = get_sessions()
+        .read()
+        .iter()
+        .find(|(_, session)| session.candidate_pids.read().contains(&pid))
+        .map(|(game_id, _)| *game_id);
+    if let Some(game_id) = owner {
+        mark_session_stopped_by_user(game_id);
+    }
+
+    Ok(close_processes(&[pid], timeout, || {}).await)
+}
+
+/// 请求关闭一组进程，超时后调用 `before_force` 并强制终止仍在运行的进程
+async fn close_processes(
+    pids: &[u32],
+    timeout: Duration,
+    before_force: impl FnOnce(),
+) -> KillOutcome {
+    let close_requested = pids
+        .iter()
+        .filter(|&&pid| request_close_windows(pid) > 0)
+        .count();
+    debug!(
+        "已向 {}/{} 个进程发送 WM_CLOSE: {:?}",
+        close_requested,
+        pids.len(),
+        pids
+    );
+
+    // 没有可见窗口的进程无法优雅关闭，直接强制终止
+    if close_requested > 0 && wait_for_processes_exit(pids, timeout, is_process_running).await {
+        return KillOutcome {
+            graceful: true,
+            killed_count: 0,
+        };
+    }
+
+    let remaining: Vec<u32> = pids
+        .iter()
+        .copied()
+        .filter(|&pid| is_process_running(pid))
+        .collect();
+    if remaining.is_empty() {
+        return KillOutcome {
+            graceful: true,
+            killed_count: 0,
+        };
+    }
+
+    before_force();
+
+    let mut killed_count = 0u32;
+    for pid in remaining {
+        match terminate_process(pid) {
+            Ok(_) => {
+                info!("优雅关闭超时，已强制终止进程 PID: {}", pid);
+                killed_count += 1;
+            }
+            Err(e) => warn!("强制终止进程 {} 失败: {}", pid, e),
+        }
+    }
+
+    KillOutcome {
+        graceful: false,
+        killed_count,
+    }
+}
+
+/// 向指定进程的所有可见顶层窗口投递 WM_CLOSE，返回投递成功的窗口数量
+fn request_close_windows(pid: u32) -> u32 {
+    struct CloseRequest {
+        pid: u32,
+        closed: u32,
+    }
+
+    unsafe extern "system" fn enum_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let request = unsafe { &mut *(lparam.0 as *mut CloseRequest) };
+        let mut window_pid = 0;
+        unsafe {
+            GetWindowThreadProcessId(hwnd, Some(&mut window_pid));
+            if window_pid == request.pid
+                && IsWindowVisible(hwnd).as_bool()
+                && PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)).is_ok()
+            {
+                request.closed += 1;
+            }
+        }
+        // 继续枚举
+        BOOL(1)
+    }
+
+    let mut request = CloseRequest { pid, closed: 0 };
+    unsafe {
+        if let Err(e) = EnumWindows(
+            Some(enum_window),
+            LPARAM(&mut request as *mut CloseRequest as isize),
+        ) {
+            debug!("枚举进程 {} 的窗口失败: {}", pid, e);
+        }
+    }
+    request.closed
+}
+
+/// 获取当前处于前台的受监控游戏及其窗口
+///
+/// 前台窗口所属进程不在任何会话的候选进程中时返回 None。
+pub fn foreground_game_window() -> Option<(u32, HWND)> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        return None;
+    }
+    let mut pid = 0;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if pid == 0 {
+        return None;
+    }
+    get_sessions()
+        .read()
+        .iter()
+        .find(|(_, session)| session.candidate_pids.read().contains(&pid))
+        .map(|(game_id, _)| (*game_id, hwnd))
+}
+
+/// 等待指定游戏的窗口成为前台窗口
+///
+/// Magpie 等工具作用于当前前台窗口，需要在游戏窗口就绪后再触发。
+///
+/// # Returns
+/// 游戏窗口在超时前获得前台时返回 true；游戏已退出或超时返回 false
+pub async fn wait_for_game_foreground(game_id: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_game_monitored(game_id) {
+            return false;
+        }
+        // 会话在游戏启动数秒后才注册，注册前候选进程为空
+        let ready = get_foreground_pid().is_some_and(|pid| {
+            get_sessions()
+                .read()
+                .get(&game_id)
+                .is_some_and(|session| session.candidate_pids.read().contains(&pid))
+        });
+        if ready {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(SESSION_POLL_INTERVAL_MS)).await;
+    }
+    false
+}
+
+/// 等待指定游戏的监控会话结束
+pub async fn wait_for_game_exit(game_id: u32) {
+    while is_game_monitored(game_id) {
+        tokio::time::sleep(Duration::from_millis(SESSION_POLL_INTERVAL_MS)).await;
+    }
+}
+
 /// 启动指定游戏进程的监控
 ///
 /// 这是模块的主入口函数，由外部调用以开始监控一个游戏进程。
 ///
 /// # Arguments
 /// * `app_handle` - Tauri 应用句柄，用于发送事件到前端
-/// * `game_id` - 游戏的唯一标识符
+/// * `registration` - 游戏的监控占位，监控任务结束时随之释放
 /// * `process_id` - 要开始监控的游戏进程的初始 PID
 /// * `detection_dir` - 游戏检测目录，用于在进程重启或切换后重新查找
 ///
@@ -195,17 +467,25 @@ pub async fn stop_game_session(game_id: u32) -> Result<u32, String> {
 /// 1. 创建 System 实例用于进程查询
 /// 2. 在异步任务中启动实际的监控循环
 /// 3. 监控循环会持续运行直到游戏进程结束
+///
+/// 每个游戏拥有独立的监控任务、前台检测线程和时间累计，可同时监控多个游戏。
 pub async fn monitor_game<R: Runtime>(
     app_handle: AppHandle<R>,
     db: DatabaseConnection,
     time_tracking_mode: TimeTrackingMode,
-    game_id: u32,
+    registration: MonitorRegistration,
     process_id: u32,
     detection_dir: String,
 ) {
     let app_handle_clone = app_handle.clone();
+    let game_id = registration.game_id();
 
     tauri::async_runtime::spawn(async move {
+        // 持有占位直到监控结束，避免同一游戏被重复启动监控
+        let _registration = registration;
+        // 按设置在会话期间阻止系统休眠，任务结束时自动释放
+        let _sleep_inhibitor = acquire_session_sleep_inhibitor(&db, game_id).await;
+
         if let Err(e) = run_game_monitor(
             app_handle_clone,
             db,
@@ -264,8 +544,8 @@ async fn run_game_monitor<R: Runtime>(
     debug!("等待 3 秒以便游戏进程充分启动...");
     tokio::time::sleep(Duration::from_secs(3)).await;
 
-    // 初始扫描：获取所有候选 PID
-    let candidate_pids = get_all_candidate_pids(&detection_dir);
+    // 初始扫描：获取所有候选 PID（游戏目录下的进程 + 初始进程的进程树）
+    let candidate_pids = get_all_candidate_pids(&detection_dir, &HashSet::from([initial_pid]));
     let mut candidate_pids_set: HashSet<u32> = candidate_pids.into_iter().collect();
     // 如果初始 PID 不在候选列表中，手动添加（容错）
     if !candidate_pids_set.contains(&initial_pid) && is_process_running(initial_pid) {
@@ -310,15 +590,13 @@ async fn run_game_monitor<R: Runtime>(
     let best_pid = monitor_state.read().best_pid;
 
     // 通知前端会话开始
-    if let Err(error) = app_handle.emit(
-        "game-session-started",
-        json!({ "gameId": game_id, "processId": best_pid, "startTime": start_time }),
-    ) {
-        warn!("无法发送 game-session-started 事件: {error}");
-    }
+    announce_session_started(&app_handle, &db, game_id, best_pid, start_time).await;
 
     let mut consecutive_failures = 0u32;
     let mut last_best_pid = best_pid;
+    let mut exit_watcher = ProcessExitWatcher::open(best_pid);
+    let mut stopped_by_user = false;
+    let mut tick_count = 0u64;
 
     // 创建精确的 1 秒间隔定时器
     let mut tick_interval = interval(Duration::from_secs(MONITOR_CHECK_INTERVAL_SECS));
@@ -331,6 +609,7 @@ async fn run_game_monitor<R: Runtime>(
         // 检查停止信号（支持外部停止）
         if stop_signal.load(Ordering::Acquire) {
             debug!("收到停止信号，结束监控游戏 {}", game_id);
+            stopped_by_user = true;
             break;
         }
 
@@ -353,8 +632,9 @@ async fn run_game_monitor<R: Runtime>(
             if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
                 warn!("最佳进程 {} 已失活，触发重新扫描", current_best_pid);
 
-                // 触发目录扫描，获取最新的候选 PID 列表
-                let new_candidate_pids_vec = get_all_candidate_pids(&detection_dir);
+                // 触发目录与进程树扫描，获取最新的候选 PID 列表
+                let tracked_pids = shared_candidate_pids.read().clone();
+                let new_candidate_pids_vec = get_all_candidate_pids(&detection_dir, &tracked_pids);
 
                 if new_candidate_pids_vec.is_empty() {
                     info!("未找到可切换的活动进程，结束监控会话");
@@ -382,16 +662,29 @@ async fn run_game_monitor<R: Runtime>(
                 debug!("成功切换到新的最佳进程 PID: {}", new_best_pid);
                 consecutive_failures = 0;
                 last_best_pid = new_best_pid;
+                watch_process_exit(&mut exit_watcher, new_best_pid);
                 continue;
             }
         } else {
             // 最佳 PID 仍在运行，重置失败计数
             consecutive_failures = 0;
 
+            // 定期刷新进程树：移除已退出的进程，并将新拉起的子孙进程纳入候选（可能不在游戏目录下）
+            tick_count += 1;
+            if tick_count.is_multiple_of(PROCESS_TREE_REFRESH_TICKS) {
+                let processes = snapshot_processes();
+                // 快照失败时返回空列表，此时不能据此清空候选
+                if !processes.is_empty() {
+                    let mut pids = shared_candidate_pids.write();
+                    refresh_tracked_pids(&processes, &mut pids, std::process::id());
+                }
+            }
+
             // 如果 best_pid 变化了，记录日志
             if current_best_pid != last_best_pid {
                 debug!("检测到进程切换: {} -> {}", last_best_pid, current_best_pid);
                 last_best_pid = current_best_pid;
+                watch_process_exit(&mut exit_watcher, current_best_pid);
             }
 
             // 前台判定：仅检查共享状态（性能优化的关键）
@@ -424,6 +717,16 @@ async fn run_game_monitor<R: Runtime>(
     // 清理会话注册
     unregister_session(game_id);
 
+    // 用户主动停止时进程被强制终止，退出码不代表崩溃
+    let exit_code = if stopped_by_user {
+        None
+    } else {
+        exit_watcher
+            .as_ref()
+            .filter(|watcher| watcher.pid == last_best_pid)
+            .and_then(ProcessExitWatcher::exit_code)
+    };
+
     finalize_monitored_session(
         &app_handle,
         &db,
@@ -434,6 +737,7 @@ async fn run_game_monitor<R: Runtime>(
             start_time,
             end_time: get_timestamp(),
             accumulated_seconds,
+            exit_code,
         },
     )
     .await;
@@ -607,26 +911,43 @@ fn is_sub_path_ignore_case(path: &str, base_dir: &str) -> bool {
 }
 // ============================================================================
 
+/// 进程快照中的单个条目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcessEntry {
+    pid: u32,
+    parent_pid: u32,
+}
+
 /// 获取当前所有候选的游戏进程 PID 列表
 ///
-/// 从游戏目录下扫描所有进程，自动过滤掉管理器自身。
+/// 候选进程由两部分组成：
+/// - 可执行文件位于游戏目录下的进程
+/// - `tracked_pids` 及其全部子孙进程（Launcher 拉起的游戏本体可能不在游戏目录下）
+///
+/// 自动过滤掉管理器自身。
 ///
 /// # Arguments
 /// * `detection_dir` - 游戏检测目录
+/// * `tracked_pids` - 已跟踪的进程 PID（初始 PID 与已知候选进程），作为进程树的根
 ///
 /// # Returns
 /// 返回所有候选 PID 的列表，如果没有找到则返回空列表
-fn get_all_candidate_pids(detection_dir: &str) -> Vec<u32> {
+fn get_all_candidate_pids(detection_dir: &str, tracked_pids: &HashSet<u32>) -> Vec<u32> {
     let manager_pid = std::process::id();
+    let processes = snapshot_processes();
 
-    let candidate_pids: Vec<u32> = get_processes_in_directory(detection_dir)
+    let mut candidate_set: HashSet<u32> = get_processes_in_directory(detection_dir, &processes)
         .into_iter()
-        .filter(|&pid| pid != manager_pid)
         .collect();
+    candidate_set.extend(collect_process_tree(&processes, tracked_pids));
+    candidate_set.remove(&manager_pid);
+
+    let mut candidate_pids: Vec<u32> = candidate_set.into_iter().collect();
+    candidate_pids.sort_unstable();
 
     if candidate_pids.is_empty() {
         debug!(
-            "未通过路径 '{}' 找到匹配的进程（已排除管理器）",
+            "未通过路径 '{}' 或进程树找到匹配的进程（已排除管理器）",
             detection_dir
         );
     } else {
@@ -640,29 +961,58 @@ fn get_all_candidate_pids(detection_dir: &str) -> Vec<u32> {
     candidate_pids
 }
 
-/// 用 Windows ToolHelp API 枚举所有运行进程，返回可执行路径在目标目录下的进程 PID 列表
-///
-/// 复用文件内已有的 `get_process_executable_path()` 获取路径，替代 sysinfo。
+/// 在进程快照中收集根进程及其全部子孙进程
 ///
-/// # Arguments
-/// * `detection_dir` - 游戏检测目录
-///
-/// # Returns
-/// 返回该目录及子目录下所有正在运行进程的 PID 列表
-fn get_processes_in_directory(detection_dir: &str) -> Vec<u32> {
-    let target_dir = Path::new(detection_dir);
-    if !target_dir.is_dir() {
-        warn!("检测目录不存在或不是目录: {}", detection_dir);
-        return Vec::new();
+/// 父进程退出后，子进程快照中的 `th32ParentProcessID` 仍指向原父 PID，
+/// 因此即使 Launcher 已退出，也能通过它找到真正的游戏进程。
+/// 只返回快照中仍然存在的进程。
+fn collect_process_tree(processes: &[ProcessEntry], root_pids: &HashSet<u32>) -> HashSet<u32> {
+    let mut children: std::collections::HashMap<u32, Vec<u32>> = std::collections::HashMap::new();
+    for entry in processes {
+        // 系统空闲进程的父 PID 为自身，跳过以避免自环
+        if entry.pid != entry.parent_pid {
+            children
+                .entry(entry.parent_pid)
+                .or_default()
+                .push(entry.pid);
+        }
     }
 
-    // 双重路径预处理：保留原始字符串 + 尝试获取物理真实规范化路径
-    let target_str = target_dir.to_string_lossy().to_string();
-    let canonical_target_str = std::fs::canonicalize(target_dir)
-        .ok()
-        .map(|p| p.to_string_lossy().to_string());
+    let mut visited: HashSet<u32> = HashSet::new();
+    let mut pending: Vec<u32> = root_pids.iter().copied().collect();
+    while let Some(pid) = pending.pop() {
+        if !visited.insert(pid) {
+            continue;
+        }
+        if let Some(child_pids) = children.get(&pid) {
+            pending.extend(child_pids.iter().copied());
+        }
+    }
 
-    let mut pids = Vec::new();
+    let alive: HashSet<u32> = processes.iter().map(|entry| entry.pid).collect();
+    visited.retain(|pid| *pid != 0 && alive.contains(pid));
+    visited
+}
+
+/// 按最新进程快照刷新已跟踪的 PID
+///
+/// 已跟踪进程的子孙进程（包括已退出进程遗留的子进程）加入跟踪，快照中已不存在的进程移出跟踪，
+/// 避免进程退出后 Windows 复用其 PID 时把无关进程当作游戏跟踪或结束。
+fn refresh_tracked_pids(processes: &[ProcessEntry], tracked: &mut HashSet<u32>, manager_pid: u32) {
+    let mut refreshed = collect_process_tree(processes, tracked);
+    refreshed.remove(&manager_pid);
+    for pid in refreshed.difference(tracked) {
+        debug!("进程树中发现新的游戏子进程: PID {}", pid);
+    }
+    for pid in tracked.difference(&refreshed) {
+        debug!("游戏进程已退出，停止跟踪: PID {}", pid);
+    }
+    *tracked = refreshed;
+}
+
+/// 用 Windows ToolHelp API 创建当前所有进程的快照（PID 与父 PID）
+fn snapshot_processes() -> Vec<ProcessEntry> {
+    let mut processes = Vec::new();
 
     unsafe {
         // 创建进程快照
@@ -685,25 +1035,10 @@ fn get_processes_in_directory(detection_dir: &str) -> Vec<u32> {
         // 遍历所有进程
         if Process32FirstW(snapshot, &mut entry).is_ok() {
             loop {
-                let pid = entry.th32ProcessID;
-                // 通过复用已有函数获取进程的完整可执行路径
-
-                if pid > 0
-                    && let Some(exe_path) = get_process_executable_path(pid)
-                    && let Some(process_dir) = exe_path.parent()
-                {
-                    let process_str = process_dir.to_string_lossy();
-
-                    // 双重无开销短路匹配
-                    let mut matches = is_sub_path_ignore_case(&process_str, &target_str);
-                    if !matches && let Some(canon_str) = &canonical_target_str {
-                        matches = is_sub_path_ignore_case(&process_str, canon_str);
-                    }
-
-                    if matches {
-                        pids.push(pid);
-                    }
-                }
+                processes.push(ProcessEntry {
+                    pid: entry.th32ProcessID,
+                    parent_pid: entry.th32ParentProcessID,
+                });
 
                 if Process32NextW(snapshot, &mut entry).is_err() {
                     break;
@@ -714,6 +1049,55 @@ fn get_processes_in_directory(detection_dir: &str) -> Vec<u32> {
         let _ = CloseHandle(snapshot);
     }
 
+    processes
+}
+
+/// 在进程快照中查找可执行路径位于目标目录下的进程 PID 列表
+///
+/// 复用文件内已有的 `get_process_executable_path()` 获取路径，替代 sysinfo。
+///
+/// # Arguments
+/// * `detection_dir` - 游戏检测目录
+/// * `processes` - 进程快照
+///
+/// # Returns
+/// 返回该目录及子目录下所有正在运行进程的 PID 列表
+fn get_processes_in_directory(detection_dir: &str, processes: &[ProcessEntry]) -> Vec<u32> {
+    let target_dir = Path::new(detection_dir);
+    if !target_dir.is_dir() {
+        warn!("检测目录不存在或不是目录: {}", detection_dir);
+        return Vec::new();
+    }
+
+    // 双重路径预处理：保留原始字符串 + 尝试获取物理真实规范化路径
+    let target_str = target_dir.to_string_lossy().to_string();
+    let canonical_target_str = std::fs::canonicalize(target_dir)
+        .ok()
+        .map(|p| p.to_string_lossy().to_string());
+
+    let mut pids = Vec::new();
+
+    for entry in processes {
+        let pid = entry.pid;
+        // 通过复用已有函数获取进程的完整可执行路径
+        if pid > 0
+            && let Some(exe_path) = get_process_executable_path(pid)
+            && let Some(process_dir) = exe_path.parent()
+        {
+            let process_str = process_dir.to_string_lossy();
+
+            // 双重无开销短路匹配
+            let mut matches = is_sub_path_ignore_case(&process_str, &target_str);
+            if !matches && let Some(canon_str) = &canonical_target_str {
+                matches = is_sub_path_ignore_case(&process_str, canon_str);
+            }
+
+            if matches {
+                pids.push(pid);
+            }
+        }
+    }
+
     debug!("找到进程目录下的进程 PID 列表: {:?}", pids);
     pids
 }
@@ -881,6 +1265,85 @@ mod tests {
         assert!(state.read().is_foreground);
     }
 
+    #[test]
+    fn process_tree_follows_children_of_exited_launcher() {
+        // 10 为已退出的 Launcher，20 为其拉起的游戏本体，30 为游戏再拉起的子进程
+        let processes = [
+            ProcessEntry {
+                pid: 20,
+                parent_pid: 10,
+            },
+            ProcessEntry {
+                pid: 30,
+                parent_pid: 20,
+            },
+            ProcessEntry {
+                pid: 40,
+                parent_pid: 1,
+            },
+        ];
+
+        let tree = collect_process_tree(&processes, &HashSet::from([10]));
+
+        assert_eq!(tree, HashSet::from([20, 30]));
+    }
+
+    #[test]
+    fn process_tree_terminates_on_reused_parent_pids() {
+        // PID 复用可能让父子关系成环，遍历必须能正常结束
+        let processes = [
+            ProcessEntry {
+                pid: 5,
+                parent_pid: 6,
+            },
+            ProcessEntry {
+                pid: 6,
+                parent_pid: 5,
+            },
+            ProcessEntry {
+                pid: 7,
+                parent_pid: 7,
+            },
+        ];
+
+        assert_eq!(
+            collect_process_tree(&processes, &HashSet::from([5])),
+            HashSet::from([5, 6])
+        );
+    }
+
+    #[test]
+    fn refresh_prunes_exited_pids_and_adds_children() {
+        let mut tracked = HashSet::from([10, 99]);
+        let processes = [
+            ProcessEntry {
+                pid: 20,
+                parent_pid: 10,
+            },
+            ProcessEntry {
+                pid: 30,
+                parent_pid: 20,
+            },
+            ProcessEntry {
+                pid: 40,
+                parent_pid: 30,
+            },
+        ];
+
+        refresh_tracked_pids(&processes, &mut tracked, 40);
+
+        // 10 与 99 已退出，已退出的 10 遗留的子进程仍被跟踪，40 是管理器自身
+        assert_eq!(tracked, HashSet::from([20, 30]));
+
+        // 退出的 PID 被复用后不再作为进程树的根
+        let reused = [ProcessEntry {
+            pid: 10,
+            parent_pid: 1,
+        }];
+        refresh_tracked_pids(&reused, &mut tracked, 40);
+        assert!(tracked.is_empty());
+    }
+
     #[test]
     fn same_pid_is_rechecked_after_becoming_candidate() {
         let state = RwLock::new(MonitorState::new(1));
@@ -0,0 +1,148 @@
+//! 续作 / FD / 同系列等关联作品
+//!
+//! 关联关系从 VNDB 条目的 relations 同步到 `game_relations` 表，关联对象以 VNDB ID 保存；
+//! 查询时只返回库中已拥有（绑定了对应 VNDB 条目）的作品。
+
+use crate::database::list_filter;
+use crate::database::repository::game_relations_repository::{
+    GameRelationsRepository, RelatedGame,
+};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::entity::game_relations;
+use crate::metadata::vndb::{VndbRelation, fetch_relations};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tauri::{State, command};
+
+/// 关联关系同步结果
+#[derive(Debug, Default, Serialize)]
+pub struct RelationSyncReport {
+    /// 绑定了 VNDB 条目的游戏数量
+    pub total: usize,
+    pub synced: usize,
+    /// 写入的关联关系总数
+    pub relations: usize,
+    pub errors: Vec<String>,
+}
+
+fn to_relation_models(game_id: i32, relations: &[VndbRelation]) -> Vec<game_relations::Model> {
+    relations
+        .iter()
+        .map(|relation| game_relations::Model {
+            game_id,
+            related_vndb_id: relation.id.trim().to_string(),
+            relation: relation.relation.clone(),
+            official: relation.relation_official,
+        })
+        .collect()
+}
+
+/// 从 VNDB 同步关联作品
+///
+/// # Arguments
+/// * `game_id` - 游戏ID，为空时同步所有绑定了 VNDB 条目的游戏
+#[command]
+pub async fn sync_game_relations(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+) -> Result<RelationSyncReport, String> {
+    let bindings: Vec<(i32, String)> = GamesRepository::get_source_bindings(&db, "vndb")
+        .await
+        .map_err(|e| format!("读取 VNDB 绑定失败: {}", e))?
+        .into_iter()
+        .filter(|(id, _)| game_id.is_none_or(|game_id| game_id == *id))
+        .collect();
+    if game_id.is_some() && bindings.is_empty() {
+        return Err("该游戏未绑定 vndb 条目".to_string());
+    }
+
+    let vn_ids: Vec<String> = bindings
+        .iter()
+        .map(|(_, vn_id)| vn_id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let relations = fetch_relations(&vn_ids).await?;
+
+    let mut report = RelationSyncReport {
+        total: bindings.len(),
+        ..Default::default()
+    };
+    for (game_id, vn_id) in bindings {
+        let Some(vn_relations) = relations.get(&vn_id) else {
+            report.errors.push(format!(
+                "VNDB 条目不存在 game_id={} vndb_id={}",
+                game_id, vn_id
+            ));
+            continue;
+        };
+        let models = to_relation_models(game_id, vn_relations);
+        match GameRelationsRepository::replace_for_game(&db, game_id, models).await {
+            Ok(count) => {
+                report.synced += 1;
+                report.relations += count;
+            }
+            Err(e) => report
+                .errors
+                .push(format!("保存关联关系失败 game_id={}: {}", game_id, e)),
+        }
+    }
+
+    log::info!(
+        "关联作品同步完成 total={} synced={} relations={} errors={}",
+        report.total,
+        report.synced,
+        report.relations,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+/// 获取库中已拥有的关联作品
+///
+/// 隐藏与安全模式的筛选规则与游戏列表相同。
+#[command]
+pub async fn get_related_games(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    show_hidden: Option<bool>,
+) -> Result<Vec<RelatedGame>, String> {
+    let filter = list_filter(&db, show_hidden).await?;
+    GameRelationsRepository::find_related_games(&db, game_id, filter)
+        .await
+        .map_err(|e| format!("获取关联作品失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_vndb_relations() {
+        let relations: Vec<VndbRelation> = serde_json::from_value(json!([
+            { "id": "v2002", "relation": "seq", "relation_official": true },
+            { "id": " v3003 ", "relation": "fan" }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            to_relation_models(7, &relations),
+            [
+                game_relations::Model {
+                    game_id: 7,
+                    related_vndb_id: "v2002".to_string(),
+                    relation: "seq".to_string(),
+                    official: true,
+                },
+                game_relations::Model {
+                    game_id: 7,
+                    related_vndb_id: "v3003".to_string(),
+                    relation: "fan".to_string(),
+                    official: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! 游戏目录迁移
+//!
+//! 将游戏目录复制或移动到新位置后更新 localpath。同盘移动直接重命名；
+//! 跨盘时分块复制并通过 `game-folder-transfer-progress` 与 `task-progress` 事件上报进度，
+//! 复制成功并确认启动程序存在后才删除源目录，失败时清理已复制的目标目录。
+//! 删除游戏目录默认移入系统回收站。
+
+use crate::database::dto::{FullGameData, UpdateGameData};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::monitor::is_game_monitored;
+use crate::game::scan::{is_same_or_descendant, replace_path_prefix};
+use crate::game::size::dir_size;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use crate::utils::trash::remove_path;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use serde_json::json;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, State, command};
+use walkdir::WalkDir;
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+/// 进度事件的最小发送间隔
+const PROGRESS_EMIT_INTERVAL_MS: u64 = 200;
+
+/// 目录迁移方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FolderTransferMode {
+    /// 复制到新位置，保留原目录
+    Copy,
+    /// 移动到新位置，完成后删除原目录
+    Move,
+}
+
+/// 复制进度，按时间间隔节流回调
+struct CopyProgress<'a> {
+    total: u64,
+    copied: u64,
+    last_report: Instant,
+    on_progress: &'a dyn Fn(u64, u64),
+}
+
+impl CopyProgress<'_> {
+    fn advance(&mut self, bytes: u64) {
+        self.copied += bytes;
+        if self.last_report.elapsed() >= Duration::from_millis(PROGRESS_EMIT_INTERVAL_MS) {
+            self.last_report = Instant::now();
+            (self.on_progress)(self.copied, self.total);
+        }
+    }
+}
+
+/// 分块复制单个文件并保留权限（Linux 下的可执行位）
+fn copy_file_chunked(from: &Path, to: &Path, progress: &mut CopyProgress) -> Result<(), String> {
+    let mut reader =
+        File::open(from).map_err(|e| format!("打开文件失败 {}: {}", from.display(), e))?;
+    let mut writer =
+        File::create(to).map_err(|e| format!("创建文件失败 {}: {}", to.display(), e))?;
+    let mut buffer = vec![0_u8; COPY_BUFFER_SIZE];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {}", from.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|e| format!("写入文件失败 {}: {}", to.display(), e))?;
+        progress.advance(read as u64);
+    }
+
+    if let Ok(metadata) = reader.metadata() {
+        let _ = fs::set_permissions(to, metadata.permissions());
+    }
+    Ok(())
+}
+
+/// 在目标位置按原样重建符号链接（不跟随链接复制其内容）
+fn copy_symlink(from: &Path, to: &Path) -> Result<(), String> {
+    let link =
+        fs::read_link(from).map_err(|e| format!("读取符号链接失败 {}: {}", from.display(), e))?;
+
+    #[cfg(unix)]
+    let created = std::os::unix::fs::symlink(&link, to);
+    #[cfg(windows)]
+    let created = if fs::metadata(from).is_ok_and(|metadata| metadata.is_dir()) {
+        std::os::windows::fs::symlink_dir(&link, to)
+    } else {
+        std::os::windows::fs::symlink_file(&link, to)
+    };
+
+    created.map_err(|e| format!("创建符号链接失败 {}: {}", to.display(), e))
+}
+
+/// 递归复制目录，遇到错误立即停止
+///
+/// 符号链接按原样重建；无法复制的特殊文件视为错误，避免移动后丢失内容。
+fn copy_tree(from: &Path, to: &Path, progress: &mut CopyProgress) -> Result<(), String> {
+    for entry in WalkDir::new(from).follow_links(false) {
+        let entry = entry.map_err(|e| format!("读取源目录失败: {}", e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(from)
+            .map_err(|e| format!("计算相对路径失败: {}", e))?;
+        let target = to.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("创建目录失败 {}: {}", target.display(), e))?;
+        } else if file_type.is_file() {
+            copy_file_chunked(entry.path(), &target, progress)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else {
+            return Err(format!("无法复制特殊文件: {}", entry.path().display()));
+        }
+    }
+    Ok(())
+}
+
+/// 执行目录迁移，返回复制的字节数（同盘重命名时为 None）
+fn transfer_folder(
+    source: &Path,
+    dest: &Path,
+    executable: Option<&str>,
+    mode: FolderTransferMode,
+    on_progress: &dyn Fn(u64, u64),
+) -> Result<Option<u64>, String> {
+    let executable_missing = |dir: &Path| executable.is_some_and(|exe| !dir.join(exe).is_file());
+
+    if mode == FolderTransferMode::Move && fs::rename(source, dest).is_ok() {
+        if executable_missing(dest) {
+            let _ = fs::rename(dest, source);
+            return Err("迁移后找不到启动程序，已撤销移动".to_string());
+        }
+        return Ok(None);
+    }
+
+    let mut progress = CopyProgress {
+        total: dir_size(source),
+        copied: 0,
+        last_report: Instant::now(),
+        on_progress,
+    };
+    let copied = copy_tree(source, dest, &mut progress).and_then(|_| {
+        if executable_missing(dest) {
+            Err("迁移后找不到启动程序".to_string())
+        } else {
+            Ok(progress.copied)
+        }
+    });
+    let copied = match copied {
+        Ok(copied) => copied,
+        Err(e) => {
+            // 目标目录由本次迁移创建，失败时整体清理，源目录保持不变
+            if let Err(cleanup) = fs::remove_dir_all(dest) {
+                log::warn!("清理目标目录失败 {}: {}", dest.display(), cleanup);
+            }
+            return Err(e);
+        }
+    };
+    on_progress(copied, progress.total);
+
+    if mode == FolderTransferMode::Move
+        && let Err(e) = fs::remove_dir_all(source)
+    {
+        log::warn!(
+            "游戏目录已复制到新位置，但删除原目录失败 {}: {}",
+            source.display(),
+            e
+        );
+    }
+    Ok(Some(copied))
+}
+
+/// 将游戏目录复制或移动到 `target_dir` 下，并更新游戏路径
+///
+/// 目标为 `target_dir/<原目录名>`，已存在时拒绝迁移。
+/// 存档目录位于游戏目录内时一并更新。
+#[command]
+pub async fn move_game_folder<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+    game_id: i32,
+    target_dir: String,
+    mode: FolderTransferMode,
+) -> Result<FullGameData, String> {
+    if is_game_monitored(game_id as u32) {
+        return Err("游戏正在运行，无法迁移目录".to_string());
+    }
+
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let localpath = game
+        .localpath
+        .ok_or_else(|| "游戏未设置本地目录".to_string())?;
+    let source = PathBuf::from(&localpath);
+    if !source.is_dir() {
+        return Err(format!("游戏目录不存在: {}", localpath));
+    }
+
+    let target_parent = PathBuf::from(target_dir.trim());
+    let folder_name = source
+        .file_name()
+        .ok_or_else(|| format!("无法获取游戏目录名: {}", localpath))?;
+    let dest = target_parent.join(folder_name);
+    if is_same_or_descendant(&dest, &source) {
+        return Err("目标位置不能位于游戏目录内".to_string());
+    }
+    if dest.exists() {
+        return Err(format!("目标位置已存在同名目录: {}", dest.display()));
+    }
+    fs::create_dir_all(&target_parent).map_err(|e| format!("创建目标目录失败: {}", e))?;
+
+    log::info!(
+        "开始迁移游戏目录 game_id={} mode={:?} {} -> {}",
+        game_id,
+        mode,
+        source.display(),
+        dest.display()
+    );
+    let executable = game.executable.clone();
+    let transfer_dest = dest.clone();
+    let task = Arc::new(tasks.start(&app_handle, TaskKind::FolderTransfer)?);
+    let progress_task = task.clone();
+    let copied = tokio::task::spawn_blocking(move || {
+        let on_progress = |copied: u64, total: u64| {
+            progress_task.progress(copied, total);
+            if let Err(e) = app_handle.emit(
+                "game-folder-transfer-progress",
+                json!({ "gameId": game_id, "copiedBytes": copied, "totalBytes": total }),
+            ) {
+                log::warn!("无法发送 game-folder-transfer-progress 事件: {}", e);
+            }
+        };
+        transfer_folder(
+            &source,
+            &transfer_dest,
+            executable.as_deref(),
+            mode,
+            &on_progress,
+        )
+    })
+    .await
+    .map_err(|e| format!("迁移任务异常: {}", e))
+    .and_then(|copied| copied);
+    task.finish(&copied);
+    let copied = copied?;
+
+    let new_localpath = dest.to_string_lossy().into_owned();
+    let new_savepath = game.savepath.as_ref().and_then(|savepath| {
+        savepath.replaced(|path| replace_path_prefix(path, Path::new(&localpath), &dest))
+    });
+    let updates = UpdateGameData {
+        localpath: Some(Some(new_localpath)),
+        savepath: new_savepath.map(Some),
+        ..Default::default()
+    };
+    let mut updated = GamesRepository::update(&db, game_id, updates)
+        .await
+        .map_err(|e| format!("游戏目录已迁移，但更新游戏路径失败: {}", e))?;
+
+    if let Some(copied) = copied {
+        let size = Some(copied as i64);
+        if let Err(e) = GamesRepository::set_folder_size(&db, game_id, size).await {
+            log::warn!("保存游戏占用空间失败 game_id={}: {}", game_id, e);
+        } else {
+            updated.folder_size = size;
+        }
+    }
+
+    log::info!("游戏目录迁移完成 game_id={} -> {}", game_id, dest.display());
+    Ok(updated)
+}
+
+/// 删除游戏目录并清除游戏的本地路径
+///
+/// 默认移入系统回收站，`permanent` 为 true 时直接删除。
+#[command]
+pub async fn delete_game_folder(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    permanent: Option<bool>,
+) -> Result<FullGameData, String> {
+    if is_game_monitored(game_id as u32) {
+        return Err("游戏正在运行，无法删除目录".to_string());
+    }
+
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let localpath = game
+        .localpath
+        .ok_or_else(|| "游戏未设置本地目录".to_string())?;
+    let folder = PathBuf::from(&localpath);
+    if !folder.is_dir() {
+        return Err(format!("游戏目录不存在: {}", localpath));
+    }
+
+    let permanent = permanent.unwrap_or(false);
+    tokio::task::spawn_blocking(move || remove_path(&folder, permanent))
+        .await
+        .map_err(|e| format!("删除游戏目录任务异常: {}", e))??;
+    log::info!(
+        "已删除游戏目录 game_id={} permanent={} {}",
+        game_id,
+        permanent,
+        localpath
+    );
+
+    let updates = UpdateGameData {
+        localpath: Some(None),
+        ..Default::default()
+    };
+    let mut updated = GamesRepository::update(&db, game_id, updates)
+        .await
+        .map_err(|e| format!("游戏目录已删除，但清除游戏路径失败: {}", e))?;
+    if let Err(e) = GamesRepository::set_folder_size(&db, game_id, None).await {
+        log::warn!("清除游戏占用空间失败 game_id={}: {}", game_id, e);
+    } else {
+        updated.folder_size = None;
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = crate::utils::test_util::temp_dir("relocate", name);
+        fs::create_dir_all(&dir).expect("应能创建测试目录");
+        dir
+    }
+
+    #[test]
+    fn copy_keeps_source_and_rejects_missing_executable() {
+        let root = temp_dir("copy");
+        let source = root.join("Game");
+        fs::create_dir_all(source.join("data")).unwrap();
+        fs::write(source.join("game.exe"), [0_u8; 8]).unwrap();
+        fs::write(source.join("data").join("data.xp3"), [0_u8; 16]).unwrap();
+
+        let dest = root.join("copied");
+        let copied = transfer_folder(
+            &source,
+            &dest,
+            Some("game.exe"),
+            FolderTransferMode::Copy,
+            &|_, _| {},
+        )
+        .unwrap();
+        assert_eq!(copied, Some(24));
+        assert!(dest.join("data").join("data.xp3").is_file());
+        assert!(source.join("game.exe").is_file());
+
+        let failed = root.join("failed");
+        let result = transfer_folder(
+            &source,
+            &failed,
+            Some("missing.exe"),
+            FolderTransferMode::Copy,
+            &|_, _| {},
+        );
+        assert!(result.is_err());
+        assert!(!failed.exists());
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn move_recreates_symlinks() {
+        let root = temp_dir("symlink");
+        let source = root.join("Game");
+        fs::create_dir_all(source.join("data")).unwrap();
+        fs::write(source.join("data").join("data.xp3"), [0_u8; 16]).unwrap();
+        std::os::unix::fs::symlink("data", source.join("link")).unwrap();
+
+        let dest = root.join("moved");
+        let mut progress = CopyProgress {
+            total: 16,
+            copied: 0,
+            last_report: Instant::now(),
+            on_progress: &|_, _| {},
+        };
+        copy_tree(&source, &dest, &mut progress).unwrap();
+        assert_eq!(
+            fs::read_link(dest.join("link")).unwrap(),
+            PathBuf::from("data")
+        );
+        assert!(dest.join("link").join("data.xp3").is_file());
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+}
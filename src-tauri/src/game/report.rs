@@ -0,0 +1,282 @@
+//! 年度报告（Year in Review）
+//!
+//! 汇总指定年份的游玩时长、月度曲线、最长会话、通关与新增作品等数据，
+//! 由前端负责渲染为可分享的卡片。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::game_stats_repository::{
+    GamePlaytime, GameStatsRepository, PlaytimeDay,
+};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::game_sessions;
+use chrono::{Local, NaiveDate, TimeZone};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{State, command};
+
+/// 报告中列出的游玩时长排行条目数
+const TOP_GAMES_LIMIT: usize = 10;
+/// 游玩状态：玩过
+const PLAY_STATUS_CLEARED: i32 = 2;
+
+/// 报告中的游戏条目
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearReportGame {
+    pub game_id: i32,
+    pub name: Option<String>,
+    /// 当年游玩时长（分钟）
+    pub playtime: i64,
+}
+
+/// 当年最长的一次会话
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearReportSession {
+    pub game_id: i32,
+    pub name: Option<String>,
+    pub start_time: i32,
+    pub end_time: i32,
+    /// 时长（分钟）
+    pub duration: i32,
+}
+
+/// 年度报告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearReport {
+    pub year: i32,
+    /// 当年总游玩时长（分钟）
+    pub total_playtime: i64,
+    pub session_count: usize,
+    /// 有游玩记录的天数
+    pub active_days: usize,
+    /// 有游玩记录的游戏数
+    pub played_games: usize,
+    /// 当年加入游戏库的游戏数
+    pub new_games: usize,
+    /// 1-12 月每月游玩时长（分钟），固定 12 项
+    pub monthly_playtime: Vec<i64>,
+    /// 游玩时长最多的一天
+    pub busiest_day: Option<PlaytimeDay>,
+    pub longest_session: Option<YearReportSession>,
+    /// 当年有游玩记录且状态为玩过的游戏，按当年游玩时长降序
+    pub cleared_games: Vec<YearReportGame>,
+    /// 按当年游玩时长降序
+    pub top_games: Vec<YearReportGame>,
+}
+
+fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate), String> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+        .ok_or_else(|| format!("无效年份: {}", year))
+}
+
+/// 当年零点到次年零点的本地时间戳范围
+fn year_timestamp_range(year: i32) -> Option<(i64, i64)> {
+    let start = |year| {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .and_then(|datetime| Local.from_local_datetime(&datetime).earliest())
+            .map(|datetime| datetime.timestamp())
+    };
+    Some((start(year)?, start(year + 1)?))
+}
+
+fn build_year_report(
+    year: i32,
+    days: &[PlaytimeDay],
+    game_playtime: &[GamePlaytime],
+    sessions: &[game_sessions::Model],
+    games: &[FullGameData],
+    new_games: usize,
+) -> YearReport {
+    let games: HashMap<i32, &FullGameData> = games.iter().map(|game| (game.id, game)).collect();
+    let name = |game_id: i32| games.get(&game_id).and_then(|game| game.display_name());
+    let report_game = |item: &GamePlaytime| YearReportGame {
+        game_id: item.game_id,
+        name: name(item.game_id),
+        playtime: item.playtime,
+    };
+
+    let mut monthly_playtime = vec![0; 12];
+    for day in days {
+        if let Some(month) = day
+            .date
+            .get(5..7)
+            .and_then(|month| month.parse::<usize>().ok())
+            .filter(|month| (1..=12).contains(month))
+        {
+            monthly_playtime[month - 1] += day.playtime;
+        }
+    }
+
+    YearReport {
+        year,
+        total_playtime: days.iter().map(|day| day.playtime).sum(),
+        session_count: sessions.len(),
+        active_days: days.iter().filter(|day| day.playtime > 0).count(),
+        played_games: game_playtime.len(),
+        new_games,
+        monthly_playtime,
+        busiest_day: days
+            .iter()
+            .filter(|day| day.playtime > 0)
+            .max_by(|a, b| a.playtime.cmp(&b.playtime).then(b.date.cmp(&a.date)))
+            .cloned(),
+        longest_session: sessions
+            .iter()
+            .max_by(|a, b| {
+                a.duration
+                    .cmp(&b.duration)
+                    .then(b.start_time.cmp(&a.start_time))
+            })
+            .map(|session| YearReportSession {
+                game_id: session.game_id,
+                name: name(session.game_id),
+                start_time: session.start_time,
+                end_time: session.end_time,
+                duration: session.duration,
+            }),
+        cleared_games: game_playtime
+            .iter()
+            .filter(|item| {
+                games
+                    .get(&item.game_id)
+                    .is_some_and(|game| game.clear == Some(PLAY_STATUS_CLEARED))
+            })
+            .map(report_game)
+            .collect(),
+        top_games: game_playtime
+            .iter()
+            .take(TOP_GAMES_LIMIT)
+            .map(report_game)
+            .collect(),
+    }
+}
+
+/// 生成指定年份的年度报告
+#[command]
+pub async fn generate_year_report(
+    db: State<'_, DatabaseConnection>,
+    year: i32,
+) -> Result<YearReport, String> {
+    let (first_day, last_day) = year_bounds(year)?;
+    let (start, end) = (
+        first_day.format("%Y-%m-%d").to_string(),
+        last_day.format("%Y-%m-%d").to_string(),
+    );
+
+    let days = GameStatsRepository::get_playtime_heatmap(&db, year, None)
+        .await
+        .map_err(|e| format!("读取每日游玩时长失败: {}", e))?;
+    let game_playtime = GameStatsRepository::get_playtime_by_game(&db, &start, &end)
+        .await
+        .map_err(|e| format!("读取游戏游玩时长失败: {}", e))?;
+    let sessions = GameStatsRepository::get_sessions_by_date_range(&db, &start, &end)
+        .await
+        .map_err(|e| format!("读取游戏会话失败: {}", e))?;
+    let games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+
+    let new_games = year_timestamp_range(year).map_or(0, |(start, end)| {
+        games
+            .iter()
+            .filter_map(|game| game.created_at)
+            .filter(|created_at| (start..end).contains(&i64::from(*created_at)))
+            .count()
+    });
+
+    Ok(build_year_report(
+        year,
+        &days,
+        &game_playtime,
+        &sessions,
+        &games,
+        new_games,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn day(date: &str, playtime: i64) -> PlaytimeDay {
+        PlaytimeDay {
+            date: date.to_string(),
+            playtime,
+        }
+    }
+
+    fn session(game_id: i32, start_time: i32, duration: i32) -> game_sessions::Model {
+        game_sessions::Model {
+            session_id: start_time,
+            game_id,
+            start_time,
+            end_time: start_time + duration * 60,
+            duration,
+            date: "2025-01-01".to_string(),
+            exit_code: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn builds_report_from_yearly_data() {
+        let games: Vec<FullGameData> = [(1, 2, "千恋＊万花"), (2, 3, "魔女的夜宴")]
+            .into_iter()
+            .map(|(id, clear, name)| {
+                serde_json::from_value(json!({
+                    "id": id,
+                    "id_type": "custom",
+                    "clear": clear,
+                    "custom_data": { "name": name },
+                    "sources": [],
+                }))
+                .expect("测试游戏应能解析")
+            })
+            .collect();
+        let days = [
+            day("2025-01-01", 0),
+            day("2025-01-02", 120),
+            day("2025-03-05", 200),
+        ];
+        let game_playtime = [
+            GamePlaytime {
+                game_id: 2,
+                playtime: 200,
+            },
+            GamePlaytime {
+                game_id: 1,
+                playtime: 120,
+            },
+        ];
+        let sessions = [
+            session(1, 1000, 120),
+            session(2, 2000, 150),
+            session(2, 3000, 50),
+        ];
+
+        let report = build_year_report(2025, &days, &game_playtime, &sessions, &games, 1);
+        assert_eq!(report.total_playtime, 320);
+        assert_eq!(report.active_days, 2);
+        assert_eq!(report.played_games, 2);
+        assert_eq!(report.session_count, 3);
+        assert_eq!(report.monthly_playtime[0], 120);
+        assert_eq!(report.monthly_playtime[2], 200);
+        assert_eq!(report.busiest_day, Some(day("2025-03-05", 200)));
+        let longest = report.longest_session.expect("应有最长会话");
+        assert_eq!((longest.game_id, longest.duration), (2, 150));
+        assert_eq!(report.cleared_games.len(), 1);
+        assert_eq!(report.cleared_games[0].name.as_deref(), Some("千恋＊万花"));
+        assert_eq!(report.top_games[0].game_id, 2);
+    }
+}
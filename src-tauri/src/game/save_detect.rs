@@ -0,0 +1,614 @@
+//! 存档路径探测
+//!
+//! 按常见规律查找游戏的存档目录候选：游戏目录下的 save/、savedata/ 等子目录，引擎固定的存档位置，
+//! `%APPDATA%`、`%LOCALAPPDATA%`、文档目录下与游戏同名的目录，以及注册表 `HKCU\Software` 下同名的键。
+//! 结果只是候选，由用户确认后再写入 `savepath`；目录候选以 `{game_dir}` 或环境变量模板表示，换电脑后仍然有效。
+
+use crate::backup::save_path::GAME_DIR_PLACEHOLDER;
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::engine::{GameEngine, detect_engine};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{State, command};
+
+/// 游戏目录下常见的存档子目录名（小写）
+const GAME_DIR_SAVE_NAMES: &[&str] = &["save", "saves", "savedata", "save_data", "sav", "userdata"];
+/// 过于通用、不能用来匹配目录名的关键词
+const GENERIC_WORDS: &[&str] = &[
+    "game", "games", "data", "save", "saves", "savedata", "user", "local", "temp", "cache",
+    "config", "setup", "launcher", "program",
+];
+/// 统计候选目录时最多遍历的条目数与层数
+const MAX_STAT_ENTRIES: usize = 5000;
+const MAX_STAT_DEPTH: usize = 4;
+
+/// 候选的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveCandidateSource {
+    /// 游戏目录下的存档子目录
+    GameDir,
+    /// 引擎固定的存档位置
+    Engine,
+    /// `%APPDATA%`、`%LOCALAPPDATA%` 等应用数据目录下的同名目录
+    AppData,
+    /// 文档目录下的同名目录
+    Documents,
+    /// 注册表 `HKCU\Software` 下的同名键
+    Registry,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavePathCandidate {
+    /// 建议写入 `savepath` 的模板；注册表候选为完整键路径
+    pub path: String,
+    /// 在当前电脑上展开后的路径
+    pub resolved: String,
+    pub source: SaveCandidateSource,
+    /// 目录中的文件数，最多统计 [`MAX_STAT_ENTRIES`] 个条目
+    pub file_count: usize,
+    /// 目录中最近修改的文件时间（Unix 秒）
+    pub modified_at: Option<i64>,
+}
+
+/// 待搜索的根目录，`template` 是该目录在 `savepath` 中的写法
+struct SearchRoot {
+    dir: PathBuf,
+    template: String,
+    source: SaveCandidateSource,
+}
+
+impl SearchRoot {
+    fn new(dir: PathBuf, template: impl Into<String>, source: SaveCandidateSource) -> Self {
+        Self {
+            dir,
+            template: template.into(),
+            source,
+        }
+    }
+}
+
+/// 名称归一化：只保留字母与数字并转为小写，忽略空格与标点
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 足够具体、可以用于匹配的关键词：英文至少 4 个字符，含中日文等非 ASCII 字符时至少 2 个
+fn is_specific(keyword: &str) -> bool {
+    let len = keyword.chars().count();
+    let min_len = if keyword.is_ascii() { 4 } else { 2 };
+    len >= min_len && !GENERIC_WORDS.contains(&keyword)
+}
+
+/// 游戏的匹配关键词：目录名、可执行文件名、显示名称、原名与中文名
+fn game_keywords(game: &FullGameData) -> Vec<String> {
+    let folder = game
+        .localpath
+        .as_deref()
+        .and_then(|path| Path::new(path.trim_end_matches(['/', '\\'])).file_name())
+        .map(|name| name.to_string_lossy().into_owned());
+    let executable = game
+        .executable
+        .as_deref()
+        .and_then(|path| Path::new(path).file_stem())
+        .map(|name| name.to_string_lossy().into_owned());
+    let names = [
+        folder,
+        executable,
+        game.display_name(),
+        game.source_string_field("name").map(ToOwned::to_owned),
+        game.source_string_field("name_cn").map(ToOwned::to_owned),
+    ];
+
+    let mut keywords = Vec::new();
+    for name in names.into_iter().flatten() {
+        let keyword = normalize(&name);
+        if is_specific(&keyword) && !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+/// 目录名是否与游戏关键词匹配：相等或互相包含，被包含的一方也必须足够具体
+fn matches_keywords(dir_name: &str, keywords: &[String]) -> bool {
+    let name = normalize(dir_name);
+    if name.is_empty() {
+        return false;
+    }
+    keywords.iter().any(|keyword| {
+        name == *keyword
+            || name.contains(keyword.as_str())
+            || (is_specific(&name) && keyword.contains(&name))
+    })
+}
+
+fn join_template(base: &str, name: &str) -> String {
+    format!("{}{}{}", base, MAIN_SEPARATOR, name)
+}
+
+fn sub_dirs(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().into_owned(),
+                entry.path(),
+            )
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// 统计目录中的文件数与最近修改时间
+fn dir_stats(dir: &Path) -> (usize, Option<i64>) {
+    let mut file_count = 0;
+    let mut modified_at = None;
+    let mut visited = 0;
+    let mut stack = vec![(dir.to_path_buf(), 0)];
+    while let Some((current, depth)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            visited += 1;
+            if visited > MAX_STAT_ENTRIES {
+                return (file_count, modified_at);
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if depth + 1 < MAX_STAT_DEPTH {
+                    stack.push((entry.path(), depth + 1));
+                }
+                continue;
+            }
+            file_count += 1;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs() as i64);
+            modified_at = modified_at.max(modified);
+        }
+    }
+    (file_count, modified_at)
+}
+
+/// 按解析后的路径去重收集候选
+#[derive(Default)]
+struct Candidates {
+    seen: HashSet<String>,
+    list: Vec<SavePathCandidate>,
+}
+
+impl Candidates {
+    fn push_dir(&mut self, dir: &Path, template: String, source: SaveCandidateSource) {
+        let resolved = dir.to_string_lossy().into_owned();
+        if !self.seen.insert(resolved.to_lowercase()) {
+            return;
+        }
+        let (file_count, modified_at) = dir_stats(dir);
+        self.list.push(SavePathCandidate {
+            path: template,
+            resolved,
+            source,
+            file_count,
+            modified_at,
+        });
+    }
+
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn push_registry_key(&mut self, key: String) {
+        if self.seen.insert(key.to_lowercase()) {
+            self.list.push(SavePathCandidate {
+                path: key.clone(),
+                resolved: key,
+                source: SaveCandidateSource::Registry,
+                file_count: 0,
+                modified_at: None,
+            });
+        }
+    }
+
+    /// 近期写入过的目录更可能是存档目录，排在前面；没有文件的目录排在最后
+    fn into_sorted(mut self) -> Vec<SavePathCandidate> {
+        self.list.sort_by_key(|candidate| {
+            (
+                candidate.file_count == 0,
+                std::cmp::Reverse(candidate.modified_at),
+            )
+        });
+        self.list
+    }
+}
+
+/// 游戏目录下的存档子目录
+fn scan_game_dir(game_dir: &Path, candidates: &mut Candidates) {
+    for (name, path) in sub_dirs(game_dir) {
+        if GAME_DIR_SAVE_NAMES.contains(&name.to_lowercase().as_str()) {
+            candidates.push_dir(
+                &path,
+                join_template(GAME_DIR_PLACEHOLDER, &name),
+                SaveCandidateSource::GameDir,
+            );
+        }
+    }
+}
+
+/// 在根目录下两层内查找与游戏同名的目录，第二层用于“开发商/游戏名”结构
+fn scan_named_dirs(root: &SearchRoot, keywords: &[String], candidates: &mut Candidates) {
+    for (name, path) in sub_dirs(&root.dir) {
+        let template = join_template(&root.template, &name);
+        if matches_keywords(&name, keywords) {
+            candidates.push_dir(&path, template, root.source);
+            continue;
+        }
+        for (child_name, child_path) in sub_dirs(&path) {
+            if matches_keywords(&child_name, keywords) {
+                candidates.push_dir(
+                    &child_path,
+                    join_template(&template, &child_name),
+                    root.source,
+                );
+            }
+        }
+    }
+}
+
+/// Unity 的存档位置由 `<游戏>_Data/app.info` 中的公司名与产品名决定
+fn unity_company_product(game_dir: &Path) -> Option<(String, String)> {
+    sub_dirs(game_dir)
+        .into_iter()
+        .filter(|(name, _)| name.to_lowercase().ends_with("_data"))
+        .find_map(|(_, path)| {
+            let info = fs::read_to_string(path.join("app.info")).ok()?;
+            let mut lines = info.lines().map(str::trim);
+            let company = lines.next().filter(|line| !line.is_empty())?;
+            let product = lines.next().filter(|line| !line.is_empty())?;
+            Some((company.to_string(), product.to_string()))
+        })
+}
+
+/// 引擎固定的存档位置
+fn scan_engine_dirs(
+    game_dir: &Path,
+    engine: GameEngine,
+    home: Option<&SearchRoot>,
+    keywords: &[String],
+    candidates: &mut Candidates,
+) {
+    match engine {
+        GameEngine::RpgMaker => {
+            let save = game_dir.join("www").join("save");
+            if save.is_dir() {
+                let template = join_template(&join_template(GAME_DIR_PLACEHOLDER, "www"), "save");
+                candidates.push_dir(&save, template, SaveCandidateSource::Engine);
+            }
+        }
+        GameEngine::Renpy => {
+            let save = game_dir.join("game").join("saves");
+            if save.is_dir() {
+                let template = join_template(&join_template(GAME_DIR_PLACEHOLDER, "game"), "saves");
+                candidates.push_dir(&save, template, SaveCandidateSource::Engine);
+            }
+            // Ren'Py 同时在用户目录保存一份，目录名为 config.save_directory，通常含游戏名
+            if let Some(root) = renpy_root(home) {
+                for (name, path) in sub_dirs(&root.dir) {
+                    if matches_keywords(&name, keywords) {
+                        candidates.push_dir(
+                            &path,
+                            join_template(&root.template, &name),
+                            SaveCandidateSource::Engine,
+                        );
+                    }
+                }
+            }
+        }
+        GameEngine::Unity => {
+            let Some((company, product)) = unity_company_product(game_dir) else {
+                return;
+            };
+            if let Some(root) = unity_root(home) {
+                let dir = root.dir.join(&company).join(&product);
+                if dir.is_dir() {
+                    let template =
+                        join_template(&join_template(&root.template, &company), &product);
+                    candidates.push_dir(&dir, template, SaveCandidateSource::Engine);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 用户主目录，`template` 为 `%USERPROFILE%` 或 `~`
+fn home_root() -> Option<SearchRoot> {
+    #[cfg(target_os = "windows")]
+    let (var, template) = ("USERPROFILE", "%USERPROFILE%");
+    #[cfg(not(target_os = "windows"))]
+    let (var, template) = ("HOME", "~");
+    let home = std::env::var_os(var).filter(|home| !home.is_empty())?;
+    Some(SearchRoot::new(
+        PathBuf::from(home),
+        template,
+        SaveCandidateSource::AppData,
+    ))
+}
+
+fn sub_root(parent: &SearchRoot, parts: &[&str], source: SaveCandidateSource) -> SearchRoot {
+    let mut dir = parent.dir.clone();
+    let mut template = parent.template.clone();
+    for part in parts {
+        dir.push(part);
+        template = join_template(&template, part);
+    }
+    SearchRoot::new(dir, template, source)
+}
+
+#[cfg(target_os = "windows")]
+fn renpy_root(_home: Option<&SearchRoot>) -> Option<SearchRoot> {
+    let app_data = std::env::var_os("APPDATA")?;
+    Some(SearchRoot::new(
+        PathBuf::from(app_data).join("RenPy"),
+        "%APPDATA%\\RenPy",
+        SaveCandidateSource::Engine,
+    ))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn renpy_root(home: Option<&SearchRoot>) -> Option<SearchRoot> {
+    home.map(|home| sub_root(home, &[".renpy"], SaveCandidateSource::Engine))
+}
+
+#[cfg(target_os = "windows")]
+fn unity_root(home: Option<&SearchRoot>) -> Option<SearchRoot> {
+    home.map(|home| sub_root(home, &["AppData", "LocalLow"], SaveCandidateSource::Engine))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn unity_root(home: Option<&SearchRoot>) -> Option<SearchRoot> {
+    home.map(|home| sub_root(home, &[".config", "unity3d"], SaveCandidateSource::Engine))
+}
+
+/// 按同名目录搜索的根目录
+fn search_roots(home: Option<&SearchRoot>) -> Vec<SearchRoot> {
+    let mut roots = Vec::new();
+    #[cfg(target_os = "windows")]
+    for (var, template) in [("APPDATA", "%APPDATA%"), ("LOCALAPPDATA", "%LOCALAPPDATA%")] {
+        if let Some(dir) = std::env::var_os(var).filter(|dir| !dir.is_empty()) {
+            roots.push(SearchRoot::new(
+                PathBuf::from(dir),
+                template,
+                SaveCandidateSource::AppData,
+            ));
+        }
+    }
+    if let Some(home) = home {
+        #[cfg(target_os = "windows")]
+        roots.push(sub_root(
+            home,
+            &["AppData", "LocalLow"],
+            SaveCandidateSource::AppData,
+        ));
+        #[cfg(not(target_os = "windows"))]
+        {
+            roots.push(sub_root(
+                home,
+                &[".local", "share"],
+                SaveCandidateSource::AppData,
+            ));
+            roots.push(sub_root(home, &[".config"], SaveCandidateSource::AppData));
+        }
+        roots.push(sub_root(
+            home,
+            &["Documents"],
+            SaveCandidateSource::Documents,
+        ));
+    }
+    roots
+}
+
+#[cfg(target_os = "windows")]
+mod registry {
+    use windows::Win32::System::Registry::{
+        HKEY, HKEY_CURRENT_USER, KEY_READ, RegCloseKey, RegEnumKeyExW, RegOpenKeyExW,
+    };
+    use windows::core::{HSTRING, PWSTR};
+
+    /// 列出 `HKCU\<sub_key>` 的直接子键名
+    pub fn sub_keys(sub_key: &str) -> Vec<String> {
+        let mut key = HKEY::default();
+        let opened = unsafe {
+            RegOpenKeyExW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(sub_key),
+                None,
+                KEY_READ,
+                &mut key,
+            )
+        };
+        if opened.is_err() {
+            return Vec::new();
+        }
+        let mut names = Vec::new();
+        // 注册表键名最长 255 个字符
+        let mut buffer = [0u16; 256];
+        for index in 0.. {
+            let mut len = buffer.len() as u32;
+            let status = unsafe {
+                RegEnumKeyExW(
+                    key,
+                    index,
+                    Some(PWSTR(buffer.as_mut_ptr())),
+                    &mut len,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+            };
+            if status.is_err() {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+        }
+        unsafe {
+            let _ = RegCloseKey(key);
+        }
+        names
+    }
+}
+
+/// `HKCU\Software` 下两层内与游戏同名的键
+#[cfg(target_os = "windows")]
+fn scan_registry(keywords: &[String], candidates: &mut Candidates) {
+    const SYSTEM_KEYS: &[&str] = &["classes", "microsoft", "policies", "wow6432node"];
+    for name in registry::sub_keys("Software") {
+        let key = format!("Software\\{}", name);
+        if matches_keywords(&name, keywords) {
+            candidates.push_registry_key(format!("HKEY_CURRENT_USER\\{}", key));
+            continue;
+        }
+        if SYSTEM_KEYS.contains(&name.to_lowercase().as_str()) {
+            continue;
+        }
+        for child in registry::sub_keys(&key) {
+            if matches_keywords(&child, keywords) {
+                candidates.push_registry_key(format!("HKEY_CURRENT_USER\\{}\\{}", key, child));
+            }
+        }
+    }
+}
+
+fn detect_candidates(
+    game_dir: Option<&Path>,
+    engine: Option<GameEngine>,
+    keywords: &[String],
+) -> Vec<SavePathCandidate> {
+    let mut candidates = Candidates::default();
+    let home = home_root();
+    if let Some(game_dir) = game_dir {
+        scan_game_dir(game_dir, &mut candidates);
+        if let Some(engine) = engine.or_else(|| detect_engine(game_dir)) {
+            scan_engine_dirs(game_dir, engine, home.as_ref(), keywords, &mut candidates);
+        }
+    }
+    if !keywords.is_empty() {
+        for root in search_roots(home.as_ref()) {
+            scan_named_dirs(&root, keywords, &mut candidates);
+        }
+        #[cfg(target_os = "windows")]
+        scan_registry(keywords, &mut candidates);
+    }
+    candidates.into_sorted()
+}
+
+/// 探测游戏的存档路径候选，供用户确认后写入 `savepath`
+#[command]
+pub async fn detect_save_path(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<Vec<SavePathCandidate>, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let game_dir = game
+        .localpath
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| path.is_dir());
+    let engine = game
+        .engine
+        .clone()
+        .and_then(|engine| serde_json::from_value(serde_json::Value::String(engine)).ok());
+    let keywords = game_keywords(&game);
+
+    let candidates = tokio::task::spawn_blocking(move || {
+        detect_candidates(game_dir.as_deref(), engine, &keywords)
+    })
+    .await
+    .map_err(|e| format!("探测存档路径失败: {}", e))?;
+    log::info!(
+        "探测存档路径 game_id={} 找到 {} 个候选",
+        game_id,
+        candidates.len()
+    );
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+
+    #[test]
+    fn matches_specific_keywords_only() {
+        let keywords = vec![normalize("Senren＊Banka"), normalize("千恋＊万花")];
+        assert_eq!(keywords, ["senrenbanka", "千恋万花"]);
+        assert!(matches_keywords("SenrenBanka", &keywords));
+        assert!(matches_keywords("senren_banka_save", &keywords));
+        assert!(matches_keywords("千恋万花", &keywords));
+        assert!(matches_keywords("Senren", &keywords));
+        assert!(!matches_keywords("Data", &keywords));
+        assert!(!matches_keywords("Microsoft", &keywords));
+    }
+
+    #[test]
+    fn finds_game_dir_and_named_dirs() {
+        let root = temp_dir("save-detect", "");
+        let game_dir = root.join("game");
+        fs::create_dir_all(game_dir.join("SaveData")).expect("应能创建测试目录");
+        fs::create_dir_all(game_dir.join("movie")).unwrap();
+        fs::write(game_dir.join("SaveData").join("data0001.bin"), b"save").unwrap();
+        let app_data = root.join("AppData");
+        fs::create_dir_all(app_data.join("Yuzusoft").join("SenrenBanka")).unwrap();
+        fs::create_dir_all(app_data.join("OtherApp")).unwrap();
+
+        let mut candidates = Candidates::default();
+        scan_game_dir(&game_dir, &mut candidates);
+        let app_root = SearchRoot::new(app_data.clone(), "%APPDATA%", SaveCandidateSource::AppData);
+        scan_named_dirs(&app_root, &["senrenbanka".to_string()], &mut candidates);
+        let candidates = candidates.into_sorted();
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(
+            candidates[0].path,
+            join_template(GAME_DIR_PLACEHOLDER, "SaveData")
+        );
+        assert_eq!(candidates[0].source, SaveCandidateSource::GameDir);
+        assert_eq!(candidates[0].file_count, 1);
+        assert!(candidates[0].modified_at.is_some());
+        assert_eq!(
+            candidates[1].path,
+            join_template(&join_template("%APPDATA%", "Yuzusoft"), "SenrenBanka")
+        );
+        assert_eq!(
+            candidates[1].resolved,
+            app_data
+                .join("Yuzusoft")
+                .join("SenrenBanka")
+                .to_string_lossy()
+        );
+        assert_eq!(candidates[1].file_count, 0);
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+}
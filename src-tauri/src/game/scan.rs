@@ -1,10 +1,20 @@
+use crate::database::dto::{FullGameData, InsertGameData};
 use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::custom_data::CustomData;
+use crate::entity::user::ScanExeRules;
+use crate::game::engine::{GameEngine, detect_engine};
+use crate::metadata::{MetadataMatch, MetadataSource, search_best_matches};
+use crate::utils::tasks::{TaskKind, TaskManager};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::{HashMap, HashSet};
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
-use tauri::{State, command};
+use tauri::{AppHandle, Emitter, Runtime, State, command};
 use walkdir::WalkDir;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,6 +25,12 @@ pub struct ScanResult {
     pub path: String,
     /// exe文件列表
     pub executables: Vec<String>,
+    /// 目录是否已作为游戏目录入库
+    pub already_added: bool,
+    /// 已入库时对应的游戏 ID
+    pub matched_game_id: Option<i32>,
+    /// 识别出的游戏引擎
+    pub engine: Option<GameEngine>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -27,6 +43,49 @@ pub enum ScanMode {
 const VALID_EXE_EXTENSIONS: &[&str] = &["exe", "bat", "cmd"];
 const MIN_SCAN_MAX_DEPTH: usize = 2;
 const MAX_SCAN_MAX_DEPTH: usize = 5;
+const SCAN_CANCELLED: &str = "扫描已取消";
+
+/// 扫描过程的取消检查与进度上报
+///
+/// 进度以扫描根目录下的一级子目录为单位：`processed / total`。
+struct ScanControl {
+    cancelled: Arc<AtomicBool>,
+    on_progress: Box<dyn Fn(usize, usize) + Send>,
+}
+
+impl ScanControl {
+    #[cfg(test)]
+    fn noop() -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            on_progress: Box::new(|_, _| {}),
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(SCAN_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn report(&self, processed: usize, total: usize) {
+        (self.on_progress)(processed, total);
+    }
+}
+
+/// 统计扫描根目录下的一级子目录数量，作为进度总数
+fn count_top_level_dirs(path: &Path) -> usize {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .count()
+        })
+        .unwrap_or(0)
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum ImportPathComponent {
@@ -37,26 +96,39 @@ enum ImportPathComponent {
 }
 
 #[derive(Default)]
-struct ImportPathIndex {
-    paths: HashSet<Vec<ImportPathComponent>>,
+pub(crate) struct ImportPathIndex {
+    /// 规范化后的游戏目录 -> 游戏 ID
+    paths: HashMap<Vec<ImportPathComponent>, i32>,
 }
 
 impl ImportPathIndex {
-    fn insert(&mut self, path: &Path) {
+    pub(crate) fn insert(&mut self, path: &Path, game_id: i32) {
         let Some(components) = normalize_import_path(path) else {
             return;
         };
 
-        self.paths.insert(components);
+        self.paths.entry(components).or_insert(game_id);
     }
 
-    /// 已导入游戏目录本身及其后代都属于同一个游戏，不再作为扫描候选。
+    /// 目录本身已入库时返回对应的游戏 ID
+    pub(crate) fn matched_game_id(&self, path: &Path) -> Option<i32> {
+        let components = normalize_import_path(path)?;
+        self.paths.get(&components).copied()
+    }
+
+    /// 已导入游戏目录本身及其后代都属于同一个游戏。
+    #[cfg(test)]
     fn is_imported_or_descendant(&self, path: &Path) -> bool {
+        self.matched_game_id(path).is_some() || self.is_inside_imported(path)
+    }
+
+    /// 路径位于已导入游戏目录之下（不含目录本身），不再作为扫描候选。
+    pub(crate) fn is_inside_imported(&self, path: &Path) -> bool {
         let Some(components) = normalize_import_path(path) else {
             return false;
         };
 
-        (1..=components.len()).any(|length| self.paths.contains(&components[..length]))
+        (1..components.len()).any(|length| self.paths.contains_key(&components[..length]))
     }
 
     fn len(&self) -> usize {
@@ -92,6 +164,42 @@ fn normalize_import_path(path: &Path) -> Option<Vec<ImportPathComponent>> {
     (!normalized.is_empty()).then_some(normalized)
 }
 
+/// 将 `path` 开头的 `old_prefix` 替换为 `new_prefix`，不以该前缀开头时返回 None
+///
+/// 按路径组件比较（Windows 下不区分大小写），`D:\Galgame` 不会匹配 `D:\Galgame2`。
+pub(crate) fn replace_path_prefix(
+    path: &str,
+    old_prefix: &Path,
+    new_prefix: &Path,
+) -> Option<String> {
+    let mut rest = Path::new(path)
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir));
+    for expected in old_prefix
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+    {
+        let actual = rest.next()?;
+        if normalize_import_component(actual.as_os_str())
+            != normalize_import_component(expected.as_os_str())
+        {
+            return None;
+        }
+    }
+
+    let mut replaced = new_prefix.to_path_buf();
+    replaced.extend(rest);
+    Some(replaced.to_string_lossy().into_owned())
+}
+
+/// `path` 是否为 `base` 本身或其后代，比较规则与入库路径去重一致
+pub(crate) fn is_same_or_descendant(path: &Path, base: &Path) -> bool {
+    match (normalize_import_path(path), normalize_import_path(base)) {
+        (Some(path), Some(base)) => path.starts_with(&base),
+        _ => false,
+    }
+}
+
 #[cfg(windows)]
 fn normalize_import_component(value: &std::ffi::OsStr) -> String {
     value.to_string_lossy().to_lowercase()
@@ -125,28 +233,7 @@ const EXCLUDED_DIRS: &[&str] = &[
     "vcredist",
 ];
 
-/// exe 文件名（stem）中包含以下关键字则视为非游戏程序，予以排除
-const EXCLUDED_EXE_PATTERNS: &[&str] = &[
-    "unins", // 卸载程序
-    "uninst",
-    "uninstall",
-    "setup", // 安装 / 配置向导
-    "install",
-    "dxsetup",  // DirectX
-    "vcredist", // VC++ 运行时
-    "vc_redist",
-    "dotnetfx",     // .NET
-    "oalinst",      // OpenAL
-    "crashhandler", // 崩溃处理器
-    "crash_handler",
-    "crashreport",
-    "crash_report",
-    "bugreport",
-    "bug_report",
-    "unitycrashandler",
-];
-
-fn trim_dirname_to_search_name(dir_name: &str) -> String {
+pub(crate) fn trim_dirname_to_search_name(dir_name: &str) -> String {
     let mut result = String::with_capacity(dir_name.len());
     let mut square_depth = 0_u32;
     let mut round_depth = 0_u32;
@@ -183,18 +270,75 @@ fn trim_dirname_to_search_name(dir_name: &str) -> String {
     }
 }
 
-fn is_excluded_dir(name: &str) -> bool {
+pub(crate) fn is_excluded_dir(name: &str) -> bool {
     let lower = name.to_lowercase();
     EXCLUDED_DIRS.iter().any(|&d| lower == d)
 }
 
-fn is_excluded_exe(path: &Path) -> bool {
-    path.file_stem().is_some_and(|stem| {
-        let lower = stem.to_string_lossy().to_lowercase();
-        EXCLUDED_EXE_PATTERNS.iter().any(|&p| lower.contains(p))
+/// exe 文件名（stem）命中排除关键字则视为非游戏程序，予以排除
+fn is_excluded_exe(path: &Path, rules: &ScanExeRules) -> bool {
+    path.file_stem()
+        .is_some_and(|stem| rules.is_excluded(&stem.to_string_lossy().to_lowercase()))
+}
+
+fn has_valid_exe_extension(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        VALID_EXE_EXTENSIONS
+            .iter()
+            .any(|expected| ext.eq_ignore_ascii_case(expected))
     })
 }
 
+/// 将扫描到的文件转换为游戏启动程序候选
+///
+/// 可执行文件原样返回；快捷方式解析为真实目标，目标不在快捷方式所在目录内时
+/// 保留快捷方式本身，启动时再解析。
+fn scanned_executable(path: &Path, rules: &ScanExeRules) -> Option<PathBuf> {
+    if is_excluded_exe(path, rules) {
+        return None;
+    }
+    if has_valid_exe_extension(path) {
+        return Some(path.to_path_buf());
+    }
+    resolve_scanned_shortcut(path, rules)
+}
+
+#[cfg(target_os = "windows")]
+fn resolve_scanned_shortcut(path: &Path, rules: &ScanExeRules) -> Option<PathBuf> {
+    use crate::utils::shortcut::{is_shortcut, resolve_shortcut};
+
+    if !is_shortcut(path) {
+        return None;
+    }
+    let target = match resolve_shortcut(path) {
+        Ok(shortcut) => shortcut.target,
+        Err(e) => {
+            log::debug!("跳过无法解析的快捷方式 {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    if !has_valid_exe_extension(&target) || is_excluded_exe(&target, rules) {
+        return None;
+    }
+
+    let target_in_dir = path.parent().is_some_and(|dir| {
+        normalize_import_path(&target)
+            .zip(normalize_import_path(dir))
+            .is_some_and(|(target, dir)| target.starts_with(&dir))
+    });
+    Some(if target_in_dir {
+        target
+    } else {
+        path.to_path_buf()
+    })
+}
+
+/// 快捷方式依赖 IShellLink 解析，其他平台不作为启动程序候选
+#[cfg(not(target_os = "windows"))]
+fn resolve_scanned_shortcut(_path: &Path, _rules: &ScanExeRules) -> Option<PathBuf> {
+    None
+}
+
 fn is_han_character(character: char) -> bool {
     matches!(
         character,
@@ -234,13 +378,19 @@ fn is_probably_chinese_executable(value: &str) -> bool {
     contains_han
 }
 
-fn sort_executables(executables: &mut [String], game_name: &str) {
+/// 按规则排序启动程序：优先关键字 > 中文文件名 > 非置后关键字 > 含游戏名 > 路径长度
+fn sort_executables(executables: &mut [String], game_name: &str, rules: &ScanExeRules) {
     let lower_name = game_name.to_lowercase();
     executables.sort_by_cached_key(|executable| {
         let lower = executable.to_lowercase();
+        let lower_stem = Path::new(&lower)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| lower.clone());
         (
-            !(lower.contains("chs") || lower.contains("cn")),
+            !rules.is_priority(&lower_stem),
             !is_probably_chinese_executable(executable),
+            rules.is_demoted(&lower_stem),
             !lower.contains(&lower_name),
             executable.len(),
             lower,
@@ -248,9 +398,15 @@ fn sort_executables(executables: &mut [String], game_name: &str) {
     });
 }
 
+/// 扫描目录中的游戏
+///
+/// 扫描在阻塞线程池中进行，期间通过 `scan-progress` 与 `task-progress` 事件上报已处理的
+/// 一级目录数量，可随时调用 [`cancel_scan`] 取消，取消后返回错误。同一时间只允许一个扫描任务。
 #[command]
-pub async fn scan_directory_for_games(
+pub async fn scan_directory_for_games<R: Runtime>(
+    app_handle: AppHandle<R>,
     db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
     path: String,
     max_depth: usize,
     scan_mode: ScanMode,
@@ -264,17 +420,32 @@ pub async fn scan_directory_for_games(
     let existing_game_directories = GamesRepository::get_all_game_directories(&db)
         .await
         .map_err(|e| format!("查询已有路径失败: {}", e))?;
+    let rules = db.get_settings().await?.scan_exe_rules();
+    let task = Arc::new(tasks.start(&app_handle, TaskKind::Scan)?);
 
     let max_depth = max_depth.clamp(MIN_SCAN_MAX_DEPTH, MAX_SCAN_MAX_DEPTH);
     let started_at = Instant::now();
     let path_for_log = path.clone();
+    let progress_task = task.clone();
+    let control = ScanControl {
+        cancelled: task.cancel_flag(),
+        on_progress: Box::new(move |processed, total| {
+            progress_task.progress(processed as u64, total as u64);
+            if let Err(e) = app_handle.emit(
+                "scan-progress",
+                json!({ "processed": processed, "total": total }),
+            ) {
+                log::warn!("无法发送 scan-progress 事件: {}", e);
+            }
+        }),
+    };
 
     // WalkDir 大量文件系统 I/O 属于阻塞操作，
     // 放入 Tokio 革层阻塞线程池，避免占用异步运行时线程。
     let results = tokio::task::spawn_blocking(move || {
         let mut existing_paths = ImportPathIndex::default();
-        for game_directory in existing_game_directories {
-            existing_paths.insert(Path::new(&game_directory));
+        for (game_id, game_directory) in existing_game_directories {
+            existing_paths.insert(Path::new(&game_directory), game_id);
         }
         log::debug!(
             "开始扫描游戏目录 path={} mode={:?} max_depth={} existing_paths={}",
@@ -283,7 +454,7 @@ pub async fn scan_directory_for_games(
             max_depth,
             existing_paths.len()
         );
-        scan_games_blocking(path, existing_paths, max_depth, scan_mode)
+        scan_games_blocking(path, existing_paths, max_depth, scan_mode, &rules, &control)
     })
     .await
     .map_err(|e| {
@@ -295,7 +466,22 @@ pub async fn scan_directory_for_games(
             e
         );
         format!("扫描任务异常: {}", e)
-    })??;
+    })
+    .and_then(|results| results);
+    task.finish(&results);
+
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            log::info!(
+                "游戏目录扫描中止 path={} elapsed_ms={}: {}",
+                path_for_log,
+                started_at.elapsed().as_millis(),
+                e
+            );
+            return Err(e);
+        }
+    };
 
     log::info!(
         "游戏目录扫描完成 mode={:?} max_depth={} result_count={} elapsed_ms={}",
@@ -308,6 +494,198 @@ pub async fn scan_directory_for_games(
     Ok(results)
 }
 
+/// 取消正在进行的目录扫描
+///
+/// # Returns
+/// 存在进行中的扫描任务时返回 true
+#[command]
+pub fn cancel_scan(tasks: State<'_, TaskManager>) -> Result<bool, String> {
+    let requested = tasks.cancel_kind(TaskKind::Scan);
+    if requested {
+        log::info!("已请求取消目录扫描");
+    }
+    Ok(requested)
+}
+
+/// 批量入库的单个扫描条目
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScannedGameInput {
+    /// 游戏名，未匹配到元数据时作为自定义名称，也用于搜索元数据
+    pub name: String,
+    /// 游戏目录
+    pub path: String,
+    /// 选定的启动程序，相对游戏目录
+    #[serde(default)]
+    pub executable: Option<String>,
+    #[serde(default)]
+    pub engine: Option<GameEngine>,
+}
+
+/// 批量入库中单个条目的结果
+#[derive(Debug, Serialize)]
+pub struct BatchAddItemResult {
+    /// 条目在请求列表中的下标
+    pub index: usize,
+    pub path: String,
+    pub success: bool,
+    pub game_id: Option<i32>,
+    /// 自动匹配采用的数据源，未匹配时为 None
+    pub matched_source: Option<MetadataSource>,
+    pub external_id: Option<String>,
+    /// 元数据匹配失败的原因，匹配失败不影响入库
+    pub match_error: Option<String>,
+    /// 入库失败的原因
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchAddScannedResult {
+    pub total: usize,
+    pub success: usize,
+    pub failed: usize,
+    pub games: Vec<FullGameData>,
+    pub items: Vec<BatchAddItemResult>,
+}
+
+/// 将扫描条目转换为插入数据
+///
+/// 启动程序可能位于子目录，此时以其所在目录作为 localpath，保证 executable 只是文件名。
+fn build_scanned_insert_data(
+    item: &ScannedGameInput,
+    matched: Option<&MetadataMatch>,
+) -> InsertGameData {
+    let game_dir = Path::new(&item.path);
+    let (localpath, executable) = match item
+        .executable
+        .as_deref()
+        .map(str::trim)
+        .filter(|exe| !exe.is_empty())
+    {
+        Some(executable) => {
+            let full_path = game_dir.join(executable);
+            let localpath = full_path.parent().unwrap_or(game_dir).to_path_buf();
+            let file_name = full_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string());
+            (localpath, file_name)
+        }
+        None => (game_dir.to_path_buf(), None),
+    };
+
+    let (id_type, custom_data, sources) = match matched {
+        Some(matched) => (
+            matched.source.as_str().to_string(),
+            None,
+            vec![matched.clone().into_source_data()],
+        ),
+        None => (
+            "custom".to_string(),
+            Some(CustomData {
+                name: Some(item.name.clone()),
+                ..Default::default()
+            }),
+            Vec::new(),
+        ),
+    };
+
+    InsertGameData {
+        id_type,
+        localpath: Some(localpath.to_string_lossy().to_string()),
+        executable,
+        engine: item.engine.map(|engine| engine.as_str().to_string()),
+        custom_data,
+        sources,
+        ..Default::default()
+    }
+}
+
+/// 批量将扫描结果入库
+///
+/// 可选按游戏名在 BGM/VNDB 自动匹配元数据（取搜索排名第一的条目），
+/// 匹配完成后在单个事务中插入全部条目，单条失败不影响其他条目。
+#[command]
+pub async fn batch_add_scanned_games(
+    db: State<'_, DatabaseConnection>,
+    items: Vec<ScannedGameInput>,
+    auto_match: Option<MetadataSource>,
+) -> Result<BatchAddScannedResult, String> {
+    let total = items.len();
+    let mut matches: Vec<Option<MetadataMatch>> = Vec::with_capacity(total);
+    let mut match_errors: Vec<Option<String>> = Vec::with_capacity(total);
+
+    if let Some(source) = auto_match {
+        let settings = db.get_settings().await?;
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        for result in search_best_matches(source, &names, &settings).await {
+            match result {
+                Ok(matched) => {
+                    matches.push(matched);
+                    match_errors.push(None);
+                }
+                Err(e) => {
+                    matches.push(None);
+                    match_errors.push(Some(e));
+                }
+            }
+        }
+    } else {
+        matches.resize_with(total, || None);
+        match_errors.resize_with(total, || None);
+    }
+
+    let games = items
+        .iter()
+        .zip(&matches)
+        .map(|(item, matched)| build_scanned_insert_data(item, matched.as_ref()))
+        .collect();
+    let result = GamesRepository::insert_batch(&db, games).await;
+
+    let mut errors: HashMap<usize, String> = result
+        .errors
+        .into_iter()
+        .map(|error| (error.index, error.message))
+        .collect();
+    let mut inserted_ids = result.ids.into_iter();
+    let items = items
+        .into_iter()
+        .zip(matches.into_iter().zip(match_errors))
+        .enumerate()
+        .map(|(index, (item, (matched, match_error)))| {
+            let error = errors.remove(&index);
+            let game_id = if error.is_none() {
+                inserted_ids.next()
+            } else {
+                None
+            };
+            BatchAddItemResult {
+                index,
+                path: item.path,
+                success: game_id.is_some(),
+                game_id,
+                matched_source: matched.as_ref().map(|matched| matched.source),
+                external_id: matched.map(|matched| matched.external_id),
+                match_error,
+                error,
+            }
+        })
+        .collect();
+
+    log::info!(
+        "批量入库扫描结果完成 total={} success={} failed={}",
+        result.total,
+        result.success,
+        result.failed
+    );
+
+    Ok(BatchAddScannedResult {
+        total: result.total,
+        success: result.success,
+        failed: result.failed,
+        games: result.games,
+        items,
+    })
+}
+
 /// 包含所有阻塞 I/O 和 CPU 密集计算的同步扫描逻辑
 ///
 /// 由 [`scan_directory_for_games`] 通过 `tokio::task::spawn_blocking` 调用，
@@ -317,15 +695,29 @@ fn scan_games_blocking(
     existing_paths: ImportPathIndex,
     max_depth: usize,
     scan_mode: ScanMode,
+    rules: &ScanExeRules,
+    control: &ScanControl,
 ) -> Result<Vec<ScanResult>, String> {
     match scan_mode {
-        ScanMode::Executable => scan_executable_games_blocking(path, existing_paths, max_depth),
-        ScanMode::FirstLevelDirectory => Ok(scan_direct_child_directories(path, existing_paths)),
+        ScanMode::Executable => {
+            scan_executable_games_blocking(path, existing_paths, max_depth, rules, control)
+        }
+        ScanMode::FirstLevelDirectory => {
+            scan_direct_child_directories(path, existing_paths, rules, control)
+        }
     }
 }
 
-fn scan_direct_child_directories(path: String, existing_paths: ImportPathIndex) -> Vec<ScanResult> {
+fn scan_direct_child_directories(
+    path: String,
+    existing_paths: ImportPathIndex,
+    rules: &ScanExeRules,
+    control: &ScanControl,
+) -> Result<Vec<ScanResult>, String> {
     let dir_path = PathBuf::from(path);
+    let total_dirs = count_top_level_dirs(&dir_path);
+    let mut processed_dirs = 0;
+    control.report(processed_dirs, total_dirs);
     let mut executables_by_dir: HashMap<PathBuf, Vec<String>> = HashMap::new();
     let mut walker = WalkDir::new(&dir_path)
         .min_depth(1)
@@ -334,6 +726,7 @@ fn scan_direct_child_directories(path: String, existing_paths: ImportPathIndex)
         .into_iter();
 
     while let Some(entry) = walker.next() {
+        control.check_cancelled()?;
         let entry = match entry {
             Ok(entry) => entry,
             Err(_) => continue,
@@ -341,9 +734,13 @@ fn scan_direct_child_directories(path: String, existing_paths: ImportPathIndex)
 
         let entry_path = entry.path();
         if entry.depth() == 1 {
+            if entry.file_type().is_dir() {
+                processed_dirs += 1;
+                control.report(processed_dirs, total_dirs);
+            }
             if !entry.file_type().is_dir()
                 || is_excluded_dir(&entry.file_name().to_string_lossy())
-                || existing_paths.is_imported_or_descendant(entry_path)
+                || existing_paths.is_inside_imported(entry_path)
             {
                 if entry.file_type().is_dir() {
                     walker.skip_current_dir();
@@ -357,27 +754,20 @@ fn scan_direct_child_directories(path: String, existing_paths: ImportPathIndex)
             continue;
         }
 
-        if !entry.file_type().is_file() || is_excluded_exe(entry_path) {
+        if !entry.file_type().is_file() {
             continue;
         }
-        let Some(ext) = entry_path.extension() else {
-            continue;
-        };
-        if !VALID_EXE_EXTENSIONS
-            .iter()
-            .any(|expected| ext.eq_ignore_ascii_case(expected))
-        {
-            continue;
-        }
-
         let Some(parent) = entry_path.parent() else {
             continue;
         };
         let Some(executables) = executables_by_dir.get_mut(parent) else {
             continue;
         };
-        if let Some(file_name) = entry_path.file_name() {
-            executables.push(file_name.to_string_lossy().to_string());
+        let Some(executable) = scanned_executable(entry_path, rules) else {
+            continue;
+        };
+        if let Ok(relative) = executable.strip_prefix(parent) {
+            executables.push(relative.to_string_lossy().to_string());
         }
     }
 
@@ -386,25 +776,36 @@ fn scan_direct_child_directories(path: String, existing_paths: ImportPathIndex)
         .filter_map(|(game_dir, mut executables)| {
             let raw_name = game_dir.file_name()?.to_string_lossy();
             let name = trim_dirname_to_search_name(&raw_name);
-            sort_executables(&mut executables, &name);
+            sort_executables(&mut executables, &name, rules);
+            // 快捷方式解析后可能与同目录的 exe 重复
+            executables.dedup();
+            let matched_game_id = existing_paths.matched_game_id(&game_dir);
             Some(ScanResult {
                 name,
                 path: game_dir.to_string_lossy().to_string(),
                 executables,
+                already_added: matched_game_id.is_some(),
+                matched_game_id,
+                engine: detect_engine(&game_dir),
             })
         })
         .collect();
 
     results.sort_by(|a, b| a.name.cmp(&b.name));
-    results
+    Ok(results)
 }
 
 fn scan_executable_games_blocking(
     path: String,
     existing_paths: ImportPathIndex,
     max_depth: usize,
+    rules: &ScanExeRules,
+    control: &ScanControl,
 ) -> Result<Vec<ScanResult>, String> {
     let dir_path = PathBuf::from(&path);
+    let total_dirs = count_top_level_dirs(&dir_path);
+    let mut processed_dirs = 0;
+    control.report(processed_dirs, total_dirs);
 
     // Phase 1: DFS 遍历，收集所有有效 exe，按其所在目录分组
     // 使用手动迭代器控制代替 filter_entry，以便调用 skip_current_dir() 实现真正的短路：
@@ -428,6 +829,7 @@ fn scan_executable_games_blocking(
         .into_iter();
 
     while let Some(entry) = walker.next() {
+        control.check_cancelled()?;
         let entry = match entry {
             Ok(e) => e,
             Err(_) => continue,
@@ -436,18 +838,27 @@ fn scan_executable_games_blocking(
         let entry_path = entry.path();
 
         if entry.file_type().is_dir() {
+            // 目录已排序在文件之后，一级目录按顺序逐个进入
+            if entry.depth() == 1 {
+                processed_dirs += 1;
+                control.report(processed_dirs, total_dirs);
+            }
             // walkdir 在 yield 目录时已将其 ReadDir 压栈，skip_current_dir() 将其弹出，
             // 从而跳过该目录的所有内容，但不影响同级其他条目。
             let should_skip = is_excluded_dir(&entry.file_name().to_string_lossy())
-                || existing_paths.is_imported_or_descendant(entry_path)
+                || existing_paths.is_inside_imported(entry_path)
                 // 父目录已有直属 exe → 该子目录无需遍历（祖先优先短路）
                 || entry_path
                     .parent()
                     .is_some_and(|p| dirs_with_exe.contains(p));
             if should_skip {
                 walker.skip_current_dir();
+            } else if existing_paths.matched_game_id(entry_path).is_some() {
+                // 已入库目录本身仍作为结果返回并标记，由前端决定是否跳过；
+                // 其子目录会被跳过，先登记目录本身以免启动程序位于子目录时漏掉。
+                exe_by_dir.entry(entry_path.to_path_buf()).or_default();
             }
-            continue; // 目录条目本身无需记录
+            continue; // 普通目录条目本身无需记录
         }
 
         if entry.file_type().is_file() {
@@ -457,20 +868,14 @@ fn scan_executable_games_blocking(
             if parent == dir_path.as_path() {
                 continue; // 忽略根目录直属文件
             }
-            // 收集有效可执行文件，并标记该目录已有 exe
-            if let Some(ext) = entry_path.extension()
-                && VALID_EXE_EXTENSIONS
-                    .iter()
-                    .any(|&e| ext.eq_ignore_ascii_case(e))
-                && !is_excluded_exe(entry_path)
-            {
-                // 已导入目录同样视为“已有直属 exe”，避免继续扫描其子目录。
+            // 收集有效可执行文件（含可解析的快捷方式），并标记该目录已有 exe
+            if let Some(executable) = scanned_executable(entry_path, rules) {
                 dirs_with_exe.insert(parent.to_path_buf());
-                if !existing_paths.is_imported_or_descendant(parent) {
+                if !existing_paths.is_inside_imported(parent) {
                     exe_by_dir
                         .entry(parent.to_path_buf())
                         .or_default()
-                        .push(entry_path.to_path_buf());
+                        .push(executable);
                 }
             }
         }
@@ -495,7 +900,7 @@ fn scan_executable_games_blocking(
         }
     }
 
-    // Phase 3: 构建结果。层级已由用户控制，这里只要求目录含有效启动程序。
+    // Phase 3: 构建结果。层级已由用户控制，这里只要求目录含有效启动程序或已入库。
     let mut results: Vec<ScanResult> = selected
         .into_iter()
         .filter_map(|game_dir| {
@@ -512,12 +917,17 @@ fn scan_executable_games_blocking(
                         .map(|rel| rel.to_string_lossy().to_string())
                 })
                 .collect();
-            sort_executables(&mut executables, &name);
+            sort_executables(&mut executables, &name, rules);
+            executables.dedup();
 
+            let matched_game_id = existing_paths.matched_game_id(&game_dir);
             Some(ScanResult {
                 name,
                 path: game_dir.to_string_lossy().to_string(),
                 executables,
+                already_added: matched_game_id.is_some(),
+                matched_game_id,
+                engine: detect_engine(&game_dir),
             })
         })
         .collect();
@@ -529,12 +939,19 @@ fn scan_executable_games_blocking(
 #[cfg(test)]
 mod tests {
     use super::{
-        ImportPathIndex, scan_direct_child_directories, scan_executable_games_blocking,
-        sort_executables, trim_dirname_to_search_name,
+        ImportPathIndex, SCAN_CANCELLED, ScanControl, ScannedGameInput, build_scanned_insert_data,
+        is_excluded_exe, replace_path_prefix, scan_direct_child_directories,
+        scan_executable_games_blocking, sort_executables, trim_dirname_to_search_name,
     };
+    use crate::entity::user::ScanExeRules;
+    use crate::game::engine::GameEngine;
+    use crate::metadata::{MetadataMatch, MetadataSource};
+    use crate::utils::test_util::temp_dir;
+    use parking_lot::Mutex;
     use std::fs;
     use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
     fn test_path(parts: &[&str]) -> PathBuf {
         parts.iter().collect()
@@ -566,7 +983,7 @@ mod tests {
             "游戏.exe".to_string(),
         ];
 
-        sort_executables(&mut executables, "Game");
+        sort_executables(&mut executables, "Game", &ScanExeRules::default());
 
         assert_eq!(
             executables,
@@ -574,15 +991,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_exe_rules_reorder_and_exclude() {
+        let rules = ScanExeRules {
+            priority_keywords: vec!["patch".to_string()],
+            demote_keywords: vec!["config".to_string()],
+            exclude_keywords: vec!["tool".to_string()],
+        };
+        let mut executables = vec![
+            "Config.exe".to_string(),
+            "Game.exe".to_string(),
+            "Game_patch.exe".to_string(),
+        ];
+
+        sort_executables(&mut executables, "Game", &rules);
+
+        assert_eq!(executables, ["Game_patch.exe", "Game.exe", "Config.exe"]);
+        assert!(is_excluded_exe(&PathBuf::from("SaveTool.exe"), &rules));
+        assert!(!is_excluded_exe(&PathBuf::from("unins000.exe"), &rules));
+    }
+
     #[test]
     fn import_index_matches_game_directory_and_descendants() {
         let game_dir = test_path(&["scan-root", "Games", "A"]);
         let sibling = test_path(&["scan-root", "Games", "AB"]);
         let mut index = ImportPathIndex::default();
-        index.insert(&game_dir);
-        index.insert(&game_dir);
+        index.insert(&game_dir, 1);
+        index.insert(&game_dir, 2);
 
         assert_eq!(index.len(), 1);
+        assert_eq!(index.matched_game_id(&game_dir), Some(1));
+        assert_eq!(index.matched_game_id(&game_dir.join("Sub")), None);
         assert!(index.is_imported_or_descendant(&game_dir));
         assert!(index.is_imported_or_descendant(&game_dir.join("Sub")));
         assert!(!index.is_imported_or_descendant(game_dir.parent().unwrap()));
@@ -591,24 +1030,19 @@ mod tests {
 
     #[test]
     fn executable_scan_does_not_find_games_below_imported_scan_root() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("系统时间应晚于 Unix epoch")
-            .as_nanos();
-        let game_dir = std::env::temp_dir().join(format!(
-            "reina-imported-scan-root-{}-{unique}",
-            std::process::id()
-        ));
+        let game_dir = temp_dir("imported-scan-root", "");
         let deep_dir = game_dir.join("Sub").join("Deep");
         fs::create_dir_all(&deep_dir).expect("应能创建深层测试目录");
         fs::write(deep_dir.join("nested.exe"), []).expect("应能创建深层启动程序");
 
         let mut existing_paths = ImportPathIndex::default();
-        existing_paths.insert(&game_dir);
+        existing_paths.insert(&game_dir, 1);
         let results = scan_executable_games_blocking(
             game_dir.to_string_lossy().into_owned(),
             existing_paths,
             5,
+            &ScanExeRules::default(),
+            &ScanControl::noop(),
         )
         .expect("扫描应成功");
 
@@ -618,14 +1052,7 @@ mod tests {
 
     #[test]
     fn first_level_scan_imports_direct_children_and_executables() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("系统时间应晚于 Unix epoch")
-            .as_nanos();
-        let root = std::env::temp_dir().join(format!(
-            "reina-first-level-scan-{}-{unique}",
-            std::process::id()
-        ));
+        let root = temp_dir("first-level-scan", "");
         let game_a = root.join("GameA");
         let game_b = root.join("GameB");
         let game_c = root.join("GameC");
@@ -636,18 +1063,141 @@ mod tests {
         fs::write(game_a.join("Deep").join("ignored.exe"), []).expect("应能创建深层启动程序");
 
         let mut existing_paths = ImportPathIndex::default();
-        existing_paths.insert(&game_b);
-        let results =
-            scan_direct_child_directories(root.to_string_lossy().into_owned(), existing_paths);
+        existing_paths.insert(&game_b, 7);
+        let results = scan_direct_child_directories(
+            root.to_string_lossy().into_owned(),
+            existing_paths,
+            &ScanExeRules::default(),
+            &ScanControl::noop(),
+        )
+        .expect("扫描应成功");
 
-        assert_eq!(results.len(), 2);
+        assert_eq!(results.len(), 3);
         assert_eq!(results[0].name, "GameA");
         assert_eq!(PathBuf::from(&results[0].path), game_a);
         assert_eq!(results[0].executables, ["GameA.exe"]);
-        assert_eq!(results[1].name, "GameC");
-        assert_eq!(PathBuf::from(&results[1].path), game_c);
-        assert!(results[1].executables.is_empty());
+        assert!(!results[0].already_added);
+        assert_eq!(results[1].name, "GameB");
+        assert!(results[1].already_added);
+        assert_eq!(results[1].matched_game_id, Some(7));
+        assert_eq!(results[2].name, "GameC");
+        assert_eq!(PathBuf::from(&results[2].path), game_c);
+        assert!(results[2].executables.is_empty());
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+
+    #[test]
+    fn executable_scan_reports_progress_and_honors_cancel() {
+        let root = temp_dir("scan-progress", "");
+        for name in ["GameA", "GameB"] {
+            fs::create_dir_all(root.join(name)).expect("应能创建测试目录");
+            fs::write(root.join(name).join("game.exe"), []).expect("应能创建启动程序");
+        }
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let recorded = reports.clone();
+        let control = ScanControl {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            on_progress: Box::new(move |processed, total| {
+                recorded.lock().push((processed, total));
+            }),
+        };
+        let results = scan_executable_games_blocking(
+            root.to_string_lossy().into_owned(),
+            ImportPathIndex::default(),
+            3,
+            &ScanExeRules::default(),
+            &control,
+        )
+        .expect("扫描应成功");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(reports.lock().last(), Some(&(2, 2)));
+
+        control.cancelled.store(true, Ordering::Relaxed);
+        let cancelled = scan_executable_games_blocking(
+            root.to_string_lossy().into_owned(),
+            ImportPathIndex::default(),
+            3,
+            &ScanExeRules::default(),
+            &control,
+        );
+        assert_eq!(cancelled.unwrap_err(), SCAN_CANCELLED);
 
         fs::remove_dir_all(root).expect("应能清理测试目录");
     }
+
+    #[test]
+    fn scanned_game_converts_to_insert_data() {
+        let game_dir = test_path(&["games", "Demo"]);
+        let item = ScannedGameInput {
+            name: "Demo".to_string(),
+            path: game_dir.to_string_lossy().to_string(),
+            executable: Some(
+                test_path(&["bin", "demo.exe"])
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            engine: Some(GameEngine::Kirikiri),
+        };
+
+        let custom = build_scanned_insert_data(&item, None);
+        assert_eq!(custom.id_type, "custom");
+        assert_eq!(
+            custom.localpath.as_deref(),
+            Some(game_dir.join("bin").to_string_lossy().as_ref())
+        );
+        assert_eq!(custom.executable.as_deref(), Some("demo.exe"));
+        assert_eq!(custom.engine.as_deref(), Some("kirikiri"));
+        assert_eq!(
+            custom.custom_data.and_then(|data| data.name).as_deref(),
+            Some("Demo")
+        );
+        assert!(custom.sources.is_empty());
+
+        let matched = MetadataMatch {
+            source: MetadataSource::Vndb,
+            external_id: "v17".to_string(),
+            data: serde_json::json!({ "name": "Demo" }),
+        };
+        let item = ScannedGameInput {
+            executable: None,
+            ..item
+        };
+        let matched = build_scanned_insert_data(&item, Some(&matched));
+        assert_eq!(matched.id_type, "vndb");
+        assert_eq!(
+            matched.localpath.as_deref(),
+            Some(game_dir.to_string_lossy().as_ref())
+        );
+        assert!(matched.executable.is_none());
+        assert!(matched.custom_data.is_none());
+        assert_eq!(matched.sources[0].external_id.as_deref(), Some("v17"));
+    }
+
+    #[test]
+    fn replace_path_prefix_matches_whole_components() {
+        let old_prefix = test_path(&["drive", "Galgame"]);
+        let new_prefix = test_path(&["other", "Galgame"]);
+        let game = test_path(&["drive", "Galgame", "Demo"]);
+
+        assert_eq!(
+            replace_path_prefix(&game.to_string_lossy(), &old_prefix, &new_prefix),
+            Some(
+                test_path(&["other", "Galgame", "Demo"])
+                    .to_string_lossy()
+                    .into_owned()
+            )
+        );
+        assert_eq!(
+            replace_path_prefix(&old_prefix.to_string_lossy(), &old_prefix, &new_prefix),
+            Some(new_prefix.to_string_lossy().into_owned())
+        );
+        let sibling = test_path(&["drive", "Galgame2", "Demo"]);
+        assert_eq!(
+            replace_path_prefix(&sibling.to_string_lossy(), &old_prefix, &new_prefix),
+            None
+        );
+    }
 }
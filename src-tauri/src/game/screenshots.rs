@@ -0,0 +1,209 @@
+//! 游戏截图管理
+//!
+//! 截图统一保存在数据目录的 `screenshots/game_{id}` 下，按拍摄时间命名为
+//! `YYYYMMDD_HHMMSS.{ext}`，同一秒内的多张截图追加 `_1`、`_2` 等序号。
+
+#[cfg(target_os = "windows")]
+pub mod hotkey;
+
+use crate::database::repository::games_repository::GamesRepository;
+use chrono::{DateTime, Local};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{State, command};
+
+/// 识别为截图的图片扩展名
+const SCREENSHOT_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "bmp", "gif"];
+
+/// 单张截图
+#[derive(Debug, Clone, Serialize)]
+pub struct Screenshot {
+    pub path: String,
+    pub file_name: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 拍摄时间（文件修改时间，Unix 秒）
+    pub taken_at: i64,
+}
+
+/// 截图导入结果
+#[derive(Debug, Default, Serialize)]
+pub struct ImportScreenshotsResult {
+    pub imported: Vec<Screenshot>,
+    pub errors: Vec<String>,
+}
+
+/// 游戏的截图目录
+pub fn screenshot_dir(game_id: i32) -> Result<PathBuf, String> {
+    Ok(reina_path::get_base_data_dir()?
+        .join("screenshots")
+        .join(format!("game_{}", game_id)))
+}
+
+fn screenshot_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .filter(|ext| SCREENSHOT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn modified_time(metadata: &fs::Metadata) -> SystemTime {
+    metadata.modified().unwrap_or_else(|_| SystemTime::now())
+}
+
+/// 按拍摄时间生成不与现有文件重名的目标路径
+fn unique_screenshot_path(dir: &Path, taken_at: DateTime<Local>, extension: &str) -> PathBuf {
+    let stem = taken_at.format("%Y%m%d_%H%M%S").to_string();
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut index = 1;
+    while path.exists() {
+        path = dir.join(format!("{stem}_{index}.{extension}"));
+        index += 1;
+    }
+    path
+}
+
+fn to_screenshot(path: PathBuf, metadata: &fs::Metadata) -> Screenshot {
+    let taken_at: DateTime<Local> = modified_time(metadata).into();
+    Screenshot {
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        taken_at: taken_at.timestamp(),
+    }
+}
+
+/// 列出目录下的截图，按拍摄时间从新到旧排列
+fn list_screenshots(dir: &Path) -> Result<Vec<Screenshot>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("读取截图目录失败: {}", e)),
+    };
+
+    let mut screenshots: Vec<Screenshot> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| screenshot_extension(&entry.path()).is_some())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+            Some(to_screenshot(entry.path(), &metadata))
+        })
+        .collect();
+    screenshots.sort_by(|a, b| {
+        b.taken_at
+            .cmp(&a.taken_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(screenshots)
+}
+
+/// 将截图数据以当前时间命名保存到游戏的截图目录
+pub fn save_screenshot(game_id: i32, bytes: &[u8], extension: &str) -> Result<Screenshot, String> {
+    let dir = screenshot_dir(game_id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建截图目录失败: {}", e))?;
+    let path = unique_screenshot_path(&dir, Local::now(), extension);
+    fs::write(&path, bytes).map_err(|e| format!("保存截图失败: {}", e))?;
+    let metadata = fs::metadata(&path).map_err(|e| format!("读取截图信息失败: {}", e))?;
+    Ok(to_screenshot(path, &metadata))
+}
+
+/// 复制单张图片到截图目录，以原文件的修改时间命名
+fn import_one(dir: &Path, source: &Path) -> Result<Screenshot, String> {
+    let extension = screenshot_extension(source).ok_or_else(|| "不支持的图片格式".to_string())?;
+    let metadata = fs::metadata(source).map_err(|e| format!("读取文件信息失败: {}", e))?;
+    if !metadata.is_file() {
+        return Err("不是文件".to_string());
+    }
+
+    let target = unique_screenshot_path(dir, modified_time(&metadata).into(), &extension);
+    fs::copy(source, &target).map_err(|e| format!("复制截图失败: {}", e))?;
+    let metadata = fs::metadata(&target).map_err(|e| format!("读取截图信息失败: {}", e))?;
+    Ok(to_screenshot(target, &metadata))
+}
+
+/// 获取游戏的截图列表，按拍摄时间从新到旧排列
+#[command]
+pub async fn get_screenshots(game_id: i32) -> Result<Vec<Screenshot>, String> {
+    list_screenshots(&screenshot_dir(game_id)?)
+}
+
+/// 将图片复制到游戏的截图目录并按拍摄时间重命名
+///
+/// 单张失败不影响其他图片，失败原因记录在 `errors` 中。
+#[command]
+pub async fn import_screenshots(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+    paths: Vec<String>,
+) -> Result<ImportScreenshotsResult, String> {
+    GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let dir = screenshot_dir(game_id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建截图目录失败: {}", e))?;
+
+    let mut result = ImportScreenshotsResult::default();
+    for path in paths {
+        match import_one(&dir, Path::new(&path)) {
+            Ok(screenshot) => result.imported.push(screenshot),
+            Err(e) => result.errors.push(format!("{}: {}", path, e)),
+        }
+    }
+    log::info!(
+        "导入截图完成 game_id={} imported={} failed={}",
+        game_id,
+        result.imported.len(),
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// 删除游戏截图目录中的指定截图
+#[command]
+pub async fn delete_screenshot(game_id: i32, file_name: String) -> Result<(), String> {
+    let is_plain_name = Path::new(&file_name)
+        .file_name()
+        .is_some_and(|name| name == file_name.as_str());
+    if !is_plain_name || screenshot_extension(Path::new(&file_name)).is_none() {
+        return Err(format!("无效的截图文件名: {}", file_name));
+    }
+
+    let path = screenshot_dir(game_id)?.join(&file_name);
+    fs::remove_file(&path).map_err(|e| format!("删除截图失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn names_screenshots_by_time_without_overwriting() {
+        let dir = temp_dir("screenshots", "");
+        fs::create_dir_all(&dir).expect("应能创建测试目录");
+        let taken_at = Local.with_ymd_and_hms(2026, 8, 1, 21, 30, 5).unwrap();
+
+        let first = unique_screenshot_path(&dir, taken_at, "png");
+        assert_eq!(first.file_name().unwrap(), "20260801_213005.png");
+        fs::write(&first, [0_u8; 4]).unwrap();
+        let second = unique_screenshot_path(&dir, taken_at, "png");
+        assert_eq!(second.file_name().unwrap(), "20260801_213005_1.png");
+        fs::write(&second, [0_u8; 8]).unwrap();
+        fs::write(dir.join("notes.txt"), "x").unwrap();
+
+        let screenshots = list_screenshots(&dir).unwrap();
+        assert_eq!(screenshots.len(), 2);
+        assert!(screenshots.iter().any(|shot| shot.size == 8));
+        assert!(list_screenshots(&dir.join("missing")).unwrap().is_empty());
+
+        fs::remove_dir_all(dir).expect("应能清理测试目录");
+    }
+}
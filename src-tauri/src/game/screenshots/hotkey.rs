@@ -0,0 +1,313 @@
+//! 游玩期间的全局截图热键（Windows）
+//!
+//! 后台线程只在有游戏被监控时通过 `RegisterHotKey` 注册 F12，最后一个监控会话结束后注销，
+//! 平时不占用其他程序的 F12。按下时若前台窗口属于受监控的游戏，
+//! 截取其客户区保存到该游戏的截图目录，并发送 `screenshot-captured` 事件。
+//!
+//! 优先使用 `PrintWindow`（PW_RENDERFULLCONTENT）截取窗口内容，不受遮挡影响；
+//! 失败或得到全黑画面（部分独占渲染的游戏）时退回从屏幕 `BitBlt`。
+
+use super::save_screenshot;
+use crate::game::monitor::{foreground_game_window, monitored_game_ids};
+use image::{ImageFormat, RgbaImage};
+use serde_json::json;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, ClientToScreen, CreateCompatibleBitmap,
+    CreateCompatibleDC, DIB_RGB_COLORS, DeleteDC, DeleteObject, GetDC, GetDIBits, HBITMAP, HDC,
+    ReleaseDC, SRCCOPY, SelectObject,
+};
+use windows::Win32::Storage::Xps::{PRINT_WINDOW_FLAGS, PW_CLIENTONLY, PrintWindow};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    MOD_NOREPEAT, RegisterHotKey, UnregisterHotKey, VK_F12,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClientRect, GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, PostThreadMessageW, WM_APP,
+    WM_HOTKEY,
+};
+
+/// 热键注册 ID，仅在本线程内唯一即可
+const SCREENSHOT_HOTKEY_ID: i32 = 1;
+
+/// PrintWindow 截取 DirectComposition 内容的标志（Windows 8.1+）
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+/// 通知热键线程按监控状态重新注册或注销热键的消息
+const WM_REFRESH_HOTKEY: u32 = WM_APP + 1;
+
+/// 热键线程的线程 ID，线程尚未启动时为 0
+static HOTKEY_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+
+/// 启动截图热键线程，应用启动时调用一次
+///
+/// 热键只在有游戏被监控时注册，被其他程序占用时只记录日志，不影响游戏监控。
+pub fn spawn_screenshot_hotkey<R: Runtime>(app_handle: AppHandle<R>) {
+    let spawned = std::thread::Builder::new()
+        .name("screenshot-hotkey".to_string())
+        .spawn(move || {
+            // 先创建消息队列再公开线程 ID，之后投递的刷新消息不会丢失
+            let mut msg = MSG::default();
+            let _ = unsafe { PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE) };
+            HOTKEY_THREAD_ID.store(unsafe { GetCurrentThreadId() }, Ordering::Release);
+
+            let mut registered = false;
+            sync_hotkey_registration(&mut registered);
+
+            // 热键消息投递到注册线程的消息队列，GetMessageW 返回 0 或 -1 时退出
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {
+                match msg.message {
+                    WM_REFRESH_HOTKEY => sync_hotkey_registration(&mut registered),
+                    WM_HOTKEY if msg.wParam.0 == SCREENSHOT_HOTKEY_ID as usize => {
+                        capture_foreground_game(&app_handle);
+                    }
+                    _ => {}
+                }
+            }
+
+            HOTKEY_THREAD_ID.store(0, Ordering::Release);
+            if registered {
+                let _ = unsafe { UnregisterHotKey(None, SCREENSHOT_HOTKEY_ID) };
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("启动截图热键线程失败: {}", e);
+    }
+}
+
+/// 监控中的游戏变化后调用，由热键线程决定注册或注销热键
+pub(crate) fn refresh_screenshot_hotkey() {
+    let thread_id = HOTKEY_THREAD_ID.load(Ordering::Acquire);
+    if thread_id == 0 {
+        return;
+    }
+    if let Err(e) =
+        unsafe { PostThreadMessageW(thread_id, WM_REFRESH_HOTKEY, WPARAM(0), LPARAM(0)) }
+    {
+        log::warn!("通知截图热键线程失败: {}", e);
+    }
+}
+
+/// 有游戏被监控时注册热键，全部结束后注销；只能在热键线程中调用
+fn sync_hotkey_registration(registered: &mut bool) {
+    let wanted = !monitored_game_ids().is_empty();
+    if wanted == *registered {
+        return;
+    }
+    if wanted {
+        match unsafe { RegisterHotKey(None, SCREENSHOT_HOTKEY_ID, MOD_NOREPEAT, VK_F12.0 as u32) } {
+            Ok(()) => {
+                *registered = true;
+                log::info!("截图热键 F12 已注册");
+            }
+            Err(e) => log::warn!("注册截图热键 F12 失败，可能已被其他程序占用: {}", e),
+        }
+    } else {
+        let _ = unsafe { UnregisterHotKey(None, SCREENSHOT_HOTKEY_ID) };
+        *registered = false;
+        log::info!("没有监控中的游戏，已注销截图热键 F12");
+    }
+}
+
+/// 截取前台游戏窗口并保存，前台不是受监控的游戏时忽略本次按键
+fn capture_foreground_game<R: Runtime>(app_handle: &AppHandle<R>) {
+    let Some((game_id, hwnd)) = foreground_game_window() else {
+        log::debug!("前台窗口不属于监控中的游戏，忽略截图热键");
+        return;
+    };
+
+    let saved = i32::try_from(game_id)
+        .map_err(|_| "游戏 ID 超出范围".to_string())
+        .and_then(|db_game_id| {
+            let png = capture_window_png(hwnd)?;
+            save_screenshot(db_game_id, &png, "png")
+        });
+    match saved {
+        Ok(screenshot) => {
+            log::info!("已保存游戏截图 game_id={}: {}", game_id, screenshot.path);
+            if let Err(e) = app_handle.emit(
+                "screenshot-captured",
+                json!({ "gameId": game_id, "screenshot": screenshot }),
+            ) {
+                log::warn!("无法发送 screenshot-captured 事件: {}", e);
+            }
+        }
+        Err(e) => log::warn!("游戏截图失败 game_id={}: {}", game_id, e),
+    }
+}
+
+/// 截取窗口客户区并编码为 PNG
+fn capture_window_png(hwnd: HWND) -> Result<Vec<u8>, String> {
+    let mut rect = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut rect) }.map_err(|e| format!("获取窗口尺寸失败: {}", e))?;
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        return Err("窗口已最小化或尺寸为空".to_string());
+    }
+
+    let mut pixels = capture_with_print_window(hwnd, width, height)
+        .filter(|pixels| !is_blank(pixels))
+        .map_or_else(|| capture_with_bitblt(hwnd, width, height), Ok)?;
+    bgra_to_rgba(&mut pixels);
+
+    let image = RgbaImage::from_raw(width as u32, height as u32, pixels)
+        .ok_or_else(|| "截图数据长度与尺寸不符".to_string())?;
+    let mut output = Cursor::new(Vec::new());
+    image
+        .write_to(&mut output, ImageFormat::Png)
+        .map_err(|e| format!("编码截图失败: {}", e))?;
+    Ok(output.into_inner())
+}
+
+/// 通过 PrintWindow 截取，失败时返回 None
+fn capture_with_print_window(hwnd: HWND, width: i32, height: i32) -> Option<Vec<u8>> {
+    let screen_dc = unsafe { GetDC(None) };
+    if screen_dc.is_invalid() {
+        return None;
+    }
+    let result = with_memory_bitmap(screen_dc, width, height, |memory_dc| {
+        let printed = unsafe { PrintWindow(hwnd, memory_dc, PW_CLIENTONLY | PW_RENDERFULLCONTENT) };
+        if !printed.as_bool() {
+            return Err("PrintWindow 调用失败".to_string());
+        }
+        Ok(())
+    });
+    unsafe { ReleaseDC(None, screen_dc) };
+    match result {
+        Ok(pixels) => Some(pixels),
+        Err(e) => {
+            log::debug!("PrintWindow 截图失败，改用屏幕截取: {}", e);
+            None
+        }
+    }
+}
+
+/// 从屏幕截取窗口客户区所在的区域，要求窗口处于前台且未被遮挡
+fn capture_with_bitblt(hwnd: HWND, width: i32, height: i32) -> Result<Vec<u8>, String> {
+    let mut origin = POINT::default();
+    if !unsafe { ClientToScreen(hwnd, &mut origin) }.as_bool() {
+        return Err("获取窗口位置失败".to_string());
+    }
+    let screen_dc = unsafe { GetDC(None) };
+    if screen_dc.is_invalid() {
+        return Err("获取屏幕设备上下文失败".to_string());
+    }
+    let result = with_memory_bitmap(screen_dc, width, height, |memory_dc| {
+        unsafe {
+            BitBlt(
+                memory_dc,
+                0,
+                0,
+                width,
+                height,
+                Some(screen_dc),
+                origin.x,
+                origin.y,
+                SRCCOPY,
+            )
+        }
+        .map_err(|e| format!("BitBlt 调用失败: {}", e))
+    });
+    unsafe { ReleaseDC(None, screen_dc) };
+    result
+}
+
+/// 创建与屏幕兼容的内存位图，选入内存 DC 供回调绘制后读取像素
+fn with_memory_bitmap(
+    screen_dc: HDC,
+    width: i32,
+    height: i32,
+    draw: impl FnOnce(HDC) -> Result<(), String>,
+) -> Result<Vec<u8>, String> {
+    unsafe {
+        let memory_dc = CreateCompatibleDC(Some(screen_dc));
+        if memory_dc.is_invalid() {
+            return Err("创建内存设备上下文失败".to_string());
+        }
+        let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+        if bitmap.is_invalid() {
+            let _ = DeleteDC(memory_dc);
+            return Err("创建截图位图失败".to_string());
+        }
+
+        let previous = SelectObject(memory_dc, bitmap.into());
+        let drawn = draw(memory_dc);
+        // GetDIBits 要求位图未被选入任何 DC，先换回原对象再读取
+        SelectObject(memory_dc, previous);
+        let result = drawn.and_then(|()| read_bitmap_pixels(memory_dc, bitmap, width, height));
+        let _ = DeleteObject(bitmap.into());
+        let _ = DeleteDC(memory_dc);
+        result
+    }
+}
+
+/// 以自上而下的 32 位 BGRA 格式读取位图像素
+fn read_bitmap_pixels(
+    memory_dc: HDC,
+    bitmap: HBITMAP,
+    width: i32,
+    height: i32,
+) -> Result<Vec<u8>, String> {
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            // 负高度表示自上而下的行顺序
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0_u8; width as usize * height as usize * 4];
+    let lines = unsafe {
+        GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr().cast()),
+            &mut info,
+            DIB_RGB_COLORS,
+        )
+    };
+    if lines != height {
+        return Err("读取截图像素失败".to_string());
+    }
+    Ok(pixels)
+}
+
+/// 画面是否全黑（忽略 Alpha 通道）
+fn is_blank(bgra: &[u8]) -> bool {
+    bgra.chunks_exact(4)
+        .all(|pixel| pixel[..3].iter().all(|channel| *channel == 0))
+}
+
+/// BGRA 转 RGBA，GDI 位图的 Alpha 通道无意义，统一设为不透明
+fn bgra_to_rgba(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = u8::MAX;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_gdi_pixels() {
+        let mut pixels = vec![0, 0, 0, 0, 10, 20, 30, 0];
+        assert!(!is_blank(&pixels));
+        assert!(is_blank(&pixels[..4]));
+
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, [0, 0, 0, 255, 30, 20, 10, 255]);
+    }
+}
@@ -0,0 +1,142 @@
+//! 游戏目录占用空间统计
+//!
+//! 统计结果缓存在 games.folder_size，列表可按占用空间排序。
+//! 全库统计作为后台任务逐个计算，通过 `game-size-progress` 事件上报进度，
+//! 完成或取消后发送 `game-size-finished` 事件。
+
+use crate::database::repository::games_repository::GamesRepository;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Runtime, State, command};
+use walkdir::WalkDir;
+
+/// 递归统计目录下所有文件的大小（字节），不跟随符号链接，无法读取的条目跳过
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// 在阻塞线程池中统计目录大小，目录不存在时返回 None
+async fn measure_dir(path: PathBuf) -> Result<Option<u64>, String> {
+    tokio::task::spawn_blocking(move || path.is_dir().then(|| dir_size(&path)))
+        .await
+        .map_err(|e| format!("统计占用空间任务异常: {}", e))
+}
+
+/// 统计单个游戏目录的占用空间并写入缓存
+///
+/// # Returns
+/// 占用空间（字节）
+#[command]
+pub async fn calc_game_size(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<u64, String> {
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let localpath = game
+        .localpath
+        .ok_or_else(|| "游戏未设置本地目录".to_string())?;
+
+    let size = measure_dir(PathBuf::from(&localpath))
+        .await?
+        .ok_or_else(|| format!("游戏目录不存在: {}", localpath))?;
+    GamesRepository::set_folder_size(&db, game_id, Some(size as i64))
+        .await
+        .map_err(|e| format!("保存占用空间失败: {}", e))?;
+    Ok(size)
+}
+
+/// 在后台统计全部本地游戏的占用空间
+///
+/// 立即返回待统计的游戏数量；目录不存在的游戏清空缓存并计入失败数。
+#[command]
+pub async fn calc_all_game_sizes<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+) -> Result<usize, String> {
+    let directories = GamesRepository::get_all_game_directories(&db)
+        .await
+        .map_err(|e| format!("查询游戏目录失败: {}", e))?;
+    let total = directories.len();
+    let db = db.inner().clone();
+
+    let events = app_handle.clone();
+    tasks.spawn(&app_handle, TaskKind::GameSize, move |task| async move {
+        let mut failed = 0_usize;
+        for (index, (game_id, localpath)) in directories.into_iter().enumerate() {
+            if task.is_cancelled() {
+                log::info!("全库占用空间统计已取消 processed={} total={}", index, total);
+                break;
+            }
+            let size = match measure_dir(PathBuf::from(&localpath)).await {
+                Ok(size) => size,
+                Err(e) => {
+                    log::warn!("统计游戏占用空间失败 game_id={}: {}", game_id, e);
+                    None
+                }
+            };
+            if size.is_none() {
+                failed += 1;
+            }
+            if let Err(e) =
+                GamesRepository::set_folder_size(&db, game_id, size.map(|size| size as i64)).await
+            {
+                log::warn!("保存游戏占用空间失败 game_id={}: {}", game_id, e);
+            }
+            task.progress((index + 1) as u64, total as u64);
+            if let Err(e) = events.emit(
+                "game-size-progress",
+                json!({
+                    "processed": index + 1,
+                    "total": total,
+                    "gameId": game_id,
+                    "size": size,
+                }),
+            ) {
+                log::warn!("无法发送 game-size-progress 事件: {}", e);
+            }
+        }
+
+        log::info!("全库占用空间统计完成 total={} failed={}", total, failed);
+        if let Err(e) = events.emit(
+            "game-size-finished",
+            json!({ "total": total, "failed": failed, "cancelled": task.is_cancelled() }),
+        ) {
+            log::warn!("无法发送 game-size-finished 事件: {}", e);
+        }
+        Ok(())
+    })?;
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn dir_size_sums_nested_files() {
+        let dir = temp_dir("size", "");
+        fs::create_dir_all(dir.join("data")).expect("应能创建测试目录");
+        fs::write(dir.join("game.exe"), [0_u8; 10]).unwrap();
+        fs::write(dir.join("data").join("data.xp3"), [0_u8; 32]).unwrap();
+
+        assert_eq!(dir_size(&dir), 42);
+
+        fs::remove_dir_all(dir).expect("应能清理测试目录");
+    }
+}
@@ -0,0 +1,435 @@
+//! 导出为 Steam 非 Steam 游戏快捷方式
+//!
+//! 向每个登录过的 Steam 账户的 `userdata/{id}/config/shortcuts.vdf` 写入快捷方式，
+//! 目标是本程序并附带 `--launch {game_id}`，经由本程序启动以保留时长统计；
+//! 游戏封面写入 `config/grid/{appid}p.{ext}` 作为库中的竖版封面。
+//!
+//! Steam 运行时会在退出前覆盖 shortcuts.vdf，因此要求先退出 Steam。
+
+pub mod vdf;
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::cover::censor::{censor_cover, should_censor_cover};
+use crate::game::desktop_shortcut::load_cover_bytes;
+use image::ImageFormat;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{State, command};
+use vdf::{VdfMap, VdfValue};
+
+/// 单个待导出的快捷方式
+struct SteamShortcut {
+    game_id: i32,
+    app_id: u32,
+    name: String,
+    launch_options: String,
+    icon: String,
+    /// 竖版封面：(扩展名, 图片数据)
+    grid: Option<(&'static str, Vec<u8>)>,
+}
+
+/// 导出结果
+#[derive(Debug, Default, Serialize)]
+pub struct SteamExportResult {
+    /// 写入的 Steam 账户数
+    pub users: usize,
+    /// 导出的游戏数
+    pub exported: usize,
+    pub errors: Vec<String>,
+}
+
+/// CRC-32（IEEE），Steam 用它计算非 Steam 游戏的 appid
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 非 Steam 快捷方式的 appid：`crc32(Exe + AppName) | 0x80000000`
+fn shortcut_app_id(exe: &str, name: &str) -> u32 {
+    crc32(format!("{}{}", exe, name).as_bytes()) | 0x8000_0000
+}
+
+#[cfg(target_os = "windows")]
+mod registry {
+    use windows::Win32::System::Registry::{
+        HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RRF_RT_REG_SZ, RegGetValueW,
+    };
+    use windows::core::HSTRING;
+
+    pub fn read_string(sub_key: &str, name: &str) -> Option<String> {
+        let mut buffer = [0u16; 1024];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(sub_key),
+                &HSTRING::from(name),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .ok()?;
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    pub fn read_dword(sub_key: &str, name: &str) -> Option<u32> {
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(sub_key),
+                &HSTRING::from(name),
+                RRF_RT_REG_DWORD,
+                None,
+                Some((&mut value as *mut u32).cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .ok()?;
+        Some(value)
+    }
+}
+
+/// Steam 安装目录
+#[cfg(target_os = "windows")]
+fn find_steam_root() -> Option<PathBuf> {
+    registry::read_string(r"Software\Valve\Steam", "SteamPath")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain([PathBuf::from(r"C:\Program Files (x86)\Steam")])
+        .find(|path| path.join("userdata").is_dir())
+}
+
+/// Steam 安装目录（含 Flatpak 版）
+#[cfg(target_os = "linux")]
+fn find_steam_root() -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    [
+        ".steam/steam",
+        ".local/share/Steam",
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ]
+    .into_iter()
+    .map(|relative| home.join(relative))
+    .find(|path| path.join("userdata").is_dir())
+}
+
+#[cfg(target_os = "windows")]
+fn is_steam_running() -> bool {
+    registry::read_dword(r"Software\Valve\Steam\ActiveProcess", "pid")
+        .is_some_and(|pid| pid != 0 && crate::game::monitor::is_process_running(pid))
+}
+
+#[cfg(target_os = "linux")]
+fn is_steam_running() -> bool {
+    let Some(home) = std::env::var_os("HOME") else {
+        return false;
+    };
+    fs::read_to_string(Path::new(&home).join(".steam/steam.pid"))
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+        .is_some_and(|pid| Path::new("/proc").join(pid.to_string()).exists())
+}
+
+/// 登录过的 Steam 账户的 config 目录
+fn steam_user_config_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root.join("userdata")) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name != "0" && !name.is_empty() && name.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|entry| entry.path().join("config"))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// 设置对象中的键值，已存在时原位替换
+fn set_value(map: &mut VdfMap, key: &str, value: VdfValue) {
+    match map
+        .iter_mut()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+    {
+        Some((_, existing)) => *existing = value,
+        None => map.push((key.to_string(), value)),
+    }
+}
+
+/// 把快捷方式写入 shortcuts 列表：已导出过的游戏（同一程序与启动参数）更新原条目
+fn upsert_shortcut(entries: &mut VdfMap, exe: &str, start_dir: &str, shortcut: &SteamShortcut) {
+    let existing = entries.iter_mut().find_map(|(_, value)| match value {
+        VdfValue::Map(entry)
+            if vdf::get(entry, "Exe").and_then(VdfValue::as_str) == Some(exe)
+                && vdf::get(entry, "LaunchOptions").and_then(VdfValue::as_str)
+                    == Some(shortcut.launch_options.as_str()) =>
+        {
+            Some(entry)
+        }
+        _ => None,
+    });
+
+    let fields = [
+        ("appid", VdfValue::Int(shortcut.app_id as i32)),
+        ("AppName", VdfValue::String(shortcut.name.clone())),
+        ("Exe", VdfValue::String(exe.to_string())),
+        ("StartDir", VdfValue::String(start_dir.to_string())),
+        ("icon", VdfValue::String(shortcut.icon.clone())),
+        (
+            "LaunchOptions",
+            VdfValue::String(shortcut.launch_options.clone()),
+        ),
+    ];
+    match existing {
+        Some(entry) => {
+            for (key, value) in fields {
+                set_value(entry, key, value);
+            }
+        }
+        None => {
+            let mut entry: VdfMap = fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect();
+            entry.extend([
+                ("ShortcutPath".to_string(), VdfValue::String(String::new())),
+                ("IsHidden".to_string(), VdfValue::Int(0)),
+                ("AllowDesktopConfig".to_string(), VdfValue::Int(1)),
+                ("AllowOverlay".to_string(), VdfValue::Int(1)),
+                ("OpenVR".to_string(), VdfValue::Int(0)),
+                ("LastPlayTime".to_string(), VdfValue::Int(0)),
+                ("tags".to_string(), VdfValue::Map(Vec::new())),
+            ]);
+            let index = entries
+                .iter()
+                .filter_map(|(key, _)| key.parse::<usize>().ok())
+                .max()
+                .map_or(0, |max| max + 1);
+            entries.push((index.to_string(), VdfValue::Map(entry)));
+        }
+    }
+}
+
+/// 写入单个账户的 shortcuts.vdf 与封面，原文件备份为 shortcuts.vdf.bak
+fn write_user_shortcuts(
+    config_dir: &Path,
+    exe: &str,
+    start_dir: &str,
+    shortcuts: &[SteamShortcut],
+) -> Result<(), String> {
+    let vdf_path = config_dir.join("shortcuts.vdf");
+    let mut root = match fs::read(&vdf_path) {
+        Ok(bytes) => {
+            let root = vdf::parse(&bytes)?;
+            fs::write(config_dir.join("shortcuts.vdf.bak"), &bytes)
+                .map_err(|e| format!("备份 shortcuts.vdf 失败: {}", e))?;
+            root
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => VdfMap::new(),
+        Err(e) => return Err(format!("读取 shortcuts.vdf 失败: {}", e)),
+    };
+
+    if vdf::get(&root, "shortcuts")
+        .and_then(VdfValue::as_map)
+        .is_none()
+    {
+        set_value(&mut root, "shortcuts", VdfValue::Map(Vec::new()));
+    }
+    if let Some((_, VdfValue::Map(entries))) = root
+        .iter_mut()
+        .find(|(key, _)| key.eq_ignore_ascii_case("shortcuts"))
+    {
+        for shortcut in shortcuts {
+            upsert_shortcut(entries, exe, start_dir, shortcut);
+        }
+    }
+    fs::write(&vdf_path, vdf::serialize(&root))
+        .map_err(|e| format!("写入 shortcuts.vdf 失败: {}", e))?;
+
+    let grid_dir = config_dir.join("grid");
+    for shortcut in shortcuts {
+        let Some((extension, bytes)) = &shortcut.grid else {
+            continue;
+        };
+        fs::create_dir_all(&grid_dir).map_err(|e| format!("创建 grid 目录失败: {}", e))?;
+        for stale in ["png", "jpg"].into_iter().filter(|ext| ext != extension) {
+            let _ = fs::remove_file(grid_dir.join(format!("{}p.{}", shortcut.app_id, stale)));
+        }
+        fs::write(
+            grid_dir.join(format!("{}p.{}", shortcut.app_id, extension)),
+            bytes,
+        )
+        .map_err(|e| format!("写入游戏 {} 的封面失败: {}", shortcut.game_id, e))?;
+    }
+    Ok(())
+}
+
+/// 读取封面作为 Steam 竖版封面，只保留 Steam 支持的 PNG / JPEG
+async fn grid_image(
+    db: &DatabaseConnection,
+    game: &FullGameData,
+) -> Option<(&'static str, Vec<u8>)> {
+    let mut bytes = load_cover_bytes(game).await?;
+    if should_censor_cover(db, game.id as u32).await {
+        bytes = censor_cover(&bytes);
+    }
+    match image::guess_format(&bytes).ok()? {
+        ImageFormat::Png => Some(("png", bytes)),
+        ImageFormat::Jpeg => Some(("jpg", bytes)),
+        _ => None,
+    }
+}
+
+/// 把游戏导出为 Steam 非 Steam 游戏快捷方式
+///
+/// 写入所有登录过的 Steam 账户；已导出过的游戏更新原条目，不会重复添加。
+#[command]
+pub async fn export_to_steam(
+    db: State<'_, DatabaseConnection>,
+    game_ids: Vec<i32>,
+) -> Result<SteamExportResult, String> {
+    let root = find_steam_root().ok_or_else(|| "未找到 Steam 安装目录".to_string())?;
+    if is_steam_running() {
+        return Err("Steam 正在运行，请先退出 Steam 再导出".to_string());
+    }
+    let config_dirs = steam_user_config_dirs(&root);
+    if config_dirs.is_empty() {
+        return Err("未找到登录过的 Steam 账户".to_string());
+    }
+
+    let exe_path = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let exe = format!("\"{}\"", exe_path.display());
+    let start_dir = exe_path
+        .parent()
+        .map(|dir| format!("\"{}\"", dir.display()))
+        .unwrap_or_default();
+
+    let games = GamesRepository::find_full_games_in_order(db.inner(), &game_ids)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?;
+    let mut shortcuts = Vec::with_capacity(games.len());
+    for game in &games {
+        let name = game
+            .display_name()
+            .unwrap_or_else(|| format!("游戏 {}", game.id));
+        let icon = game
+            .localpath
+            .as_deref()
+            .zip(game.executable.as_deref())
+            .map(|(dir, executable)| Path::new(dir).join(executable))
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        shortcuts.push(SteamShortcut {
+            game_id: game.id,
+            app_id: shortcut_app_id(&exe, &name),
+            launch_options: format!("--launch {}", game.id),
+            icon,
+            grid: grid_image(&db, game).await,
+            name,
+        });
+    }
+
+    let mut result = SteamExportResult::default();
+    for config_dir in config_dirs {
+        match write_user_shortcuts(&config_dir, &exe, &start_dir, &shortcuts) {
+            Ok(()) => result.users += 1,
+            Err(e) => result
+                .errors
+                .push(format!("{}: {}", config_dir.display(), e)),
+        }
+    }
+    if result.users > 0 {
+        result.exported = shortcuts.len();
+    }
+    log::info!(
+        "导出 Steam 快捷方式完成 games={} users={} failed={}",
+        result.exported,
+        result.users,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(game_id: i32, name: &str) -> SteamShortcut {
+        SteamShortcut {
+            game_id,
+            app_id: shortcut_app_id("\"/opt/reina\"", name),
+            name: name.to_string(),
+            launch_options: format!("--launch {}", game_id),
+            icon: String::new(),
+            grid: None,
+        }
+    }
+
+    #[test]
+    fn computes_app_id() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(shortcut_app_id("\"/opt/reina\"", "千恋＊万花"), 0xD469_5421);
+    }
+
+    #[test]
+    fn upserts_shortcuts() {
+        let mut entries: VdfMap = vec![(
+            "0".to_string(),
+            VdfValue::Map(vec![
+                ("AppName".to_string(), VdfValue::String("Other".to_string())),
+                (
+                    "Exe".to_string(),
+                    VdfValue::String("\"other.exe\"".to_string()),
+                ),
+            ]),
+        )];
+
+        upsert_shortcut(&mut entries, "\"/opt/reina\"", "", &shortcut(3, "旧名称"));
+        upsert_shortcut(&mut entries, "\"/opt/reina\"", "", &shortcut(3, "新名称"));
+        assert_eq!(entries.len(), 2);
+        let entry = entries[1].1.as_map().unwrap();
+        assert_eq!(entries[1].0, "1");
+        assert_eq!(
+            vdf::get(entry, "AppName").and_then(VdfValue::as_str),
+            Some("新名称")
+        );
+        assert_eq!(
+            vdf::get(entry, "LaunchOptions").and_then(VdfValue::as_str),
+            Some("--launch 3")
+        );
+
+        upsert_shortcut(
+            &mut entries,
+            "\"/opt/reina\"",
+            "",
+            &shortcut(4, "另一个游戏"),
+        );
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].0, "2");
+    }
+}
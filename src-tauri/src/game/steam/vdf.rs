@@ -0,0 +1,174 @@
+//! Steam 二进制 VDF 读写
+//!
+//! `shortcuts.vdf` 使用二进制 KeyValues 格式：每个条目以类型字节开头，
+//! 随后是以 0 结尾的键名和值；嵌套对象以 0x08 结束。只支持快捷方式文件用到的
+//! 对象、字符串和 32 位整数三种类型。
+
+/// 键值对象，保持原有顺序
+pub type VdfMap = Vec<(String, VdfValue)>;
+
+const TYPE_MAP: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT: u8 = 0x02;
+const TYPE_END: u8 = 0x08;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfValue {
+    Map(VdfMap),
+    String(String),
+    Int(i32),
+}
+
+impl VdfValue {
+    pub fn as_map(&self) -> Option<&VdfMap> {
+        match self {
+            Self::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// 按键名查找（Steam 写入的键名大小写不统一，忽略大小写）
+pub fn get<'a>(map: &'a VdfMap, key: &str) -> Option<&'a VdfValue> {
+    map.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| "VDF 数据意外结束".to_string())?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn cstring(&mut self) -> Result<String, String> {
+        let rest = &self.bytes[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(|| "VDF 字符串缺少结束符".to_string())?;
+        self.pos += len + 1;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+
+    fn int(&mut self) -> Result<i32, String> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| "VDF 整数数据不完整".to_string())?;
+        self.pos += 4;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn map(&mut self) -> Result<VdfMap, String> {
+        let mut map = VdfMap::new();
+        loop {
+            let kind = self.byte()?;
+            if kind == TYPE_END {
+                return Ok(map);
+            }
+            let key = self.cstring()?;
+            let value = match kind {
+                TYPE_MAP => VdfValue::Map(self.map()?),
+                TYPE_STRING => VdfValue::String(self.cstring()?),
+                TYPE_INT => VdfValue::Int(self.int()?),
+                other => return Err(format!("不支持的 VDF 类型: {:#04x}", other)),
+            };
+            map.push((key, value));
+        }
+    }
+}
+
+/// 解析二进制 VDF，返回顶层对象
+pub fn parse(bytes: &[u8]) -> Result<VdfMap, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let map = reader.map()?;
+    if reader.pos != bytes.len() {
+        return Err("VDF 结尾存在多余数据".to_string());
+    }
+    Ok(map)
+}
+
+fn write_map(output: &mut Vec<u8>, map: &VdfMap) {
+    for (key, value) in map {
+        let kind = match value {
+            VdfValue::Map(_) => TYPE_MAP,
+            VdfValue::String(_) => TYPE_STRING,
+            VdfValue::Int(_) => TYPE_INT,
+        };
+        output.push(kind);
+        output.extend_from_slice(key.as_bytes());
+        output.push(0);
+        match value {
+            VdfValue::Map(children) => write_map(output, children),
+            VdfValue::String(text) => {
+                output.extend_from_slice(text.as_bytes());
+                output.push(0);
+            }
+            VdfValue::Int(number) => output.extend_from_slice(&number.to_le_bytes()),
+        }
+    }
+    output.push(TYPE_END);
+}
+
+/// 序列化为二进制 VDF
+pub fn serialize(map: &VdfMap) -> Vec<u8> {
+    let mut output = Vec::new();
+    write_map(&mut output, map);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_shortcuts() {
+        let shortcuts: VdfMap = vec![(
+            "shortcuts".to_string(),
+            VdfValue::Map(vec![(
+                "0".to_string(),
+                VdfValue::Map(vec![
+                    ("appid".to_string(), VdfValue::Int(-1_234_567)),
+                    (
+                        "AppName".to_string(),
+                        VdfValue::String("千恋＊万花".to_string()),
+                    ),
+                    ("tags".to_string(), VdfValue::Map(Vec::new())),
+                ]),
+            )]),
+        )];
+
+        let bytes = serialize(&shortcuts);
+        assert_eq!(bytes.last(), Some(&TYPE_END));
+        let parsed = parse(&bytes).unwrap();
+        assert_eq!(parsed, shortcuts);
+
+        let entry = get(&parsed, "Shortcuts")
+            .and_then(VdfValue::as_map)
+            .and_then(|entries| get(entries, "0"))
+            .and_then(VdfValue::as_map)
+            .unwrap();
+        assert_eq!(
+            get(entry, "appname").and_then(VdfValue::as_str),
+            Some("千恋＊万花")
+        );
+        assert!(parse(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
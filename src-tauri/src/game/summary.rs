@@ -0,0 +1,370 @@
+//! 全库与合集总览统计
+//!
+//! 一次性汇总首页仪表盘所需的游戏数量、游玩时长、通关比例与开发商/标签排行，
+//! 以及合集页头部展示的合集统计，避免前端多次调用再自行聚合。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::collections_repository::CollectionsRepository;
+use crate::database::repository::game_stats_repository::{
+    GameStatsRepository, PlaytimeGranularity,
+};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::game_statistics;
+use chrono::{Datelike, Local, NaiveDate};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{State, command};
+
+/// 排行默认返回的条目数
+pub(crate) const DEFAULT_TOP_N: usize = 10;
+/// 游玩状态：玩过
+const PLAY_STATUS_CLEARED: i32 = 2;
+
+/// 开发商或标签排行条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedName {
+    pub name: String,
+    /// 相关游戏数量
+    pub game_count: usize,
+    /// 相关游戏的总游玩时长（分钟）
+    pub playtime: i64,
+}
+
+/// 全库总览
+#[derive(Debug, Clone, Serialize)]
+pub struct LibrarySummary {
+    pub total_games: usize,
+    /// 设置了本地目录的游戏数量
+    pub local_games: usize,
+    /// 总游玩时长（分钟）
+    pub total_playtime: i64,
+    pub total_sessions: i64,
+    /// 状态为玩过的游戏数量
+    pub cleared_games: usize,
+    /// 玩过的游戏占全部游戏的比例（0-1）
+    pub cleared_ratio: f64,
+    /// 本月游玩时长（分钟）
+    pub month_playtime: i64,
+    /// 各游玩状态的游戏数量，键为 games.clear
+    pub play_status_counts: BTreeMap<i32, usize>,
+    /// 按游玩时长降序的开发商排行
+    pub top_developers: Vec<RankedName>,
+    /// 按游玩时长降序的标签排行
+    pub top_tags: Vec<RankedName>,
+    /// 设置了个人评分的游戏数量
+    pub rated_games: usize,
+    /// 个人评分平均分，没有评分时为空
+    pub average_rating: Option<f64>,
+    /// 个人评分分布，键为四舍五入后的分数（1-10）
+    pub rating_distribution: BTreeMap<u8, usize>,
+}
+
+/// 合集（含子合集）统计
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionStatistics {
+    pub total_games: usize,
+    /// 总游玩时长（分钟）
+    pub total_playtime: i64,
+    /// 状态为玩过的游戏数量
+    pub cleared_games: usize,
+    /// 玩过的游戏占合集内游戏的比例（0-1）
+    pub cleared_ratio: f64,
+    /// 已统计占用空间的游戏总大小（字节）
+    pub total_size: i64,
+    /// 已统计占用空间的游戏数量
+    pub sized_games: usize,
+}
+
+fn add_ranked(ranking: &mut HashMap<String, (usize, i64)>, name: &str, playtime: i64) {
+    let entry = ranking.entry(name.to_string()).or_default();
+    entry.0 += 1;
+    entry.1 += playtime;
+}
+
+fn top_ranked(ranking: HashMap<String, (usize, i64)>, top_n: usize) -> Vec<RankedName> {
+    let mut ranked: Vec<RankedName> = ranking
+        .into_iter()
+        .map(|(name, (game_count, playtime))| RankedName {
+            name,
+            game_count,
+            playtime,
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.playtime
+            .cmp(&a.playtime)
+            .then(b.game_count.cmp(&a.game_count))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranked.truncate(top_n);
+    ranked
+}
+
+/// 评分分布的分组：四舍五入到整数，范围 1-10
+fn rating_bucket(rating: f64) -> u8 {
+    rating.round().clamp(1.0, 10.0) as u8
+}
+
+/// `games` 不含回收站中的游戏，统计数据只计入其中的游戏
+fn build_summary(
+    games: &[FullGameData],
+    statistics: &[game_statistics::Model],
+    month_playtime: i64,
+    top_n: usize,
+) -> LibrarySummary {
+    let game_ids: HashSet<i32> = games.iter().map(|game| game.id).collect();
+    let statistics: Vec<&game_statistics::Model> = statistics
+        .iter()
+        .filter(|item| game_ids.contains(&item.game_id))
+        .collect();
+    let playtime_by_game: HashMap<i32, i64> = statistics
+        .iter()
+        .map(|item| (item.game_id, i64::from(item.total_time.unwrap_or(0))))
+        .collect();
+
+    let mut play_status_counts = BTreeMap::new();
+    let mut developers = HashMap::new();
+    let mut tags = HashMap::new();
+    let mut ratings = Vec::new();
+    let mut rating_distribution = BTreeMap::new();
+    for game in games {
+        if let Some(rating) = game.user_rating() {
+            ratings.push(rating);
+            *rating_distribution
+                .entry(rating_bucket(rating))
+                .or_insert(0) += 1;
+        }
+        if let Some(clear) = game.clear {
+            *play_status_counts.entry(clear).or_insert(0) += 1;
+        }
+        let playtime = playtime_by_game.get(&game.id).copied().unwrap_or(0);
+        for developer in game.developers() {
+            add_ranked(&mut developers, &developer, playtime);
+        }
+        for tag in game.tags() {
+            add_ranked(&mut tags, &tag, playtime);
+        }
+    }
+
+    let total_games = games.len();
+    let cleared_games = play_status_counts
+        .get(&PLAY_STATUS_CLEARED)
+        .copied()
+        .unwrap_or(0);
+    LibrarySummary {
+        total_games,
+        local_games: games.iter().filter(|game| game.localpath.is_some()).count(),
+        total_playtime: playtime_by_game.values().sum(),
+        total_sessions: statistics
+            .iter()
+            .map(|item| i64::from(item.session_count.unwrap_or(0)))
+            .sum(),
+        cleared_games,
+        cleared_ratio: if total_games == 0 {
+            0.0
+        } else {
+            cleared_games as f64 / total_games as f64
+        },
+        month_playtime,
+        play_status_counts,
+        top_developers: top_ranked(developers, top_n),
+        top_tags: top_ranked(tags, top_n),
+        rated_games: ratings.len(),
+        average_rating: (!ratings.is_empty())
+            .then(|| ratings.iter().sum::<f64>() / ratings.len() as f64),
+        rating_distribution,
+    }
+}
+
+fn build_collection_statistics(
+    games: &[FullGameData],
+    statistics: &[game_statistics::Model],
+) -> CollectionStatistics {
+    let playtime_by_game: HashMap<i32, i64> = statistics
+        .iter()
+        .map(|item| (item.game_id, i64::from(item.total_time.unwrap_or(0))))
+        .collect();
+
+    let total_games = games.len();
+    let cleared_games = games
+        .iter()
+        .filter(|game| game.clear == Some(PLAY_STATUS_CLEARED))
+        .count();
+    let sizes: Vec<i64> = games.iter().filter_map(|game| game.folder_size).collect();
+    CollectionStatistics {
+        total_games,
+        total_playtime: games
+            .iter()
+            .filter_map(|game| playtime_by_game.get(&game.id))
+            .sum(),
+        cleared_games,
+        cleared_ratio: if total_games == 0 {
+            0.0
+        } else {
+            cleared_games as f64 / total_games as f64
+        },
+        total_size: sizes.iter().sum(),
+        sized_games: sizes.len(),
+    }
+}
+
+/// 获取全库总览统计
+///
+/// # Arguments
+/// * `top_n` - 开发商与标签排行返回的条目数，默认 10
+#[command]
+pub async fn get_library_summary(
+    db: State<'_, DatabaseConnection>,
+    top_n: Option<usize>,
+) -> Result<LibrarySummary, String> {
+    library_summary(&db, top_n.unwrap_or(DEFAULT_TOP_N)).await
+}
+
+/// 统计全库总览，供命令与远程控制接口共用
+pub(crate) async fn library_summary(
+    db: &DatabaseConnection,
+    top_n: usize,
+) -> Result<LibrarySummary, String> {
+    let games =
+        GamesRepository::find_all(db, GameType::All, SortOption::Addtime, SortOrder::Asc, None)
+            .await
+            .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let statistics = GameStatsRepository::get_all_statistics(db)
+        .await
+        .map_err(|e| format!("读取游戏统计失败: {}", e))?;
+
+    let today = Local::now().date_naive();
+    let month_start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today);
+    let month_playtime = GameStatsRepository::get_playtime_by_range(
+        db,
+        None,
+        &month_start.format("%Y-%m-%d").to_string(),
+        &today.format("%Y-%m-%d").to_string(),
+        PlaytimeGranularity::Month,
+    )
+    .await
+    .map_err(|e| format!("统计本月游玩时长失败: {}", e))?
+    .iter()
+    .map(|bucket| bucket.playtime)
+    .sum();
+
+    Ok(build_summary(&games, &statistics, month_playtime, top_n))
+}
+
+/// 获取合集统计，包含其全部子合集中的游戏（同一游戏只计一次）
+///
+/// 占用空间取自已统计的 `folder_size`，未统计的游戏不计入。
+///
+/// # Arguments
+/// * `collection_id` - 合集ID
+#[command]
+pub async fn get_collection_statistics(
+    db: State<'_, DatabaseConnection>,
+    collection_id: i32,
+) -> Result<CollectionStatistics, String> {
+    let game_ids = CollectionsRepository::get_games_in_subtree(&db, collection_id)
+        .await
+        .map_err(|e| format!("获取合集中的游戏失败: {}", e))?;
+    let games = GamesRepository::find_full_games_in_order(db.inner(), &game_ids)
+        .await
+        .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let statistics = GameStatsRepository::get_all_statistics(&db)
+        .await
+        .map_err(|e| format!("读取游戏统计失败: {}", e))?;
+
+    Ok(build_collection_statistics(&games, &statistics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn game(id: i32, clear: i32, data: Value) -> FullGameData {
+        serde_json::from_value(json!({
+            "id": id,
+            "id_type": "bgm",
+            "clear": clear,
+            "custom_data": { "user_rating": id as f64 * 2.5 },
+            "sources": [{ "source": "bgm", "external_id": id.to_string(), "data": data }],
+        }))
+        .expect("测试游戏应能解析")
+    }
+
+    fn statistics(game_id: i32, total_time: i32) -> game_statistics::Model {
+        game_statistics::Model {
+            game_id,
+            total_time: Some(total_time),
+            session_count: Some(1),
+            last_played: None,
+            daily_stats: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_status_and_rankings() {
+        let games = vec![
+            game(
+                1,
+                2,
+                json!({ "developer": "社A/社B", "tags": ["纯爱", "校园"] }),
+            ),
+            game(2, 3, json!({ "developer": "社B", "tags": ["校园"] })),
+            game(3, 2, json!({})),
+        ];
+        // 游戏 4 已移入回收站，不在游戏列表中，其统计不应计入
+        let summary = build_summary(
+            &games,
+            &[statistics(1, 60), statistics(2, 30), statistics(4, 500)],
+            15,
+            1,
+        );
+
+        assert_eq!(summary.total_games, 3);
+        assert_eq!(summary.total_playtime, 90);
+        assert_eq!(summary.total_sessions, 2);
+        assert_eq!(summary.cleared_games, 2);
+        assert!((summary.cleared_ratio - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(summary.month_playtime, 15);
+        assert_eq!(summary.play_status_counts.get(&3), Some(&1));
+        assert_eq!(
+            summary.top_developers,
+            vec![RankedName {
+                name: "社B".to_string(),
+                game_count: 2,
+                playtime: 90,
+            }]
+        );
+        assert_eq!(summary.top_tags[0].name, "校园");
+        assert_eq!(summary.rated_games, 3);
+        assert_eq!(summary.average_rating, Some(5.0));
+        assert_eq!(
+            summary.rating_distribution,
+            BTreeMap::from([(3, 1), (5, 1), (8, 1)])
+        );
+    }
+
+    #[test]
+    fn summarizes_collection_statistics() {
+        let mut games = vec![
+            game(1, 2, json!({})),
+            game(2, 3, json!({})),
+            game(3, 2, json!({})),
+        ];
+        games[0].folder_size = Some(1024);
+        games[2].folder_size = Some(2048);
+        let stats = build_collection_statistics(
+            &games,
+            &[statistics(1, 60), statistics(2, 30), statistics(9, 500)],
+        );
+
+        assert_eq!(stats.total_games, 3);
+        assert_eq!(stats.total_playtime, 90);
+        assert_eq!(stats.cleared_games, 2);
+        assert!((stats.cleared_ratio - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!((stats.total_size, stats.sized_games), (3072, 2));
+    }
+}
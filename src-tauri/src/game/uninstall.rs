@@ -0,0 +1,140 @@
+//! 卸载游戏
+//!
+//! 一条命令完成卸载：可选删除游戏目录，清理封面缓存并删除游戏条目。
+//! 保留存档时游戏移入应用内回收站，游玩统计与存档备份随之保留，可通过 `restore_game` 恢复；
+//! 不保留时永久删除游戏，统计随外键级联删除，存档备份目录一并删除。
+//! 游戏目录与存档备份目录默认移入系统回收站，`permanent` 为 true 时直接删除。
+
+use crate::backup::savedata::resolve_savedata_backup_root;
+use crate::database::cleanup_deleted_game_covers;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::cover::cloud::get_game_cover_dir;
+use crate::game::cover::{DownloadState, delete_cloud_cache};
+use crate::game::monitor::is_game_monitored;
+use crate::game::size::dir_size;
+use crate::utils::trash::remove_path;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{State, command};
+
+/// 卸载结果
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallResult {
+    /// 实际释放的空间（字节），移入系统回收站的目录不计入
+    pub freed_bytes: u64,
+    pub folder_deleted: bool,
+    pub backups_deleted: usize,
+    /// 条目已删除后清理资源时遇到的错误
+    pub errors: Vec<String>,
+}
+
+async fn measure(path: PathBuf) -> u64 {
+    tokio::task::spawn_blocking(move || dir_size(&path))
+        .await
+        .unwrap_or(0)
+}
+
+/// 卸载游戏
+///
+/// 删除游戏目录失败时不会删除游戏条目；条目删除后的资源清理失败只记录到结果中。
+///
+/// # Arguments
+/// * `delete_files` - 是否删除游戏目录
+/// * `keep_saves` - 是否保留存档备份与游玩统计（游戏移入应用内回收站）
+/// * `permanent` - 是否直接删除游戏目录与存档备份目录，默认移入系统回收站
+#[command]
+pub async fn uninstall_game(
+    db: State<'_, DatabaseConnection>,
+    cover_state: State<'_, DownloadState>,
+    game_id: i32,
+    delete_files: bool,
+    keep_saves: bool,
+    permanent: Option<bool>,
+) -> Result<UninstallResult, String> {
+    if is_game_monitored(game_id as u32) {
+        return Err("游戏正在运行，无法卸载".to_string());
+    }
+    let game = GamesRepository::find_by_id(&db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+    let permanent = permanent.unwrap_or(false);
+    let mut result = UninstallResult::default();
+
+    if delete_files && let Some(localpath) = game.localpath.as_deref() {
+        let folder = PathBuf::from(localpath);
+        if folder.is_dir() {
+            let size = tokio::task::spawn_blocking(move || {
+                let size = if permanent { dir_size(&folder) } else { 0 };
+                remove_path(&folder, permanent).map(|_| size)
+            })
+            .await
+            .map_err(|e| format!("删除游戏目录任务异常: {}", e))??;
+            result.freed_bytes += size;
+            result.folder_deleted = true;
+        }
+    }
+
+    let cover_dir = get_game_cover_dir(game_id as u32)?;
+    let cover_size = measure(cover_dir.clone()).await;
+
+    if keep_saves {
+        GamesRepository::soft_delete_many(&db, vec![game_id])
+            .await
+            .map_err(|e| format!("删除游戏失败: {}", e))?;
+        // 自定义封面随条目保留，只清理可重新下载的云端缓存
+        if let Err(e) = delete_cloud_cache(game_id as u32, cover_state).await {
+            result.errors.push(format!("清理封面缓存失败: {}", e));
+        }
+    } else {
+        let backup_count = GamesRepository::get_savedata_count(&db, game_id)
+            .await
+            .map_err(|e| format!("查询存档备份失败: {}", e))?;
+        let backup_dir = resolve_savedata_backup_root(&db)
+            .await?
+            .join(format!("game_{}", game_id));
+        GamesRepository::delete(&db, game_id)
+            .await
+            .map_err(|e| format!("删除游戏失败: {}", e))?;
+        cleanup_deleted_game_covers(&cover_state, &[game_id]).await;
+
+        let backup_size = if permanent {
+            measure(backup_dir.clone()).await
+        } else {
+            0
+        };
+        match remove_backup_dir(backup_dir, permanent).await {
+            Ok(()) => {
+                result.freed_bytes += backup_size;
+                result.backups_deleted = backup_count as usize;
+            }
+            Err(e) => result.errors.push(e),
+        }
+    }
+    result.freed_bytes += cover_size.saturating_sub(measure(cover_dir).await);
+
+    log::info!(
+        "游戏卸载完成 game_id={} folder_deleted={} keep_saves={} permanent={} freed_bytes={} errors={}",
+        game_id,
+        result.folder_deleted,
+        keep_saves,
+        permanent,
+        result.freed_bytes,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// 删除存档备份目录，目录不存在时视为成功
+async fn remove_backup_dir(dir: PathBuf, permanent: bool) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        if !dir.exists() {
+            return Ok(());
+        }
+        remove_path(&dir, permanent).map_err(|e| format!("删除存档备份目录失败: {}", e))
+    })
+    .await
+    .map_err(|e| format!("删除存档备份目录任务异常: {}", e))?
+}
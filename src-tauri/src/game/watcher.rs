@@ -0,0 +1,465 @@
+//! 游戏库根目录监听与失效路径巡检
+//!
+//! 后台监听用户设置的游戏库根目录：根目录下出现未入库的新文件夹时发送
+//! `library-folder-added` 事件提示入库；已入库的游戏目录消失或恢复时更新
+//! games.missing 并发送 `library-missing-changed` 事件。
+//! [`verify_game_paths`] 则对全库做一次完整检查，不限于库根目录。
+//!
+//! 根目录变化通过文件系统通知（notify）即时触发巡检。移动硬盘插拔、网络盘掉线时
+//! 通知往往丢失，因此仍保留低频轮询兜底，每轮只读取根目录第一层，开销很小。
+
+use crate::backup::save_path::expand_save_path;
+use crate::database::repository::games_repository::{GamePathState, GamesRepository};
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::game::scan::{
+    ImportPathIndex, is_excluded_dir, is_same_or_descendant, trim_dirname_to_search_name,
+};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime, State, command};
+
+/// 没有收到文件系统通知时的兜底巡检间隔
+const LIBRARY_WATCH_INTERVAL_SECS: u64 = 30;
+
+/// 收到通知后等待的时间，合并解压、复制时连续产生的事件
+const LIBRARY_WATCH_DEBOUNCE_MS: u64 = 1500;
+
+/// 每个根目录上一轮看到的一级子目录
+type RootSnapshots = HashMap<PathBuf, HashSet<PathBuf>>;
+
+/// 单轮巡检的结果
+#[derive(Debug, Default)]
+struct WatchReport {
+    /// 新出现且未入库的文件夹：(根目录, 文件夹)
+    new_folders: Vec<(PathBuf, PathBuf)>,
+    /// 目录消失、需要标记为缺失的游戏
+    missing: Vec<i32>,
+    /// 目录重新出现、需要清除缺失标记的游戏
+    restored: Vec<i32>,
+}
+
+/// 启动后台巡检任务，应用启动时调用一次
+///
+/// 每轮都重新读取设置，修改监听目录或开关后无需重启任务。
+/// 无法创建文件系统监听时退化为纯轮询。
+pub fn spawn_library_watcher<R: Runtime>(app_handle: AppHandle<R>, db: DatabaseConnection) {
+    tauri::async_runtime::spawn(async move {
+        // 任务自身持有一个发送端，监听器创建失败时 recv 也不会立即返回 None
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let mut watcher = create_fs_watcher(event_tx.clone());
+        let mut watched = HashSet::new();
+        let mut snapshots = RootSnapshots::new();
+        loop {
+            match poll_library(&app_handle, &db, &mut snapshots).await {
+                Ok(roots) => {
+                    if let Some(watcher) = watcher.as_mut() {
+                        sync_watched_roots(watcher, &mut watched, &roots);
+                    }
+                }
+                Err(e) => log::warn!("游戏库目录巡检失败: {}", e),
+            }
+
+            let interval = Duration::from_secs(LIBRARY_WATCH_INTERVAL_SECS);
+            if let Ok(Some(())) = tokio::time::timeout(interval, event_rx.recv()).await {
+                tokio::time::sleep(Duration::from_millis(LIBRARY_WATCH_DEBOUNCE_MS)).await;
+                while event_rx.try_recv().is_ok() {}
+            }
+        }
+    });
+}
+
+/// 创建文件系统监听器，根目录下增删、重命名条目时向 `event_tx` 发送信号
+fn create_fs_watcher(
+    event_tx: tokio::sync::mpsc::UnboundedSender<()>,
+) -> Option<RecommendedWatcher> {
+    let handler = move |result: notify::Result<Event>| match result {
+        Ok(event) if is_layout_change(&event.kind) => {
+            let _ = event_tx.send(());
+        }
+        Ok(_) => {}
+        Err(e) => log::debug!("游戏库目录通知错误: {}", e),
+    };
+    notify::recommended_watcher(handler)
+        .map_err(|e| log::warn!("无法创建游戏库目录监听，改为轮询: {}", e))
+        .ok()
+}
+
+/// 只有新增、删除与重命名会改变根目录下的文件夹列表
+fn is_layout_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+    )
+}
+
+/// 让监听的根目录与设置保持一致
+///
+/// 不可访问的根目录（移动硬盘已拔出）从监听中移除，重新出现后在下一轮巡检时补上。
+fn sync_watched_roots(
+    watcher: &mut RecommendedWatcher,
+    watched: &mut HashSet<PathBuf>,
+    roots: &[PathBuf],
+) {
+    watched.retain(|root| {
+        let keep = roots.contains(root) && root.is_dir();
+        if !keep {
+            let _ = watcher.unwatch(root);
+        }
+        keep
+    });
+    for root in roots {
+        if watched.contains(root) || !root.is_dir() {
+            continue;
+        }
+        match watcher.watch(root, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                watched.insert(root.clone());
+            }
+            Err(e) => log::debug!("无法监听游戏库目录 {}: {}", root.display(), e),
+        }
+    }
+}
+
+/// 执行一轮巡检，返回当前启用的根目录（未启用时为空）
+async fn poll_library<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    snapshots: &mut RootSnapshots,
+) -> Result<Vec<PathBuf>, String> {
+    let settings = db.get_settings().await?.library_watch_settings();
+    if !settings.enabled || settings.roots.is_empty() {
+        snapshots.clear();
+        return Ok(Vec::new());
+    }
+
+    let roots: Vec<PathBuf> = settings.roots.iter().map(PathBuf::from).collect();
+    let game_states = GamesRepository::get_path_states(db)
+        .await
+        .map_err(|e| format!("查询游戏路径失败: {}", e))?;
+    let previous = std::mem::take(snapshots);
+
+    let check_list = roots.clone();
+    let (next, report) =
+        tokio::task::spawn_blocking(move || check_roots(&check_list, previous, &game_states))
+            .await
+            .map_err(|e| format!("巡检任务异常: {}", e))?;
+    *snapshots = next;
+
+    for (root, folder) in &report.new_folders {
+        let name = folder
+            .file_name()
+            .map(|name| trim_dirname_to_search_name(&name.to_string_lossy()))
+            .unwrap_or_default();
+        log::info!("游戏库发现新文件夹: {}", folder.display());
+        if let Err(e) = app_handle.emit(
+            "library-folder-added",
+            json!({
+                "root": root.to_string_lossy(),
+                "path": folder.to_string_lossy(),
+                "name": name,
+            }),
+        ) {
+            log::warn!("无法发送 library-folder-added 事件: {}", e);
+        }
+    }
+
+    apply_missing_changes(app_handle, db, &report.missing, &report.restored).await?;
+    Ok(roots)
+}
+
+/// 写入缺失标记变化并通知前端
+async fn apply_missing_changes<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    missing: &[i32],
+    restored: &[i32],
+) -> Result<(), String> {
+    if missing.is_empty() && restored.is_empty() {
+        return Ok(());
+    }
+
+    GamesRepository::set_missing(db, missing, true)
+        .await
+        .map_err(|e| format!("标记游戏文件缺失失败: {}", e))?;
+    GamesRepository::set_missing(db, restored, false)
+        .await
+        .map_err(|e| format!("清除游戏文件缺失标记失败: {}", e))?;
+    log::info!(
+        "游戏文件缺失状态变化 missing={:?} restored={:?}",
+        missing,
+        restored
+    );
+    if let Err(e) = app_handle.emit(
+        "library-missing-changed",
+        json!({ "missing": missing, "restored": restored }),
+    ) {
+        log::warn!("无法发送 library-missing-changed 事件: {}", e);
+    }
+
+    Ok(())
+}
+
+/// 根据游戏目录是否存在判断缺失标记是否需要变化
+///
+/// 返回 Some(true) 表示需要标记缺失，Some(false) 表示需要清除标记。
+fn missing_flag_change(state: &GamePathState) -> Option<bool> {
+    let exists = Path::new(state.localpath.as_deref()?).is_dir();
+    match (exists, state.missing) {
+        (false, false) => Some(true),
+        (true, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// 读取根目录的一级子目录，根目录不可访问时返回 None
+fn list_child_dirs(root: &Path) -> Option<HashSet<PathBuf>> {
+    let entries = std::fs::read_dir(root).ok()?;
+    Some(
+        entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .filter(|entry| !is_excluded_dir(&entry.file_name().to_string_lossy()))
+            .map(|entry| entry.path())
+            .collect(),
+    )
+}
+
+/// 对比上一轮快照得出新文件夹，并检查根目录下已入库游戏的目录是否存在
+///
+/// 根目录首次出现（包括移动硬盘重新插入）时只记录快照，不把已有文件夹当作新增。
+fn check_roots(
+    roots: &[PathBuf],
+    mut previous: RootSnapshots,
+    game_states: &[GamePathState],
+) -> (RootSnapshots, WatchReport) {
+    let mut imported = ImportPathIndex::default();
+    for state in game_states {
+        if let Some(localpath) = &state.localpath {
+            imported.insert(Path::new(localpath), state.id);
+        }
+    }
+
+    let mut snapshots = RootSnapshots::new();
+    let mut report = WatchReport::default();
+
+    for root in roots {
+        let Some(current) = list_child_dirs(root) else {
+            continue;
+        };
+        if let Some(before) = previous.remove(root) {
+            let mut added: Vec<PathBuf> = current
+                .difference(&before)
+                .filter(|dir| {
+                    imported.matched_game_id(dir).is_none() && !imported.is_inside_imported(dir)
+                })
+                .cloned()
+                .collect();
+            added.sort();
+            report
+                .new_folders
+                .extend(added.into_iter().map(|dir| (root.clone(), dir)));
+        }
+        snapshots.insert(root.clone(), current);
+    }
+
+    for state in game_states {
+        let Some(localpath) = &state.localpath else {
+            continue;
+        };
+        if !roots
+            .iter()
+            .any(|root| is_same_or_descendant(Path::new(localpath), root))
+        {
+            continue;
+        }
+        match missing_flag_change(state) {
+            Some(true) => report.missing.push(state.id),
+            Some(false) => report.restored.push(state.id),
+            None => {}
+        }
+    }
+
+    (snapshots, report)
+}
+
+/// 单个游戏中已失效的路径
+#[derive(Debug, Serialize)]
+pub struct MissingGamePath {
+    pub game_id: i32,
+    /// 不存在的游戏目录
+    pub localpath: Option<String>,
+    /// 不存在的存档目录（模板原文）
+    pub savepaths: Vec<String>,
+}
+
+/// 失效路径巡检结果
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyPathsReport {
+    /// 检查的游戏数量
+    pub checked: usize,
+    /// 存在失效路径的游戏
+    pub missing: Vec<MissingGamePath>,
+    /// 本次新标记为文件缺失的游戏
+    pub marked: Vec<i32>,
+    /// 本次清除文件缺失标记的游戏
+    pub restored: Vec<i32>,
+}
+
+fn verify_path_states(game_states: &[GamePathState]) -> VerifyPathsReport {
+    let mut report = VerifyPathsReport {
+        checked: game_states.len(),
+        ..Default::default()
+    };
+
+    for state in game_states {
+        let missing_dir = |path: &Option<String>| {
+            path.as_ref()
+                .filter(|path| !Path::new(path.as_str()).exists())
+                .cloned()
+        };
+        let localpath = missing_dir(&state.localpath);
+        // 存档路径可能是模板，展开失败同样视为失效
+        let savepaths: Vec<String> = state
+            .savepath
+            .iter()
+            .flat_map(|savepaths| &savepaths.0)
+            .filter(|savepath| {
+                expand_save_path(savepath, state.localpath.as_deref())
+                    .map_or(true, |path| !path.exists())
+            })
+            .cloned()
+            .collect();
+        if localpath.is_some() || !savepaths.is_empty() {
+            report.missing.push(MissingGamePath {
+                game_id: state.id,
+                localpath,
+                savepaths,
+            });
+        }
+
+        match missing_flag_change(state) {
+            Some(true) => report.marked.push(state.id),
+            Some(false) => report.restored.push(state.id),
+            None => {}
+        }
+    }
+
+    report
+}
+
+/// 检查全库游戏目录与存档目录是否存在
+///
+/// 返回存在失效路径的游戏列表，并同步 games.missing 标记。
+/// 缺失标记只反映游戏目录，存档目录可能在首次存档前尚未创建，只在列表中报告。
+#[command]
+pub async fn verify_game_paths<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+) -> Result<VerifyPathsReport, String> {
+    let game_states = GamesRepository::get_path_states(&db)
+        .await
+        .map_err(|e| format!("查询游戏路径失败: {}", e))?;
+    let report = tokio::task::spawn_blocking(move || verify_path_states(&game_states))
+        .await
+        .map_err(|e| format!("巡检任务异常: {}", e))?;
+
+    apply_missing_changes(&app_handle, &db, &report.marked, &report.restored).await?;
+    log::info!(
+        "失效路径巡检完成 checked={} missing={}",
+        report.checked,
+        report.missing.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::save_options::SavePaths;
+    use crate::utils::test_util::temp_dir;
+    use std::fs;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = temp_dir("watch", name);
+        fs::create_dir_all(&dir).expect("应能创建测试目录");
+        dir
+    }
+
+    fn path_state(id: i32, localpath: &Path, missing: bool) -> GamePathState {
+        GamePathState {
+            id,
+            localpath: Some(localpath.to_string_lossy().to_string()),
+            savepath: None,
+            missing,
+        }
+    }
+
+    #[test]
+    fn reports_new_folders_and_missing_games() {
+        let root = temp_root("library");
+        let imported = root.join("Imported");
+        let removed = root.join("Removed");
+        fs::create_dir_all(&imported).unwrap();
+        fs::create_dir_all(&removed).unwrap();
+        let roots = vec![root.clone()];
+        let states = vec![
+            path_state(1, &imported, false),
+            path_state(2, &removed, false),
+        ];
+
+        // 首轮只记录快照
+        let (snapshots, report) = check_roots(&roots, RootSnapshots::new(), &states);
+        assert!(report.new_folders.is_empty());
+        assert!(report.missing.is_empty());
+
+        let new_game = root.join("New Game");
+        fs::create_dir_all(&new_game).unwrap();
+        fs::remove_dir_all(&removed).unwrap();
+        let (snapshots, report) = check_roots(&roots, snapshots, &states);
+        assert_eq!(report.new_folders, vec![(root.clone(), new_game)]);
+        assert_eq!(report.missing, vec![2]);
+
+        fs::create_dir_all(&removed).unwrap();
+        let states = vec![path_state(2, &removed, true)];
+        let (_, report) = check_roots(&roots, snapshots, &states);
+        assert!(report.new_folders.is_empty());
+        assert_eq!(report.restored, vec![2]);
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+
+    #[test]
+    fn verify_reports_missing_local_and_save_paths() {
+        let root = temp_root("verify");
+        let present = root.join("Present");
+        fs::create_dir_all(&present).unwrap();
+        let gone = root.join("Gone");
+        let mut save_missing = path_state(1, &present, true);
+        save_missing.savepath = Some(SavePaths(vec![
+            present.to_string_lossy().to_string(),
+            root.join("Save").to_string_lossy().to_string(),
+        ]));
+        let states = vec![save_missing, path_state(2, &gone, false)];
+
+        let report = verify_path_states(&states);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.missing.len(), 2);
+        assert!(report.missing[0].localpath.is_none());
+        assert_eq!(
+            report.missing[0].savepaths,
+            vec![root.join("Save").to_string_lossy().to_string()]
+        );
+        assert!(report.missing[1].savepaths.is_empty());
+        assert!(report.missing[1].localpath.is_some());
+        assert_eq!(report.marked, vec![2]);
+        assert_eq!(report.restored, vec![1]);
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+}
@@ -0,0 +1,165 @@
+//! 从其他启动器与在线服务导入游戏库
+//!
+//! 各导入器先解析外部数据并预览（标记与本库重复的条目），确认后再批量入库，
+//! 入库成功的游戏按外部记录补写游玩会话。
+
+pub mod bgm;
+pub mod playnite;
+pub mod potatovn;
+pub mod vndb;
+
+use crate::backup::library::GameMatchIndex;
+use crate::database::dto::{BatchOperationError, FullGameData, InsertGameData};
+use crate::database::repository::game_stats_repository::{GameStatsRepository, ImportedSession};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::entity::custom_data::CustomData;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 导入预览条目
+#[derive(Debug, Serialize)]
+pub struct ImportPreviewItem<T> {
+    #[serde(flatten)]
+    pub game: T,
+    /// 游戏目录在本机是否存在
+    pub path_exists: bool,
+    /// 与本库已有游戏重复时为对应的游戏 ID
+    pub duplicate_game_id: Option<i32>,
+    /// 重复的判定方式：bgm / vndb / localpath
+    pub matched_by: Option<String>,
+}
+
+/// 导入预览
+#[derive(Debug, Serialize)]
+pub struct ImportPreview<T> {
+    pub total: usize,
+    pub duplicates: usize,
+    pub items: Vec<ImportPreviewItem<T>>,
+}
+
+impl<T> ImportPreview<T> {
+    pub(crate) fn new(items: Vec<ImportPreviewItem<T>>) -> Self {
+        Self {
+            total: items.len(),
+            duplicates: items
+                .iter()
+                .filter(|item| item.duplicate_game_id.is_some())
+                .count(),
+            items,
+        }
+    }
+}
+
+impl<T> ImportPreviewItem<T> {
+    pub(crate) fn new(
+        game: T,
+        localpath: Option<&str>,
+        duplicate: Option<(i32, &'static str)>,
+    ) -> Self {
+        Self {
+            path_exists: localpath.is_some_and(|path| Path::new(path).is_dir()),
+            duplicate_game_id: duplicate.map(|(game_id, _)| game_id),
+            matched_by: duplicate.map(|(_, matched_by)| matched_by.to_string()),
+            game,
+        }
+    }
+}
+
+/// 导入结果
+#[derive(Debug, Default, Serialize)]
+pub struct ImportGamesResult {
+    pub total: usize,
+    pub success: usize,
+    pub failed: usize,
+    /// 与已有游戏重复而跳过的数量
+    pub skipped: usize,
+    /// 自动匹配到元数据的数量
+    pub matched: usize,
+    pub sessions_added: usize,
+    pub games: Vec<FullGameData>,
+    /// 失败条目，`index` 为外部数据中的下标
+    pub errors: Vec<BatchOperationError>,
+}
+
+/// 待入库的外部游戏
+pub(crate) struct ImportCandidate {
+    /// 在外部数据中的下标
+    pub index: usize,
+    pub game: InsertGameData,
+    pub sessions: Vec<ImportedSession>,
+}
+
+/// 读取已有游戏并建立重复检测索引
+pub(crate) async fn load_match_index(db: &DatabaseConnection) -> Result<GameMatchIndex, String> {
+    let games =
+        GamesRepository::find_all(db, GameType::All, SortOption::Addtime, SortOrder::Asc, None)
+            .await
+            .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    Ok(GameMatchIndex::from_games(&games))
+}
+
+/// 只保留选中的下标，`indices` 为空时全部保留
+pub(crate) fn is_selected(selected: Option<&HashSet<usize>>, index: usize) -> bool {
+    selected.is_none_or(|selected| selected.contains(&index))
+}
+
+/// 在保留其他自定义字段的前提下写入个人评分
+pub(crate) fn with_user_rating(custom_data: Option<CustomData>, rating: f64) -> CustomData {
+    CustomData {
+        user_rating: Some(rating),
+        ..custom_data.unwrap_or_default()
+    }
+}
+
+/// 批量入库并为成功的游戏写入游玩会话，会话写入失败只记录日志
+pub(crate) async fn insert_candidates(
+    db: &DatabaseConnection,
+    candidates: Vec<ImportCandidate>,
+) -> ImportGamesResult {
+    let (meta, games): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .map(|candidate| ((candidate.index, candidate.sessions), candidate.game))
+        .unzip();
+    let result = GamesRepository::insert_batch(db, games).await;
+
+    let failed_positions: HashSet<usize> = result.errors.iter().map(|error| error.index).collect();
+    let errors = result
+        .errors
+        .into_iter()
+        .map(|error| BatchOperationError {
+            index: meta[error.index].0,
+            message: error.message,
+        })
+        .collect();
+
+    // 插入结果按顺序只包含成功的条目
+    let mut sessions_added = 0;
+    let succeeded = meta
+        .iter()
+        .enumerate()
+        .filter(|(position, _)| !failed_positions.contains(position))
+        .map(|(_, (_, sessions))| sessions);
+    for (sessions, inserted) in succeeded.zip(result.games.iter()) {
+        if sessions.is_empty() {
+            continue;
+        }
+        match GameStatsRepository::import_sessions(db, inserted.id, sessions).await {
+            Ok(count) => sessions_added += count,
+            Err(e) => log::warn!("导入游玩时长失败 game_id={}: {}", inserted.id, e),
+        }
+    }
+
+    ImportGamesResult {
+        total: result.total,
+        success: result.success,
+        failed: result.failed,
+        sessions_added,
+        games: result.games,
+        errors,
+        ..Default::default()
+    }
+}
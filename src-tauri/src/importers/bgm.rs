@@ -0,0 +1,174 @@
+//! Bangumi 收藏导入
+//!
+//! 使用已保存的 BGM 授权拉取用户的游戏收藏，按收藏类型写入本地游戏状态。
+//! 本库已有的条目（按 BGM 条目 ID 匹配）只更新状态与评分，其余条目读取完整条目数据后
+//! 作为无本地目录的在线游戏入库。
+
+use super::{ImportCandidate, insert_candidates, with_user_rating};
+use crate::backup::library::GameMatchIndex;
+use crate::database::dto::{BatchOperationError, FullGameData, InsertGameData, UpdateGameData};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder, SyncService, SyncStatus,
+};
+use crate::metadata::bgm::{
+    BgmCollectionEntry, fetch_game_collections, fetch_subject, fetch_token_username,
+};
+use crate::metadata::{CachePolicy, METADATA_MATCH_INTERVAL_MS};
+use crate::utils::bgm_auth::valid_bgm_auth;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{State, command};
+
+/// 默认导入的收藏类型：想玩 / 玩过 / 在玩
+const DEFAULT_COLLECTION_TYPES: [i32; 3] = [1, 2, 3];
+
+/// Bangumi 收藏导入结果
+#[derive(Debug, Default, Serialize)]
+pub struct BgmCollectionImportResult {
+    pub username: String,
+    pub total: usize,
+    pub inserted: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub games: Vec<FullGameData>,
+    /// 失败条目，`index` 为收藏列表中的下标
+    pub errors: Vec<BatchOperationError>,
+}
+
+fn rate_to_rating(rate: i32) -> Option<f64> {
+    (1..=10).contains(&rate).then(|| f64::from(rate))
+}
+
+fn update_data_from(current: &FullGameData, entry: &BgmCollectionEntry) -> UpdateGameData {
+    UpdateGameData {
+        clear: Some(Some(entry.collection_type)),
+        custom_data: rate_to_rating(entry.rate)
+            .map(|rating| Some(with_user_rating(current.custom_data.clone(), rating))),
+        ..Default::default()
+    }
+}
+
+/// 导入 Bangumi 游戏收藏
+///
+/// # Arguments
+/// * `collection_types` - 要导入的收藏类型（1 想玩 / 2 玩过 / 3 在玩 / 4 搁置 / 5 抛弃），
+///   为空时导入想玩、玩过与在玩
+#[command]
+pub async fn import_bgm_collection(
+    db: State<'_, DatabaseConnection>,
+    collection_types: Option<Vec<i32>>,
+) -> Result<BgmCollectionImportResult, String> {
+    let auth = valid_bgm_auth(&db).await?;
+    let token = auth.access_token.trim().to_string();
+    let username = match auth.username.filter(|name| !name.trim().is_empty()) {
+        Some(username) => username,
+        None => fetch_token_username(&token).await?,
+    };
+
+    let mut collection_types = collection_types
+        .unwrap_or_else(|| DEFAULT_COLLECTION_TYPES.to_vec())
+        .into_iter()
+        .filter(|collection_type| (1..=5).contains(collection_type))
+        .collect::<Vec<_>>();
+    collection_types.sort_unstable();
+    collection_types.dedup();
+
+    let mut entries = Vec::new();
+    for collection_type in collection_types {
+        entries.extend(fetch_game_collections(&username, &token, collection_type).await?);
+    }
+
+    let existing_games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let index = GameMatchIndex::from_games(&existing_games);
+    let existing_games: HashMap<i32, FullGameData> = existing_games
+        .into_iter()
+        .map(|game| (game.id, game))
+        .collect();
+
+    let mut result = BgmCollectionImportResult {
+        username: username.clone(),
+        total: entries.len(),
+        ..Default::default()
+    };
+    let mut updates = Vec::new();
+    let mut new_entries = Vec::new();
+    for (position, entry) in entries.iter().enumerate() {
+        let subject_id = entry.subject_id.to_string();
+        match index
+            .find_parts(&[("bgm", subject_id.as_str())], None)
+            .and_then(|(game_id, _)| existing_games.get(&game_id))
+        {
+            Some(current) => updates.push((current.id, update_data_from(current, entry))),
+            None => new_entries.push((position, entry)),
+        }
+    }
+
+    let updated = GamesRepository::update_batch(&db, updates)
+        .await
+        .map_err(|e| format!("更新已有游戏状态失败: {}", e))?;
+    // 状态来自 Bangumi 本身，无需再进入回写队列
+    let updated_ids: Vec<i32> = updated.iter().map(|game| game.id).collect();
+    if let Err(e) =
+        GamesRepository::set_sync_status(&db, SyncService::Bgm, &updated_ids, SyncStatus::Synced)
+            .await
+    {
+        log::warn!("更新 Bangumi 回写状态失败: {}", e);
+    }
+    result.updated = updated.len();
+    result.games.extend(updated);
+
+    // 收藏列表只包含精简条目信息，新条目逐个读取完整数据
+    let mut candidates = Vec::with_capacity(new_entries.len());
+    for (fetched, (position, entry)) in new_entries.into_iter().enumerate() {
+        if fetched > 0 {
+            tokio::time::sleep(Duration::from_millis(METADATA_MATCH_INTERVAL_MS)).await;
+        }
+        match fetch_subject(entry.subject_id, Some(&token), CachePolicy::Prefer).await {
+            Ok(matched) => candidates.push(ImportCandidate {
+                index: position,
+                game: InsertGameData {
+                    id_type: matched.source.as_str().to_string(),
+                    clear: Some(entry.collection_type),
+                    custom_data: rate_to_rating(entry.rate)
+                        .map(|rating| with_user_rating(None, rating)),
+                    sources: vec![matched.into_source_data()],
+                    ..Default::default()
+                },
+                sessions: Vec::new(),
+            }),
+            Err(e) => {
+                log::warn!("读取 BGM 条目失败 subject_id={}: {}", entry.subject_id, e);
+                result.errors.push(BatchOperationError {
+                    index: position,
+                    message: e,
+                });
+            }
+        }
+    }
+
+    let inserted = insert_candidates(&db, candidates).await;
+    result.inserted = inserted.success;
+    result.failed = result.errors.len() + inserted.failed;
+    result.errors.extend(inserted.errors);
+    result.games.extend(inserted.games);
+
+    log::info!(
+        "BGM 收藏导入完成 user={} total={} inserted={} updated={} failed={}",
+        username,
+        result.total,
+        result.inserted,
+        result.updated,
+        result.failed
+    );
+    Ok(result)
+}
@@ -0,0 +1,400 @@
+//! Playnite 游戏库导入
+//!
+//! 解析 Playnite 游戏库的 JSON 导出（`Game` 对象数组），导入游戏名称、安装目录、
+//! 启动程序、完成状态与游玩时长。Playnite 自身的 LiteDB 数据库无法直接读取，
+//! 需先用导出插件导出为 JSON。可选按名称自动匹配 BGM/VNDB 条目。
+
+use super::{
+    ImportCandidate, ImportGamesResult, ImportPreview, ImportPreviewItem, insert_candidates,
+    is_selected, load_match_index,
+};
+use crate::database::dto::InsertGameData;
+use crate::database::repository::game_stats_repository::ImportedSession;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::custom_data::CustomData;
+use crate::metadata::{MetadataMatch, MetadataSource, search_best_matches};
+use chrono::DateTime;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{State, command};
+
+/// 启动动作路径中表示安装目录的占位符
+const INSTALL_DIR_PLACEHOLDER: &str = "{InstallDir}";
+/// Playnite `GameActionType.File`
+const ACTION_TYPE_FILE: i64 = 0;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlayniteGameRecord {
+    name: Option<String>,
+    install_directory: Option<String>,
+    #[serde(default)]
+    is_installed: bool,
+    /// 游玩时长（秒）
+    #[serde(default)]
+    playtime: u64,
+    #[serde(default)]
+    play_count: u64,
+    last_activity: Option<String>,
+    /// 导出插件输出为名称字符串或 `{ "Name": ... }` 对象
+    completion_status: Option<Value>,
+    #[serde(default)]
+    game_actions: Vec<PlayniteGameAction>,
+    /// Playnite 8 及更早版本的启动动作
+    play_action: Option<PlayniteGameAction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlayniteGameAction {
+    /// 数字或枚举名称
+    #[serde(rename = "Type")]
+    action_type: Option<Value>,
+    path: Option<String>,
+    #[serde(default = "default_play_action")]
+    is_play_action: bool,
+}
+
+fn default_play_action() -> bool {
+    true
+}
+
+impl PlayniteGameAction {
+    fn is_file(&self) -> bool {
+        match &self.action_type {
+            None => true,
+            Some(Value::Number(number)) => number.as_i64() == Some(ACTION_TYPE_FILE),
+            Some(Value::String(name)) => name.eq_ignore_ascii_case("File"),
+            Some(_) => false,
+        }
+    }
+}
+
+/// 解析后的 Playnite 游戏
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayniteGame {
+    /// 在导出文件中的下标，确认导入时用于选择条目
+    pub index: usize,
+    pub name: String,
+    pub localpath: Option<String>,
+    pub executable: Option<String>,
+    pub installed: bool,
+    /// 映射后的游戏状态（games.clear）
+    pub play_status: Option<i32>,
+    /// 游玩时长（分钟）
+    pub playtime_minutes: i32,
+    pub play_count: u64,
+    /// 最后游玩时间（Unix 秒）
+    pub last_activity: Option<i64>,
+}
+
+/// Playnite 默认完成状态名称映射为 games.clear
+fn map_completion_status(status: Option<&Value>) -> Option<i32> {
+    let name = match status? {
+        Value::String(name) => name.as_str(),
+        Value::Object(object) => object.get("Name")?.as_str()?,
+        _ => return None,
+    };
+    match name.trim().to_ascii_lowercase().as_str() {
+        "plan to play" | "not played" => Some(1),
+        "played" | "beaten" | "completed" => Some(2),
+        "playing" => Some(3),
+        "on hold" => Some(4),
+        "abandoned" => Some(5),
+        _ => None,
+    }
+}
+
+/// 从启动动作中解析启动程序的完整路径
+fn resolve_play_action_path(record: &PlayniteGameRecord) -> Option<PathBuf> {
+    let action = record
+        .game_actions
+        .iter()
+        .find(|action| action.is_play_action && action.is_file())
+        .or(record
+            .play_action
+            .as_ref()
+            .filter(|action| action.is_file()))?;
+    let path = action.path.as_deref()?.trim();
+    let install_dir = record.install_directory.as_deref();
+    let has_placeholder = path.contains(INSTALL_DIR_PLACEHOLDER);
+
+    let expanded = match install_dir {
+        Some(dir) => path.replace(INSTALL_DIR_PLACEHOLDER, dir),
+        None => path.to_string(),
+    };
+    // 其他占位符（如 {PlayniteDir}、{EmulatorDir}）无法在本应用中解析
+    if expanded.contains('{') {
+        return None;
+    }
+
+    let expanded = PathBuf::from(expanded);
+    if has_placeholder || expanded.is_absolute() {
+        Some(expanded)
+    } else {
+        install_dir.map(|dir| Path::new(dir).join(expanded))
+    }
+}
+
+fn parse_game(index: usize, record: PlayniteGameRecord) -> Option<PlayniteGame> {
+    let name = record
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())?
+        .to_string();
+    let install_dir = record
+        .install_directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty() && !dir.contains('{'));
+
+    // 与扫描导入一致，启动程序所在目录作为游戏目录
+    let (localpath, executable) = match resolve_play_action_path(&record) {
+        Some(exe_path) => match (exe_path.parent(), exe_path.file_name()) {
+            (Some(parent), Some(file_name)) if !parent.as_os_str().is_empty() => (
+                Some(parent.to_string_lossy().into_owned()),
+                Some(file_name.to_string_lossy().into_owned()),
+            ),
+            _ => (install_dir.map(ToOwned::to_owned), None),
+        },
+        None => (install_dir.map(ToOwned::to_owned), None),
+    };
+
+    Some(PlayniteGame {
+        index,
+        name,
+        localpath,
+        executable,
+        installed: record.is_installed,
+        play_status: map_completion_status(record.completion_status.as_ref()),
+        playtime_minutes: i32::try_from(record.playtime / 60).unwrap_or(i32::MAX),
+        play_count: record.play_count,
+        last_activity: record
+            .last_activity
+            .as_deref()
+            .and_then(|text| DateTime::parse_from_rfc3339(text.trim()).ok())
+            .map(|datetime| datetime.timestamp())
+            .filter(|timestamp| *timestamp > 0),
+    })
+}
+
+/// 解析 Playnite 游戏库 JSON 导出
+pub fn parse_playnite_games(content: &str) -> Result<Vec<PlayniteGame>, String> {
+    let records: Vec<Value> =
+        serde_json::from_str(content).map_err(|e| format!("解析 Playnite 导出文件失败: {}", e))?;
+
+    Ok(records
+        .into_iter()
+        .enumerate()
+        .filter_map(
+            |(index, record)| match serde_json::from_value::<PlayniteGameRecord>(record) {
+                Ok(record) => parse_game(index, record),
+                Err(e) => {
+                    log::warn!("跳过无法解析的 Playnite 条目 index={}: {}", index, e);
+                    None
+                }
+            },
+        )
+        .collect())
+}
+
+async fn load_games(path: &str) -> Result<Vec<PlayniteGame>, String> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("读取 Playnite 导出文件失败: {}", e))?;
+    parse_playnite_games(&content)
+}
+
+impl PlayniteGame {
+    fn to_insert_data(&self, matched: Option<MetadataMatch>) -> InsertGameData {
+        let (id_type, custom_data, sources) = match matched {
+            Some(matched) => (
+                matched.source.as_str().to_string(),
+                None,
+                vec![matched.into_source_data()],
+            ),
+            None => (
+                "custom".to_string(),
+                Some(CustomData {
+                    name: Some(self.name.clone()),
+                    ..Default::default()
+                }),
+                Vec::new(),
+            ),
+        };
+
+        InsertGameData {
+            id_type,
+            localpath: self.localpath.clone(),
+            executable: self.executable.clone(),
+            clear: self.play_status,
+            custom_data,
+            sources,
+            ..Default::default()
+        }
+    }
+
+    /// Playnite 只记录总时长，以最后游玩时间为结束时间生成一条总时长会话
+    fn to_sessions(&self) -> Vec<ImportedSession> {
+        if self.playtime_minutes <= 0 {
+            return Vec::new();
+        }
+        let end_time = self
+            .last_activity
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        let start_time = end_time - i64::from(self.playtime_minutes) * 60;
+        match (i32::try_from(start_time), i32::try_from(end_time)) {
+            (Ok(start_time), Ok(end_time)) if start_time > 0 => vec![ImportedSession {
+                start_time,
+                end_time,
+                duration: self.playtime_minutes,
+            }],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// 预览 Playnite 导出文件的解析结果，并标记与本库重复的条目
+///
+/// # Arguments
+/// * `path` - Playnite 游戏库 JSON 导出文件路径
+#[command]
+pub async fn preview_playnite_import(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+) -> Result<ImportPreview<PlayniteGame>, String> {
+    let games = load_games(&path).await?;
+    let index = load_match_index(&db).await?;
+
+    Ok(ImportPreview::new(
+        games
+            .into_iter()
+            .map(|game| {
+                let duplicate = index.find_parts(&[], game.localpath.as_deref());
+                let localpath = game.localpath.clone();
+                ImportPreviewItem::new(game, localpath.as_deref(), duplicate)
+            })
+            .collect(),
+    ))
+}
+
+/// 导入 Playnite 导出文件中的游戏与游玩时长
+///
+/// # Arguments
+/// * `path` - Playnite 游戏库 JSON 导出文件路径
+/// * `indices` - 要导入的条目下标，为空时导入全部条目；目录已入库的条目总是跳过
+/// * `auto_match` - 按游戏名自动匹配元数据的数据源，为空时作为自定义游戏导入
+#[command]
+pub async fn import_playnite_games(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+    indices: Option<Vec<usize>>,
+    auto_match: Option<MetadataSource>,
+) -> Result<ImportGamesResult, String> {
+    let games = load_games(&path).await?;
+    let index = load_match_index(&db).await?;
+    let selected: Option<HashSet<usize>> = indices.map(|indices| indices.into_iter().collect());
+
+    let mut skipped = 0;
+    let games: Vec<PlayniteGame> = games
+        .into_iter()
+        .filter(|game| is_selected(selected.as_ref(), game.index))
+        .filter(|game| {
+            let duplicated = index.find_parts(&[], game.localpath.as_deref()).is_some();
+            if duplicated {
+                skipped += 1;
+            }
+            !duplicated
+        })
+        .collect();
+
+    let matches: Vec<Option<MetadataMatch>> = match auto_match {
+        Some(source) => {
+            let settings = db.get_settings().await?;
+            let names: Vec<&str> = games.iter().map(|game| game.name.as_str()).collect();
+            search_best_matches(source, &names, &settings)
+                .await
+                .into_iter()
+                .map(|result| result.ok().flatten())
+                .collect()
+        }
+        None => games.iter().map(|_| None).collect(),
+    };
+    let matched = matches.iter().filter(|matched| matched.is_some()).count();
+
+    let candidates = games
+        .iter()
+        .zip(matches)
+        .map(|(game, matched)| ImportCandidate {
+            index: game.index,
+            game: game.to_insert_data(matched),
+            sessions: game.to_sessions(),
+        })
+        .collect();
+
+    let mut result = insert_candidates(&db, candidates).await;
+    result.total += skipped;
+    result.skipped = skipped;
+    result.matched = matched;
+
+    log::info!(
+        "Playnite 导入完成 path={} success={} failed={} skipped={} matched={}",
+        path,
+        result.success,
+        result.failed,
+        skipped,
+        matched
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_playnite_export() {
+        let content = json!([
+            {
+                "Name": "Summer Pockets",
+                "InstallDirectory": "D:/Games/SummerPockets",
+                "IsInstalled": true,
+                "Playtime": 7260,
+                "PlayCount": 3,
+                "LastActivity": "2024-05-01T12:00:00+08:00",
+                "CompletionStatus": { "Name": "Beaten" },
+                "GameActions": [
+                    { "Type": 1, "Path": "https://example.com", "IsPlayAction": false },
+                    { "Type": 0, "Path": "{InstallDir}/bin/SiglusEngine.exe", "IsPlayAction": true }
+                ]
+            },
+            { "Name": "Emulated", "GameActions": [{ "Type": "Emulator", "IsPlayAction": true }] },
+            { "Name": "   " }
+        ])
+        .to_string();
+
+        let games = parse_playnite_games(&content).unwrap();
+        assert_eq!(games.len(), 2);
+
+        let game = &games[0];
+        assert_eq!(
+            Path::new(game.localpath.as_deref().unwrap()),
+            Path::new("D:/Games/SummerPockets/bin")
+        );
+        assert_eq!(game.executable.as_deref(), Some("SiglusEngine.exe"));
+        assert_eq!(game.play_status, Some(2));
+        assert_eq!(game.playtime_minutes, 121);
+        let sessions = game.to_sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].end_time, 1_714_536_000);
+        assert_eq!(game.to_insert_data(None).id_type, "custom");
+
+        assert_eq!(games[1].localpath, None);
+        assert!(games[1].to_sessions().is_empty());
+    }
+}
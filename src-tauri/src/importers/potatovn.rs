@@ -0,0 +1,441 @@
+//! PotatoVN 游戏库导入
+//!
+//! 解析 PotatoVN（GalgameManager）的游戏数据文件（`galgames.json`），
+//! 将游戏目录、启动程序、游玩时长与游玩状态映射为本应用的游戏与游玩会话。
+//! 先调用 `preview_potatovn_import` 预览解析结果与重复项，
+//! 确认后再调用 `import_potatovn_games` 导入选定条目。
+
+use super::{
+    ImportCandidate, ImportGamesResult, ImportPreview, ImportPreviewItem, insert_candidates,
+    is_selected, load_match_index,
+};
+use crate::database::dto::{InsertGameData, UpsertGameSourceData};
+use crate::database::repository::game_stats_repository::ImportedSession;
+use crate::entity::custom_data::CustomData;
+use crate::entity::save_options::SavePaths;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{State, command};
+
+/// PotatoVN `Ids` 数组中各数据源的下标，与其 `RssType` 枚举顺序一致
+const POTATOVN_BGM_ID_INDEX: usize = 0;
+const POTATOVN_VNDB_ID_INDEX: usize = 1;
+/// 单日游玩记录最多折算为 24 小时
+const MAX_DAILY_MINUTES: i32 = 24 * 60;
+
+/// 解析后的 PotatoVN 游戏
+#[derive(Debug, Clone, Serialize)]
+pub struct PotatoVnGame {
+    /// 在数据文件中的下标，确认导入时用于选择条目
+    pub index: usize,
+    pub name: String,
+    pub description: Option<String>,
+    pub localpath: Option<String>,
+    pub executable: Option<String>,
+    pub savepath: Option<String>,
+    pub bgm_id: Option<String>,
+    pub vndb_id: Option<String>,
+    /// 映射后的游戏状态（games.clear）
+    pub play_status: Option<i32>,
+    /// 总游玩时长（分钟）
+    pub total_minutes: i32,
+    /// 按日记录的游玩时长（日期 -> 分钟）
+    #[serde(skip)]
+    pub daily_minutes: Vec<(NaiveDate, i32)>,
+    #[serde(skip)]
+    pub last_played: Option<NaiveDateTime>,
+}
+
+/// 不区分大小写地读取对象字段，并展开 PotatoVN 的可锁定属性（`{ "Value": ... }`）
+fn field<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    let found = value
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)?;
+    match found {
+        Value::Object(_) => field(found, "Value").or(Some(found)),
+        _ => Some(found),
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    field(value, key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn int_field(value: &Value, key: &str) -> Option<i64> {
+    field(value, key).and_then(|value| {
+        value
+            .as_i64()
+            .or_else(|| value.as_f64().map(|number| number as i64))
+            .or_else(|| value.as_str()?.trim().parse().ok())
+    })
+}
+
+/// PotatoVN 的 PlayType 映射为 games.clear
+///
+/// PotatoVN：None / Played / Playing / Shelved / Abandoned / WantToPlay，
+/// 本应用：1 想玩 / 2 玩过 / 3 在玩 / 4 搁置 / 5 弃坑。
+fn map_play_type(value: Option<&Value>) -> Option<i32> {
+    let play_type = match value? {
+        Value::Number(number) => number.as_i64()?,
+        Value::String(text) => match text.trim().to_ascii_lowercase().as_str() {
+            "played" => 1,
+            "playing" => 2,
+            "shelved" => 3,
+            "abandoned" => 4,
+            "wanttoplay" => 5,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match play_type {
+        1 => Some(2),
+        2 => Some(3),
+        3 => Some(4),
+        4 => Some(5),
+        5 => Some(1),
+        _ => None,
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    ["%Y/%m/%d", "%Y-%m-%d", "%Y.%m.%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text.trim(), format).ok())
+}
+
+/// 解析最后游玩时间；C# 的 `DateTime.MinValue` 等无效值返回 None
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    let datetime = DateTime::parse_from_rfc3339(text)
+        .map(|datetime| datetime.with_timezone(&Local).naive_local())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        .or_else(|| parse_date(text).and_then(|date| date.and_hms_opt(0, 0, 0)))?;
+    (datetime.and_utc().timestamp() > 0).then_some(datetime)
+}
+
+fn external_id(ids: Option<&Value>, index: usize) -> Option<String> {
+    ids?.as_array()?
+        .get(index)?
+        .as_str()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// 解析单个 PotatoVN 游戏，名称和目录都缺失的条目忽略
+fn parse_game(index: usize, value: &Value) -> Option<PotatoVnGame> {
+    let exe_path = string_field(value, "ExePath");
+    let mut localpath = string_field(value, "Path");
+    let mut executable = None;
+
+    // 启动程序可能位于游戏目录的子目录中，与扫描导入一致取其所在目录作为游戏目录
+    if let Some(exe_path) = exe_path.as_deref() {
+        let exe_path = Path::new(exe_path);
+        if let (Some(parent), Some(file_name)) = (exe_path.parent(), exe_path.file_name())
+            && !parent.as_os_str().is_empty()
+        {
+            localpath = Some(parent.to_string_lossy().into_owned());
+            executable = Some(file_name.to_string_lossy().into_owned());
+        }
+    }
+
+    let name = string_field(value, "Name").or_else(|| {
+        Path::new(localpath.as_deref()?)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    })?;
+
+    let mut daily_minutes: Vec<(NaiveDate, i32)> = field(value, "PlayedTime")
+        .and_then(Value::as_object)
+        .map(|days| {
+            days.iter()
+                .filter_map(|(date, minutes)| {
+                    let minutes = i32::try_from(minutes.as_i64()?).ok()?;
+                    (minutes > 0).then_some((parse_date(date)?, minutes))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    daily_minutes.sort();
+
+    let ids = field(value, "Ids");
+    Some(PotatoVnGame {
+        index,
+        name,
+        description: string_field(value, "Description"),
+        localpath,
+        executable,
+        savepath: string_field(value, "SavePath"),
+        bgm_id: external_id(ids, POTATOVN_BGM_ID_INDEX),
+        vndb_id: external_id(ids, POTATOVN_VNDB_ID_INDEX).map(|id| {
+            if id.starts_with('v') {
+                id
+            } else {
+                format!("v{}", id)
+            }
+        }),
+        play_status: map_play_type(field(value, "PlayType")),
+        total_minutes: int_field(value, "TotalPlayTime")
+            .and_then(|minutes| i32::try_from(minutes).ok())
+            .unwrap_or(0)
+            .max(0),
+        daily_minutes,
+        last_played: string_field(value, "LastPlayTime")
+            .or_else(|| string_field(value, "LastPlay"))
+            .as_deref()
+            .and_then(parse_datetime),
+    })
+}
+
+/// 解析 PotatoVN 数据文件，兼容顶层为数组或包含 `Galgames` 字段的对象
+pub fn parse_potatovn_games(content: &str) -> Result<Vec<PotatoVnGame>, String> {
+    let root: Value =
+        serde_json::from_str(content).map_err(|e| format!("解析 PotatoVN 数据文件失败: {}", e))?;
+    let games = root
+        .as_array()
+        .or_else(|| field(&root, "Galgames")?.as_array())
+        .ok_or_else(|| "无法识别的 PotatoVN 数据文件格式".to_string())?;
+
+    Ok(games
+        .iter()
+        .enumerate()
+        .filter_map(|(index, game)| parse_game(index, game))
+        .collect())
+}
+
+async fn load_games(path: &str) -> Result<Vec<PotatoVnGame>, String> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("读取 PotatoVN 数据文件失败: {}", e))?;
+    parse_potatovn_games(&content)
+}
+
+impl PotatoVnGame {
+    fn external_ids(&self) -> Vec<(&str, &str)> {
+        [
+            ("bgm", self.bgm_id.as_deref()),
+            ("vndb", self.vndb_id.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(source, id)| Some((source, id?)))
+        .collect()
+    }
+
+    fn to_insert_data(&self) -> InsertGameData {
+        let source_data = json!({ "name": self.name, "summary": self.description });
+        let sources: Vec<UpsertGameSourceData> = self
+            .external_ids()
+            .into_iter()
+            .map(|(source, id)| UpsertGameSourceData {
+                source: source.to_string(),
+                external_id: Some(id.to_string()),
+                data: Some(source_data.clone()),
+            })
+            .collect();
+
+        let (id_type, custom_data) = match sources.as_slice() {
+            [] => (
+                "custom".to_string(),
+                Some(CustomData {
+                    name: Some(self.name.clone()),
+                    summary: self.description.clone(),
+                    ..Default::default()
+                }),
+            ),
+            [source] => (source.source.clone(), None),
+            _ => ("mixed".to_string(), None),
+        };
+
+        InsertGameData {
+            id_type,
+            localpath: self.localpath.clone(),
+            executable: self.executable.clone(),
+            savepath: self.savepath.clone().map(|path| SavePaths(vec![path])),
+            clear: self.play_status,
+            custom_data,
+            sources,
+            ..Default::default()
+        }
+    }
+
+    /// 将游玩时长转换为会话：有按日记录时每天一条会话（从当天零点开始），
+    /// 否则以最后游玩时间为结束时间生成一条总时长会话
+    fn to_sessions(&self) -> Vec<ImportedSession> {
+        let local_timestamp = |datetime: NaiveDateTime| {
+            Local
+                .from_local_datetime(&datetime)
+                .earliest()
+                .and_then(|datetime| i32::try_from(datetime.timestamp()).ok())
+        };
+        let session = |start_time: i32, minutes: i32| ImportedSession {
+            start_time,
+            end_time: start_time + minutes * 60,
+            duration: minutes,
+        };
+
+        if !self.daily_minutes.is_empty() {
+            return self
+                .daily_minutes
+                .iter()
+                .filter_map(|(date, minutes)| {
+                    let start_time = local_timestamp(date.and_hms_opt(0, 0, 0)?)?;
+                    Some(session(start_time, (*minutes).min(MAX_DAILY_MINUTES)))
+                })
+                .collect();
+        }
+
+        if self.total_minutes <= 0 {
+            return Vec::new();
+        }
+        let end_time = self
+            .last_played
+            .and_then(local_timestamp)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp() as i32);
+        let start_time = end_time - self.total_minutes * 60;
+        if start_time <= 0 {
+            return Vec::new();
+        }
+        vec![session(start_time, self.total_minutes)]
+    }
+}
+
+/// 预览 PotatoVN 数据文件的解析结果，并标记与本库重复的条目
+///
+/// # Arguments
+/// * `path` - PotatoVN 数据文件路径
+#[command]
+pub async fn preview_potatovn_import(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+) -> Result<ImportPreview<PotatoVnGame>, String> {
+    let games = load_games(&path).await?;
+    let index = load_match_index(&db).await?;
+
+    Ok(ImportPreview::new(
+        games
+            .into_iter()
+            .map(|game| {
+                let duplicate = index.find_parts(&game.external_ids(), game.localpath.as_deref());
+                let localpath = game.localpath.clone();
+                ImportPreviewItem::new(game, localpath.as_deref(), duplicate)
+            })
+            .collect(),
+    ))
+}
+
+/// 导入 PotatoVN 数据文件中的游戏与游玩时长
+///
+/// # Arguments
+/// * `path` - PotatoVN 数据文件路径
+/// * `indices` - 要导入的条目下标，为空时导入全部条目；与已有游戏重复的条目总是跳过
+#[command]
+pub async fn import_potatovn_games(
+    db: State<'_, DatabaseConnection>,
+    path: String,
+    indices: Option<Vec<usize>>,
+) -> Result<ImportGamesResult, String> {
+    let games = load_games(&path).await?;
+    let index = load_match_index(&db).await?;
+    let selected: Option<HashSet<usize>> = indices.map(|indices| indices.into_iter().collect());
+
+    let mut skipped = 0;
+    let candidates: Vec<ImportCandidate> = games
+        .into_iter()
+        .filter(|game| is_selected(selected.as_ref(), game.index))
+        .filter(|game| {
+            let duplicated = index
+                .find_parts(&game.external_ids(), game.localpath.as_deref())
+                .is_some();
+            if duplicated {
+                skipped += 1;
+            }
+            !duplicated
+        })
+        .map(|game| ImportCandidate {
+            index: game.index,
+            game: game.to_insert_data(),
+            sessions: game.to_sessions(),
+        })
+        .collect();
+
+    let mut result = insert_candidates(&db, candidates).await;
+    result.total += skipped;
+    result.skipped = skipped;
+
+    log::info!(
+        "PotatoVN 导入完成 path={} success={} failed={} skipped={} sessions={}",
+        path,
+        result.success,
+        result.failed,
+        skipped,
+        result.sessions_added
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_potatovn_galgames() {
+        let content = json!([
+            {
+                "Name": { "Value": "Ever17", "IsLock": false },
+                "Path": "D:/Games/Ever17",
+                "ExePath": "D:/Games/Ever17/bin/ever17.exe",
+                "Ids": ["1234", "17", null, null, null],
+                "PlayType": 1,
+                "TotalPlayTime": 150,
+                "PlayedTime": { "2024/1/2": 90, "2024/1/1": 60 }
+            },
+            { "Name": "", "Path": "" },
+            {
+                "Name": "Custom",
+                "Path": "D:/Games/Custom",
+                "PlayType": "WantToPlay",
+                "TotalPlayTime": 30,
+                "LastPlayTime": "0001-01-01T00:00:00"
+            }
+        ])
+        .to_string();
+
+        let games = parse_potatovn_games(&content).unwrap();
+        assert_eq!(games.len(), 2);
+
+        let ever17 = &games[0];
+        assert_eq!(ever17.name, "Ever17");
+        assert_eq!(
+            Path::new(ever17.localpath.as_deref().unwrap()),
+            Path::new("D:/Games/Ever17/bin")
+        );
+        assert_eq!(ever17.executable.as_deref(), Some("ever17.exe"));
+        assert_eq!(ever17.vndb_id.as_deref(), Some("v17"));
+        assert_eq!(ever17.play_status, Some(2));
+        let sessions = ever17.to_sessions();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].duration, 60);
+        assert_eq!(sessions[0].end_time - sessions[0].start_time, 3600);
+        let insert = ever17.to_insert_data();
+        assert_eq!(insert.id_type, "mixed");
+        assert_eq!(insert.sources.len(), 2);
+
+        let custom = &games[1];
+        assert_eq!(custom.index, 2);
+        assert_eq!(custom.play_status, Some(1));
+        assert_eq!(custom.last_played, None);
+        assert_eq!(custom.to_sessions().len(), 1);
+        assert_eq!(custom.to_insert_data().id_type, "custom");
+    }
+}
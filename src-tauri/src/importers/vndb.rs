@@ -0,0 +1,228 @@
+//! VNDB 用户列表导入
+//!
+//! 通过 VNDB API 拉取用户的 VN 列表，按标签映射游戏状态、按投票分写入个人评分。
+//! 本库已有的条目（按 VNDB ID 匹配）只更新状态与评分，其余条目作为无本地目录的
+//! 在线游戏入库。
+
+use super::{ImportCandidate, insert_candidates, with_user_rating};
+use crate::backup::library::GameMatchIndex;
+use crate::database::dto::{BatchOperationError, FullGameData, InsertGameData, UpdateGameData};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder, SyncService, SyncStatus,
+};
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::metadata::vndb::{VndbUserListEntry, fetch_token_user, fetch_user_list};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{State, command};
+
+/// VNDB 的 Blacklist 标签，此类条目不导入
+const VNDB_LABEL_BLACKLIST: u32 = 6;
+/// VNDB 标签到 games.clear 的映射，同时存在多个标签时按此顺序取第一个
+pub(crate) const VNDB_LABEL_PLAY_STATUS: [(u32, i32); 5] = [
+    (1, 3), // Playing -> 在玩
+    (2, 2), // Finished -> 玩过
+    (3, 4), // Stalled -> 搁置
+    (4, 5), // Dropped -> 弃坑
+    (5, 1), // Wishlist -> 想玩
+];
+
+/// VNDB 用户列表导入结果
+#[derive(Debug, Default, Serialize)]
+pub struct VndbUserListImportResult {
+    pub user_id: String,
+    pub total: usize,
+    pub inserted: usize,
+    pub updated: usize,
+    pub failed: usize,
+    /// Blacklist 等未导入的条目数量
+    pub ignored: usize,
+    pub games: Vec<FullGameData>,
+    /// 失败条目，`index` 为用户列表中的下标
+    pub errors: Vec<BatchOperationError>,
+}
+
+fn label_play_status(label_ids: &[u32]) -> Option<i32> {
+    VNDB_LABEL_PLAY_STATUS
+        .iter()
+        .find(|(label, _)| label_ids.contains(label))
+        .map(|(_, status)| *status)
+}
+
+/// VNDB 投票分（10-100）换算为本地评分（0-10）
+fn vote_to_rating(vote: Option<u32>) -> Option<f64> {
+    vote.filter(|vote| *vote > 0)
+        .map(|vote| f64::from(vote.min(100)) / 10.0)
+}
+
+fn insert_data_from(entry: &VndbUserListEntry) -> InsertGameData {
+    InsertGameData {
+        id_type: entry.matched.source.as_str().to_string(),
+        clear: label_play_status(&entry.label_ids),
+        custom_data: vote_to_rating(entry.vote).map(|rating| with_user_rating(None, rating)),
+        sources: vec![entry.matched.clone().into_source_data()],
+        ..Default::default()
+    }
+}
+
+fn update_data_from(current: &FullGameData, entry: &VndbUserListEntry) -> Option<UpdateGameData> {
+    let clear = label_play_status(&entry.label_ids);
+    let rating = vote_to_rating(entry.vote);
+    if clear.is_none() && rating.is_none() {
+        return None;
+    }
+    Some(UpdateGameData {
+        clear: clear.map(Some),
+        custom_data: rating
+            .map(|rating| Some(with_user_rating(current.custom_data.clone(), rating))),
+        ..Default::default()
+    })
+}
+
+/// 解析参数得到用户 ID 与可选的 Token：`u123` 形式视为用户 ID，其余视为 API Token
+fn parse_user_or_token(value: &str) -> (Option<String>, Option<String>) {
+    let value = value.trim();
+    let is_user_id = value.len() > 1
+        && value.starts_with('u')
+        && value[1..].chars().all(|ch| ch.is_ascii_digit());
+    if is_user_id {
+        (Some(value.to_string()), None)
+    } else {
+        (None, Some(value.to_string()))
+    }
+}
+
+/// 导入 VNDB 用户列表
+///
+/// # Arguments
+/// * `token_or_uid` - VNDB 用户 ID（如 `u12345`，仅能读取公开标签）或 API Token；
+///   为空时使用设置中保存的 VNDB Token
+#[command]
+pub async fn import_vndb_userlist(
+    db: State<'_, DatabaseConnection>,
+    token_or_uid: Option<String>,
+) -> Result<VndbUserListImportResult, String> {
+    let value = match token_or_uid
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        Some(value) => value,
+        None => db
+            .get_settings()
+            .await?
+            .vndb_token
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| "未提供 VNDB 用户 ID，且设置中没有保存 VNDB Token".to_string())?,
+    };
+    let (user_id, token) = match parse_user_or_token(&value) {
+        (Some(user_id), token) => (user_id, token),
+        (None, token) => {
+            let token = token.unwrap_or_default();
+            (fetch_token_user(&token).await?, Some(token))
+        }
+    };
+
+    let entries = fetch_user_list(&user_id, token.as_deref()).await?;
+    let existing_games = GamesRepository::find_all(
+        &db,
+        GameType::All,
+        SortOption::Addtime,
+        SortOrder::Asc,
+        None,
+    )
+    .await
+    .map_err(|e| format!("读取游戏数据失败: {}", e))?;
+    let index = GameMatchIndex::from_games(&existing_games);
+    let existing_games: HashMap<i32, FullGameData> = existing_games
+        .into_iter()
+        .map(|game| (game.id, game))
+        .collect();
+
+    let mut result = VndbUserListImportResult {
+        user_id: user_id.clone(),
+        total: entries.len(),
+        ..Default::default()
+    };
+    let mut candidates = Vec::new();
+    let mut updates = Vec::new();
+    for (position, entry) in entries.iter().enumerate() {
+        if entry.label_ids.contains(&VNDB_LABEL_BLACKLIST) {
+            result.ignored += 1;
+            continue;
+        }
+        let external_ids = [("vndb", entry.matched.external_id.as_str())];
+        match index
+            .find_parts(&external_ids, None)
+            .and_then(|(game_id, _)| existing_games.get(&game_id))
+        {
+            Some(current) => {
+                if let Some(update) = update_data_from(current, entry) {
+                    updates.push((current.id, update));
+                }
+            }
+            None => candidates.push(ImportCandidate {
+                index: position,
+                game: insert_data_from(entry),
+                sessions: Vec::new(),
+            }),
+        }
+    }
+
+    match GamesRepository::update_batch(&db, updates).await {
+        Ok(updated) => {
+            // 状态与评分来自 VNDB 本身，无需再进入回写队列
+            let updated_ids: Vec<i32> = updated.iter().map(|game| game.id).collect();
+            if let Err(e) = GamesRepository::set_sync_status(
+                &db,
+                SyncService::Vndb,
+                &updated_ids,
+                SyncStatus::Synced,
+            )
+            .await
+            {
+                log::warn!("更新 VNDB 回写状态失败: {}", e);
+            }
+            result.updated = updated.len();
+            result.games.extend(updated);
+        }
+        Err(e) => return Err(format!("更新已有游戏状态失败: {}", e)),
+    }
+
+    let inserted = insert_candidates(&db, candidates).await;
+    result.inserted = inserted.success;
+    result.failed = inserted.failed;
+    result.errors = inserted.errors;
+    result.games.extend(inserted.games);
+
+    log::info!(
+        "VNDB 用户列表导入完成 user={} total={} inserted={} updated={} failed={}",
+        user_id,
+        result.total,
+        result.inserted,
+        result.updated,
+        result.failed
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_labels_and_votes() {
+        assert_eq!(label_play_status(&[7, 2]), Some(2));
+        assert_eq!(label_play_status(&[5, 1]), Some(3));
+        assert_eq!(label_play_status(&[7]), None);
+        assert_eq!(vote_to_rating(Some(85)), Some(8.5));
+        assert_eq!(vote_to_rating(None), None);
+
+        let (user_id, token) = parse_user_or_token(" u12345 ");
+        assert_eq!(user_id.as_deref(), Some("u12345"));
+        assert!(token.is_none());
+        let (user_id, token) = parse_user_or_token("abcd-efgh-ijkl");
+        assert!(user_id.is_none());
+        assert_eq!(token.as_deref(), Some("abcd-efgh-ijkl"));
+    }
+}
@@ -1,29 +1,82 @@
 mod backup;
 mod database;
 mod entity;
+mod error;
 mod game;
+mod importers;
+mod metadata;
+mod sync;
 mod utils;
 
+use backup::audit::{audit_savedata, cleanup_savedata_orphans};
+use backup::collection_share::{export_collection, import_collection};
 use backup::covers::backup_custom_covers;
-use backup::database::{backup_database, import_database};
+use backup::database::{backup_database, import_database, list_db_backups, restore_database};
+use backup::library::{export_library, import_library};
+use backup::retention::apply_retention_policy;
+use backup::save_path::resolve_game_savepaths;
 use backup::savedata::{
-    create_savedata_backup, delete_savedata_backup, move_backup_folder, restore_savedata_backup,
+    create_savedata_backup, delete_savedata_backup, delete_savedata_backups,
+    has_savedata_backup_password, move_backup_folder, pin_savedata_backup, restore_savedata_backup,
+    set_savedata_backup_password,
 };
+use backup::savedata_diff::diff_savedata_backups;
+use backup::sessions::export_sessions_csv;
 use database::*;
-use game::cover::custom::{delete_game_covers, import_clipboard_image_to_temp};
+use game::archive::{archive_game, unarchive_game};
+use game::characters::{fetch_bgm_characters, get_game_characters, search_games_by_voice_actor};
+use game::cover::candidates::search_cover_candidates;
+use game::cover::custom::{delete_game_covers, import_clipboard_image_to_temp, set_custom_cover};
 use game::cover::{delete_cloud_cache, register_game_cover_protocol};
-use game::launch::{launch_game, stop_game};
-use game::scan::scan_directory_for_games;
+use game::desktop_shortcut::create_desktop_shortcut;
+use game::developers::{find_games_by_developer, get_all_developers};
+use game::duplicates::{find_duplicate_games, merge_games};
+use game::engine::detect_game_engine;
+use game::launch::locale_emulator::list_le_profiles;
+use game::launch::{get_running_game_ids, kill_game, kill_process, launch_game, stop_game};
+use game::relations::{get_related_games, sync_game_relations};
+use game::relocate::{delete_game_folder, move_game_folder};
+use game::report::generate_year_report;
+use game::save_detect::detect_save_path;
+use game::scan::{batch_add_scanned_games, cancel_scan, scan_directory_for_games};
+use game::screenshots::{delete_screenshot, get_screenshots, import_screenshots};
+use game::size::{calc_all_game_sizes, calc_game_size};
+use game::steam::export_to_steam;
+use game::summary::{get_collection_statistics, get_library_summary};
+use game::uninstall::uninstall_game;
+use game::watcher::verify_game_paths;
+use importers::bgm::import_bgm_collection;
+use importers::playnite::{import_playnite_games, preview_playnite_import};
+use importers::potatovn::{import_potatovn_games, preview_potatovn_import};
+use importers::vndb::import_vndb_userlist;
+use metadata::cache::clear_metadata_cache;
+use metadata::matching::{auto_apply_metadata, auto_match_metadata};
+use metadata::merge::get_merged_views;
+use metadata::refresh::refresh_metadata_batch;
 use migration::MigratorTrait;
+use sync::bgm::{sync_all_bgm_status, sync_bgm_status};
+use sync::lan::{
+    discover_lan_peers, has_lan_sync_pairing_secret, set_lan_sync_pairing_secret,
+    sync_with_lan_peer,
+};
+use sync::vndb::{sync_all_vndb_status, sync_vndb_status};
 use tauri::Manager;
 use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+use tauri_plugin_window_state::StateFlags;
 use utils::{
-    bgm_auth::{bgm_oauth_exchange_code, bgm_oauth_refresh_token, bgm_oauth_start_login},
+    bgm_auth::{
+        bgm_oauth_exchange_code, bgm_oauth_login, bgm_oauth_refresh_token, bgm_oauth_start_login,
+        cancel_bgm_oauth_login,
+    },
+    cli,
+    deep_link::take_pending_deep_link,
     fs::{copy_file, delete_file, is_portable_mode, open_directory, resolve_dropped_local_path},
-    http::update_proxy_config,
+    http::{send_http_request, update_proxy_config},
     image::register_image_proxy_protocol,
     legacy_migration::run_startup_migrations,
     logs::{get_reina_log_level, set_reina_log_level},
+    remote_api::generate_remote_api_token,
+    tasks::{TaskManager, cancel_task, get_task, list_tasks},
 };
 
 const LOG_MAX_FILE_SIZE: u128 = 1_000_000;
@@ -34,18 +87,29 @@ fn restart_app(app: tauri::AppHandle) {
     app.request_restart();
 }
 
+/// 命令接口版本，命令参数或返回结构发生不兼容变更时递增
+const API_SCHEMA_VERSION: u32 = 1;
+
+/// 获取命令接口版本，供前端做兼容协商
+#[tauri::command]
+fn get_api_schema_version() -> u32 {
+    API_SCHEMA_VERSION
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     register_image_proxy_protocol(register_game_cover_protocol(tauri::Builder::default()))
         .plugin(tauri_plugin_store::Builder::new().build())
-        .plugin(tauri_plugin_window_state::Builder::new().build())
+        // 窗口可见性由启动参数决定，不随窗口状态恢复
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(StateFlags::all() & !StateFlags::VISIBLE)
+                .build(),
+        )
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.unminimize();
-                let _ = window.set_focus();
-            }
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // 应用已运行时，第二个实例的启动参数转交到这里处理
+            cli::handle_cli_args(app, cli::parse_cli_args(args.iter().skip(1)));
         }))
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
@@ -54,44 +118,122 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(TaskManager::default())
         .invoke_handler(tauri::generate_handler![
             // 工具类 commands
             launch_game,
             stop_game,
+            kill_game,
+            kill_process,
+            get_running_game_ids,
+            list_le_profiles,
             open_directory,
             resolve_dropped_local_path,
             is_portable_mode,
+            take_pending_deep_link,
+            generate_remote_api_token,
+            discover_lan_peers,
+            sync_with_lan_peer,
+            set_lan_sync_pairing_secret,
+            has_lan_sync_pairing_secret,
             scan_directory_for_games,
+            cancel_scan,
+            list_tasks,
+            get_task,
+            cancel_task,
+            batch_add_scanned_games,
+            get_screenshots,
+            import_screenshots,
+            delete_screenshot,
+            verify_game_paths,
+            calc_game_size,
+            calc_all_game_sizes,
+            find_duplicate_games,
+            merge_games,
+            move_game_folder,
+            delete_game_folder,
+            uninstall_game,
+            archive_game,
+            unarchive_game,
+            detect_game_engine,
+            detect_save_path,
             move_backup_folder,
             copy_file,
             create_savedata_backup,
             delete_savedata_backup,
+            delete_savedata_backups,
+            pin_savedata_backup,
             restore_savedata_backup,
+            set_savedata_backup_password,
+            has_savedata_backup_password,
+            diff_savedata_backups,
+            resolve_game_savepaths,
+            apply_retention_policy,
+            audit_savedata,
+            cleanup_savedata_orphans,
             delete_file,
             import_clipboard_image_to_temp,
             delete_game_covers,
+            search_cover_candidates,
+            create_desktop_shortcut,
+            export_to_steam,
+            set_custom_cover,
             delete_cloud_cache,
             backup_database,
+            list_db_backups,
+            restore_database,
             backup_custom_covers,
             import_database,
+            export_library,
+            import_library,
+            export_collection,
+            import_collection,
+            export_sessions_csv,
+            preview_potatovn_import,
+            import_potatovn_games,
+            preview_playnite_import,
+            import_playnite_games,
+            import_vndb_userlist,
+            import_bgm_collection,
+            clear_metadata_cache,
+            refresh_metadata_batch,
+            auto_match_metadata,
+            auto_apply_metadata,
+            get_merged_views,
+            sync_bgm_status,
+            sync_all_bgm_status,
+            sync_vndb_status,
+            sync_all_vndb_status,
             // 游戏数据相关 commands
             insert_game,
             insert_games_batch,
             find_game_by_id,
             find_all_games,
             find_game_ids,
+            find_game_summaries,
+            search_games,
             update_game,
+            set_game_review,
+            clear_game_review,
             delete_game,
             delete_games_batch,
+            restore_game,
+            find_deleted_games,
+            purge_deleted_games,
             count_games,
             get_source_bindings,
             update_games_batch,
+            bulk_update_games,
+            migrate_localpath_prefix,
             // 存档备份相关 commands
             save_savedata_record,
+            update_savedata_note,
             get_savedata_count,
             get_savedata_records,
             // 游戏统计相关 commands
             create_manual_game_session,
+            add_manual_session,
+            update_game_session,
             rebuild_game_statistics,
             get_game_sessions,
             get_recent_sessions_for_all,
@@ -99,23 +241,56 @@ pub fn run() {
             get_game_statistics,
             get_all_game_statistics,
             get_all_game_last_played,
+            get_playtime_by_range,
+            get_playtime_heatmap,
+            get_streaks,
+            get_game_notes,
+            create_game_note,
+            update_game_note,
+            delete_game_note,
+            search_game_notes,
+            get_custom_fields,
+            create_custom_field,
+            update_custom_field,
+            delete_custom_field,
+            get_game_custom_fields,
+            set_game_custom_field,
+            find_games_by_custom_field,
+            get_library_summary,
+            get_collection_statistics,
+            get_all_developers,
+            find_games_by_developer,
+            sync_game_relations,
+            fetch_bgm_characters,
+            get_game_characters,
+            search_games_by_voice_actor,
+            get_related_games,
+            generate_year_report,
             // 用户设置相关 commands
             get_all_settings,
             update_settings,
+            get_scan_exe_rules,
+            update_scan_exe_rules,
+            reset_scan_exe_rules,
             update_proxy_config,
+            send_http_request,
             // BGM OAuth 相关 commands
             bgm_oauth_start_login,
+            bgm_oauth_login,
+            cancel_bgm_oauth_login,
             bgm_oauth_exchange_code,
             bgm_oauth_refresh_token,
             // 日志相关 commands（运行时动态调整）
             set_reina_log_level,
             get_reina_log_level,
             restart_app,
+            get_api_schema_version,
             // 合集相关 commands
             create_collection,
             find_root_collections,
             get_root_collections_with_count,
             update_collection,
+            move_collection_subtree,
             delete_collection,
             remove_games_from_collection,
             get_games_in_collection,
@@ -123,14 +298,14 @@ pub fn run() {
             add_games_to_collections,
             set_game_collections,
             update_category_games,
+            reorder_collection_games,
             count_games_in_group,
             get_categories_with_count,
+            optimize_database,
+            check_database_integrity,
+            cleanup_orphan_records,
         ])
         .setup(|app| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-            }
-
             // 仅在调试模式下自动打开开发者工具
             #[cfg(debug_assertions)]
             {
@@ -177,6 +352,9 @@ pub fn run() {
                 log::set_max_level(log::LevelFilter::Info);
             }
 
+            // 按启动参数显示、最小化或隐藏主窗口
+            cli::handle_cli_args(app.handle(), cli::parse_cli_args(std::env::args().skip(1)));
+
             match run_startup_migrations() {
                 Ok(result) if result.executed == 0 => {
                     log::debug!("启动迁移检查完成，无需执行");
@@ -196,6 +374,10 @@ pub fn run() {
                 }
             }
 
+            // 游玩期间的截图热键
+            #[cfg(target_os = "windows")]
+            game::screenshots::hotkey::spawn_screenshot_hotkey(app.handle().clone());
+
             // 执行 SeaORM 数据库迁移并注册到状态管理
             let app_handle = app.handle().clone();
             tauri::async_runtime::block_on(async move {
@@ -215,6 +397,18 @@ pub fn run() {
 
                         // 将数据库连接注册到 Tauri 状态管理
                         app_handle.manage(conn.clone());
+
+                        // 按网络设置初始化共享 HTTP 客户端
+                        utils::http::init_from_settings(&conn).await;
+
+                        // 启动游戏库根目录巡检
+                        game::watcher::spawn_library_watcher(app_handle.clone(), conn.clone());
+
+                        // 启动数据库定时备份
+                        backup::schedule::spawn_db_backup_scheduler(conn.clone());
+
+                        // 按设置启动局域网远程控制接口
+                        utils::remote_api::init_from_settings(&app_handle, &conn).await;
                     }
                     Err(e) => {
                         log::error!("无法建立数据库连接: {}", e);
@@ -222,6 +416,14 @@ pub fn run() {
                     }
                 }
             });
+
+            // 注册 reina:// 协议
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            {
+                if let Err(e) = utils::deep_link::register_protocol() {
+                    log::warn!("{}", e);
+                }
+            }
             Ok(())
         })
         .build(tauri::generate_context!())
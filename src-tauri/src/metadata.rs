@@ -0,0 +1,244 @@
+//! 后端元数据抓取
+//!
+//! 数据转换与前端 `src/metadata/api` 保持一致，生成的 JSON 直接写入 `game_sources.data`，
+//! 供批量入库等不经过前端的流程使用。
+
+pub mod bgm;
+pub mod cache;
+pub mod matching;
+pub mod merge;
+pub mod refresh;
+pub mod vndb;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri_plugin_http::reqwest::RequestBuilder;
+
+use crate::database::dto::UpsertGameSourceData;
+use crate::entity::user;
+
+/// 批量自动匹配时相邻两次搜索的间隔
+pub const METADATA_MATCH_INTERVAL_MS: u64 = 1000;
+
+/// 后端支持按名称搜索的元数据源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataSource {
+    Bgm,
+    Vndb,
+}
+
+impl MetadataSource {
+    /// 与 `games.id_type` 及 `game_sources.source` 一致的数据源标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MetadataSource::Bgm => "bgm",
+            MetadataSource::Vndb => "vndb",
+        }
+    }
+}
+
+/// 单个元数据搜索结果
+#[derive(Debug, Clone)]
+pub struct MetadataMatch {
+    pub source: MetadataSource,
+    pub external_id: String,
+    pub data: Value,
+}
+
+impl MetadataMatch {
+    pub fn into_source_data(self) -> UpsertGameSourceData {
+        UpsertGameSourceData {
+            source: self.source.as_str().to_string(),
+            external_id: Some(self.external_id),
+            data: Some(self.data),
+        }
+    }
+}
+
+/// 按名称在指定数据源中搜索，返回排名第一的条目
+pub async fn search_best_match(
+    source: MetadataSource,
+    name: &str,
+    settings: &user::Model,
+) -> Result<Option<MetadataMatch>, String> {
+    let keyword = name.trim();
+    if keyword.is_empty() {
+        return Ok(None);
+    }
+
+    let results = match source {
+        MetadataSource::Bgm => {
+            let token = settings
+                .bgm_auth
+                .as_ref()
+                .map(|auth| auth.access_token.trim())
+                .filter(|token| !token.is_empty());
+            bgm::search_by_name(keyword, token, 1).await?
+        }
+        MetadataSource::Vndb => vndb::search_by_name(keyword, 1).await?,
+    };
+    Ok(results.into_iter().next())
+}
+
+/// 按名称逐条搜索最佳匹配，结果与输入一一对应
+///
+/// 相邻两次搜索之间等待 [`METADATA_MATCH_INTERVAL_MS`]，避免触发元数据源的限流。
+pub async fn search_best_matches(
+    source: MetadataSource,
+    names: &[&str],
+    settings: &user::Model,
+) -> Vec<Result<Option<MetadataMatch>, String>> {
+    let mut results = Vec::with_capacity(names.len());
+    for (index, name) in names.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(Duration::from_millis(METADATA_MATCH_INTERVAL_MS)).await;
+        }
+        let result = search_best_match(source, name, settings).await;
+        if let Err(e) = &result {
+            log::warn!("自动匹配元数据失败 name={}: {}", name, e);
+        }
+        results.push(result);
+    }
+    results
+}
+
+fn with_json_body(request: RequestBuilder, body: &Value) -> Result<RequestBuilder, String> {
+    Ok(request
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_vec(body).map_err(|e| format!("序列化请求体失败: {}", e))?))
+}
+
+/// 发送 JSON POST 请求并解析响应
+async fn post_json<T: DeserializeOwned>(
+    url: &str,
+    body: &Value,
+    authorization: Option<String>,
+) -> Result<T, String> {
+    let request = with_json_body(crate::utils::http::get_client().post(url), body)?;
+    send_json(request, authorization).await
+}
+
+/// 查询接口对响应缓存的使用方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// 有效期内的相同请求直接读取缓存
+    Prefer,
+    /// 跳过缓存重新请求，并用新响应更新缓存
+    Refresh,
+}
+
+/// 发送 JSON POST 查询请求并解析响应，响应写入缓存
+async fn post_json_cached<T: DeserializeOwned>(
+    url: &str,
+    body: &Value,
+    authorization: Option<String>,
+    policy: CachePolicy,
+) -> Result<T, String> {
+    let payload = serde_json::to_vec(body).map_err(|e| format!("序列化请求体失败: {}", e))?;
+    let key = cache::cache_key("POST", url, Some(&payload), authorization.as_deref());
+    if policy == CachePolicy::Prefer
+        && let Some(value) = read_cached_json(&key).await
+    {
+        return Ok(value);
+    }
+    let request = with_json_body(crate::utils::http::get_client().post(url), body)?;
+    let text = send(request, authorization).await?;
+    parse_and_cache(&key, &text).await
+}
+
+/// 发送 GET 请求并解析 JSON 响应，响应写入缓存
+async fn get_json_cached<T: DeserializeOwned>(
+    url: &str,
+    authorization: Option<String>,
+    policy: CachePolicy,
+) -> Result<T, String> {
+    let key = cache::cache_key("GET", url, None, authorization.as_deref());
+    if policy == CachePolicy::Prefer
+        && let Some(value) = read_cached_json(&key).await
+    {
+        return Ok(value);
+    }
+    let text = send(crate::utils::http::get_client().get(url), authorization).await?;
+    parse_and_cache(&key, &text).await
+}
+
+/// 读取缓存并解析，缓存内容无法解析时视为未命中
+async fn read_cached_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let text = cache::read_cached(key).await?;
+    serde_json::from_str(&text).ok()
+}
+
+async fn parse_and_cache<T: DeserializeOwned>(key: &str, text: &str) -> Result<T, String> {
+    let value = serde_json::from_str(text).map_err(|e| format!("解析元数据响应失败: {}", e))?;
+    cache::write_cached(key, text).await;
+    Ok(value)
+}
+
+/// 发送 JSON POST 请求，忽略响应内容（用于 202/204 等无响应体的写接口）
+async fn post_json_no_content(
+    url: &str,
+    body: &Value,
+    authorization: Option<String>,
+) -> Result<(), String> {
+    let request = with_json_body(crate::utils::http::get_client().post(url), body)?;
+    send(request, authorization).await.map(|_| ())
+}
+
+/// 发送 JSON PATCH 请求，忽略响应内容
+async fn patch_json_no_content(
+    url: &str,
+    body: &Value,
+    authorization: Option<String>,
+) -> Result<(), String> {
+    let request = with_json_body(crate::utils::http::get_client().patch(url), body)?;
+    send(request, authorization).await.map(|_| ())
+}
+
+/// 发送 GET 请求并解析 JSON 响应
+async fn get_json<T: DeserializeOwned>(
+    url: &str,
+    authorization: Option<String>,
+) -> Result<T, String> {
+    send_json(crate::utils::http::get_client().get(url), authorization).await
+}
+
+async fn send_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+    authorization: Option<String>,
+) -> Result<T, String> {
+    let text = send(request, authorization).await?;
+    serde_json::from_str(&text).map_err(|e| format!("解析元数据响应失败: {}", e))
+}
+
+/// 发送请求并返回响应文本，非 2xx 状态视为失败
+async fn send(
+    mut request: RequestBuilder,
+    authorization: Option<String>,
+) -> Result<String, String> {
+    request = request.header("Accept", "application/json");
+    if let Some(authorization) = authorization {
+        request = request.header("Authorization", authorization);
+    }
+
+    let (client, request) = request.build_split();
+    let request = request.map_err(|e| format!("构造元数据请求失败: {}", e))?;
+    cache::throttle(request.url()).await;
+
+    let response = crate::utils::http::send_with_retry(RequestBuilder::from_parts(client, request))
+        .await
+        .map_err(|e| format!("请求元数据接口失败: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("元数据接口请求失败 ({}): {}", status, body));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("读取元数据响应失败: {}", e))
+}
@@ -0,0 +1,388 @@
+//! Bangumi 条目搜索与用户收藏读写
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::{
+    CachePolicy, MetadataMatch, MetadataSource, get_json, get_json_cached, post_json_cached,
+    post_json_no_content,
+};
+
+const BGM_API_BASE_URL: &str = "https://api.bgm.tv/v0";
+/// 条目类型 4 = 游戏
+const BGM_SUBJECT_TYPE_GAME: i32 = 4;
+const BGM_COLLECTION_PAGE_SIZE: usize = 50;
+const SENSITIVE_KEYWORDS: [&str; 6] = ["台独", "港独", "藏独", "分裂", "反华", "辱华"];
+const DEVELOPER_KEYWORDS: [&str; 3] = ["开发", "游戏开发商", "开发商"];
+
+#[derive(Debug, Deserialize)]
+struct BgmSearchResponse {
+    #[serde(default)]
+    data: Vec<BgmSubjectResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmUserResponse {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmCollectionPage {
+    #[serde(default)]
+    data: Vec<BgmCollectionItem>,
+    #[serde(default)]
+    total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmCollectionItem {
+    subject_id: i64,
+    #[serde(rename = "type")]
+    collection_type: i32,
+    #[serde(default)]
+    rate: i32,
+}
+
+/// 用户的单个游戏收藏
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BgmCollectionEntry {
+    pub subject_id: i64,
+    /// 收藏类型：1 想玩 / 2 玩过 / 3 在玩 / 4 搁置 / 5 抛弃，与 games.clear 取值一致
+    pub collection_type: i32,
+    /// 用户评分 1-10，0 表示未评分
+    pub rate: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmSubjectResponse {
+    id: i64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    name_cn: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    nsfw: bool,
+    date: Option<String>,
+    images: Option<BgmImages>,
+    #[serde(default)]
+    infobox: Vec<BgmInfoboxItem>,
+    rating: Option<BgmRating>,
+    #[serde(default)]
+    tags: Vec<BgmTag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmImages {
+    large: Option<String>,
+    common: Option<String>,
+    medium: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmInfoboxItem {
+    key: String,
+    value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmRating {
+    rank: Option<i64>,
+    score: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmTag {
+    name: String,
+    /// 搜索接口返回有效总数，条目详情接口固定返回 0
+    #[serde(default)]
+    total_count: i64,
+}
+
+/// 写入 game_sources.data 的 BGM 数据，字段与前端 `BgmData` 一致
+#[derive(Debug, Deserialize)]
+struct BgmRelatedCharacter {
+    id: i64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    relation: String,
+    images: Option<BgmImages>,
+    #[serde(default)]
+    actors: Vec<BgmPerson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BgmPerson {
+    id: i64,
+    #[serde(default)]
+    name: String,
+}
+
+/// 条目角色及其声优
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BgmCharacter {
+    pub id: i64,
+    pub name: String,
+    /// 主角 / 配角 / 客串
+    pub relation: String,
+    pub image: Option<String>,
+    /// 声优（人物 ID, 名称）
+    pub actors: Vec<(i64, String)>,
+}
+
+#[derive(Debug, Serialize)]
+struct BgmData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    summary: String,
+    name: String,
+    name_cn: String,
+    aliases: Vec<String>,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rank: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    developer: Option<String>,
+    nsfw: bool,
+}
+
+/// 按名称搜索 BGM 游戏条目
+pub async fn search_by_name(
+    name: &str,
+    token: Option<&str>,
+    limit: usize,
+) -> Result<Vec<MetadataMatch>, String> {
+    let body = json!({
+        "keyword": name.trim(),
+        "filter": { "type": [BGM_SUBJECT_TYPE_GAME] },
+    });
+    let url = format!("{}/search/subjects?limit={}", BGM_API_BASE_URL, limit);
+    let response: BgmSearchResponse =
+        post_json_cached(&url, &body, token.map(bearer), CachePolicy::Prefer).await?;
+
+    Ok(response.data.into_iter().map(transform_subject).collect())
+}
+
+fn bearer(token: &str) -> String {
+    format!("Bearer {}", token.trim())
+}
+
+/// 读取单个条目的完整数据
+pub async fn fetch_subject(
+    subject_id: i64,
+    token: Option<&str>,
+    policy: CachePolicy,
+) -> Result<MetadataMatch, String> {
+    let url = format!("{}/subjects/{}", BGM_API_BASE_URL, subject_id);
+    let subject: BgmSubjectResponse = get_json_cached(&url, token.map(bearer), policy).await?;
+    Ok(transform_subject(subject))
+}
+
+/// 读取条目各尺寸的封面地址，按尺寸从大到小排列
+pub async fn fetch_subject_images(
+    subject_id: i64,
+    token: Option<&str>,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let url = format!("{}/subjects/{}", BGM_API_BASE_URL, subject_id);
+    let subject: BgmSubjectResponse =
+        get_json_cached(&url, token.map(bearer), CachePolicy::Prefer).await?;
+    let Some(images) = subject.images else {
+        return Ok(Vec::new());
+    };
+    Ok([
+        ("large", images.large),
+        ("common", images.common),
+        ("medium", images.medium),
+    ]
+    .into_iter()
+    .filter_map(|(size, url)| {
+        url.filter(|url| !url.trim().is_empty())
+            .map(|url| (size, url))
+    })
+    .collect())
+}
+
+/// 读取条目的角色列表，保持 BGM 返回的顺序
+pub async fn fetch_subject_characters(
+    subject_id: i64,
+    token: Option<&str>,
+) -> Result<Vec<BgmCharacter>, String> {
+    let url = format!("{}/subjects/{}/characters", BGM_API_BASE_URL, subject_id);
+    let characters: Vec<BgmRelatedCharacter> =
+        get_json_cached(&url, token.map(bearer), CachePolicy::Prefer).await?;
+    Ok(characters
+        .into_iter()
+        .map(|character| BgmCharacter {
+            id: character.id,
+            name: character.name,
+            relation: character.relation,
+            image: character
+                .images
+                .and_then(|images| images.medium.or(images.large))
+                .filter(|url| !url.trim().is_empty()),
+            actors: character
+                .actors
+                .into_iter()
+                .map(|actor| (actor.id, actor.name))
+                .collect(),
+        })
+        .collect())
+}
+
+/// 获取 Token 对应的用户名
+pub async fn fetch_token_username(token: &str) -> Result<String, String> {
+    let url = format!("{}/me", BGM_API_BASE_URL);
+    let user: BgmUserResponse = get_json(&url, Some(bearer(token))).await?;
+    Ok(user.username)
+}
+
+/// 分页读取用户指定收藏类型的全部游戏收藏
+pub async fn fetch_game_collections(
+    username: &str,
+    token: &str,
+    collection_type: i32,
+) -> Result<Vec<BgmCollectionEntry>, String> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let url = format!(
+            "{}/users/{}/collections?subject_type={}&type={}&limit={}&offset={}",
+            BGM_API_BASE_URL,
+            username,
+            BGM_SUBJECT_TYPE_GAME,
+            collection_type,
+            BGM_COLLECTION_PAGE_SIZE,
+            offset
+        );
+        let page: BgmCollectionPage = get_json(&url, Some(bearer(token))).await?;
+        let count = page.data.len();
+        entries.extend(page.data.into_iter().map(|item| BgmCollectionEntry {
+            subject_id: item.subject_id,
+            collection_type: item.collection_type,
+            rate: item.rate,
+        }));
+        offset += count;
+        if count == 0 || offset >= page.total {
+            break;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 新增或修改当前用户对条目的收藏类型
+pub async fn update_collection_type(
+    subject_id: &str,
+    token: &str,
+    collection_type: i32,
+) -> Result<(), String> {
+    let url = format!("{}/users/-/collections/{}", BGM_API_BASE_URL, subject_id);
+    let body = json!({ "type": collection_type });
+    post_json_no_content(&url, &body, Some(bearer(token))).await
+}
+
+fn transform_subject(subject: BgmSubjectResponse) -> MetadataMatch {
+    let aliases = subject
+        .infobox
+        .iter()
+        .find(|item| item.key == "别名")
+        .map(|item| match &item.value {
+            Value::Array(values) => values
+                .iter()
+                .filter_map(|value| match value {
+                    Value::String(alias) => Some(alias.clone()),
+                    _ => value.get("v").and_then(Value::as_str).map(str::to_owned),
+                })
+                .collect(),
+            Value::String(alias) => vec![alias.clone()],
+            _ => Vec::new(),
+        })
+        .unwrap_or_default();
+
+    let tags = subject
+        .tags
+        .iter()
+        .filter(|tag| tag.total_count != 1)
+        .filter(|tag| !SENSITIVE_KEYWORDS.iter().any(|kw| tag.name.contains(kw)))
+        .map(|tag| tag.name.clone())
+        .collect();
+
+    let mut developers: Vec<String> = Vec::new();
+    for item in &subject.infobox {
+        if !DEVELOPER_KEYWORDS.contains(&item.key.as_str()) {
+            continue;
+        }
+        let Some(value) = item.value.as_str() else {
+            continue;
+        };
+        for name in value.split(['、', '×']).map(str::trim) {
+            if !name.is_empty() && !developers.iter().any(|existing| existing == name) {
+                developers.push(name.to_string());
+            }
+        }
+    }
+
+    let data = BgmData {
+        date: subject.date,
+        image: subject.images.and_then(|images| images.large),
+        summary: subject.summary,
+        name: subject.name,
+        name_cn: subject.name_cn,
+        aliases,
+        tags,
+        rank: subject.rating.as_ref().and_then(|rating| rating.rank),
+        score: subject.rating.as_ref().and_then(|rating| rating.score),
+        developer: (!developers.is_empty()).then(|| developers.join("/")),
+        nsfw: subject.nsfw,
+    };
+
+    MetadataMatch {
+        source: MetadataSource::Bgm,
+        external_id: subject.id.to_string(),
+        data: serde_json::to_value(data).unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transforms_search_subject_like_frontend() {
+        let subject: BgmSubjectResponse = serde_json::from_value(json!({
+            "id": 12345,
+            "name": "ゲーム",
+            "name_cn": "游戏",
+            "summary": "简介",
+            "nsfw": true,
+            "date": "2020-01-31",
+            "images": { "large": "https://lain.bgm.tv/pic/cover/l/12345.jpg" },
+            "infobox": [
+                { "key": "别名", "value": [{ "v": "Game" }, { "v": "ゲム" }] },
+                { "key": "开发", "value": "社A、社B" },
+                { "key": "游戏开发商", "value": "社B×社C" }
+            ],
+            "rating": { "rank": 100, "score": 8.1 },
+            "tags": [
+                { "name": "纯爱", "total_count": 20 },
+                { "name": "冷门", "total_count": 1 }
+            ]
+        }))
+        .unwrap();
+
+        let matched = transform_subject(subject);
+        assert_eq!(matched.external_id, "12345");
+        assert_eq!(matched.data["aliases"], json!(["Game", "ゲム"]));
+        assert_eq!(matched.data["tags"], json!(["纯爱"]));
+        assert_eq!(matched.data["developer"], "社A/社B/社C");
+        assert_eq!(matched.data["date"], "2020-01-31");
+        assert_eq!(matched.data["nsfw"], true);
+    }
+}
@@ -0,0 +1,207 @@
+//! 元数据请求限流与响应缓存
+//!
+//! 同一主机的请求至少间隔 [`HOST_MIN_INTERVAL`]，批量刷新时不会触发 BGM / VNDB 的限流。
+//! 条目查询与搜索的响应缓存在数据目录下的 `cache/metadata` 中，[`METADATA_CACHE_TTL_SECS`]
+//! 内重复请求直接读取缓存；用户收藏等随账号变化的接口不缓存。
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::command;
+use tauri_plugin_http::reqwest::Url;
+use tokio::time::Instant;
+
+/// 同一主机相邻两次请求的最小间隔
+const HOST_MIN_INTERVAL: Duration = Duration::from_secs(1);
+/// 响应缓存的有效期
+const METADATA_CACHE_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// 各主机下一次允许发出请求的时间
+static HOST_NEXT_SLOT: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    cached_at: i64,
+    body: String,
+}
+
+/// 等待到该主机允许发出下一次请求
+///
+/// 先预约时间槽再等待，同一主机的并发请求依次排队。
+pub async fn throttle(url: &Url) {
+    let Some(host) = url.host_str() else {
+        return;
+    };
+    let slot = {
+        let mut slots = HOST_NEXT_SLOT.lock();
+        let now = Instant::now();
+        let slot = slots
+            .get(host)
+            .copied()
+            .filter(|next| *next > now)
+            .unwrap_or(now);
+        slots.insert(host.to_string(), slot + HOST_MIN_INTERVAL);
+        slot
+    };
+    tokio::time::sleep_until(slot).await;
+}
+
+fn cache_dir() -> Result<PathBuf, String> {
+    Ok(reina_path::get_base_data_dir()?
+        .join("cache")
+        .join("metadata"))
+}
+
+/// 由请求方法、地址、请求体与授权信息生成缓存键
+///
+/// 缓存文件跨版本保留，使用 SHA-256 而不是 `DefaultHasher`，后者的算法不保证在不同 Rust 版本间稳定。
+/// 各部分先写入长度，避免不同字段拼接后产生相同的输入。
+pub fn cache_key(
+    method: &str,
+    url: &str,
+    body: Option<&[u8]>,
+    authorization: Option<&str>,
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        Some(method.as_bytes()),
+        Some(url.as_bytes()),
+        body,
+        authorization.map(str::as_bytes),
+    ] {
+        match part {
+            Some(bytes) => {
+                hasher.update([1]);
+                hasher.update((bytes.len() as u64).to_le_bytes());
+                hasher.update(bytes);
+            }
+            None => hasher.update([0]),
+        }
+    }
+    hasher
+        .finalize()
+        .iter()
+        .fold(String::with_capacity(64), |mut key, byte| {
+            let _ = write!(key, "{byte:02x}");
+            key
+        })
+}
+
+fn is_fresh(entry: &CacheEntry, now: i64) -> bool {
+    (0..METADATA_CACHE_TTL_SECS).contains(&(now - entry.cached_at))
+}
+
+/// 读取未过期的缓存响应
+pub async fn read_cached(key: &str) -> Option<String> {
+    let path = cache_dir().ok()?.join(format!("{key}.json"));
+    let content = tokio::fs::read(&path).await.ok()?;
+    let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+    is_fresh(&entry, chrono::Utc::now().timestamp()).then_some(entry.body)
+}
+
+/// 写入响应缓存，失败时只记录日志
+pub async fn write_cached(key: &str, body: &str) {
+    let result = async {
+        let dir = cache_dir()?;
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| format!("创建缓存目录失败: {}", e))?;
+        let entry = CacheEntry {
+            cached_at: chrono::Utc::now().timestamp(),
+            body: body.to_string(),
+        };
+        let content = serde_json::to_vec(&entry).map_err(|e| format!("序列化缓存失败: {}", e))?;
+        tokio::fs::write(dir.join(format!("{key}.json")), content)
+            .await
+            .map_err(|e| format!("写入缓存失败: {}", e))
+    }
+    .await;
+    if let Err(e) = result {
+        log::warn!("元数据响应缓存写入失败: {}", e);
+    }
+}
+
+/// 清空元数据响应缓存，返回删除的文件数量
+#[command]
+pub async fn clear_metadata_cache() -> Result<usize, String> {
+    let dir = cache_dir()?;
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("读取缓存目录失败: {}", e)),
+    };
+
+    let mut removed = 0;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("读取缓存目录失败: {}", e))?
+    {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            tokio::fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("删除缓存文件失败: {}", e))?;
+            removed += 1;
+        }
+    }
+    log::info!("已清空元数据缓存 removed={}", removed);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_and_freshness() {
+        let key = cache_key("POST", "https://api.vndb.org/kana/vn", Some(b"{}"), None);
+        assert_eq!(key.len(), 64);
+        assert!(key.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        assert_eq!(
+            key,
+            cache_key("POST", "https://api.vndb.org/kana/vn", Some(b"{}"), None)
+        );
+        assert_ne!(
+            key,
+            cache_key(
+                "POST",
+                "https://api.vndb.org/kana/vn",
+                Some(b"{}"),
+                Some("Token a")
+            )
+        );
+        assert_ne!(
+            cache_key("GET", "https://bgm.tv/a", None, None),
+            cache_key("GET", "https://bgm.tv/a", Some(b""), None)
+        );
+
+        let entry = |cached_at| CacheEntry {
+            cached_at,
+            body: String::new(),
+        };
+        let now = 1_700_000_000;
+        assert!(is_fresh(&entry(now - 60), now));
+        assert!(!is_fresh(&entry(now - METADATA_CACHE_TTL_SECS), now));
+        assert!(!is_fresh(&entry(now + 60), now));
+    }
+
+    #[tokio::test]
+    async fn throttles_requests_per_host() {
+        let first = Url::parse("https://throttle-a.invalid/kana/vn").unwrap();
+        let second = Url::parse("https://throttle-b.invalid/v0/subjects/1").unwrap();
+        let start = Instant::now();
+
+        throttle(&first).await;
+        throttle(&second).await;
+        assert!(start.elapsed() < HOST_MIN_INTERVAL);
+        throttle(&first).await;
+        assert!(start.elapsed() >= HOST_MIN_INTERVAL);
+    }
+}
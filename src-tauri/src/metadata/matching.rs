@@ -0,0 +1,438 @@
+//! 按名称自动匹配元数据
+//!
+//! 目录名常带有汉化组、版本号、`(18禁)` 等噪声，搜索前先清洗；
+//! 候选条目的置信度为清洗后的名称与条目各标题（原名、中文名、别名）的最高相似度。
+
+use super::{MetadataMatch, MetadataSource, bgm, vndb};
+use crate::database::dto::{FullGameData, UpdateGameData};
+use crate::database::repository::games_repository::GamesRepository;
+use crate::game::duplicates::{normalize_title, title_similarity};
+use crate::utils::bgm_auth::valid_bgm_auth;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::{State, command};
+
+/// 每个数据源返回的候选数量
+const AUTO_MATCH_CANDIDATE_LIMIT: usize = 5;
+/// 批量自动采用的默认最低置信度
+const DEFAULT_MIN_CONFIDENCE: f64 = 0.7;
+/// 成对出现的括号，括号内的内容视为噪声
+const BRACKET_PAIRS: [(char, char); 7] = [
+    ('[', ']'),
+    ('【', '】'),
+    ('(', ')'),
+    ('（', '）'),
+    ('{', '}'),
+    ('〔', '〕'),
+    ('［', '］'),
+];
+/// 名称末尾常见的发行形式后缀
+const NOISE_SUFFIXES: [&str; 12] = [
+    "汉化硬盘版",
+    "汉化版",
+    "硬盘版",
+    "免安装版",
+    "免安装",
+    "中文版",
+    "完整版",
+    "绿色版",
+    "DL版",
+    "PC版",
+    "Steam版",
+    "パッケージ版",
+];
+
+/// 单个候选条目
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchCandidate {
+    pub source: MetadataSource,
+    pub external_id: String,
+    pub name: String,
+    pub name_cn: Option<String>,
+    /// 0 ~ 1，越高越可能是同一作品
+    pub confidence: f64,
+    pub data: Value,
+}
+
+/// 自动匹配结果
+#[derive(Debug, Serialize)]
+pub struct AutoMatchResult {
+    /// 清洗后用于搜索的关键词
+    pub keyword: String,
+    /// 按置信度从高到低排列的候选
+    pub candidates: Vec<MatchCandidate>,
+    /// 搜索失败的数据源及原因
+    pub errors: Vec<String>,
+}
+
+/// 批量自动采用中单个游戏的结果
+#[derive(Debug, Serialize)]
+pub struct AutoApplyItem {
+    pub game_id: i32,
+    pub keyword: String,
+    pub source: Option<MetadataSource>,
+    pub external_id: Option<String>,
+    pub confidence: Option<f64>,
+    pub applied: bool,
+    pub error: Option<String>,
+}
+
+/// 批量自动采用结果
+#[derive(Debug, Default, Serialize)]
+pub struct AutoApplyReport {
+    pub total: usize,
+    pub applied: usize,
+    pub items: Vec<AutoApplyItem>,
+}
+
+/// 去掉括号包裹的内容（可嵌套），缺少右括号时保留剩余内容
+fn strip_brackets(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut stack: Vec<char> = Vec::new();
+    let mut pending = String::new();
+    for ch in name.chars() {
+        if let Some((_, close)) = BRACKET_PAIRS.iter().find(|(open, _)| *open == ch) {
+            stack.push(*close);
+            pending.push(ch);
+        } else if stack.last() == Some(&ch) {
+            stack.pop();
+            if stack.is_empty() {
+                pending.clear();
+                result.push(' ');
+            } else {
+                pending.push(ch);
+            }
+        } else if stack.is_empty() {
+            result.push(ch);
+        } else {
+            pending.push(ch);
+        }
+    }
+    result.push_str(&pending);
+    result
+}
+
+/// 版本号片段，如 `v1.02`、`ver2.0`、`1.0.3`
+fn is_version_token(token: &str) -> bool {
+    let lower = token.to_ascii_lowercase();
+    let digits = lower
+        .strip_prefix("ver.")
+        .or_else(|| lower.strip_prefix("ver"))
+        .or_else(|| lower.strip_prefix('v'))
+        .unwrap_or(&lower);
+    digits.starts_with(|ch: char| ch.is_ascii_digit())
+        && digits.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+        && (digits.contains('.') || digits.len() < lower.len())
+}
+
+/// 清洗目录名或游戏名，得到适合搜索的关键词
+///
+/// 去掉括号内容、版本号与常见发行形式后缀，清洗后为空时返回原名称。
+pub fn clean_search_name(name: &str) -> String {
+    let stripped = strip_brackets(&name.replace('_', " "));
+    let mut tokens: Vec<&str> = stripped
+        .split_whitespace()
+        .filter(|token| !is_version_token(token))
+        .collect();
+
+    if let Some(mut last) = tokens.pop() {
+        while let Some(rest) = NOISE_SUFFIXES
+            .iter()
+            .find_map(|suffix| last.strip_suffix(suffix))
+        {
+            last = rest.trim_end_matches(['-', ' ']);
+        }
+        tokens.push(last);
+    }
+    let cleaned = tokens
+        .into_iter()
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let cleaned = cleaned.trim_matches(['-', ' ']);
+
+    if cleaned.is_empty() {
+        name.trim().to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// 条目中可用于比较的全部标题
+fn candidate_titles(data: &Value) -> Vec<&str> {
+    let single = ["name", "name_cn"]
+        .into_iter()
+        .filter_map(|field| data.get(field).and_then(Value::as_str));
+    let lists = ["aliases", "all_titles"]
+        .into_iter()
+        .filter_map(|field| data.get(field).and_then(Value::as_array))
+        .flatten()
+        .filter_map(Value::as_str);
+    single
+        .chain(lists)
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .collect()
+}
+
+/// 关键词与条目标题的最高相似度，保留两位小数
+fn match_confidence(keyword: &str, data: &Value) -> f64 {
+    let keyword = normalize_title(keyword);
+    if keyword.is_empty() {
+        return 0.0;
+    }
+    let best = candidate_titles(data)
+        .into_iter()
+        .map(|title| title_similarity(&keyword, &normalize_title(title)))
+        .fold(0.0, f64::max);
+    (best * 100.0).round() / 100.0
+}
+
+fn to_candidate(keyword: &str, matched: MetadataMatch) -> MatchCandidate {
+    let text = |field: &str| {
+        matched
+            .data
+            .get(field)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    };
+    MatchCandidate {
+        confidence: match_confidence(keyword, &matched.data),
+        name: text("name").unwrap_or_default(),
+        name_cn: text("name_cn"),
+        source: matched.source,
+        external_id: matched.external_id,
+        data: matched.data,
+    }
+}
+
+/// 游戏用于匹配的原始名称：目录名优先，其次为展示名称
+pub(crate) fn game_match_name(game: &FullGameData) -> Option<String> {
+    game.localpath
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| game.display_name())
+}
+
+/// 在各数据源搜索候选，按置信度从高到低排序
+async fn search_candidates(
+    db: &DatabaseConnection,
+    keyword: &str,
+    sources: &[MetadataSource],
+) -> AutoMatchResult {
+    let mut candidates = Vec::new();
+    let mut errors = Vec::new();
+    for source in sources {
+        let results = match source {
+            MetadataSource::Bgm => {
+                let token = valid_bgm_auth(db)
+                    .await
+                    .ok()
+                    .map(|auth| auth.access_token.trim().to_string())
+                    .filter(|token| !token.is_empty());
+                bgm::search_by_name(keyword, token.as_deref(), AUTO_MATCH_CANDIDATE_LIMIT).await
+            }
+            MetadataSource::Vndb => vndb::search_by_name(keyword, AUTO_MATCH_CANDIDATE_LIMIT).await,
+        };
+        match results {
+            Ok(results) => candidates.extend(
+                results
+                    .into_iter()
+                    .map(|matched| to_candidate(keyword, matched)),
+            ),
+            Err(e) => {
+                log::warn!(
+                    "自动匹配搜索失败 source={} keyword={}: {}",
+                    source.as_str(),
+                    keyword,
+                    e
+                );
+                errors.push(format!("{}: {}", source.as_str(), e));
+            }
+        }
+    }
+    // 置信度相同时保持数据源与搜索排名的原有顺序
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    AutoMatchResult {
+        keyword: keyword.to_string(),
+        candidates,
+        errors,
+    }
+}
+
+fn resolve_sources(sources: Option<Vec<MetadataSource>>) -> Vec<MetadataSource> {
+    sources
+        .filter(|sources| !sources.is_empty())
+        .unwrap_or_else(|| vec![MetadataSource::Bgm, MetadataSource::Vndb])
+}
+
+/// 自动匹配元数据，返回候选列表与置信度
+///
+/// # Arguments
+/// * `game_id` - 游戏ID，未指定 `name` 时使用游戏目录名（或展示名称）
+/// * `name` - 直接指定搜索名称
+/// * `sources` - 搜索的数据源，为空时搜索 bgm 与 vndb
+#[command]
+pub async fn auto_match_metadata(
+    db: State<'_, DatabaseConnection>,
+    game_id: Option<i32>,
+    name: Option<String>,
+    sources: Option<Vec<MetadataSource>>,
+) -> Result<AutoMatchResult, String> {
+    let raw_name = match (name.filter(|name| !name.trim().is_empty()), game_id) {
+        (Some(name), _) => name,
+        (None, Some(game_id)) => {
+            let game = GamesRepository::find_full_games_in_order(db.inner(), &[game_id])
+                .await
+                .map_err(|e| format!("查询游戏数据失败: {}", e))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("游戏不存在: {}", game_id))?;
+            game_match_name(&game).ok_or_else(|| "游戏没有可用于匹配的名称".to_string())?
+        }
+        (None, None) => return Err("请指定游戏或搜索名称".to_string()),
+    };
+
+    let keyword = clean_search_name(&raw_name);
+    Ok(search_candidates(&db, &keyword, &resolve_sources(sources)).await)
+}
+
+/// 批量自动匹配并采用置信度最高的候选
+///
+/// 最高置信度低于 `min_confidence`（默认 0.7）或已绑定该数据源的游戏不做修改；
+/// 采用后自定义游戏的 `id_type` 改为对应数据源，已绑定其他数据源的改为 `mixed`。
+#[command]
+pub async fn auto_apply_metadata(
+    db: State<'_, DatabaseConnection>,
+    game_ids: Vec<i32>,
+    sources: Option<Vec<MetadataSource>>,
+    min_confidence: Option<f64>,
+) -> Result<AutoApplyReport, String> {
+    let sources = resolve_sources(sources);
+    let min_confidence = min_confidence.unwrap_or(DEFAULT_MIN_CONFIDENCE);
+    let games = GamesRepository::find_full_games_in_order(db.inner(), &game_ids)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?;
+
+    let mut report = AutoApplyReport {
+        total: games.len(),
+        ..Default::default()
+    };
+    for game in games {
+        let keyword = game_match_name(&game)
+            .map(|name| clean_search_name(&name))
+            .unwrap_or_default();
+        let mut item = AutoApplyItem {
+            game_id: game.id,
+            keyword: keyword.clone(),
+            source: None,
+            external_id: None,
+            confidence: None,
+            applied: false,
+            error: None,
+        };
+        if keyword.is_empty() {
+            item.error = Some("游戏没有可用于匹配的名称".to_string());
+            report.items.push(item);
+            continue;
+        }
+
+        let result = search_candidates(&db, &keyword, &sources).await;
+        let Some(best) = result.candidates.into_iter().next() else {
+            item.error = Some(if result.errors.is_empty() {
+                "未找到匹配条目".to_string()
+            } else {
+                result.errors.join("; ")
+            });
+            report.items.push(item);
+            continue;
+        };
+        item.source = Some(best.source);
+        item.external_id = Some(best.external_id.clone());
+        item.confidence = Some(best.confidence);
+
+        let source = best.source.as_str();
+        if best.confidence < min_confidence {
+            item.error = Some("置信度低于阈值".to_string());
+        } else if game.sources.iter().any(|bound| bound.source == source) {
+            item.error = Some(format!("已绑定 {} 条目", source));
+        } else {
+            let id_type = match game.id_type.as_str() {
+                "custom" => source,
+                current if current == source => source,
+                _ => "mixed",
+            };
+            let updates = UpdateGameData {
+                id_type: Some(id_type.to_string()),
+                upsert_sources: Some(vec![
+                    MetadataMatch {
+                        source: best.source,
+                        external_id: best.external_id,
+                        data: best.data,
+                    }
+                    .into_source_data(),
+                ]),
+                ..Default::default()
+            };
+            match GamesRepository::update(&db, game.id, updates).await {
+                Ok(_) => {
+                    item.applied = true;
+                    report.applied += 1;
+                }
+                Err(e) => item.error = Some(format!("保存元数据失败: {}", e)),
+            }
+        }
+        report.items.push(item);
+    }
+
+    log::info!(
+        "批量自动匹配元数据完成 total={} applied={}",
+        report.total,
+        report.applied
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cleans_directory_names() {
+        assert_eq!(
+            clean_search_name("[某汉化组] 千恋＊万花 (18禁) v1.02"),
+            "千恋＊万花"
+        );
+        assert_eq!(
+            clean_search_name("【PC】魔女的夜宴汉化硬盘版"),
+            "魔女的夜宴"
+        );
+        assert_eq!(
+            clean_search_name("Summer_Pockets_REFLECTION_BLUE [Key]"),
+            "Summer Pockets REFLECTION BLUE"
+        );
+        assert_eq!(clean_search_name("[汉化组]"), "[汉化组]");
+        assert_eq!(clean_search_name("Riddle Joker ver1.10"), "Riddle Joker");
+    }
+
+    #[test]
+    fn scores_candidates_by_best_title() {
+        let data = json!({
+            "name": "千恋＊万花",
+            "name_cn": "",
+            "aliases": ["Senren＊Banka"],
+        });
+        assert_eq!(match_confidence("Senren Banka", &data), 1.0);
+        assert_eq!(match_confidence("千恋＊万花", &data), 1.0);
+        assert!(match_confidence("Riddle Joker", &data) < 0.2);
+        assert_eq!(match_confidence("", &data), 0.0);
+    }
+}
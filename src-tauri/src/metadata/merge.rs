@@ -0,0 +1,307 @@
+//! 多数据源元数据的字段级合并
+//!
+//! 同时绑定多个数据源时，按用户设置的 [`MetadataMergeStrategy`] 逐字段选取数据源，
+//! 生成供前端直接展示的合并视图。`custom_data` 中的自定义值始终优先。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::custom_data::SourceType;
+use crate::entity::user::MetadataMergeStrategy;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tauri::{State, command};
+
+/// 字段值来自 `custom_data` 时的来源标识
+const CUSTOM_SOURCE: &str = "custom";
+
+/// 合并后的展示数据
+#[derive(Debug, Default, Serialize)]
+pub struct MergedGameView {
+    pub game_id: i32,
+    /// 展示标题：各数据源中文名优先
+    pub title: Option<String>,
+    pub name: Option<String>,
+    pub name_cn: Option<String>,
+    pub image: Option<String>,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub date: Option<String>,
+    pub developer: Option<String>,
+    pub score: Option<f64>,
+    /// 各字段的取值来源：数据源标识或 `custom`
+    pub field_sources: BTreeMap<String, String>,
+}
+
+fn non_empty_str(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn string_field(data: &Value, field: &str) -> Option<String> {
+    data.get(field)
+        .and_then(Value::as_str)
+        .and_then(non_empty_str)
+}
+
+fn string_list_field(data: &Value, field: &str) -> Option<Vec<String>> {
+    let values: Vec<String> = data
+        .get(field)?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(non_empty_str)
+        .collect();
+    (!values.is_empty()).then_some(values)
+}
+
+fn source_type_str(source: &SourceType) -> &'static str {
+    match source {
+        SourceType::Bgm => "bgm",
+        SourceType::Vndb => "vndb",
+        SourceType::Ymgal => "ymgal",
+        SourceType::Kun => "kun",
+    }
+}
+
+struct Merger<'a> {
+    game: &'a FullGameData,
+    strategy: &'a MetadataMergeStrategy,
+    field_sources: BTreeMap<String, String>,
+}
+
+impl<'a> Merger<'a> {
+    /// 字段的数据源读取顺序：策略配置的优先级在前，其余按默认顺序
+    fn source_order(&self, field: &str, preferred: Option<&'a str>) -> Vec<&'a str> {
+        let mut order: Vec<&'a str> = Vec::new();
+        let configured = self.strategy.priority(field).iter().map(String::as_str);
+        for source in preferred
+            .into_iter()
+            .chain(configured)
+            .chain(self.game.sources_by_priority())
+        {
+            if !order.contains(&source) {
+                order.push(source);
+            }
+        }
+        order
+    }
+
+    /// 按顺序取第一个能提取出值的数据源，并记录取值来源
+    fn pick<T>(
+        &mut self,
+        field: &str,
+        preferred: Option<&'a str>,
+        extract: impl Fn(&Value) -> Option<T>,
+    ) -> Option<T> {
+        let game = self.game;
+        self.source_order(field, preferred)
+            .into_iter()
+            .find_map(|source| {
+                let data = game
+                    .sources
+                    .iter()
+                    .find(|item| item.source == source)?
+                    .data
+                    .as_ref()?;
+                extract(data).map(|value| (source, value))
+            })
+            .map(|(source, value)| {
+                self.field_sources
+                    .insert(field.to_string(), source.to_string());
+                value
+            })
+    }
+
+    /// 自定义值优先，没有时按数据源合并
+    fn pick_or_custom<T>(
+        &mut self,
+        field: &str,
+        custom: Option<T>,
+        extract: impl Fn(&Value) -> Option<T>,
+    ) -> Option<T> {
+        if custom.is_some() {
+            self.field_sources
+                .insert(field.to_string(), CUSTOM_SOURCE.to_string());
+            return custom;
+        }
+        self.pick(field, None, extract)
+    }
+}
+
+/// 按合并策略生成单个游戏的展示数据
+pub fn merged_view(game: &FullGameData, strategy: &MetadataMergeStrategy) -> MergedGameView {
+    let custom = game.custom_data.clone().unwrap_or_default();
+    let custom_string = |value: Option<String>| value.as_deref().and_then(non_empty_str);
+    let custom_list = |value: Option<Vec<String>>| {
+        let values: Vec<String> = value
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| non_empty_str(item))
+            .collect();
+        (!values.is_empty()).then_some(values)
+    };
+
+    let mut merger = Merger {
+        game,
+        strategy,
+        field_sources: BTreeMap::new(),
+    };
+    let title = merger.pick_or_custom("title", custom_string(custom.name), |data| {
+        string_field(data, "name_cn").or_else(|| string_field(data, "name"))
+    });
+    let name = merger.pick("name", None, |data| string_field(data, "name"));
+    let name_cn = merger.pick("name_cn", None, |data| string_field(data, "name_cn"));
+    let image = match custom_string(custom.image) {
+        Some(image) => {
+            merger
+                .field_sources
+                .insert("image".to_string(), CUSTOM_SOURCE.to_string());
+            Some(image)
+        }
+        // Mixed 模式下手动选定的封面数据源优先于合并策略
+        None => merger.pick(
+            "image",
+            custom.cover_source.as_ref().map(source_type_str),
+            |data| string_field(data, "image"),
+        ),
+    };
+    let summary = merger.pick_or_custom("summary", custom_string(custom.summary), |data| {
+        string_field(data, "summary")
+    });
+    let tags = merger.pick_or_custom("tags", custom_list(custom.tags), |data| {
+        string_list_field(data, "tags")
+    });
+    let aliases = merger.pick_or_custom("aliases", custom_list(custom.aliases), |data| {
+        string_list_field(data, "aliases")
+    });
+    let date = merger
+        .pick("date", None, |data| string_field(data, "date"))
+        .or_else(|| game.date.as_deref().and_then(non_empty_str));
+    let developer = merger.pick_or_custom("developer", custom_string(custom.developer), |data| {
+        string_field(data, "developer")
+    });
+    let score = merger.pick("score", None, |data| {
+        data.get("score")
+            .and_then(Value::as_f64)
+            .filter(|score| *score > 0.0)
+    });
+
+    MergedGameView {
+        game_id: game.id,
+        title,
+        name,
+        name_cn,
+        image,
+        summary,
+        tags: tags.unwrap_or_default(),
+        aliases: aliases.unwrap_or_default(),
+        date,
+        developer,
+        score,
+        field_sources: merger.field_sources,
+    }
+}
+
+/// 按用户设置的合并策略获取游戏的合并视图，结果顺序与输入一致
+#[command]
+pub async fn get_merged_views(
+    db: State<'_, DatabaseConnection>,
+    game_ids: Vec<i32>,
+) -> Result<Vec<MergedGameView>, String> {
+    let strategy = db.get_settings().await?.metadata_merge_strategy();
+    let games = GamesRepository::find_full_games_in_order(db.inner(), &game_ids)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?;
+    Ok(games
+        .iter()
+        .map(|game| merged_view(game, &strategy))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_fields_by_strategy() {
+        let game: FullGameData = serde_json::from_value(json!({
+            "id": 1,
+            "id_type": "mixed",
+            "custom_data": { "tags": ["收藏"] },
+            "sources": [
+                {
+                    "source": "bgm",
+                    "external_id": "100",
+                    "data": {
+                        "name": "千恋＊万花",
+                        "name_cn": "千恋万花",
+                        "image": "https://lain.bgm.tv/cover.jpg",
+                        "summary": "",
+                        "tags": ["纯爱"],
+                        "score": 7.6,
+                    },
+                },
+                {
+                    "source": "vndb",
+                    "external_id": "v19073",
+                    "data": {
+                        "name": "Senren＊Banka",
+                        "image": "https://t.vndb.org/cover.jpg",
+                        "summary": "VNDB 简介",
+                        "score": 8.2,
+                    },
+                },
+            ],
+        }))
+        .unwrap();
+        let strategy = MetadataMergeStrategy {
+            fields: BTreeMap::from([
+                ("image".to_string(), vec!["vndb".to_string()]),
+                ("score".to_string(), vec!["vndb".to_string()]),
+            ]),
+        };
+
+        let view = merged_view(&game, &strategy);
+        assert_eq!(view.title.as_deref(), Some("千恋万花"));
+        assert_eq!(view.name.as_deref(), Some("千恋＊万花"));
+        assert_eq!(view.image.as_deref(), Some("https://t.vndb.org/cover.jpg"));
+        assert_eq!(view.summary.as_deref(), Some("VNDB 简介"));
+        assert_eq!(view.tags, ["收藏"]);
+        assert_eq!(view.score, Some(8.2));
+        assert_eq!(view.field_sources["title"], "bgm");
+        assert_eq!(view.field_sources["summary"], "vndb");
+        assert_eq!(view.field_sources["tags"], "custom");
+
+        let view = merged_view(&game, &MetadataMergeStrategy::default());
+        assert_eq!(view.image.as_deref(), Some("https://lain.bgm.tv/cover.jpg"));
+        assert_eq!(view.score, Some(7.6));
+    }
+
+    #[test]
+    fn cleans_strategy() {
+        let strategy = MetadataMergeStrategy {
+            fields: BTreeMap::from([
+                (
+                    "summary".to_string(),
+                    vec![
+                        " YMGal ".to_string(),
+                        "ymgal".to_string(),
+                        "steam".to_string(),
+                    ],
+                ),
+                ("unknown".to_string(), vec!["bgm".to_string()]),
+                ("image".to_string(), Vec::new()),
+            ]),
+        }
+        .cleaned();
+        assert_eq!(
+            strategy.fields,
+            BTreeMap::from([("summary".to_string(), vec!["ymgal".to_string()])])
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! 批量刷新元数据
+//!
+//! 按游戏已绑定的条目 ID 在后台逐个重新拉取 BGM / VNDB 数据并写回 `game_sources.data`，
+//! 每处理一个条目发送 `metadata-refresh-progress` 事件，完成后发送
+//! `metadata-refresh-finished` 事件并附带失败列表。任务登记在 [`TaskManager`]，可通过
+//! `cancel_task` 取消，取消后不再处理剩余条目。
+
+use super::{CachePolicy, MetadataMatch, bgm, vndb};
+use crate::database::dto::UpdateGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::utils::bgm_auth::valid_bgm_auth;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Emitter, Runtime, State, command};
+
+/// 未指定数据源时刷新的数据源
+const DEFAULT_REFRESH_SOURCES: [&str; 2] = ["bgm", "vndb"];
+
+/// 单个待刷新的条目
+struct RefreshTask {
+    game_id: i32,
+    source: String,
+    external_id: String,
+    /// 当前保存的元数据，仅刷新缺失字段时用于合并
+    existing: Option<Value>,
+}
+
+/// 刷新失败的条目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshFailure {
+    game_id: i32,
+    source: String,
+    error: String,
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// 只用新数据补齐现有数据中为空的字段，已有的非空字段保持不变
+fn fill_missing(existing: Option<Value>, fetched: Value) -> Value {
+    match (existing, fetched) {
+        (Some(Value::Object(mut current)), Value::Object(fetched)) => {
+            for (key, value) in fetched {
+                if current.get(&key).is_none_or(is_empty_value) {
+                    current.insert(key, value);
+                }
+            }
+            Value::Object(current)
+        }
+        (Some(current), _) if !is_empty_value(&current) => current,
+        (_, fetched) => fetched,
+    }
+}
+
+async fn fetch_source(
+    db: &DatabaseConnection,
+    source: &str,
+    external_id: &str,
+) -> Result<MetadataMatch, String> {
+    match source {
+        "bgm" => {
+            let subject_id = external_id
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("无效的 Bangumi 条目 ID: {}", external_id))?;
+            let token = valid_bgm_auth(db)
+                .await
+                .ok()
+                .map(|auth| auth.access_token.trim().to_string())
+                .filter(|token| !token.is_empty());
+            bgm::fetch_subject(subject_id, token.as_deref(), CachePolicy::Refresh).await
+        }
+        "vndb" => vndb::fetch_vn(external_id, CachePolicy::Refresh)
+            .await?
+            .ok_or_else(|| format!("VNDB 条目不存在: {}", external_id)),
+        _ => Err(format!("暂不支持刷新 {} 数据源", source)),
+    }
+}
+
+async fn refresh_one(
+    db: &DatabaseConnection,
+    task: RefreshTask,
+    missing_only: bool,
+) -> Result<(), String> {
+    let mut source_data = fetch_source(db, &task.source, &task.external_id)
+        .await?
+        .into_source_data();
+    if missing_only {
+        source_data.data = source_data
+            .data
+            .map(|fetched| fill_missing(task.existing, fetched));
+    }
+
+    let updates = UpdateGameData {
+        upsert_sources: Some(vec![source_data]),
+        ..Default::default()
+    };
+    GamesRepository::update(db, task.game_id, updates)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("保存元数据失败: {}", e))
+}
+
+/// 在后台批量刷新游戏元数据
+///
+/// 立即返回待刷新的条目数量；游戏未绑定的数据源直接跳过。
+///
+/// # Arguments
+/// * `game_ids` - 待刷新的游戏 ID
+/// * `sources` - 刷新的数据源，为空时刷新 bgm 与 vndb
+/// * `missing_only` - 为 true 时只补齐缺失字段，保留已有数据
+#[command]
+pub async fn refresh_metadata_batch<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+    game_ids: Vec<i32>,
+    sources: Option<Vec<String>>,
+    missing_only: Option<bool>,
+) -> Result<usize, String> {
+    let sources: Vec<String> = sources
+        .filter(|sources| !sources.is_empty())
+        .unwrap_or_else(|| DEFAULT_REFRESH_SOURCES.map(String::from).to_vec());
+    let missing_only = missing_only.unwrap_or(false);
+
+    let games = GamesRepository::find_full_games_in_order(db.inner(), &game_ids)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?;
+    let items: Vec<RefreshTask> = games
+        .into_iter()
+        .flat_map(|game| {
+            let game_id = game.id;
+            game.sources.into_iter().filter_map(move |item| {
+                let external_id = item.external_id.filter(|id| !id.trim().is_empty())?;
+                Some(RefreshTask {
+                    game_id,
+                    source: item.source,
+                    external_id,
+                    existing: item.data,
+                })
+            })
+        })
+        .filter(|item| sources.contains(&item.source))
+        .collect();
+    let total = items.len();
+    let db = db.inner().clone();
+
+    let events = app_handle.clone();
+    tasks.spawn(
+        &app_handle,
+        TaskKind::MetadataRefresh,
+        move |task| async move {
+            let mut failures = Vec::new();
+            let mut processed = 0_usize;
+            for item in items {
+                if task.is_cancelled() {
+                    log::info!(
+                        "元数据批量刷新已取消 processed={} total={}",
+                        processed,
+                        total
+                    );
+                    break;
+                }
+                processed += 1;
+                let game_id = item.game_id;
+                let source = item.source.clone();
+                let result = refresh_one(&db, item, missing_only).await;
+                task.progress(processed as u64, total as u64);
+                if let Err(e) = events.emit(
+                    "metadata-refresh-progress",
+                    json!({
+                        "processed": processed,
+                        "total": total,
+                        "gameId": game_id,
+                        "source": source,
+                        "success": result.is_ok(),
+                        "error": result.as_ref().err(),
+                    }),
+                ) {
+                    log::warn!("无法发送 metadata-refresh-progress 事件: {}", e);
+                }
+                if let Err(error) = result {
+                    log::warn!(
+                        "刷新元数据失败 game_id={} source={}: {}",
+                        game_id,
+                        source,
+                        error
+                    );
+                    failures.push(RefreshFailure {
+                        game_id,
+                        source,
+                        error,
+                    });
+                }
+            }
+
+            log::info!(
+                "元数据批量刷新完成 total={} processed={} failed={}",
+                total,
+                processed,
+                failures.len()
+            );
+            if let Err(e) = events.emit(
+                "metadata-refresh-finished",
+                json!({
+                    "total": total,
+                    "succeeded": processed - failures.len(),
+                    "failed": failures,
+                    "cancelled": task.is_cancelled(),
+                }),
+            ) {
+                log::warn!("无法发送 metadata-refresh-finished 事件: {}", e);
+            }
+            Ok(())
+        },
+    )?;
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_missing_keeps_existing_fields() {
+        let existing = json!({
+            "name": "千恋＊万花",
+            "summary": "",
+            "tags": [],
+            "score": 8.1,
+        });
+        let fetched = json!({
+            "name": "Senren＊Banka",
+            "summary": "新简介",
+            "tags": ["纯爱"],
+            "score": 7.9,
+            "rank": 12,
+        });
+
+        assert_eq!(
+            fill_missing(Some(existing), fetched.clone()),
+            json!({
+                "name": "千恋＊万花",
+                "summary": "新简介",
+                "tags": ["纯爱"],
+                "score": 8.1,
+                "rank": 12,
+            })
+        );
+        assert_eq!(fill_missing(None, fetched.clone()), fetched);
+        assert_eq!(fill_missing(Some(Value::Null), fetched.clone()), fetched);
+    }
+}
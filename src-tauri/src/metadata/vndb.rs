@@ -0,0 +1,400 @@
+//! VNDB 条目搜索与用户列表读写
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use super::{
+    CachePolicy, MetadataMatch, MetadataSource, get_json, patch_json_no_content, post_json,
+    post_json_cached,
+};
+
+const VNDB_API_BASE: &str = "https://api.vndb.org/kana";
+const VNDB_FIELDS: &str = "id,titles{title,lang,main},aliases,image{url},released,rating,tags{name,rating,spoiler},description,developers{name},length_minutes";
+/// 后端无法读取前端的剧透等级设置，只保留无剧透标签
+const VNDB_MAX_SPOILER_LEVEL: u8 = 0;
+const VNDB_NO_SEXUAL_CONTENT_TAG: &str = "No Sexual Content";
+const VNDB_ULIST_PAGE_SIZE: usize = 100;
+/// 单次按 ID 查询的条目数上限
+const VNDB_QUERY_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct VndbQueryResponse {
+    #[serde(default)]
+    results: Vec<VndbVisualNovelResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbRelationsResponse {
+    #[serde(default)]
+    results: Vec<VndbRelationsItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbRelationsItem {
+    id: String,
+    #[serde(default)]
+    relations: Vec<VndbRelation>,
+}
+
+/// VNDB 条目的关联作品
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VndbRelation {
+    /// 关联作品的 VNDB ID
+    pub id: String,
+    /// 关联类型：seq / preq / fan / ser / side / par / set / alt / char / orig
+    pub relation: String,
+    #[serde(default)]
+    pub relation_official: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbAuthInfo {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbUserListResponse {
+    #[serde(default)]
+    results: Vec<VndbUserListItem>,
+    #[serde(default)]
+    more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbUserListItem {
+    vote: Option<u32>,
+    #[serde(default)]
+    labels: Vec<VndbUserListLabel>,
+    vn: VndbVisualNovelResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbUserListLabel {
+    id: u32,
+}
+
+/// VNDB 用户列表条目
+#[derive(Debug, Clone)]
+pub struct VndbUserListEntry {
+    /// 条目所在的标签 ID（1 Playing / 2 Finished / 3 Stalled / 4 Dropped / 5 Wishlist / 6 Blacklist / 7 Voted）
+    pub label_ids: Vec<u32>,
+    /// 投票分，范围 10-100
+    pub vote: Option<u32>,
+    pub matched: MetadataMatch,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbVisualNovelResponse {
+    id: String,
+    #[serde(default)]
+    titles: Vec<VndbTitle>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    image: Option<VndbImage>,
+    released: Option<String>,
+    rating: Option<f64>,
+    #[serde(default)]
+    tags: Vec<VndbTag>,
+    description: Option<String>,
+    #[serde(default)]
+    developers: Vec<VndbDeveloper>,
+    length_minutes: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbTitle {
+    title: String,
+    lang: String,
+    #[serde(default)]
+    main: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbImage {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbCoverResponse {
+    results: Vec<VndbCoverItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbCoverItem {
+    image: Option<VndbCoverImage>,
+}
+
+/// 封面地址与尺寸，`dims` 为 `[宽, 高]`
+#[derive(Debug, Deserialize)]
+pub struct VndbCoverImage {
+    pub url: String,
+    pub dims: Option<(u32, u32)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbTag {
+    name: String,
+    rating: f64,
+    spoiler: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct VndbDeveloper {
+    name: String,
+}
+
+/// 写入 game_sources.data 的 VNDB 数据，字段与前端 `VndbData` 一致
+#[derive(Debug, Serialize)]
+struct VndbData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    name: String,
+    name_cn: String,
+    all_titles: Vec<String>,
+    aliases: Vec<String>,
+    tags: Vec<String>,
+    score: Option<f64>,
+    developer: String,
+    average_hours: Option<f64>,
+    nsfw: bool,
+}
+
+/// 按名称搜索 VNDB 条目，结果按搜索相关度排序
+pub async fn search_by_name(name: &str, limit: usize) -> Result<Vec<MetadataMatch>, String> {
+    let body = json!({
+        "filters": ["search", "=", name.trim()],
+        "fields": VNDB_FIELDS,
+        "results": limit,
+        "sort": "searchrank",
+    });
+    let url = format!("{}/vn", VNDB_API_BASE);
+    let response: VndbQueryResponse =
+        post_json_cached(&url, &body, None, CachePolicy::Prefer).await?;
+
+    Ok(response.results.into_iter().map(transform_vn).collect())
+}
+
+/// 按 ID 获取单个条目，条目不存在时返回 `None`
+pub async fn fetch_vn(vn_id: &str, policy: CachePolicy) -> Result<Option<MetadataMatch>, String> {
+    let body = json!({
+        "filters": ["id", "=", vn_id.trim()],
+        "fields": VNDB_FIELDS,
+    });
+    let url = format!("{}/vn", VNDB_API_BASE);
+    let response: VndbQueryResponse = post_json_cached(&url, &body, None, policy).await?;
+
+    Ok(response.results.into_iter().next().map(transform_vn))
+}
+
+/// 读取条目封面的地址与尺寸，条目不存在或没有封面时返回 `None`
+pub async fn fetch_cover(vn_id: &str) -> Result<Option<VndbCoverImage>, String> {
+    let body = json!({
+        "filters": ["id", "=", vn_id.trim()],
+        "fields": "image{url,dims}",
+    });
+    let url = format!("{}/vn", VNDB_API_BASE);
+    let response: VndbCoverResponse =
+        post_json_cached(&url, &body, None, CachePolicy::Prefer).await?;
+
+    Ok(response
+        .results
+        .into_iter()
+        .next()
+        .and_then(|item| item.image))
+}
+
+/// 批量获取条目的关联作品，返回 VNDB ID 到关联列表的映射
+///
+/// 每次请求最多查询 [`VNDB_QUERY_PAGE_SIZE`] 个条目，不存在的条目不出现在结果中。
+pub async fn fetch_relations(
+    vn_ids: &[String],
+) -> Result<HashMap<String, Vec<VndbRelation>>, String> {
+    let url = format!("{}/vn", VNDB_API_BASE);
+    let mut relations = HashMap::new();
+    for chunk in vn_ids.chunks(VNDB_QUERY_PAGE_SIZE) {
+        let mut filters = vec![json!("or")];
+        filters.extend(chunk.iter().map(|id| json!(["id", "=", id])));
+        let body = json!({
+            "filters": filters,
+            "fields": "id,relations{id,relation,relation_official}",
+            "results": VNDB_QUERY_PAGE_SIZE,
+        });
+        let response: VndbRelationsResponse =
+            post_json_cached(&url, &body, None, CachePolicy::Prefer).await?;
+        relations.extend(
+            response
+                .results
+                .into_iter()
+                .map(|item| (item.id, item.relations)),
+        );
+    }
+    Ok(relations)
+}
+
+fn token_header(token: &str) -> String {
+    format!("Token {}", token.trim())
+}
+
+/// 获取 API Token 对应的用户 ID（如 `u12345`）
+pub async fn fetch_token_user(token: &str) -> Result<String, String> {
+    let url = format!("{}/authinfo", VNDB_API_BASE);
+    let info: VndbAuthInfo = get_json(&url, Some(token_header(token))).await?;
+    Ok(info.id)
+}
+
+/// 拉取用户列表的全部条目，提供 Token 时包含私有标签
+pub async fn fetch_user_list(
+    user_id: &str,
+    token: Option<&str>,
+) -> Result<Vec<VndbUserListEntry>, String> {
+    let url = format!("{}/ulist", VNDB_API_BASE);
+    let fields = format!("id,vote,labels{{id}},vn{{{}}}", VNDB_FIELDS);
+    let mut entries = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let body = json!({
+            "user": user_id,
+            "fields": fields,
+            "results": VNDB_ULIST_PAGE_SIZE,
+            "page": page,
+            "sort": "id",
+        });
+        let response: VndbUserListResponse =
+            post_json(&url, &body, token.map(token_header)).await?;
+        entries.extend(response.results.into_iter().map(|item| VndbUserListEntry {
+            label_ids: item.labels.into_iter().map(|label| label.id).collect(),
+            vote: item.vote,
+            matched: transform_vn(item.vn),
+        }));
+        if !response.more {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(entries)
+}
+
+/// 修改用户列表中的条目，条目不在列表中时由 VNDB 自动添加
+///
+/// 需要具备 listwrite 权限的 Token；`vote` 为 None 时保留 VNDB 上原有的投票。
+pub async fn update_user_list_entry(
+    vn_id: &str,
+    token: &str,
+    labels_set: &[u32],
+    labels_unset: &[u32],
+    vote: Option<u32>,
+) -> Result<(), String> {
+    let url = format!("{}/ulist/{}", VNDB_API_BASE, vn_id);
+    let mut body = json!({
+        "labels_set": labels_set,
+        "labels_unset": labels_unset,
+    });
+    if let Some(vote) = vote {
+        body["vote"] = json!(vote);
+    }
+    patch_json_no_content(&url, &body, Some(token_header(token))).await
+}
+
+/// 保留到指定小数位，与前端 `toFixed` 的结果一致
+fn round_to(value: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits);
+    (value * factor).round() / factor
+}
+
+fn transform_vn(mut vn: VndbVisualNovelResponse) -> MetadataMatch {
+    let main_title = vn
+        .titles
+        .iter()
+        .find(|title| title.main)
+        .or_else(|| vn.titles.first())
+        .map(|title| title.title.clone())
+        .unwrap_or_default();
+    let chinese_title = vn
+        .titles
+        .iter()
+        .find(|title| matches!(title.lang.as_str(), "zh-Hans" | "zh-Hant" | "zh"))
+        .map(|title| title.title.clone())
+        .unwrap_or_default();
+
+    vn.tags.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+    let tags: Vec<String> = vn
+        .tags
+        .into_iter()
+        .filter(|tag| tag.spoiler <= VNDB_MAX_SPOILER_LEVEL)
+        .map(|tag| tag.name)
+        .collect();
+    let nsfw = !tags.iter().any(|tag| tag == VNDB_NO_SEXUAL_CONTENT_TAG);
+
+    let data = VndbData {
+        date: vn.released,
+        image: vn.image.map(|image| image.url),
+        summary: vn.description,
+        name: main_title,
+        name_cn: chinese_title,
+        all_titles: vn.titles.into_iter().map(|title| title.title).collect(),
+        aliases: vn.aliases,
+        tags,
+        score: vn.rating.map(|rating| round_to(rating / 10.0, 2)),
+        developer: vn
+            .developers
+            .into_iter()
+            .map(|developer| developer.name)
+            .collect::<Vec<_>>()
+            .join("/"),
+        average_hours: vn.length_minutes.map(|minutes| round_to(minutes / 60.0, 1)),
+        nsfw,
+    };
+
+    MetadataMatch {
+        source: MetadataSource::Vndb,
+        external_id: vn.id,
+        data: serde_json::to_value(data).unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transforms_vn_like_frontend() {
+        let vn: VndbVisualNovelResponse = serde_json::from_value(json!({
+            "id": "v17",
+            "titles": [
+                { "title": "Ever17", "lang": "en", "main": false },
+                { "title": "Ever17 -the out of infinity-", "lang": "ja", "main": true },
+                { "title": "时空轮回", "lang": "zh-Hans", "main": false }
+            ],
+            "aliases": ["E17"],
+            "image": { "url": "https://t.vndb.org/cv/00/100.jpg" },
+            "released": "2002-08-29",
+            "rating": 86.57,
+            "tags": [
+                { "name": "Amnesia", "rating": 2.0, "spoiler": 2 },
+                { "name": "No Sexual Content", "rating": 2.5, "spoiler": 0 },
+                { "name": "Sci-fi", "rating": 2.8, "spoiler": 0 }
+            ],
+            "description": null,
+            "developers": [{ "name": "KID" }],
+            "length_minutes": 2400
+        }))
+        .unwrap();
+
+        let matched = transform_vn(vn);
+        assert_eq!(matched.external_id, "v17");
+        assert_eq!(matched.data["name"], "Ever17 -the out of infinity-");
+        assert_eq!(matched.data["name_cn"], "时空轮回");
+        assert_eq!(matched.data["tags"], json!(["Sci-fi", "No Sexual Content"]));
+        assert_eq!(matched.data["score"], 8.66);
+        assert_eq!(matched.data["average_hours"], 40.0);
+        assert_eq!(matched.data["nsfw"], false);
+        assert!(matched.data.get("summary").is_none());
+    }
+}
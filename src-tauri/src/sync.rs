@@ -0,0 +1,144 @@
+//! 将本地游玩状态回写在线服务
+//!
+//! 游玩状态变更时游戏进入对应服务的回写队列（games 表的 `*_sync_status` 列），
+//! 由同步命令逐个推送，失败的条目保留在队列中等待下次重试。
+//! 局域网内两台设备之间的双向同步见 [`lan`]。
+
+pub mod bgm;
+pub mod lan;
+pub mod vndb;
+
+use crate::database::repository::games_repository::{
+    GamesRepository, SyncService, SyncStatus, SyncTarget,
+};
+use crate::metadata::METADATA_MATCH_INTERVAL_MS;
+use crate::utils::tasks::TaskHandle;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
+use tauri::Runtime;
+
+/// 单个游戏的回写失败信息
+#[derive(Debug, Serialize)]
+pub struct SyncError {
+    pub game_id: i32,
+    pub message: String,
+}
+
+/// 批量回写结果
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub total: usize,
+    pub synced: usize,
+    pub failed: usize,
+    pub errors: Vec<SyncError>,
+}
+
+/// 推送单个游戏并记录回写状态，返回推送结果
+async fn sync_target<F, Fut>(
+    db: &DatabaseConnection,
+    service: SyncService,
+    target: SyncTarget,
+    push: &F,
+) -> Result<(), String>
+where
+    F: Fn(SyncTarget) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let game_id = target.game_id;
+    let external_id = target.external_id.clone();
+    let result = push(target).await;
+    let status = if result.is_ok() {
+        SyncStatus::Synced
+    } else {
+        SyncStatus::Failed
+    };
+    GamesRepository::set_sync_status(db, service, &[game_id], status)
+        .await
+        .map_err(|e| format!("更新回写状态失败: {}", e))?;
+    if let Err(e) = &result {
+        log::warn!(
+            "回写游玩状态失败 source={} game_id={} external_id={}: {}",
+            service.source(),
+            game_id,
+            external_id,
+            e
+        );
+    }
+    result
+}
+
+/// 立即回写指定游戏，忽略其当前回写状态
+pub(crate) async fn sync_game<F, Fut>(
+    db: &DatabaseConnection,
+    service: SyncService,
+    game_id: i32,
+    push: F,
+) -> Result<(), String>
+where
+    F: Fn(SyncTarget) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let target = GamesRepository::get_sync_targets(db, service, Some(game_id))
+        .await
+        .map_err(|e| format!("读取游戏数据失败: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("该游戏未绑定 {} 条目", service.source()))?;
+    sync_target(db, service, target, &push).await
+}
+
+/// 逐个回写队列中待同步与此前回写失败的游戏，通过 `task` 上报进度，取消后不再处理剩余游戏
+pub(crate) async fn sync_queue<R, F, Fut>(
+    db: &DatabaseConnection,
+    service: SyncService,
+    task: &TaskHandle<R>,
+    push: F,
+) -> Result<SyncReport, String>
+where
+    R: Runtime,
+    F: Fn(SyncTarget) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let targets = GamesRepository::get_sync_targets(db, service, None)
+        .await
+        .map_err(|e| format!("读取回写队列失败: {}", e))?;
+
+    let mut report = SyncReport {
+        total: targets.len(),
+        ..Default::default()
+    };
+    for (index, target) in targets.into_iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(Duration::from_millis(METADATA_MATCH_INTERVAL_MS)).await;
+        }
+        if task.is_cancelled() {
+            log::info!(
+                "游玩状态回写已取消 source={} processed={} total={}",
+                service.source(),
+                index,
+                report.total
+            );
+            break;
+        }
+        let game_id = target.game_id;
+        match sync_target(db, service, target, &push).await {
+            Ok(()) => report.synced += 1,
+            Err(message) => {
+                report.failed += 1;
+                report.errors.push(SyncError { game_id, message });
+            }
+        }
+        task.progress((index + 1) as u64, report.total as u64);
+    }
+
+    log::info!(
+        "游玩状态回写完成 source={} total={} synced={} failed={}",
+        service.source(),
+        report.total,
+        report.synced,
+        report.failed
+    );
+    Ok(report)
+}
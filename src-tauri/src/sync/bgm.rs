@@ -0,0 +1,56 @@
+//! Bangumi 收藏状态回写
+//!
+//! games.clear 与 Bangumi 收藏类型取值一致（1 想玩 / 2 玩过 / 3 在玩 / 4 搁置 / 5 抛弃），
+//! 直接作为收藏类型提交。
+
+use super::{SyncReport, sync_game, sync_queue};
+use crate::database::repository::games_repository::{SyncService, SyncTarget};
+use crate::metadata::bgm::update_collection_type;
+use crate::utils::bgm_auth::valid_bgm_auth;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use sea_orm::DatabaseConnection;
+use tauri::{AppHandle, Runtime, State, command};
+
+async fn access_token(db: &DatabaseConnection) -> Result<String, String> {
+    valid_bgm_auth(db)
+        .await
+        .map(|auth| auth.access_token.trim().to_string())
+}
+
+async fn push_target(token: String, target: SyncTarget) -> Result<(), String> {
+    let collection_type = target
+        .clear
+        .filter(|clear| (1..=5).contains(clear))
+        .ok_or_else(|| "游玩状态无效，无法回写".to_string())?;
+    update_collection_type(&target.external_id, &token, collection_type).await
+}
+
+/// 立即将指定游戏的游玩状态回写 Bangumi
+#[command]
+pub async fn sync_bgm_status(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<(), String> {
+    let token = access_token(&db).await?;
+    sync_game(&db, SyncService::Bgm, game_id, |target| {
+        push_target(token.clone(), target)
+    })
+    .await
+}
+
+/// 回写所有待同步与此前回写失败的游戏
+#[command]
+pub async fn sync_all_bgm_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+) -> Result<SyncReport, String> {
+    let token = access_token(&db).await?;
+    let task = tasks.start(&app_handle, TaskKind::StatusSync)?;
+    let result = sync_queue(&db, SyncService::Bgm, &task, |target| {
+        push_target(token.clone(), target)
+    })
+    .await;
+    task.finish(&result);
+    result
+}
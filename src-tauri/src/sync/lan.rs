@@ -0,0 +1,611 @@
+//! 局域网内两台设备之间的库同步
+//!
+//! 对端需开启远程控制接口（[`crate::utils::remote_api`]），两端设置相同的配对密钥。
+//! 配对密钥与远程控制的访问令牌相互独立，保存在系统凭据管理器中，同步接口只接受配对密钥：
+//! - 发现：向局域网广播 UDP 探测包，开启了远程控制的实例回复设备名与端口；
+//! - 同步：拉取对端快照合并到本库，再把合并后的本库快照推送给对端合并。
+//!
+//! 只同步以 Bangumi / VNDB 条目关联的游戏：游玩会话按开始时间取并集（统计随之重建），
+//! 游玩状态、个人评分与短评逐字段比较各自的修改时间，取较新的一端。
+//! 本地路径等设备相关字段各自独立。
+
+use crate::backup::library::GameMatchIndex;
+use crate::database::dto::{FullGameData, UpdateGameData};
+use crate::database::repository::game_stats_repository::{GameStatsRepository, ImportedSession};
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder, SyncFieldTimes,
+};
+use crate::entity::custom_data::CustomData;
+use crate::utils::credentials::{delete_secret, read_secret, write_secret};
+use crate::utils::http::get_client;
+use sea_orm::DatabaseConnection;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::{State, command};
+use tauri_plugin_http::reqwest::RequestBuilder;
+use tokio::net::UdpSocket;
+
+/// 快照格式版本，不一致时拒绝合并
+pub const SNAPSHOT_VERSION: u32 = 2;
+/// 发现服务的 UDP 端口
+pub const DISCOVERY_PORT: u16 = 17891;
+const DISCOVERY_REQUEST: &[u8] = b"REINA_LAN_SYNC_DISCOVER";
+const DISCOVERY_SERVICE: &str = "reina-lan-sync";
+/// 等待发现回复的时间
+const DISCOVERY_WAIT_MS: u64 = 1500;
+/// 系统凭据管理器中配对密钥的键名
+const PAIRING_SECRET_CREDENTIAL: &str = "lan_sync_pairing_secret";
+/// 配对密钥的最短长度
+const MIN_PAIRING_SECRET_LEN: usize = 8;
+
+/// 本进程的随机标识，用于在发现结果中排除自己
+static INSTANCE_ID: LazyLock<String> = LazyLock::new(|| {
+    let mut bytes = [0u8; 8];
+    match getrandom::fill(&mut bytes) {
+        Ok(()) => format!("{:016x}", u64::from_le_bytes(bytes)),
+        Err(_) => format!("pid-{}", std::process::id()),
+    }
+});
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscoveryReply {
+    service: String,
+    instance_id: String,
+    name: String,
+    port: u16,
+}
+
+/// 局域网内发现的设备
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPeer {
+    pub address: String,
+    pub port: u16,
+    pub name: String,
+}
+
+/// 同步的游玩会话
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSession {
+    pub start_time: i32,
+    pub end_time: i32,
+    /// 游玩时长（分钟）
+    pub duration: i32,
+}
+
+/// 单个游戏的同步数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncGame {
+    /// (数据源, 外部 ID)，用于在两端匹配同一游戏
+    pub external_ids: Vec<(String, String)>,
+    pub clear: Option<i32>,
+    pub clear_updated_at: Option<i32>,
+    pub user_rating: Option<f64>,
+    pub user_rating_updated_at: Option<i32>,
+    pub user_review: Option<String>,
+    pub user_review_updated_at: Option<i32>,
+    pub sessions: Vec<SyncSession>,
+}
+
+/// 一端的同步快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSnapshot {
+    pub version: u32,
+    pub games: Vec<SyncGame>,
+}
+
+/// 合并快照的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    /// 在本库中匹配到的游戏数量
+    pub matched_games: usize,
+    /// 采用对端游玩状态或评分的游戏数量
+    pub updated_games: usize,
+    pub sessions_added: usize,
+}
+
+/// 一次双向同步的结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSyncResult {
+    /// 对端数据合并到本库
+    pub pulled: MergeReport,
+    /// 本库数据合并到对端
+    pub pushed: MergeReport,
+}
+
+/// 读取本机保存的配对密钥
+pub(crate) async fn pairing_secret() -> Option<String> {
+    read_secret(PAIRING_SECRET_CREDENTIAL)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("读取局域网同步配对密钥失败: {}", e);
+            None
+        })
+        .filter(|secret| !secret.is_empty())
+}
+
+/// 保存局域网同步配对密钥到系统凭据管理器，传空值时删除
+///
+/// 两端需设置相同的密钥；未设置密钥时本机拒绝对端的同步请求。
+#[command]
+pub async fn set_lan_sync_pairing_secret(secret: Option<String>) -> Result<(), String> {
+    let secret = secret
+        .map(|secret| secret.trim().to_string())
+        .filter(|secret| !secret.is_empty());
+    if secret
+        .as_ref()
+        .is_some_and(|secret| secret.chars().count() < MIN_PAIRING_SECRET_LEN)
+    {
+        return Err(format!("配对密钥至少需要 {} 位", MIN_PAIRING_SECRET_LEN));
+    }
+    let result = match secret {
+        Some(secret) => write_secret(PAIRING_SECRET_CREDENTIAL, &secret).await,
+        None => delete_secret(PAIRING_SECRET_CREDENTIAL).await,
+    };
+    result.map_err(|e| format!("保存配对密钥失败: {}", e))
+}
+
+/// 是否已设置局域网同步配对密钥
+#[command]
+pub async fn has_lan_sync_pairing_secret() -> Result<bool, String> {
+    Ok(pairing_secret().await.is_some())
+}
+
+/// 本机名称，取不到时使用程序名
+fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "ReinaManager".to_string())
+}
+
+/// 回复局域网发现请求，随远程控制接口一起运行
+pub(crate) async fn answer_discovery(port: u16) {
+    let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("局域网同步发现服务监听失败: {}", e);
+            return;
+        }
+    };
+    let reply = DiscoveryReply {
+        service: DISCOVERY_SERVICE.to_string(),
+        instance_id: INSTANCE_ID.clone(),
+        name: device_name(),
+        port,
+    };
+    let reply = match serde_json::to_vec(&reply) {
+        Ok(reply) => reply,
+        Err(e) => {
+            log::warn!("序列化发现回复失败: {}", e);
+            return;
+        }
+    };
+
+    let mut buffer = [0u8; 64];
+    loop {
+        match socket.recv_from(&mut buffer).await {
+            Ok((len, peer)) if &buffer[..len] == DISCOVERY_REQUEST => {
+                if let Err(e) = socket.send_to(&reply, peer).await {
+                    log::debug!("回复发现请求失败 {}: {}", peer, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::debug!("接收发现请求失败: {}", e);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+        }
+    }
+}
+
+/// 广播探测局域网内开启了远程控制的其他设备
+#[command]
+pub async fn discover_lan_peers() -> Result<Vec<LanPeer>, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("创建发现套接字失败: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("开启广播失败: {}", e))?;
+    socket
+        .send_to(DISCOVERY_REQUEST, (Ipv4Addr::BROADCAST, DISCOVERY_PORT))
+        .await
+        .map_err(|e| format!("发送发现请求失败: {}", e))?;
+
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(DISCOVERY_WAIT_MS);
+    let mut peers = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+    {
+        let (len, address) = match received {
+            Ok(received) => received,
+            Err(e) => {
+                log::debug!("接收发现回复失败: {}", e);
+                continue;
+            }
+        };
+        let Ok(reply) = serde_json::from_slice::<DiscoveryReply>(&buffer[..len]) else {
+            continue;
+        };
+        if reply.service != DISCOVERY_SERVICE || reply.instance_id == *INSTANCE_ID {
+            continue;
+        }
+        let peer = LanPeer {
+            address: address.ip().to_string(),
+            port: reply.port,
+            name: reply.name,
+        };
+        if !peers.contains(&peer) {
+            peers.push(peer);
+        }
+    }
+    Ok(peers)
+}
+
+fn external_ids(game: &FullGameData) -> Vec<(String, String)> {
+    game.sources
+        .iter()
+        .filter_map(|source| Some((source.source.clone(), source.external_id.clone()?)))
+        .collect()
+}
+
+async fn load_games(db: &DatabaseConnection) -> Result<Vec<FullGameData>, String> {
+    GamesRepository::find_all(db, GameType::All, SortOption::Addtime, SortOrder::Asc, None)
+        .await
+        .map_err(|e| format!("读取游戏数据失败: {}", e))
+}
+
+/// 生成本库的同步快照
+pub(crate) async fn build_snapshot(db: &DatabaseConnection) -> Result<SyncSnapshot, String> {
+    let games = load_games(db).await?;
+    let field_times = GamesRepository::get_sync_field_times(db)
+        .await
+        .map_err(|e| format!("读取字段修改时间失败: {}", e))?;
+    let mut sessions_by_game: HashMap<i32, Vec<SyncSession>> = HashMap::new();
+    for session in GameStatsRepository::get_all_sessions(db, None)
+        .await
+        .map_err(|e| format!("读取游玩会话失败: {}", e))?
+    {
+        sessions_by_game
+            .entry(session.game_id)
+            .or_default()
+            .push(SyncSession {
+                start_time: session.start_time,
+                end_time: session.end_time,
+                duration: session.duration,
+            });
+    }
+
+    let games = games
+        .iter()
+        .filter(|game| game.deleted_at.is_none())
+        .filter_map(|game| {
+            let external_ids = external_ids(game);
+            if external_ids.is_empty() {
+                return None;
+            }
+            let custom_data = game.custom_data.as_ref();
+            let times = field_times.get(&game.id).copied().unwrap_or_default();
+            Some(SyncGame {
+                external_ids,
+                clear: game.clear,
+                clear_updated_at: times.clear,
+                user_rating: custom_data.and_then(|data| data.user_rating),
+                user_rating_updated_at: times.user_rating,
+                user_review: custom_data.and_then(|data| data.user_review.clone()),
+                user_review_updated_at: times.user_review,
+                sessions: sessions_by_game.remove(&game.id).unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(SyncSnapshot {
+        version: SNAPSHOT_VERSION,
+        games,
+    })
+}
+
+/// 对端的修改时间较新且值不同时采用对端的值，未记录修改时间视为最旧
+fn remote_wins<T: PartialEq>(
+    local: &T,
+    local_time: Option<i32>,
+    remote: &T,
+    remote_time: Option<i32>,
+) -> bool {
+    remote_time.unwrap_or(0) > local_time.unwrap_or(0) && remote != local
+}
+
+/// 逐字段比较修改时间，返回需要写入本库的更新及所采用字段在对端的修改时间
+fn sync_update(
+    local: &FullGameData,
+    local_times: SyncFieldTimes,
+    remote: &SyncGame,
+) -> Option<(UpdateGameData, SyncFieldTimes)> {
+    let mut custom_data = local.custom_data.clone().unwrap_or_default();
+    let clear_changed = remote.clear.is_some()
+        && remote_wins(
+            &local.clear,
+            local_times.clear,
+            &remote.clear,
+            remote.clear_updated_at,
+        );
+    let rating_changed = remote_wins(
+        &custom_data.user_rating,
+        local_times.user_rating,
+        &remote.user_rating,
+        remote.user_rating_updated_at,
+    );
+    let review_changed = remote_wins(
+        &custom_data.user_review,
+        local_times.user_review,
+        &remote.user_review,
+        remote.user_review_updated_at,
+    );
+    if !clear_changed && !rating_changed && !review_changed {
+        return None;
+    }
+
+    if rating_changed {
+        custom_data.user_rating = remote.user_rating;
+    }
+    if review_changed {
+        custom_data.user_review = remote.user_review.clone();
+    }
+    let updates = UpdateGameData {
+        clear: clear_changed.then_some(remote.clear),
+        custom_data: (rating_changed || review_changed).then_some(Some(custom_data)),
+        ..Default::default()
+    };
+    let times = SyncFieldTimes {
+        clear: remote.clear_updated_at.filter(|_| clear_changed),
+        user_rating: remote.user_rating_updated_at.filter(|_| rating_changed),
+        user_review: remote.user_review_updated_at.filter(|_| review_changed),
+    };
+    Some((updates, times))
+}
+
+/// 把对端快照合并到本库
+pub(crate) async fn merge_snapshot(
+    db: &DatabaseConnection,
+    snapshot: &SyncSnapshot,
+) -> Result<MergeReport, String> {
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "同步数据版本不一致（本机 {}，对端 {}），请将两端更新到相同版本",
+            SNAPSHOT_VERSION, snapshot.version
+        ));
+    }
+
+    let games = load_games(db).await?;
+    let field_times = GamesRepository::get_sync_field_times(db)
+        .await
+        .map_err(|e| format!("读取字段修改时间失败: {}", e))?;
+    let index = GameMatchIndex::from_games(&games);
+    let games: HashMap<i32, FullGameData> = games.into_iter().map(|game| (game.id, game)).collect();
+    let mut report = MergeReport::default();
+
+    for remote in &snapshot.games {
+        let ids: Vec<(&str, &str)> = remote
+            .external_ids
+            .iter()
+            .map(|(source, external_id)| (source.as_str(), external_id.as_str()))
+            .collect();
+        let Some(local) = index
+            .find_parts(&ids, None)
+            .and_then(|(game_id, _)| games.get(&game_id))
+            .filter(|game| game.deleted_at.is_none())
+        else {
+            continue;
+        };
+        report.matched_games += 1;
+
+        let local_times = field_times.get(&local.id).copied().unwrap_or_default();
+        if let Some((updates, times)) = sync_update(local, local_times, remote) {
+            let now = chrono::Utc::now().timestamp() as i32;
+            GamesRepository::update_aggregate(db, local.id, updates.cleaned(), now)
+                .await
+                .map_err(|e| format!("更新游戏 {} 失败: {}", local.id, e))?;
+            // 沿用对端的修改时间，避免同步本身让这些字段显得比对端更新
+            GamesRepository::set_sync_field_times(db, local.id, times)
+                .await
+                .map_err(|e| format!("更新游戏 {} 失败: {}", local.id, e))?;
+            report.updated_games += 1;
+        }
+
+        let sessions: Vec<ImportedSession> = remote
+            .sessions
+            .iter()
+            .filter(|session| {
+                session.duration > 0
+                    && session.start_time > 0
+                    && session.end_time > session.start_time
+            })
+            .map(|session| ImportedSession {
+                start_time: session.start_time,
+                end_time: session.end_time,
+                duration: session.duration,
+            })
+            .collect();
+        if !sessions.is_empty() {
+            report.sessions_added += GameStatsRepository::import_sessions(db, local.id, &sessions)
+                .await
+                .map_err(|e| format!("合并游戏 {} 的游玩会话失败: {}", local.id, e))?;
+        }
+    }
+
+    log::info!(
+        "局域网同步合并完成 matched={} updated={} sessions_added={}",
+        report.matched_games,
+        report.updated_games,
+        report.sessions_added
+    );
+    Ok(report)
+}
+
+/// 发送请求并解析对端返回的 JSON
+async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("连接对端失败: {}", e))?;
+    let status = response.status();
+    if status.as_u16() == 401 {
+        return Err("对端拒绝了配对密钥，请确认两端设置了相同的配对密钥".to_string());
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("读取对端响应失败: {}", e))?;
+    if !status.is_success() {
+        return Err(format!(
+            "对端返回 {}: {}",
+            status,
+            String::from_utf8_lossy(&body)
+        ));
+    }
+    serde_json::from_slice(&body).map_err(|e| format!("解析对端响应失败: {}", e))
+}
+
+/// 与局域网内的另一台设备双向同步
+///
+/// # Arguments
+/// * `address` - 对端 IP 地址或主机名
+/// * `port` - 对端远程控制接口端口
+#[command]
+pub async fn sync_with_lan_peer(
+    db: State<'_, DatabaseConnection>,
+    address: String,
+    port: u16,
+) -> Result<LanSyncResult, String> {
+    let secret = pairing_secret()
+        .await
+        .ok_or_else(|| "请先设置局域网同步配对密钥，对端需设置相同的密钥".to_string())?;
+
+    let address = address.trim();
+    let url = match address.parse::<IpAddr>() {
+        Ok(ip) => format!("http://{}/api/sync", SocketAddr::new(ip, port)),
+        Err(_) => format!("http://{}:{}/api/sync", address, port),
+    };
+    let authorization = format!("Bearer {}", secret);
+
+    let remote: SyncSnapshot = send_json(
+        get_client()
+            .get(&url)
+            .header("Authorization", &authorization),
+    )
+    .await?;
+    let pulled = merge_snapshot(&db, &remote).await?;
+
+    let local = build_snapshot(&db).await?;
+    let body = serde_json::to_vec(&local).map_err(|e| format!("序列化同步数据失败: {}", e))?;
+    let pushed: MergeReport = send_json(
+        get_client()
+            .post(&url)
+            .header("Authorization", &authorization)
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await?;
+
+    Ok(LanSyncResult { pulled, pushed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn local_game() -> FullGameData {
+        serde_json::from_value(json!({
+            "id": 1,
+            "id_type": "bgm",
+            "clear": 3,
+            "custom_data": { "name": "素晴らしき日々", "user_rating": 8.0 },
+            "sources": [{ "source": "bgm", "external_id": "1234" }],
+        }))
+        .unwrap()
+    }
+
+    fn remote_game(updated_at: i32) -> SyncGame {
+        SyncGame {
+            external_ids: vec![("bgm".to_string(), "1234".to_string())],
+            clear: Some(2),
+            clear_updated_at: Some(updated_at),
+            user_rating: Some(9.5),
+            user_rating_updated_at: Some(updated_at),
+            user_review: None,
+            user_review_updated_at: None,
+            sessions: Vec::new(),
+        }
+    }
+
+    const LOCAL_TIMES: SyncFieldTimes = SyncFieldTimes {
+        clear: Some(200),
+        user_rating: Some(200),
+        user_review: None,
+    };
+
+    #[test]
+    fn newer_remote_fields_win() {
+        let local = local_game();
+        assert_eq!(external_ids(&local), remote_game(0).external_ids);
+        assert!(sync_update(&local, LOCAL_TIMES, &remote_game(200)).is_none());
+
+        let (updates, times) = sync_update(&local, LOCAL_TIMES, &remote_game(300)).unwrap();
+        assert_eq!(updates.clear, Some(Some(2)));
+        let custom_data = updates.custom_data.flatten().unwrap();
+        assert_eq!(custom_data.user_rating, Some(9.5));
+        assert_eq!(custom_data.name.as_deref(), Some("素晴らしき日々"));
+        assert_eq!(
+            times,
+            SyncFieldTimes {
+                clear: Some(300),
+                user_rating: Some(300),
+                user_review: None,
+            }
+        );
+
+        let mut unchanged = remote_game(300);
+        unchanged.clear = Some(3);
+        unchanged.user_rating = Some(8.0);
+        assert!(sync_update(&local, LOCAL_TIMES, &unchanged).is_none());
+    }
+
+    #[test]
+    fn compares_each_field_by_its_own_time() {
+        let local = local_game();
+        // 对端的游玩状态较旧，评分较新：只采用评分
+        let mut remote = remote_game(100);
+        remote.user_rating_updated_at = Some(300);
+        remote.user_review = Some("良い".to_string());
+        remote.user_review_updated_at = Some(50);
+
+        let (updates, times) = sync_update(&local, LOCAL_TIMES, &remote).unwrap();
+        assert_eq!(updates.clear, None);
+        let custom_data = updates.custom_data.flatten().unwrap();
+        assert_eq!(custom_data.user_rating, Some(9.5));
+        // 本地短评从未修改过，对端有修改时间即视为较新
+        assert_eq!(custom_data.user_review.as_deref(), Some("良い"));
+        assert_eq!(
+            times,
+            SyncFieldTimes {
+                clear: None,
+                user_rating: Some(300),
+                user_review: Some(50),
+            }
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! VNDB 列表标签与投票回写
+//!
+//! 游玩状态按导入时的映射反向写入 VNDB 的 Playing / Finished / Stalled / Dropped / Wishlist
+//! 标签并取消其余状态标签，个人评分换算为 10-100 的投票分。需要在设置中保存具备
+//! listwrite 权限的 VNDB API Token。
+
+use super::{SyncReport, sync_game, sync_queue};
+use crate::database::repository::games_repository::{SyncService, SyncTarget};
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::importers::vndb::VNDB_LABEL_PLAY_STATUS;
+use crate::metadata::vndb::update_user_list_entry;
+use crate::utils::tasks::{TaskKind, TaskManager};
+use sea_orm::DatabaseConnection;
+use tauri::{AppHandle, Runtime, State, command};
+
+async fn api_token(db: &DatabaseConnection) -> Result<String, String> {
+    db.get_settings()
+        .await?
+        .vndb_token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| "设置中没有保存 VNDB Token".to_string())
+}
+
+/// 游玩状态对应的标签，以及需要取消的其余状态标签
+fn status_labels(clear: Option<i32>) -> Option<(u32, Vec<u32>)> {
+    let label = VNDB_LABEL_PLAY_STATUS
+        .iter()
+        .find(|(_, status)| Some(*status) == clear)
+        .map(|(label, _)| *label)?;
+    let others = VNDB_LABEL_PLAY_STATUS
+        .iter()
+        .map(|(other, _)| *other)
+        .filter(|other| *other != label)
+        .collect();
+    Some((label, others))
+}
+
+/// 本地评分（0-10）换算为 VNDB 投票分（10-100），未评分时不修改投票
+fn rating_to_vote(rating: Option<f64>) -> Option<u32> {
+    rating
+        .filter(|rating| *rating > 0.0)
+        .map(|rating| (rating * 10.0).round().clamp(10.0, 100.0) as u32)
+}
+
+async fn push_target(token: String, target: SyncTarget) -> Result<(), String> {
+    let (label, unset) =
+        status_labels(target.clear).ok_or_else(|| "游玩状态无效，无法回写".to_string())?;
+    update_user_list_entry(
+        &target.external_id,
+        &token,
+        &[label],
+        &unset,
+        rating_to_vote(target.user_rating),
+    )
+    .await
+}
+
+/// 立即将指定游戏的游玩状态与评分回写 VNDB
+#[command]
+pub async fn sync_vndb_status(
+    db: State<'_, DatabaseConnection>,
+    game_id: i32,
+) -> Result<(), String> {
+    let token = api_token(&db).await?;
+    sync_game(&db, SyncService::Vndb, game_id, |target| {
+        push_target(token.clone(), target)
+    })
+    .await
+}
+
+/// 回写所有待同步与此前回写失败的游戏
+#[command]
+pub async fn sync_all_vndb_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseConnection>,
+    tasks: State<'_, TaskManager>,
+) -> Result<SyncReport, String> {
+    let token = api_token(&db).await?;
+    let task = tasks.start(&app_handle, TaskKind::StatusSync)?;
+    let result = sync_queue(&db, SyncService::Vndb, &task, |target| {
+        push_target(token.clone(), target)
+    })
+    .await;
+    task.finish(&result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_status_and_rating_to_vndb() {
+        let (label, unset) = status_labels(Some(2)).unwrap();
+        assert_eq!(label, 2);
+        assert_eq!(unset, vec![1, 3, 4, 5]);
+        assert_eq!(status_labels(Some(1)).map(|(label, _)| label), Some(5));
+        assert!(status_labels(None).is_none());
+
+        assert_eq!(rating_to_vote(Some(8.54)), Some(85));
+        assert_eq!(rating_to_vote(Some(0.2)), Some(10));
+        assert_eq!(rating_to_vote(Some(0.0)), None);
+        assert_eq!(rating_to_vote(None), None);
+    }
+}
@@ -1,9 +1,23 @@
 #[cfg(target_os = "windows")]
 pub mod command_ext;
+#[cfg(target_os = "windows")]
+pub mod shortcut;
 
 pub mod bgm_auth;
+pub mod cli;
+pub mod credentials;
+pub mod deep_link;
+pub mod discord_rpc;
 pub mod fs;
 pub mod http;
 pub mod image;
 pub mod legacy_migration;
 pub mod logs;
+pub mod obs_output;
+pub mod power;
+pub mod remote_api;
+pub mod tasks;
+#[cfg(test)]
+pub mod test_util;
+pub mod trash;
+pub mod webhook;
@@ -1,24 +1,37 @@
 //! BGM OAuth 授权模块。
 //!
 //! 仅放需要 `BGM_APP_SECRET` 的流程：授权 URL、code 换 token、refresh。
+//! 后端使用 token 前通过 [`valid_bgm_auth`] 获取，临近过期时自动刷新。
 
 use std::fmt::Write as _;
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use parking_lot::Mutex;
+use sea_orm::DatabaseConnection;
 use serde::Deserialize;
 use tauri::{AppHandle, Emitter, State};
 
+use crate::database::dto::UpdateSettingsData;
 use crate::database::repository::settings_repository::SettingsRepository;
 use crate::entity::user::BgmAuth;
+#[cfg(target_os = "windows")]
+use crate::utils::command_ext::CommandGuiExt;
 
 const BGM_APP_ID: &str = "bgm606669f8b19c14e6e";
 const BGM_REDIRECT_URI: &str = "http://127.0.0.1:23380/callback";
 const BGM_CALLBACK_PORT: u16 = 23380;
 const BGM_CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+/// access token 剩余有效期不足该秒数时提前刷新
+const BGM_REFRESH_MARGIN_SECS: i64 = 300;
+
+/// 正在等待回调的登录的取消标记，取消后回调监听在下一次轮询时退出并释放端口
+static PENDING_LOGIN: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
 
 #[derive(Debug, Deserialize)]
 struct BgmTokenResponse {
@@ -27,10 +40,8 @@ struct BgmTokenResponse {
     refresh_token: Option<String>,
 }
 
-#[tauri::command]
-pub async fn bgm_oauth_start_login(app: AppHandle) -> Result<String, String> {
-    let state = generate_oauth_state()?;
-
+/// 启动本地回环端口上的 OAuth 回调监听
+fn bind_callback_listener() -> Result<TcpListener, String> {
     let listener = TcpListener::bind(("127.0.0.1", BGM_CALLBACK_PORT)).map_err(|e| {
         format!(
             "启动 OAuth 回调服务失败（端口 {} 可能被占用）: {}",
@@ -43,10 +54,70 @@ pub async fn bgm_oauth_start_login(app: AppHandle) -> Result<String, String> {
         .map_err(|e| format!("设置 OAuth 回调监听失败: {}", e))?;
 
     log::info!("BGM OAuth 回调服务已启动 port={}", BGM_CALLBACK_PORT);
+    Ok(listener)
+}
+
+/// 登记新的等待中登录，返回其取消标记
+fn register_pending_login() -> Arc<AtomicBool> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    *PENDING_LOGIN.lock() = Some(cancelled.clone());
+    cancelled
+}
+
+/// 登录结束后清除登记；已被新的登录替换时不做处理
+fn finish_pending_login(cancelled: &Arc<AtomicBool>) {
+    let mut pending = PENDING_LOGIN.lock();
+    if pending
+        .as_ref()
+        .is_some_and(|current| Arc::ptr_eq(current, cancelled))
+    {
+        *pending = None;
+    }
+}
+
+/// 等待 OAuth 回调，结束后清除登记；监听随之释放
+fn wait_for_pending_callback(
+    listener: TcpListener,
+    expected_state: &str,
+    cancelled: Arc<AtomicBool>,
+) -> Result<String, String> {
+    let result = wait_for_callback(&listener, expected_state, &cancelled);
+    drop(listener);
+    finish_pending_login(&cancelled);
+    result
+}
+
+/// 取消正在等待回调的 BGM OAuth 登录，并释放回调端口
+#[tauri::command]
+pub async fn cancel_bgm_oauth_login() -> Result<(), String> {
+    if let Some(cancelled) = PENDING_LOGIN.lock().take() {
+        cancelled.store(true, Ordering::Relaxed);
+        log::info!("已取消等待中的 BGM OAuth 登录");
+    }
+    Ok(())
+}
+
+fn authorize_url(state: &str) -> Result<String, String> {
+    let mut url = url::Url::parse("https://bgm.tv/oauth/authorize")
+        .map_err(|e| format!("构造 BGM 授权地址失败: {}", e))?;
+    url.query_pairs_mut()
+        .append_pair("client_id", BGM_APP_ID)
+        .append_pair("response_type", "code")
+        .append_pair("redirect_uri", BGM_REDIRECT_URI)
+        .append_pair("state", state);
+
+    Ok(url.to_string())
+}
+
+#[tauri::command]
+pub async fn bgm_oauth_start_login(app: AppHandle) -> Result<String, String> {
+    let state = generate_oauth_state()?;
+    let listener = bind_callback_listener()?;
+    let cancelled = register_pending_login();
 
     let expected_state = state.clone();
-    std::thread::spawn(
-        move || match wait_for_callback(&listener, &expected_state) {
+    std::thread::spawn(move || {
+        match wait_for_pending_callback(listener, &expected_state, cancelled) {
             Ok(code) => {
                 if let Err(e) = app.emit("bgm-oauth-code", &code) {
                     log::warn!("发送 BGM OAuth code 事件失败: {}", e);
@@ -58,18 +129,53 @@ pub async fn bgm_oauth_start_login(app: AppHandle) -> Result<String, String> {
                     log::warn!("发送 BGM OAuth error 事件失败: {}", e);
                 }
             }
-        },
-    );
+        }
+    });
 
-    let mut url = url::Url::parse("https://bgm.tv/oauth/authorize")
-        .map_err(|e| format!("构造 BGM 授权地址失败: {}", e))?;
-    url.query_pairs_mut()
-        .append_pair("client_id", BGM_APP_ID)
-        .append_pair("response_type", "code")
-        .append_pair("redirect_uri", BGM_REDIRECT_URI)
-        .append_pair("state", &state);
+    authorize_url(&state)
+}
 
-    Ok(url.to_string())
+/// 一次完成 BGM OAuth 登录
+///
+/// 在系统浏览器中打开授权页，等待本地回环端口收到回调后用 code 换取 token 并保存。
+/// 浏览器无法自动打开时，前端可通过 `bgm-oauth-authorize-url` 事件拿到授权地址。
+/// 最多等待 5 分钟，期间可调用 [`cancel_bgm_oauth_login`] 提前结束并释放回调端口。
+#[tauri::command]
+pub async fn bgm_oauth_login(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> Result<BgmAuth, String> {
+    let state = generate_oauth_state()?;
+    let listener = bind_callback_listener()?;
+    let url = authorize_url(&state)?;
+    let cancelled = register_pending_login();
+
+    if let Err(e) = app.emit("bgm-oauth-authorize-url", &url) {
+        log::warn!("发送 BGM OAuth 授权地址事件失败: {}", e);
+    }
+    if let Err(e) = open_in_browser(&url) {
+        log::warn!("打开 BGM 授权页失败: {}", e);
+    }
+
+    let code =
+        tokio::task::spawn_blocking(move || wait_for_pending_callback(listener, &state, cancelled))
+            .await
+            .map_err(|e| format!("等待 OAuth 回调失败: {}", e))??;
+    exchange_code(&db, code).await
+}
+
+fn open_in_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url])
+        .gui_safe()
+        .spawn();
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("xdg-open").arg(url).spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("无法打开浏览器: {}", e))
 }
 
 fn generate_oauth_state() -> Result<String, String> {
@@ -89,6 +195,10 @@ pub async fn bgm_oauth_exchange_code(
     db: State<'_, DatabaseConnection>,
     code: String,
 ) -> Result<BgmAuth, String> {
+    exchange_code(&db, code).await
+}
+
+async fn exchange_code(db: &DatabaseConnection, code: String) -> Result<BgmAuth, String> {
     let app_secret = read_bgm_app_secret()?;
 
     let token_resp = request_token(&serde_json::json!({
@@ -108,7 +218,7 @@ pub async fn bgm_oauth_exchange_code(
         nickname: None,
     };
 
-    store_bgm_auth(&db, &auth).await?;
+    store_bgm_auth(db, &auth).await?;
     log::info!("BGM OAuth 授权信息已保存 expires_at={:?}", auth.expires_at);
     Ok(auth)
 }
@@ -118,6 +228,10 @@ pub async fn bgm_oauth_refresh_token(
     db: State<'_, DatabaseConnection>,
     refresh_token: String,
 ) -> Result<BgmAuth, String> {
+    refresh_bgm_auth(&db, &refresh_token).await
+}
+
+async fn refresh_bgm_auth(db: &DatabaseConnection, refresh_token: &str) -> Result<BgmAuth, String> {
     let app_secret = read_bgm_app_secret()?;
 
     let token_resp = request_token(&serde_json::json!({
@@ -129,7 +243,7 @@ pub async fn bgm_oauth_refresh_token(
     }))
     .await?;
 
-    let settings = SettingsRepository::get_all_settings(&db)
+    let settings = SettingsRepository::get_all_settings(db)
         .await
         .map_err(|e| format!("获取现有设置失败: {}", e))?;
     let existing = settings.bgm_auth.as_ref();
@@ -144,11 +258,50 @@ pub async fn bgm_oauth_refresh_token(
         nickname: existing.and_then(|auth| auth.nickname.clone()),
     };
 
-    store_bgm_auth(&db, &auth).await?;
+    store_bgm_auth(db, &auth).await?;
     log::info!("BGM OAuth 授权信息已刷新 expires_at={:?}", auth.expires_at);
     Ok(auth)
 }
 
+/// access token 是否需要刷新：有 refresh token 且即将过期（手动填写的 token 没有过期时间）
+fn needs_refresh(auth: &BgmAuth, now: i64) -> bool {
+    auth.refresh_token
+        .as_deref()
+        .is_some_and(|token| !token.trim().is_empty())
+        && auth
+            .expires_at
+            .is_some_and(|expires_at| expires_at - now <= BGM_REFRESH_MARGIN_SECS)
+}
+
+/// 获取可用的 BGM 授权信息，access token 临近过期时自动刷新
+///
+/// 刷新失败时，若原 token 尚未过期则继续使用，否则返回错误提示重新登录。
+pub(crate) async fn valid_bgm_auth(db: &DatabaseConnection) -> Result<BgmAuth, String> {
+    let auth = SettingsRepository::get_all_settings(db)
+        .await
+        .map_err(|e| format!("获取用户设置失败: {}", e))?
+        .bgm_auth
+        .filter(|auth| !auth.access_token.trim().is_empty())
+        .ok_or_else(|| "尚未登录 Bangumi 账号".to_string())?;
+
+    let now = Utc::now().timestamp();
+    if !needs_refresh(&auth, now) {
+        return Ok(auth);
+    }
+    let refresh_token = auth.refresh_token.clone().unwrap_or_default();
+    match refresh_bgm_auth(db, refresh_token.trim()).await {
+        Ok(refreshed) => Ok(refreshed),
+        Err(e) if auth.expires_at.is_some_and(|expires_at| expires_at > now) => {
+            log::warn!("BGM token 自动刷新失败，继续使用原 token: {}", e);
+            Ok(auth)
+        }
+        Err(e) => Err(format!(
+            "Bangumi 登录已过期且自动刷新失败，请重新登录: {}",
+            e
+        )),
+    }
+}
+
 fn read_bgm_app_secret() -> Result<String, String> {
     if let Some(value) = option_env!("BGM_APP_SECRET") {
         let value = value.trim().to_string();
@@ -164,13 +317,20 @@ fn read_bgm_app_secret() -> Result<String, String> {
         .ok_or_else(|| "缺少环境变量 BGM_APP_SECRET".to_string())
 }
 
-fn wait_for_callback(listener: &TcpListener, expected_state: &str) -> Result<String, String> {
+fn wait_for_callback(
+    listener: &TcpListener,
+    expected_state: &str,
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
     let deadline = std::time::Instant::now() + BGM_CALLBACK_TIMEOUT;
 
     let mut stream = loop {
         match listener.accept() {
             Ok((stream, _)) => break stream,
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err("OAuth 登录已取消".to_string());
+                }
                 if std::time::Instant::now() >= deadline {
                     log::warn!("BGM OAuth 回调等待超时");
                     return Err("OAuth 回调等待超时，请重新登录".to_string());
@@ -256,16 +416,51 @@ async fn request_token(body: &serde_json::Value) -> Result<BgmTokenResponse, Str
 }
 
 async fn store_bgm_auth(db: &DatabaseConnection, auth: &BgmAuth) -> Result<(), String> {
-    let settings = SettingsRepository::get_all_settings(db)
-        .await
-        .map_err(|e| format!("获取用户记录失败: {}", e))?;
+    SettingsRepository::update_settings(
+        db,
+        UpdateSettingsData {
+            bgm_auth: Some(Some(auth.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(|e| format!("保存 BGM 授权信息失败: {}", e))
+}
 
-    let mut active: crate::entity::user::ActiveModel = settings.into();
-    active.bgm_auth = Set(Some(auth.clone()));
-    active
-        .update(db)
-        .await
-        .map_err(|e| format!("保存 BGM 授权信息失败: {}", e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    fn auth(refresh_token: Option<&str>, expires_at: Option<i64>) -> BgmAuth {
+        BgmAuth {
+            access_token: "token".to_string(),
+            refresh_token: refresh_token.map(str::to_string),
+            expires_at,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn refreshes_only_expiring_oauth_tokens() {
+        let now = 1_700_000_000;
+        assert!(needs_refresh(&auth(Some("refresh"), Some(now + 60)), now));
+        assert!(needs_refresh(&auth(Some("refresh"), Some(now - 60)), now));
+        assert!(!needs_refresh(
+            &auth(Some("refresh"), Some(now + 86_400)),
+            now
+        ));
+        assert!(!needs_refresh(&auth(Some(" "), Some(now - 60)), now));
+        assert!(!needs_refresh(&auth(None, None), now));
+    }
+
+    #[test]
+    fn cancelled_login_stops_waiting_for_callback() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let cancelled = AtomicBool::new(true);
+        assert_eq!(
+            wait_for_callback(&listener, "state", &cancelled),
+            Err("OAuth 登录已取消".to_string())
+        );
+    }
 }
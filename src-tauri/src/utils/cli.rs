@@ -0,0 +1,111 @@
+//! 命令行参数
+//!
+//! - `--launch <game_id>`：启动游戏，主窗口最小化。动作与协议链接一样交给前端
+//!   `useDeepLinkHandler` 执行，走界面上的启动流程以沿用计时模式设置
+//! - `--minimized`：静默启动，只保留托盘图标
+//! - `reina://...`：协议链接，见 [`crate::utils::deep_link`]
+//!
+//! 首次启动时在 setup 中处理；应用已运行时由单实例插件把第二实例的参数转交过来。
+//! 无法识别的参数直接忽略。
+
+use crate::utils::deep_link::{DeepLink, handle_deep_link, is_deep_link_arg, parse_deep_link};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// 解析后的命令行参数
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    /// 静默启动到托盘
+    pub minimized: bool,
+    /// 需要前端处理的动作
+    pub action: Option<DeepLink>,
+}
+
+fn parse_launch_id(value: &str) -> Option<DeepLink> {
+    match value.trim().parse::<u32>() {
+        Ok(game_id) => Some(DeepLink::Launch { game_id }),
+        Err(_) => {
+            log::warn!("忽略无效的 --launch 参数: {}", value);
+            None
+        }
+    }
+}
+
+/// 解析命令行参数，`args` 不含程序路径
+pub fn parse_cli_args<I, S>(args: I) -> CliArgs
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut parsed = CliArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref().trim();
+        if arg == "--minimized" {
+            parsed.minimized = true;
+        } else if arg == "--launch" {
+            match args.next() {
+                Some(value) => parsed.action = parse_launch_id(value.as_ref()).or(parsed.action),
+                None => log::warn!("--launch 缺少游戏 ID"),
+            }
+        } else if let Some(value) = arg.strip_prefix("--launch=") {
+            parsed.action = parse_launch_id(value).or(parsed.action);
+        } else if is_deep_link_arg(arg) {
+            match parse_deep_link(arg) {
+                Ok(deep_link) => parsed.action = Some(deep_link),
+                Err(e) => log::warn!("忽略无法处理的链接: {}", e),
+            }
+        }
+    }
+    parsed
+}
+
+/// 按参数调整主窗口并分发动作
+///
+/// 启动游戏时最小化主窗口；`--minimized` 时保持隐藏；其余情况显示并聚焦主窗口。
+pub fn handle_cli_args<R: Runtime>(app_handle: &AppHandle<R>, args: CliArgs) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        match (&args.action, args.minimized) {
+            (Some(DeepLink::Launch { .. }), _) => {
+                let _ = window.show();
+                let _ = window.minimize();
+            }
+            (_, true) => {}
+            _ => {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }
+    }
+    if let Some(action) = args.action {
+        handle_deep_link(app_handle, action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cli_args() {
+        assert_eq!(
+            parse_cli_args(["--launch", "42"]),
+            CliArgs {
+                minimized: false,
+                action: Some(DeepLink::Launch { game_id: 42 }),
+            }
+        );
+        assert_eq!(
+            parse_cli_args(["--minimized", "--flag1", "--launch=abc"]),
+            CliArgs {
+                minimized: true,
+                action: None,
+            }
+        );
+        assert_eq!(
+            parse_cli_args(["reina://game/7"]).action,
+            Some(DeepLink::Open { game_id: 7 })
+        );
+        assert_eq!(parse_cli_args(Vec::<String>::new()), CliArgs::default());
+    }
+}
@@ -0,0 +1,279 @@
+//! 系统凭据管理器
+//!
+//! Windows 使用凭据管理器（Credential Manager），Linux 使用 Secret Service（D-Bus）。
+//! 读取结果缓存在内存中，避免每次读取设置都访问系统服务。
+//! 系统凭据管理器不可用时返回错误，由调用方回退到数据库存储；首次失败后本次运行不再访问系统服务。
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{LazyLock, OnceLock};
+
+/// 凭据的服务名，Windows 下作为凭据目标名前缀
+const CREDENTIAL_SERVICE: &str = "ReinaManager";
+
+/// 已读取或写入的凭据，`None` 表示系统中不存在该凭据
+static CREDENTIAL_CACHE: LazyLock<Mutex<HashMap<String, Option<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 系统凭据管理器首次访问失败的原因
+static UNAVAILABLE: OnceLock<String> = OnceLock::new();
+
+/// 已确认不可用时直接返回首次失败的原因，不再访问系统服务
+fn check_available() -> Result<(), String> {
+    match UNAVAILABLE.get() {
+        Some(reason) => Err(reason.clone()),
+        None => Ok(()),
+    }
+}
+
+/// 记录系统凭据管理器不可用，只在首次失败时输出日志
+fn mark_unavailable(error: String) -> String {
+    if UNAVAILABLE.set(error.clone()).is_ok() {
+        log::warn!("系统凭据管理器不可用，本次运行改用数据库存储: {}", error);
+    }
+    error
+}
+
+/// 读取凭据，不存在时返回 `None`
+pub async fn read_secret(key: &str) -> Result<Option<String>, String> {
+    if let Some(cached) = CREDENTIAL_CACHE.lock().get(key) {
+        return Ok(cached.clone());
+    }
+    check_available()?;
+    let secret = platform::read(key).await.map_err(mark_unavailable)?;
+    CREDENTIAL_CACHE
+        .lock()
+        .insert(key.to_string(), secret.clone());
+    Ok(secret)
+}
+
+/// 写入凭据，已存在时覆盖
+pub async fn write_secret(key: &str, secret: &str) -> Result<(), String> {
+    check_available()?;
+    platform::write(key, secret)
+        .await
+        .map_err(mark_unavailable)?;
+    CREDENTIAL_CACHE
+        .lock()
+        .insert(key.to_string(), Some(secret.to_string()));
+    Ok(())
+}
+
+/// 删除凭据，不存在时视为成功
+pub async fn delete_secret(key: &str) -> Result<(), String> {
+    check_available()?;
+    platform::delete(key).await.map_err(mark_unavailable)?;
+    CREDENTIAL_CACHE.lock().insert(key.to_string(), None);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::CREDENTIAL_SERVICE;
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows::Win32::Security::Credentials::{
+        CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC, CREDENTIALW, CredDeleteW, CredFree,
+        CredReadW, CredWriteW,
+    };
+    use windows::core::{PCWSTR, PWSTR};
+
+    fn target_name(key: &str) -> Vec<u16> {
+        OsStr::new(&format!("{}:{}", CREDENTIAL_SERVICE, key))
+            .encode_wide()
+            .chain(Some(0))
+            .collect()
+    }
+
+    fn is_not_found(error: &windows::core::Error) -> bool {
+        error.code() == ERROR_NOT_FOUND.to_hresult()
+    }
+
+    pub async fn read(key: &str) -> Result<Option<String>, String> {
+        let target = target_name(key);
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        if let Err(e) = unsafe {
+            CredReadW(
+                PCWSTR(target.as_ptr()),
+                CRED_TYPE_GENERIC,
+                None,
+                &mut credential,
+            )
+        } {
+            return if is_not_found(&e) {
+                Ok(None)
+            } else {
+                Err(format!("读取系统凭据失败: {}", e))
+            };
+        }
+
+        let blob = unsafe {
+            let credential_ref = &*credential;
+            let blob = std::slice::from_raw_parts(
+                credential_ref.CredentialBlob,
+                credential_ref.CredentialBlobSize as usize,
+            )
+            .to_vec();
+            CredFree(credential as *const _);
+            blob
+        };
+        String::from_utf8(blob)
+            .map(Some)
+            .map_err(|e| format!("系统凭据内容无效: {}", e))
+    }
+
+    pub async fn write(key: &str, secret: &str) -> Result<(), String> {
+        let mut target = target_name(key);
+        let mut blob = secret.as_bytes().to_vec();
+        let credential = CREDENTIALW {
+            Type: CRED_TYPE_GENERIC,
+            TargetName: PWSTR(target.as_mut_ptr()),
+            CredentialBlobSize: blob.len() as u32,
+            CredentialBlob: blob.as_mut_ptr(),
+            Persist: CRED_PERSIST_LOCAL_MACHINE,
+            ..Default::default()
+        };
+        unsafe { CredWriteW(&credential, 0) }.map_err(|e| format!("写入系统凭据失败: {}", e))
+    }
+
+    pub async fn delete(key: &str) -> Result<(), String> {
+        let target = target_name(key);
+        match unsafe { CredDeleteW(PCWSTR(target.as_ptr()), CRED_TYPE_GENERIC, None) } {
+            Err(e) if !is_not_found(&e) => Err(format!("删除系统凭据失败: {}", e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::CREDENTIAL_SERVICE;
+    use crate::game::monitor::get_connection;
+    use std::collections::HashMap;
+    use zbus::Proxy;
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+    const SECRETS_DESTINATION: &str = "org.freedesktop.secrets";
+    const SECRETS_PATH: &str = "/org/freedesktop/secrets";
+    const DEFAULT_COLLECTION_PATH: &str = "/org/freedesktop/secrets/aliases/default";
+
+    /// Secret Service 中的 Secret 结构：(session, parameters, value, content_type)
+    type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+    fn attributes(key: &str) -> HashMap<&str, &str> {
+        HashMap::from([("service", CREDENTIAL_SERVICE), ("account", key)])
+    }
+
+    async fn proxy(path: &str, interface: &'static str) -> Result<Proxy<'static>, String> {
+        let connection = get_connection()
+            .await
+            .map_err(|e| format!("连接 D-Bus 会话总线失败: {}", e))?;
+        Proxy::new(connection, SECRETS_DESTINATION, path.to_string(), interface)
+            .await
+            .map_err(|e| format!("连接 Secret Service 失败: {}", e))
+    }
+
+    async fn service() -> Result<Proxy<'static>, String> {
+        proxy(SECRETS_PATH, "org.freedesktop.Secret.Service").await
+    }
+
+    /// 打开明文传输会话（仅经由本机会话总线传输）
+    async fn open_session(service: &Proxy<'_>) -> Result<OwnedObjectPath, String> {
+        let (_, session): (OwnedValue, OwnedObjectPath) = service
+            .call("OpenSession", &("plain", Value::from("")))
+            .await
+            .map_err(|e| format!("打开 Secret Service 会话失败: {}", e))?;
+        Ok(session)
+    }
+
+    /// 查找已解锁的凭据条目；条目被锁定时返回错误
+    async fn search(service: &Proxy<'_>, key: &str) -> Result<Vec<OwnedObjectPath>, String> {
+        let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) = service
+            .call("SearchItems", &(attributes(key),))
+            .await
+            .map_err(|e| format!("查找系统凭据失败: {}", e))?;
+        if unlocked.is_empty() && !locked.is_empty() {
+            return Err("系统密钥环已锁定，请先解锁".to_string());
+        }
+        Ok(unlocked)
+    }
+
+    pub async fn read(key: &str) -> Result<Option<String>, String> {
+        let service = service().await?;
+        let items = search(&service, key).await?;
+        let Some(item) = items.into_iter().next() else {
+            return Ok(None);
+        };
+        let session = open_session(&service).await?;
+        let secrets: HashMap<OwnedObjectPath, Secret> = service
+            .call("GetSecrets", &(vec![item.clone()], session))
+            .await
+            .map_err(|e| format!("读取系统凭据失败: {}", e))?;
+        let Some((_, _, value, _)) = secrets.get(&item) else {
+            return Ok(None);
+        };
+        String::from_utf8(value.clone())
+            .map(Some)
+            .map_err(|e| format!("系统凭据内容无效: {}", e))
+    }
+
+    pub async fn write(key: &str, secret: &str) -> Result<(), String> {
+        let service = service().await?;
+        let session = open_session(&service).await?;
+        let collection =
+            proxy(DEFAULT_COLLECTION_PATH, "org.freedesktop.Secret.Collection").await?;
+
+        let label = format!("{} {}", CREDENTIAL_SERVICE, key);
+        let properties: HashMap<&str, Value> = HashMap::from([
+            ("org.freedesktop.Secret.Item.Label", Value::from(label)),
+            (
+                "org.freedesktop.Secret.Item.Attributes",
+                Value::from(attributes(key)),
+            ),
+        ]);
+        let secret_value = (
+            session,
+            Vec::<u8>::new(),
+            secret.as_bytes().to_vec(),
+            "text/plain",
+        );
+        let (_, prompt): (OwnedObjectPath, OwnedObjectPath) = collection
+            .call("CreateItem", &(properties, secret_value, true))
+            .await
+            .map_err(|e| format!("写入系统凭据失败: {}", e))?;
+        if prompt.as_str() != "/" {
+            return Err("系统密钥环需要用户确认，请先解锁默认密钥环".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn delete(key: &str) -> Result<(), String> {
+        let service = service().await?;
+        for item in search(&service, key).await? {
+            let item_proxy = proxy(item.as_str(), "org.freedesktop.Secret.Item").await?;
+            let _: OwnedObjectPath = item_proxy
+                .call("Delete", &())
+                .await
+                .map_err(|e| format!("删除系统凭据失败: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    const UNSUPPORTED: &str = "当前平台不支持系统凭据管理器";
+
+    pub async fn read(_key: &str) -> Result<Option<String>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub async fn write(_key: &str, _secret: &str) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub async fn delete(_key: &str) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
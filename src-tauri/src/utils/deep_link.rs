@@ -0,0 +1,236 @@
+//! `reina://` 协议链接
+//!
+//! 支持的链接：
+//! - `reina://game/{id}`：打开游戏详情页
+//! - `reina://game/{id}/launch`：启动游戏
+//!
+//! 链接作为命令行参数传入，由 [`crate::utils::cli`] 统一解析（`--launch <id>` 同样转换为
+//! 启动动作）。动作暂存为待处理状态并发送 `deep-link` 事件，前端在启动完成和收到事件时
+//! 调用 [`take_pending_deep_link`] 取出处理（见前端 `useDeepLinkHandler`）。启动游戏仍由前端
+//! 发起，以沿用用户的计时模式设置。
+//!
+//! 协议在每次启动时注册：Windows 写入当前用户的注册表，Linux 写入
+//! `x-scheme-handler/reina` 的桌面文件并通过 `xdg-mime` 设为默认处理程序。
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, command};
+use url::Url;
+
+/// 协议名
+pub const DEEP_LINK_SCHEME: &str = "reina";
+
+/// 解析后的链接动作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "action",
+    rename_all = "lowercase",
+    rename_all_fields = "camelCase"
+)]
+pub enum DeepLink {
+    /// 打开游戏详情页
+    Open { game_id: u32 },
+    /// 启动游戏
+    Launch { game_id: u32 },
+}
+
+/// 等待前端处理的链接，新的链接覆盖未处理的旧链接
+static PENDING_DEEP_LINK: Mutex<Option<DeepLink>> = Mutex::new(None);
+
+/// 解析 `reina://` 链接
+pub fn parse_deep_link(link: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("无效的链接 {}: {}", link, e))?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("不支持的协议: {}", url.scheme()));
+    }
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match (url.host_str(), segments.as_slice()) {
+        (Some("game"), [id, rest @ ..]) => {
+            let game_id = id
+                .parse::<u32>()
+                .map_err(|_| format!("无效的游戏 ID: {}", id))?;
+            match rest {
+                [] => Ok(DeepLink::Open { game_id }),
+                ["launch"] => Ok(DeepLink::Launch { game_id }),
+                _ => Err(format!("不支持的链接: {}", link)),
+            }
+        }
+        _ => Err(format!("不支持的链接: {}", link)),
+    }
+}
+
+/// 判断命令行参数是否为 `reina://` 链接
+pub fn is_deep_link_arg(arg: &str) -> bool {
+    arg.trim()
+        .to_ascii_lowercase()
+        .starts_with(&format!("{}://", DEEP_LINK_SCHEME))
+}
+
+/// 暂存待处理的动作并通知前端
+pub fn handle_deep_link<R: Runtime>(app_handle: &AppHandle<R>, deep_link: DeepLink) {
+    log::info!("收到待处理动作: {:?}", deep_link);
+    *PENDING_DEEP_LINK.lock() = Some(deep_link);
+    if let Err(e) = app_handle.emit("deep-link", ()) {
+        log::warn!("无法发送 deep-link 事件: {}", e);
+    }
+}
+
+/// 取出待处理的链接，没有时返回 None
+#[command]
+pub fn take_pending_deep_link() -> Option<DeepLink> {
+    PENDING_DEEP_LINK.lock().take()
+}
+
+/// 在当前用户下注册 `reina://` 协议，指向当前可执行文件
+///
+/// 每次启动时覆盖写入，便携版移动位置后也能指向新的路径。
+#[cfg(target_os = "windows")]
+pub fn register_protocol() -> Result<(), String> {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, REG_SZ, RegSetKeyValueW};
+    use windows::core::{HSTRING, PCWSTR};
+
+    let exe = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let key = format!(r"Software\Classes\{}", DEEP_LINK_SCHEME);
+    let values = [
+        (key.clone(), None, "URL:ReinaManager Protocol".to_string()),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (
+            format!(r"{}\shell\open\command", key),
+            None,
+            format!("\"{}\" \"%1\"", exe.display()),
+        ),
+    ];
+
+    for (sub_key, name, data) in values {
+        let wide: Vec<u16> = data.encode_utf16().chain(std::iter::once(0)).collect();
+        let name = name.map(HSTRING::from);
+        unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(sub_key.as_str()),
+                name.as_ref()
+                    .map_or(PCWSTR::null(), |name| PCWSTR(name.as_ptr())),
+                REG_SZ.0,
+                Some(wide.as_ptr().cast()),
+                (wide.len() * std::mem::size_of::<u16>()) as u32,
+            )
+        }
+        .ok()
+        .map_err(|e| format!("注册 {} 协议失败: {}", DEEP_LINK_SCHEME, e))?;
+    }
+    Ok(())
+}
+
+/// Linux 下注册协议用的桌面文件名
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE_NAME: &str = "reina-manager-handler.desktop";
+
+/// 生成处理 `reina://` 链接的桌面文件内容
+#[cfg(any(target_os = "linux", test))]
+fn desktop_entry(exe: &std::path::Path) -> String {
+    // Exec 中的路径用双引号包裹：先按引号规则转义，再按字符串值规则转义反斜杠，
+    // `%` 需要写成 `%%` 以免被当作字段代码
+    let quoted: String = exe
+        .display()
+        .to_string()
+        .chars()
+        .flat_map(|c| match c {
+            '"' | '`' | '$' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect();
+    let exe = quoted.replace('\\', "\\\\").replace('%', "%%");
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=ReinaManager\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        exe, DEEP_LINK_SCHEME
+    )
+}
+
+/// 在当前用户下注册 `reina://` 协议，指向当前可执行文件
+///
+/// 写入 `$XDG_DATA_HOME/applications` 下的桌面文件并设为默认处理程序。
+/// AppImage 运行时使用 `$APPIMAGE` 指向的镜像路径，而不是临时挂载目录中的程序。
+#[cfg(target_os = "linux")]
+pub fn register_protocol() -> Result<(), String> {
+    use std::path::PathBuf;
+
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(appimage) => PathBuf::from(appimage),
+        None => std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?,
+    };
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .ok_or_else(|| "无法确定用户数据目录".to_string())?;
+    let applications = data_home.join("applications");
+    std::fs::create_dir_all(&applications)
+        .map_err(|e| format!("创建目录失败 {}: {}", applications.display(), e))?;
+
+    let desktop_file = applications.join(DESKTOP_FILE_NAME);
+    let entry = desktop_entry(&exe);
+    // 内容未变化时跳过，避免每次启动都调用 xdg-mime
+    if std::fs::read_to_string(&desktop_file).is_ok_and(|existing| existing == entry) {
+        return Ok(());
+    }
+    std::fs::write(&desktop_file, entry)
+        .map_err(|e| format!("写入桌面文件失败 {}: {}", desktop_file.display(), e))?;
+
+    let status = std::process::Command::new("xdg-mime")
+        .args([
+            "default",
+            DESKTOP_FILE_NAME,
+            &format!("x-scheme-handler/{}", DEEP_LINK_SCHEME),
+        ])
+        .status()
+        .map_err(|e| format!("注册 {} 协议失败: {}", DEEP_LINK_SCHEME, e))?;
+    if !status.success() {
+        return Err(format!(
+            "注册 {} 协议失败: xdg-mime 退出码 {}",
+            DEEP_LINK_SCHEME, status
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_game_links() {
+        assert_eq!(
+            parse_deep_link("reina://game/42/launch"),
+            Ok(DeepLink::Launch { game_id: 42 })
+        );
+        assert_eq!(
+            parse_deep_link("reina://game/42/"),
+            Ok(DeepLink::Open { game_id: 42 })
+        );
+        assert!(parse_deep_link("reina://game/abc").is_err());
+        assert!(parse_deep_link("reina://game/42/delete").is_err());
+        assert!(parse_deep_link("https://game/42").is_err());
+
+        assert!(is_deep_link_arg("REINA://game/7"));
+        assert!(!is_deep_link_arg("--flag1"));
+    }
+
+    #[test]
+    fn desktop_entry_registers_scheme_handler() {
+        let entry = desktop_entry(std::path::Path::new("/opt/Reina \"Manager\"/reina-manager"));
+        assert!(entry.contains("MimeType=x-scheme-handler/reina;\n"));
+        assert!(entry.contains("Exec=\"/opt/Reina \\\\\"Manager\\\\\"/reina-manager\" %u\n"));
+    }
+}
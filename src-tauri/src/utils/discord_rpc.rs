@@ -0,0 +1,464 @@
+//! Discord Rich Presence
+//!
+//! 通过 Discord 客户端的本地 IPC（Windows 命名管道 / Unix 套接字）推送当前游玩状态。
+//! 所有 IPC 读写都在专用线程中完成，Discord 未运行时静默等待，下次更新或定时重试时再连接。
+//! 同时运行多个游戏时展示最近开始的会话，结束后回退到仍在运行的上一个会话。
+
+use log::{debug, info, warn};
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::sync::OnceLock;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::time::Duration;
+
+/// Discord 应用 ID，编译期从环境变量注入
+const DISCORD_CLIENT_ID: Option<&str> = option_env!("DISCORD_CLIENT_ID");
+
+/// Discord 对 details/state 等文本字段的长度上限
+const MAX_TEXT_CHARS: usize = 128;
+
+/// 同步失败后的重试间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 单次 IPC 读写的超时时间，超时后断开连接并在下次重试时重连
+const IPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// IPC 帧操作码
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+/// 默认文案模板
+pub const DEFAULT_DETAILS_TEMPLATE: &str = "{name}";
+pub const DEFAULT_STATE_TEMPLATE: &str = "累计游玩 {playtime}";
+pub const DEFAULT_NSFW_PLACEHOLDER: &str = "某部游戏";
+
+/// 推送到 Discord 的活动状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceActivity {
+    pub details: String,
+    pub state: Option<String>,
+    /// 会话开始时间（Unix 秒），Discord 据此显示已游玩时长
+    pub start_time: u64,
+    pub large_image: Option<String>,
+    pub large_text: Option<String>,
+}
+
+impl PresenceActivity {
+    fn to_json(&self) -> Value {
+        let mut activity = json!({
+            "details": truncate_text(&self.details),
+            "timestamps": { "start": self.start_time },
+        });
+
+        if let Some(state) = self.state.as_deref().filter(|state| !state.is_empty()) {
+            activity["state"] = json!(truncate_text(state));
+        }
+
+        if let Some(image) = self.large_image.as_deref() {
+            let mut assets = json!({ "large_image": image });
+            if let Some(text) = self.large_text.as_deref().filter(|text| !text.is_empty()) {
+                assets["large_text"] = json!(truncate_text(text));
+            }
+            activity["assets"] = assets;
+        }
+
+        activity
+    }
+}
+
+/// 按字符截断文本，避免 Discord 拒绝过长字段
+fn truncate_text(text: &str) -> String {
+    if text.chars().count() <= MAX_TEXT_CHARS {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_TEXT_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 将分钟数格式化为「X小时Y分钟」
+pub fn format_playtime(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}分钟", minutes),
+        (hours, 0) => format!("{}小时", hours),
+        (hours, minutes) => format!("{}小时{}分钟", hours, minutes),
+    }
+}
+
+/// 渲染展示文案模板，支持 `{name}` 与 `{playtime}` 占位符
+pub fn render_template(template: &str, name: &str, playtime_minutes: u64) -> String {
+    template
+        .replace("{name}", name)
+        .replace("{playtime}", &format_playtime(playtime_minutes))
+        .trim()
+        .to_string()
+}
+
+enum PresenceCommand {
+    Update {
+        game_id: u32,
+        activity: PresenceActivity,
+    },
+    Remove {
+        game_id: u32,
+    },
+    Clear,
+}
+
+static PRESENCE_SENDER: OnceLock<Option<Sender<PresenceCommand>>> = OnceLock::new();
+
+fn presence_sender() -> Option<&'static Sender<PresenceCommand>> {
+    PRESENCE_SENDER
+        .get_or_init(|| {
+            let Some(client_id) = DISCORD_CLIENT_ID else {
+                info!("未配置 DISCORD_CLIENT_ID，Discord Rich Presence 不可用");
+                return None;
+            };
+
+            let (sender, receiver) = channel();
+            match std::thread::Builder::new()
+                .name("reina-discord-rpc".to_string())
+                .spawn(move || run_presence_worker(client_id, receiver))
+            {
+                Ok(_) => Some(sender),
+                Err(error) => {
+                    warn!("创建 Discord RPC 线程失败: {}", error);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+fn send_command(command: PresenceCommand) {
+    if let Some(sender) = presence_sender()
+        && sender.send(command).is_err()
+    {
+        warn!("Discord RPC 线程已退出，无法更新状态");
+    }
+}
+
+/// 设置指定游戏的 Discord 状态，多个游戏同时运行时展示最近设置的一个
+pub fn update_presence(game_id: u32, activity: PresenceActivity) {
+    send_command(PresenceCommand::Update { game_id, activity });
+}
+
+/// 移除指定游戏的 Discord 状态
+pub fn remove_presence(game_id: u32) {
+    // 从未启用过时无需为移除操作启动 IPC 线程
+    if PRESENCE_SENDER.get().is_some() {
+        send_command(PresenceCommand::Remove { game_id });
+    }
+}
+
+/// 清空所有 Discord 状态（例如用户在设置中关闭了该功能）
+pub fn clear_presence() {
+    if PRESENCE_SENDER.get().is_some() {
+        send_command(PresenceCommand::Clear);
+    }
+}
+
+fn run_presence_worker(client_id: &'static str, receiver: Receiver<PresenceCommand>) {
+    let mut activities: Vec<(u32, PresenceActivity)> = Vec::new();
+    let mut connection: Option<DiscordIpc> = None;
+    let mut synced = true;
+
+    loop {
+        let command = if synced {
+            receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            receiver.recv_timeout(RETRY_INTERVAL)
+        };
+
+        match command {
+            Ok(PresenceCommand::Update { game_id, activity }) => {
+                activities.retain(|(id, _)| *id != game_id);
+                activities.push((game_id, activity));
+            }
+            Ok(PresenceCommand::Remove { game_id }) => {
+                activities.retain(|(id, _)| *id != game_id);
+            }
+            Ok(PresenceCommand::Clear) => activities.clear(),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let current = activities.last().map(|(_, activity)| activity.to_json());
+
+        // 没有活动且尚未连接时无需连接 Discord
+        if current.is_none() && connection.is_none() {
+            synced = true;
+            continue;
+        }
+
+        if connection.is_none() {
+            connection = DiscordIpc::connect(client_id);
+        }
+
+        synced = match connection.as_mut() {
+            Some(ipc) => match ipc.set_activity(current) {
+                Ok(()) => true,
+                Err(error) => {
+                    debug!("更新 Discord 状态失败，稍后重试: {}", error);
+                    connection = None;
+                    false
+                }
+            },
+            None => false,
+        };
+
+        // 没有活动时断开连接，Discord 会立即清除状态
+        if activities.is_empty() && synced {
+            connection = None;
+        }
+    }
+}
+
+trait IpcStream: Read + Write + Send {}
+impl<T: Read + Write + Send> IpcStream for T {}
+
+struct DiscordIpc {
+    stream: Box<dyn IpcStream>,
+    nonce: u64,
+}
+
+impl DiscordIpc {
+    /// 依次尝试 discord-ipc-0..9 并完成握手
+    fn connect(client_id: &str) -> Option<Self> {
+        for index in 0..10 {
+            let Some(stream) = open_ipc_stream(index) else {
+                continue;
+            };
+            let mut ipc = Self { stream, nonce: 0 };
+            match ipc.handshake(client_id) {
+                Ok(()) => {
+                    info!("已连接 Discord IPC: discord-ipc-{}", index);
+                    return Some(ipc);
+                }
+                Err(error) => debug!("Discord IPC 握手失败 (discord-ipc-{}): {}", index, error),
+            }
+        }
+        None
+    }
+
+    fn handshake(&mut self, client_id: &str) -> Result<(), String> {
+        self.write_frame(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+        let (op, payload) = self.read_frame()?;
+        if op == OP_CLOSE {
+            return Err(format!("Discord 拒绝连接: {}", payload));
+        }
+        if payload.get("evt").and_then(Value::as_str) != Some("READY") {
+            return Err(format!("未收到 READY 事件: {}", payload));
+        }
+        Ok(())
+    }
+
+    fn set_activity(&mut self, activity: Option<Value>) -> Result<(), String> {
+        self.nonce += 1;
+        self.write_frame(
+            OP_FRAME,
+            &json!({
+                "cmd": "SET_ACTIVITY",
+                "args": { "pid": std::process::id(), "activity": activity },
+                "nonce": self.nonce.to_string(),
+            }),
+        )?;
+
+        let (op, payload) = self.read_frame()?;
+        if op == OP_CLOSE {
+            return Err(format!("Discord 关闭了连接: {}", payload));
+        }
+        if payload.get("evt").and_then(Value::as_str) == Some("ERROR") {
+            return Err(format!("Discord 返回错误: {}", payload["data"]));
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self, op: u32, payload: &Value) -> Result<(), String> {
+        let body = payload.to_string();
+        let mut frame = Vec::with_capacity(8 + body.len());
+        frame.extend_from_slice(&op.to_le_bytes());
+        frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        frame.extend_from_slice(body.as_bytes());
+        self.stream
+            .write_all(&frame)
+            .and_then(|_| self.stream.flush())
+            .map_err(|e| format!("写入 Discord IPC 失败: {}", e))
+    }
+
+    fn read_frame(&mut self) -> Result<(u32, Value), String> {
+        let mut header = [0u8; 8];
+        self.stream
+            .read_exact(&mut header)
+            .map_err(|e| format!("读取 Discord IPC 失败: {}", e))?;
+        let op = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+
+        let mut body = vec![0u8; len];
+        self.stream
+            .read_exact(&mut body)
+            .map_err(|e| format!("读取 Discord IPC 失败: {}", e))?;
+        let payload = serde_json::from_slice(&body)
+            .map_err(|e| format!("解析 Discord IPC 响应失败: {}", e))?;
+        Ok((op, payload))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn open_ipc_stream(index: u32) -> Option<Box<dyn IpcStream>> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows::Win32::Storage::FileSystem::FILE_FLAG_OVERLAPPED;
+
+    // 以重叠模式打开管道，读写才能设置超时，避免 Discord 无响应时线程永久阻塞
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(FILE_FLAG_OVERLAPPED.0)
+        .open(format!(r"\\.\pipe\discord-ipc-{}", index))
+        .ok()?;
+    OverlappedPipe::new(file)
+        .map(|pipe| Box::new(pipe) as Box<dyn IpcStream>)
+        .ok()
+}
+
+/// 带超时的重叠 I/O 命名管道
+#[cfg(target_os = "windows")]
+struct OverlappedPipe {
+    file: std::fs::File,
+    event: std::os::windows::io::OwnedHandle,
+}
+
+#[cfg(target_os = "windows")]
+impl OverlappedPipe {
+    fn new(file: std::fs::File) -> std::io::Result<Self> {
+        use std::os::windows::io::FromRawHandle;
+        use windows::Win32::System::Threading::CreateEventW;
+        use windows::core::PCWSTR;
+
+        let event = unsafe { CreateEventW(None, true, false, PCWSTR::null()) }
+            .map_err(std::io::Error::other)?;
+        Ok(Self {
+            file,
+            event: unsafe { std::os::windows::io::OwnedHandle::from_raw_handle(event.0) },
+        })
+    }
+
+    /// 发起一次重叠读写并等待完成，超过 IPC_TIMEOUT 时取消请求并返回 TimedOut
+    fn transfer(
+        &mut self,
+        read: Option<&mut [u8]>,
+        write: Option<&[u8]>,
+    ) -> std::io::Result<usize> {
+        use std::os::windows::io::AsRawHandle;
+        use windows::Win32::Foundation::{ERROR_IO_PENDING, HANDLE, WAIT_OBJECT_0, WAIT_TIMEOUT};
+        use windows::Win32::Storage::FileSystem::{ReadFile, WriteFile};
+        use windows::Win32::System::IO::{CancelIoEx, GetOverlappedResult, OVERLAPPED};
+        use windows::Win32::System::Threading::{ResetEvent, WaitForSingleObject};
+
+        let handle = HANDLE(self.file.as_raw_handle());
+        let event = HANDLE(self.event.as_raw_handle());
+        let mut overlapped = OVERLAPPED {
+            hEvent: event,
+            ..Default::default()
+        };
+
+        unsafe {
+            ResetEvent(event).map_err(std::io::Error::other)?;
+            let started = match (read, write) {
+                (Some(buf), _) => ReadFile(handle, Some(buf), None, Some(&mut overlapped)),
+                (None, Some(buf)) => WriteFile(handle, Some(buf), None, Some(&mut overlapped)),
+                (None, None) => return Ok(0),
+            };
+            if let Err(error) = started
+                && error.code() != ERROR_IO_PENDING.to_hresult()
+            {
+                return Err(std::io::Error::other(error));
+            }
+
+            let wait = WaitForSingleObject(event, IPC_TIMEOUT.as_millis() as u32);
+            let mut transferred = 0u32;
+            if wait == WAIT_TIMEOUT {
+                // 取消后仍需等待请求结束，缓冲区和 OVERLAPPED 才能安全释放
+                let _ = CancelIoEx(handle, Some(&overlapped));
+                let _ = GetOverlappedResult(handle, &overlapped, &mut transferred, true);
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    "Discord IPC 响应超时",
+                ));
+            }
+            if wait != WAIT_OBJECT_0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            GetOverlappedResult(handle, &overlapped, &mut transferred, false)
+                .map_err(std::io::Error::other)?;
+            Ok(transferred as usize)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Read for OverlappedPipe {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.transfer(Some(buf), None)
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Write for OverlappedPipe {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.transfer(None, Some(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_ipc_stream(index: u32) -> Option<Box<dyn IpcStream>> {
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+
+    let base_dirs = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|key| std::env::var_os(key).map(PathBuf::from))
+        .chain(std::iter::once(PathBuf::from("/tmp")));
+
+    // 兼容 Flatpak / Snap 安装的 Discord
+    for base in base_dirs {
+        for sub_dir in ["", "app/com.discordapp.Discord", "snap.discord"] {
+            let path = base.join(sub_dir).join(format!("discord-ipc-{}", index));
+            if let Ok(stream) = UnixStream::connect(&path) {
+                let _ = stream.set_read_timeout(Some(IPC_TIMEOUT));
+                let _ = stream.set_write_timeout(Some(IPC_TIMEOUT));
+                return Some(Box::new(stream));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_replaces_name_and_playtime() {
+        assert_eq!(
+            render_template("{name} · 已玩 {playtime}", "千恋＊万花", 125),
+            "千恋＊万花 · 已玩 2小时5分钟"
+        );
+        assert_eq!(format_playtime(45), "45分钟");
+        assert_eq!(format_playtime(120), "2小时");
+    }
+
+    #[test]
+    fn long_text_is_truncated_by_chars() {
+        let text = "啊".repeat(200);
+        let truncated = truncate_text(&text);
+
+        assert_eq!(truncated.chars().count(), MAX_TEXT_CHARS);
+        assert!(truncated.ends_with('…'));
+    }
+}
@@ -1,11 +1,16 @@
 #[cfg(target_os = "windows")]
 use crate::utils::command_ext::CommandGuiExt;
 
+use crate::utils::tasks::{TaskKind, TaskManager};
+use crate::utils::trash::remove_path;
 use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use tauri::command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Runtime, State, command};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use walkdir::WalkDir;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct PortableModeResult {
@@ -407,32 +412,238 @@ fn count_files_in_dir(dir: &Path) -> Result<usize, String> {
     Ok(count)
 }
 
+/// 分块复制的块大小
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 复制被取消时返回的错误
+pub const COPY_CANCELLED: &str = "复制已取消";
+
+/// 分块异步复制单个文件并保留权限，每写入一块回调已复制的字节数
+///
+/// 取消或出错时删除未写完的目标文件。
+pub(crate) async fn copy_file_chunked(
+    src: &Path,
+    dst: &Path,
+    cancelled: &AtomicBool,
+    mut on_chunk: impl FnMut(u64),
+) -> Result<u64, String> {
+    let mut reader = tokio::fs::File::open(src)
+        .await
+        .map_err(|e| format!("打开文件失败 {}: {}", src.display(), e))?;
+    let writer = tokio::fs::File::create(dst)
+        .await
+        .map_err(|e| format!("创建文件失败 {}: {}", dst.display(), e))?;
+
+    let copied = write_chunks(&mut reader, writer, src, dst, cancelled, &mut on_chunk).await;
+    match copied {
+        Ok(copied) => {
+            if let Ok(metadata) = reader.metadata().await {
+                let _ = tokio::fs::set_permissions(dst, metadata.permissions()).await;
+            }
+            Ok(copied)
+        }
+        Err(e) => {
+            if let Err(cleanup) = tokio::fs::remove_file(dst).await {
+                log::warn!("清理未完成的文件失败 {}: {}", dst.display(), cleanup);
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn write_chunks(
+    reader: &mut tokio::fs::File,
+    mut writer: tokio::fs::File,
+    src: &Path,
+    dst: &Path,
+    cancelled: &AtomicBool,
+    on_chunk: &mut impl FnMut(u64),
+) -> Result<u64, String> {
+    let mut buffer = vec![0_u8; COPY_CHUNK_SIZE];
+    let mut copied = 0_u64;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(COPY_CANCELLED.to_string());
+        }
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取文件失败 {}: {}", src.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .await
+            .map_err(|e| format!("写入文件失败 {}: {}", dst.display(), e))?;
+        copied += read as u64;
+        on_chunk(copied);
+    }
+    writer
+        .flush()
+        .await
+        .map_err(|e| format!("写入文件失败 {}: {}", dst.display(), e))?;
+    Ok(copied)
+}
+
+/// 待复制的目录项
+struct CopyEntry {
+    path: PathBuf,
+    relative: PathBuf,
+    is_dir: bool,
+    len: u64,
+}
+
+/// 分块异步复制目录，回调 (已复制字节数, 总字节数)
+///
+/// 遇到错误或取消立即停止，已复制的目标文件由调用方决定是否清理；符号链接不跟随也不复制。
+pub(crate) async fn copy_dir_chunked(
+    src: &Path,
+    dst: &Path,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<u64, String> {
+    let root = src.to_path_buf();
+    let entries = tokio::task::spawn_blocking(move || {
+        let mut entries = Vec::new();
+        for entry in WalkDir::new(&root).follow_links(false) {
+            let entry = entry.map_err(|e| format!("读取源目录失败: {}", e))?;
+            let file_type = entry.file_type();
+            if !file_type.is_dir() && !file_type.is_file() {
+                log::warn!("跳过符号链接: {}", entry.path().display());
+                continue;
+            }
+            let relative = entry
+                .path()
+                .strip_prefix(&root)
+                .map_err(|e| format!("计算相对路径失败: {}", e))?
+                .to_path_buf();
+            let len = if file_type.is_file() {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            } else {
+                0
+            };
+            entries.push(CopyEntry {
+                path: entry.into_path(),
+                relative,
+                is_dir: file_type.is_dir(),
+                len,
+            });
+        }
+        Ok::<_, String>(entries)
+    })
+    .await
+    .map_err(|e| format!("读取源目录任务异常: {}", e))??;
+
+    let total: u64 = entries.iter().map(|entry| entry.len).sum();
+    let mut copied = 0_u64;
+    for entry in entries {
+        let target = dst.join(&entry.relative);
+        if entry.is_dir {
+            tokio::fs::create_dir_all(&target)
+                .await
+                .map_err(|e| format!("创建目录失败 {}: {}", target.display(), e))?;
+            continue;
+        }
+        let base = copied;
+        copied += copy_file_chunked(&entry.path, &target, cancelled, |written| {
+            on_progress(base + written, total)
+        })
+        .await?;
+    }
+    on_progress(copied, total);
+    Ok(copied)
+}
+
+/// 复制文件
+///
+/// 分块复制并通过 `task-progress` 事件上报进度；取消后删除未写完的目标文件
 #[command]
-pub async fn copy_file(src: String, dst: String) -> Result<(), String> {
+pub async fn copy_file<R: Runtime>(
+    app_handle: AppHandle<R>,
+    tasks: State<'_, TaskManager>,
+    src: String,
+    dst: String,
+) -> Result<(), String> {
     let src_path = Path::new(&src);
     let dst_path = Path::new(&dst);
 
-    if !src_path.exists() {
-        return Err(format!("源文件不存在: {}", src));
-    }
+    let metadata = tokio::fs::metadata(src_path)
+        .await
+        .map_err(|_| format!("源文件不存在: {}", src))?;
 
     if let Some(parent) = dst_path.parent()
-        && !parent.exists()
+        && !parent.as_os_str().is_empty()
     {
-        fs::create_dir_all(parent).map_err(|e| format!("无法创建目标目录的父目录: {}", e))?;
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("无法创建目标目录的父目录: {}", e))?;
     }
-    fs::copy(src_path, dst_path).map_err(|e| format!("无法复制文件: {}", e))?;
-    Ok(())
+
+    let task = tasks.start(&app_handle, TaskKind::FileCopy)?;
+    let total = metadata.len();
+    let cancelled = task.cancel_flag();
+    let result = copy_file_chunked(src_path, dst_path, &cancelled, |copied| {
+        task.progress(copied, total)
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("无法复制文件: {}", e));
+    task.finish(&result);
+    result
 }
 
 /// 删除文件
+///
+/// 默认移入系统回收站，`permanent` 为 true 时直接删除
 #[command]
-pub async fn delete_file(file_path: String) -> Result<(), String> {
-    let path = Path::new(&file_path);
+pub async fn delete_file(file_path: String, permanent: Option<bool>) -> Result<(), String> {
+    let path = PathBuf::from(&file_path);
     if !path.exists() {
         return Ok(()); // 文件不存在，视为成功
     }
 
-    fs::remove_file(path).map_err(|e| format!("无法删除文件: {}", e))?;
-    Ok(())
+    tokio::task::spawn_blocking(move || remove_path(&path, permanent.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("删除文件任务异常: {}", e))?
+        .map_err(|e| format!("无法删除文件: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+
+    #[tokio::test]
+    async fn copy_dir_chunked_copies_tree_and_stops_on_cancel() {
+        let src = temp_dir("fs", "src");
+        fs::create_dir_all(src.join("2024").join("empty")).unwrap();
+        fs::write(src.join("index.json"), [1_u8; 10]).unwrap();
+        fs::write(src.join("2024").join("save.7z"), [2_u8; 32]).unwrap();
+
+        let dst = temp_dir("fs", "dst");
+        let mut last = (0, 0);
+        let copied = copy_dir_chunked(&src, &dst, &AtomicBool::new(false), |copied, total| {
+            last = (copied, total)
+        })
+        .await
+        .unwrap();
+        assert_eq!(copied, 42);
+        assert_eq!(last, (42, 42));
+        assert_eq!(
+            fs::read(dst.join("2024").join("save.7z")).unwrap(),
+            [2_u8; 32]
+        );
+        assert!(dst.join("2024").join("empty").is_dir());
+
+        let cancelled_dst = temp_dir("fs", "cancelled");
+        let result =
+            copy_dir_chunked(&src, &cancelled_dst, &AtomicBool::new(true), |_, _| {}).await;
+        assert_eq!(result, Err(COPY_CANCELLED.to_string()));
+        assert!(!cancelled_dst.join("index.json").exists());
+
+        for dir in [src, dst, cancelled_dst] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
 }
@@ -1,7 +1,17 @@
-use serde::Deserialize;
+//! 后端共享的 HTTP 客户端
+//!
+//! 代理、User-Agent、超时与重试次数取自用户的网络设置，启动时及设置变更后重建客户端。
+//! 所有后端请求通过 [`get_client`] 获取客户端，元数据抓取另经 [`send_with_retry`] 自动重试。
+//! 前端的元数据请求经 [`send_http_request`] 转发，同样使用这些设置。
+
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::user::NetworkSettings;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{OnceLock, RwLock};
 use std::time::Duration;
-use tauri_plugin_http::reqwest::{Client, NoProxy, Proxy};
+use tauri_plugin_http::reqwest::{self, Client, NoProxy, Proxy, RequestBuilder, Response};
 
 const GLOBAL_USER_AGENT: &str = concat!(
     "huoshen80/ReinaManager/",
@@ -11,6 +21,9 @@ const GLOBAL_USER_AGENT: &str = concat!(
 
 const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
 const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_MAX_RETRIES: u32 = 2;
+/// 首次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY_MS: u64 = 500;
 const LOCAL_PROXY_BYPASS: &str = "localhost,127.0.0.0/8,::1,0.0.0.0,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16,169.254.0.0/16,fc00::/7,fe80::/10,.local";
 
 #[derive(Debug, Clone, Deserialize)]
@@ -18,29 +31,79 @@ pub struct ProxyConfig {
     pub url: String,
 }
 
-static GLOBAL_HTTP_CLIENT: OnceLock<RwLock<Client>> = OnceLock::new();
+struct SharedClient {
+    client: Client,
+    settings: NetworkSettings,
+}
+
+static GLOBAL_HTTP_CLIENT: OnceLock<RwLock<SharedClient>> = OnceLock::new();
 
+/// 仅更新代理地址，其余网络设置保持不变
 #[tauri::command]
 pub fn update_proxy_config(config: ProxyConfig) -> Result<(), String> {
-    let client = build_client(config.url.trim())?;
+    let settings = NetworkSettings {
+        proxy_url: Some(config.url),
+        ..current_settings()
+    };
+    apply_network_settings(settings.cleaned())
+}
+
+/// 按网络设置重建共享客户端，设置无效时保持原客户端不变
+pub fn apply_network_settings(settings: NetworkSettings) -> Result<(), String> {
+    let client = build_client(&settings)?;
     let mut guard = http_client()
         .write()
         .map_err(|_| "更新 HTTP 客户端失败".to_string())?;
-    *guard = client;
+    *guard = SharedClient { client, settings };
     Ok(())
 }
 
-fn build_client(proxy_url: &str) -> Result<Client, String> {
+/// 启动时按已保存的网络设置初始化共享客户端，失败时保留默认客户端
+pub async fn init_from_settings(db: &DatabaseConnection) {
+    let settings = match db.get_settings().await {
+        Ok(settings) => settings.network_settings(),
+        Err(e) => {
+            log::warn!("读取网络设置失败，使用默认设置: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = apply_network_settings(settings) {
+        log::warn!("应用网络设置失败，使用默认设置: {}", e);
+    }
+}
+
+/// 校验网络设置能否构建客户端（如代理地址是否有效）
+pub fn validate_network_settings(settings: &NetworkSettings) -> Result<(), String> {
+    build_client(settings).map(|_| ())
+}
+
+fn build_proxy(proxy_url: &str) -> Result<Proxy, String> {
+    let scheme = proxy_url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(scheme.as_str(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "代理地址无效: 仅支持 http://、https://、socks5:// 或 socks5h:// 代理，当前为 {proxy_url}"
+        ));
+    }
+
+    Ok(Proxy::all(proxy_url)
+        .map_err(|e| format!("代理地址无效: {e}"))?
+        .no_proxy(NoProxy::from_string(LOCAL_PROXY_BYPASS)))
+}
+
+fn build_client(settings: &NetworkSettings) -> Result<Client, String> {
+    let timeout_secs = settings.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
     let mut builder = Client::builder()
-        .connect_timeout(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS))
-        .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-        .user_agent(GLOBAL_USER_AGENT);
-
-    if !proxy_url.is_empty() {
-        let proxy = Proxy::all(proxy_url)
-            .map_err(|e| format!("代理地址无效: {e}"))?
-            .no_proxy(NoProxy::from_string(LOCAL_PROXY_BYPASS));
-        builder = builder.proxy(proxy);
+        .connect_timeout(Duration::from_secs(
+            DEFAULT_CONNECT_TIMEOUT_SECS.min(timeout_secs),
+        ))
+        .timeout(Duration::from_secs(timeout_secs))
+        .user_agent(settings.user_agent.as_deref().unwrap_or(GLOBAL_USER_AGENT));
+
+    if let Some(proxy_url) = settings.proxy_url.as_deref() {
+        builder = builder.proxy(build_proxy(proxy_url)?);
     }
 
     builder
@@ -48,14 +111,170 @@ fn build_client(proxy_url: &str) -> Result<Client, String> {
         .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
 }
 
-fn http_client() -> &'static RwLock<Client> {
-    GLOBAL_HTTP_CLIENT
-        .get_or_init(|| RwLock::new(build_client("").expect("failed to build default http client")))
+fn http_client() -> &'static RwLock<SharedClient> {
+    GLOBAL_HTTP_CLIENT.get_or_init(|| {
+        let settings = NetworkSettings::default();
+        RwLock::new(SharedClient {
+            client: build_client(&settings).expect("failed to build default http client"),
+            settings,
+        })
+    })
+}
+
+fn current_settings() -> NetworkSettings {
+    http_client()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .settings
+        .clone()
 }
 
 pub fn get_client() -> Client {
     http_client()
         .read()
         .unwrap_or_else(|e| e.into_inner())
+        .client
         .clone()
 }
+
+fn should_retry_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 发送请求，连接失败、超时、429 与 5xx 响应按网络设置的次数重试
+///
+/// 请求体无法复制（如流式请求体）时只发送一次。
+pub async fn send_with_retry(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    send_with_retry_impl(request, true).await
+}
+
+/// `retry_rate_limited` 为 false 时 429 响应直接返回，由调用方按限速策略处理
+async fn send_with_retry_impl(
+    request: RequestBuilder,
+    retry_rate_limited: bool,
+) -> Result<Response, reqwest::Error> {
+    let max_retries = current_settings()
+        .max_retries
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+    let mut attempt = 0;
+    loop {
+        let Some(current) = (attempt < max_retries)
+            .then(|| request.try_clone())
+            .flatten()
+        else {
+            return request.send().await;
+        };
+
+        match current.send().await {
+            Ok(response)
+                if !should_retry_status(response.status())
+                    || (!retry_rate_limited
+                        && response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS) =>
+            {
+                return Ok(response);
+            }
+            Ok(response) => log::debug!(
+                "HTTP 请求返回 {}，准备第 {} 次重试: {}",
+                response.status(),
+                attempt + 1,
+                response.url()
+            ),
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                log::debug!("HTTP 请求失败，准备第 {} 次重试: {}", attempt + 1, e)
+            }
+            Err(e) => return Err(e),
+        }
+
+        tokio::time::sleep(Duration::from_millis(RETRY_BASE_DELAY_MS << attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// 转发给前端的 HTTP 响应
+#[derive(Debug, Serialize)]
+pub struct HttpResponseData {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// 使用共享客户端发送前端的 HTTP 请求
+///
+/// 代理、User-Agent、超时与重试设置与后端请求一致；429 响应不重试，交由前端的限速逻辑处理。
+/// 请求头中的 User-Agent 会覆盖设置中的值。
+///
+/// # Arguments
+/// * `method` - 请求方法
+/// * `url` - 完整请求地址（已包含查询参数）
+/// * `headers` - 请求头
+/// * `body` - 请求体文本
+#[tauri::command]
+pub async fn send_http_request(
+    method: String,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    body: Option<String>,
+) -> Result<HttpResponseData, String> {
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|e| format!("无效的请求方法 {}: {}", method, e))?;
+    let mut request = get_client().request(method, &url);
+    for (name, value) in headers.unwrap_or_default() {
+        request = request.header(name, value);
+    }
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+
+    let response = send_with_retry_impl(request, false)
+        .await
+        .map_err(|e| format!("请求失败 {}: {}", url, e))?;
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("读取响应失败 {}: {}", url, e))?;
+    Ok(HttpResponseData {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_network_settings() {
+        let settings = |proxy_url: &str| NetworkSettings {
+            proxy_url: Some(proxy_url.to_string()),
+            ..Default::default()
+        };
+
+        assert!(validate_network_settings(&NetworkSettings::default()).is_ok());
+        assert!(validate_network_settings(&settings("http://127.0.0.1:7890")).is_ok());
+        assert!(validate_network_settings(&settings("socks5://127.0.0.1:7890")).is_ok());
+        assert!(validate_network_settings(&settings("socks5h://127.0.0.1:7890")).is_ok());
+        assert!(validate_network_settings(&settings("ftp://127.0.0.1:7890")).is_err());
+        assert!(validate_network_settings(&settings("127.0.0.1:7890")).is_err());
+
+        let cleaned = NetworkSettings {
+            proxy_url: Some("  ".to_string()),
+            user_agent: Some(" Reina ".to_string()),
+            timeout_secs: Some(1),
+            max_retries: Some(99),
+        }
+        .cleaned();
+        assert_eq!(cleaned.proxy_url, None);
+        assert_eq!(cleaned.user_agent.as_deref(), Some("Reina"));
+        assert_eq!(cleaned.timeout_secs, Some(5));
+        assert_eq!(cleaned.max_retries, Some(NetworkSettings::MAX_RETRIES));
+    }
+}
@@ -168,6 +168,14 @@ async fn fetch_image(url: &str) -> Result<(Vec<u8>, String), StatusCode> {
     Ok((bytes.to_vec(), content_type))
 }
 
+/// 下载网络图片，返回图片字节
+pub async fn download_image(url: &str) -> Result<Vec<u8>, String> {
+    fetch_image(url)
+        .await
+        .map(|(bytes, _)| bytes)
+        .map_err(|status| format!("下载图片失败: {}", status))
+}
+
 pub fn register_image_proxy_protocol<R: tauri::Runtime>(
     builder: tauri::Builder<R>,
 ) -> tauri::Builder<R> {
@@ -0,0 +1,219 @@
+//! OBS「正在游玩」文本输出
+//!
+//! 会话开始时把渲染后的文案写入用户指定的文本文件，OBS 添加「文本（读取自文件）」来源即可显示；
+//! 会话期间定期刷新时长，结束后清空文件。同时运行多个游戏时输出最近开始的会话，
+//! 结束后回退到仍在运行的上一个会话。本次时长按会话开始以来的实际时间计算。
+
+use crate::database::repository::game_stats_repository::GameStatsRepository;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::utils::discord_rpc::{DEFAULT_NSFW_PLACEHOLDER, format_playtime};
+use parking_lot::Mutex;
+use sea_orm::DatabaseConnection;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 默认文案模板
+pub const DEFAULT_OBS_TEMPLATE: &str = "正在游玩：{name}\n累计 {playtime}";
+
+/// 文件刷新间隔
+const REFRESH_INTERVAL_SECS: u64 = 30;
+
+/// 正在输出的会话
+#[derive(Debug, Clone)]
+struct OutputSession {
+    game_id: u32,
+    path: PathBuf,
+    template: String,
+    name: String,
+    /// 本次会话之前的累计时长（分钟）
+    base_minutes: u64,
+    /// 会话开始时间（Unix 秒）
+    start_time: u64,
+}
+
+impl OutputSession {
+    fn render(&self, now: u64) -> String {
+        let session_minutes = now.saturating_sub(self.start_time) / 60;
+        render_obs_template(
+            &self.template,
+            &self.name,
+            self.base_minutes + session_minutes,
+            session_minutes,
+        )
+    }
+}
+
+/// 按开始顺序排列的会话，最后一个为当前输出的会话
+static OUTPUT_SESSIONS: Mutex<Vec<OutputSession>> = Mutex::new(Vec::new());
+
+/// 渲染文案模板，支持 `{name}`、`{playtime}` 与 `{session}` 占位符
+pub fn render_obs_template(
+    template: &str,
+    name: &str,
+    playtime_minutes: u64,
+    session_minutes: u64,
+) -> String {
+    template
+        .replace("{name}", name)
+        .replace("{playtime}", &format_playtime(playtime_minutes))
+        .replace("{session}", &format_playtime(session_minutes))
+        .trim()
+        .to_string()
+}
+
+fn now_secs() -> u64 {
+    u64::try_from(chrono::Utc::now().timestamp()).unwrap_or(0)
+}
+
+async fn write_output(path: &Path, text: &str) {
+    if let Err(e) = tokio::fs::write(path, text).await {
+        log::warn!("写入 OBS 文本文件失败 {}: {}", path.display(), e);
+    }
+}
+
+/// 当前应输出的会话
+fn current_session() -> Option<OutputSession> {
+    OUTPUT_SESSIONS.lock().last().cloned()
+}
+
+/// 按用户设置开始输出会话，并在会话期间定期刷新
+pub(crate) async fn start_obs_output(db: &DatabaseConnection, game_id: u32, start_time: u64) {
+    let session = match build_output_session(db, game_id, start_time).await {
+        Ok(Some(session)) => session,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("生成 OBS 文本失败 game_id={}: {}", game_id, e);
+            return;
+        }
+    };
+    {
+        let mut sessions = OUTPUT_SESSIONS.lock();
+        sessions.retain(|existing| existing.game_id != game_id);
+        sessions.push(session.clone());
+    }
+    write_output(&session.path, &session.render(now_secs())).await;
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let sessions = OUTPUT_SESSIONS.lock().clone();
+            if !sessions
+                .iter()
+                .any(|existing| existing.game_id == game_id && existing.start_time == start_time)
+            {
+                break;
+            }
+            if let Some(current) = sessions.last().filter(|current| current.game_id == game_id) {
+                write_output(&current.path, &current.render(now_secs())).await;
+            }
+        }
+    });
+}
+
+async fn build_output_session(
+    db: &DatabaseConnection,
+    game_id: u32,
+    start_time: u64,
+) -> Result<Option<OutputSession>, String> {
+    let settings = db.get_settings().await?.obs_output_settings();
+    let Some(path) = settings.path.filter(|_| settings.enabled) else {
+        return Ok(None);
+    };
+
+    let db_game_id = i32::try_from(game_id).map_err(|_| "游戏 ID 超出范围".to_string())?;
+    let game = GamesRepository::find_by_id(db, db_game_id)
+        .await
+        .map_err(|e| format!("查询游戏失败: {}", e))?
+        .ok_or_else(|| format!("游戏 {} 不存在", game_id))?;
+    let base_minutes = GameStatsRepository::get_statistics(db, db_game_id)
+        .await
+        .map_err(|e| format!("查询游戏统计失败: {}", e))?
+        .and_then(|stats| stats.total_time)
+        .map_or(0, |minutes| u64::try_from(minutes).unwrap_or(0));
+
+    let name = if settings.hide_nsfw && game.is_nsfw() {
+        DEFAULT_NSFW_PLACEHOLDER.to_string()
+    } else {
+        game.display_name()
+            .unwrap_or_else(|| format!("游戏 #{}", game_id))
+    };
+    Ok(Some(OutputSession {
+        game_id,
+        path: PathBuf::from(path),
+        template: settings
+            .template
+            .unwrap_or_else(|| DEFAULT_OBS_TEMPLATE.to_string()),
+        name,
+        base_minutes,
+        start_time,
+    }))
+}
+
+/// 会话结束：回退到仍在运行的上一个会话，没有时清空文件
+pub(crate) async fn stop_obs_output(game_id: u32) {
+    let removed = {
+        let mut sessions = OUTPUT_SESSIONS.lock();
+        let index = sessions
+            .iter()
+            .position(|session| session.game_id == game_id);
+        index.map(|index| sessions.remove(index))
+    };
+    let Some(removed) = removed else {
+        return;
+    };
+
+    match current_session() {
+        Some(current) => {
+            if current.path != removed.path {
+                write_output(&removed.path, "").await;
+            }
+            write_output(&current.path, &current.render(now_secs())).await;
+        }
+        None => write_output(&removed.path, "").await,
+    }
+}
+
+/// 关闭输出时清空所有会话的文件
+pub(crate) async fn clear_obs_output() {
+    let sessions = std::mem::take(&mut *OUTPUT_SESSIONS.lock());
+    let mut cleared: Vec<PathBuf> = Vec::new();
+    for session in sessions {
+        if !cleared.contains(&session.path) {
+            write_output(&session.path, "").await;
+            cleared.push(session.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_template() {
+        assert_eq!(
+            render_obs_template(DEFAULT_OBS_TEMPLATE, "白昼夢の青写真", 125, 5),
+            "正在游玩：白昼夢の青写真\n累计 2小时5分钟"
+        );
+        assert_eq!(
+            render_obs_template(" {name} · 本次 {session} ", "ATRI", 0, 0),
+            "ATRI · 本次 0分钟"
+        );
+
+        let session = OutputSession {
+            game_id: 1,
+            path: PathBuf::from("now_playing.txt"),
+            template: "{playtime}/{session}".to_string(),
+            name: String::new(),
+            base_minutes: 60,
+            start_time: 1_000,
+        };
+        assert_eq!(
+            session.render(1_000 + 90 * 60 + 59),
+            "2小时30分钟/1小时30分钟"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! 系统休眠抑制
+//!
+//! 游戏会话期间阻止系统自动睡眠与息屏，守卫析构时自动释放。
+//! - Windows：在专用线程上调用 `SetThreadExecutionState`（该状态按线程生效，线程退出即失效）
+//! - Linux：通过 `systemd-inhibit` 持有 sleep/idle 抑制锁
+
+use log::{debug, warn};
+
+#[cfg(target_os = "windows")]
+use std::sync::mpsc::{Sender, channel};
+
+#[cfg(target_os = "linux")]
+use std::process::{Child, Command, Stdio};
+
+/// 休眠抑制守卫
+///
+/// 持有期间系统不会因空闲进入睡眠或关闭显示器，析构时释放。
+pub struct SleepInhibitor {
+    #[cfg(target_os = "windows")]
+    release: Option<Sender<()>>,
+    #[cfg(target_os = "linux")]
+    child: Child,
+}
+
+impl SleepInhibitor {
+    /// 开始阻止系统休眠
+    ///
+    /// # Arguments
+    /// * `reason` - 抑制原因，Linux 下会显示在 `systemd-inhibit --list` 中
+    #[cfg(target_os = "windows")]
+    pub fn acquire(reason: &str) -> Result<Self, String> {
+        use windows::Win32::System::Power::{
+            ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+        };
+
+        let (release_tx, release_rx) = channel::<()>();
+        let (ready_tx, ready_rx) = channel::<bool>();
+
+        std::thread::Builder::new()
+            .name("reina-sleep-inhibitor".to_string())
+            .spawn(move || {
+                let previous = unsafe {
+                    SetThreadExecutionState(
+                        ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+                    )
+                };
+                let _ = ready_tx.send(previous.0 != 0);
+                if previous.0 == 0 {
+                    return;
+                }
+
+                // 等待守卫析构（发送端被丢弃时 recv 同样返回）
+                let _ = release_rx.recv();
+                unsafe {
+                    SetThreadExecutionState(ES_CONTINUOUS);
+                }
+            })
+            .map_err(|e| format!("创建休眠抑制线程失败: {}", e))?;
+
+        match ready_rx.recv() {
+            Ok(true) => {
+                debug!("已阻止系统休眠: {}", reason);
+                Ok(Self {
+                    release: Some(release_tx),
+                })
+            }
+            _ => Err("SetThreadExecutionState 调用失败".to_string()),
+        }
+    }
+
+    /// 开始阻止系统休眠
+    ///
+    /// # Arguments
+    /// * `reason` - 抑制原因，Linux 下会显示在 `systemd-inhibit --list` 中
+    #[cfg(target_os = "linux")]
+    pub fn acquire(reason: &str) -> Result<Self, String> {
+        let child = Command::new("systemd-inhibit")
+            .arg("--what=sleep:idle")
+            .arg("--who=ReinaManager")
+            .arg(format!("--why={}", reason))
+            .arg("--mode=block")
+            .arg("sleep")
+            .arg("infinity")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("启动 systemd-inhibit 失败: {}", e))?;
+
+        debug!("已阻止系统休眠: {}, inhibitor pid={}", reason, child.id());
+        Ok(Self { child })
+    }
+}
+
+impl Drop for SleepInhibitor {
+    #[cfg(target_os = "windows")]
+    fn drop(&mut self) {
+        if let Some(release) = self.release.take() {
+            let _ = release.send(());
+        }
+        debug!("已释放系统休眠抑制");
+    }
+
+    #[cfg(target_os = "linux")]
+    fn drop(&mut self) {
+        if let Err(e) = self.child.kill() {
+            warn!("结束 systemd-inhibit 进程失败: {}", e);
+        }
+        let _ = self.child.wait();
+        debug!("已释放系统休眠抑制");
+    }
+}
+
+/// 按设置决定是否为游戏会话阻止系统休眠，失败时仅记录日志
+pub fn inhibit_sleep_for_game(enabled: bool, game_id: u32) -> Option<SleepInhibitor> {
+    if !enabled {
+        return None;
+    }
+
+    match SleepInhibitor::acquire(&format!("正在游玩游戏 {}", game_id)) {
+        Ok(inhibitor) => Some(inhibitor),
+        Err(e) => {
+            warn!("阻止系统休眠失败 game_id={}: {}", game_id, e);
+            None
+        }
+    }
+}
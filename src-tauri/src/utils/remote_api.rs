@@ -0,0 +1,628 @@
+//! 局域网远程控制接口
+//!
+//! 可选的内置 HTTP 服务，监听 `0.0.0.0:<port>`，供手机等局域网设备使用：
+//! - `GET /api/games`：游戏列表（不含隐藏的游戏，安全模式下不含 NSFW 作品）
+//! - `GET /api/stats`：全库总览，同 [`crate::game::summary::get_library_summary`]
+//! - `POST /api/games/{id}/launch`：启动游戏
+//! - `GET/POST /api/sync`：局域网同步的快照读取与合并，见 [`crate::sync::lan`]
+//!
+//! 每个请求都需携带 `Authorization: Bearer <token>` 或查询参数 `?token=`。同步接口使用
+//! 局域网同步的配对密钥，其余接口使用访问令牌，只知道访问令牌的设备无法读写同步数据。
+//! 启动游戏与 `reina://` 链接一样交由前端执行，以沿用用户的计时模式设置。
+//!
+//! 只实现接口需要的最小 HTTP/1.1 子集：每个连接处理一个请求，鉴权通过后才读取请求体。
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::game_stats_repository::GameStatsRepository;
+use crate::database::repository::games_repository::{
+    GameType, GamesRepository, SortOption, SortOrder,
+};
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::database::service::list_filter;
+use crate::entity::user::RemoteApiSettings;
+use crate::game::summary::{DEFAULT_TOP_N, library_summary};
+use crate::sync::lan::{self, SyncSnapshot};
+use crate::utils::deep_link::{DeepLink, handle_deep_link};
+use parking_lot::Mutex;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Runtime, command};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 请求行与请求头的最大长度
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+/// 请求体的最大长度（同步快照包含全部游玩会话）
+const MAX_REQUEST_BODY_BYTES: usize = 64 * 1024 * 1024;
+/// 读取请求头的超时时间
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// 读取请求体的超时时间
+const REQUEST_BODY_TIMEOUT_SECS: u64 = 120;
+
+/// 正在运行的服务
+struct RunningServer {
+    port: u16,
+    /// 令牌变更时直接替换，无需重启监听
+    token: String,
+    task: JoinHandle<()>,
+}
+
+static RUNNING_SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// 游戏列表条目，只包含远程展示需要的字段
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteGame {
+    id: i32,
+    name: Option<String>,
+    /// 远程封面地址，本地自定义封面不返回
+    cover: Option<String>,
+    clear: Option<i32>,
+    favorite: bool,
+    /// 是否设置了可用的启动程序
+    launchable: bool,
+    /// 总游玩时长（分钟）
+    total_playtime: i64,
+    last_played: Option<i32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    bearer_token: Option<String>,
+    query_token: Option<String>,
+    content_length: usize,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Games,
+    Stats,
+    Launch(i32),
+    SyncSnapshot,
+    SyncMerge,
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, json!({ "error": message }))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            202 => "Accepted",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        };
+        let body = self.body.to_string();
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+}
+
+/// 校验远程控制设置：启用时必须设置足够长的访问令牌
+pub fn validate_remote_api_settings(settings: &RemoteApiSettings) -> Result<(), String> {
+    let token_too_short = settings
+        .token
+        .as_ref()
+        .is_none_or(|token| token.chars().count() < RemoteApiSettings::MIN_TOKEN_LEN);
+    if settings.enabled && token_too_short {
+        return Err(format!(
+            "启用远程控制前请设置至少 {} 位的访问令牌",
+            RemoteApiSettings::MIN_TOKEN_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// 按设置启动、重启或停止服务
+///
+/// 只修改令牌时沿用现有监听；端口无法监听时返回错误，原服务已停止。
+pub async fn apply_remote_api_settings<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    settings: RemoteApiSettings,
+) -> Result<(), String> {
+    validate_remote_api_settings(&settings)?;
+    let port = settings.port_value();
+    let token = settings.token.unwrap_or_default();
+    {
+        let mut running = RUNNING_SERVER.lock();
+        if settings.enabled
+            && let Some(server) = running.as_mut()
+            && server.port == port
+        {
+            server.token = token;
+            return Ok(());
+        }
+        if let Some(server) = running.take() {
+            server.task.abort();
+            log::info!("远程控制接口已停止");
+        }
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .map_err(|e| format!("远程控制接口监听端口 {} 失败: {}", port, e))?;
+    let server = serve(listener, app_handle.clone(), db.clone());
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::join!(server, lan::answer_discovery(port));
+    });
+    let previous = RUNNING_SERVER
+        .lock()
+        .replace(RunningServer { port, token, task });
+    if let Some(server) = previous {
+        server.task.abort();
+    }
+    log::info!("远程控制接口已启动，端口 {}", port);
+    Ok(())
+}
+
+/// 启动时按已保存的设置启动服务，失败时只记录日志
+pub async fn init_from_settings<R: Runtime>(app_handle: &AppHandle<R>, db: &DatabaseConnection) {
+    let settings = match db.get_settings().await {
+        Ok(settings) => settings.remote_api_settings(),
+        Err(e) => {
+            log::warn!("读取远程控制设置失败: {}", e);
+            return;
+        }
+    };
+    if !settings.enabled {
+        return;
+    }
+    if let Err(e) = apply_remote_api_settings(app_handle, db, settings).await {
+        log::warn!("启动远程控制接口失败: {}", e);
+    }
+}
+
+/// 生成随机访问令牌，供前端填入设置
+#[command]
+pub fn generate_remote_api_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| format!("生成访问令牌失败: {}", e))?;
+
+    let mut token = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(&mut token, "{byte:02x}").map_err(|e| format!("生成访问令牌失败: {}", e))?;
+    }
+    Ok(token)
+}
+
+async fn serve<R: Runtime>(
+    listener: TcpListener,
+    app_handle: AppHandle<R>,
+    db: DatabaseConnection,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let app_handle = app_handle.clone();
+                let db = db.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &app_handle, &db).await {
+                        log::debug!("远程控制请求处理失败 {}: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::warn!("远程控制接口接受连接失败: {}", e);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection<R: Runtime>(
+    mut stream: TcpStream,
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+) -> Result<(), String> {
+    let (head, body) = tokio::time::timeout(
+        Duration::from_secs(REQUEST_TIMEOUT_SECS),
+        read_request_head(&mut stream),
+    )
+    .await
+    .map_err(|_| "读取请求超时".to_string())??;
+
+    let response = match parse_request(&head) {
+        Some(mut request) => match check_request(&request).await {
+            Ok(route) => {
+                if request.content_length > MAX_REQUEST_BODY_BYTES {
+                    Response::error(413, "请求体过大")
+                } else {
+                    request.body = tokio::time::timeout(
+                        Duration::from_secs(REQUEST_BODY_TIMEOUT_SECS),
+                        read_request_body(&mut stream, body, request.content_length),
+                    )
+                    .await
+                    .map_err(|_| "读取请求体超时".to_string())??;
+                    respond(route, &request, app_handle, db).await
+                }
+            }
+            Err(response) => response,
+        },
+        None => Response::error(400, "无效的请求"),
+    };
+    stream
+        .write_all(&response.to_bytes())
+        .await
+        .map_err(|e| format!("发送响应失败: {}", e))?;
+    let _ = stream.shutdown().await;
+    Ok(())
+}
+
+/// 读取到空行为止的请求行与请求头，同时返回已读到的部分请求体
+async fn read_request_head(stream: &mut TcpStream) -> Result<(String, Vec<u8>), String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("读取请求失败: {}", e))?;
+        if read == 0 {
+            return Err("连接已关闭".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            let body = buffer.split_off(end + 4);
+            buffer.truncate(end);
+            return Ok((String::from_utf8_lossy(&buffer).into_owned(), body));
+        }
+        if buffer.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err("请求头过大".to_string());
+        }
+    }
+}
+
+/// 按 `Content-Length` 读完请求体
+async fn read_request_body(
+    stream: &mut TcpStream,
+    mut body: Vec<u8>,
+    content_length: usize,
+) -> Result<Vec<u8>, String> {
+    let mut chunk = vec![0u8; 64 * 1024];
+    while body.len() < content_length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("读取请求体失败: {}", e))?;
+        if read == 0 {
+            return Err("请求体不完整".to_string());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+    Ok(body)
+}
+
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_ascii_uppercase();
+    let target = request_line.next()?;
+    if !request_line.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query_token = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned());
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    };
+    let bearer_token = header("authorization").and_then(|value| {
+        let (scheme, credentials) = value.split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| credentials.trim().to_string())
+    });
+    let content_length = match header("content-length") {
+        Some(value) => value.parse().ok()?,
+        None => 0,
+    };
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        bearer_token,
+        query_token,
+        content_length,
+        body: Vec::new(),
+    })
+}
+
+/// 逐字节比较令牌，耗时与匹配位置无关
+fn token_matches(candidate: &str, token: &str) -> bool {
+    candidate.len() == token.len()
+        && candidate
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+    !token.is_empty()
+        && request
+            .bearer_token
+            .as_deref()
+            .or(request.query_token.as_deref())
+            .is_some_and(|candidate| token_matches(candidate, token))
+}
+
+fn route(method: &str, path: &str) -> Result<Route, Response> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let routes = match segments.as_slice() {
+        ["api", "games"] => vec![("GET", Route::Games)],
+        ["api", "stats"] => vec![("GET", Route::Stats)],
+        ["api", "sync"] => vec![("GET", Route::SyncSnapshot), ("POST", Route::SyncMerge)],
+        ["api", "games", id, "launch"] => {
+            let game_id = id
+                .parse::<i32>()
+                .map_err(|_| Response::error(400, "无效的游戏 ID"))?;
+            vec![("POST", Route::Launch(game_id))]
+        }
+        _ => return Err(Response::error(404, "未知的接口")),
+    };
+    routes
+        .into_iter()
+        .find(|(expected_method, _)| *expected_method == method)
+        .map(|(_, route)| route)
+        .ok_or_else(|| Response::error(405, "不支持的请求方法"))
+}
+
+/// 匹配接口并鉴权，通过后才读取请求体
+///
+/// 同步接口校验局域网同步的配对密钥，其余接口校验访问令牌。
+async fn check_request(request: &Request) -> Result<Route, Response> {
+    let token = RUNNING_SERVER
+        .lock()
+        .as_ref()
+        .map(|server| server.token.clone());
+    let Some(token) = token else {
+        return Err(Response::error(503, "远程控制接口已关闭"));
+    };
+    let route = route(&request.method, &request.path)?;
+    if matches!(route, Route::SyncSnapshot | Route::SyncMerge) {
+        let Some(secret) = lan::pairing_secret().await else {
+            return Err(Response::error(403, "本机未设置局域网同步配对密钥"));
+        };
+        if !authorized(request, &secret) {
+            return Err(Response::error(401, "配对密钥无效"));
+        }
+    } else if !authorized(request, &token) {
+        return Err(Response::error(401, "访问令牌无效"));
+    }
+    Ok(route)
+}
+
+async fn respond<R: Runtime>(
+    route: Route,
+    request: &Request,
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+) -> Response {
+    let result = match route {
+        Route::Games => list_games(db)
+            .await
+            .map(|games| Response::json(200, json!(games))),
+        Route::Stats => library_summary(db, DEFAULT_TOP_N)
+            .await
+            .map(|summary| Response::json(200, json!(summary))),
+        Route::Launch(game_id) => launch_game(app_handle, db, game_id).await,
+        Route::SyncSnapshot => lan::build_snapshot(db)
+            .await
+            .map(|snapshot| Response::json(200, json!(snapshot))),
+        Route::SyncMerge => match serde_json::from_slice::<SyncSnapshot>(&request.body) {
+            Ok(snapshot) => lan::merge_snapshot(db, &snapshot)
+                .await
+                .map(|report| Response::json(200, json!(report))),
+            Err(e) => Ok(Response::error(400, &format!("无效的同步数据: {}", e))),
+        },
+    };
+    result.unwrap_or_else(|e| {
+        log::warn!(
+            "远程控制请求失败 {} {}: {}",
+            request.method,
+            request.path,
+            e
+        );
+        Response::error(500, &e)
+    })
+}
+
+fn is_launchable(game: &FullGameData) -> bool {
+    let non_empty = |value: Option<&str>| value.is_some_and(|value| !value.trim().is_empty());
+    game.missing != Some(1)
+        && non_empty(game.localpath.as_deref())
+        && non_empty(game.executable.as_deref())
+}
+
+async fn list_games(db: &DatabaseConnection) -> Result<Vec<RemoteGame>, String> {
+    let filter = list_filter(db, None).await?;
+    let games = GamesRepository::find_listed(
+        db,
+        GameType::All,
+        SortOption::LastPlayed,
+        SortOrder::Desc,
+        None,
+        filter,
+    )
+    .await
+    .map_err(|e| format!("获取游戏数据失败: {}", e))?;
+    let statistics: HashMap<i32, _> = GameStatsRepository::get_all_statistics(db)
+        .await
+        .map_err(|e| format!("读取游戏统计失败: {}", e))?
+        .into_iter()
+        .map(|item| (item.game_id, item))
+        .collect();
+
+    Ok(games
+        .iter()
+        .map(|game| {
+            let stats = statistics.get(&game.id);
+            RemoteGame {
+                id: game.id,
+                name: game.display_name(),
+                cover: game.remote_cover_url(),
+                clear: game.clear,
+                favorite: game.favorite,
+                launchable: is_launchable(game),
+                total_playtime: stats
+                    .and_then(|stats| stats.total_time)
+                    .map(i64::from)
+                    .unwrap_or(0),
+                last_played: stats.and_then(|stats| stats.last_played),
+            }
+        })
+        .collect())
+}
+
+/// 校验游戏可以启动后交给前端处理
+async fn launch_game<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db: &DatabaseConnection,
+    game_id: i32,
+) -> Result<Response, String> {
+    let filter = list_filter(db, Some(true)).await?;
+    let game = GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏数据失败: {}", e))?
+        .filter(|game| game.deleted_at.is_none() && !(filter.exclude_nsfw && game.is_nsfw()));
+    let Some(game) = game else {
+        return Ok(Response::error(404, "游戏不存在"));
+    };
+    if !is_launchable(&game) {
+        return Ok(Response::error(409, "游戏未设置可用的启动程序"));
+    }
+
+    log::info!("远程控制请求启动游戏 game_id={}", game_id);
+    handle_deep_link(
+        app_handle,
+        DeepLink::Launch {
+            game_id: game_id as u32,
+        },
+    );
+    Ok(Response::json(202, json!({ "gameId": game_id })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_authorizes_requests() {
+        let request = parse_request(
+            "POST /api/games/42/launch HTTP/1.1\r\nHost: 192.168.1.2\r\nauthorization: Bearer secret-token",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.bearer_token.as_deref(), Some("secret-token"));
+        assert!(authorized(&request, "secret-token"));
+        assert!(!authorized(&request, "secret-tokem"));
+        assert_eq!(route(&request.method, &request.path), Ok(Route::Launch(42)));
+
+        let request = parse_request("GET /api/games/?token=a%20b HTTP/1.1").unwrap();
+        assert_eq!(request.path, "/api/games/");
+        assert!(authorized(&request, "a b"));
+        assert!(!authorized(&request, ""));
+        assert_eq!(route(&request.method, &request.path), Ok(Route::Games));
+
+        assert_eq!(route("POST", "/api/sync"), Ok(Route::SyncMerge));
+        assert_eq!(route("POST", "/api/stats").unwrap_err().status, 405);
+        assert_eq!(
+            route("POST", "/api/games/x/launch").unwrap_err().status,
+            400
+        );
+        assert_eq!(route("GET", "/").unwrap_err().status, 404);
+        assert!(parse_request("GET /api/games").is_none());
+        assert_eq!(
+            parse_request("POST /api/sync HTTP/1.1\r\nContent-Length: 128")
+                .unwrap()
+                .content_length,
+            128
+        );
+        assert!(parse_request("POST /api/sync HTTP/1.1\r\nContent-Length: -1").is_none());
+    }
+
+    #[tokio::test]
+    async fn unauthorized_requests_get_matching_status_line() {
+        let task = tauri::async_runtime::spawn(async {});
+        *RUNNING_SERVER.lock() = Some(RunningServer {
+            port: RemoteApiSettings::DEFAULT_PORT,
+            token: "secret-token".to_string(),
+            task,
+        });
+        let request =
+            parse_request("GET /api/games HTTP/1.1\r\nAuthorization: Bearer wrong").unwrap();
+        let response = check_request(&request).await.unwrap_err();
+        RUNNING_SERVER.lock().take();
+
+        let bytes = String::from_utf8(response.to_bytes()).unwrap();
+        assert!(bytes.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        let bytes = String::from_utf8(Response::error(403, "forbidden").to_bytes()).unwrap();
+        assert!(bytes.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+    }
+
+    #[test]
+    fn validates_settings() {
+        let mut settings = RemoteApiSettings {
+            enabled: true,
+            port: None,
+            token: Some("short".to_string()),
+        };
+        assert!(validate_remote_api_settings(&settings).is_err());
+        settings.token = Some("long-enough".to_string());
+        assert!(validate_remote_api_settings(&settings).is_ok());
+        settings.enabled = false;
+        settings.token = None;
+        assert!(validate_remote_api_settings(&settings).is_ok());
+
+        let token = generate_remote_api_token().unwrap();
+        assert_eq!(token.len(), 32);
+    }
+}
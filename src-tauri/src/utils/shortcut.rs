@@ -0,0 +1,242 @@
+//! Windows 快捷方式（.lnk）解析与创建
+//!
+//! 通过 IShellLinkW 读取快捷方式的真实目标、工作目录与启动参数，
+//! 供启动游戏和扫描目录时把 `start.lnk` 之类的入口还原为实际的可执行文件；
+//! 也用于为游戏生成桌面 / 开始菜单快捷方式。
+
+use std::path::{Path, PathBuf};
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+    CoUninitialize, IPersistFile, STGM_READ,
+};
+use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+use windows::core::{HSTRING, Interface};
+
+/// 路径缓冲区长度，覆盖启用长路径后的常见情况
+const PATH_BUFFER_LEN: usize = 1024;
+
+/// 参数缓冲区长度（INFOTIPSIZE）
+const ARGUMENTS_BUFFER_LEN: usize = 1024;
+
+/// 快捷方式解析结果
+#[derive(Debug, Clone)]
+pub struct ShortcutTarget {
+    /// 目标文件的完整路径
+    pub target: PathBuf,
+    /// 快捷方式中设置的起始位置
+    pub working_dir: Option<PathBuf>,
+    /// 快捷方式附带的原始参数字符串
+    pub arguments: Option<String>,
+}
+
+impl ShortcutTarget {
+    /// 启动时使用的工作目录，未设置起始位置时使用目标所在目录
+    pub fn effective_working_dir(&self) -> Option<PathBuf> {
+        self.working_dir
+            .clone()
+            .or_else(|| self.target.parent().map(Path::to_path_buf))
+    }
+}
+
+/// 判断路径是否为快捷方式文件
+pub fn is_shortcut(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+}
+
+/// 当前线程的 COM 初始化守卫，仅在本次调用成功初始化时负责反初始化
+struct ComGuard {
+    initialized: bool,
+}
+
+impl ComGuard {
+    fn new() -> Self {
+        // 线程已按其他模式初始化时返回 RPC_E_CHANGED_MODE，此时直接沿用现有模式
+        let initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }.is_ok();
+        Self { initialized }
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+/// 解析快捷方式的真实目标
+///
+/// # Arguments
+/// * `path` - .lnk 文件路径
+///
+/// # Returns
+/// 目标路径、工作目录与参数；目标为空（例如指向“此电脑”等虚拟对象）时返回错误
+pub fn resolve_shortcut(path: &Path) -> Result<ShortcutTarget, String> {
+    let _com = ComGuard::new();
+
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("创建 ShellLink 实例失败: {}", e))?;
+        let persist: IPersistFile = link
+            .cast()
+            .map_err(|e| format!("获取 IPersistFile 接口失败: {}", e))?;
+        persist
+            .Load(&HSTRING::from(path), STGM_READ)
+            .map_err(|e| format!("读取快捷方式失败 {}: {}", path.display(), e))?;
+
+        let mut target = [0u16; PATH_BUFFER_LEN];
+        // fFlags 为 0：返回展开环境变量后的长路径
+        link.GetPath(&mut target, std::ptr::null_mut(), 0)
+            .map_err(|e| format!("读取快捷方式目标失败 {}: {}", path.display(), e))?;
+        let target = wide_to_string(&target)
+            .ok_or_else(|| format!("快捷方式没有指向文件: {}", path.display()))?;
+
+        let mut working_dir = [0u16; PATH_BUFFER_LEN];
+        let working_dir = link
+            .GetWorkingDirectory(&mut working_dir)
+            .ok()
+            .and_then(|_| wide_to_string(&working_dir));
+
+        let mut arguments = [0u16; ARGUMENTS_BUFFER_LEN];
+        let arguments = link
+            .GetArguments(&mut arguments)
+            .ok()
+            .and_then(|_| wide_to_string(&arguments));
+
+        Ok(ShortcutTarget {
+            target: PathBuf::from(target),
+            working_dir: working_dir.map(PathBuf::from),
+            arguments,
+        })
+    }
+}
+
+/// 创建快捷方式时写入的内容
+#[derive(Debug, Clone)]
+pub struct ShortcutSpec<'a> {
+    pub target: &'a Path,
+    pub arguments: &'a str,
+    pub working_dir: Option<&'a Path>,
+    /// 图标文件（.ico 或 .exe）路径，使用其中第一个图标
+    pub icon: Option<&'a Path>,
+    pub description: &'a str,
+}
+
+/// 创建或覆盖快捷方式文件
+pub fn create_shortcut(path: &Path, spec: &ShortcutSpec<'_>) -> Result<(), String> {
+    let _com = ComGuard::new();
+
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("创建 ShellLink 实例失败: {}", e))?;
+        link.SetPath(&HSTRING::from(spec.target))
+            .map_err(|e| format!("设置快捷方式目标失败: {}", e))?;
+        link.SetArguments(&HSTRING::from(spec.arguments))
+            .map_err(|e| format!("设置快捷方式参数失败: {}", e))?;
+        link.SetDescription(&HSTRING::from(spec.description))
+            .map_err(|e| format!("设置快捷方式说明失败: {}", e))?;
+        if let Some(working_dir) = spec.working_dir {
+            link.SetWorkingDirectory(&HSTRING::from(working_dir))
+                .map_err(|e| format!("设置快捷方式起始位置失败: {}", e))?;
+        }
+        if let Some(icon) = spec.icon {
+            link.SetIconLocation(&HSTRING::from(icon), 0)
+                .map_err(|e| format!("设置快捷方式图标失败: {}", e))?;
+        }
+
+        let persist: IPersistFile = link
+            .cast()
+            .map_err(|e| format!("获取 IPersistFile 接口失败: {}", e))?;
+        persist
+            .Save(&HSTRING::from(path), true)
+            .map_err(|e| format!("保存快捷方式失败 {}: {}", path.display(), e))
+    }
+}
+
+/// 按 Windows 命令行规则拆分快捷方式的参数字符串
+///
+/// 遵循 MSVC 运行库的解析规则：双引号包裹含空格的参数，引号前的反斜杠成对转义。
+pub fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+    let mut backslashes = 0usize;
+
+    for c in arguments.chars() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                continue;
+            }
+            '"' => {
+                current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                if has_token || !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+                has_token = false;
+            }
+            c => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                current.push(c);
+                has_token = true;
+            }
+        }
+        backslashes = 0;
+    }
+
+    current.extend(std::iter::repeat_n('\\', backslashes));
+    if has_token || !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// 将以 null 结尾的 UTF-16 缓冲区转换为字符串，空字符串返回 None
+fn wide_to_string(buffer: &[u16]) -> Option<String> {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    let value = String::from_utf16_lossy(&buffer[..len]);
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_arguments(r#"-window "C:\Program Files\Game" -lang ja"#),
+            vec!["-window", r"C:\Program Files\Game", "-lang", "ja"]
+        );
+        assert_eq!(split_arguments(r#"a "" b"#), vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn handles_escaped_quotes_and_backslashes() {
+        assert_eq!(split_arguments(r#"say \"hi\""#), vec!["say", r#""hi""#]);
+        assert_eq!(split_arguments(r#"a\\\"b"#), vec![r#"a\"b"#]);
+        // 引号前成对的反斜杠减半，其余反斜杠原样保留
+        assert_eq!(
+            split_arguments(r#""C:\Games\save\\" next dir\"#),
+            vec![r"C:\Games\save\", "next", r"dir\"]
+        );
+    }
+
+    #[test]
+    fn empty_input_yields_no_arguments() {
+        assert!(split_arguments("").is_empty());
+        assert!(split_arguments(" \t ").is_empty());
+    }
+}
@@ -0,0 +1,414 @@
+//! 后台长任务管理
+//!
+//! 目录扫描、批量刷新元数据、占用空间统计、目录迁移与归档、文件复制与游玩状态回写等长任务统一登记到
+//! [`TaskManager`]（注册为 Tauri Managed State）。前端可查询任务列表、取消任务，
+//! 任务的开始、进度与结束统一通过 `task-progress` 事件推送，事件内容为 [`TaskInfo`]。
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, State, command};
+
+/// 任务状态变化事件
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+
+/// 保留的已结束任务数量，超出时丢弃最早结束的任务
+const MAX_FINISHED_TASKS: usize = 50;
+
+/// 进度事件的最小发送间隔
+const PROGRESS_EMIT_INTERVAL_MS: u64 = 200;
+
+/// 任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Scan,
+    MetadataRefresh,
+    GameSize,
+    FolderTransfer,
+    StatusSync,
+    FileCopy,
+    BackupFolderMove,
+    GameArchive,
+}
+
+impl TaskKind {
+    fn label(self) -> &'static str {
+        match self {
+            TaskKind::Scan => "目录扫描",
+            TaskKind::MetadataRefresh => "元数据批量刷新",
+            TaskKind::GameSize => "占用空间统计",
+            TaskKind::FolderTransfer => "游戏目录迁移",
+            TaskKind::StatusSync => "游玩状态回写",
+            TaskKind::FileCopy => "文件复制",
+            TaskKind::BackupFolderMove => "存档备份目录迁移",
+            TaskKind::GameArchive => "游戏归档",
+        }
+    }
+
+    /// 同一时间只允许一个在运行的任务类型
+    fn exclusive(self) -> bool {
+        matches!(
+            self,
+            TaskKind::Scan
+                | TaskKind::MetadataRefresh
+                | TaskKind::GameSize
+                | TaskKind::BackupFolderMove
+                | TaskKind::GameArchive
+        )
+    }
+
+    fn cancellable(self) -> bool {
+        !matches!(self, TaskKind::FolderTransfer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// 任务信息，同时作为 `task-progress` 事件的内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: u64,
+    pub kind: TaskKind,
+    pub status: TaskStatus,
+    pub processed: u64,
+    pub total: u64,
+    pub error: Option<String>,
+    pub cancellable: bool,
+    /// 开始时间（Unix 秒）
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// 按登记顺序保存的任务
+#[derive(Default)]
+struct TaskRegistry {
+    next_id: u64,
+    tasks: Vec<TaskEntry>,
+}
+
+impl TaskRegistry {
+    fn register(
+        &mut self,
+        kind: TaskKind,
+        now: i64,
+    ) -> Result<(TaskInfo, Arc<AtomicBool>), String> {
+        if kind.exclusive()
+            && self
+                .tasks
+                .iter()
+                .any(|task| task.info.kind == kind && task.info.status == TaskStatus::Running)
+        {
+            return Err(format!("{}正在进行中", kind.label()));
+        }
+
+        self.next_id += 1;
+        let info = TaskInfo {
+            id: self.next_id,
+            kind,
+            status: TaskStatus::Running,
+            processed: 0,
+            total: 0,
+            error: None,
+            cancellable: kind.cancellable(),
+            started_at: now,
+            finished_at: None,
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.tasks.push(TaskEntry {
+            info: info.clone(),
+            cancelled: cancelled.clone(),
+        });
+        self.prune();
+        Ok((info, cancelled))
+    }
+
+    fn prune(&mut self) {
+        let finished = self
+            .tasks
+            .iter()
+            .filter(|task| task.info.status != TaskStatus::Running)
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_TASKS);
+        self.tasks.retain(|task| {
+            if excess > 0 && task.info.status != TaskStatus::Running {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+
+    fn update(&mut self, id: u64, apply: impl FnOnce(&mut TaskInfo)) -> Option<TaskInfo> {
+        let task = self.tasks.iter_mut().find(|task| task.info.id == id)?;
+        apply(&mut task.info);
+        Some(task.info.clone())
+    }
+
+    /// 请求取消运行中的任务，任务不可取消或已结束时返回 false
+    fn cancel(&self, predicate: impl Fn(&TaskInfo) -> bool) -> bool {
+        let mut requested = false;
+        for task in self.tasks.iter().filter(|task| {
+            task.info.status == TaskStatus::Running
+                && task.info.cancellable
+                && predicate(&task.info)
+        }) {
+            task.cancelled.store(true, Ordering::Relaxed);
+            requested = true;
+        }
+        requested
+    }
+}
+
+/// 长任务管理器
+#[derive(Clone, Default)]
+pub struct TaskManager {
+    registry: Arc<Mutex<TaskRegistry>>,
+}
+
+impl TaskManager {
+    /// 登记一个新任务并发送开始事件；同类独占任务仍在运行时拒绝
+    pub fn start<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        kind: TaskKind,
+    ) -> Result<TaskHandle<R>, String> {
+        let (info, cancelled) = self
+            .registry
+            .lock()
+            .register(kind, chrono::Utc::now().timestamp())?;
+        log::debug!("开始后台任务 id={} kind={:?}", info.id, kind);
+        emit_task(app_handle, &info);
+        Ok(TaskHandle {
+            id: info.id,
+            app_handle: app_handle.clone(),
+            registry: self.registry.clone(),
+            cancelled,
+            last_emit: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    /// 提交任务：登记后在后台执行 `run`，按其结果结束任务
+    ///
+    /// # Returns
+    /// 任务 ID
+    pub fn spawn<R, F, Fut>(
+        &self,
+        app_handle: &AppHandle<R>,
+        kind: TaskKind,
+        run: F,
+    ) -> Result<u64, String>
+    where
+        R: Runtime,
+        F: FnOnce(Arc<TaskHandle<R>>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let task = Arc::new(self.start(app_handle, kind)?);
+        let id = task.id();
+        tauri::async_runtime::spawn(async move {
+            let result = run(task.clone()).await;
+            task.finish(&result);
+        });
+        Ok(id)
+    }
+
+    pub fn list(&self) -> Vec<TaskInfo> {
+        self.registry
+            .lock()
+            .tasks
+            .iter()
+            .map(|task| task.info.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<TaskInfo> {
+        self.registry
+            .lock()
+            .tasks
+            .iter()
+            .find(|task| task.info.id == id)
+            .map(|task| task.info.clone())
+    }
+
+    pub fn cancel(&self, id: u64) -> bool {
+        self.registry.lock().cancel(|info| info.id == id)
+    }
+
+    /// 取消指定类型的所有运行中任务
+    pub fn cancel_kind(&self, kind: TaskKind) -> bool {
+        self.registry.lock().cancel(|info| info.kind == kind)
+    }
+}
+
+/// 运行中任务的句柄，用于上报进度与检查取消
+///
+/// 可以通过 `Arc` 共享给阻塞线程；未调用 [`TaskHandle::finish`] 就被丢弃时记为失败。
+pub struct TaskHandle<R: Runtime> {
+    id: u64,
+    app_handle: AppHandle<R>,
+    registry: Arc<Mutex<TaskRegistry>>,
+    cancelled: Arc<AtomicBool>,
+    last_emit: Mutex<Option<Instant>>,
+    finished: AtomicBool,
+}
+
+impl<R: Runtime> TaskHandle<R> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 取消标记，供只接受 `AtomicBool` 的阻塞代码检查
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// 更新进度，按时间间隔节流发送事件，完成最后一项时总是发送
+    pub fn progress(&self, processed: u64, total: u64) {
+        let Some(info) = self.registry.lock().update(self.id, |info| {
+            info.processed = processed;
+            info.total = total;
+        }) else {
+            return;
+        };
+
+        {
+            let mut last_emit = self.last_emit.lock();
+            let throttled = last_emit.is_some_and(|last| {
+                last.elapsed() < Duration::from_millis(PROGRESS_EMIT_INTERVAL_MS)
+            });
+            if throttled && processed < total {
+                return;
+            }
+            *last_emit = Some(Instant::now());
+        }
+        emit_task(&self.app_handle, &info);
+    }
+
+    /// 按执行结果结束任务；已请求取消时记为取消，重复调用无效
+    pub fn finish<T>(&self, result: &Result<T, String>) {
+        let (status, error) = match result {
+            _ if self.is_cancelled() => (TaskStatus::Cancelled, None),
+            Ok(_) => (TaskStatus::Completed, None),
+            Err(e) => (TaskStatus::Failed, Some(e.clone())),
+        };
+        self.complete(status, error);
+    }
+
+    fn complete(&self, status: TaskStatus, error: Option<String>) {
+        if self.finished.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let info = {
+            let mut registry = self.registry.lock();
+            let info = registry.update(self.id, |info| {
+                info.status = status;
+                info.error = error;
+                info.finished_at = Some(chrono::Utc::now().timestamp());
+            });
+            registry.prune();
+            info
+        };
+        if let Some(info) = info {
+            log::debug!("后台任务结束 id={} status={:?}", info.id, info.status);
+            emit_task(&self.app_handle, &info);
+        }
+    }
+}
+
+impl<R: Runtime> Drop for TaskHandle<R> {
+    fn drop(&mut self) {
+        self.complete(TaskStatus::Failed, Some("任务意外中止".to_string()));
+    }
+}
+
+fn emit_task<R: Runtime>(app_handle: &AppHandle<R>, info: &TaskInfo) {
+    if let Err(e) = app_handle.emit(TASK_PROGRESS_EVENT, info) {
+        log::warn!("无法发送 {} 事件: {}", TASK_PROGRESS_EVENT, e);
+    }
+}
+
+/// 列出运行中与最近结束的后台任务
+#[command]
+pub fn list_tasks(tasks: State<'_, TaskManager>) -> Result<Vec<TaskInfo>, String> {
+    Ok(tasks.list())
+}
+
+/// 查询单个后台任务
+#[command]
+pub fn get_task(tasks: State<'_, TaskManager>, task_id: u64) -> Result<Option<TaskInfo>, String> {
+    Ok(tasks.get(task_id))
+}
+
+/// 请求取消后台任务
+///
+/// # Returns
+/// 任务正在运行且可以取消时返回 true
+#[command]
+pub fn cancel_task(tasks: State<'_, TaskManager>, task_id: u64) -> Result<bool, String> {
+    let requested = tasks.cancel(task_id);
+    if requested {
+        log::info!("已请求取消后台任务 id={}", task_id);
+    }
+    Ok(requested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_enforces_exclusive_kinds_and_cancellation() {
+        let mut registry = TaskRegistry::default();
+        let (scan, scan_cancelled) = registry.register(TaskKind::Scan, 100).unwrap();
+        assert_eq!(scan.id, 1);
+        assert!(registry.register(TaskKind::Scan, 100).is_err());
+
+        let (first, _) = registry.register(TaskKind::FolderTransfer, 100).unwrap();
+        let (second, _) = registry.register(TaskKind::FolderTransfer, 100).unwrap();
+        assert_ne!(first.id, second.id);
+        assert!(!registry.cancel(|info| info.id == first.id));
+
+        assert!(registry.cancel(|info| info.kind == TaskKind::Scan));
+        assert!(scan_cancelled.load(Ordering::Relaxed));
+
+        registry.update(scan.id, |info| info.status = TaskStatus::Cancelled);
+        assert!(!registry.cancel(|info| info.id == scan.id));
+        assert!(registry.register(TaskKind::Scan, 200).is_ok());
+    }
+
+    #[test]
+    fn registry_prunes_oldest_finished_tasks() {
+        let mut registry = TaskRegistry::default();
+        let (running, _) = registry.register(TaskKind::StatusSync, 0).unwrap();
+        for _ in 0..MAX_FINISHED_TASKS + 5 {
+            let (info, _) = registry.register(TaskKind::GameSize, 0).unwrap();
+            registry.update(info.id, |info| info.status = TaskStatus::Completed);
+        }
+        registry.prune();
+
+        assert_eq!(registry.tasks.len(), MAX_FINISHED_TASKS + 1);
+        assert_eq!(registry.tasks[0].info.id, running.id);
+        assert_eq!(registry.tasks[1].info.id, running.id + 6);
+    }
+}
@@ -0,0 +1,29 @@
+//! 测试辅助函数
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 同一纳秒内多次调用时用于区分目录
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 生成测试专用的临时目录路径（不创建目录）
+///
+/// 路径形如 `reina-<prefix>-<name>-<pid>-<nanos>-<n>`，`name` 为空时省略，
+/// 并行运行的测试之间不会冲突。
+pub fn temp_dir(prefix: &str, name: &str) -> PathBuf {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("系统时间应晚于 Unix epoch")
+        .as_nanos();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let label = if name.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{prefix}-{name}")
+    };
+    std::env::temp_dir().join(format!(
+        "reina-{label}-{}-{unique}-{count}",
+        std::process::id()
+    ))
+}
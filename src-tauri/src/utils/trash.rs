@@ -0,0 +1,192 @@
+//! 系统回收站
+//!
+//! Windows 通过 `SHFileOperationW`（`FOF_ALLOWUNDO`）移入回收站；
+//! Linux 按 FreeDesktop 回收站规范移入用户回收站，跨磁盘时交给 `gio trash` 处理。
+
+use std::fs;
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+/// 删除文件或目录：默认移入系统回收站，`permanent` 为 true 时直接删除
+pub fn remove_path(path: &Path, permanent: bool) -> Result<(), String> {
+    if !permanent {
+        return move_to_trash(path);
+    }
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|e| format!("删除失败 {}: {}", path.display(), e))
+}
+
+/// 把文件或目录移入系统回收站
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    let path = std::path::absolute(path).map_err(|e| format!("解析路径失败: {}", e))?;
+    platform_trash(&path)?;
+    log::info!("已移入回收站: {}", path.display());
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_trash(path: &Path) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::Win32::UI::Shell::{
+        FO_DELETE, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, SHFILEOPSTRUCTW,
+        SHFileOperationW,
+    };
+    use windows::core::PCWSTR;
+
+    // pFrom 是以双 NUL 结尾的路径列表
+    let from: Vec<u16> = path.as_os_str().encode_wide().chain([0, 0]).collect();
+    let mut operation = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: PCWSTR(from.as_ptr()),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT).0 as u16,
+        ..Default::default()
+    };
+    let code = unsafe { SHFileOperationW(&mut operation) };
+    if code != 0 {
+        return Err(format!(
+            "移入回收站失败 {}: 错误码 {}",
+            path.display(),
+            code
+        ));
+    }
+    if operation.fAnyOperationsAborted.as_bool() {
+        return Err(format!("移入回收站被中止: {}", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_trash(path: &Path) -> Result<(), String> {
+    let trash_dir = home_trash_dir().ok_or_else(|| "无法确定回收站目录".to_string())?;
+    match trash_into(path, &trash_dir) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            // 与用户回收站不在同一磁盘时无法重命名，交给 gio 使用该磁盘的回收站
+            log::debug!("移入用户回收站失败，尝试 gio trash: {}", e);
+            let output = std::process::Command::new("gio")
+                .arg("trash")
+                .arg("--")
+                .arg(path)
+                .output()
+                .map_err(|_| e.clone())?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{}; gio trash 失败: {}",
+                    e,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/Trash`，未设置时为 `~/.local/share/Trash`
+#[cfg(target_os = "linux")]
+fn home_trash_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(data_home.join("Trash"))
+}
+
+/// 按 FreeDesktop 规范移入 `trash_dir`，返回回收站中的路径
+///
+/// 先以独占方式创建 `info/<名称>.trashinfo` 占用名称，重名时追加序号；移动失败时删除该记录。
+#[cfg(target_os = "linux")]
+fn trash_into(path: &Path, trash_dir: &Path) -> Result<PathBuf, String> {
+    use std::io::Write;
+
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::create_dir_all(&files_dir).map_err(|e| format!("创建回收站目录失败: {}", e))?;
+    fs::create_dir_all(&info_dir).map_err(|e| format!("创建回收站目录失败: {}", e))?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("无法移入回收站: {}", path.display()))?
+        .to_string_lossy()
+        .into_owned();
+    let encoded_path = url::Url::from_file_path(path)
+        .map_err(|_| format!("无法编码路径: {}", path.display()))?
+        .path()
+        .to_string();
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encoded_path,
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+
+    for index in 1..1000 {
+        let name = if index == 1 {
+            file_name.clone()
+        } else {
+            format!("{} {}", file_name, index)
+        };
+        let info_path = info_dir.join(format!("{}.trashinfo", name));
+        let target = files_dir.join(&name);
+        if target.exists() {
+            continue;
+        }
+        let mut info_file = match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("写入回收站记录失败: {}", e)),
+        };
+        let moved = info_file
+            .write_all(info.as_bytes())
+            .map_err(|e| format!("写入回收站记录失败: {}", e))
+            .and_then(|_| {
+                fs::rename(path, &target)
+                    .map_err(|e| format!("移入回收站失败 {}: {}", path.display(), e))
+            });
+        if let Err(e) = moved {
+            let _ = fs::remove_file(&info_path);
+            return Err(e);
+        }
+        return Ok(target);
+    }
+    Err(format!("回收站中同名项目过多: {}", file_name))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::utils::test_util::temp_dir;
+
+    #[test]
+    fn trash_into_keeps_info_and_renames_duplicates() {
+        let root = temp_dir("trash", "");
+        let trash_dir = root.join("Trash");
+        fs::create_dir_all(root.join("saves")).expect("应能创建测试目录");
+        fs::write(root.join("saves").join("save.dat"), b"save").unwrap();
+
+        let trashed = trash_into(&root.join("saves"), &trash_dir).unwrap();
+        assert_eq!(trashed, trash_dir.join("files").join("saves"));
+        assert!(!root.join("saves").exists());
+        assert_eq!(fs::read(trashed.join("save.dat")).unwrap(), b"save");
+        let info = fs::read_to_string(trash_dir.join("info").join("saves.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\nPath=/"));
+        assert!(info.contains("/saves\nDeletionDate="));
+
+        fs::create_dir_all(root.join("saves")).unwrap();
+        let trashed = trash_into(&root.join("saves"), &trash_dir).unwrap();
+        assert_eq!(trashed, trash_dir.join("files").join("saves 2"));
+        assert!(trash_dir.join("info").join("saves 2.trashinfo").is_file());
+
+        fs::remove_dir_all(root).expect("应能清理测试目录");
+    }
+}
@@ -0,0 +1,184 @@
+//! 会话事件 Webhook
+//!
+//! 会话开始/结束、游玩状态变更时向用户配置的地址 POST 一个 JSON，便于接入 HomeAssistant、
+//! IFTTT 或群机器人等自动化。请求在后台发送，失败只记录日志，不影响会话记录。
+//!
+//! 请求体示例：
+//! ```json
+//! {
+//!   "event": "session_ended",
+//!   "timestamp": 1735660800,
+//!   "game": { "id": 1, "name": "千恋＊万花", "cover": "https://..." },
+//!   "data": { "startTime": 1735653600, "endTime": 1735660800, "durationMinutes": 120, ... }
+//! }
+//! ```
+
+use crate::database::dto::FullGameData;
+use crate::database::repository::games_repository::GamesRepository;
+use crate::database::repository::settings_repository::DbSettingsExt;
+use crate::entity::user::WebhookSettings;
+use crate::utils::http::get_client;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+use url::Url;
+
+/// 单次请求超时
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Webhook 事件，序列化为 `event` 与 `data` 两个字段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "event",
+    content = "data",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum WebhookEvent {
+    SessionStarted {
+        process_id: u32,
+        start_time: u64,
+    },
+    SessionEnded {
+        start_time: u64,
+        end_time: u64,
+        /// 计入统计的时长（分钟），低于记录阈值时为 0
+        duration_minutes: u64,
+        recorded: bool,
+        exit_code: Option<i32>,
+    },
+    /// 游玩状态（games.clear）变更
+    PlayStatusChanged {
+        previous: Option<i32>,
+        current: Option<i32>,
+    },
+}
+
+/// 校验 Webhook 设置：启用时必须填写 http(s) 地址
+pub fn validate_webhook_settings(settings: &WebhookSettings) -> Result<(), String> {
+    match settings.url.as_deref() {
+        Some(url) => {
+            let parsed = Url::parse(url).map_err(|e| format!("Webhook 地址无效: {}", e))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(format!(
+                    "Webhook 地址仅支持 http:// 或 https://，当前为 {url}"
+                ));
+            }
+            Ok(())
+        }
+        None if settings.enabled => Err("启用 Webhook 前请填写地址".to_string()),
+        None => Ok(()),
+    }
+}
+
+fn build_payload(
+    event: &WebhookEvent,
+    game_id: i32,
+    game: Option<&FullGameData>,
+    hide_nsfw: bool,
+    timestamp: i64,
+) -> Value {
+    let hidden = hide_nsfw && game.is_some_and(FullGameData::is_nsfw);
+    let (name, cover) = match game {
+        Some(game) if !hidden => (game.display_name(), game.remote_cover_url()),
+        _ => (None, None),
+    };
+
+    let mut payload = json!(event);
+    payload["timestamp"] = json!(timestamp);
+    payload["game"] = json!({ "id": game_id, "name": name, "cover": cover });
+    payload
+}
+
+async fn deliver(
+    db: &DatabaseConnection,
+    game_id: i32,
+    event: &WebhookEvent,
+) -> Result<(), String> {
+    let settings = db.get_settings().await?.webhook_settings();
+    let Some(url) = settings.url.as_deref().filter(|_| settings.enabled) else {
+        return Ok(());
+    };
+
+    let game = GamesRepository::find_by_id(db, game_id)
+        .await
+        .map_err(|e| format!("查询游戏失败: {}", e))?;
+    let payload = build_payload(
+        event,
+        game_id,
+        game.as_ref(),
+        settings.hide_nsfw,
+        chrono::Utc::now().timestamp(),
+    );
+    let body = serde_json::to_vec(&payload).map_err(|e| format!("序列化请求体失败: {}", e))?;
+
+    let response = get_client()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("服务端返回 {}", response.status()));
+    }
+    Ok(())
+}
+
+/// 在后台发送 Webhook，未启用时不做任何事
+pub fn notify_webhook(db: &DatabaseConnection, game_id: i32, event: WebhookEvent) {
+    let db = db.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = deliver(&db, game_id, &event).await {
+            log::warn!("发送 Webhook 失败 game_id={}: {}", game_id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_payload() {
+        let game: FullGameData = serde_json::from_value(json!({
+            "id": 3,
+            "id_type": "custom",
+            "custom_data": { "name": "サクラノ詩" },
+            "sources": [],
+        }))
+        .unwrap();
+        let event = WebhookEvent::PlayStatusChanged {
+            previous: Some(3),
+            current: Some(2),
+        };
+
+        let payload = build_payload(&event, 3, Some(&game), false, 100);
+        assert_eq!(payload["event"], "play_status_changed");
+        assert_eq!(payload["data"], json!({ "previous": 3, "current": 2 }));
+        assert_eq!(payload["timestamp"], 100);
+        assert_eq!(payload["game"]["name"], "サクラノ詩");
+
+        let payload = build_payload(&event, 3, None, true, 100);
+        assert_eq!(
+            payload["game"],
+            json!({ "id": 3, "name": null, "cover": null })
+        );
+    }
+
+    #[test]
+    fn validates_settings() {
+        let mut settings = WebhookSettings {
+            enabled: true,
+            url: None,
+            hide_nsfw: false,
+        };
+        assert!(validate_webhook_settings(&settings).is_err());
+        settings.url = Some("ftp://example.com/hook".to_string());
+        assert!(validate_webhook_settings(&settings).is_err());
+        settings.url = Some("https://example.com/hook".to_string());
+        assert!(validate_webhook_settings(&settings).is_ok());
+    }
+}
@@ -24,6 +24,7 @@ import { useEffect, useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { useShallow } from "zustand/react/shallow";
 import { useProxyImageUrlResolver } from "@/hooks/common/useProxyImageUrlResolver";
+import { useDeepLinkHandler } from "@/hooks/features/games/useDeepLinkHandler";
 import { snackbar } from "@/providers/snackBar";
 import { destroyCurrentWindow, getRunningGameCount } from "@/services/appExit";
 import { fileService } from "@/services/invoke";
@@ -338,6 +339,9 @@ const WindowsHandler: React.FC = () => {
 	const [open, setOpen] = useState(false);
 	const [runningExitOpen, setRunningExitOpen] = useState(false);
 
+	// 处理 reina:// 链接与 --launch 参数
+	useDeepLinkHandler();
+
 	useEffect(() => {
 		const w = getCurrentWindow();
 		let unlisten = () => {};
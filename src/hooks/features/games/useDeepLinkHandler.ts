@@ -0,0 +1,79 @@
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useCallback, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { useNavigate } from "react-router-dom";
+import { getDisplayGameData } from "@/metadata/data/dataTransform";
+import { snackbar } from "@/providers/snackBar";
+import { gameService } from "@/services/invoke";
+import { getUserErrorMessage } from "@/utils/errors";
+import { useGameLaunchFlow } from "./useGameLaunchFlow";
+
+/**
+ * 处理 reina:// 链接与 --launch 命令行参数
+ *
+ * 后端把动作暂存后发送 deep-link 事件；挂载时先取一次，处理首次启动时传入的动作，
+ * 之后每次收到事件再取出处理。启动游戏沿用界面上的启动流程，保持计时模式等设置一致。
+ */
+export function useDeepLinkHandler() {
+	const { t } = useTranslation();
+	const navigate = useNavigate();
+	const { launchGame } = useGameLaunchFlow();
+
+	const handlePendingDeepLink = useCallback(async () => {
+		try {
+			const deepLink = await gameService.takePendingDeepLink();
+			if (!deepLink) return;
+
+			if (deepLink.action === "open") {
+				navigate(`/libraries/${deepLink.gameId}`);
+				return;
+			}
+
+			const game = await gameService.getGameById(deepLink.gameId);
+			if (!game) {
+				snackbar.error(
+					t(
+						"components.LaunchModal.gameNotFound",
+						"找不到游戏 (ID: {{id}})",
+						{ id: deepLink.gameId },
+					),
+				);
+				return;
+			}
+			const gameData = getDisplayGameData(game);
+			if (!gameData.localpath || !gameData.executable) {
+				// 以 --launch 启动时主窗口已最小化，需要选择可执行文件时恢复窗口
+				const window = getCurrentWindow();
+				await window.unminimize();
+				await window.setFocus();
+			}
+			await launchGame(gameData);
+		} catch (error) {
+			snackbar.error(
+				`${t("components.LaunchModal.launchFailed", "游戏启动失败:")}: ${getUserErrorMessage(error, t)}`,
+			);
+		}
+	}, [launchGame, navigate, t]);
+
+	useEffect(() => {
+		let disposed = false;
+		let unlisten = () => {};
+
+		void handlePendingDeepLink();
+		listen("deep-link", () => {
+			void handlePendingDeepLink();
+		}).then((fn) => {
+			if (disposed) {
+				fn();
+			} else {
+				unlisten = fn;
+			}
+		});
+
+		return () => {
+			disposed = true;
+			unlisten();
+		};
+	}, [handlePendingDeepLink]);
+}
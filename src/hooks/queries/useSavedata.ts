@@ -33,7 +33,6 @@ export const saveDataKeys = {
 
 interface CreateBackupParams {
 	gameId: number;
-	savePath: string;
 }
 
 interface DeleteBackupParams {
@@ -44,7 +43,6 @@ interface DeleteBackupParams {
 interface RestoreBackupParams {
 	gameId: number;
 	backup: SavedataRecord;
-	savePath: string;
 }
 
 // ============================================================================
@@ -84,9 +82,9 @@ export function useSaveDataBackupCount(gameId: number) {
  */
 export async function createBackupAndSync(
 	queryClient: QueryClient,
-	{ gameId, savePath }: CreateBackupParams,
+	{ gameId }: CreateBackupParams,
 ) {
-	const backupInfo = await createGameSavedataBackup(gameId, savePath);
+	const backupInfo = await createGameSavedataBackup(gameId);
 
 	await queryClient.invalidateQueries({
 		queryKey: saveDataKeys.backups(gameId),
@@ -105,8 +103,8 @@ function useCreateBackup() {
 	const queryClient = useQueryClient();
 
 	return useMutation({
-		mutationFn: async ({ gameId, savePath }: CreateBackupParams) => {
-			return createBackupAndSync(queryClient, { gameId, savePath });
+		mutationFn: async ({ gameId }: CreateBackupParams) => {
+			return createBackupAndSync(queryClient, { gameId });
 		},
 	});
 }
@@ -139,13 +137,13 @@ function useDeleteBackup() {
  */
 function useRestoreBackup() {
 	return useMutation({
-		mutationFn: async ({ gameId, backup, savePath }: RestoreBackupParams) => {
+		mutationFn: async ({ gameId, backup }: RestoreBackupParams) => {
 			// 获取备份文件完整路径
 			const savedataBackupPath = await getSavedataBackupPath(gameId);
 			const backupFilePath = join(savedataBackupPath, backup.file);
 
 			// 恢复备份
-			await savedataService.restoreBackup(backupFilePath, savePath);
+			await savedataService.restoreBackup(gameId, backupFilePath);
 		},
 	});
 }
@@ -1,6 +1,6 @@
 /**
  * @file HTTP 请求工具
- * @description 基于 Axios 和 Tauri HTTP 的请求工具，支持浏览器和 Tauri 环境的 HTTP 请求。
+ * @description 元数据请求工具，请求经后端共享 HTTP 客户端发送，代理、超时与重试沿用网络设置。
  * @module src/metadata/api/http
  * @author ReinaManager
  * @copyright AGPL-3.0
@@ -11,13 +11,11 @@
  * - 默认导出 http：全局 HTTP 实例
  *
  * 依赖：
- * - axios
- * - @tauri-apps/plugin-http
+ * - @tauri-apps/api/core（后端 send_http_request 命令）
  */
 
 import { version } from "@pkg";
-import { fetch as tauriFetch } from "@tauri-apps/plugin-http";
-import { useStore } from "@/store/appStore";
+import { invoke } from "@tauri-apps/api/core";
 import {
 	ApiRateLimitError,
 	AppError,
@@ -33,8 +31,6 @@ import {
 } from "./rateLimit";
 
 export const USER_AGENT = `huoshen80/ReinaManager/${version} (https://github.com/huoshen80/ReinaManager)`;
-const LOCAL_PROXY_BYPASS =
-	"localhost,127.0.0.0/8,::1,0.0.0.0,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16,169.254.0.0/16,fc00::/7,fe80::/10,.local";
 
 export interface TauriHttpOptions {
 	headers?: Record<string, string>;
@@ -45,6 +41,61 @@ export interface TauriHttpOptions {
 	responseType?: "json" | "text";
 }
 
+/**
+ * 后端 send_http_request 返回的响应
+ */
+interface BackendHttpResponse {
+	status: number;
+	status_text: string;
+	headers: [string, string][];
+	body: string;
+}
+
+/** 不允许携带响应体的状态码 */
+const NULL_BODY_STATUSES = new Set([101, 204, 205, 304]);
+
+/**
+ * 经后端共享客户端发送请求，并转换为标准 Response
+ *
+ * 后端请求无法中途取消，signal 触发时直接放弃等待结果。
+ */
+async function fetchViaBackend(
+	url: string,
+	init: {
+		method: string;
+		headers: Record<string, string>;
+		body?: string;
+		signal?: AbortSignal;
+	},
+): Promise<Response> {
+	init.signal?.throwIfAborted();
+	const request = invoke<BackendHttpResponse>("send_http_request", {
+		method: init.method,
+		url,
+		headers: init.headers,
+		body: init.body,
+	});
+	const signal = init.signal;
+	const result = signal
+		? await new Promise<BackendHttpResponse>((resolve, reject) => {
+				const onAbort = () => reject(signal.reason);
+				signal.addEventListener("abort", onAbort, { once: true });
+				request.then(resolve, reject).finally(() => {
+					signal.removeEventListener("abort", onAbort);
+				});
+			})
+		: await request;
+
+	return new Response(
+		NULL_BODY_STATUSES.has(result.status) ? null : result.body,
+		{
+			status: result.status,
+			statusText: result.status_text,
+			headers: result.headers,
+		},
+	);
+}
+
 interface TauriHttpResponse<T = unknown> {
 	data: T;
 	status: number;
@@ -107,25 +158,14 @@ async function requestTauriHttp<T>(
 		options?.rateLimit?.source ?? inferRateLimitSource(url);
 
 	const fetchResponse = () => {
-		const { proxyConfig } = useStore.getState();
-		const proxyOption = proxyConfig.url
-			? {
-					all: {
-						url: proxyConfig.url,
-						noProxy: LOCAL_PROXY_BYPASS,
-					},
-				}
-			: undefined;
-
 		if (import.meta.env.DEV) {
 			console.log(`[TauriHTTP] ${method} ${fullUrl}`, {
 				headers: options?.headers,
 				body: data,
-				proxy: proxyOption,
 			});
 		}
 
-		return tauriFetch(fullUrl, {
+		return fetchViaBackend(fullUrl, {
 			method,
 			headers: {
 				...(method === "GET" ? {} : { "Content-Type": "application/json" }),
@@ -136,7 +176,6 @@ async function requestTauriHttp<T>(
 					? undefined
 					: JSON.stringify(data),
 			signal: options?.signal,
-			proxy: proxyOption,
 		});
 	};
 
@@ -247,7 +286,7 @@ function getApiRateLimitErrorMessage(source: ApiRateLimitSource): string {
 
 /**
  * Tauri HTTP 客户端
- * 使用 Tauri 的原生 HTTP 请求，可以绕过浏览器限制，支持自定义 User-Agent
+ * 经后端共享客户端发送请求，可以绕过浏览器限制，支持自定义 User-Agent
  */
 export const tauriHttp = {
 	/**
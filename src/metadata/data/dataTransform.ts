@@ -53,6 +53,7 @@ export function getDisplayGameData(fullData: FullGameData): GameData {
 		localpath: nullToUndefined(fullData.localpath),
 		executable: nullToUndefined(fullData.executable),
 		savepath: nullToUndefined(fullData.savepath),
+		save_registry_keys: nullToUndefined(fullData.save_registry_keys),
 		autosave: nullToUndefined(fullData.autosave),
 		maxbackups: nullToUndefined(fullData.maxbackups),
 		clear: nullToUndefined(fullData.clear),
@@ -1,3 +1,4 @@
+import AddIcon from "@mui/icons-material/Add";
 import BackupIcon from "@mui/icons-material/Backup";
 import DeleteIcon from "@mui/icons-material/Delete";
 import FolderOpenIcon from "@mui/icons-material/FolderOpen";
@@ -74,9 +75,10 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 	const updateGameMutation = useUpdateGame();
 	const { t } = useTranslation();
 	const originalAutoSaveEnabled = selectedGame.autosave === 1;
-	const originalSaveDataPath = selectedGame.savepath || "";
+	// 以换行拼接作为依赖，避免数组引用变化导致重复同步
+	const originalSaveDataPathsKey = (selectedGame.savepath ?? []).join("\n");
 	const originalMaxBackups = selectedGame.maxbackups ?? 20;
-	const hasSavedGameSavePath = Boolean(originalSaveDataPath);
+	const hasSavedGameSavePath = originalSaveDataPathsKey !== "";
 
 	// React Query hooks
 	const {
@@ -87,7 +89,7 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 	} = useSaveDataResources(gameId);
 
 	// 备份设置 - 本地状态（统一保存）
-	const [saveDataPath, setSaveDataPath] = useState("");
+	const [saveDataPaths, setSaveDataPaths] = useState<string[]>([""]);
 	const [autoSaveEnabled, setAutoSaveEnabled] = useState(false);
 	const [maxBackups, setMaxBackups] = useState(20);
 
@@ -104,23 +106,31 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 	// 从 selectedGame 同步设置状态
 	useEffect(() => {
 		setAutoSaveEnabled(originalAutoSaveEnabled);
-		setSaveDataPath(originalSaveDataPath);
+		setSaveDataPaths(
+			originalSaveDataPathsKey ? originalSaveDataPathsKey.split("\n") : [""],
+		);
 		setMaxBackups(originalMaxBackups);
-	}, [originalAutoSaveEnabled, originalMaxBackups, originalSaveDataPath]);
+	}, [originalAutoSaveEnabled, originalMaxBackups, originalSaveDataPathsKey]);
+
+	// 去除空行后的存档路径
+	const cleanSaveDataPaths = useMemo(
+		() => saveDataPaths.map((path) => path.trim()).filter(Boolean),
+		[saveDataPaths],
+	);
 
 	// 检测是否有未保存的更改
 	const hasUnsavedChanges = useMemo(
 		() =>
 			autoSaveEnabled !== originalAutoSaveEnabled ||
-			saveDataPath !== originalSaveDataPath ||
+			cleanSaveDataPaths.join("\n") !== originalSaveDataPathsKey ||
 			maxBackups !== originalMaxBackups,
 		[
 			autoSaveEnabled,
+			cleanSaveDataPaths,
 			maxBackups,
 			originalAutoSaveEnabled,
 			originalMaxBackups,
-			originalSaveDataPath,
-			saveDataPath,
+			originalSaveDataPathsKey,
 		],
 	);
 
@@ -136,14 +146,14 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 		}
 
 		try {
-			const clearsavePath = saveDataPath.trim();
-			const autosaveValue = clearsavePath ? (autoSaveEnabled ? 1 : 0) : 0;
+			const hasPaths = cleanSaveDataPaths.length > 0;
+			const autosaveValue = hasPaths && autoSaveEnabled ? 1 : 0;
 			// 如果路径被清空，强制关闭自动备份，避免界面状态与保存结果不一致
 			setAutoSaveEnabled(autosaveValue === 1);
 			await updateGameMutation.mutateAsync({
 				gameId,
 				updates: {
-					savepath: clearsavePath,
+					savepath: hasPaths ? cleanSaveDataPaths : null,
 					autosave: autosaveValue,
 					maxbackups: maxBackups,
 				},
@@ -158,11 +168,26 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 		}
 	};
 
-	// 选择存档文件夹
-	const handleSelectSaveDataPath = async () => {
+	// 修改指定行的存档路径
+	const handleChangeSaveDataPath = (index: number, value: string) => {
+		setSaveDataPaths((paths) =>
+			paths.map((path, i) => (i === index ? value : path)),
+		);
+	};
+
+	// 删除指定行，至少保留一行输入框
+	const handleRemoveSaveDataPath = (index: number) => {
+		setSaveDataPaths((paths) => {
+			const next = paths.filter((_, i) => i !== index);
+			return next.length > 0 ? next : [""];
+		});
+	};
+
+	// 为指定行选择存档文件夹
+	const handleSelectSaveDataPath = async (index: number) => {
 		const selectedPath = await handleFolder(selectedGame.localpath ?? "");
 		if (selectedPath) {
-			setSaveDataPath(selectedPath);
+			handleChangeSaveDataPath(index, selectedPath);
 		}
 	};
 
@@ -176,10 +201,7 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 		}
 
 		try {
-			await createBackupMutation.mutateAsync({
-				gameId,
-				savePath: originalSaveDataPath,
-			});
+			await createBackupMutation.mutateAsync({ gameId });
 			snackbar.success(t("pages.Detail.Backup.backupSuccess", "备份创建成功"));
 		} catch (error) {
 			snackbar.error(
@@ -209,7 +231,7 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 		}
 
 		try {
-			await openGameSaveDataFolder(originalSaveDataPath);
+			await openGameSaveDataFolder(gameId);
 		} catch (error) {
 			snackbar.error(
 				`${t("pages.Detail.Backup.openSaveDataFolderFailed", "打开存档文件夹失败")}: ${getUserErrorMessage(error, t)}`,
@@ -269,7 +291,6 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 			await restoreBackupMutation.mutateAsync({
 				gameId,
 				backup: backupToRestore,
-				savePath: originalSaveDataPath,
 			});
 			snackbar.success(t("pages.Detail.Backup.restoreSuccess", "存档恢复成功"));
 			setRestoreDialogOpen(false);
@@ -299,7 +320,7 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 										<Switch
 											checked={autoSaveEnabled}
 											onChange={(e) => setAutoSaveEnabled(e.target.checked)}
-											disabled={!saveDataPath}
+											disabled={cleanSaveDataPaths.length === 0}
 										/>
 									}
 									label={t("pages.Detail.Backup.autoSave", "自动备份")}
@@ -328,34 +349,73 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 								{t("pages.Detail.Backup.savePathSettings", "存档路径设置")}
 							</Typography>
 
-							<TextField
-								label={t("pages.Detail.Backup.saveDataPath", "存档文件夹路径")}
-								variant="outlined"
-								fullWidth
-								value={saveDataPath}
-								onChange={(e) => setSaveDataPath(e.target.value)}
+							{saveDataPaths.map((path, index) => (
+								<Box
+									// biome-ignore lint/suspicious/noArrayIndexKey: 路径可重复编辑，按行序号区分
+									key={index}
+									sx={{ display: "flex", alignItems: "center", gap: 1 }}
+								>
+									<TextField
+										label={t(
+											"pages.Detail.Backup.saveDataPath",
+											"存档文件夹路径",
+										)}
+										variant="outlined"
+										fullWidth
+										value={path}
+										onChange={(e) =>
+											handleChangeSaveDataPath(index, e.target.value)
+										}
+										disabled={isSaving}
+										placeholder={t(
+											"pages.Detail.Backup.selectSaveDataFolder",
+											"选择存档文件夹",
+										)}
+										slotProps={{
+											input: {
+												endAdornment: (
+													<InputAdornment position="end">
+														<IconButton
+															onClick={() => handleSelectSaveDataPath(index)}
+															disabled={isSaving}
+															edge="end"
+															size="small"
+														>
+															<FolderOpenIcon />
+														</IconButton>
+													</InputAdornment>
+												),
+											},
+										}}
+									/>
+									<Tooltip
+										title={t(
+											"pages.Detail.Backup.removeSavePath",
+											"移除存档路径",
+										)}
+									>
+										<span>
+											<IconButton
+												onClick={() => handleRemoveSaveDataPath(index)}
+												disabled={isSaving}
+												size="small"
+											>
+												<DeleteIcon />
+											</IconButton>
+										</span>
+									</Tooltip>
+								</Box>
+							))}
+
+							<Button
+								variant="text"
+								onClick={() => setSaveDataPaths((paths) => [...paths, ""])}
 								disabled={isSaving}
-								placeholder={t(
-									"pages.Detail.Backup.selectSaveDataFolder",
-									"选择存档文件夹",
-								)}
-								slotProps={{
-									input: {
-										endAdornment: (
-											<InputAdornment position="end">
-												<IconButton
-													onClick={handleSelectSaveDataPath}
-													disabled={isSaving}
-													edge="end"
-													size="small"
-												>
-													<FolderOpenIcon />
-												</IconButton>
-											</InputAdornment>
-										),
-									},
-								}}
-							/>
+								startIcon={<AddIcon />}
+								sx={{ alignSelf: "flex-start" }}
+							>
+								{t("pages.Detail.Backup.addSavePath", "添加存档路径")}
+							</Button>
 
 							<Divider />
 
@@ -511,9 +571,25 @@ function SaveDataContent({ selectedGame, gameId }: SaveDataContentProps) {
 										}
 									>
 										<ListItemText
-											primary={backup.file}
+											primary={
+												backup.label
+													? `${backup.label} (${backup.file})`
+													: backup.file
+											}
 											secondary={
 												<>
+													{backup.comment && (
+														<>
+															<Typography
+																variant="body2"
+																color="textSecondary"
+																component="span"
+															>
+																{backup.comment}
+															</Typography>
+															<br />
+														</>
+													)}
 													<Typography
 														variant="body2"
 														color="textSecondary"
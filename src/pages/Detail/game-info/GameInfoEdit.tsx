@@ -180,7 +180,7 @@ export const GameInfoEdit: React.FC<GameInfoEditProps> = ({
 		setClipboardTempImagePath(null);
 
 		try {
-			await fileService.deleteFile(tempPath);
+			await fileService.deleteFile(tempPath, true);
 		} catch (error) {
 			console.warn("删除剪贴板临时封面失败:", error);
 		}
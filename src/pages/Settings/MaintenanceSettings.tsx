@@ -219,6 +219,12 @@ export const DatabaseBackupSettings = () => {
 							},
 						),
 					);
+					// 数据库连接已关闭，必须重启才能重新连接
+					if (result.restart_required) {
+						setTimeout(async () => {
+							await restartApp();
+						}, 3000);
+					}
 				}
 			}
 		} catch (error) {
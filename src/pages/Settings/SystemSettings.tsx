@@ -30,6 +30,9 @@ import { useStore } from "@/store/appStore";
 import { getUserErrorMessage } from "@/utils/errors";
 import { SettingsGroup, SettingsItem } from "./SettingsLayout";
 
+/** 后端 HTTP 客户端支持的代理协议 */
+const PROXY_PROTOCOLS = ["http:", "https:", "socks5:", "socks5h:"];
+
 export const AutoStartSettings = () => {
 	const { t } = useTranslation();
 	const [autoStart, setAutoStart] = useState(false);
@@ -446,14 +449,14 @@ export const ProxySettings = () => {
 	}, [proxyConfig.url]);
 
 	const validateProxyUrl = (value: string) => {
-		if (!/^https?:\/\//i.test(value)) {
+		if (!/^(https?|socks5h?):\/\//i.test(value)) {
 			return false;
 		}
 
 		try {
 			const parsed = new URL(value);
 			return (
-				(parsed.protocol === "http:" || parsed.protocol === "https:") &&
+				PROXY_PROTOCOLS.includes(parsed.protocol) &&
 				Boolean(parsed.hostname)
 			);
 		} catch {
@@ -477,7 +480,7 @@ export const ProxySettings = () => {
 			setProxyUrlError(
 				t(
 					"pages.Settings.proxy.invalidUrl",
-					"请输入以 http:// 或 https:// 开头的有效代理地址",
+					"请输入以 http://、https://、socks5:// 或 socks5h:// 开头的有效代理地址",
 				),
 			);
 			return false;
@@ -9,10 +9,9 @@ import { toError } from "@/utils/errors";
 
 export async function createGameSavedataBackup(
 	gameId: number,
-	saveDataPath: string,
 ): Promise<{ folder_name: string; backup_time: number; file_size: number }> {
 	try {
-		const backupInfo = await savedataService.createBackup(gameId, saveDataPath);
+		const backupInfo = await savedataService.createBackup(gameId);
 
 		await savedataService.saveSavedataRecord(
 			gameId,
@@ -33,13 +32,14 @@ export async function openGameBackupFolder(gameId: number): Promise<void> {
 	await fileService.openDirectory(backupPath);
 }
 
-export async function openGameSaveDataFolder(
-	saveDataPath: string,
-): Promise<void> {
-	if (!saveDataPath) {
+export async function openGameSaveDataFolder(gameId: number): Promise<void> {
+	const saveDataPaths = await savedataService.resolveSavePaths(gameId);
+	if (saveDataPaths.length === 0) {
 		throw new Error("存档路径不能为空");
 	}
-	await fileService.openDirectory(saveDataPath);
+	for (const saveDataPath of saveDataPaths) {
+		await fileService.openDirectory(saveDataPath);
+	}
 }
 
 export async function openDatabaseBackupFolder(): Promise<void> {
@@ -222,14 +222,11 @@ export async function initGameTimeTracking(
 						console.error("游戏数据未找到，无法进行自动备份");
 						return;
 					}
-					if (fullgame.autosave === 1 && fullgame.savepath) {
+					if (fullgame.autosave === 1 && fullgame.savepath?.length) {
 						console.log(
-							`开始自动备份游戏 ${gameId}，存档路径: ${fullgame.savepath}`,
+							`开始自动备份游戏 ${gameId}，存档路径: ${fullgame.savepath.join(", ")}`,
 						);
-						await createBackupAndSync(queryClient, {
-							gameId,
-							savePath: fullgame.savepath,
-						});
+						await createBackupAndSync(queryClient, { gameId });
 						console.log(`游戏 ${gameId} 自动备份完成`);
 					}
 				} catch (backupError) {
@@ -21,6 +21,8 @@ export interface ImportResult {
 	success: boolean;
 	message: string;
 	backup_path: string | null;
+	/** 数据库连接已关闭，无论成功与否都需要重启应用 */
+	restart_required: boolean;
 }
 
 export interface MoveBackupFolderResult {
@@ -92,10 +94,11 @@ class FileService extends BaseService {
 	}
 
 	/**
-	 * 删除文件
+	 * 删除文件，默认移入系统回收站
+	 * @param permanent 为 true 时直接删除
 	 */
-	async deleteFile(filePath: string): Promise<void> {
-		return this.invoke<void>("delete_file", { filePath });
+	async deleteFile(filePath: string, permanent?: boolean): Promise<void> {
+		return this.invoke<void>("delete_file", { filePath, permanent });
 	}
 
 	/**
@@ -144,6 +147,13 @@ class FileService extends BaseService {
 		return this.invoke<ImportResult>("import_database", { sourcePath });
 	}
 
+	/**
+	 * 从备份恢复数据库，失败时后端已回滚，但仍需按 restart_required 重启应用
+	 */
+	async restoreDatabase(backupPath: string): Promise<ImportResult> {
+		return this.invoke<ImportResult>("restore_database", { backupPath });
+	}
+
 	/**
 	 * 移动备份文件夹
 	 */
@@ -19,6 +19,14 @@ import type {
 import { BaseService } from "./base";
 import type { GameType, SortOption, SortOrder } from "./types";
 
+/**
+ * 待处理的协议链接或命令行动作
+ */
+export interface DeepLinkAction {
+	action: "open" | "launch";
+	gameId: number;
+}
+
 type WireBatchOperationResult = Omit<BatchOperationResult, "games"> & {
 	games: FullGameData[];
 };
@@ -176,6 +184,13 @@ class GameService extends BaseService {
 			updates,
 		});
 	}
+
+	/**
+	 * 取出待处理的 reina:// 链接或 --launch 动作，没有时返回 null
+	 */
+	async takePendingDeepLink(): Promise<DeepLinkAction | null> {
+		return this.invoke<DeepLinkAction | null>("take_pending_deep_link");
+	}
 }
 
 // 导出单例
@@ -14,19 +14,61 @@ export interface BackupInfo {
 	backup_path: string;
 }
 
+/** 存档路径候选 */
+export interface SavePathCandidate {
+	/** 建议写入存档路径的模板；注册表候选为完整键路径 */
+	path: string;
+	/** 在当前电脑上展开后的路径 */
+	resolved: string;
+	source: "game_dir" | "engine" | "app_data" | "documents" | "registry";
+	file_count: number;
+	modified_at: number | null;
+}
+
+/** 备份差异中只存在于一方的文件 */
+export interface DiffFileEntry {
+	path: string;
+	size: number;
+}
+
+/** 备份 B 相对备份 A 的差异 */
+export interface SavedataDiff {
+	/** 只在 B 中存在 */
+	added: DiffFileEntry[];
+	/** 只在 A 中存在 */
+	removed: DiffFileEntry[];
+	modified: { path: string; size_a: number; size_b: number }[];
+	/** 内容相同的文件数 */
+	unchanged: number;
+}
+
 class SavedataService extends BaseService {
 	/**
-	 * 创建存档备份
+	 * 创建存档备份，打包游戏的全部存档路径
 	 * @param gameId 游戏ID
-	 * @param sourcePath 存档文件夹路径
+	 * @param password 备份密码，开启默认加密时不传则使用已保存的默认备份密码
 	 */
-	async createBackup(gameId: number, sourcePath: string): Promise<BackupInfo> {
+	async createBackup(gameId: number, password?: string): Promise<BackupInfo> {
 		return this.invoke<BackupInfo>("create_savedata_backup", {
 			gameId,
-			sourcePath,
+			password,
 		});
 	}
 
+	/**
+	 * 保存默认备份密码到系统凭据管理器，传空值删除
+	 */
+	async setBackupPassword(password: string | null): Promise<void> {
+		return this.invoke<void>("set_savedata_backup_password", { password });
+	}
+
+	/**
+	 * 是否已保存默认备份密码
+	 */
+	async hasBackupPassword(): Promise<boolean> {
+		return this.invoke<boolean>("has_savedata_backup_password");
+	}
+
 	/**
 	 * 删除备份文件和数据库记录（二合一）
 	 * @param backupId 备份记录ID
@@ -36,34 +78,93 @@ class SavedataService extends BaseService {
 	}
 
 	/**
-	 * 恢复存档备份
+	 * 恢复存档备份到游戏的存档路径
+	 * @param gameId 游戏ID
 	 * @param backupFilePath 备份文件完整路径
-	 * @param targetPath 目标恢复路径
+	 * @param password 备份密码，不传时使用已保存的默认备份密码
 	 */
 	async restoreBackup(
+		gameId: number,
 		backupFilePath: string,
-		targetPath: string,
+		password?: string,
 	): Promise<void> {
 		return this.invoke<void>("restore_savedata_backup", {
+			gameId,
 			backupFilePath,
-			targetPath,
+			password,
 		});
 	}
 
+	/**
+	 * 对比两个备份的文件列表
+	 * @param backupA 作为基准的备份记录ID
+	 * @param backupB 与之对比的备份记录ID
+	 * @param password 备份密码，对比加密备份时必须提供
+	 */
+	async diffBackups(
+		backupA: number,
+		backupB: number,
+		password?: string,
+	): Promise<SavedataDiff> {
+		return this.invoke<SavedataDiff>("diff_savedata_backups", {
+			backupA,
+			backupB,
+			password,
+		});
+	}
+
+	/**
+	 * 获取展开环境变量与占位符后的全部存档路径
+	 * @param gameId 游戏ID
+	 */
+	async resolveSavePaths(gameId: number): Promise<string[]> {
+		return this.invoke<string[]>("resolve_game_savepaths", { gameId });
+	}
+
+	/**
+	 * 探测存档路径候选，供用户确认
+	 * @param gameId 游戏ID
+	 */
+	async detectSavePath(gameId: number): Promise<SavePathCandidate[]> {
+		return this.invoke<SavePathCandidate[]>("detect_save_path", { gameId });
+	}
+
 	/**
 	 * 保存存档备份记录
+	 * @param label 可选的备份标签
+	 * @param comment 可选的备份备注
 	 */
 	async saveSavedataRecord(
 		gameId: number,
 		fileName: string,
 		backupTime: number,
 		fileSize: number,
+		label?: string,
+		comment?: string,
 	): Promise<number> {
 		return this.invoke<number>("save_savedata_record", {
 			gameId,
 			fileName,
 			backupTime,
 			fileSize,
+			label,
+			comment,
+		});
+	}
+
+	/**
+	 * 修改备份的标签与备注，传空值清除
+	 * @param backupId 备份记录ID
+	 */
+	async updateSavedataNote(
+		backupId: number,
+		label?: string | null,
+		comment?: string | null,
+	): Promise<void> {
+		return this.invoke<void>("update_savedata_note", {
+			backupId,
+			label,
+			comment,
 		});
 	}
 
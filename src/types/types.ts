@@ -221,7 +221,10 @@ export type IdType = apiSourceType | "custom" | "Whitecloud";
 interface GameRuntimePayload {
 	localpath?: Nullable<string>;
 	executable?: Nullable<string>;
-	savepath?: Nullable<string>;
+	/** 存档路径，可以配置多个，每项都可以使用存档路径模板 */
+	savepath?: Nullable<string[]>;
+	/** 随存档一起备份的注册表键，仅支持 HKEY_CURRENT_USER */
+	save_registry_keys?: Nullable<string[]>;
 	autosave?: number;
 	maxbackups?: number;
 	clear?: number;
@@ -287,7 +290,7 @@ export interface InsertGameParams
 	date?: string;
 	localpath?: string;
 	executable?: string;
-	savepath?: string;
+	savepath?: string[];
 	custom_data?: Nullable<CustomData>;
 }
 
@@ -308,7 +311,8 @@ export interface UpdateGameParams {
 	date?: Nullable<string>;
 	localpath?: Nullable<string>;
 	executable?: Nullable<string>;
-	savepath?: Nullable<string>;
+	savepath?: Nullable<string[]>;
+	save_registry_keys?: Nullable<string[]>;
 	autosave?: Nullable<number>;
 	maxbackups?: Nullable<number>;
 	clear?: Nullable<number>;
@@ -355,7 +359,7 @@ export interface GameData
 	date?: string;
 	localpath?: string;
 	executable?: string;
-	savepath?: string;
+	savepath?: string[];
 	custom_data?: CustomData;
 	created_at?: number;
 	updated_at?: number;
@@ -451,6 +455,10 @@ export interface SavedataRecord {
 	file: string; // 对应数据库中的 file 列（备份文件名）
 	backup_time: number;
 	file_size: number;
+	/** 简短标签，如“true end 前” */
+	label?: Nullable<string>;
+	/** 备注说明 */
+	comment?: Nullable<string>;
 }
 
 /**
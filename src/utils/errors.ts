@@ -14,7 +14,14 @@ export type AppErrorCode =
 	| "http_response_error"
 	| "http_response_parse_failed"
 	| "api_rate_limited"
-	| "metadata_request_failed";
+	| "metadata_request_failed"
+	// 后端 AppError 的错误码
+	| "game_not_found"
+	| "game_not_in_trash"
+	| "backup_password_required"
+	| "invalid_argument"
+	| "database_error"
+	| "internal_error";
 
 type ApiRateLimitSource =
 	| "bgm"